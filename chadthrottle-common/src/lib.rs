@@ -2,23 +2,32 @@
 
 /// Token bucket state for rate limiting
 /// This struct is shared between userspace and eBPF programs via BPF maps
+///
+/// Buckets live in a per-CPU map: each CPU refills its own bucket at its share of
+/// the throttle rate, so packets on different CPUs never race on the same tokens.
+/// Userspace periodically moves the shares (and unused tokens) to the busy CPUs.
 #[repr(C)]
 #[derive(Clone, Copy)]
 pub struct TokenBucket {
-    /// Maximum tokens (equals rate limit in bytes per second)
+    /// Maximum tokens (this CPU's share of the burst size)
     pub capacity: u64,
     /// Current tokens available
     pub tokens: u64,
-    /// Last update timestamp in nanoseconds
+    /// Time (ns) the tokens are accounted up to; trails the last update by the time
+    /// that hasn't added up to a whole token yet
     pub last_update_ns: u64,
-    /// Rate limit in bytes per second
+    /// This CPU's share of the rate limit in bytes per second
     pub rate_bps: u64,
+    /// Earliest departure time for the next packet when shaping (EDT pacing)
+    pub next_departure_ns: u64,
 }
 
 // SAFETY: TokenBucket is a plain old data type with all u64 fields
 #[cfg(feature = "userspace")]
 unsafe impl aya::Pod for TokenBucket {}
 
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+
 impl TokenBucket {
     pub const fn new() -> Self {
         Self {
@@ -26,7 +35,73 @@ impl TokenBucket {
             tokens: 0,
             last_update_ns: 0,
             rate_bps: 0,
+            next_departure_ns: 0,
+        }
+    }
+
+    /// Add the tokens accrued at `rate_bps` since the last update, capped at capacity
+    ///
+    /// A bucket that was never updated (`last_update_ns` = 0) only starts its clock:
+    /// userspace fills buckets without knowing the kernel's monotonic clock, so the
+    /// tokens it was created with are the initial burst.
+    ///
+    /// Only the time that became whole tokens is spent: the rest carries over to the
+    /// next refill, so packets arriving faster than one token's worth of time still
+    /// add up to the rate.
+    #[inline(always)]
+    pub fn refill(&mut self, now_ns: u64) {
+        if self.last_update_ns == 0 || self.rate_bps == 0 {
+            self.last_update_ns = now_ns;
+            return;
         }
+
+        // eBPF has no 128-bit math: split the elapsed time into whole seconds and the
+        // rest, which keeps the products within u64 for rates below 18 GB/s
+        let elapsed_ns = now_ns.saturating_sub(self.last_update_ns);
+        let secs = elapsed_ns / NANOS_PER_SEC;
+        let rest_ns = elapsed_ns % NANOS_PER_SEC;
+        let rest_tokens = rest_ns.wrapping_mul(self.rate_bps) / NANOS_PER_SEC;
+        let tokens_to_add = secs.wrapping_mul(self.rate_bps).saturating_add(rest_tokens);
+
+        self.tokens = self.tokens.saturating_add(tokens_to_add);
+        if self.tokens >= self.capacity {
+            // Full: time beyond that isn't owed
+            self.tokens = self.capacity;
+            self.last_update_ns = now_ns;
+            return;
+        }
+
+        // Time the whole tokens took (rounded up, so the carry never over-credits)
+        let spent_ns = rest_tokens
+            .wrapping_mul(NANOS_PER_SEC)
+            .div_ceil(self.rate_bps);
+        self.last_update_ns = now_ns - (rest_ns - spent_ns);
+    }
+
+    /// Take `bytes` tokens if there are enough; false leaves the bucket unchanged
+    #[inline(always)]
+    pub fn try_consume(&mut self, bytes: u64) -> bool {
+        if self.tokens >= bytes {
+            self.tokens -= bytes;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Smallest burst (bucket capacity) a throttle gets: one full-size packet. A bucket
+/// smaller than a packet never collects enough tokens for it, so at very low rates
+/// the burst is larger than 2x the rate and a packet waits until enough accumulate
+pub const MIN_BURST_SIZE: u64 = 1500;
+
+/// Burst size for a throttle: 2x the rate, but at least one full-size packet
+pub const fn burst_size_for(rate_bps: u64) -> u64 {
+    let burst = rate_bps.saturating_mul(2);
+    if burst < MIN_BURST_SIZE {
+        MIN_BURST_SIZE
+    } else {
+        burst
     }
 }
 
@@ -35,6 +110,19 @@ pub const TRAFFIC_TYPE_ALL: u8 = 0;
 pub const TRAFFIC_TYPE_INTERNET: u8 = 1;
 pub const TRAFFIC_TYPE_LOCAL: u8 = 2;
 
+/// Over-limit policy values for eBPF
+pub const POLICY_DROP: u8 = 0;
+pub const POLICY_SHAPE: u8 = 1;
+
+/// Address family values for eBPF (which IP version a throttle applies to)
+pub const ADDRESS_FAMILY_ANY: u8 = 0;
+pub const ADDRESS_FAMILY_V4: u8 = 1;
+pub const ADDRESS_FAMILY_V6: u8 = 2;
+
+/// EtherType values of `skb->protocol`
+pub const ETH_P_IP: u16 = 0x0800;
+pub const ETH_P_IPV6: u16 = 0x86DD;
+
 /// Configuration for a cgroup throttle
 #[repr(C)]
 #[derive(Clone, Copy)]
@@ -45,12 +133,19 @@ pub struct CgroupThrottleConfig {
     pub pid: u32,
     /// Traffic type to throttle (0=All, 1=Internet, 2=Local)
     pub traffic_type: u8,
-    /// Padding for alignment (3 bytes to maintain 8-byte alignment)
-    pub _padding: [u8; 3],
+    /// What to do with packets over the limit (0=Drop, 1=Shape)
+    pub policy: u8,
+    /// Number of CPUs the rate is split across until userspace rebalances the
+    /// per-CPU buckets (a CPU without a bucket starts with rate / cpu_count)
+    pub cpu_count: u16,
     /// Rate limit in bytes per second (sustained rate)
     pub rate_bps: u64,
     /// Burst size in bytes (maximum tokens, allows short bursts above rate)
     pub burst_size: u64,
+    /// IP version to throttle (0=Any, 1=IPv4, 2=IPv6)
+    pub address_family: u8,
+    /// Explicit padding so the struct has no uninitialized bytes
+    pub _padding: [u8; 7],
 }
 
 // SAFETY: CgroupThrottleConfig is a plain old data type with all primitive fields
@@ -63,14 +158,18 @@ impl CgroupThrottleConfig {
             cgroup_id: 0,
             pid: 0,
             traffic_type: TRAFFIC_TYPE_ALL,
-            _padding: [0, 0, 0], // Explicit array literal - [0; 3] causes LLVM errors in eBPF
+            policy: POLICY_DROP,
+            cpu_count: 1,
             rate_bps: 0,
             burst_size: 0,
+            address_family: ADDRESS_FAMILY_ANY,
+            _padding: [0; 7],
         }
     }
 }
 
 /// Statistics for a throttled cgroup
+/// Kept per CPU like the token buckets; userspace adds the CPUs' counters up
 #[repr(C)]
 #[derive(Clone, Copy)]
 pub struct ThrottleStats {
@@ -88,6 +187,12 @@ pub struct ThrottleStats {
     pub config_misses: u64,
     /// The cgroup ID that the eBPF program actually sees (diagnostic)
     pub cgroup_id_seen: u64,
+    /// Packets passed because the token bucket had enough tokens
+    pub passed_with_tokens: u64,
+    /// Packets dropped because the token bucket was out of tokens
+    pub dropped_no_tokens: u64,
+    /// Packets passed because they didn't match the throttle's traffic type filter
+    pub passed_filter_mismatch: u64,
     /// Reserved for future use
     pub _reserved: u64,
 }
@@ -106,7 +211,173 @@ impl ThrottleStats {
             program_calls: 0,
             config_misses: 0,
             cgroup_id_seen: 0,
+            passed_with_tokens: 0,
+            dropped_no_tokens: 0,
+            passed_filter_mismatch: 0,
             _reserved: 0,
         }
     }
 }
+
+/// Length of the boot id in /proc/sys/kernel/random/boot_id (a UUID string)
+pub const PIN_BOOT_ID_LEN: usize = 36;
+/// Maximum length of the owning ChadThrottle version string
+pub const PIN_VERSION_LEN: usize = 28;
+
+/// Owner of a set of pinned maps (single entry in the PIN_METADATA map)
+/// Pins left by another version or boot are discarded instead of adopted
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct PinMetadata {
+    /// Boot id the maps were pinned in (ASCII, not NUL-terminated)
+    pub boot_id: [u8; PIN_BOOT_ID_LEN],
+    /// ChadThrottle version that pinned the maps (ASCII, NUL-padded)
+    pub version: [u8; PIN_VERSION_LEN],
+}
+
+// SAFETY: PinMetadata is a plain old data type with only byte array fields
+#[cfg(feature = "userspace")]
+unsafe impl aya::Pod for PinMetadata {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEC: u64 = 1_000_000_000;
+
+    fn bucket(rate_bps: u64, capacity: u64, tokens: u64) -> TokenBucket {
+        TokenBucket {
+            capacity,
+            tokens,
+            last_update_ns: SEC,
+            rate_bps,
+            next_departure_ns: 0,
+        }
+    }
+
+    #[test]
+    fn test_refill_accrues_at_rate() {
+        let mut b = bucket(10_000, 100_000, 0);
+        b.refill(SEC + SEC / 2);
+        assert_eq!(b.tokens, 5_000);
+        assert_eq!(b.last_update_ns, SEC + SEC / 2);
+
+        // Long gaps accrue the whole gap, not just its whole seconds
+        b.refill(4 * SEC + SEC / 2 + SEC / 4);
+        assert_eq!(b.tokens, 37_500);
+
+        // A clock going backwards adds nothing
+        b.refill(SEC);
+        assert_eq!(b.tokens, 37_500);
+    }
+
+    #[test]
+    fn test_sub_token_refills_add_up_to_rate() {
+        // 1000 B/s refilled every 300us: 0.3 tokens each time
+        let mut b = bucket(1000, 100_000, 0);
+        let mut now = SEC;
+        for _ in 0..10_000 {
+            now += 300_000;
+            b.refill(now);
+        }
+        assert_eq!(b.tokens, 3000);
+
+        // Consuming along the way doesn't lose the carried fractions either
+        let mut consumed = 0;
+        for _ in 0..10_000 {
+            now += 300_000;
+            b.refill(now);
+            if b.try_consume(1) {
+                consumed += 1;
+            }
+        }
+        assert_eq!(b.tokens + consumed, 6000);
+    }
+
+    #[test]
+    fn test_refill_clamps_to_capacity() {
+        let mut b = bucket(10_000, 15_000, 12_000);
+        b.refill(2 * SEC);
+        assert_eq!(b.tokens, 15_000);
+
+        // Even after a very long idle period
+        b.refill(1_000_000 * SEC);
+        assert_eq!(b.tokens, 15_000);
+    }
+
+    #[test]
+    fn test_first_refill_starts_clock() {
+        let mut b = bucket(10_000, 20_000, 20_000);
+        b.last_update_ns = 0;
+        b.refill(5 * SEC);
+        assert_eq!(b.tokens, 20_000);
+        assert_eq!(b.last_update_ns, 5 * SEC);
+    }
+
+    #[test]
+    fn test_burst_then_rate() {
+        // 1000 B/s with a full 2000 byte burst
+        let rate = 1000;
+        let mut b = bucket(rate, burst_size_for(rate), burst_size_for(rate));
+        let packet = 500;
+
+        // The burst passes immediately, then packets are refused
+        let mut now = SEC;
+        b.refill(now);
+        let burst = (0..10).filter(|_| b.try_consume(packet)).count();
+        assert_eq!(burst, 4);
+        assert!(!b.try_consume(1));
+        assert_eq!(b.tokens, 0);
+
+        // Afterwards packets pass at the rate: 2 per second
+        let mut passed = 0;
+        for _ in 0..100 {
+            now += SEC / 10;
+            b.refill(now);
+            if b.try_consume(packet) {
+                passed += 1;
+            }
+        }
+        assert_eq!(passed, 20);
+    }
+
+    #[test]
+    fn test_lowest_limit_holds_for_a_packet_stream() {
+        // 128 B/s, with full-size packets offered every 5ms for 10 minutes
+        let rate = 128;
+        let packet = 1500;
+        let mut b = bucket(rate, burst_size_for(rate), burst_size_for(rate));
+
+        let mut now = SEC;
+        let mut delivered = 0;
+        for tick in 1..=120_000u64 {
+            now += SEC / 200;
+            b.refill(now);
+            if b.try_consume(packet) {
+                delivered += packet;
+            }
+
+            // Each minute: the burst plus the rate so far, give or take a packet
+            if tick % 12_000 == 0 {
+                let expected = burst_size_for(rate) + rate * (tick / 200);
+                assert!(delivered <= expected, "{} > {}", delivered, expected);
+                assert!(
+                    delivered + packet > expected,
+                    "{} at {}s",
+                    delivered,
+                    tick / 200
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_try_consume_leaves_bucket_unchanged_when_short() {
+        let mut b = bucket(1000, 1500, 1000);
+        assert!(!b.try_consume(1001));
+        assert_eq!(b.tokens, 1000);
+        assert!(b.try_consume(1000));
+        assert_eq!(b.tokens, 0);
+        assert!(b.try_consume(0));
+    }
+}
@@ -0,0 +1,64 @@
+// Parsing throughput of a large synthetic /proc/net/tcp
+//
+//     cargo bench -p chadthrottle-core --bench procfs_parse
+//
+// Builds a table of 50,000 sockets (a busy proxy or load balancer) and reports how
+// long the procfs socket mapper takes to parse it, so regressions in the parser show
+// up as a drop in sockets per second.
+
+#[cfg(target_os = "linux")]
+fn main() {
+    use chadthrottle_core::backends::process::socket_mapper::linux::parse_net_table;
+    use std::fmt::Write;
+    use std::hint::black_box;
+    use std::time::Instant;
+
+    const SOCKETS: u32 = 50_000;
+    const ROUNDS: u32 = 20;
+
+    let mut table = String::from(
+        "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n",
+    );
+    for sl in 0..SOCKETS {
+        let (state, remote) = if sl % 100 == 0 {
+            ("0A", 0)
+        } else {
+            ("01", 0x5DB8_D822u32.wrapping_add(sl))
+        };
+        writeln!(
+            table,
+            "{:>4}: 0F02000A:{:04X} {:08X}:01BB {} 00000000:00000000 00:00000000 00000000  1000        0 {} 1 0000000000000000 20 4 30 10 -1",
+            sl,
+            1024 + sl % 60_000,
+            remote,
+            state,
+            100_000 + sl
+        )
+        .unwrap();
+    }
+
+    let mut entries = Vec::new();
+    parse_net_table(table.as_bytes(), true, &mut entries);
+    assert_eq!(entries.len(), SOCKETS as usize);
+
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        entries.clear();
+        parse_net_table(black_box(table.as_bytes()), true, &mut entries);
+        black_box(&entries);
+    }
+    let per_round = start.elapsed() / ROUNDS;
+
+    println!(
+        "procfs_parse: {} sockets ({:.1} MB) in {:?} per table, {:.0} sockets/s",
+        SOCKETS,
+        table.len() as f64 / 1_048_576.0,
+        per_round,
+        SOCKETS as f64 / per_round.as_secs_f64()
+    );
+}
+
+#[cfg(not(target_os = "linux"))]
+fn main() {
+    println!("procfs_parse: /proc/net only exists on Linux, nothing to measure");
+}
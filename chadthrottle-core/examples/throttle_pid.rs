@@ -0,0 +1,64 @@
+// Throttle one process until Ctrl+C, without the TUI
+//
+//     cargo build -p chadthrottle-core --example throttle_pid --features linux-full
+//     sudo target/debug/examples/throttle_pid <PID> <LIMIT>   # e.g. 1234 500K
+//
+// The limit applies to both directions (download only if a download backend is
+// available). Throttles are removed when the manager is dropped.
+
+use anyhow::{Context, Result};
+use chadthrottle_core::backends::process::create_process_utils;
+use chadthrottle_core::backends::throttle::{
+    ThrottleManager, select_download_backend, select_upload_backend,
+};
+use chadthrottle_core::limits::parse_throttle_limit;
+use chadthrottle_core::process::{AddressFamily, Priority, ThrottleLimit, TrafficType};
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    let [_, pid, limit] = args.as_slice() else {
+        anyhow::bail!("Usage: throttle_pid <PID> <LIMIT>");
+    };
+    let pid: i32 = pid.parse().context("Invalid PID")?;
+    let rate = parse_throttle_limit(limit)?;
+
+    let name = create_process_utils()
+        .get_process_name(pid)
+        .with_context(|| format!("No process with PID {}", pid))?;
+
+    // Best available backends; pass a name (e.g. Some("tc_htb")) to pick one
+    let upload = select_upload_backend(None);
+    let download = select_download_backend(None);
+    if upload.is_none() && download.is_none() {
+        anyhow::bail!("No throttle backend available (check the enabled features)");
+    }
+    let mut manager = ThrottleManager::new(upload, download);
+    let (upload_name, download_name) = manager.backend_names();
+
+    let throttle = ThrottleLimit {
+        download_limit: download_name.as_ref().map(|_| rate),
+        upload_limit: upload_name.as_ref().map(|_| rate),
+        upload_minimum: None,
+        traffic_type: TrafficType::All,
+        interfaces: None,
+        policy: None,
+        family: AddressFamily::Any,
+        priority: Priority::Normal,
+    };
+    manager.throttle_process(pid, name.clone(), &throttle)?;
+
+    println!(
+        "Throttling {} (PID {}) to {}/s (upload: {}, download: {}). Ctrl+C to stop",
+        name,
+        pid,
+        limit,
+        upload_name.as_deref().unwrap_or("none"),
+        download_name.as_deref().unwrap_or("none"),
+    );
+    tokio::signal::ctrl_c().await?;
+
+    manager.remove_throttle(pid)?;
+    println!("Throttle removed");
+    Ok(())
+}
@@ -0,0 +1,122 @@
+// Whether a process is actually transferring, or only waiting for connections
+//
+// A process "has an active transfer" when its byte totals grew (or it had a rate)
+// within the last `ACTIVE_WINDOW`. A process that hasn't, and whose sockets are all
+// listeners (TCP LISTEN, UDP without a peer), is "listen-only" - a daemon like sshd
+// or nginx sitting idle. Only the totals and the last time they moved are kept per
+// process, so classifying is a pass over the process map each update.
+
+use crate::process::ProcessMap;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// How recently a process must have moved bytes to count as transferring
+pub const ACTIVE_WINDOW: Duration = Duration::from_secs(60);
+
+#[derive(Debug)]
+struct Activity {
+    /// Download plus upload total at the last update
+    total: u64,
+    /// When the total last grew (None = not since the process was first seen)
+    last_transfer: Option<Instant>,
+}
+
+/// Classifies each process as transferring or listen-only across updates
+#[derive(Debug, Default)]
+pub struct ActivityTracker {
+    activity: HashMap<i32, Activity>,
+}
+
+impl ActivityTracker {
+    /// Set `has_active_transfer` and `listen_only` on every process
+    pub fn update(&mut self, processes: &mut ProcessMap, now: Instant) {
+        // Forget processes that are gone so reused PIDs start fresh
+        self.activity.retain(|pid, _| processes.contains_key(pid));
+
+        for process in processes.values_mut() {
+            let total = process.total_download + process.total_upload;
+            let moving = process.download_rate > 0 || process.upload_rate > 0;
+            let activity = self.activity.entry(process.pid).or_insert(Activity {
+                total,
+                last_transfer: None,
+            });
+            if moving || total > activity.total {
+                activity.last_transfer = Some(now);
+            }
+            activity.total = total;
+
+            process.has_active_transfer = activity
+                .last_transfer
+                .is_some_and(|at| now.saturating_duration_since(at) < ACTIVE_WINDOW);
+            process.listen_only = !process.has_active_transfer
+                && !process.connections.is_empty()
+                && process.connections.iter().all(|conn| conn.is_listener());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::{ConnectionDetail, ProcessInfo};
+    use std::net::{IpAddr, Ipv4Addr};
+
+    fn connection(protocol: &str, state: &str, remote_port: u16) -> ConnectionDetail {
+        ConnectionDetail {
+            protocol: protocol.to_string(),
+            local_addr: IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            local_port: 22,
+            remote_addr: IpAddr::V4(Ipv4Addr::new(10, 0, 0, 5)),
+            remote_port,
+            state: state.to_string(),
+            inode: 1,
+        }
+    }
+
+    #[test]
+    fn test_idle_listeners_are_listen_only() {
+        let mut sshd = ProcessInfo::new(1, "sshd".to_string());
+        sshd.connections = vec![
+            connection("TCP", "Listen", 0),
+            connection("TCP6", "LISTEN", 0),
+            connection("UDP", "Close", 0),
+        ];
+        let mut curl = ProcessInfo::new(2, "curl".to_string());
+        curl.connections = vec![connection("TCP", "Established", 443)];
+        let mut processes: ProcessMap = [(1, sshd), (2, curl)].into_iter().collect();
+
+        let mut tracker = ActivityTracker::default();
+        tracker.update(&mut processes, Instant::now());
+
+        assert!(processes[&1].listen_only);
+        assert!(!processes[&1].has_active_transfer);
+        // Idle, but with a connection that can carry traffic
+        assert!(!processes[&2].listen_only);
+    }
+
+    #[test]
+    fn test_transfers_stay_active_for_a_minute() {
+        let mut nginx = ProcessInfo::new(1, "nginx".to_string());
+        nginx.connections = vec![connection("TCP", "Listen", 0)];
+        nginx.total_download = 500;
+        let mut processes: ProcessMap = [(1, nginx)].into_iter().collect();
+        let start = Instant::now();
+        let mut tracker = ActivityTracker::default();
+
+        // Bytes from before the first sighting aren't a transfer
+        tracker.update(&mut processes, start);
+        assert!(processes[&1].listen_only);
+
+        // A request was served (totals grew), then the process went quiet again
+        processes.get_mut(&1).unwrap().total_download = 900;
+        tracker.update(&mut processes, start + Duration::from_secs(1));
+        assert!(processes[&1].has_active_transfer);
+        assert!(!processes[&1].listen_only);
+
+        tracker.update(&mut processes, start + Duration::from_secs(60));
+        assert!(processes[&1].has_active_transfer);
+        tracker.update(&mut processes, start + Duration::from_secs(61));
+        assert!(!processes[&1].has_active_transfer);
+        assert!(processes[&1].listen_only);
+    }
+}
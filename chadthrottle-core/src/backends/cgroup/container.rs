@@ -0,0 +1,266 @@
+//! Processes in docker and podman containers
+//!
+//! A container's processes all live under one cgroup the runtime created:
+//!
+//! - docker with the systemd driver: `system.slice/docker-<id>.scope`
+//! - docker with the cgroupfs driver: `docker/<id>`
+//! - podman: `machine.slice/libpod-<id>.scope` (rootless: under the user's
+//!   `user@<uid>.service`), with the processes sometimes one level down in
+//!   `libpod-<id>.scope/container`
+//!
+//! Like app sandboxes, containers are throttled in place: moving a PID out of that
+//! cgroup would also take it out of the container's memory and CPU limits. Throttling
+//! any one process of a container therefore throttles the whole container.
+
+use std::fs;
+use std::path::Path;
+
+/// Length of the ids docker and podman give containers (hex)
+const ID_LEN: usize = 64;
+
+/// Where the name of a container is stored, relative to the filesystem root
+const DOCKER_CONTAINERS: &str = "var/lib/docker/containers";
+const PODMAN_CONTAINERS: &str = "var/lib/containers/storage/overlay-containers/containers.json";
+
+/// The engine that created a container
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ContainerRuntime {
+    Docker,
+    Podman,
+}
+
+impl ContainerRuntime {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ContainerRuntime::Docker => "docker",
+            ContainerRuntime::Podman => "podman",
+        }
+    }
+}
+
+/// A container a process runs in
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Container {
+    pub runtime: ContainerRuntime,
+    /// Full container id
+    pub id: String,
+    /// The container's v2 cgroup path, relative to the hierarchy root
+    pub cgroup: String,
+    /// Name given to the container, where the runtime's state could be read
+    pub name: Option<String>,
+}
+
+impl Container {
+    /// The 12-character id `docker ps` and `podman ps` show
+    pub fn short_id(&self) -> &str {
+        &self.id[..12]
+    }
+
+    /// The container's name, or its short id when the name is unknown
+    pub fn display_name(&self) -> &str {
+        self.name.as_deref().unwrap_or_else(|| self.short_id())
+    }
+}
+
+fn is_container_id(id: &str) -> bool {
+    id.len() == ID_LEN && id.bytes().all(|byte| byte.is_ascii_hexdigit())
+}
+
+/// Find a container in a cgroup path: its runtime, id and the container's own cgroup
+/// (the path up to the runtime's component)
+pub fn parse_container_cgroup(path: &str) -> Option<(ContainerRuntime, String, String)> {
+    let path = path.trim_matches('/');
+    let components: Vec<&str> = path.split('/').collect();
+    for (index, component) in components.iter().enumerate() {
+        let found = if let Some(id) = component
+            .strip_prefix("docker-")
+            .and_then(|rest| rest.strip_suffix(".scope"))
+        {
+            Some((ContainerRuntime::Docker, id))
+        } else if *component == "docker" {
+            components
+                .get(index + 1)
+                .map(|id| (ContainerRuntime::Docker, *id))
+        } else {
+            // libpod-conmon-<id>.scope holds podman's monitor, not the container
+            component
+                .strip_prefix("libpod-")
+                .and_then(|rest| rest.strip_suffix(".scope"))
+                .map(|id| (ContainerRuntime::Podman, id))
+        };
+
+        if let Some((runtime, id)) = found
+            && is_container_id(id)
+        {
+            let depth = if *component == "docker" {
+                index + 2
+            } else {
+                index + 1
+            };
+            return Some((runtime, id.to_string(), components[..depth].join("/")));
+        }
+    }
+    None
+}
+
+/// The container `pid` runs in, or None if it isn't in one (or has exited)
+pub fn container_of(proc_root: &Path, pid: i32) -> Option<Container> {
+    let contents = fs::read_to_string(proc_root.join(pid.to_string()).join("cgroup")).ok()?;
+    // The unified hierarchy's line where there is one; on cgroup v1 any controller's
+    // path will do
+    let (runtime, id, cgroup) = contents
+        .lines()
+        .find_map(|line| line.strip_prefix("0::"))
+        .and_then(parse_container_cgroup)
+        .or_else(|| {
+            contents
+                .lines()
+                .filter_map(|line| line.splitn(3, ':').nth(2))
+                .find_map(parse_container_cgroup)
+        })?;
+    let name = container_name(Path::new("/"), runtime, &id);
+    Some(Container {
+        runtime,
+        id,
+        cgroup,
+        name,
+    })
+}
+
+/// The name a container was given, from the runtime's state under `root`
+/// (rootless podman keeps its state in the user's home and isn't looked up)
+pub fn container_name(root: &Path, runtime: ContainerRuntime, id: &str) -> Option<String> {
+    match runtime {
+        ContainerRuntime::Docker => {
+            // {"Name": "/web", ...}
+            let config =
+                fs::read_to_string(root.join(DOCKER_CONTAINERS).join(id).join("config.v2.json"))
+                    .ok()?;
+            let config: serde_json::Value = serde_json::from_str(&config).ok()?;
+            let name = config.get("Name")?.as_str()?.trim_start_matches('/');
+            (!name.is_empty()).then(|| name.to_string())
+        }
+        ContainerRuntime::Podman => {
+            // [{"id": "...", "names": ["web"], ...}, ...]
+            let containers = fs::read_to_string(root.join(PODMAN_CONTAINERS)).ok()?;
+            let containers: serde_json::Value = serde_json::from_str(&containers).ok()?;
+            containers
+                .as_array()?
+                .iter()
+                .find(|container| container.get("id").and_then(|v| v.as_str()) == Some(id))?
+                .get("names")?
+                .as_array()?
+                .first()?
+                .as_str()
+                .map(str::to_string)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: &str = "4f1c2b3a5d6e7f8091a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f708";
+
+    #[test]
+    fn test_parse_container_cgroup() {
+        let parse = |path: String| parse_container_cgroup(&path);
+
+        assert_eq!(
+            parse(format!("/system.slice/docker-{}.scope", ID)),
+            Some((
+                ContainerRuntime::Docker,
+                ID.to_string(),
+                format!("system.slice/docker-{}.scope", ID)
+            ))
+        );
+        assert_eq!(
+            parse(format!("/docker/{}", ID)),
+            Some((
+                ContainerRuntime::Docker,
+                ID.to_string(),
+                format!("docker/{}", ID)
+            ))
+        );
+        // Processes one level below the container's scope belong to it
+        assert_eq!(
+            parse(format!(
+                "/user.slice/user-1000.slice/user@1000.service/user.slice/libpod-{}.scope/container",
+                ID
+            )),
+            Some((
+                ContainerRuntime::Podman,
+                ID.to_string(),
+                format!(
+                    "user.slice/user-1000.slice/user@1000.service/user.slice/libpod-{}.scope",
+                    ID
+                )
+            ))
+        );
+
+        // podman's monitor, a short id and an ordinary service
+        assert_eq!(
+            parse(format!("/machine.slice/libpod-conmon-{}.scope", ID)),
+            None
+        );
+        assert_eq!(
+            parse("/system.slice/docker-4f1c2b3a.scope".to_string()),
+            None
+        );
+        assert_eq!(parse("/system.slice/docker.service".to_string()), None);
+    }
+
+    #[test]
+    fn test_container_of_reads_names() {
+        let dir =
+            std::env::temp_dir().join(format!("chadthrottle-container-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("proc/300")).unwrap();
+        fs::create_dir_all(dir.join("proc/301")).unwrap();
+        fs::write(
+            dir.join("proc/300/cgroup"),
+            format!("0::/system.slice/docker-{}.scope\n", ID),
+        )
+        .unwrap();
+        fs::write(
+            dir.join("proc/301/cgroup"),
+            "0::/user.slice/session-2.scope\n",
+        )
+        .unwrap();
+
+        let container = container_of(&dir.join("proc"), 300).unwrap();
+        assert_eq!(container.runtime, ContainerRuntime::Docker);
+        assert_eq!(container.display_name(), "4f1c2b3a5d6e");
+        assert_eq!(container_of(&dir.join("proc"), 301), None);
+
+        let docker = dir.join(DOCKER_CONTAINERS).join(ID);
+        fs::create_dir_all(&docker).unwrap();
+        fs::write(
+            docker.join("config.v2.json"),
+            r#"{"ID": "x", "Name": "/web"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            container_name(&dir, ContainerRuntime::Docker, ID).as_deref(),
+            Some("web")
+        );
+
+        let podman = dir.join(PODMAN_CONTAINERS);
+        fs::create_dir_all(podman.parent().unwrap()).unwrap();
+        fs::write(
+            &podman,
+            format!(
+                r#"[{{"id": "{}", "names": ["db"]}}, {{"id": "other", "names": ["x"]}}]"#,
+                ID
+            ),
+        )
+        .unwrap();
+        assert_eq!(
+            container_name(&dir, ContainerRuntime::Podman, ID).as_deref(),
+            Some("db")
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
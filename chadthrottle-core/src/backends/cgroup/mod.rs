@@ -20,7 +20,12 @@
 
 use anyhow::Result;
 use std::fmt;
+use std::path::{Path, PathBuf};
 
+pub mod container;
+#[cfg(any(feature = "cgroup-v1", feature = "cgroup-v2-nftables"))]
+mod original;
+pub mod sandbox;
 #[cfg(feature = "cgroup-v1")]
 pub mod v1;
 
@@ -48,6 +53,18 @@ impl fmt::Display for CgroupBackendType {
     }
 }
 
+/// Which cgroup backend throttling backends pick on this system, for display
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CgroupInfo {
+    /// What `select_best_backend` picks (None when no backend is available)
+    pub selected: Option<CgroupBackendType>,
+    /// Every compiled-in backend with its availability and reason (see `list_all_backends`)
+    pub backends: Vec<(CgroupBackendType, bool, String)>,
+    /// Where the selected backend's hierarchy is mounted (without a selected backend,
+    /// whichever cgroup hierarchy is mounted)
+    pub mount_path: Option<PathBuf>,
+}
+
 /// Handle to a cgroup for a specific process
 ///
 /// This is an opaque handle that different backends can use to track
@@ -60,6 +77,12 @@ pub struct CgroupHandle {
     pub identifier: String,
     /// Which backend created this handle
     pub backend_type: CgroupBackendType,
+    /// Cgroup the process was in before we moved it, restored on removal
+    /// (None for handles found at startup, whose original is unknown)
+    pub original_cgroup: Option<PathBuf>,
+    /// The process's own (sandbox) cgroup, matched where it is instead of moving the
+    /// process: removal drops the rules but never moves processes or deletes it
+    pub in_place: bool,
 }
 
 /// Core trait for cgroup backend implementations
@@ -89,7 +112,9 @@ pub trait CgroupBackend: Send + Sync {
 
     /// Create or join a cgroup for the given process
     ///
-    /// This should:
+    /// Sandboxed processes (see `sandbox`) are not moved: backends that can match
+    /// their existing cgroup return an `in_place` handle for it, others refuse.
+    /// Otherwise this should:
     /// 1. Create the cgroup hierarchy if needed
     /// 2. Add the process to the cgroup
     /// 3. Set up any backend-specific tagging (classid, etc.)
@@ -107,8 +132,8 @@ pub trait CgroupBackend: Send + Sync {
 
     /// Remove a process from its cgroup and clean up
     ///
-    /// This should:
-    /// 1. Remove the process from the cgroup (if still running)
+    /// For an `in_place` handle there is nothing to clean up. Otherwise this should:
+    /// 1. Move the processes in the cgroup back to `handle.original_cgroup`
     /// 2. Delete the cgroup directory/hierarchy
     /// 3. Clean up any backend-specific state (classid allocations, etc.)
     ///
@@ -117,6 +142,15 @@ pub trait CgroupBackend: Send + Sync {
     /// * `handle` - The handle returned from `create_cgroup()`
     fn remove_cgroup(&self, handle: &CgroupHandle) -> Result<()>;
 
+    /// Add another process to an existing cgroup (e.g. a child of the process it
+    /// was created for), so traffic from both matches the same rules
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle returned from `create_cgroup()`
+    /// * `pid` - Process ID to add
+    fn add_process(&self, handle: &CgroupHandle, pid: i32) -> Result<()>;
+
     /// Get the filter expression for use in firewall/TC rules
     ///
     /// This returns the backend-specific syntax for matching traffic from
@@ -186,6 +220,66 @@ pub fn select_best_backend() -> Result<Option<Box<dyn CgroupBackend>>> {
     Ok(None)
 }
 
+/// Preference order of `select_best_backend`
+const SELECTION_ORDER: [CgroupBackendType; 3] = [
+    CgroupBackendType::V2Ebpf,
+    CgroupBackendType::V2Nftables,
+    CgroupBackendType::V1,
+];
+
+/// Find where the hierarchy a backend type uses is mounted in a `/proc/mounts` listing:
+/// the unified (cgroup2) hierarchy for v2, the net_cls controller for v1
+///
+/// ```text
+/// cgroup2 /sys/fs/cgroup cgroup2 rw,nosuid,nodev,noexec,relatime,nsdelegate 0 0
+/// cgroup /sys/fs/cgroup/net_cls,net_prio cgroup rw,nosuid,nodev,noexec,relatime,net_cls,net_prio 0 0
+/// ```
+fn parse_mount_path(mounts: &str, backend_type: CgroupBackendType) -> Option<PathBuf> {
+    mounts.lines().find_map(|line| {
+        let [_, mount_point, fs_type, options, ..] =
+            line.split_whitespace().collect::<Vec<_>>()[..]
+        else {
+            return None;
+        };
+        let matches = match backend_type {
+            CgroupBackendType::V2Nftables | CgroupBackendType::V2Ebpf => fs_type == "cgroup2",
+            CgroupBackendType::V1 => {
+                fs_type == "cgroup" && options.split(',').any(|option| option == "net_cls")
+            }
+        };
+        // Spaces in mount points are escaped as \040
+        matches.then(|| PathBuf::from(mount_point.replace("\\040", " ")))
+    })
+}
+
+/// Find where the hierarchy a backend type uses is mounted on this system
+pub fn mount_path(backend_type: CgroupBackendType) -> Option<PathBuf> {
+    let mounts = std::fs::read_to_string(Path::new("/proc/mounts")).ok()?;
+    parse_mount_path(&mounts, backend_type)
+}
+
+/// Detect which cgroup backend is selected, why the others aren't, and where its
+/// hierarchy is mounted (the same checks as `select_best_backend`, without logging)
+pub fn detect_cgroup_info() -> CgroupInfo {
+    let backends = list_all_backends();
+    let selected = SELECTION_ORDER.into_iter().find(|backend_type| {
+        backends
+            .iter()
+            .any(|(listed, available, _)| listed == backend_type && *available)
+    });
+    let mount_path = match selected {
+        Some(backend_type) => mount_path(backend_type),
+        None => {
+            mount_path(CgroupBackendType::V2Nftables).or_else(|| mount_path(CgroupBackendType::V1))
+        }
+    };
+    CgroupInfo {
+        selected,
+        backends,
+        mount_path,
+    }
+}
+
 /// Check if cgroup v1 with net_cls controller is available
 ///
 /// This is used by backends that specifically require cgroup v1,
@@ -271,3 +365,36 @@ pub fn list_all_backends() -> Vec<(CgroupBackendType, bool, String)> {
 
     backends
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MOUNTS: &str = "\
+sysfs /sys sysfs rw,nosuid,nodev,noexec,relatime 0 0
+tmpfs /sys/fs/cgroup tmpfs ro,nosuid,nodev,noexec,mode=755 0 0
+cgroup2 /sys/fs/cgroup/unified cgroup2 rw,nosuid,nodev,noexec,relatime,nsdelegate 0 0
+cgroup /sys/fs/cgroup/cpu,cpuacct cgroup rw,nosuid,nodev,noexec,relatime,cpu,cpuacct 0 0
+cgroup /sys/fs/cgroup/net_cls,net_prio cgroup rw,nosuid,nodev,noexec,relatime,net_cls,net_prio 0 0
+";
+
+    #[test]
+    fn test_parse_mount_path() {
+        // A hybrid hierarchy: both versions mounted
+        assert_eq!(
+            parse_mount_path(MOUNTS, CgroupBackendType::V2Nftables),
+            Some(PathBuf::from("/sys/fs/cgroup/unified"))
+        );
+        assert_eq!(
+            parse_mount_path(MOUNTS, CgroupBackendType::V1),
+            Some(PathBuf::from("/sys/fs/cgroup/net_cls,net_prio"))
+        );
+
+        let unified = "cgroup2 /sys/fs/cgroup cgroup2 rw,nosuid,nodev,noexec,relatime 0 0\n";
+        assert_eq!(
+            parse_mount_path(unified, CgroupBackendType::V2Ebpf),
+            Some(PathBuf::from("/sys/fs/cgroup"))
+        );
+        assert_eq!(parse_mount_path(unified, CgroupBackendType::V1), None);
+    }
+}
@@ -0,0 +1,74 @@
+//! Where a process was before we moved it into one of our cgroups
+//!
+//! Moving a PID into `chadthrottle/<name>` takes it out of the cgroup systemd (or a
+//! container runtime) put it in. On removal it goes back there, so that service's
+//! resource accounting and limits apply to it again.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Path of the cgroup a process is in, relative to its hierarchy's root, from the
+/// contents of /proc/<pid>/cgroup. `controller` picks the v1 hierarchy it is
+/// mounted in ("4:net_cls,net_prio:/user.slice"); None picks the v2 entry ("0::/user.slice")
+fn parse_proc_cgroup(contents: &str, controller: Option<&str>) -> Option<String> {
+    contents.lines().find_map(|line| {
+        let mut fields = line.splitn(3, ':');
+        let (_, controllers, path) = (fields.next()?, fields.next()?, fields.next()?);
+        let found = match controller {
+            Some(controller) => controllers.split(',').any(|c| c == controller),
+            None => controllers.is_empty(),
+        };
+        found.then(|| path.to_string())
+    })
+}
+
+/// The cgroup `pid` is in under `hierarchy_root`, unless it is one of ours (under
+/// `base_path`): then the other direction's throttle already moved it, and its
+/// handle is the one that knows where the process came from
+pub fn original_cgroup(
+    proc_root: &Path,
+    pid: i32,
+    controller: Option<&str>,
+    hierarchy_root: &Path,
+    base_path: &Path,
+) -> Option<PathBuf> {
+    let contents = fs::read_to_string(proc_root.join(pid.to_string()).join("cgroup")).ok()?;
+    let relative = parse_proc_cgroup(&contents, controller)?;
+    let path = hierarchy_root.join(relative.trim_start_matches('/'));
+    (!path.starts_with(base_path)).then_some(path)
+}
+
+/// Move every process in `cgroup` back to `original`. If that cgroup is gone (its
+/// service restarted, say) the nearest existing ancestor outside `base_path` is used
+pub fn restore_processes(cgroup: &Path, original: &Path, base_path: &Path) -> Result<()> {
+    let mut target = original;
+    while !target.is_dir() || target.starts_with(base_path) {
+        target = target
+            .parent()
+            .context(format!("No cgroup left to restore {:?} to", original))?;
+    }
+    if target != original {
+        log::info!(
+            "Original cgroup {:?} no longer exists, restoring to {:?}",
+            original,
+            target
+        );
+    }
+
+    let procs = fs::read_to_string(cgroup.join("cgroup.procs"))
+        .context(format!("Failed to read processes of {:?}", cgroup))?;
+    let target_procs = target.join("cgroup.procs");
+    // The kernel takes one PID per write
+    for pid in procs
+        .lines()
+        .filter_map(|line| line.trim().parse::<i32>().ok())
+    {
+        match fs::write(&target_procs, pid.to_string()) {
+            Ok(()) => log::debug!("Restored PID {} to cgroup {:?}", pid, target),
+            // The process may have exited since we read the list
+            Err(e) => log::warn!("Failed to restore PID {} to {:?}: {}", pid, target, e),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,88 @@
+//! Processes in app sandboxes (Flatpak) or containers that must stay in their own cgroup
+//!
+//! Flatpak puts each app in a systemd scope (`app-flatpak-<id>-<n>.scope`) that the
+//! portal and systemd keep track of. Moving one of its PIDs into `chadthrottle/<name>`
+//! breaks that accounting and sometimes the app, so such processes are throttled
+//! "in place": rules match (or programs attach to) the scope they are already in,
+//! and removal only drops the rules, never deleting a cgroup we don't own. Docker and
+//! podman containers are treated the same way, in the container's cgroup (see
+//! `container`).
+
+use super::container::parse_container_cgroup;
+use std::fs;
+use std::path::Path;
+
+/// Scope name prefix systemd gives Flatpak apps
+const FLATPAK_SCOPE_PREFIX: &str = "app-flatpak-";
+
+/// Written into the root of every Flatpak sandbox
+const FLATPAK_INFO: &str = ".flatpak-info";
+
+/// The v2 cgroup path (relative to the hierarchy root, e.g.
+/// "user.slice/.../app-flatpak-org.mozilla.firefox-1234.scope") of a sandboxed
+/// process, or None if `pid` is not sandboxed (or has exited). For a process in a
+/// container this is the container's cgroup, so every process in it gets the same one
+pub fn sandbox_cgroup(proc_root: &Path, pid: i32) -> Option<String> {
+    let proc_dir = proc_root.join(pid.to_string());
+    let contents = fs::read_to_string(proc_dir.join("cgroup")).ok()?;
+    let path = contents
+        .lines()
+        .find_map(|line| line.strip_prefix("0::"))?
+        .trim_start_matches('/');
+
+    if let Some((_, _, container)) = parse_container_cgroup(path) {
+        return Some(container);
+    }
+
+    let in_flatpak_scope = path
+        .split('/')
+        .any(|component| component.starts_with(FLATPAK_SCOPE_PREFIX));
+    (in_flatpak_scope || proc_dir.join("root").join(FLATPAK_INFO).exists())
+        .then(|| path.to_string())
+}
+
+/// Whether `pid` runs in an app sandbox or container whose cgroup we must not move it out of
+pub fn is_sandboxed(pid: i32) -> bool {
+    sandbox_cgroup(Path::new("/proc"), pid).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_flatpak_scopes_and_info_file() {
+        let dir = std::env::temp_dir().join(format!("chadthrottle-sandbox-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let scope = "user.slice/user-1000.slice/user@1000.service/app.slice/\
+                     app-flatpak-org.mozilla.firefox-4242.scope";
+        let session = "user.slice/user-1000.slice/session-2.scope";
+        let container = "machine.slice/libpod-\
+                         4f1c2b3a5d6e7f8091a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f708.scope";
+        let in_container = format!("{}/container", container);
+        for (pid, cgroup) in [
+            (4242, scope),
+            (4243, session),
+            (4244, session),
+            (4246, in_container.as_str()),
+        ] {
+            fs::create_dir_all(dir.join(format!("{}/root", pid))).unwrap();
+            fs::write(
+                dir.join(format!("{}/cgroup", pid)),
+                format!("0::/{}\n", cgroup),
+            )
+            .unwrap();
+        }
+        // Sandboxed, but in a scope that doesn't follow the naming scheme
+        fs::write(dir.join("4244/root/.flatpak-info"), "[Application]\n").unwrap();
+
+        assert_eq!(sandbox_cgroup(&dir, 4242).as_deref(), Some(scope));
+        assert_eq!(sandbox_cgroup(&dir, 4243), None);
+        assert_eq!(sandbox_cgroup(&dir, 4244).as_deref(), Some(session));
+        assert_eq!(sandbox_cgroup(&dir, 4245), None);
+        // Processes in a container share the container's cgroup
+        assert_eq!(sandbox_cgroup(&dir, 4246).as_deref(), Some(container));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -22,6 +22,8 @@ use std::fs;
 use std::path::PathBuf;
 use std::sync::Mutex;
 
+use crate::backends::cgroup::original::{original_cgroup, restore_processes};
+use crate::backends::cgroup::sandbox::sandbox_cgroup;
 use crate::backends::cgroup::{CgroupBackend, CgroupBackendType, CgroupHandle};
 
 /// Base path for net_cls cgroup controller
@@ -30,6 +32,10 @@ const CHADTHROTTLE_CGROUP: &str = "chadthrottle";
 
 /// Cgroup v1 backend using net_cls controller
 pub struct CgroupV1Backend {
+    /// Root of the net_cls hierarchy
+    hierarchy_root: PathBuf,
+    /// Where /proc is (to read a process's current cgroup)
+    proc_root: PathBuf,
     /// Base path for our cgroups
     base_path: PathBuf,
     /// Track allocated classids to avoid collisions
@@ -41,12 +47,22 @@ pub struct CgroupV1Backend {
 
 impl CgroupV1Backend {
     pub fn new() -> Result<Self> {
-        let base_path = PathBuf::from(CGROUP_V1_BASE).join(CHADTHROTTLE_CGROUP);
-        Ok(Self {
+        Ok(Self::with_paths(
+            PathBuf::from(CGROUP_V1_BASE),
+            PathBuf::from("/proc"),
+        ))
+    }
+
+    /// Backend for a net_cls hierarchy mounted at `hierarchy_root`
+    fn with_paths(hierarchy_root: PathBuf, proc_root: PathBuf) -> Self {
+        let base_path = hierarchy_root.join(CHADTHROTTLE_CGROUP);
+        Self {
+            hierarchy_root,
+            proc_root,
             base_path,
             allocated_classids: Mutex::new(HashMap::new()),
             next_classid: Mutex::new(1), // Start at 1:1 (0x10001)
-        })
+        }
     }
 
     /// Convert classid number to hex format (e.g., 1 -> 0x10001 for major:minor 1:1)
@@ -135,6 +151,17 @@ impl CgroupBackend for CgroupV1Backend {
     }
 
     fn create_cgroup(&self, pid: i32, name: &str) -> Result<CgroupHandle> {
+        // net_cls tags come from our own cgroup, and moving a sandboxed process into
+        // one breaks the sandbox
+        if sandbox_cgroup(&self.proc_root, pid).is_some() {
+            anyhow::bail!(
+                "PID {} ({}) is sandboxed and can't be moved to a net_cls cgroup; \
+                 use the ebpf or nftables backend on cgroup v2 instead",
+                pid,
+                name
+            );
+        }
+
         // Allocate classid
         let classid = self.allocate_classid(pid)?;
         let classid_hex = Self::classid_to_hex(classid);
@@ -154,7 +181,14 @@ impl CgroupBackend for CgroupV1Backend {
         fs::write(&classid_file, format!("{}", classid_hex))
             .context(format!("Failed to write classid to {:?}", classid_file))?;
 
-        // Add process to cgroup
+        // Remember where the process was, then add it to our cgroup
+        let original_cgroup = original_cgroup(
+            &self.proc_root,
+            pid,
+            Some("net_cls"),
+            &self.hierarchy_root,
+            &self.base_path,
+        );
         let procs_file = cgroup_path.join("cgroup.procs");
         fs::write(&procs_file, format!("{}", pid))
             .context(format!("Failed to add PID {} to cgroup", pid))?;
@@ -170,6 +204,8 @@ impl CgroupBackend for CgroupV1Backend {
             pid,
             identifier: Self::classid_to_tc_format(classid),
             backend_type: CgroupBackendType::V1,
+            original_cgroup,
+            in_place: false,
         })
     }
 
@@ -187,6 +223,14 @@ impl CgroupBackend for CgroupV1Backend {
             // Check if this is a cgroup for our PID
             if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
                 if name.ends_with(&format!("_{}", handle.pid)) {
+                    // Processes still in the cgroup go back where they came from
+                    // (added children included: they start in their parent's cgroup)
+                    if let Some(original) = &handle.original_cgroup
+                        && let Err(e) = restore_processes(&path, original, &self.base_path)
+                    {
+                        log::warn!("Failed to restore processes of {:?}: {:#}", path, e);
+                    }
+
                     // Try to remove the directory
                     if let Err(e) = fs::remove_dir(&path) {
                         log::warn!("Failed to remove cgroup directory {:?}: {}", path, e);
@@ -204,6 +248,33 @@ impl CgroupBackend for CgroupV1Backend {
         Ok(())
     }
 
+    fn add_process(&self, handle: &CgroupHandle, pid: i32) -> Result<()> {
+        // Directories are named <name>_<pid>, so find the one for the handle's PID
+        let suffix = format!("_{}", handle.pid);
+        let cgroup_path = fs::read_dir(&self.base_path)
+            .context(format!(
+                "Failed to read cgroup directory {:?}",
+                self.base_path
+            ))?
+            .flatten()
+            .map(|entry| entry.path())
+            .find(|path| {
+                path.file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|name| name.ends_with(&suffix))
+            })
+            .context(format!("No cgroup found for PID {}", handle.pid))?;
+
+        let procs_file = cgroup_path.join("cgroup.procs");
+        fs::write(&procs_file, format!("{}", pid)).context(format!(
+            "Failed to add PID {} to cgroup {:?}",
+            pid, cgroup_path
+        ))?;
+
+        log::debug!("Added PID {} to cgroup v1 at {:?}", pid, cgroup_path);
+        Ok(())
+    }
+
     fn get_filter_expression(&self, handle: &CgroupHandle) -> String {
         // For TC filters, we match on the classid
         // The TC command looks like: tc filter add ... handle 1: cgroup
@@ -245,6 +316,8 @@ impl CgroupBackend for CgroupV1Backend {
                                     pid,
                                     identifier: Self::classid_to_tc_format(classid),
                                     backend_type: CgroupBackendType::V1,
+                                    original_cgroup: None,
+                                    in_place: false,
                                 });
                             }
                         }
@@ -256,3 +329,38 @@ impl CgroupBackend for CgroupV1Backend {
         Ok(handles)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_removal_restores_original_cgroup() {
+        let dir = std::env::temp_dir().join(format!("chadthrottle-cgv1-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let service = dir.join("net_cls/system.slice/backup.service");
+        fs::create_dir_all(&service).unwrap();
+        fs::create_dir_all(dir.join("proc/4242")).unwrap();
+        fs::write(
+            dir.join("proc/4242/cgroup"),
+            "5:memory:/system.slice/backup.service\n3:net_cls,net_prio:/system.slice/backup.service\n0::/system.slice/backup.service\n",
+        )
+        .unwrap();
+
+        let backend = CgroupV1Backend::with_paths(dir.join("net_cls"), dir.join("proc"));
+        let handle = backend.create_cgroup(4242, "restic").unwrap();
+        assert_eq!(handle.original_cgroup, Some(service.clone()));
+        assert_eq!(
+            fs::read_to_string(dir.join("net_cls/chadthrottle/restic_4242/cgroup.procs")).unwrap(),
+            "4242"
+        );
+
+        backend.remove_cgroup(&handle).unwrap();
+        assert_eq!(
+            fs::read_to_string(service.join("cgroup.procs")).unwrap(),
+            "4242"
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -7,8 +7,12 @@
 //!
 //! 1. Create cgroup at `/sys/fs/cgroup/chadthrottle/<name>`
 //! 2. Write PID to `cgroup.procs`
-//! 3. nftables rule matches with: `socket cgroupv2 "/sys/fs/cgroup/chadthrottle/pid_1234"`
-//! 4. Rate limit enforced by nftables limit + drop action
+//! 3. The cgroup path (`chadthrottle/pid_1234`) goes into an nftables map looked up
+//!    with `socket cgroupv2 level 2`, pointing at the throttle's limit object
+//! 4. Rate limit enforced by the limit object + drop action
+//!
+//! Sandboxed (Flatpak) processes skip steps 1 and 2: rules match the app's own scope
+//! cgroup, which is left alone on removal.
 //!
 //! # Requirements
 //!
@@ -20,6 +24,8 @@ use anyhow::{Context, Result};
 use std::fs;
 use std::path::PathBuf;
 
+use crate::backends::cgroup::original::{original_cgroup, restore_processes};
+use crate::backends::cgroup::sandbox::sandbox_cgroup;
 use crate::backends::cgroup::{CgroupBackend, CgroupBackendType, CgroupHandle};
 
 /// Base path for cgroup v2 unified hierarchy
@@ -28,14 +34,30 @@ const CHADTHROTTLE_CGROUP: &str = "chadthrottle";
 
 /// Cgroup v2 backend for nftables
 pub struct CgroupV2NftablesBackend {
+    /// Root of the unified hierarchy
+    hierarchy_root: PathBuf,
+    /// Where /proc is (to read a process's current cgroup)
+    proc_root: PathBuf,
     /// Base path for our cgroups
     base_path: PathBuf,
 }
 
 impl CgroupV2NftablesBackend {
     pub fn new() -> Result<Self> {
-        let base_path = PathBuf::from(CGROUP_V2_BASE).join(CHADTHROTTLE_CGROUP);
-        Ok(Self { base_path })
+        Ok(Self::with_paths(
+            PathBuf::from(CGROUP_V2_BASE),
+            PathBuf::from("/proc"),
+        ))
+    }
+
+    /// Backend for a unified hierarchy mounted at `hierarchy_root`
+    fn with_paths(hierarchy_root: PathBuf, proc_root: PathBuf) -> Self {
+        let base_path = hierarchy_root.join(CHADTHROTTLE_CGROUP);
+        Self {
+            hierarchy_root,
+            proc_root,
+            base_path,
+        }
     }
 
     /// Get the cgroup path for a process
@@ -116,6 +138,23 @@ impl CgroupBackend for CgroupV2NftablesBackend {
     }
 
     fn create_cgroup(&self, pid: i32, name: &str) -> Result<CgroupHandle> {
+        // Match a sandboxed process in the scope it's in rather than moving it out
+        if let Some(scope) = sandbox_cgroup(&self.proc_root, pid) {
+            log::info!(
+                "PID {} ({}) is sandboxed, throttling it in place in {:?}",
+                pid,
+                name,
+                scope
+            );
+            return Ok(CgroupHandle {
+                pid,
+                identifier: scope,
+                backend_type: CgroupBackendType::V2Nftables,
+                original_cgroup: None,
+                in_place: true,
+            });
+        }
+
         // Create base chadthrottle cgroup if needed
         fs::create_dir_all(&self.base_path).context(format!(
             "Failed to create base cgroup at {:?}",
@@ -134,7 +173,14 @@ impl CgroupBackend for CgroupV2NftablesBackend {
         fs::create_dir_all(&cgroup_path)
             .context(format!("Failed to create cgroup at {:?}", cgroup_path))?;
 
-        // Add process to cgroup
+        // Remember where the process was, then add it to our cgroup
+        let original_cgroup = original_cgroup(
+            &self.proc_root,
+            pid,
+            None,
+            &self.hierarchy_root,
+            &self.base_path,
+        );
         let procs_file = cgroup_path.join("cgroup.procs");
         fs::write(&procs_file, format!("{}", pid))
             .context(format!("Failed to add PID {} to cgroup", pid))?;
@@ -152,14 +198,29 @@ impl CgroupBackend for CgroupV2NftablesBackend {
             pid,
             identifier: relative_path,
             backend_type: CgroupBackendType::V2Nftables,
+            original_cgroup,
+            in_place: false,
         })
     }
 
     fn remove_cgroup(&self, handle: &CgroupHandle) -> Result<()> {
+        // Not ours: the sandbox keeps its processes and its cgroup
+        if handle.in_place {
+            return Ok(());
+        }
+
         let cgroup_name = format!("pid_{}", handle.pid);
         let cgroup_path = self.get_cgroup_path(&cgroup_name);
 
         if cgroup_path.exists() {
+            // Processes still in the cgroup go back where they came from
+            // (added children included: they start in their parent's cgroup)
+            if let Some(original) = &handle.original_cgroup
+                && let Err(e) = restore_processes(&cgroup_path, original, &self.base_path)
+            {
+                log::warn!("Failed to restore processes of {:?}: {:#}", cgroup_path, e);
+            }
+
             // Try to remove the directory
             // Note: This will fail if processes are still in the cgroup
             if let Err(e) = fs::remove_dir(&cgroup_path) {
@@ -186,10 +247,40 @@ impl CgroupBackend for CgroupV2NftablesBackend {
         Ok(())
     }
 
+    fn add_process(&self, handle: &CgroupHandle, pid: i32) -> Result<()> {
+        // Children of a sandboxed process start out in its scope, and nothing else
+        // gets moved into a cgroup we don't own
+        if handle.in_place {
+            return match sandbox_cgroup(&self.proc_root, pid) {
+                Some(scope) if scope == handle.identifier => Ok(()),
+                _ => Err(anyhow::anyhow!(
+                    "PID {} is not in the sandbox cgroup {:?} of PID {}",
+                    pid,
+                    handle.identifier,
+                    handle.pid
+                )),
+            };
+        }
+
+        let cgroup_path = self.get_cgroup_path(&format!("pid_{}", handle.pid));
+        let procs_file = cgroup_path.join("cgroup.procs");
+        fs::write(&procs_file, format!("{}", pid)).context(format!(
+            "Failed to add PID {} to cgroup {:?}",
+            pid, cgroup_path
+        ))?;
+
+        log::debug!("Added PID {} to cgroup v2 at {:?}", pid, cgroup_path);
+        Ok(())
+    }
+
     fn get_filter_expression(&self, handle: &CgroupHandle) -> String {
         // Return the cgroup path for nftables socket cgroupv2 matcher
-        // The path should be relative to /sys/fs/cgroup/
-        format!("socket cgroupv2 \"{}\"", handle.identifier)
+        // The path should be relative to /sys/fs/cgroup/, matched at its own depth
+        format!(
+            "socket cgroupv2 level {} \"{}\"",
+            handle.identifier.trim_matches('/').split('/').count(),
+            handle.identifier
+        )
     }
 
     fn list_active_cgroups(&self) -> Result<Vec<CgroupHandle>> {
@@ -221,6 +312,8 @@ impl CgroupBackend for CgroupV2NftablesBackend {
                             pid,
                             identifier: relative_path,
                             backend_type: CgroupBackendType::V2Nftables,
+                            original_cgroup: None,
+                            in_place: false,
                         });
                     }
                 }
@@ -230,3 +323,71 @@ impl CgroupBackend for CgroupV2NftablesBackend {
         Ok(handles)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_removal_falls_back_to_parent_of_missing_cgroup() {
+        let dir = std::env::temp_dir().join(format!("chadthrottle-cgv2-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("cgroup/system.slice/backup.service")).unwrap();
+        fs::create_dir_all(dir.join("proc/4242")).unwrap();
+        fs::write(
+            dir.join("proc/4242/cgroup"),
+            "0::/system.slice/backup.service\n",
+        )
+        .unwrap();
+
+        let backend = CgroupV2NftablesBackend::with_paths(dir.join("cgroup"), dir.join("proc"));
+        let handle = backend.create_cgroup(4242, "restic").unwrap();
+        assert_eq!(
+            handle.original_cgroup,
+            Some(dir.join("cgroup/system.slice/backup.service"))
+        );
+
+        // Throttling the other direction finds the process in our cgroup already
+        fs::write(dir.join("proc/4242/cgroup"), "0::/chadthrottle/pid_4242\n").unwrap();
+        assert_eq!(
+            backend
+                .create_cgroup(4242, "restic")
+                .unwrap()
+                .original_cgroup,
+            None
+        );
+
+        // The service was restarted (its cgroup recreated elsewhere) while throttled
+        fs::remove_dir(dir.join("cgroup/system.slice/backup.service")).unwrap();
+        backend.remove_cgroup(&handle).unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.join("cgroup/system.slice/cgroup.procs")).unwrap(),
+            "4242"
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_sandboxed_process_is_throttled_in_place() {
+        let dir = std::env::temp_dir().join(format!("chadthrottle-cgv2-sb-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let scope = "user.slice/app-flatpak-org.example.App-4242.scope";
+        fs::create_dir_all(dir.join("cgroup").join(scope)).unwrap();
+        fs::create_dir_all(dir.join("proc/4242")).unwrap();
+        fs::write(dir.join("proc/4242/cgroup"), format!("0::/{}\n", scope)).unwrap();
+
+        let backend = CgroupV2NftablesBackend::with_paths(dir.join("cgroup"), dir.join("proc"));
+        let handle = backend.create_cgroup(4242, "app").unwrap();
+        assert!(handle.in_place);
+        assert_eq!(handle.identifier, scope);
+        assert!(!dir.join("cgroup/chadthrottle").exists());
+        assert!(!dir.join("cgroup").join(scope).join("cgroup.procs").exists());
+
+        // Removal leaves the sandbox's cgroup alone
+        backend.remove_cgroup(&handle).unwrap();
+        assert!(dir.join("cgroup").join(scope).is_dir());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,635 @@
+// Mock backends for tests (no root, tc or eBPF required)
+//
+// Each mock records the calls it receives and keeps its throttles in a `MockState`
+// shared with the test through a `MockHandle`, so the test can inspect and inject
+// failures even after the manager has taken ownership of the backend.
+
+use super::cgroup::CgroupInfo;
+use super::process::{ConnectionMap, ProcessEntry, ProcessUtils};
+use super::throttle::external::ExternalShaping;
+use super::throttle::{
+    AdoptionReport, BackendProvider, DownloadBackendInfo, DownloadThrottleBackend, ThrottleError,
+    ThrottleResult, UploadBackendInfo, UploadThrottleBackend,
+};
+use super::{BackendCapabilities, BackendPriority, PolicySupport, TrafficTypeSupport};
+use crate::process::{AddressFamily, Policy, TrafficType};
+use anyhow::{Result, anyhow};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Interface the mock upload backend shapes (for interface ceilings and scoping)
+pub const MOCK_INTERFACE: &str = "mock0";
+
+/// Interfaces reported by `MockBackendProvider`
+pub const MOCK_NETWORK_INTERFACES: [&str; 2] = [MOCK_INTERFACE, "lo"];
+
+/// A call received by a mock backend
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MockCall {
+    Init,
+    Throttle {
+        pid: i32,
+        limit: u64,
+        traffic_type: TrafficType,
+    },
+    Remove(i32),
+    Cleanup,
+}
+
+/// Shared state of a mock backend
+#[derive(Debug, Default)]
+pub struct MockState {
+    pub calls: Vec<MockCall>,
+    pub throttles: HashMap<i32, u64>,
+    // Over-limit policy each throttle was installed with
+    pub policies: HashMap<i32, Policy>,
+    // Processes sharing a throttle: member PID -> throttled PID
+    pub members: HashMap<i32, i32>,
+    pub ceilings: HashMap<String, u64>,
+    // Throttles a previous run left installed (PID -> limit), until adopt_or_clean
+    pub leftovers: HashMap<i32, u64>,
+    pub fail_init: bool,
+    pub fail_throttle: bool,
+    pub fail_throttle_pids: Vec<i32>, // Throttling only these PIDs fails
+    pub fail_remove: bool,
+    pub attach_method: Option<&'static str>,
+    pub max_throttles: Option<usize>,
+}
+
+/// Test-side handle to a mock backend's state
+#[derive(Debug, Clone, Default)]
+pub struct MockHandle(Arc<Mutex<MockState>>);
+
+impl MockHandle {
+    pub fn state(&self) -> MutexGuard<'_, MockState> {
+        self.0.lock().unwrap()
+    }
+
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.state().calls.clone()
+    }
+
+    pub fn throttles(&self) -> HashMap<i32, u64> {
+        self.state().throttles.clone()
+    }
+}
+
+/// Configuration and behavior shared by the upload and download mocks
+#[derive(Debug, Clone)]
+struct MockBackend {
+    name: &'static str,
+    priority: BackendPriority,
+    capabilities: BackendCapabilities,
+    traffic_support: TrafficTypeSupport,
+    policy_support: PolicySupport,
+    handle: MockHandle,
+}
+
+impl MockBackend {
+    fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            priority: BackendPriority::Good,
+            capabilities: BackendCapabilities {
+                ipv4_support: true,
+                ipv6_support: true,
+                per_process: true,
+                per_connection: false,
+                tcp_support: true,
+                udp_support: true,
+                traffic_types: TrafficTypeSupport::default(),
+            },
+            traffic_support: TrafficTypeSupport::default(),
+            policy_support: PolicySupport::DROP_ONLY,
+            handle: MockHandle::default(),
+        }
+    }
+
+    fn init(&mut self) -> ThrottleResult<()> {
+        let mut state = self.handle.state();
+        state.calls.push(MockCall::Init);
+        if state.fail_init {
+            return Err(anyhow!("{}: injected init failure", self.name).into());
+        }
+        Ok(())
+    }
+
+    fn throttle(
+        &mut self,
+        pid: i32,
+        limit: u64,
+        traffic_type: TrafficType,
+        policy: Policy,
+    ) -> ThrottleResult<()> {
+        if !self.policy_support.supports(policy) {
+            return Err(anyhow!("{}: cannot {} traffic", self.name, policy).into());
+        }
+        let mut state = self.handle.state();
+        state.calls.push(MockCall::Throttle {
+            pid,
+            limit,
+            traffic_type,
+        });
+        if state.fail_throttle || state.fail_throttle_pids.contains(&pid) {
+            return Err(anyhow!("{}: injected throttle failure", self.name).into());
+        }
+        state.throttles.insert(pid, limit);
+        state.policies.insert(pid, policy);
+        Ok(())
+    }
+
+    fn adopt_or_clean(&mut self, adoptable: &HashMap<i32, u64>) -> ThrottleResult<AdoptionReport> {
+        let mut state = self.handle.state();
+        let mut report = AdoptionReport::default();
+        for (pid, limit) in std::mem::take(&mut state.leftovers) {
+            if adoptable.get(&pid) == Some(&limit) {
+                state.throttles.insert(pid, limit);
+                report.adopted.push(pid);
+            } else {
+                report.removed += 1;
+            }
+        }
+        Ok(report)
+    }
+
+    fn remove(&mut self, pid: i32) -> ThrottleResult<()> {
+        let mut state = self.handle.state();
+        state.calls.push(MockCall::Remove(pid));
+        if state.fail_remove {
+            return Err(anyhow!("{}: injected remove failure", self.name).into());
+        }
+        state
+            .throttles
+            .remove(&pid)
+            .map(|_| ())
+            .ok_or(ThrottleError::NotFound)
+    }
+
+    fn add_member(&mut self, pid: i32, member_pid: i32) -> ThrottleResult<()> {
+        let mut state = self.handle.state();
+        if !state.throttles.contains_key(&pid) {
+            return Err(ThrottleError::NotFound);
+        }
+        state.members.insert(member_pid, pid);
+        Ok(())
+    }
+
+    fn get(&self, pid: i32) -> Option<u64> {
+        self.handle.state().throttles.get(&pid).copied()
+    }
+
+    fn all(&self) -> HashMap<i32, u64> {
+        self.handle.throttles()
+    }
+
+    fn cleanup(&mut self) -> ThrottleResult<()> {
+        let mut state = self.handle.state();
+        state.calls.push(MockCall::Cleanup);
+        state.throttles.clear();
+        Ok(())
+    }
+}
+
+/// Mock upload backend
+#[derive(Debug, Clone)]
+pub struct MockUploadBackend(MockBackend);
+
+impl MockUploadBackend {
+    pub fn new(name: &'static str) -> Self {
+        Self(MockBackend::new(name))
+    }
+
+    pub fn with_capabilities(mut self, capabilities: BackendCapabilities) -> Self {
+        self.0.capabilities = capabilities;
+        self
+    }
+
+    pub fn with_policy_support(mut self, policy_support: PolicySupport) -> Self {
+        self.0.policy_support = policy_support;
+        self
+    }
+
+    /// Handle for inspecting calls and injecting failures
+    pub fn handle(&self) -> MockHandle {
+        self.0.handle.clone()
+    }
+}
+
+impl UploadThrottleBackend for MockUploadBackend {
+    fn name(&self) -> &'static str {
+        self.0.name
+    }
+
+    fn priority(&self) -> BackendPriority {
+        self.0.priority
+    }
+
+    fn is_available() -> bool {
+        true
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            traffic_types: self.0.traffic_support,
+            ..self.0.capabilities
+        }
+    }
+
+    fn policy_support(&self) -> PolicySupport {
+        self.0.policy_support
+    }
+
+    fn init(&mut self) -> ThrottleResult<()> {
+        self.0.init()
+    }
+
+    fn throttle_upload(
+        &mut self,
+        pid: i32,
+        _process_name: String,
+        limit_bytes_per_sec: u64,
+        traffic_type: TrafficType,
+    ) -> ThrottleResult<()> {
+        let policy = self.0.policy_support.default;
+        self.0
+            .throttle(pid, limit_bytes_per_sec, traffic_type, policy)
+    }
+
+    fn throttle_upload_with_policy(
+        &mut self,
+        pid: i32,
+        _process_name: String,
+        limit_bytes_per_sec: u64,
+        traffic_type: TrafficType,
+        policy: Policy,
+    ) -> ThrottleResult<()> {
+        self.0
+            .throttle(pid, limit_bytes_per_sec, traffic_type, policy)
+    }
+
+    fn supports_interfaces(&self, interfaces: &[String]) -> bool {
+        interfaces.iter().all(|name| name == MOCK_INTERFACE)
+    }
+
+    fn throttle_upload_on_interfaces(
+        &mut self,
+        pid: i32,
+        _process_name: String,
+        limit_bytes_per_sec: u64,
+        traffic_type: TrafficType,
+        interfaces: &[String],
+        policy: Policy,
+    ) -> ThrottleResult<()> {
+        if !self.supports_interfaces(interfaces) {
+            return Err(anyhow!("{}: only shapes {}", self.0.name, MOCK_INTERFACE).into());
+        }
+        self.0
+            .throttle(pid, limit_bytes_per_sec, traffic_type, policy)
+    }
+
+    fn supports_address_family(&self, family: AddressFamily) -> bool {
+        family.supported_by(&self.0.capabilities)
+    }
+
+    fn throttle_upload_for_family(
+        &mut self,
+        pid: i32,
+        _process_name: String,
+        limit_bytes_per_sec: u64,
+        traffic_type: TrafficType,
+        family: AddressFamily,
+        _interfaces: Option<&[String]>,
+        policy: Policy,
+    ) -> ThrottleResult<()> {
+        if !self.supports_address_family(family) {
+            return Err(anyhow!("{}: cannot throttle {}", self.0.name, family).into());
+        }
+        self.0
+            .throttle(pid, limit_bytes_per_sec, traffic_type, policy)
+    }
+
+    fn adopt_or_clean(&mut self, adoptable: &HashMap<i32, u64>) -> ThrottleResult<AdoptionReport> {
+        self.0.adopt_or_clean(adoptable)
+    }
+
+    fn remove_upload_throttle(&mut self, pid: i32) -> ThrottleResult<()> {
+        self.0.remove(pid)
+    }
+
+    fn add_to_throttle(&mut self, pid: i32, member_pid: i32) -> ThrottleResult<()> {
+        self.0.add_member(pid, member_pid)
+    }
+
+    fn get_upload_throttle(&self, pid: i32) -> Option<u64> {
+        self.0.get(pid)
+    }
+
+    fn get_all_throttles(&self) -> HashMap<i32, u64> {
+        self.0.all()
+    }
+
+    fn cleanup(&mut self) -> ThrottleResult<()> {
+        self.0.cleanup()
+    }
+
+    fn supports_traffic_type(&self, traffic_type: TrafficType) -> bool {
+        self.0.traffic_support.supports(traffic_type)
+    }
+
+    fn set_interface_ceilings(&mut self, ceilings: &HashMap<String, u64>) -> ThrottleResult<()> {
+        self.0.handle.state().ceilings = ceilings.clone();
+        Ok(())
+    }
+
+    fn interface_ceiling(&self) -> Option<(String, u64)> {
+        let interface = MOCK_INTERFACE.to_string();
+        let ceiling = self.0.handle.state().ceilings.get(&interface).copied();
+        ceiling.map(|c| (interface, c))
+    }
+
+    fn attach_method(&self) -> Option<&'static str> {
+        self.0.handle.state().attach_method
+    }
+
+    fn max_throttles(&self) -> Option<usize> {
+        self.0.handle.state().max_throttles
+    }
+}
+
+/// Mock download backend
+#[derive(Debug, Clone)]
+pub struct MockDownloadBackend(MockBackend);
+
+impl MockDownloadBackend {
+    pub fn new(name: &'static str) -> Self {
+        Self(MockBackend::new(name))
+    }
+
+    pub fn with_priority(mut self, priority: BackendPriority) -> Self {
+        self.0.priority = priority;
+        self
+    }
+
+    pub fn with_traffic_support(mut self, traffic_support: TrafficTypeSupport) -> Self {
+        self.0.traffic_support = traffic_support;
+        self
+    }
+
+    /// Handle for inspecting calls and injecting failures
+    pub fn handle(&self) -> MockHandle {
+        self.0.handle.clone()
+    }
+}
+
+impl DownloadThrottleBackend for MockDownloadBackend {
+    fn name(&self) -> &'static str {
+        self.0.name
+    }
+
+    fn priority(&self) -> BackendPriority {
+        self.0.priority
+    }
+
+    fn is_available() -> bool {
+        true
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            traffic_types: self.0.traffic_support,
+            ..self.0.capabilities
+        }
+    }
+
+    fn policy_support(&self) -> PolicySupport {
+        self.0.policy_support
+    }
+
+    fn init(&mut self) -> ThrottleResult<()> {
+        self.0.init()
+    }
+
+    fn throttle_download(
+        &mut self,
+        pid: i32,
+        _process_name: String,
+        limit_bytes_per_sec: u64,
+        traffic_type: TrafficType,
+    ) -> ThrottleResult<()> {
+        let policy = self.0.policy_support.default;
+        self.0
+            .throttle(pid, limit_bytes_per_sec, traffic_type, policy)
+    }
+
+    fn adopt_or_clean(&mut self, adoptable: &HashMap<i32, u64>) -> ThrottleResult<AdoptionReport> {
+        self.0.adopt_or_clean(adoptable)
+    }
+
+    fn remove_download_throttle(&mut self, pid: i32) -> ThrottleResult<()> {
+        self.0.remove(pid)
+    }
+
+    fn get_download_throttle(&self, pid: i32) -> Option<u64> {
+        self.0.get(pid)
+    }
+
+    fn get_all_throttles(&self) -> HashMap<i32, u64> {
+        self.0.all()
+    }
+
+    fn cleanup(&mut self) -> ThrottleResult<()> {
+        self.0.cleanup()
+    }
+
+    fn supports_traffic_type(&self, traffic_type: TrafficType) -> bool {
+        self.0.traffic_support.supports(traffic_type)
+    }
+
+    fn attach_method(&self) -> Option<&'static str> {
+        self.0.handle.state().attach_method
+    }
+
+    fn max_throttles(&self) -> Option<usize> {
+        self.0.handle.state().max_throttles
+    }
+}
+
+/// Backend provider serving mock backends
+///
+/// Backends created through the provider share state with the registered
+/// template, so handles taken before registration see all later calls.
+#[derive(Default)]
+pub struct MockBackendProvider {
+    upload: Vec<(MockUploadBackend, bool)>,
+    download: Vec<(MockDownloadBackend, bool)>,
+    sandboxed: HashMap<i32, String>,
+    external: Vec<ExternalShaping>,
+}
+
+impl MockBackendProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register an upload backend and whether it reports as available
+    pub fn with_upload(mut self, backend: MockUploadBackend, available: bool) -> Self {
+        self.upload.push((backend, available));
+        self
+    }
+
+    /// Register a download backend and whether it reports as available
+    pub fn with_download(mut self, backend: MockDownloadBackend, available: bool) -> Self {
+        self.download.push((backend, available));
+        self
+    }
+
+    /// Report a PID as running in an app sandbox
+    pub fn with_sandboxed(mut self, pid: i32) -> Self {
+        self.sandboxed
+            .insert(pid, format!("app-flatpak-mock-{}.scope", pid));
+        self
+    }
+
+    /// Report a PID as running in a container or sandbox with the given cgroup
+    pub fn with_sandbox_cgroup(mut self, pid: i32, cgroup: &str) -> Self {
+        self.sandboxed.insert(pid, cgroup.to_string());
+        self
+    }
+
+    /// Report shaping set up outside chadthrottle (found only when its interface,
+    /// if any, is scanned)
+    pub fn with_external_shaping(mut self, shaping: ExternalShaping) -> Self {
+        self.external.push(shaping);
+        self
+    }
+}
+
+impl BackendProvider for MockBackendProvider {
+    fn upload_backends(&self) -> Vec<UploadBackendInfo> {
+        self.upload
+            .iter()
+            .map(|(b, available)| UploadBackendInfo {
+                name: b.0.name,
+                priority: b.0.priority,
+                available: *available,
+                unavailable_reason: (!available).then(|| "mock unavailable".to_string()),
+            })
+            .collect()
+    }
+
+    fn download_backends(&self) -> Vec<DownloadBackendInfo> {
+        self.download
+            .iter()
+            .map(|(b, available)| DownloadBackendInfo {
+                name: b.0.name,
+                priority: b.0.priority,
+                available: *available,
+                unavailable_reason: (!available).then(|| "mock unavailable".to_string()),
+            })
+            .collect()
+    }
+
+    fn create_upload_backend(&self, name: &str) -> Result<Box<dyn UploadThrottleBackend>> {
+        self.upload
+            .iter()
+            .find(|(b, _)| b.0.name == name)
+            .map(|(b, _)| Box::new(b.clone()) as Box<dyn UploadThrottleBackend>)
+            .ok_or_else(|| anyhow!("Unknown upload backend: {}", name))
+    }
+
+    fn create_download_backend(&self, name: &str) -> Result<Box<dyn DownloadThrottleBackend>> {
+        self.download
+            .iter()
+            .find(|(b, _)| b.0.name == name)
+            .map(|(b, _)| Box::new(b.clone()) as Box<dyn DownloadThrottleBackend>)
+            .ok_or_else(|| anyhow!("Unknown download backend: {}", name))
+    }
+
+    fn network_interfaces(&self) -> Vec<String> {
+        MOCK_NETWORK_INTERFACES
+            .iter()
+            .map(|name| name.to_string())
+            .collect()
+    }
+
+    fn sandbox_cgroup(&self, pid: i32) -> Option<String> {
+        self.sandboxed.get(&pid).cloned()
+    }
+
+    fn external_shaping(&self, interfaces: &[String]) -> Vec<ExternalShaping> {
+        self.external
+            .iter()
+            .filter(|shaping| {
+                shaping
+                    .interface
+                    .as_ref()
+                    .is_none_or(|interface| interfaces.contains(interface))
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn cgroup_info(&self) -> CgroupInfo {
+        CgroupInfo::default()
+    }
+}
+
+/// Process utilities backed by a fixed PID -> name table
+#[derive(Debug, Default)]
+pub struct MockProcessUtils {
+    pub processes: HashMap<i32, String>,
+    pub parents: HashMap<i32, i32>,
+    pub cmdlines: HashMap<i32, String>,
+}
+
+impl MockProcessUtils {
+    pub fn with_processes(processes: &[(i32, &str)]) -> Self {
+        Self {
+            processes: processes
+                .iter()
+                .map(|(pid, name)| (*pid, name.to_string()))
+                .collect(),
+            parents: HashMap::new(),
+            cmdlines: HashMap::new(),
+        }
+    }
+}
+
+impl ProcessUtils for MockProcessUtils {
+    fn get_process_name(&self, pid: i32) -> Result<String> {
+        self.processes
+            .get(&pid)
+            .cloned()
+            .ok_or_else(|| anyhow!("No such process: {}", pid))
+    }
+
+    fn process_exists(&self, pid: i32) -> bool {
+        self.processes.contains_key(&pid)
+    }
+
+    fn get_cmdline(&self, pid: i32) -> Result<String> {
+        self.cmdlines
+            .get(&pid)
+            .cloned()
+            .ok_or_else(|| anyhow!("No command line for process: {}", pid))
+    }
+
+    fn get_all_processes(&self) -> Result<Vec<ProcessEntry>> {
+        Ok(self
+            .processes
+            .iter()
+            .map(|(pid, name)| ProcessEntry {
+                pid: *pid,
+                name: name.clone(),
+            })
+            .collect())
+    }
+
+    fn get_parent_pid(&self, pid: i32) -> Option<i32> {
+        self.parents.get(&pid).copied()
+    }
+
+    fn get_connection_map(&self) -> Result<ConnectionMap> {
+        Ok(ConnectionMap::default())
+    }
+}
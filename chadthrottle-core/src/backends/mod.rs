@@ -0,0 +1,162 @@
+// Backend trait definitions and core types
+
+pub mod capability;
+pub mod cgroup;
+#[cfg(test)]
+pub mod mock;
+pub mod monitor;
+pub mod process;
+pub mod throttle;
+
+use crate::process::{AddressFamily, Policy, Priority, TrafficType};
+use anyhow::Result;
+
+/// Platform identifiers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    Linux,
+    MacOS,
+    Windows,
+    BSD,
+}
+
+impl Platform {
+    pub fn current() -> Self {
+        #[cfg(target_os = "linux")]
+        return Platform::Linux;
+
+        #[cfg(target_os = "macos")]
+        return Platform::MacOS;
+
+        #[cfg(target_os = "windows")]
+        return Platform::Windows;
+
+        #[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+        return Platform::BSD;
+    }
+}
+
+/// Backend priority ranking (higher = better)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BackendPriority {
+    Fallback = 1, // Works but limited (iptables, /proc parsing)
+    Good = 2,     // Solid implementation (IFB+TC, WFP)
+    Better = 3,   // Modern, efficient (eBPF XDP, nftables)
+    Best = 4,     // Optimal (eBPF cgroup, native APIs)
+}
+
+/// Capabilities that a backend supports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BackendCapabilities {
+    pub ipv4_support: bool,
+    pub ipv6_support: bool,
+    pub per_process: bool,
+    pub per_connection: bool,
+    pub tcp_support: bool,
+    pub udp_support: bool, // false if UDP traffic passes through unseen/unshaped
+    // Throttle backends: traffic types they can filter on.
+    // Monitors: whether they tell internet and local traffic apart
+    pub traffic_types: TrafficTypeSupport,
+}
+
+/// Traffic-type filtering supported by a throttle backend
+///
+/// Derived from the backend's `supports_traffic_type`, so it stays in sync
+/// with what the backend will actually accept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TrafficTypeSupport {
+    pub supports_internet_filter: bool,
+    pub supports_local_filter: bool,
+}
+
+impl TrafficTypeSupport {
+    /// Only 'All Traffic' (no internet/local distinction)
+    pub const ALL_ONLY: Self = Self {
+        supports_internet_filter: false,
+        supports_local_filter: false,
+    };
+
+    /// Build from a `supports_traffic_type`-style predicate
+    pub fn from_predicate(supports: impl Fn(crate::process::TrafficType) -> bool) -> Self {
+        use crate::process::TrafficType;
+        Self {
+            supports_internet_filter: supports(TrafficType::Internet),
+            supports_local_filter: supports(TrafficType::Local),
+        }
+    }
+
+    /// Check if a traffic type is supported (All is always supported)
+    pub fn supports(&self, traffic_type: crate::process::TrafficType) -> bool {
+        use crate::process::TrafficType;
+        match traffic_type {
+            TrafficType::All => true,
+            TrafficType::Internet => self.supports_internet_filter,
+            TrafficType::Local => self.supports_local_filter,
+        }
+    }
+
+    /// Traffic types supported by both `self` and `other`
+    pub fn intersect(&self, other: &Self) -> Self {
+        Self {
+            supports_internet_filter: self.supports_internet_filter
+                && other.supports_internet_filter,
+            supports_local_filter: self.supports_local_filter && other.supports_local_filter,
+        }
+    }
+}
+
+/// Over-limit policies a throttle backend can enforce
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PolicySupport {
+    pub shape: bool,
+    pub drop: bool,
+    /// Policy used for throttles that don't ask for one
+    pub default: Policy,
+}
+
+impl PolicySupport {
+    /// Backends that drop packets over the limit (police/filter style)
+    pub const DROP_ONLY: Self = Self::only(Policy::Drop);
+
+    /// Backends that can only enforce one policy, e.g. `only(Policy::Shape)` for
+    /// qdisc/pipe style backends that queue packets over the limit
+    pub const fn only(policy: Policy) -> Self {
+        Self {
+            shape: matches!(policy, Policy::Shape),
+            drop: matches!(policy, Policy::Drop),
+            default: policy,
+        }
+    }
+
+    /// Check if a policy is supported
+    pub fn supports(&self, policy: Policy) -> bool {
+        match policy {
+            Policy::Shape => self.shape,
+            Policy::Drop => self.drop,
+        }
+    }
+}
+
+/// Active throttle information
+#[derive(Debug, Clone)]
+pub struct ActiveThrottle {
+    pub pid: i32,
+    pub process_name: String,
+    pub upload_limit: Option<u64>,        // bytes/sec
+    pub download_limit: Option<u64>,      // bytes/sec
+    pub upload_minimum: Option<u64>,      // guaranteed upload rate (ignored where unsupported)
+    pub interfaces: Option<Vec<String>>,  // None = all interfaces
+    pub traffic_type: TrafficType,        // which traffic the limits apply to
+    pub family: AddressFamily,            // IP version the limits apply to
+    pub upload_backend: Option<String>,   // backend enforcing the upload limit
+    pub download_backend: Option<String>, // backend enforcing the download limit
+    pub policy: Option<Policy>,           // None = each backend's default policy
+    pub priority: Priority,               // rank against other throttled processes
+    pub children: Option<Vec<i32>>,       // descendants followed (None = process only)
+    /// Traffic type asked for when no backend could filter it and All was used instead
+    pub downgraded_from: Option<TrafficType>,
+    /// Runs in an app sandbox (Flatpak) or container (docker/podman): cgroup backends
+    /// throttle it in the sandbox's own cgroup, which covers the whole app or container,
+    /// and leave that cgroup alone on removal
+    pub sandboxed: bool,
+}
@@ -0,0 +1,303 @@
+// nettop-based network monitoring backend for macOS
+//
+// Reads per-process byte counters from `nettop` instead of capturing packets, so it
+// works for unprivileged users (no BPF device access needed). nettop doesn't say
+// which interface or remote address the bytes belong to, so all traffic is
+// attributed to a single "all" pseudo-interface and isn't split into internet/local.
+
+use crate::backends::monitor::{MonitorBackend, counter_rate, sample_interval};
+use crate::backends::process::{ProcessUtils, create_process_utils};
+use crate::backends::{BackendCapabilities, BackendPriority, TrafficTypeSupport};
+use crate::process::{InterfaceInfo, InterfaceMap, InterfaceStats, ProcessInfo, ProcessMap};
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+use std::time::Instant;
+
+const NETTOP_PATH: &str = "/usr/bin/nettop";
+
+/// One sample, per process, raw numbers, no DNS lookups, only the byte counters
+const NETTOP_ARGS: [&str; 7] = ["-P", "-L", "1", "-x", "-n", "-J", "bytes_in,bytes_out"];
+
+/// Pseudo-interface all traffic is attributed to
+pub const ALL_INTERFACES: &str = "all";
+
+/// Cumulative byte counters nettop reported for one process
+#[derive(Debug, Clone, PartialEq)]
+struct NettopSample {
+    name: String,
+    bytes_in: u64,
+    bytes_out: u64,
+}
+
+/// Per-process counters between polls
+struct ProcessCounters {
+    name: String,
+    last_bytes_in: u64,
+    last_bytes_out: u64,
+    total_download: u64, // bytes seen since monitoring started
+    total_upload: u64,
+    download_rate: u64,
+    upload_rate: u64,
+}
+
+impl ProcessCounters {
+    /// Start from nettop's current counters so traffic from before we started
+    /// doesn't show up as a burst
+    fn new(sample: &NettopSample) -> Self {
+        Self {
+            name: sample.name.clone(),
+            last_bytes_in: sample.bytes_in,
+            last_bytes_out: sample.bytes_out,
+            total_download: 0,
+            total_upload: 0,
+            download_rate: 0,
+            upload_rate: 0,
+        }
+    }
+
+    /// Record a poll `interval` seconds after the previous one (None after a suspend:
+    /// the traffic counts towards the totals, but gives no rate)
+    fn record(&mut self, sample: &NettopSample, interval: Option<f64>) {
+        // Counters drop when sockets close; that's not negative traffic
+        self.total_download += sample.bytes_in.saturating_sub(self.last_bytes_in);
+        self.total_upload += sample.bytes_out.saturating_sub(self.last_bytes_out);
+        self.download_rate = counter_rate(sample.bytes_in, self.last_bytes_in, interval);
+        self.upload_rate = counter_rate(sample.bytes_out, self.last_bytes_out, interval);
+        self.last_bytes_in = sample.bytes_in;
+        self.last_bytes_out = sample.bytes_out;
+    }
+}
+
+/// Per-process monitoring by polling nettop (no packet capture)
+pub struct NettopMonitor {
+    processes: HashMap<i32, ProcessCounters>,
+    parent_pids: HashMap<i32, Option<i32>>,
+    process_utils: Box<dyn ProcessUtils>,
+    last_update: Instant,
+}
+
+impl NettopMonitor {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            processes: HashMap::new(),
+            parent_pids: HashMap::new(),
+            process_utils: create_process_utils(),
+            last_update: Instant::now(),
+        })
+    }
+
+    fn poll_nettop() -> Result<HashMap<i32, NettopSample>> {
+        let output = Command::new(NETTOP_PATH)
+            .args(NETTOP_ARGS)
+            .output()
+            .context("Failed to run nettop")?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "nettop failed ({}): {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(parse_nettop_csv(&String::from_utf8_lossy(&output.stdout)))
+    }
+}
+
+/// Parse `nettop -P -L 1` CSV output into samples by PID
+///
+/// The header names the byte columns; the process column ("name.pid") has an empty header.
+fn parse_nettop_csv(output: &str) -> HashMap<i32, NettopSample> {
+    let mut samples = HashMap::new();
+    let mut lines = output.lines();
+    let Some(header) = lines.next() else {
+        return samples;
+    };
+    let columns: Vec<&str> = header.split(',').collect();
+    let (Some(in_col), Some(out_col)) = (
+        columns.iter().position(|c| *c == "bytes_in"),
+        columns.iter().position(|c| *c == "bytes_out"),
+    ) else {
+        log::warn!("Unexpected nettop header: {}", header);
+        return samples;
+    };
+    let process_col = columns.iter().position(|c| c.is_empty()).unwrap_or(0);
+
+    for line in lines.filter(|line| *line != header) {
+        let fields: Vec<&str> = line.split(',').collect();
+        let Some((name, pid)) = fields
+            .get(process_col)
+            .and_then(|process| process.rsplit_once('.'))
+        else {
+            continue;
+        };
+        let (Ok(pid), Some(bytes_in), Some(bytes_out)) = (
+            pid.parse::<i32>(),
+            fields.get(in_col).and_then(|v| v.parse::<u64>().ok()),
+            fields.get(out_col).and_then(|v| v.parse::<u64>().ok()),
+        ) else {
+            continue;
+        };
+        samples.insert(
+            pid,
+            NettopSample {
+                name: name.to_string(),
+                bytes_in,
+                bytes_out,
+            },
+        );
+    }
+    samples
+}
+
+impl MonitorBackend for NettopMonitor {
+    fn name(&self) -> &'static str {
+        "nettop"
+    }
+
+    fn priority(&self) -> BackendPriority {
+        BackendPriority::Better
+    }
+
+    fn is_available() -> bool {
+        Path::new(NETTOP_PATH).exists()
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            ipv4_support: true,
+            ipv6_support: true,
+            per_process: true,
+            per_connection: false,
+            tcp_support: true,
+            udp_support: true,
+            traffic_types: TrafficTypeSupport::ALL_ONLY,
+        }
+    }
+
+    fn init(&mut self) -> Result<()> {
+        // Take the baseline sample so the first update reports real rates
+        for (pid, sample) in Self::poll_nettop()? {
+            self.processes.insert(pid, ProcessCounters::new(&sample));
+        }
+        self.last_update = Instant::now();
+        Ok(())
+    }
+
+    fn update(&mut self) -> Result<(ProcessMap, InterfaceMap)> {
+        let samples = Self::poll_nettop()?;
+        let now = Instant::now();
+        let interval = sample_interval(self.last_update, now);
+        self.last_update = now;
+
+        // nettop only lists processes with sockets; forget the rest
+        self.processes.retain(|pid, _| samples.contains_key(pid));
+        self.parent_pids.retain(|pid, _| samples.contains_key(pid));
+
+        let mut process_map = ProcessMap::new();
+        for (pid, sample) in &samples {
+            let counters = self
+                .processes
+                .entry(*pid)
+                .or_insert_with(|| ProcessCounters::new(sample));
+            counters.record(sample, interval);
+
+            let process_utils = &self.process_utils;
+            let parent_pid = *self
+                .parent_pids
+                .entry(*pid)
+                .or_insert_with(|| process_utils.get_parent_pid(*pid));
+
+            let mut info = ProcessInfo::new(*pid, counters.name.clone());
+            info.parent_pid = parent_pid;
+            info.download_rate = counters.download_rate;
+            info.upload_rate = counters.upload_rate;
+            info.total_download = counters.total_download;
+            info.total_upload = counters.total_upload;
+            info.interface_stats.insert(
+                ALL_INTERFACES.to_string(),
+                InterfaceStats {
+                    download_rate: counters.download_rate,
+                    upload_rate: counters.upload_rate,
+                    total_download: counters.total_download,
+                    total_upload: counters.total_upload,
+                    internet_download_rate: 0,
+                    internet_upload_rate: 0,
+                    local_download_rate: 0,
+                    local_upload_rate: 0,
+                },
+            );
+            process_map.insert(*pid, info);
+        }
+
+        let mut interface_map = InterfaceMap::new();
+        interface_map.insert(
+            ALL_INTERFACES.to_string(),
+            InterfaceInfo {
+                name: ALL_INTERFACES.to_string(),
+                mac_address: None,
+                ip_addresses: Vec::new(),
+                is_up: true,
+                is_loopback: false,
+                mtu: None,
+                speed_mbps: None,
+                operstate: None,
+                driver: None,
+                errors: None,
+                total_download_rate: process_map.values().map(|p| p.download_rate).sum(),
+                total_upload_rate: process_map.values().map(|p| p.upload_rate).sum(),
+                process_count: process_map.len(),
+            },
+        );
+
+        Ok((process_map, interface_map))
+    }
+
+    fn cleanup(&mut self) -> Result<()> {
+        // nettop runs once per update; nothing to stop
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_nettop_csv() {
+        let output = "time,,bytes_in,bytes_out,\n\
+                      12:00:00.000000,launchd.1,0,0,\n\
+                      12:00:00.000000,Google Chrome He.4242,123456,7890,\n\
+                      12:00:00.000000,garbage,1,2,\n";
+        let samples = parse_nettop_csv(output);
+        assert_eq!(samples.len(), 2);
+        assert_eq!(
+            samples[&4242],
+            NettopSample {
+                name: "Google Chrome He".to_string(),
+                bytes_in: 123456,
+                bytes_out: 7890,
+            }
+        );
+    }
+
+    #[test]
+    fn test_counters_ignore_closed_sockets() {
+        let sample = |bytes_in, bytes_out| NettopSample {
+            name: "curl".to_string(),
+            bytes_in,
+            bytes_out,
+        };
+        let mut counters = ProcessCounters::new(&sample(1000, 100));
+        counters.record(&sample(3000, 300), Some(2.0));
+        assert_eq!((counters.download_rate, counters.upload_rate), (1000, 100));
+
+        // A socket closing lowers nettop's counters - no traffic, not an underflow
+        counters.record(&sample(500, 50), Some(1.0));
+        assert_eq!((counters.download_rate, counters.upload_rate), (0, 0));
+        assert_eq!(
+            (counters.total_download, counters.total_upload),
+            (2000, 200)
+        );
+    }
+}
@@ -0,0 +1,490 @@
+// Network monitoring backend trait and implementations
+
+use super::{BackendCapabilities, BackendPriority};
+use crate::process::{InterfaceMap, ProcessMap};
+use anyhow::Result;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "monitor-pnet")]
+pub mod pnet;
+
+#[cfg(target_os = "windows")]
+pub mod windows_poll;
+
+#[cfg(target_os = "macos")]
+pub mod macos_nettop;
+
+/// Network monitoring backend trait
+pub trait MonitorBackend: Send + Sync {
+    /// Backend name (e.g., "pnet", "ebpf", "wfp")
+    fn name(&self) -> &'static str;
+
+    /// Backend priority for auto-selection
+    fn priority(&self) -> BackendPriority;
+
+    /// Check if this backend is available on the current system
+    fn is_available() -> bool
+    where
+        Self: Sized;
+
+    /// Get backend capabilities
+    fn capabilities(&self) -> BackendCapabilities;
+
+    /// Initialize the monitor
+    fn init(&mut self) -> Result<()>;
+
+    /// Update network statistics (called periodically)
+    fn update(&mut self) -> Result<(ProcessMap, InterfaceMap)>;
+
+    /// Cleanup on shutdown
+    fn cleanup(&mut self) -> Result<()>;
+}
+
+/// Which interfaces a packet-capture monitor processes
+///
+/// This is applied at capture time, independent of the UI interface filter:
+/// packets from excluded interfaces are never parsed or attributed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CaptureOptions {
+    /// Capture only on these interfaces (None = all usable interfaces)
+    pub interfaces: Option<Vec<String>>,
+    /// Drop loopback packets unprocessed, unless loopback is listed in `interfaces`
+    pub skip_loopback: bool,
+    /// Time between scans of the system's sockets (never below
+    /// `MIN_CONNECTION_SCAN_INTERVAL`, so zero means every update)
+    pub connection_scan_interval: Duration,
+}
+
+impl CaptureOptions {
+    /// Build from config: the capture allowlist, and skip loopback in the Internet view
+    pub fn from_config(config: &crate::config::Config) -> Self {
+        Self {
+            interfaces: config.capture_interfaces.clone(),
+            skip_loopback: config.traffic_view_mode == Some(crate::process::TrafficType::Internet),
+            connection_scan_interval: Duration::from_secs(
+                config.connection_scan_interval_secs.unwrap_or_default(),
+            ),
+        }
+    }
+
+    /// Whether a capture thread should be started on this interface
+    pub fn captures(&self, interface_name: &str) -> bool {
+        self.interfaces
+            .as_ref()
+            .is_none_or(|names| names.iter().any(|n| n == interface_name))
+    }
+
+    /// Whether loopback skipping applies to this interface (explicitly allowlisted
+    /// loopback interfaces are always processed)
+    pub fn is_skippable(&self, interface_name: &str, is_loopback: bool) -> bool {
+        is_loopback
+            && !self
+                .interfaces
+                .as_ref()
+                .is_some_and(|names| names.iter().any(|n| n == interface_name))
+    }
+}
+
+/// Connections are scanned at most this often (the monitor's update interval)
+pub const MIN_CONNECTION_SCAN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Longest a slow scan can stretch the wait between connection scans
+pub const MAX_CONNECTION_SCAN_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Wait before the next connection scan: the configured interval, stretched so that a
+/// slow scan (tens of thousands of sockets) keeps a thread busy a quarter of the time
+/// at most
+pub fn connection_scan_wait(configured: Duration, scan_took: Duration) -> Duration {
+    let interval = configured.max(MIN_CONNECTION_SCAN_INTERVAL);
+    interval.max((scan_took * 4).min(MAX_CONNECTION_SCAN_BACKOFF))
+}
+
+/// Link details the kernel reports for an interface
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LinkInfo {
+    /// Operational state (None if not reported - loopback and tun say "unknown")
+    pub is_up: Option<bool>,
+    /// The kernel's operational state as reported ("up", "down", "dormant", ...)
+    pub operstate: Option<String>,
+    pub mtu: Option<u32>,
+    /// None for virtual interfaces, most Wi-Fi drivers, and links that are down
+    pub speed_mbps: Option<u64>,
+    /// Kernel driver bound to the device (None for virtual interfaces)
+    pub driver: Option<String>,
+    pub errors: Option<InterfaceErrors>,
+}
+
+impl LinkInfo {
+    /// Read from /sys/class/net/<interface>/ on Linux and getifaddrs on macOS
+    /// (nothing is reported on other platforms)
+    pub fn read(interface_name: &str) -> Self {
+        #[cfg(target_os = "linux")]
+        {
+            let dir = std::path::Path::new("/sys/class/net").join(interface_name);
+            let read = |file: &str| std::fs::read_to_string(dir.join(file)).ok();
+            let counter = |name: &str| {
+                read(&format!("statistics/{}", name)).and_then(|value| value.trim().parse().ok())
+            };
+            let operstate = read("operstate")
+                .map(|state| state.trim().to_string())
+                .filter(|state| !state.is_empty());
+            Self {
+                is_up: operstate.as_deref().and_then(parse_operstate),
+                operstate,
+                mtu: read("mtu").and_then(|mtu| mtu.trim().parse().ok()),
+                // Reading fails with EINVAL when the driver has no speed to report
+                speed_mbps: read("speed").and_then(|speed| parse_link_speed(&speed)),
+                driver: std::fs::read_link(dir.join("device/driver"))
+                    .ok()
+                    .and_then(|path| Some(path.file_name()?.to_string_lossy().into_owned())),
+                errors: (|| {
+                    Some(InterfaceErrors {
+                        rx_errors: counter("rx_errors")?,
+                        tx_errors: counter("tx_errors")?,
+                        rx_dropped: counter("rx_dropped")?,
+                        tx_dropped: counter("tx_dropped")?,
+                    })
+                })(),
+            }
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            read_macos_link_info(interface_name).unwrap_or_default()
+        }
+
+        #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+        {
+            let _ = interface_name;
+            Self::default()
+        }
+    }
+}
+
+/// Error and drop counters of an interface (totals since the interface came up)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InterfaceErrors {
+    pub rx_errors: u64,
+    pub tx_errors: u64,
+    pub rx_dropped: u64,
+    pub tx_dropped: u64,
+}
+
+impl InterfaceErrors {
+    /// How much each counter grew since `previous` (a counter that was reset counts as
+    /// no growth)
+    pub fn increase_since(&self, previous: &InterfaceErrors) -> InterfaceErrors {
+        InterfaceErrors {
+            rx_errors: self.rx_errors.saturating_sub(previous.rx_errors),
+            tx_errors: self.tx_errors.saturating_sub(previous.tx_errors),
+            rx_dropped: self.rx_dropped.saturating_sub(previous.rx_dropped),
+            tx_dropped: self.tx_dropped.saturating_sub(previous.tx_dropped),
+        }
+    }
+}
+
+/// MTU, up/down and error counters from the interface's AF_LINK entry in getifaddrs
+#[cfg(target_os = "macos")]
+fn read_macos_link_info(interface_name: &str) -> Option<LinkInfo> {
+    let mut addrs: *mut libc::ifaddrs = std::ptr::null_mut();
+    // SAFETY: getifaddrs allocates the list, which is freed below
+    if unsafe { libc::getifaddrs(&mut addrs) } != 0 {
+        return None;
+    }
+
+    let mut info = None;
+    let mut cursor = addrs;
+    while !cursor.is_null() {
+        // SAFETY: entries stay valid until freeifaddrs
+        let entry = unsafe { &*cursor };
+        cursor = entry.ifa_next;
+        if entry.ifa_addr.is_null() || entry.ifa_data.is_null() {
+            continue;
+        }
+        // SAFETY: ifa_addr and ifa_name are set for every entry with an address
+        let (family, name) = unsafe {
+            (
+                (*entry.ifa_addr).sa_family as i32,
+                std::ffi::CStr::from_ptr(entry.ifa_name),
+            )
+        };
+        if family != libc::AF_LINK || name.to_bytes() != interface_name.as_bytes() {
+            continue;
+        }
+
+        // SAFETY: ifa_data of an AF_LINK entry points to its if_data
+        let data = unsafe { &*(entry.ifa_data as *const libc::if_data) };
+        let running = (libc::IFF_UP | libc::IFF_RUNNING) as u32;
+        let is_up = entry.ifa_flags & running == running;
+        info = Some(LinkInfo {
+            is_up: Some(is_up),
+            operstate: Some(if is_up { "up" } else { "down" }.to_string()),
+            mtu: Some(data.ifi_mtu),
+            speed_mbps: None,
+            driver: None,
+            errors: Some(InterfaceErrors {
+                rx_errors: data.ifi_ierrors as u64,
+                tx_errors: data.ifi_oerrors as u64,
+                rx_dropped: data.ifi_iqdrops as u64,
+                tx_dropped: 0,
+            }),
+        });
+        break;
+    }
+
+    // SAFETY: allocated by getifaddrs above
+    unsafe { libc::freeifaddrs(addrs) };
+    info
+}
+
+#[cfg(target_os = "linux")]
+fn parse_operstate(state: &str) -> Option<bool> {
+    match state.trim() {
+        "up" => Some(true),
+        "unknown" | "" => None,
+        _ => Some(false), // down, dormant, lowerlayerdown, notpresent, testing
+    }
+}
+
+/// Link speed in Mb/s (drivers report -1 when the link is down or the speed unknown)
+#[cfg(target_os = "linux")]
+fn parse_link_speed(speed: &str) -> Option<u64> {
+    speed
+        .trim()
+        .parse::<i64>()
+        .ok()
+        .filter(|&mbps| mbps > 0)
+        .map(|mbps| mbps as u64)
+}
+
+/// How long terminated processes stay listed (None = forever), set from the config
+static TERMINATED_RETENTION: OnceLock<Option<Duration>> = OnceLock::new();
+
+/// Install the terminated-process retention (call once at startup, before monitoring)
+pub fn init_terminated_retention(retention: Option<Duration>) {
+    TERMINATED_RETENTION.get_or_init(|| retention);
+}
+
+/// How long terminated processes stay listed before they're forgotten (None = forever)
+pub fn terminated_retention() -> Option<Duration> {
+    TERMINATED_RETENTION
+        .get()
+        .copied()
+        .unwrap_or_else(|| crate::config::Config::default().terminated_retention())
+}
+
+/// Whether a process that terminated `since_termination` ago should still be listed
+pub fn is_terminated_retained(since_termination: Duration) -> bool {
+    terminated_retention().is_none_or(|retention| since_termination < retention)
+}
+
+/// Longest gap between two samples that still gives a meaningful rate. Longer gaps
+/// (a suspend/resume, a stalled update loop) are discarded and the baselines start over
+pub const MAX_SAMPLE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Seconds between a baseline read at `since` and a sample read at `now`, or None if
+/// the sample gives no rate (no time passed, or more than `MAX_SAMPLE_INTERVAL`)
+pub fn sample_interval(since: Instant, now: Instant) -> Option<f64> {
+    let elapsed = now.saturating_duration_since(since);
+    (!elapsed.is_zero() && elapsed <= MAX_SAMPLE_INTERVAL).then_some(elapsed.as_secs_f64())
+}
+
+/// Bytes per second between two readings of a cumulative counter `interval` seconds
+/// apart. A counter smaller than before was reset, which gives no rate rather than a
+/// negative (or wrapped) one
+pub fn counter_rate(current: u64, previous: u64, interval: Option<f64>) -> u64 {
+    match interval {
+        Some(secs) if current >= previous => ((current - previous) as f64 / secs) as u64,
+        _ => 0,
+    }
+}
+
+/// Monitor backend metadata for selection
+#[derive(Debug, Clone)]
+pub struct MonitorBackendInfo {
+    pub name: &'static str,
+    pub priority: BackendPriority,
+    pub available: bool,
+}
+
+/// Detect all available monitor backends on current system
+pub fn detect_available_backends() -> Vec<MonitorBackendInfo> {
+    let mut backends = Vec::new();
+
+    #[cfg(feature = "monitor-pnet")]
+    {
+        backends.push(MonitorBackendInfo {
+            name: "pnet",
+            priority: BackendPriority::Good,
+            available: pnet::PnetMonitor::is_available(),
+        });
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        backends.push(MonitorBackendInfo {
+            name: "windows-poll",
+            priority: BackendPriority::Good,
+            available: windows_poll::WindowsPollingMonitor::is_available(),
+        });
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        // No packet capture (or root) needed, so preferred over pnet when present
+        backends.push(MonitorBackendInfo {
+            name: "nettop",
+            priority: BackendPriority::Better,
+            available: macos_nettop::NettopMonitor::is_available(),
+        });
+    }
+
+    backends
+}
+
+/// Auto-select best available monitor backend
+pub fn select_monitor_backend(preference: Option<&str>) -> Result<Box<dyn MonitorBackend>> {
+    let available = detect_available_backends();
+
+    if let Some(name) = preference {
+        // User explicitly requested a backend
+        return create_monitor_backend(name);
+    }
+
+    // Auto-select best available
+    available
+        .iter()
+        .filter(|b| b.available)
+        .max_by_key(|b| b.priority)
+        .and_then(|info| create_monitor_backend(info.name).ok())
+        .ok_or_else(|| anyhow::anyhow!("No monitoring backend available"))
+}
+
+/// Create a monitor backend by name
+fn create_monitor_backend(name: &str) -> Result<Box<dyn MonitorBackend>> {
+    match name {
+        #[cfg(feature = "monitor-pnet")]
+        "pnet" => Ok(Box::new(pnet::PnetMonitor::new()?)),
+
+        #[cfg(target_os = "windows")]
+        "windows-poll" | "windows-poll-basic" | "windows-poll-stats" => {
+            Ok(Box::new(windows_poll::WindowsPollingMonitor::new()?))
+        }
+
+        #[cfg(target_os = "macos")]
+        "nettop" => {
+            let mut backend = macos_nettop::NettopMonitor::new()?;
+            backend.init()?;
+            Ok(Box::new(backend))
+        }
+
+        _ => Err(anyhow::anyhow!("Unknown monitor backend: {}", name)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_options_allowlist() {
+        let all = CaptureOptions {
+            interfaces: None,
+            skip_loopback: true,
+            ..Default::default()
+        };
+        assert!(all.captures("eth0"));
+        assert!(all.is_skippable("lo", true));
+        assert!(!all.is_skippable("eth0", false));
+
+        // Explicitly listed loopback is never skipped
+        let listed = CaptureOptions {
+            interfaces: Some(vec!["eth0".to_string(), "lo".to_string()]),
+            skip_loopback: true,
+            ..Default::default()
+        };
+        assert!(listed.captures("lo"));
+        assert!(!listed.captures("wlan0"));
+        assert!(!listed.is_skippable("lo", true));
+    }
+
+    #[test]
+    fn test_slow_connection_scans_back_off() {
+        let secs = Duration::from_secs;
+        let ms = Duration::from_millis;
+        assert_eq!(connection_scan_wait(Duration::ZERO, ms(20)), secs(1));
+        assert_eq!(connection_scan_wait(secs(5), ms(20)), secs(5));
+        // A 600 ms scan waits 2.4 s, however often scans were asked for
+        assert_eq!(connection_scan_wait(secs(1), ms(600)), ms(2400));
+        assert_eq!(connection_scan_wait(secs(1), secs(20)), secs(30));
+        assert_eq!(connection_scan_wait(secs(60), secs(20)), secs(60));
+    }
+
+    #[test]
+    fn test_rates_use_the_real_interval() {
+        let start = Instant::now();
+        let interval = sample_interval(start, start + Duration::from_millis(250));
+        assert_eq!(interval, Some(0.25));
+        assert_eq!(counter_rate(1500, 1000, interval), 2000);
+
+        // A delayed tick is still measured correctly
+        let late = sample_interval(start, start + Duration::from_secs(3));
+        assert_eq!(counter_rate(4000, 1000, late), 1000);
+    }
+
+    #[test]
+    fn test_suspend_gaps_and_counter_resets_give_no_rate() {
+        let start = Instant::now();
+        // Resumed after an hour: the bytes queued meanwhile don't show as a spike
+        let resumed = sample_interval(start, start + Duration::from_secs(3600));
+        assert_eq!(resumed, None);
+        assert_eq!(counter_rate(5_000_000_000, 0, resumed), 0);
+        assert_eq!(sample_interval(start, start), None);
+        assert_eq!(
+            sample_interval(start, start + MAX_SAMPLE_INTERVAL),
+            Some(MAX_SAMPLE_INTERVAL.as_secs_f64())
+        );
+
+        // A counter that went backwards was reset, not a huge (wrapped) delta
+        let interval = sample_interval(start, start + Duration::from_secs(1));
+        assert_eq!(counter_rate(100, u64::MAX - 5, interval), 0);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_parse_link_sysfs_values() {
+        assert_eq!(parse_operstate("up\n"), Some(true));
+        assert_eq!(parse_operstate("dormant\n"), Some(false));
+        assert_eq!(parse_operstate("unknown\n"), None);
+
+        assert_eq!(parse_link_speed("1000\n"), Some(1000));
+        assert_eq!(parse_link_speed("-1\n"), None);
+        assert_eq!(parse_link_speed(""), None);
+    }
+
+    #[test]
+    fn test_error_counter_increase() {
+        let previous = InterfaceErrors {
+            rx_errors: 10,
+            tx_errors: 4,
+            rx_dropped: 7,
+            tx_dropped: 0,
+        };
+        let current = InterfaceErrors {
+            rx_errors: 12,
+            tx_errors: 4,
+            rx_dropped: 3, // reset (interface went down and up)
+            tx_dropped: 1,
+        };
+        assert_eq!(
+            current.increase_since(&previous),
+            InterfaceErrors {
+                rx_errors: 2,
+                tx_errors: 0,
+                rx_dropped: 0,
+                tx_dropped: 1,
+            }
+        );
+    }
+}
@@ -1,7 +1,7 @@
 // pnet-based network monitoring backend
 
-use crate::backends::monitor::MonitorBackend;
-use crate::backends::{BackendCapabilities, BackendPriority};
+use crate::backends::monitor::{CaptureOptions, MonitorBackend};
+use crate::backends::{BackendCapabilities, BackendPriority, TrafficTypeSupport};
 use crate::monitor::NetworkMonitor as LegacyNetworkMonitor;
 use crate::process::{InterfaceMap, ProcessMap};
 use anyhow::Result;
@@ -12,9 +12,14 @@ pub struct PnetMonitor {
 }
 
 impl PnetMonitor {
+    /// Create a monitor using the socket mapper and capture interfaces from config
     pub fn new() -> Result<Self> {
+        let config = crate::config::Config::load().unwrap_or_default();
         Ok(Self {
-            inner: LegacyNetworkMonitor::new()?,
+            inner: LegacyNetworkMonitor::with_socket_mapper(
+                config.preferred_socket_mapper.as_deref(),
+                CaptureOptions::from_config(&config),
+            )?,
         })
     }
 }
@@ -43,6 +48,12 @@ impl MonitorBackend for PnetMonitor {
             ipv6_support: true,
             per_process: true,
             per_connection: false,
+            tcp_support: true,
+            udp_support: true,
+            traffic_types: TrafficTypeSupport {
+                supports_internet_filter: true,
+                supports_local_filter: true,
+            },
         }
     }
 
@@ -56,7 +67,7 @@ impl MonitorBackend for PnetMonitor {
     }
 
     fn cleanup(&mut self) -> Result<()> {
-        // NetworkMonitor doesn't need explicit cleanup
-        Ok(())
+        // Stops and joins the capture threads
+        self.inner.cleanup()
     }
 }
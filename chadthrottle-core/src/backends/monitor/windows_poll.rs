@@ -9,12 +9,15 @@
 // Uses polling (1 second interval) instead of packet capture.
 // All metrics are accurate - no approximations or estimations.
 
-use crate::backends::monitor::MonitorBackend;
+use crate::backends::monitor::{
+    MAX_SAMPLE_INTERVAL, MonitorBackend, counter_rate, is_terminated_retained, sample_interval,
+};
 use crate::backends::process::{ConnectionMap, ProcessUtils};
-use crate::backends::{BackendCapabilities, BackendPriority};
+use crate::backends::{BackendCapabilities, BackendPriority, TrafficTypeSupport};
+use crate::history::BandwidthSample;
 use crate::process::{InterfaceMap, ProcessInfo, ProcessMap};
 use anyhow::Result;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::net::IpAddr;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
@@ -44,6 +47,9 @@ struct MIB_TCPROW {
     dwRemotePort: u32,
 }
 
+/// Number of bandwidth samples kept per process for the graph (1 per poll)
+const MAX_HISTORY_SAMPLES: usize = 60;
+
 /// Monitoring tier based on available privileges and features
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MonitoringTier {
@@ -75,6 +81,12 @@ struct ConnectionTracker {
     // Per-process aggregated bandwidth
     process_bandwidth: HashMap<i32, ProcessBandwidth>,
 
+    // PID -> termination time (for showing terminated processes temporarily)
+    terminated_processes: HashMap<i32, Instant>,
+
+    // Per-process rate samples (one per poll) for the bandwidth graph
+    bandwidth_history: HashMap<i32, VecDeque<BandwidthSample>>,
+
     // Per-interface aggregated bandwidth
     interface_bandwidth: HashMap<String, InterfaceBandwidth>,
 
@@ -136,7 +148,7 @@ struct ConnectionStats {
 
 /// Aggregated bandwidth per process
 #[derive(Debug, Clone)]
-struct ProcessBandwidth {
+pub struct ProcessBandwidth {
     name: String,
 
     // Current sum of all connection cumulative totals (for reference)
@@ -222,6 +234,14 @@ struct ProcessInterfaceBandwidth {
     last_internet_tx_bytes: u64,
     last_local_rx_bytes: u64,
     last_local_tx_bytes: u64,
+
+    // Calculated rates (bytes/sec), as of the last poll
+    rx_rate: u64,
+    tx_rate: u64,
+    internet_rx_rate: u64,
+    internet_tx_rate: u64,
+    local_rx_rate: u64,
+    local_tx_rate: u64,
 }
 
 /// Windows network interface information
@@ -234,6 +254,8 @@ struct WindowsNetworkInterface {
     ip_addresses: Vec<IpAddr>,
     is_up: bool,
     is_loopback: bool,
+    mtu: Option<u32>,
+    speed_mbps: Option<u64>,
 }
 
 impl WindowsPollingMonitor {
@@ -263,6 +285,8 @@ impl WindowsPollingMonitor {
             udp_connections: HashMap::new(),
             udp6_connections: HashMap::new(),
             process_bandwidth: HashMap::new(),
+            terminated_processes: HashMap::new(),
+            bandwidth_history: HashMap::new(),
             interface_bandwidth: HashMap::new(),
             process_interface_bandwidth: HashMap::new(),
             #[cfg(target_os = "windows")]
@@ -316,6 +340,63 @@ impl WindowsPollingMonitor {
     pub fn tier(&self) -> MonitoringTier {
         self.tier
     }
+
+    /// Per-process bandwidth samples recorded by the polling thread
+    pub fn get_bandwidth_data(&self) -> HashMap<i32, Vec<BandwidthSample>> {
+        let tracker = self.connection_tracker.lock().unwrap();
+        tracker
+            .bandwidth_history
+            .iter()
+            .map(|(pid, samples)| (*pid, samples.iter().cloned().collect()))
+            .collect()
+    }
+
+    /// Extract bandwidth data for preservation when switching backends
+    pub fn extract_bandwidth_data(
+        &self,
+    ) -> (HashMap<i32, ProcessBandwidth>, HashMap<i32, Instant>) {
+        let tracker = self.connection_tracker.lock().unwrap();
+        (
+            tracker.process_bandwidth.clone(),
+            tracker.terminated_processes.clone(),
+        )
+    }
+
+    /// Restore bandwidth data after switching backends
+    pub fn restore_bandwidth_data(
+        &mut self,
+        process_bandwidth: HashMap<i32, ProcessBandwidth>,
+        terminated_processes: HashMap<i32, Instant>,
+    ) {
+        let mut tracker = self.connection_tracker.lock().unwrap();
+        tracker.process_bandwidth = process_bandwidth;
+        tracker.terminated_processes = terminated_processes;
+        log::info!(
+            "Restored bandwidth data for {} processes ({} terminated)",
+            tracker.process_bandwidth.len(),
+            tracker.terminated_processes.len()
+        );
+    }
+
+    /// Restore graph samples after switching backends
+    pub fn restore_bandwidth_history(&mut self, history: HashMap<i32, Vec<BandwidthSample>>) {
+        let mut tracker = self.connection_tracker.lock().unwrap();
+        tracker.bandwidth_history = history
+            .into_iter()
+            .map(|(pid, samples)| (pid, samples.into()))
+            .collect();
+    }
+}
+
+impl ConnectionTracker {
+    /// Drop all state kept for a process
+    fn forget_process(&mut self, pid: i32) {
+        self.process_bandwidth.remove(&pid);
+        self.terminated_processes.remove(&pid);
+        self.bandwidth_history.remove(&pid);
+        self.process_interface_bandwidth
+            .retain(|(p, _), _| *p != pid);
+    }
 }
 
 impl Drop for WindowsPollingMonitor {
@@ -355,6 +436,12 @@ impl MonitorBackend for WindowsPollingMonitor {
             ipv6_support: true,
             per_process: matches!(self.tier, MonitoringTier::Stats),
             per_connection: matches!(self.tier, MonitoringTier::Stats),
+            tcp_support: true,
+            udp_support: false, // TCP estats only
+            traffic_types: TrafficTypeSupport {
+                supports_internet_filter: true,
+                supports_local_filter: true,
+            },
         }
     }
 
@@ -364,12 +451,14 @@ impl MonitorBackend for WindowsPollingMonitor {
     }
 
     fn update(&mut self) -> Result<(ProcessMap, InterfaceMap)> {
-        let tracker = self.connection_tracker.lock().unwrap();
+        // Refresh the System cache once per cycle so newly started processes are found
+        // (used for exit detection and parent PIDs)
+        self.process_utils.refresh_caches();
+
+        let mut tracker = self.connection_tracker.lock().unwrap();
         let mut process_map = ProcessMap::new();
 
-        // Calculate elapsed time for rate calculations
         let now = Instant::now();
-        let elapsed = now.duration_since(tracker.last_update).as_secs_f64();
 
         match tracker.tier {
             MonitoringTier::Basic => {
@@ -405,9 +494,24 @@ impl MonitorBackend for WindowsPollingMonitor {
             }
 
             MonitoringTier::Stats => {
+                // Mark processes that have exited, and forget them once they've been
+                // shown as terminated long enough
+                let pids: Vec<i32> = tracker.process_bandwidth.keys().copied().collect();
+                for pid in pids {
+                    if self.process_utils.process_exists(pid) {
+                        tracker.terminated_processes.remove(&pid);
+                        continue;
+                    }
+                    let terminated_at = *tracker.terminated_processes.entry(pid).or_insert(now);
+                    if !is_terminated_retained(now.duration_since(terminated_at)) {
+                        tracker.forget_process(pid);
+                    }
+                }
+
                 // Show processes with accurate bandwidth data
                 for (&pid, bandwidth) in &tracker.process_bandwidth {
                     let mut info = ProcessInfo::new(pid, bandwidth.name.clone());
+                    info.is_terminated = tracker.terminated_processes.contains_key(&pid);
                     info.download_rate = bandwidth.rx_rate;
                     info.upload_rate = bandwidth.tx_rate;
                     // Use lifetime accumulated deltas, not sum of connection totals
@@ -425,71 +529,30 @@ impl MonitorBackend for WindowsPollingMonitor {
                     info.local_total_download = bandwidth.lifetime_local_rx_bytes;
                     info.local_total_upload = bandwidth.lifetime_local_tx_bytes;
 
+                    // Byte counts come from TCP connection statistics only
+                    info.tcp_download_rate = bandwidth.rx_rate;
+                    info.tcp_upload_rate = bandwidth.tx_rate;
+                    info.tcp_total_download = bandwidth.lifetime_rx_bytes;
+                    info.tcp_total_upload = bandwidth.lifetime_tx_bytes;
+
                     // Populate per-interface stats for this process
                     info.interface_stats = tracker
                         .process_interface_bandwidth
                         .iter()
                         .filter(|((p, _), _)| *p == pid)
                         .map(|((_, iface), bw)| {
-                            // Calculate rates based on deltas
-                            let rx_diff = bw.rx_bytes.saturating_sub(bw.last_rx_bytes);
-                            let tx_diff = bw.tx_bytes.saturating_sub(bw.last_tx_bytes);
-
-                            let download_rate = if elapsed > 0.0 {
-                                (rx_diff as f64 / elapsed) as u64
-                            } else {
-                                0
-                            };
-                            let upload_rate = if elapsed > 0.0 {
-                                (tx_diff as f64 / elapsed) as u64
-                            } else {
-                                0
-                            };
-
-                            // Calculate categorized rates
-                            let internet_rx_diff = bw
-                                .internet_rx_bytes
-                                .saturating_sub(bw.last_internet_rx_bytes);
-                            let internet_tx_diff = bw
-                                .internet_tx_bytes
-                                .saturating_sub(bw.last_internet_tx_bytes);
-                            let local_rx_diff =
-                                bw.local_rx_bytes.saturating_sub(bw.last_local_rx_bytes);
-                            let local_tx_diff =
-                                bw.local_tx_bytes.saturating_sub(bw.last_local_tx_bytes);
-
-                            let internet_download_rate = if elapsed > 0.0 {
-                                (internet_rx_diff as f64 / elapsed) as u64
-                            } else {
-                                0
-                            };
-                            let internet_upload_rate = if elapsed > 0.0 {
-                                (internet_tx_diff as f64 / elapsed) as u64
-                            } else {
-                                0
-                            };
-                            let local_download_rate = if elapsed > 0.0 {
-                                (local_rx_diff as f64 / elapsed) as u64
-                            } else {
-                                0
-                            };
-                            let local_upload_rate = if elapsed > 0.0 {
-                                (local_tx_diff as f64 / elapsed) as u64
-                            } else {
-                                0
-                            };
-
+                            // Rates come from the poll, over the interval it measured
                             (
                                 iface.clone(),
                                 crate::process::InterfaceStats {
-                                    download_rate,
-                                    upload_rate,
+                                    download_rate: bw.rx_rate,
+                                    upload_rate: bw.tx_rate,
                                     total_download: bw.rx_bytes,
                                     total_upload: bw.tx_bytes,
-                                    internet_download_rate,
-                                    internet_upload_rate,
-                                    local_download_rate,
-                                    local_upload_rate,
+                                    internet_download_rate: bw.internet_rx_rate,
+                                    internet_upload_rate: bw.internet_tx_rate,
+                                    local_download_rate: bw.local_rx_rate,
+                                    local_upload_rate: bw.local_tx_rate,
                                 },
                             )
                         })
@@ -500,6 +563,11 @@ impl MonitorBackend for WindowsPollingMonitor {
             }
         }
 
+        // Parent PIDs for the process tree view
+        for info in process_map.values_mut() {
+            info.parent_pid = self.process_utils.get_parent_pid(info.pid);
+        }
+
         // Build interface map with bandwidth statistics (INSIDE LOCK - no clones!)
         let mut interface_map = InterfaceMap::new();
 
@@ -621,6 +689,11 @@ impl MonitorBackend for WindowsPollingMonitor {
                         ip_addresses: interface.ip_addresses.clone(),
                         is_up: interface.is_up,
                         is_loopback: interface.is_loopback,
+                        mtu: interface.mtu,
+                        speed_mbps: interface.speed_mbps,
+                        operstate: Some(if interface.is_up { "up" } else { "down" }.to_string()),
+                        driver: None,
+                        errors: None,
                         total_download_rate: download_rate,
                         total_upload_rate: upload_rate,
                         process_count,
@@ -944,7 +1017,14 @@ fn update_connection_stats(tracker: &Arc<Mutex<ConnectionTracker>>) -> Result<()
     // Use a separate mutex lock AFTER connection list is updated
     let mut tracker = tracker.lock().unwrap();
     let now = Instant::now();
-    let elapsed = now.duration_since(tracker.last_update).as_secs_f64();
+    // None after a suspend (or a stalled poll): rates are zeroed and the baselines restart
+    let interval = sample_interval(tracker.last_update, now);
+    if now.duration_since(tracker.last_update) > MAX_SAMPLE_INTERVAL {
+        log::info!(
+            "{:.0}s since the last poll (suspended?), discarding this sample's rates",
+            now.duration_since(tracker.last_update).as_secs_f64()
+        );
+    }
 
     // Refresh interface cache if needed (every 10 seconds)
     #[cfg(target_os = "windows")]
@@ -1048,12 +1128,14 @@ fn update_connection_stats(tracker: &Arc<Mutex<ConnectionTracker>>) -> Result<()
             stats.remote_addr,
             stats.remote_port
         );
-        if let Some((rx_bytes, tx_bytes)) = get_tcp_stats(
-            &stats.local_addr,
-            stats.local_port,
-            &stats.remote_addr,
-            stats.remote_port,
-        ) {
+        if crate::traffic_classifier::is_counted(&stats.remote_addr)
+            && let Some((rx_bytes, tx_bytes)) = get_tcp_stats(
+                &stats.local_addr,
+                stats.local_port,
+                &stats.remote_addr,
+                stats.remote_port,
+            )
+        {
             log::trace!("  -> Got {} RX bytes, {} TX bytes", rx_bytes, tx_bytes);
 
             // Look up previous connection stats to calculate delta
@@ -1071,8 +1153,8 @@ fn update_connection_stats(tracker: &Arc<Mutex<ConnectionTracker>>) -> Result<()
                 .unwrap_or((0, 0));
 
             // Calculate delta for this specific connection
-            let delta_rx = rx_bytes.saturating_sub(prev_rx);
-            let delta_tx = tx_bytes.saturating_sub(prev_tx);
+            let delta_rx = counter_delta(rx_bytes, prev_rx);
+            let delta_tx = counter_delta(tx_bytes, prev_tx);
 
             // Accumulate: sum of connection cumulative totals (for reference)
             entry.rx_bytes += rx_bytes;
@@ -1189,12 +1271,14 @@ fn update_connection_stats(tracker: &Arc<Mutex<ConnectionTracker>>) -> Result<()
             stats.remote_addr,
             stats.remote_port
         );
-        if let Some((rx_bytes, tx_bytes)) = get_tcp_stats(
-            &stats.local_addr,
-            stats.local_port,
-            &stats.remote_addr,
-            stats.remote_port,
-        ) {
+        if crate::traffic_classifier::is_counted(&stats.remote_addr)
+            && let Some((rx_bytes, tx_bytes)) = get_tcp_stats(
+                &stats.local_addr,
+                stats.local_port,
+                &stats.remote_addr,
+                stats.remote_port,
+            )
+        {
             log::trace!("  -> Got {} RX bytes, {} TX bytes", rx_bytes, tx_bytes);
 
             // Look up previous connection stats to calculate delta
@@ -1212,8 +1296,8 @@ fn update_connection_stats(tracker: &Arc<Mutex<ConnectionTracker>>) -> Result<()
                 .unwrap_or((0, 0));
 
             // Calculate delta for this specific connection
-            let delta_rx = rx_bytes.saturating_sub(prev_rx);
-            let delta_tx = tx_bytes.saturating_sub(prev_tx);
+            let delta_rx = counter_delta(rx_bytes, prev_rx);
+            let delta_tx = counter_delta(tx_bytes, prev_tx);
 
             // Accumulate: sum of connection cumulative totals (for reference)
             entry.rx_bytes += rx_bytes;
@@ -1372,6 +1456,23 @@ fn update_connection_stats(tracker: &Arc<Mutex<ConnectionTracker>>) -> Result<()
         entry.connection_count += 1;
     }
 
+    // Keep processes that currently have no connections (idle or exited) so their
+    // totals survive; update() marks exited ones as terminated and expires them
+    for (pid, bandwidth) in &tracker.process_bandwidth {
+        new_process_bandwidth
+            .entry(*pid)
+            .or_insert_with(|| ProcessBandwidth {
+                rx_bytes: 0,
+                tx_bytes: 0,
+                internet_rx_bytes: 0,
+                internet_tx_bytes: 0,
+                local_rx_bytes: 0,
+                local_tx_bytes: 0,
+                connection_count: 0,
+                ..bandwidth.clone()
+            });
+    }
+
     // Update the tracker's process_bandwidth map
     tracker.process_bandwidth = new_process_bandwidth;
 
@@ -1426,6 +1527,12 @@ fn update_connection_stats(tracker: &Arc<Mutex<ConnectionTracker>>) -> Result<()
                 last_internet_tx_bytes: 0,
                 last_local_rx_bytes: 0,
                 last_local_tx_bytes: 0,
+                rx_rate: 0,
+                tx_rate: 0,
+                internet_rx_rate: 0,
+                internet_tx_rate: 0,
+                local_rx_rate: 0,
+                local_tx_rate: 0,
             });
 
         proc_iface_entry.rx_bytes += delta_rx;
@@ -1444,59 +1551,71 @@ fn update_connection_stats(tracker: &Arc<Mutex<ConnectionTracker>>) -> Result<()
     }
 
     // Calculate rates based on lifetime deltas
-    if elapsed > 0.0 {
+    {
+        let tracker = &mut *tracker;
         for (pid, bandwidth) in &mut tracker.process_bandwidth {
-            // Calculate delta from lifetime accumulators (not from sum of connections)
-            let rx_diff = bandwidth
-                .lifetime_rx_bytes
-                .saturating_sub(bandwidth.last_lifetime_rx_bytes);
-            let tx_diff = bandwidth
-                .lifetime_tx_bytes
-                .saturating_sub(bandwidth.last_lifetime_tx_bytes);
+            // Calculate rates from lifetime accumulators (not from sum of connections)
+            bandwidth.rx_rate = counter_rate(
+                bandwidth.lifetime_rx_bytes,
+                bandwidth.last_lifetime_rx_bytes,
+                interval,
+            );
+            bandwidth.tx_rate = counter_rate(
+                bandwidth.lifetime_tx_bytes,
+                bandwidth.last_lifetime_tx_bytes,
+                interval,
+            );
 
             // Log rate calculation details for processes with activity
-            if rx_diff > 0 || tx_diff > 0 {
-                let rx_rate = (rx_diff as f64 / elapsed) as u64;
-                let tx_rate = (tx_diff as f64 / elapsed) as u64;
+            if bandwidth.rx_rate > 0 || bandwidth.tx_rate > 0 {
                 log::debug!(
-                    "PID {} ({}): Lifetime: RX={} TX={} | Last: RX={} TX={} | Delta: RX={} TX={} | Rate: ↓ {}/s ({:.2} MB/s) ↑ {}/s ({:.2} MB/s) | elapsed={:.2}s",
+                    "PID {} ({}): Lifetime: RX={} TX={} | Last: RX={} TX={} | Rate: ↓ {}/s ({:.2} MB/s) ↑ {}/s ({:.2} MB/s) | interval={:.2}s",
                     pid,
                     bandwidth.name,
                     bandwidth.lifetime_rx_bytes,
                     bandwidth.lifetime_tx_bytes,
                     bandwidth.last_lifetime_rx_bytes,
                     bandwidth.last_lifetime_tx_bytes,
-                    rx_diff,
-                    tx_diff,
-                    rx_rate,
-                    rx_rate as f64 / 1_048_576.0,
-                    tx_rate,
-                    tx_rate as f64 / 1_048_576.0,
-                    elapsed
+                    bandwidth.rx_rate,
+                    bandwidth.rx_rate as f64 / 1_048_576.0,
+                    bandwidth.tx_rate,
+                    bandwidth.tx_rate as f64 / 1_048_576.0,
+                    interval.unwrap_or_default()
                 );
             }
 
-            bandwidth.rx_rate = (rx_diff as f64 / elapsed) as u64;
-            bandwidth.tx_rate = (tx_diff as f64 / elapsed) as u64;
-
             // Calculate categorized rates
-            let internet_rx_diff = bandwidth
-                .lifetime_internet_rx_bytes
-                .saturating_sub(bandwidth.last_internet_rx_bytes);
-            let internet_tx_diff = bandwidth
-                .lifetime_internet_tx_bytes
-                .saturating_sub(bandwidth.last_internet_tx_bytes);
-            let local_rx_diff = bandwidth
-                .lifetime_local_rx_bytes
-                .saturating_sub(bandwidth.last_local_rx_bytes);
-            let local_tx_diff = bandwidth
-                .lifetime_local_tx_bytes
-                .saturating_sub(bandwidth.last_local_tx_bytes);
-
-            bandwidth.internet_rx_rate = (internet_rx_diff as f64 / elapsed) as u64;
-            bandwidth.internet_tx_rate = (internet_tx_diff as f64 / elapsed) as u64;
-            bandwidth.local_rx_rate = (local_rx_diff as f64 / elapsed) as u64;
-            bandwidth.local_tx_rate = (local_tx_diff as f64 / elapsed) as u64;
+            bandwidth.internet_rx_rate = counter_rate(
+                bandwidth.lifetime_internet_rx_bytes,
+                bandwidth.last_internet_rx_bytes,
+                interval,
+            );
+            bandwidth.internet_tx_rate = counter_rate(
+                bandwidth.lifetime_internet_tx_bytes,
+                bandwidth.last_internet_tx_bytes,
+                interval,
+            );
+            bandwidth.local_rx_rate = counter_rate(
+                bandwidth.lifetime_local_rx_bytes,
+                bandwidth.last_local_rx_bytes,
+                interval,
+            );
+            bandwidth.local_tx_rate = counter_rate(
+                bandwidth.lifetime_local_tx_bytes,
+                bandwidth.last_local_tx_bytes,
+                interval,
+            );
+
+            // Record a graph sample for this cycle
+            let history = tracker.bandwidth_history.entry(*pid).or_default();
+            history.push_back(BandwidthSample::now(
+                bandwidth.rx_rate,
+                bandwidth.tx_rate,
+                interval.unwrap_or_default(),
+            ));
+            while history.len() > MAX_HISTORY_SAMPLES {
+                history.pop_front();
+            }
 
             // Update last lifetime values for next cycle
             bandwidth.last_lifetime_rx_bytes = bandwidth.lifetime_rx_bytes;
@@ -1509,22 +1628,41 @@ fn update_connection_stats(tracker: &Arc<Mutex<ConnectionTracker>>) -> Result<()
 
         // Calculate interface rates
         for (_, iface_bandwidth) in &mut tracker.interface_bandwidth {
-            let rx_diff = iface_bandwidth
-                .lifetime_rx_bytes
-                .saturating_sub(iface_bandwidth.last_lifetime_rx_bytes);
-            let tx_diff = iface_bandwidth
-                .lifetime_tx_bytes
-                .saturating_sub(iface_bandwidth.last_lifetime_tx_bytes);
-
-            iface_bandwidth.rx_rate = (rx_diff as f64 / elapsed) as u64;
-            iface_bandwidth.tx_rate = (tx_diff as f64 / elapsed) as u64;
+            iface_bandwidth.rx_rate = counter_rate(
+                iface_bandwidth.lifetime_rx_bytes,
+                iface_bandwidth.last_lifetime_rx_bytes,
+                interval,
+            );
+            iface_bandwidth.tx_rate = counter_rate(
+                iface_bandwidth.lifetime_tx_bytes,
+                iface_bandwidth.last_lifetime_tx_bytes,
+                interval,
+            );
 
             iface_bandwidth.last_lifetime_rx_bytes = iface_bandwidth.lifetime_rx_bytes;
             iface_bandwidth.last_lifetime_tx_bytes = iface_bandwidth.lifetime_tx_bytes;
         }
 
-        // Calculate process-interface rates (no need to store rates, they're calculated on-demand in update())
+        // Calculate process-interface rates
         for (_, proc_iface_bandwidth) in &mut tracker.process_interface_bandwidth {
+            let bw = &*proc_iface_bandwidth;
+            let rates = (
+                counter_rate(bw.rx_bytes, bw.last_rx_bytes, interval),
+                counter_rate(bw.tx_bytes, bw.last_tx_bytes, interval),
+                counter_rate(bw.internet_rx_bytes, bw.last_internet_rx_bytes, interval),
+                counter_rate(bw.internet_tx_bytes, bw.last_internet_tx_bytes, interval),
+                counter_rate(bw.local_rx_bytes, bw.last_local_rx_bytes, interval),
+                counter_rate(bw.local_tx_bytes, bw.last_local_tx_bytes, interval),
+            );
+            (
+                proc_iface_bandwidth.rx_rate,
+                proc_iface_bandwidth.tx_rate,
+                proc_iface_bandwidth.internet_rx_rate,
+                proc_iface_bandwidth.internet_tx_rate,
+                proc_iface_bandwidth.local_rx_rate,
+                proc_iface_bandwidth.local_tx_rate,
+            ) = rates;
+
             // Update last values for next cycle's rate calculation
             proc_iface_bandwidth.last_rx_bytes = proc_iface_bandwidth.rx_bytes;
             proc_iface_bandwidth.last_tx_bytes = proc_iface_bandwidth.tx_bytes;
@@ -1540,6 +1678,17 @@ fn update_connection_stats(tracker: &Arc<Mutex<ConnectionTracker>>) -> Result<()
     Ok(())
 }
 
+/// Bytes transferred since the previous reading of a connection counter.
+/// A counter smaller than before means the connection was closed and the 4-tuple
+/// reused (or its stats were reset), so everything it reports now is new data
+fn counter_delta(current: u64, previous: u64) -> u64 {
+    if current >= previous {
+        current - previous
+    } else {
+        current
+    }
+}
+
 /// Build MIB_TCPROW structure from connection details (IPv4 only)
 #[cfg(target_os = "windows")]
 fn build_mib_tcprow(
@@ -1791,6 +1940,12 @@ fn enumerate_windows_interfaces() -> Result<Vec<WindowsNetworkInterface>> {
             // Check if loopback (IfType == 24 means IF_TYPE_SOFTWARE_LOOPBACK)
             let is_loopback = adapter.IfType == 24;
 
+            // Loopback reports an MTU of u32::MAX; an unknown speed is 0 or u64::MAX (bits/s)
+            let mtu = (adapter.Mtu != u32::MAX).then_some(adapter.Mtu);
+            let speed_mbps = (adapter.TransmitLinkSpeed != u64::MAX)
+                .then_some(adapter.TransmitLinkSpeed / 1_000_000)
+                .filter(|&mbps| mbps > 0);
+
             interfaces.push(WindowsNetworkInterface {
                 name,
                 friendly_name,
@@ -1798,6 +1953,8 @@ fn enumerate_windows_interfaces() -> Result<Vec<WindowsNetworkInterface>> {
                 ip_addresses,
                 is_up,
                 is_loopback,
+                mtu,
+                speed_mbps,
             });
 
             adapter_ptr = adapter.Next;
@@ -1853,3 +2010,16 @@ fn get_tcp_stats(
 ) -> Option<(u64, u64)> {
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counter_delta_handles_resets() {
+        assert_eq!(counter_delta(1500, 1000), 500);
+        assert_eq!(counter_delta(1000, 1000), 0);
+        // Connection closed and 4-tuple reused: new counter starts from zero
+        assert_eq!(counter_delta(300, 1000), 300);
+    }
+}
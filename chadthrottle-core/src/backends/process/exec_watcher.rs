@@ -0,0 +1,199 @@
+// New processes as they exec, from the kernel's proc connector (NETLINK_CONNECTOR)
+//
+// The process map only learns about a process on the next update, once it has a
+// socket. Subscribing to the proc connector's multicast group gets an event for
+// every exec on the system right away, which lets standing rules throttle a process
+// before it has sent anything. Subscribing needs CAP_NET_ADMIN.
+
+use std::io;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::sync::mpsc::{self, Receiver};
+
+/// CN_IDX_PROC / CN_VAL_PROC: the proc connector's id, also its multicast group
+const CN_IDX_PROC: u32 = 1;
+const CN_VAL_PROC: u32 = 1;
+/// enum proc_cn_mcast_op
+const PROC_CN_MCAST_LISTEN: u32 = 1;
+/// enum proc_event.what for an exec
+const PROC_EVENT_EXEC: u32 = 2;
+
+/// struct nlmsghdr
+const NLMSG_HEADER_LEN: usize = 16;
+/// struct cn_msg (without its data)
+const CN_MSG_LEN: usize = 20;
+/// Where the event data starts in struct proc_event (after what, cpu, timestamp_ns),
+/// and the exec event's process_tgid field
+const EVENT_DATA: usize = 16;
+const EXEC_TGID: usize = EVENT_DATA + 4;
+
+/// Reports the PIDs of processes that exec'd, read on a thread of its own
+pub struct ExecWatcher {
+    events: Receiver<i32>,
+}
+
+impl ExecWatcher {
+    /// Subscribe to exec events. Fails without CAP_NET_ADMIN or where the kernel
+    /// lacks the proc connector (CONFIG_PROC_EVENTS)
+    pub fn start() -> io::Result<Self> {
+        let fd = unsafe {
+            libc::socket(
+                libc::AF_NETLINK,
+                libc::SOCK_DGRAM | libc::SOCK_CLOEXEC,
+                libc::NETLINK_CONNECTOR,
+            )
+        };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+
+        let mut address: libc::sockaddr_nl = unsafe { std::mem::zeroed() };
+        address.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+        address.nl_groups = CN_IDX_PROC;
+        let bound = unsafe {
+            libc::bind(
+                fd.as_raw_fd(),
+                (&address as *const libc::sockaddr_nl).cast(),
+                std::mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+            )
+        };
+        if bound < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let request = listen_request();
+        let sent = unsafe { libc::send(fd.as_raw_fd(), request.as_ptr().cast(), request.len(), 0) };
+        if sent < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let (tx, events) = mpsc::channel();
+        std::thread::Builder::new()
+            .name("exec-watcher".to_string())
+            .spawn(move || {
+                let mut buf = vec![0u8; 4096];
+                loop {
+                    let received = unsafe {
+                        libc::recv(fd.as_raw_fd(), buf.as_mut_ptr().cast(), buf.len(), 0)
+                    };
+                    if received < 0 {
+                        let err = io::Error::last_os_error();
+                        match err.raw_os_error() {
+                            Some(libc::EINTR) => continue,
+                            // The kernel dropped events we were too slow for; the
+                            // next process map update covers those processes
+                            Some(libc::ENOBUFS) => {
+                                log::debug!("Exec watcher missed events (receive buffer full)");
+                                continue;
+                            }
+                            _ => {
+                                log::warn!("Exec watcher stopped: {}", err);
+                                return;
+                            }
+                        }
+                    }
+                    for pid in parse_exec_events(&buf[..received as usize]) {
+                        // The watcher was dropped
+                        if tx.send(pid).is_err() {
+                            return;
+                        }
+                    }
+                }
+            })?;
+
+        Ok(Self { events })
+    }
+
+    /// PIDs that exec'd since the last call, in order
+    pub fn new_pids(&self) -> Vec<i32> {
+        self.events.try_iter().collect()
+    }
+}
+
+/// Netlink message subscribing to the proc connector's events
+fn listen_request() -> Vec<u8> {
+    let data = PROC_CN_MCAST_LISTEN.to_ne_bytes();
+    let len = NLMSG_HEADER_LEN + CN_MSG_LEN + data.len();
+    let mut request = Vec::with_capacity(len);
+
+    // struct nlmsghdr (sequence and port ID left at 0)
+    request.extend_from_slice(&(len as u32).to_ne_bytes());
+    request.extend_from_slice(&(libc::NLMSG_DONE as u16).to_ne_bytes());
+    request.resize(NLMSG_HEADER_LEN, 0);
+
+    // struct cn_msg (sequence and acknowledgement left at 0), then the operation
+    request.extend_from_slice(&CN_IDX_PROC.to_ne_bytes());
+    request.extend_from_slice(&CN_VAL_PROC.to_ne_bytes());
+    request.resize(NLMSG_HEADER_LEN + 16, 0);
+    request.extend_from_slice(&(data.len() as u16).to_ne_bytes());
+    request.resize(NLMSG_HEADER_LEN + CN_MSG_LEN, 0);
+    request.extend_from_slice(&data);
+    request
+}
+
+/// The process (thread group) IDs of the exec events in a datagram
+fn parse_exec_events(mut messages: &[u8]) -> Vec<i32> {
+    let mut pids = Vec::new();
+    while messages.len() >= NLMSG_HEADER_LEN {
+        let len = read_u32(messages, 0).unwrap_or(0) as usize;
+        if len < NLMSG_HEADER_LEN || len > messages.len() {
+            break;
+        }
+        let payload = &messages[NLMSG_HEADER_LEN..len];
+        if read_u32(payload, 0) == Some(CN_IDX_PROC) {
+            let event = payload.get(CN_MSG_LEN..).unwrap_or_default();
+            if read_u32(event, 0) == Some(PROC_EVENT_EXEC)
+                && let Some(tgid) = read_u32(event, EXEC_TGID)
+            {
+                pids.push(tgid as i32);
+            }
+        }
+        messages = &messages[align(len).min(messages.len())..];
+    }
+    pids
+}
+
+/// Netlink messages are padded to 4 bytes
+fn align(len: usize) -> usize {
+    (len + 3) & !3
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_ne_bytes(
+        bytes.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A proc connector message carrying one event
+    fn message(what: u32, pid: u32, tgid: u32) -> Vec<u8> {
+        let mut event = Vec::new();
+        event.extend_from_slice(&what.to_ne_bytes());
+        event.resize(EVENT_DATA, 0);
+        event.extend_from_slice(&pid.to_ne_bytes());
+        event.extend_from_slice(&tgid.to_ne_bytes());
+
+        let len = NLMSG_HEADER_LEN + CN_MSG_LEN + event.len();
+        let mut message = (len as u32).to_ne_bytes().to_vec();
+        message.resize(NLMSG_HEADER_LEN, 0);
+        message.extend_from_slice(&CN_IDX_PROC.to_ne_bytes());
+        message.extend_from_slice(&CN_VAL_PROC.to_ne_bytes());
+        message.resize(NLMSG_HEADER_LEN + CN_MSG_LEN, 0);
+        message.extend_from_slice(&event);
+        message
+    }
+
+    #[test]
+    fn test_parse_exec_events() {
+        // A thread of process 300 exec'd, and process 301 forked
+        let mut datagram = message(PROC_EVENT_EXEC, 302, 300);
+        datagram.extend(message(1, 301, 301));
+        assert_eq!(parse_exec_events(&datagram), vec![300]);
+
+        assert!(parse_exec_events(&datagram[..10]).is_empty());
+        assert_eq!(listen_request().len(), NLMSG_HEADER_LEN + CN_MSG_LEN + 4);
+    }
+}
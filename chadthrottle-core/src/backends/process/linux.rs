@@ -41,6 +41,12 @@ impl LinuxProcessUtils {
     }
 }
 
+impl Default for LinuxProcessUtils {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ProcessUtils for LinuxProcessUtils {
     fn get_process_name(&self, pid: i32) -> Result<String> {
         std::fs::read_to_string(format!("/proc/{}/comm", pid))
@@ -52,6 +58,21 @@ impl ProcessUtils for LinuxProcessUtils {
         procfs::process::Process::new(pid).is_ok()
     }
 
+    fn get_cmdline(&self, pid: i32) -> Result<String> {
+        // Empty for kernel threads and zombies
+        let args = procfs::process::Process::new(pid)?.cmdline()?;
+        if args.is_empty() {
+            anyhow::bail!("Process {} has no command line", pid);
+        }
+        Ok(args.join(" "))
+    }
+
+    fn get_parent_pid(&self, pid: i32) -> Option<i32> {
+        // Field 4 of /proc/[pid]/stat; 0 means no parent (e.g. init, kernel threads)
+        let stat = procfs::process::Process::new(pid).ok()?.stat().ok()?;
+        (stat.ppid > 0).then_some(stat.ppid)
+    }
+
     fn get_all_processes(&self) -> Result<Vec<ProcessEntry>> {
         let all_procs = all_processes()?;
         let mut entries = Vec::new();
@@ -75,4 +96,8 @@ impl ProcessUtils for LinuxProcessUtils {
         // Delegate to pluggable socket mapper backend
         self.socket_mapper.get_connection_map()
     }
+
+    fn socket_cache_stats(&self) -> Option<super::socket_mapper::SocketCacheStats> {
+        self.socket_mapper.cache_stats()
+    }
 }
@@ -3,7 +3,7 @@
 use super::socket_mapper::{SocketMapperBackend, select_socket_mapper};
 use super::{ConnectionMap, ProcessEntry, ProcessUtils};
 use anyhow::Result;
-use sysinfo::{Pid, System};
+use sysinfo::{Pid, ProcessesToUpdate, System};
 
 /// macOS process utilities with pluggable socket mapping
 pub struct MacOSProcessUtils {
@@ -41,6 +41,12 @@ impl MacOSProcessUtils {
     }
 }
 
+impl Default for MacOSProcessUtils {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ProcessUtils for MacOSProcessUtils {
     fn get_process_name(&self, pid: i32) -> Result<String> {
         let sys = System::new_all();
@@ -57,6 +63,34 @@ impl ProcessUtils for MacOSProcessUtils {
         sys.process(pid_obj).is_some()
     }
 
+    fn get_cmdline(&self, pid: i32) -> Result<String> {
+        // Only refresh the one process, like get_parent_pid
+        let mut sys = System::new();
+        let pid_obj = Pid::from_u32(pid as u32);
+        sys.refresh_processes(ProcessesToUpdate::Some(&[pid_obj]));
+
+        let process = sys
+            .process(pid_obj)
+            .ok_or_else(|| anyhow::anyhow!("Process {} not found", pid))?;
+        let args: Vec<_> = process.cmd().iter().map(|a| a.to_string_lossy()).collect();
+        if args.is_empty() {
+            anyhow::bail!("Process {} has no command line", pid);
+        }
+        Ok(args.join(" "))
+    }
+
+    fn get_parent_pid(&self, pid: i32) -> Option<i32> {
+        // Only refresh the one process - System::new_all() is far too slow per PID
+        let mut sys = System::new();
+        let pid_obj = Pid::from_u32(pid as u32);
+        sys.refresh_processes(ProcessesToUpdate::Some(&[pid_obj]));
+
+        sys.process(pid_obj)
+            .and_then(|p| p.parent())
+            .map(|parent| parent.as_u32() as i32)
+            .filter(|&ppid| ppid > 0)
+    }
+
     fn get_all_processes(&self) -> Result<Vec<ProcessEntry>> {
         let sys = System::new_all();
 
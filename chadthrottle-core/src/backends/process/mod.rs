@@ -15,12 +15,49 @@ pub trait ProcessUtils: Send + Sync {
     /// Check if process exists
     fn process_exists(&self, pid: i32) -> bool;
 
+    /// Get the full command line of a process (arguments joined by spaces)
+    fn get_cmdline(&self, pid: i32) -> Result<String>;
+
     /// Get all running processes with their names
     fn get_all_processes(&self) -> Result<Vec<ProcessEntry>>;
 
+    /// Get the parent PID of a process (None if it has no parent or can't be determined)
+    fn get_parent_pid(&self, pid: i32) -> Option<i32>;
+
+    /// Get all descendants of a process (children, grandchildren, ...)
+    /// Default implementation walks the parent of every running process
+    fn get_descendants(&self, pid: i32) -> Vec<ProcessEntry> {
+        let Ok(processes) = self.get_all_processes() else {
+            return Vec::new();
+        };
+        let mut children: HashMap<i32, Vec<ProcessEntry>> = HashMap::new();
+        for entry in processes {
+            if let Some(parent) = self.get_parent_pid(entry.pid)
+                && parent != entry.pid
+            {
+                children.entry(parent).or_default().push(entry);
+            }
+        }
+
+        let mut descendants = Vec::new();
+        let mut pending = vec![pid];
+        while let Some(parent) = pending.pop() {
+            for child in children.remove(&parent).unwrap_or_default() {
+                pending.push(child.pid);
+                descendants.push(child);
+            }
+        }
+        descendants
+    }
+
     /// Get socket-to-PID mapping for network connections
     fn get_connection_map(&self) -> Result<ConnectionMap>;
 
+    /// Hit/miss counts of the socket mapper's owner cache (None = it doesn't cache)
+    fn socket_cache_stats(&self) -> Option<socket_mapper::SocketCacheStats> {
+        None
+    }
+
     /// Refresh internal caches (e.g., process list)
     /// This should be called periodically to keep cached data fresh
     /// Default implementation is a no-op for platforms that don't cache
@@ -77,6 +114,13 @@ pub struct ConnectionEntry {
 // Socket mapper backend system (cross-platform)
 pub mod socket_mapper;
 
+// Per-process TCP retransmits and RTT
+pub mod tcp_health;
+
+// New processes as they exec, for standing rules
+#[cfg(target_os = "linux")]
+pub mod exec_watcher;
+
 // Platform-specific implementations
 #[cfg(target_os = "linux")]
 mod linux;
@@ -9,7 +9,7 @@
 
 use super::super::SocketMapperBackend;
 use crate::backends::process::{ConnectionEntry, ConnectionMap};
-use crate::backends::{BackendCapabilities, BackendPriority};
+use crate::backends::{BackendCapabilities, BackendPriority, TrafficTypeSupport};
 use anyhow::{Context, Result};
 use procfs::process::FDTarget;
 use std::collections::HashMap;
@@ -47,12 +47,19 @@ impl SocketMapperBackend for LibprocSocketMapper {
         std::path::Path::new("/proc/net/tcp").exists()
     }
 
+    fn unavailable_reason() -> String {
+        "/proc/net/tcp not found".to_string()
+    }
+
     fn capabilities(&self) -> BackendCapabilities {
         BackendCapabilities {
             ipv4_support: true,
             ipv6_support: true,
             per_process: true,
             per_connection: true,
+            tcp_support: true,
+            udp_support: true,
+            traffic_types: TrafficTypeSupport::ALL_ONLY,
         }
     }
 
@@ -2,9 +2,10 @@
 
 mod libproc;
 mod procfs;
+mod socket_cache;
 
 pub use libproc::LibprocSocketMapper;
-pub use procfs::ProcfsSocketMapper;
+pub use procfs::{ProcfsSocketMapper, parse_net_table};
 
 use super::{SocketMapperBackend, SocketMapperInfo};
 use crate::backends::BackendPriority;
@@ -13,16 +14,8 @@ use anyhow::Result;
 /// Detect all available socket mapper backends on Linux
 pub fn detect_socket_mappers() -> Vec<SocketMapperInfo> {
     vec![
-        SocketMapperInfo {
-            name: "procfs",
-            priority: BackendPriority::Best,
-            available: ProcfsSocketMapper::is_available(),
-        },
-        SocketMapperInfo {
-            name: "libproc",
-            priority: BackendPriority::Good,
-            available: LibprocSocketMapper::is_available(),
-        },
+        SocketMapperInfo::probe::<ProcfsSocketMapper>("procfs", BackendPriority::Best),
+        SocketMapperInfo::probe::<LibprocSocketMapper>("libproc", BackendPriority::Good),
     ]
 }
 
@@ -0,0 +1,320 @@
+// Linux procfs-based socket mapper
+
+use super::super::{SocketCacheStats, SocketMapperBackend};
+use super::socket_cache::SocketCache;
+use crate::backends::process::{ConnectionEntry, ConnectionMap};
+use crate::backends::{BackendCapabilities, BackendPriority, TrafficTypeSupport};
+use anyhow::Result;
+use procfs::process::{FDTarget, all_processes};
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::sync::Mutex;
+
+/// Socket mapper using Linux /proc filesystem
+///
+/// This backend reads socket information from:
+/// - /proc/[pid]/fd/ - for socket inodes
+/// - /proc/net/tcp - for TCP IPv4 connections
+/// - /proc/net/tcp6 - for TCP IPv6 connections
+/// - /proc/net/udp - for UDP IPv4 connections
+/// - /proc/net/udp6 - for UDP IPv6 connections
+///
+/// Socket owners are cached between maps (see `SocketCache`), so the fd directories
+/// are only read when new sockets show up.
+pub struct ProcfsSocketMapper {
+    cache: Mutex<SocketCache>,
+}
+
+impl ProcfsSocketMapper {
+    pub fn new() -> Result<Self> {
+        if !Self::is_available() {
+            anyhow::bail!("procfs not available (is /proc mounted?)");
+        }
+        Ok(Self {
+            cache: Mutex::new(SocketCache::default()),
+        })
+    }
+}
+
+impl SocketMapperBackend for ProcfsSocketMapper {
+    fn name(&self) -> &'static str {
+        "procfs"
+    }
+
+    fn priority(&self) -> BackendPriority {
+        BackendPriority::Best // Native Linux API, always available, fast
+    }
+
+    fn is_available() -> bool {
+        // Check if /proc/net/tcp exists
+        std::path::Path::new("/proc/net/tcp").exists()
+    }
+
+    fn unavailable_reason() -> String {
+        "/proc/net/tcp not found".to_string()
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            ipv4_support: true,
+            ipv6_support: true,
+            per_process: true,
+            per_connection: true,
+            tcp_support: true,
+            udp_support: true,
+            traffic_types: TrafficTypeSupport::ALL_ONLY,
+        }
+    }
+
+    fn get_connection_map(&self) -> Result<ConnectionMap> {
+        let mut tcp_connections = Vec::new();
+        let mut tcp6_connections = Vec::new();
+        let mut udp_connections = Vec::new();
+        let mut udp6_connections = Vec::new();
+
+        // Parse the socket tables, reusing one read buffer for all four
+        let mut buffer = Vec::new();
+        read_net_table("/proc/net/tcp", true, &mut buffer, &mut tcp_connections);
+        read_net_table("/proc/net/tcp6", true, &mut buffer, &mut tcp6_connections);
+        read_net_table("/proc/net/udp", false, &mut buffer, &mut udp_connections);
+        read_net_table("/proc/net/udp6", false, &mut buffer, &mut udp6_connections);
+
+        // Inode 0: TIME_WAIT and other sockets no process holds
+        let inodes: HashSet<u64> = [
+            &tcp_connections,
+            &tcp6_connections,
+            &udp_connections,
+            &udp6_connections,
+        ]
+        .into_iter()
+        .flatten()
+        .map(|entry| entry.inode)
+        .filter(|&inode| inode != 0)
+        .collect();
+
+        // Only sockets that are new since the last map are searched for in /proc/[pid]/fd
+        let socket_to_pid = self
+            .cache
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Socket cache lock poisoned"))?
+            .resolve(&inodes, process_name, find_socket_owners);
+
+        Ok(ConnectionMap {
+            socket_to_pid,
+            tcp_connections,
+            tcp6_connections,
+            udp_connections,
+            udp6_connections,
+        })
+    }
+
+    fn cache_stats(&self) -> Option<SocketCacheStats> {
+        self.cache.lock().ok().map(|cache| cache.stats())
+    }
+}
+
+fn process_name(pid: i32) -> Option<String> {
+    let comm = std::fs::read_to_string(format!("/proc/{}/comm", pid)).ok()?;
+    Some(comm.trim_end().to_string())
+}
+
+/// Owners of the given socket inodes, from the fd directories of all processes.
+/// Stops as soon as every inode is found
+fn find_socket_owners(inodes: &HashSet<u64>) -> HashMap<u64, (i32, String)> {
+    let mut owners = HashMap::new();
+    let Ok(all_procs) = all_processes() else {
+        return owners;
+    };
+    for process in all_procs.flatten() {
+        let Ok(fds) = process.fd() else {
+            continue;
+        };
+        let pid = process.pid();
+        let mut name = None;
+        for fd_info in fds.flatten() {
+            if let FDTarget::Socket(inode) = fd_info.target
+                && inodes.contains(&inode)
+            {
+                // Only read for processes holding a socket we're after
+                let name = name.get_or_insert_with(|| match process.stat() {
+                    Ok(stat) => stat.comm,
+                    Err(_) => format!("PID {}", pid),
+                });
+                owners.insert(inode, (pid, name.clone()));
+            }
+        }
+        if owners.len() == inodes.len() {
+            break;
+        }
+    }
+    owners
+}
+
+/// Read one of the /proc/net socket tables into `out` (left empty if it can't be read)
+fn read_net_table(path: &str, tcp: bool, buffer: &mut Vec<u8>, out: &mut Vec<ConnectionEntry>) {
+    buffer.clear();
+    let read = std::fs::File::open(path).and_then(|mut file| file.read_to_end(buffer));
+    if let Err(e) = read {
+        log::debug!("Failed to read {}: {}", path, e);
+        return;
+    }
+    parse_net_table(buffer, tcp, out);
+}
+
+/// Parse a /proc/net/{tcp,tcp6,udp,udp6} table (`tcp` selects the state names) into `out`
+///
+/// Works on the raw bytes: fields are split in place and hex is decoded digit by digit,
+/// so the only allocation per socket is its state name. Lines that don't parse are
+/// skipped.
+pub fn parse_net_table(contents: &[u8], tcp: bool, out: &mut Vec<ConnectionEntry>) {
+    // One socket per line after the header
+    let sockets = contents.iter().filter(|&&byte| byte == b'\n').count();
+    out.reserve(sockets.saturating_sub(1));
+
+    for line in contents.split(|&byte| byte == b'\n').skip(1) {
+        if let Some(entry) = parse_socket_line(line, tcp) {
+            out.push(entry);
+        }
+    }
+}
+
+/// `sl local_address rem_address st tx_queue:rx_queue tr:tm->when retrnsmt uid timeout inode ...`
+fn parse_socket_line(line: &[u8], tcp: bool) -> Option<ConnectionEntry> {
+    let mut fields = line
+        .split(|&byte| byte == b' ')
+        .filter(|field| !field.is_empty());
+    fields.next()?; // sl
+    let (local_addr, local_port) = parse_endpoint(fields.next()?)?;
+    let (remote_addr, remote_port) = parse_endpoint(fields.next()?)?;
+    let state = parse_hex(fields.next()?)?;
+    // Skip tx_queue:rx_queue, tr:tm->when, retrnsmt, uid and timeout
+    let inode = parse_decimal(fields.nth(5)?)?;
+
+    Some(ConnectionEntry {
+        local_addr,
+        local_port,
+        remote_addr,
+        remote_port,
+        inode,
+        state: state_name(tcp, state).to_string(),
+    })
+}
+
+/// `0100007F:0277` or the 32-digit IPv6 form. Addresses are printed as native-endian
+/// 32-bit words
+fn parse_endpoint(field: &[u8]) -> Option<(IpAddr, u16)> {
+    let colon = field.iter().position(|&byte| byte == b':')?;
+    let (addr, port) = (&field[..colon], &field[colon + 1..]);
+    let port = u16::try_from(parse_hex(port)?).ok()?;
+
+    let addr = match addr.len() {
+        8 => IpAddr::V4(Ipv4Addr::from(parse_hex(addr)?.to_ne_bytes())),
+        32 => {
+            let mut bytes = [0u8; 16];
+            for (word, out) in addr.chunks_exact(8).zip(bytes.chunks_exact_mut(4)) {
+                out.copy_from_slice(&parse_hex(word)?.to_ne_bytes());
+            }
+            IpAddr::V6(Ipv6Addr::from(bytes))
+        }
+        _ => return None,
+    };
+    Some((addr, port))
+}
+
+/// Up to 8 hex digits
+fn parse_hex(digits: &[u8]) -> Option<u32> {
+    if digits.is_empty() || digits.len() > 8 {
+        return None;
+    }
+    digits.iter().try_fold(0u32, |value, &digit| {
+        let nibble = match digit {
+            b'0'..=b'9' => digit - b'0',
+            b'A'..=b'F' => digit - b'A' + 10,
+            b'a'..=b'f' => digit - b'a' + 10,
+            _ => return None,
+        };
+        Some(value << 4 | nibble as u32)
+    })
+}
+
+fn parse_decimal(digits: &[u8]) -> Option<u64> {
+    if digits.is_empty() {
+        return None;
+    }
+    digits.iter().try_fold(0u64, |value, &digit| {
+        if !digit.is_ascii_digit() {
+            return None;
+        }
+        value.checked_mul(10)?.checked_add((digit - b'0') as u64)
+    })
+}
+
+/// State names as the other socket mappers report them ("Established", "Listen", ...)
+fn state_name(tcp: bool, state: u32) -> &'static str {
+    match (tcp, state) {
+        (_, 0x01) => "Established",
+        (true, 0x02) => "SynSent",
+        (true, 0x03) => "SynRecv",
+        (true, 0x04) => "FinWait1",
+        (true, 0x05) => "FinWait2",
+        (true, 0x06) => "TimeWait",
+        (_, 0x07) => "Close",
+        (true, 0x08) => "CloseWait",
+        (true, 0x09) => "LastAck",
+        (true, 0x0A) => "Listen",
+        (true, 0x0B) => "Closing",
+        (true, 0x0C) => "NewSynRecv",
+        _ => "Unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TCP: &str = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 0100007F:0277 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 18724 1 0000000000000000 100 0 0 10 0
+   1: 0F02000A:C8A4 22D8B85D:01BB 01 00000000:00000000 01:00000016 0000000A  1000        0 92811 2 0000000000000000 20 4 30 10 -1
+   2: garbage
+";
+
+    #[test]
+    fn test_parse_net_table_skips_bad_lines() {
+        let mut entries = Vec::new();
+        parse_net_table(TCP.as_bytes(), true, &mut entries);
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].local_addr, IpAddr::V4(Ipv4Addr::LOCALHOST));
+        assert_eq!(entries[0].local_port, 631);
+        assert_eq!(entries[0].remote_port, 0);
+        assert_eq!(entries[0].state, "Listen");
+        assert_eq!(entries[0].inode, 18724);
+        assert_eq!(
+            entries[1].local_addr,
+            IpAddr::V4(Ipv4Addr::new(10, 0, 2, 15))
+        );
+        assert_eq!(
+            entries[1].remote_addr,
+            IpAddr::V4(Ipv4Addr::new(93, 184, 216, 34))
+        );
+        assert_eq!(entries[1].remote_port, 443);
+        assert_eq!(entries[1].state, "Established");
+        assert_eq!(entries[1].inode, 92811);
+    }
+
+    #[test]
+    fn test_parse_ipv6_and_udp() {
+        let udp6 = "  sl  local_address                         remote_address                        st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode ref pointer drops
+  512: 00000000000000000000000001000000:0035 00000000000000000000000000000000:0000 07 00000000:00000000 00:00000000 00000000   101        0 2345 2 0000000000000000 0
+";
+        let mut entries = Vec::new();
+        parse_net_table(udp6.as_bytes(), false, &mut entries);
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].local_addr, IpAddr::V6(Ipv6Addr::LOCALHOST));
+        assert_eq!(entries[0].local_port, 53);
+        assert_eq!(entries[0].state, "Close");
+        assert_eq!(entries[0].inode, 2345);
+    }
+}
@@ -0,0 +1,130 @@
+// Socket inode -> owning process, kept between connection maps
+//
+// Finding a socket's owner means reading the fd directory of every process, which
+// dominates a connection map on hosts with thousands of sockets. Most sockets outlive
+// many maps, so owners are remembered by inode: only inodes that weren't in the
+// previous tables send us through the fd directories, and the scan stops once all of
+// them are found. Inodes that left the tables are forgotten.
+
+use super::super::SocketCacheStats;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Default)]
+pub struct SocketCache {
+    /// inode -> (PID, process name); None when no process we can read holds it
+    /// (sockets in flight between processes, processes we lack permission for)
+    owners: HashMap<u64, Option<(i32, String)>>,
+    stats: SocketCacheStats,
+}
+
+impl SocketCache {
+    /// Owners of the sockets in `inodes`, by inode
+    ///
+    /// `process_name` returns a PID's current name (None once it exited): it's called
+    /// once per cached owner, so owners that exited are looked up again and renames
+    /// (exec) are picked up. `scan` finds the owners of the inodes it's given.
+    pub fn resolve(
+        &mut self,
+        inodes: &HashSet<u64>,
+        mut process_name: impl FnMut(i32) -> Option<String>,
+        scan: impl FnOnce(&HashSet<u64>) -> HashMap<u64, (i32, String)>,
+    ) -> HashMap<u64, (i32, String)> {
+        // Closed sockets
+        self.owners.retain(|inode, _| inodes.contains(inode));
+
+        let mut names: HashMap<i32, Option<String>> = HashMap::new();
+        let mut owners = HashMap::with_capacity(inodes.len());
+        let mut unknown = HashSet::new();
+        for &inode in inodes {
+            match self.owners.get_mut(&inode) {
+                Some(Some((pid, name))) => {
+                    let current = names.entry(*pid).or_insert_with(|| process_name(*pid));
+                    let Some(current) = current else {
+                        // The owner exited; a child may have inherited the socket
+                        unknown.insert(inode);
+                        continue;
+                    };
+                    if name != current {
+                        name.clone_from(current);
+                    }
+                    self.stats.hits += 1;
+                    owners.insert(inode, (*pid, name.clone()));
+                }
+                Some(None) => self.stats.hits += 1,
+                None => {
+                    unknown.insert(inode);
+                }
+            }
+        }
+        if unknown.is_empty() {
+            return owners;
+        }
+
+        self.stats.misses += unknown.len() as u64;
+        self.stats.fd_scans += 1;
+        let mut found = scan(&unknown);
+        for inode in unknown {
+            let owner = found.remove(&inode);
+            if let Some(owner) = &owner {
+                owners.insert(inode, owner.clone());
+            }
+            self.owners.insert(inode, owner);
+        }
+        owners
+    }
+
+    pub fn stats(&self) -> SocketCacheStats {
+        self.stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn owner(pid: i32, name: &str) -> (i32, String) {
+        (pid, name.to_string())
+    }
+
+    #[test]
+    fn test_only_new_sockets_are_scanned() {
+        let mut cache = SocketCache::default();
+        let alive = |pid: i32| (pid != 300).then(|| format!("proc{}", pid));
+
+        // First map: everything is new; socket 12 has no visible owner
+        let inodes = HashSet::from([10, 11, 12]);
+        let owners = cache.resolve(&inodes, alive, |unknown| {
+            assert_eq!(unknown.len(), 3);
+            HashMap::from([(10, owner(100, "proc100")), (11, owner(200, "proc200"))])
+        });
+        assert_eq!(owners.len(), 2);
+
+        // Socket 11 closed and 13 opened: only 13 is scanned for, 10 and 12 are hits
+        let inodes = HashSet::from([10, 12, 13]);
+        let owners = cache.resolve(&inodes, alive, |unknown| {
+            assert_eq!(unknown, &HashSet::from([13]));
+            HashMap::from([(13, owner(300, "proc300"))])
+        });
+        assert_eq!(owners[&13], owner(300, "proc300"));
+        assert_eq!(owners[&10], owner(100, "proc100"));
+
+        // Nothing new: no scan
+        cache.resolve(&HashSet::from([10, 12]), alive, |_| panic!("scanned"));
+
+        // The owner of 13 exited: its socket is looked up again
+        let owners = cache.resolve(&HashSet::from([10, 13]), alive, |unknown| {
+            assert_eq!(unknown, &HashSet::from([13]));
+            HashMap::from([(13, owner(301, "proc301"))])
+        });
+        assert_eq!(owners[&13], owner(301, "proc301"));
+
+        assert_eq!(
+            cache.stats(),
+            SocketCacheStats {
+                hits: 5,
+                misses: 5,
+                fd_scans: 3,
+            }
+        );
+    }
+}
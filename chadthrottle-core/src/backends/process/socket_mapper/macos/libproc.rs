@@ -6,7 +6,7 @@
 
 use super::super::SocketMapperBackend;
 use crate::backends::process::{ConnectionEntry, ConnectionMap};
-use crate::backends::{BackendCapabilities, BackendPriority};
+use crate::backends::{BackendCapabilities, BackendPriority, TrafficTypeSupport};
 use anyhow::{Context, Result};
 use std::collections::HashMap;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
@@ -309,6 +309,9 @@ impl SocketMapperBackend for LibprocSocketMapper {
             ipv6_support: true,
             per_process: true,
             per_connection: true,
+            tcp_support: true,
+            udp_support: false,
+            traffic_types: TrafficTypeSupport::ALL_ONLY,
         }
     }
 
@@ -2,7 +2,7 @@
 
 use super::super::SocketMapperBackend;
 use crate::backends::process::{ConnectionEntry, ConnectionMap};
-use crate::backends::{BackendCapabilities, BackendPriority};
+use crate::backends::{BackendCapabilities, BackendPriority, TrafficTypeSupport};
 use anyhow::{Context, Result};
 use std::collections::HashMap;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
@@ -247,12 +247,19 @@ impl SocketMapperBackend for LsofSocketMapper {
             .unwrap_or(false)
     }
 
+    fn unavailable_reason() -> String {
+        "lsof command not found".to_string()
+    }
+
     fn capabilities(&self) -> BackendCapabilities {
         BackendCapabilities {
             ipv4_support: true,
             ipv6_support: true,
             per_process: true,
             per_connection: true,
+            tcp_support: true,
+            udp_support: false,
+            traffic_types: TrafficTypeSupport::ALL_ONLY,
         }
     }
 
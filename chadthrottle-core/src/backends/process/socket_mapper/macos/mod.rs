@@ -13,16 +13,8 @@ use anyhow::Result;
 /// Detect all available socket mapper backends on macOS
 pub fn detect_socket_mappers() -> Vec<SocketMapperInfo> {
     vec![
-        SocketMapperInfo {
-            name: "libproc",
-            priority: BackendPriority::Best,
-            available: LibprocSocketMapper::is_available(),
-        },
-        SocketMapperInfo {
-            name: "lsof",
-            priority: BackendPriority::Good,
-            available: LsofSocketMapper::is_available(),
-        },
+        SocketMapperInfo::probe::<LibprocSocketMapper>("libproc", BackendPriority::Best),
+        SocketMapperInfo::probe::<LsofSocketMapper>("lsof", BackendPriority::Good),
     ]
 }
 
@@ -9,6 +9,7 @@
 use super::ConnectionMap;
 use crate::backends::{BackendCapabilities, BackendPriority};
 use anyhow::Result;
+use serde::Serialize;
 
 /// Cross-platform socket-to-PID mapping backend trait
 ///
@@ -26,6 +27,15 @@ pub trait SocketMapperBackend: Send + Sync {
     where
         Self: Sized;
 
+    /// Human-readable reason this backend is unavailable (for diagnostics)
+    /// Only called if `is_available()` returns false
+    fn unavailable_reason() -> String
+    where
+        Self: Sized,
+    {
+        "unknown".to_string()
+    }
+
     /// Get backend capabilities
     fn capabilities(&self) -> BackendCapabilities;
 
@@ -36,6 +46,30 @@ pub trait SocketMapperBackend: Send + Sync {
     /// - macOS calls lsof or libproc
     /// - Windows uses netstat or WMI
     fn get_connection_map(&self) -> Result<ConnectionMap>;
+
+    /// How well the backend's socket owner cache is doing (None = it doesn't cache)
+    fn cache_stats(&self) -> Option<SocketCacheStats> {
+        None
+    }
+}
+
+/// Socket owner lookups since a caching backend was created
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct SocketCacheStats {
+    /// Sockets whose owner was already known
+    pub hits: u64,
+    /// New sockets that had to be looked up
+    pub misses: u64,
+    /// Scans of the processes' file descriptors (at most one per connection map)
+    pub fd_scans: u64,
+}
+
+impl SocketCacheStats {
+    /// Share of lookups served from the cache (None before the first lookup)
+    pub fn hit_rate(&self) -> Option<f64> {
+        let total = self.hits + self.misses;
+        (total > 0).then(|| self.hits as f64 / total as f64)
+    }
 }
 
 /// Socket mapper backend metadata for selection
@@ -44,6 +78,21 @@ pub struct SocketMapperInfo {
     pub name: &'static str,
     pub priority: BackendPriority,
     pub available: bool,
+    /// Why the backend is unavailable (None when available)
+    pub unavailable_reason: Option<String>,
+}
+
+impl SocketMapperInfo {
+    /// Check a compiled-in backend's availability
+    pub fn probe<B: SocketMapperBackend>(name: &'static str, priority: BackendPriority) -> Self {
+        let available = B::is_available();
+        Self {
+            name,
+            priority,
+            available,
+            unavailable_reason: (!available).then(B::unavailable_reason),
+        }
+    }
 }
 
 // Platform-specific modules
@@ -8,7 +8,7 @@
 
 use crate::backends::process::socket_mapper::SocketMapperBackend;
 use crate::backends::process::{ConnectionEntry, ConnectionMap};
-use crate::backends::{BackendCapabilities, BackendPriority};
+use crate::backends::{BackendCapabilities, BackendPriority, TrafficTypeSupport};
 use anyhow::Result;
 use std::collections::HashMap;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
@@ -55,6 +55,9 @@ impl SocketMapperBackend for IpHelperSocketMapper {
             ipv6_support: true,
             per_process: true,
             per_connection: true,
+            tcp_support: true,
+            udp_support: true,
+            traffic_types: TrafficTypeSupport::ALL_ONLY,
         }
     }
 
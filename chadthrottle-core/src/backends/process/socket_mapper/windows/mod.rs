@@ -13,11 +13,10 @@ use anyhow::Result;
 
 /// Detect available socket mapper backends on Windows
 pub fn detect_socket_mappers() -> Vec<SocketMapperInfo> {
-    vec![SocketMapperInfo {
-        name: "iphelper",
-        priority: BackendPriority::Best,
-        available: IpHelperSocketMapper::is_available(),
-    }]
+    vec![SocketMapperInfo::probe::<IpHelperSocketMapper>(
+        "iphelper",
+        BackendPriority::Best,
+    )]
 }
 
 /// Select socket mapper backend for Windows
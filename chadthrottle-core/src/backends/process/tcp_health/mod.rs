@@ -0,0 +1,246 @@
+// Per-process TCP health: retransmissions and round-trip time
+//
+// Bandwidth alone doesn't show whether a throttle hurts a flow - a drop-based
+// backend shows up as retransmits instead. On Linux the per-socket counters come
+// from sock_diag netlink (the interface `ss -ti` uses). Where that isn't permitted
+// (some containers and seccomp sandboxes) the retransmit column of /proc/net/tcp
+// is used instead, which has no RTT. Other platforms report nothing.
+
+#[cfg(target_os = "linux")]
+mod sock_diag;
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// Counters for one TCP socket
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SocketHealth {
+    pub retransmits: u32,    // segments retransmitted so far
+    pub rtt_us: Option<u32>, // smoothed RTT (None = not measured)
+}
+
+/// TCP health of a process, summed over its sockets
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TcpHealth {
+    pub retransmit_rate: f64,    // segments retransmitted per second
+    pub avg_rtt_ms: Option<f64>, // mean smoothed RTT of sockets with one
+}
+
+enum Source {
+    #[cfg(target_os = "linux")]
+    SockDiag(sock_diag::SockDiag),
+    #[cfg(target_os = "linux")]
+    ProcNetTcp,
+    #[cfg(not(target_os = "linux"))]
+    Unavailable,
+}
+
+/// Samples TCP socket counters and turns them into per-process rates
+pub struct TcpHealthCollector {
+    source: Source,
+    previous: HashMap<u64, u32>, // socket inode -> retransmits at the last sample
+    last_sample: Option<Instant>,
+}
+
+impl TcpHealthCollector {
+    pub fn new() -> Self {
+        #[cfg(target_os = "linux")]
+        let source = match sock_diag::SockDiag::open() {
+            Ok(sock_diag) => Source::SockDiag(sock_diag),
+            Err(e) => {
+                log::info!(
+                    "sock_diag unavailable ({}), reading /proc/net/tcp instead",
+                    e
+                );
+                Source::ProcNetTcp
+            }
+        };
+        #[cfg(not(target_os = "linux"))]
+        let source = Source::Unavailable;
+
+        Self {
+            source,
+            previous: HashMap::new(),
+            last_sample: None,
+        }
+    }
+
+    /// Sample every TCP socket and attribute it to its owner through `socket_map`
+    /// (socket inode -> PID). Rates cover the time since the previous call
+    pub fn collect(&mut self, socket_map: &HashMap<u64, (i32, String)>) -> HashMap<i32, TcpHealth> {
+        let sockets = self.sample();
+        let now = Instant::now();
+        let elapsed = self
+            .last_sample
+            .map(|last| now.duration_since(last).as_secs_f64());
+
+        let health = aggregate(&sockets, &self.previous, socket_map, elapsed);
+
+        self.previous = sockets
+            .iter()
+            .map(|(inode, socket)| (*inode, socket.retransmits))
+            .collect();
+        self.last_sample = Some(now);
+        health
+    }
+
+    /// Per-socket counters by inode, falling back to /proc/net/tcp if sock_diag stops working
+    fn sample(&mut self) -> HashMap<u64, SocketHealth> {
+        match &self.source {
+            #[cfg(target_os = "linux")]
+            Source::SockDiag(sock_diag) => match sock_diag.tcp_sockets() {
+                Ok(sockets) => sockets,
+                Err(e) => {
+                    log::info!(
+                        "sock_diag query failed ({}), reading /proc/net/tcp instead",
+                        e
+                    );
+                    self.source = Source::ProcNetTcp;
+                    self.sample()
+                }
+            },
+            #[cfg(target_os = "linux")]
+            Source::ProcNetTcp => ["/proc/net/tcp", "/proc/net/tcp6"]
+                .into_iter()
+                .filter_map(|path| std::fs::read_to_string(path).ok())
+                .flat_map(|contents| parse_proc_net_tcp(&contents))
+                .collect(),
+            #[cfg(not(target_os = "linux"))]
+            Source::Unavailable => HashMap::new(),
+        }
+    }
+}
+
+impl Default for TcpHealthCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Sum socket counters per process. Retransmits only count for sockets seen in the
+/// previous sample too, so a new socket (or the first sample) doesn't show up as a spike
+fn aggregate(
+    sockets: &HashMap<u64, SocketHealth>,
+    previous: &HashMap<u64, u32>,
+    socket_map: &HashMap<u64, (i32, String)>,
+    elapsed_secs: Option<f64>,
+) -> HashMap<i32, TcpHealth> {
+    // PID -> (retransmits since the last sample, RTT sum in µs, sockets with an RTT)
+    let mut totals: HashMap<i32, (u64, f64, u32)> = HashMap::new();
+    for (inode, socket) in sockets {
+        let Some(&(pid, _)) = socket_map.get(inode) else {
+            continue;
+        };
+        let total = totals.entry(pid).or_default();
+        if let Some(&before) = previous.get(inode) {
+            total.0 += socket.retransmits.saturating_sub(before) as u64;
+        }
+        if let Some(rtt_us) = socket.rtt_us {
+            total.1 += rtt_us as f64;
+            total.2 += 1;
+        }
+    }
+
+    totals
+        .into_iter()
+        .map(|(pid, (retransmits, rtt_sum, rtt_count))| {
+            let retransmit_rate = match elapsed_secs {
+                Some(secs) if secs > 0.0 => retransmits as f64 / secs,
+                _ => 0.0,
+            };
+            let avg_rtt_ms = (rtt_count > 0).then(|| rtt_sum / rtt_count as f64 / 1000.0);
+            (
+                pid,
+                TcpHealth {
+                    retransmit_rate,
+                    avg_rtt_ms,
+                },
+            )
+        })
+        .collect()
+}
+
+/// Parse /proc/net/tcp{,6}: the `retrnsmt` column (unrecovered retransmit timeouts,
+/// in hex) is the only retransmit counter there, and there is no RTT
+#[cfg(target_os = "linux")]
+fn parse_proc_net_tcp(contents: &str) -> Vec<(u64, SocketHealth)> {
+    contents
+        .lines()
+        .skip(1) // header
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let retransmits = u32::from_str_radix(fields.get(6)?, 16).ok()?;
+            let inode: u64 = fields.get(9)?.parse().ok()?;
+            // TIME_WAIT sockets no longer belong to a process (inode 0)
+            (inode != 0).then_some((
+                inode,
+                SocketHealth {
+                    retransmits,
+                    rtt_us: None,
+                },
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aggregate_counts_retransmits_of_known_sockets() {
+        let socket = |retransmits, rtt_us| SocketHealth {
+            retransmits,
+            rtt_us,
+        };
+        let sockets = HashMap::from([
+            (1, socket(15, Some(20_000))),
+            (2, socket(7, Some(40_000))),
+            (3, socket(100, None)),      // new since the last sample
+            (4, socket(9, Some(1_000))), // not owned by a known process
+        ]);
+        let previous = HashMap::from([(1, 5), (2, 7), (4, 0)]);
+        let socket_map = HashMap::from([
+            (1, (10, "curl".to_string())),
+            (2, (10, "curl".to_string())),
+            (3, (20, "wget".to_string())),
+        ]);
+
+        let health = aggregate(&sockets, &previous, &socket_map, Some(2.0));
+        assert_eq!(
+            health[&10],
+            TcpHealth {
+                retransmit_rate: 5.0,
+                avg_rtt_ms: Some(30.0),
+            }
+        );
+        assert_eq!(health[&20], TcpHealth::default());
+        assert_eq!(health.len(), 2);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_parse_proc_net_tcp() {
+        let contents = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n   0: 0100007F:0277 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 18724 1 0000000000000000 100 0 0 10 0\n   1: 0F02000A:C8A4 22D8B85D:01BB 01 00000000:00000000 01:00000016 0000000A  1000        0 92811 2 0000000000000000 20 4 30 10 -1\n   2: 0F02000A:C8A6 22D8B85D:01BB 06 00000000:00000000 03:00000F9E 00000000     0        0 0 3 0000000000000000\n";
+
+        assert_eq!(
+            parse_proc_net_tcp(contents),
+            vec![
+                (
+                    18724,
+                    SocketHealth {
+                        retransmits: 0,
+                        rtt_us: None
+                    }
+                ),
+                (
+                    92811,
+                    SocketHealth {
+                        retransmits: 10,
+                        rtt_us: None
+                    }
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,228 @@
+// TCP socket counters over sock_diag netlink (NETLINK_SOCK_DIAG), like `ss -ti`
+//
+// One dump request per address family returns an inet_diag_msg per socket, with
+// the kernel's struct tcp_info attached as an INET_DIAG_INFO attribute.
+
+use super::SocketHealth;
+use std::collections::HashMap;
+use std::io;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+
+const SOCK_DIAG_BY_FAMILY: u16 = 20;
+const INET_DIAG_INFO: u16 = 2;
+const NLMSG_DONE: u16 = libc::NLMSG_DONE as u16;
+const NLMSG_ERROR: u16 = libc::NLMSG_ERROR as u16;
+
+/// struct nlmsghdr
+const NLMSG_HEADER_LEN: usize = 16;
+/// struct inet_diag_req_v2
+const DIAG_REQUEST_LEN: usize = 56;
+/// struct inet_diag_msg, and where its idiag_inode field is
+const DIAG_MSG_LEN: usize = 72;
+const DIAG_MSG_INODE: usize = 68;
+/// Fields of struct tcp_info
+const TCPI_RTT: usize = 68;
+const TCPI_TOTAL_RETRANS: usize = 100;
+
+/// Every TCP state except LISTEN (listening sockets don't send data)
+const TCP_STATES: u32 = !(1 << 10);
+
+pub struct SockDiag {
+    fd: OwnedFd,
+}
+
+impl SockDiag {
+    /// Open a sock_diag socket and check that dumps are allowed (some sandboxes allow
+    /// the socket but refuse the query)
+    pub fn open() -> io::Result<Self> {
+        let fd = unsafe {
+            libc::socket(
+                libc::AF_NETLINK,
+                libc::SOCK_DGRAM | libc::SOCK_CLOEXEC,
+                libc::NETLINK_SOCK_DIAG,
+            )
+        };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let sock_diag = Self {
+            fd: unsafe { OwnedFd::from_raw_fd(fd) },
+        };
+        sock_diag.tcp_sockets()?;
+        Ok(sock_diag)
+    }
+
+    /// Counters of every IPv4 and IPv6 TCP socket, by inode
+    pub fn tcp_sockets(&self) -> io::Result<HashMap<u64, SocketHealth>> {
+        let mut sockets = HashMap::new();
+        for family in [libc::AF_INET, libc::AF_INET6] {
+            self.dump(family as u8, &mut sockets)?;
+        }
+        Ok(sockets)
+    }
+
+    fn dump(&self, family: u8, sockets: &mut HashMap<u64, SocketHealth>) -> io::Result<()> {
+        let request = dump_request(family);
+        let sent = unsafe {
+            libc::send(
+                self.fd.as_raw_fd(),
+                request.as_ptr().cast(),
+                request.len(),
+                0,
+            )
+        };
+        if sent < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let mut buf = vec![0u8; 64 * 1024];
+        loop {
+            let received =
+                unsafe { libc::recv(self.fd.as_raw_fd(), buf.as_mut_ptr().cast(), buf.len(), 0) };
+            if received < 0 {
+                let err = io::Error::last_os_error();
+                if err.kind() == io::ErrorKind::Interrupted {
+                    continue;
+                }
+                return Err(err);
+            }
+
+            let mut messages = &buf[..received as usize];
+            while messages.len() >= NLMSG_HEADER_LEN {
+                let len = read_u32(messages, 0).unwrap_or(0) as usize;
+                let kind = read_u16(messages, 4).unwrap_or(0);
+                if len < NLMSG_HEADER_LEN || len > messages.len() {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "truncated netlink message",
+                    ));
+                }
+                let payload = &messages[NLMSG_HEADER_LEN..len];
+                match kind {
+                    NLMSG_DONE => return Ok(()),
+                    NLMSG_ERROR => match read_u32(payload, 0).map(|errno| errno as i32) {
+                        Some(0) => {} // acknowledgement
+                        Some(errno) => return Err(io::Error::from_raw_os_error(-errno)),
+                        None => {
+                            return Err(io::Error::new(
+                                io::ErrorKind::InvalidData,
+                                "truncated netlink error",
+                            ));
+                        }
+                    },
+                    SOCK_DIAG_BY_FAMILY => {
+                        if let Some((inode, health)) = parse_diag_msg(payload) {
+                            sockets.insert(inode, health);
+                        }
+                    }
+                    _ => {}
+                }
+                messages = &messages[align(len).min(messages.len())..];
+            }
+        }
+    }
+}
+
+/// SOCK_DIAG_BY_FAMILY dump request for all TCP sockets of `family`, asking for tcp_info
+fn dump_request(family: u8) -> Vec<u8> {
+    let len = NLMSG_HEADER_LEN + DIAG_REQUEST_LEN;
+    let mut request = Vec::with_capacity(len);
+
+    // struct nlmsghdr (sequence and port ID left at 0)
+    request.extend_from_slice(&(len as u32).to_ne_bytes());
+    request.extend_from_slice(&SOCK_DIAG_BY_FAMILY.to_ne_bytes());
+    request.extend_from_slice(&((libc::NLM_F_REQUEST | libc::NLM_F_DUMP) as u16).to_ne_bytes());
+    request.resize(NLMSG_HEADER_LEN, 0);
+
+    // struct inet_diag_req_v2 (an all-zero socket ID matches every socket)
+    request.push(family);
+    request.push(libc::IPPROTO_TCP as u8);
+    request.push(1 << (INET_DIAG_INFO - 1));
+    request.push(0);
+    request.extend_from_slice(&TCP_STATES.to_ne_bytes());
+    request.resize(len, 0);
+    request
+}
+
+/// Inode and counters of one socket from an inet_diag_msg and its attributes
+/// (None for sockets without an owner or without tcp_info)
+fn parse_diag_msg(payload: &[u8]) -> Option<(u64, SocketHealth)> {
+    let inode = read_u32(payload, DIAG_MSG_INODE)? as u64;
+    if inode == 0 {
+        return None;
+    }
+
+    let mut attributes = payload.get(DIAG_MSG_LEN..)?;
+    while attributes.len() >= 4 {
+        let len = read_u16(attributes, 0)? as usize;
+        let kind = read_u16(attributes, 2)?;
+        if len < 4 || len > attributes.len() {
+            return None;
+        }
+        if kind == INET_DIAG_INFO {
+            let info = &attributes[4..len];
+            return Some((
+                inode,
+                SocketHealth {
+                    retransmits: read_u32(info, TCPI_TOTAL_RETRANS)?,
+                    rtt_us: read_u32(info, TCPI_RTT).filter(|rtt| *rtt > 0),
+                },
+            ));
+        }
+        attributes = &attributes[align(len).min(attributes.len())..];
+    }
+    None
+}
+
+/// Netlink messages and attributes are padded to 4 bytes
+fn align(len: usize) -> usize {
+    (len + 3) & !3
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_ne_bytes(
+        bytes.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_ne_bytes(
+        bytes.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_diag_msg() {
+        let mut info = vec![0u8; 104];
+        info[TCPI_RTT..TCPI_RTT + 4].copy_from_slice(&25_000u32.to_ne_bytes());
+        info[TCPI_TOTAL_RETRANS..TCPI_TOTAL_RETRANS + 4].copy_from_slice(&42u32.to_ne_bytes());
+
+        let mut payload = vec![0u8; DIAG_MSG_LEN];
+        payload[DIAG_MSG_INODE..DIAG_MSG_INODE + 4].copy_from_slice(&9001u32.to_ne_bytes());
+        // An unrelated attribute (INET_DIAG_MEMINFO) with padding, then INET_DIAG_INFO
+        payload.extend_from_slice(&6u16.to_ne_bytes());
+        payload.extend_from_slice(&1u16.to_ne_bytes());
+        payload.extend_from_slice(&[0, 0, 0, 0]);
+        payload.extend_from_slice(&((4 + info.len()) as u16).to_ne_bytes());
+        payload.extend_from_slice(&INET_DIAG_INFO.to_ne_bytes());
+        payload.extend_from_slice(&info);
+
+        assert_eq!(
+            parse_diag_msg(&payload),
+            Some((
+                9001,
+                SocketHealth {
+                    retransmits: 42,
+                    rtt_us: Some(25_000),
+                }
+            ))
+        );
+
+        // Without tcp_info there is nothing to report
+        assert_eq!(parse_diag_msg(&payload[..DIAG_MSG_LEN + 8]), None);
+    }
+}
@@ -60,6 +60,12 @@ impl WindowsProcessUtils {
     }
 }
 
+impl Default for WindowsProcessUtils {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ProcessUtils for WindowsProcessUtils {
     fn get_process_name(&self, pid: i32) -> Result<String> {
         // Use cached System instance instead of creating new one
@@ -80,6 +86,32 @@ impl ProcessUtils for WindowsProcessUtils {
         sys.process(pid_obj).is_some()
     }
 
+    fn get_cmdline(&self, pid: i32) -> Result<String> {
+        // Use cached System instance (command line comes from the process snapshot)
+        let sys = self.cached_system.lock().unwrap();
+        let pid_obj = Pid::from_u32(pid as u32);
+
+        let process = sys
+            .process(pid_obj)
+            .ok_or_else(|| anyhow::anyhow!("Process {} not found", pid))?;
+        let args: Vec<_> = process.cmd().iter().map(|a| a.to_string_lossy()).collect();
+        if args.is_empty() {
+            anyhow::bail!("Process {} has no command line", pid);
+        }
+        Ok(args.join(" "))
+    }
+
+    fn get_parent_pid(&self, pid: i32) -> Option<i32> {
+        // Use cached System instance (parent comes from the process snapshot)
+        let sys = self.cached_system.lock().unwrap();
+        let pid_obj = Pid::from_u32(pid as u32);
+
+        sys.process(pid_obj)
+            .and_then(|p| p.parent())
+            .map(|parent| parent.as_u32() as i32)
+            .filter(|&ppid| ppid > 0)
+    }
+
     fn get_all_processes(&self) -> Result<Vec<ProcessEntry>> {
         let sys = System::new_all();
 
@@ -20,35 +20,38 @@ use std::path::PathBuf;
 
 #[cfg(feature = "throttle-ebpf")]
 use aya::{
-    maps::HashMap as BpfHashMap,
+    maps::{HashMap as BpfHashMap, PerCpuHashMap as BpfPerCpuHashMap},
     programs::{CgroupSkb, CgroupSkbAttachType},
     Ebpf,
 };
 
 #[cfg(feature = "throttle-ebpf")]
 /// Track attached programs for proper cleanup
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 struct AttachedProgram {
     cgroup_path: PathBuf,
     attach_type: CgroupSkbAttachType,
-    program_fd: i32, // Required for detaching with BPF_F_ALLOW_MULTI
-    cgroup_id: u64,  // Required to find this entry without querying /proc
+    attachment: CgroupSkbAttachment, // bpf_link or legacy, decides how to detach
+    program_fd: i32,                 // Required for detaching with BPF_F_ALLOW_MULTI
+    cgroup_id: u64,                  // Required to find this entry without querying /proc
 }
 
 #[cfg(feature = "throttle-ebpf")]
-use chadthrottle_common::{CgroupThrottleConfig, ThrottleStats, TokenBucket};
+use chadthrottle_common::{CgroupThrottleConfig, POLICY_DROP, ThrottleStats, TokenBucket};
 
 #[cfg(feature = "throttle-ebpf")]
 use crate::backends::throttle::linux_ebpf_utils::*;
 #[cfg(feature = "throttle-ebpf")]
-use crate::backends::throttle::DownloadThrottleBackend;
+use crate::backends::throttle::{DownloadThrottleBackend, ThrottleError, ThrottleResult};
 #[cfg(feature = "throttle-ebpf")]
-use crate::backends::{BackendCapabilities, BackendPriority};
+use crate::backends::{BackendCapabilities, BackendPriority, TrafficTypeSupport};
+
+use crate::process::AddressFamily;
 
 #[cfg(not(feature = "throttle-ebpf"))]
-use crate::backends::throttle::DownloadThrottleBackend;
+use crate::backends::throttle::{DownloadThrottleBackend, ThrottleResult};
 #[cfg(not(feature = "throttle-ebpf"))]
-use crate::backends::{BackendCapabilities, BackendPriority};
+use crate::backends::{BackendCapabilities, BackendPriority, TrafficTypeSupport};
 #[cfg(not(feature = "throttle-ebpf"))]
 use anyhow::{anyhow, Result};
 #[cfg(not(feature = "throttle-ebpf"))]
@@ -70,6 +73,24 @@ pub struct EbpfDownload {
     #[cfg(feature = "throttle-ebpf")]
     /// Track attached programs for proper cleanup (especially for legacy attach method)
     attached_programs: Vec<AttachedProgram>,
+    #[cfg(feature = "throttle-ebpf")]
+    /// Config written for each PID, kept to repair the config map
+    throttle_configs: HashMap<i32, CgroupThrottleConfig>,
+    #[cfg(feature = "throttle-ebpf")]
+    /// Watches for config misses that point at a stale cgroup id
+    cgroup_watch: CgroupIdWatch,
+    #[cfg(feature = "throttle-ebpf")]
+    /// How many times each PID's throttle was repaired
+    cgroup_repairs: HashMap<i32, u64>,
+    #[cfg(feature = "throttle-ebpf")]
+    /// Watches for a program that stopped seeing the throttled processes' traffic
+    attachment_watch: AttachmentWatch,
+    #[cfg(feature = "throttle-ebpf")]
+    /// How many times each PID's program was re-attached
+    reattachments: HashMap<i32, u64>,
+    #[cfg(feature = "throttle-ebpf")]
+    /// Moves the per-CPU token bucket shares to the CPUs with traffic
+    cpu_rebalancer: CpuRebalancer,
     active_throttles: HashMap<i32, u64>,
 }
 
@@ -89,6 +110,12 @@ impl EbpfDownload {
                 cgroup_refcount: HashMap::new(),
                 attached_cgroups: std::collections::HashSet::new(),
                 attached_programs: Vec::new(),
+                throttle_configs: HashMap::new(),
+                cgroup_watch: CgroupIdWatch::default(),
+                cgroup_repairs: HashMap::new(),
+                attachment_watch: AttachmentWatch::default(),
+                reattachments: HashMap::new(),
+                cpu_rebalancer: CpuRebalancer::default(),
                 active_throttles: HashMap::new(),
             })
         }
@@ -115,7 +142,8 @@ impl EbpfDownload {
                 const PROGRAM_BYTES: &[u8] =
                     aya::include_bytes_aligned!(concat!(env!("OUT_DIR"), "/chadthrottle-ingress"));
 
-                let mut ebpf = load_ebpf_program(PROGRAM_BYTES)?;
+                // Maps are pinned, so a restart adopts them instead of creating new ones
+                let mut ebpf = load_pinned_ebpf_program(PROGRAM_BYTES, "chadthrottle_ingress")?;
 
                 // Load the program into the kernel NOW to create map FDs
                 // This ensures there's only ONE set of maps that both
@@ -131,6 +159,7 @@ impl EbpfDownload {
                     .load()
                     .context("Failed to load chadthrottle_ingress program into kernel")?;
                 log::info!("✅ Loaded chadthrottle_ingress program into kernel (maps created)");
+                pin_program(&mut ebpf, "chadthrottle_ingress");
 
                 self.ebpf = Some(ebpf);
                 return Ok(());
@@ -159,14 +188,9 @@ impl EbpfDownload {
             None => return Ok(()), // PID not tracked
         };
 
-        // CRITICAL: Use fixed key (0) to match eBPF program
-        const MAP_KEY: u64 = 0;
-
-        if let Some(ref mut ebpf) = self.ebpf {
-            // Read stats from CGROUP_STATS map
-            let stats_map: BpfHashMap<_, u64, ThrottleStats> = get_bpf_map(ebpf, "CGROUP_STATS")?;
-
-            if let Ok(stats) = stats_map.get(&MAP_KEY, 0) {
+        if let Some(ref ebpf) = self.ebpf {
+            // Read stats from CGROUP_STATS map (summed over all CPUs)
+            if let Some(stats) = read_throttle_stats(ebpf) {
                 let drop_rate = if stats.packets_total > 0 {
                     (stats.packets_dropped as f64 / stats.packets_total as f64) * 100.0
                 } else {
@@ -195,16 +219,20 @@ impl EbpfDownload {
                 } else if stats.packets_dropped == 0 && stats.packets_total > 100 {
                     log::warn!(
                         "⚠️  PID {} cgroup {}: eBPF NOT DROPPING PACKETS! \
-                         packets_total={}, packets_dropped=0, bytes_total={}, drop_rate=0.0%",
+                         packets_total={}, packets_dropped=0, bytes_total={}, drop_rate=0.0%, \
+                         passed_with_tokens={}, passed_filter_mismatch={}",
                         pid,
                         cgroup_id,
                         stats.packets_total,
-                        stats.bytes_total
+                        stats.bytes_total,
+                        stats.passed_with_tokens,
+                        stats.passed_filter_mismatch
                     );
                 } else {
                     log::info!(
                         "eBPF stats PID {} cgroup {}: program_calls={}, packets={}, dropped={} ({:.1}%), \
-                         bytes={}, bytes_dropped={}, config_misses={}, cgroup_id_seen={}",
+                         bytes={}, bytes_dropped={}, config_misses={}, cgroup_id_seen={}, \
+                         passed_with_tokens={}, dropped_no_tokens={}, passed_filter_mismatch={}",
                         pid,
                         cgroup_id,
                         stats.program_calls,
@@ -214,7 +242,10 @@ impl EbpfDownload {
                         stats.bytes_total,
                         stats.bytes_dropped,
                         stats.config_misses,
-                        stats.cgroup_id_seen
+                        stats.cgroup_id_seen,
+                        stats.passed_with_tokens,
+                        stats.dropped_no_tokens,
+                        stats.passed_filter_mismatch
                     );
                 }
             } else {
@@ -226,93 +257,187 @@ impl EbpfDownload {
             }
 
             // Read token bucket state
-            let bucket_map: BpfHashMap<_, u64, TokenBucket> = get_bpf_map(ebpf, "CGROUP_BUCKETS")?;
-
-            if let Ok(bucket) = bucket_map.get(&MAP_KEY, 0) {
+            if let Some(buckets) = read_cpu_buckets(ebpf) {
+                let active: Vec<&TokenBucket> = buckets
+                    .iter()
+                    .filter(|bucket| bucket.rate_bps > 0)
+                    .collect();
                 log::debug!(
-                    "PID {} token bucket: tokens={}/{} bytes, rate={} bytes/sec",
+                    "PID {} token buckets: tokens={}/{} bytes, rate={} bytes/sec across {} CPUs",
                     pid,
-                    bucket.tokens,
-                    bucket.capacity,
-                    bucket.rate_bps
+                    active.iter().map(|bucket| bucket.tokens).sum::<u64>(),
+                    active.iter().map(|bucket| bucket.capacity).sum::<u64>(),
+                    active.iter().map(|bucket| bucket.rate_bps).sum::<u64>(),
+                    active.len()
                 );
             }
         }
 
         Ok(())
     }
-}
-
-impl DownloadThrottleBackend for EbpfDownload {
-    fn name(&self) -> &'static str {
-        "ebpf"
-    }
-
-    fn priority(&self) -> BackendPriority {
-        BackendPriority::Best // Highest priority - no IFB needed!
-    }
 
-    fn is_available() -> bool {
-        #[cfg(feature = "throttle-ebpf")]
-        {
-            // Check basic kernel support (cgroup v2, kernel version)
-            if !check_ebpf_support() {
-                return false;
-            }
+    /// Repair throttles whose program keeps missing its config
+    ///
+    /// When config misses rise while the program is being called, the config map
+    /// no longer matches the cgroup the program sees. Each throttled PID's cgroup
+    /// is re-read: a PID that moved cgroups is throttled again in its new cgroup,
+    /// otherwise a missing config is rewritten with the current cgroup id.
+    #[cfg(feature = "throttle-ebpf")]
+    fn repair_cgroup_ids(&mut self) -> Result<Vec<i32>> {
+        let Some(stats) = self.ebpf.as_ref().and_then(read_throttle_stats) else {
+            return Ok(Vec::new());
+        };
+        if !self.cgroup_watch.misses_rising(&stats) {
+            return Ok(Vec::new());
+        }
 
-            // Check if eBPF programs are actually built and embedded
-            #[cfg(not(ebpf_programs_built))]
-            {
-                log::debug!(
-                    "eBPF download backend unavailable: programs not built.\n\
-                     Build eBPF programs first:\n\
-                     1. Install bpf-linker: cargo install bpf-linker\n\
-                     2. Add rust-src: rustup component add rust-src\n\
-                     3. Build programs: cargo xtask build-ebpf"
+        let mut repaired = Vec::new();
+        let tracked: Vec<(i32, u64)> = self.pid_to_cgroup.iter().map(|(p, c)| (*p, *c)).collect();
+        for (pid, old_cgroup_id) in tracked {
+            // Skip processes that have exited
+            let Ok(cgroup_id) = get_cgroup_id(pid) else {
+                continue;
+            };
+            let Some(mut config) = self.throttle_configs.get(&pid).copied() else {
+                continue;
+            };
+            let repairs = self.cgroup_repairs.get(&pid).copied().unwrap_or(0) + 1;
+
+            if cgroup_id != old_cgroup_id {
+                log::warn!(
+                    "eBPF download throttle for PID {}: cgroup id changed ({} -> {}), re-attaching",
+                    pid,
+                    old_cgroup_id,
+                    cgroup_id
+                );
+                self.remove_download_throttle(pid)?;
+                self.throttle_download_with_policy(
+                    pid,
+                    String::new(),
+                    config.rate_bps,
+                    traffic_type_from_ebpf(config.traffic_type),
+                    policy_from_ebpf(config.policy),
+                )?;
+            } else {
+                config.cgroup_id = cgroup_id;
+                let Some(ref mut ebpf) = self.ebpf else {
+                    break;
+                };
+                if !rewrite_missing_config(ebpf, config)? {
+                    continue;
+                }
+                log::warn!(
+                    "eBPF download throttle for PID {}: config was missing, rewrote it for cgroup {}",
+                    pid,
+                    cgroup_id
                 );
-                return false;
             }
 
-            // All checks passed
-            true
-        }
-
-        #[cfg(not(feature = "throttle-ebpf"))]
-        {
-            false
+            self.cgroup_repairs.insert(pid, repairs);
+            repaired.push(pid);
         }
-    }
 
-    fn capabilities(&self) -> BackendCapabilities {
-        BackendCapabilities {
-            ipv4_support: true,
-            ipv6_support: true,
-            per_process: true,
-            per_connection: false,
-        }
+        Ok(repaired)
     }
 
-    fn init(&mut self) -> Result<()> {
-        #[cfg(feature = "throttle-ebpf")]
+    /// Re-attach programs that stopped seeing traffic
+    ///
+    /// If the program calls stop rising while throttled processes (`flowing`) still
+    /// have traffic, the attachments went stale. Each one is detached and attached
+    /// again, to the cgroup path its processes are in now, with the configured
+    /// `BpfAttachMethod`. Returns the PIDs in the re-attached cgroups.
+    #[cfg(feature = "throttle-ebpf")]
+    fn reattach_stale_programs(
+        &mut self,
+        flowing: &std::collections::HashSet<i32>,
+    ) -> Result<Vec<i32>> {
+        let Some(ref mut ebpf) = self.ebpf else {
+            return Ok(Vec::new());
+        };
+        let program_calls = read_throttle_stats(ebpf).map_or(0, |stats| stats.program_calls);
+        let traffic_flowing = flowing
+            .iter()
+            .any(|pid| self.pid_to_cgroup.contains_key(pid));
+        if !self
+            .attachment_watch
+            .calls_frozen(program_calls, traffic_flowing)
         {
-            self.ensure_loaded()?;
-            log::info!("eBPF download backend initialized");
-            Ok(())
+            return Ok(Vec::new());
         }
 
-        #[cfg(not(feature = "throttle-ebpf"))]
-        {
-            Err(anyhow!("eBPF backend not compiled"))
+        let mut reattached = Vec::new();
+        for attached in std::mem::take(&mut self.attached_programs) {
+            let pids: Vec<i32> = self
+                .pid_to_cgroup
+                .iter()
+                .filter(|(_, cgroup_id)| **cgroup_id == attached.cgroup_id)
+                .map(|(pid, _)| *pid)
+                .collect();
+            // The cgroup may have been recreated: attach where the processes are now
+            let cgroup_path = pids
+                .iter()
+                .find_map(|pid| get_cgroup_path(*pid).ok())
+                .unwrap_or_else(|| attached.cgroup_path.clone());
+            log::warn!(
+                "eBPF download program stopped seeing traffic (program_calls stuck at {}), \
+                 re-attaching to {:?}",
+                program_calls,
+                cgroup_path
+            );
+
+            // The old attachment may already be gone with its cgroup
+            if let Err(e) = detach_cgroup_skb(
+                Some(&mut *ebpf),
+                "chadthrottle_ingress",
+                &attached.cgroup_path,
+                attached.attach_type,
+                attached.attachment,
+                attached.program_fd,
+            ) {
+                log::debug!(
+                    "Detaching stale program from {:?}: {}",
+                    attached.cgroup_path,
+                    e
+                );
+            }
+            self.attached_cgroups.remove(&attached.cgroup_path);
+
+            let attachment = match attach_cgroup_skb(
+                ebpf,
+                "chadthrottle_ingress",
+                &cgroup_path,
+                attached.attach_type,
+            ) {
+                Ok(attachment) => attachment,
+                Err(e) => {
+                    log::error!("Failed to re-attach program to {:?}: {:#}", cgroup_path, e);
+                    continue;
+                }
+            };
+            self.attached_cgroups.insert(cgroup_path.clone());
+            self.attached_programs.push(AttachedProgram {
+                cgroup_path,
+                attachment,
+                ..attached
+            });
+
+            for pid in pids {
+                *self.reattachments.entry(pid).or_insert(0) += 1;
+                reattached.push(pid);
+            }
         }
+
+        Ok(reattached)
     }
 
-    fn throttle_download(
+    /// Throttle a process's ingress traffic, optionally of one IP version only
+    fn throttle(
         &mut self,
         pid: i32,
-        _process_name: String,
         limit_bytes_per_sec: u64,
         traffic_type: crate::process::TrafficType,
-    ) -> Result<()> {
+        family: AddressFamily,
+    ) -> ThrottleResult<()> {
         use crate::process::TrafficType;
 
         // eBPF backend now supports all traffic types via IP classification in kernel
@@ -346,7 +471,7 @@ impl DownloadThrottleBackend for EbpfDownload {
                         "Attaching eBPF ingress program to cgroup (path: {:?})",
                         cgroup_path
                     );
-                    attach_cgroup_skb(
+                    let attachment = attach_cgroup_skb(
                         ebpf,
                         "chadthrottle_ingress",
                         &cgroup_path,
@@ -378,6 +503,7 @@ impl DownloadThrottleBackend for EbpfDownload {
                     self.attached_programs.push(AttachedProgram {
                         cgroup_path: cgroup_path.clone(),
                         attach_type: CgroupSkbAttachType::Ingress,
+                        attachment,
                         program_fd,
                         cgroup_id,
                     });
@@ -391,8 +517,8 @@ impl DownloadThrottleBackend for EbpfDownload {
 
             // Update BPF maps with configuration
             if let Some(ref mut ebpf) = self.ebpf {
-                // Allow bursts up to 2x the sustained rate (configurable)
-                let burst_size = limit_bytes_per_sec * 2;
+                // Allow bursts up to 2x the sustained rate, and at least one packet
+                let burst_size = chadthrottle_common::burst_size_for(limit_bytes_per_sec);
 
                 // CRITICAL: Use fixed key (0) instead of cgroup_id
                 // The eBPF program uses a fixed key because it runs in softirq context
@@ -418,35 +544,32 @@ impl DownloadThrottleBackend for EbpfDownload {
                     cgroup_id, // Store for diagnostics
                     pid: pid as u32,
                     traffic_type: traffic_type_value,
-                    _padding: [0; 3],
+                    policy: POLICY_DROP, // Ingress packets can't be held back, only dropped
+                    cpu_count: online_cpu_count(),
                     rate_bps: limit_bytes_per_sec,
                     burst_size,
+                    address_family: address_family_value(family),
+                    _padding: [0; 7],
                 };
 
-                config_map.insert(MAP_KEY, config, 0)?;
+                config_map
+                    .insert(MAP_KEY, config, 0)
+                    .context("Failed to store throttle config")?;
+                self.throttle_configs.insert(pid, config);
 
-                // Initialize token bucket
-                let mut bucket_map: BpfHashMap<_, u64, TokenBucket> =
-                    get_bpf_map(ebpf, "CGROUP_BUCKETS")?;
-
-                // NOTE: Set last_update_ns to 0 to let eBPF program initialize it on first packet
-                // This avoids clock mismatch between userspace (wall clock via SystemTime)
-                // and kernel (monotonic clock via bpf_ktime_get_ns)
-                let bucket = TokenBucket {
-                    capacity: burst_size,
-                    tokens: burst_size,
-                    last_update_ns: 0, // eBPF will initialize on first packet
-                    rate_bps: limit_bytes_per_sec,
-                };
-
-                bucket_map.insert(MAP_KEY, bucket, 0)?;
+                // Initialize the per-CPU token buckets (the rate is split evenly
+                // until the first rebalance). last_update_ns stays 0 so the eBPF
+                // program initializes it on first packet, avoiding a clock mismatch
+                // between userspace (wall clock) and kernel (monotonic clock)
+                let tokens = init_cpu_buckets(ebpf, &config)?;
 
                 log::debug!(
-                    "Initialized token bucket for cgroup {}: rate={} bytes/sec, burst={} bytes, tokens={}",
+                    "Initialized token buckets for cgroup {}: rate={} bytes/sec across {} CPUs, burst={} bytes, tokens={}",
                     cgroup_id,
                     limit_bytes_per_sec,
+                    config.cpu_count,
                     burst_size,
-                    bucket.tokens
+                    tokens
                 );
             }
 
@@ -457,11 +580,113 @@ impl DownloadThrottleBackend for EbpfDownload {
 
         #[cfg(not(feature = "throttle-ebpf"))]
         {
-            Err(anyhow!("eBPF backend not compiled"))
+            Err(anyhow!("eBPF backend not compiled").into())
         }
     }
+}
 
-    fn remove_download_throttle(&mut self, pid: i32) -> Result<()> {
+impl DownloadThrottleBackend for EbpfDownload {
+    fn name(&self) -> &'static str {
+        "ebpf"
+    }
+
+    fn priority(&self) -> BackendPriority {
+        BackendPriority::Best // Highest priority - no IFB needed!
+    }
+
+    fn is_available() -> bool {
+        #[cfg(feature = "throttle-ebpf")]
+        {
+            // Check basic kernel support (cgroup v2, kernel version)
+            if !check_ebpf_support() {
+                return false;
+            }
+
+            // Check if eBPF programs are actually built and embedded
+            #[cfg(not(ebpf_programs_built))]
+            {
+                log::debug!(
+                    "eBPF download backend unavailable: programs not built.\n\
+                     Build eBPF programs first:\n\
+                     1. Install bpf-linker: cargo install bpf-linker\n\
+                     2. Add rust-src: rustup component add rust-src\n\
+                     3. Build programs: cargo xtask build-ebpf"
+                );
+                return false;
+            }
+
+            // All checks passed
+            true
+        }
+
+        #[cfg(not(feature = "throttle-ebpf"))]
+        {
+            false
+        }
+    }
+
+    fn unavailable_reason() -> String {
+        if !check_ebpf_support() {
+            return "requires cgroup v2 and kernel 4.10+".to_string();
+        }
+        // Kernel support is there, so the embedded programs are missing
+        "eBPF programs not built (run: cargo xtask build-ebpf)".to_string()
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            ipv4_support: true,
+            ipv6_support: true,
+            per_process: true,
+            per_connection: false,
+            tcp_support: true,
+            udp_support: true,
+            traffic_types: TrafficTypeSupport::from_predicate(|t| self.supports_traffic_type(t)),
+        }
+    }
+
+    fn init(&mut self) -> ThrottleResult<()> {
+        #[cfg(feature = "throttle-ebpf")]
+        {
+            self.ensure_loaded()?;
+            log::info!("eBPF download backend initialized");
+            Ok(())
+        }
+
+        #[cfg(not(feature = "throttle-ebpf"))]
+        {
+            Err(anyhow!("eBPF backend not compiled").into())
+        }
+    }
+
+    fn throttle_download(
+        &mut self,
+        pid: i32,
+        _process_name: String,
+        limit_bytes_per_sec: u64,
+        traffic_type: crate::process::TrafficType,
+    ) -> ThrottleResult<()> {
+        self.throttle(pid, limit_bytes_per_sec, traffic_type, AddressFamily::Any)
+    }
+
+    fn supports_address_family(&self, family: AddressFamily) -> bool {
+        family.supported_by(&self.capabilities()) // the program checks the EtherType
+    }
+
+    fn throttle_download_for_family(
+        &mut self,
+        pid: i32,
+        _process_name: String,
+        limit_bytes_per_sec: u64,
+        traffic_type: crate::process::TrafficType,
+        family: AddressFamily,
+        _interfaces: Option<&[String]>,
+        _policy: crate::process::Policy,
+    ) -> ThrottleResult<()> {
+        self.throttle(pid, limit_bytes_per_sec, traffic_type, family)
+    }
+
+    fn remove_download_throttle(&mut self, pid: i32) -> ThrottleResult<()> {
         #[cfg(feature = "throttle-ebpf")]
         {
             // Get the cgroup ID for this PID
@@ -495,13 +720,13 @@ impl DownloadThrottleBackend for EbpfDownload {
                             let _ = config_map.remove(&MAP_KEY);
                             log::debug!("Removed config from map[{}]", MAP_KEY);
 
-                            let mut bucket_map: BpfHashMap<_, u64, TokenBucket> =
-                                get_bpf_map(ebpf, "CGROUP_BUCKETS")?;
+                            let mut bucket_map: BpfPerCpuHashMap<_, u64, TokenBucket> =
+                                get_bpf_per_cpu_map(ebpf, "CGROUP_BUCKETS")?;
                             let _ = bucket_map.remove(&MAP_KEY);
                             log::debug!("Removed bucket from map[{}]", MAP_KEY);
 
-                            let mut stats_map: BpfHashMap<_, u64, ThrottleStats> =
-                                get_bpf_map(ebpf, "CGROUP_STATS")?;
+                            let mut stats_map: BpfPerCpuHashMap<_, u64, ThrottleStats> =
+                                get_bpf_per_cpu_map(ebpf, "CGROUP_STATS")?;
                             let _ = stats_map.remove(&MAP_KEY);
                             log::debug!("Removed stats from map[{}]", MAP_KEY);
                         }
@@ -520,9 +745,12 @@ impl DownloadThrottleBackend for EbpfDownload {
                                 attached.cgroup_id,
                                 attached.program_fd
                             );
-                            if let Err(e) = detach_cgroup_skb_legacy(
+                            if let Err(e) = detach_cgroup_skb(
+                                self.ebpf.as_mut(),
+                                "chadthrottle_ingress",
                                 &attached.cgroup_path,
                                 attached.attach_type,
+                                attached.attachment,
                                 attached.program_fd,
                             ) {
                                 log::error!(
@@ -550,12 +778,43 @@ impl DownloadThrottleBackend for EbpfDownload {
             }
 
             self.active_throttles.remove(&pid);
+            self.throttle_configs.remove(&pid);
+            self.cgroup_repairs.remove(&pid);
+            self.reattachments.remove(&pid);
             Ok(())
         }
 
         #[cfg(not(feature = "throttle-ebpf"))]
         {
-            Err(anyhow!("eBPF backend not compiled"))
+            Err(anyhow!("eBPF backend not compiled").into())
+        }
+    }
+
+    fn add_to_throttle(&mut self, pid: i32, member_pid: i32) -> ThrottleResult<()> {
+        #[cfg(feature = "throttle-ebpf")]
+        {
+            // The program is attached to the process's own cgroup rather than one
+            // created for it, so a member is only covered if it already shares it
+            let cgroup_id = self
+                .pid_to_cgroup
+                .get(&pid)
+                .copied()
+                .ok_or(ThrottleError::NotFound)?;
+            if get_cgroup_id(member_pid)? == cgroup_id {
+                return Ok(());
+            }
+            Err(anyhow::anyhow!(
+                "PID {} is in a different cgroup than PID {} (eBPF can't move processes)",
+                member_pid,
+                pid
+            )
+            .into())
+        }
+
+        #[cfg(not(feature = "throttle-ebpf"))]
+        {
+            let _ = (pid, member_pid);
+            Err(anyhow!("eBPF backend not compiled").into())
         }
     }
 
@@ -567,7 +826,7 @@ impl DownloadThrottleBackend for EbpfDownload {
         self.active_throttles.clone()
     }
 
-    fn cleanup(&mut self) -> Result<()> {
+    fn cleanup(&mut self) -> ThrottleResult<()> {
         #[cfg(feature = "throttle-ebpf")]
         {
             log::info!("Cleaning up eBPF download backend");
@@ -588,16 +847,19 @@ impl DownloadThrottleBackend for EbpfDownload {
                 );
 
                 // Detach any remaining programs
-                for attached in &self.attached_programs {
+                for attached in self.attached_programs.drain(..) {
                     log::warn!(
                         "Detaching orphaned program: {:?} (id: {}, fd: {})",
                         attached.cgroup_path,
                         attached.cgroup_id,
                         attached.program_fd
                     );
-                    if let Err(e) = detach_cgroup_skb_legacy(
+                    if let Err(e) = detach_cgroup_skb(
+                        self.ebpf.as_mut(),
+                        "chadthrottle_ingress",
                         &attached.cgroup_path,
                         attached.attach_type,
+                        attached.attachment,
                         attached.program_fd,
                     ) {
                         log::error!("Failed to detach orphaned program: {}", e);
@@ -611,6 +873,9 @@ impl DownloadThrottleBackend for EbpfDownload {
             self.pid_to_cgroup.clear();
             self.cgroup_refcount.clear();
             self.attached_cgroups.clear();
+            self.throttle_configs.clear();
+            self.cgroup_repairs.clear();
+            self.reattachments.clear();
 
             log::info!("eBPF download backend cleanup complete");
             Ok(())
@@ -618,19 +883,21 @@ impl DownloadThrottleBackend for EbpfDownload {
 
         #[cfg(not(feature = "throttle-ebpf"))]
         {
-            Err(anyhow!("eBPF backend not compiled"))
+            Err(anyhow!("eBPF backend not compiled").into())
         }
     }
 
-    fn supports_traffic_type(&self, _traffic_type: crate::process::TrafficType) -> bool {
-        // eBPF backend supports all traffic types with IP filtering
-        true
+    fn supports_traffic_type(&self, traffic_type: crate::process::TrafficType) -> bool {
+        // eBPF backend supports all traffic types with IP filtering, but the
+        // in-kernel classifier only knows the built-in ranges, not subnet overrides
+        traffic_type == crate::process::TrafficType::All
+            || crate::traffic_classifier::subnet_overrides().is_empty()
     }
 
-    fn log_diagnostics(&mut self, pid: i32) -> Result<()> {
+    fn log_diagnostics(&mut self, pid: i32) -> ThrottleResult<()> {
         #[cfg(feature = "throttle-ebpf")]
         {
-            self.log_throttle_stats(pid)
+            Ok(self.log_throttle_stats(pid)?)
         }
 
         #[cfg(not(feature = "throttle-ebpf"))]
@@ -639,43 +906,91 @@ impl DownloadThrottleBackend for EbpfDownload {
         }
     }
 
-    fn get_stats(&self, _pid: i32) -> Option<crate::backends::throttle::BackendStats> {
-        // Note: Reading from BPF maps requires mutable access (aya limitation)
-        // Stats are being collected in the eBPF program, but we can't read them
-        // from an immutable context. Use get_stats_mut() if you need statistics.
-        None
+    fn get_stats(&self, pid: i32) -> Option<crate::backends::throttle::BackendStats> {
+        #[cfg(feature = "throttle-ebpf")]
+        {
+            if !self.pid_to_cgroup.contains_key(&pid) {
+                return None;
+            }
+            let mut stats: crate::backends::throttle::BackendStats =
+                self.ebpf.as_ref().and_then(read_throttle_stats)?.into();
+            stats.cgroup_repairs = self.cgroup_repairs.get(&pid).copied().unwrap_or(0);
+            stats.reattachments = self.reattachments.get(&pid).copied().unwrap_or(0);
+            Some(stats)
+        }
+
+        #[cfg(not(feature = "throttle-ebpf"))]
+        {
+            let _ = pid;
+            None
+        }
     }
-}
 
-#[cfg(feature = "throttle-ebpf")]
-impl EbpfDownload {
-    /// Get statistics for a throttled process (requires mutable access)
-    /// This is a workaround for aya requiring mut access to read from maps
-    pub fn get_stats_mut(&mut self, pid: i32) -> Option<crate::backends::throttle::BackendStats> {
-        use crate::backends::throttle::BackendStats;
-
-        // Check if PID is tracked
-        if !self.pid_to_cgroup.contains_key(&pid) {
-            return None;
-        }
-
-        // CRITICAL: Use fixed key (0) to match eBPF program
-        const MAP_KEY: u64 = 0;
-
-        // Try to read stats from BPF map
-        if let Some(ref mut ebpf) = self.ebpf {
-            let stats_map: BpfHashMap<_, u64, ThrottleStats> =
-                get_bpf_map(ebpf, "CGROUP_STATS").ok()?;
-
-            if let Ok(stats) = unsafe { stats_map.get(&MAP_KEY, 0) } {
-                return Some(BackendStats {
-                    packets_total: stats.packets_total,
-                    bytes_total: stats.bytes_total,
-                    packets_dropped: stats.packets_dropped,
-                    bytes_dropped: stats.bytes_dropped,
-                });
+    fn repair_throttles(
+        &mut self,
+        flowing: &std::collections::HashSet<i32>,
+    ) -> ThrottleResult<Vec<i32>> {
+        #[cfg(feature = "throttle-ebpf")]
+        {
+            let mut repaired = self.repair_cgroup_ids()?;
+            repaired.extend(self.reattach_stale_programs(flowing)?);
+            Ok(repaired)
+        }
+
+        #[cfg(not(feature = "throttle-ebpf"))]
+        {
+            let _ = flowing;
+            Ok(Vec::new())
+        }
+    }
+
+    fn rebalance_throttles(&mut self) -> ThrottleResult<()> {
+        #[cfg(feature = "throttle-ebpf")]
+        {
+            if let Some(ref mut ebpf) = self.ebpf
+                && !self.active_throttles.is_empty()
+            {
+                self.cpu_rebalancer.rebalance(ebpf)?;
             }
+            Ok(())
+        }
+
+        #[cfg(not(feature = "throttle-ebpf"))]
+        {
+            Ok(())
+        }
+    }
+
+    fn attach_method(&self) -> Option<&'static str> {
+        #[cfg(feature = "throttle-ebpf")]
+        {
+            // With auto, the latest attachment shows what the kernel accepted
+            self.attached_programs
+                .last()
+                .map(|attached| attached.attachment.label())
+        }
+
+        #[cfg(not(feature = "throttle-ebpf"))]
+        {
+            None
+        }
+    }
+
+    fn max_throttles(&self) -> Option<usize> {
+        #[cfg(feature = "throttle-ebpf")]
+        {
+            // The loaded maps' size, or the size they will be created with
+            let capacity = self
+                .ebpf
+                .as_ref()
+                .and_then(map_capacity)
+                .unwrap_or_else(|| get_bpf_config().map_capacity());
+            Some(capacity as usize)
+        }
+
+        #[cfg(not(feature = "throttle-ebpf"))]
+        {
+            None
         }
-        None
     }
 }
@@ -16,8 +16,8 @@
 // - Implement token bucket in eBPF
 // - Use BPF maps for per-cgroup limits
 
-use crate::backends::throttle::DownloadThrottleBackend;
-use crate::backends::{BackendCapabilities, BackendPriority};
+use crate::backends::throttle::{DownloadThrottleBackend, ThrottleResult};
+use crate::backends::{BackendCapabilities, BackendPriority, TrafficTypeSupport};
 use anyhow::{anyhow, Result};
 use std::collections::HashMap;
 
@@ -56,11 +56,14 @@ impl DownloadThrottleBackend for EbpfCgroupDownload {
             ipv6_support: true,
             per_process: true,
             per_connection: false,
+            tcp_support: true,
+            udp_support: true,
+            traffic_types: TrafficTypeSupport::from_predicate(|t| self.supports_traffic_type(t)),
         }
     }
 
-    fn init(&mut self) -> Result<()> {
-        Err(anyhow!("Not implemented"))
+    fn init(&mut self) -> ThrottleResult<()> {
+        Err(anyhow!("Not implemented").into())
     }
 
     fn throttle_download(
@@ -69,12 +72,12 @@ impl DownloadThrottleBackend for EbpfCgroupDownload {
         _process_name: String,
         _limit_bytes_per_sec: u64,
         _traffic_type: crate::process::TrafficType,
-    ) -> Result<()> {
-        Err(anyhow!("Not implemented"))
+    ) -> ThrottleResult<()> {
+        Err(anyhow!("Not implemented").into())
     }
 
-    fn remove_download_throttle(&mut self, _pid: i32) -> Result<()> {
-        Err(anyhow!("Not implemented"))
+    fn remove_download_throttle(&mut self, _pid: i32) -> ThrottleResult<()> {
+        Err(anyhow!("Not implemented").into())
     }
 
     fn get_download_throttle(&self, pid: i32) -> Option<u64> {
@@ -85,7 +88,7 @@ impl DownloadThrottleBackend for EbpfCgroupDownload {
         self.active_throttles.clone()
     }
 
-    fn cleanup(&mut self) -> Result<()> {
-        Err(anyhow!("Not implemented"))
+    fn cleanup(&mut self) -> ThrottleResult<()> {
+        Err(anyhow!("Not implemented").into())
     }
 }
@@ -26,8 +26,12 @@
 
 use crate::backends::cgroup::{CgroupBackend, CgroupBackendType, CgroupHandle};
 use crate::backends::throttle::linux_tc_utils::*;
-use crate::backends::throttle::DownloadThrottleBackend;
-use crate::backends::{BackendCapabilities, BackendPriority};
+use crate::backends::throttle::{
+    AdoptionReport, BackendStats, DownloadThrottleBackend, SOFT_THROTTLE_LIMIT, ThrottleError,
+    ThrottleResult,
+};
+use crate::backends::{BackendCapabilities, BackendPriority, PolicySupport, TrafficTypeSupport};
+use crate::process::Priority;
 use anyhow::{anyhow, Context, Result};
 use std::collections::HashMap;
 use std::process::Command;
@@ -46,6 +50,7 @@ struct ThrottleInfo {
     classid: u32,
     cgroup_handle: CgroupHandle,
     limit_bytes_per_sec: u64,
+    priority: Priority,
 }
 
 impl IfbTcDownload {
@@ -351,17 +356,38 @@ impl DownloadThrottleBackend for IfbTcDownload {
         true
     }
 
+    fn unavailable_reason() -> String {
+        if !check_ifb_availability() {
+            return "IFB kernel module unavailable".to_string();
+        }
+        if !check_tc_available() {
+            return "tc command not found".to_string();
+        }
+        "requires cgroup v1 net_cls controller (use 'ebpf' on cgroup v2)".to_string()
+    }
+
     fn capabilities(&self) -> BackendCapabilities {
         BackendCapabilities {
             ipv4_support: true,
             ipv6_support: true,
             per_process: true,
             per_connection: false,
+            tcp_support: true,
+            udp_support: true,
+            traffic_types: TrafficTypeSupport::from_predicate(|t| self.supports_traffic_type(t)),
         }
     }
 
-    fn init(&mut self) -> Result<()> {
-        self.setup_ifb()
+    fn policy_support(&self) -> PolicySupport {
+        // HTB classes on the IFB device queue excess packets
+        PolicySupport::only(crate::process::Policy::Shape)
+    }
+
+    fn init(&mut self) -> ThrottleResult<()> {
+        if !check_tc_available() {
+            return Err(ThrottleError::ToolMissing("tc".to_string()));
+        }
+        Ok(self.setup_ifb()?)
     }
 
     fn throttle_download(
@@ -370,7 +396,7 @@ impl DownloadThrottleBackend for IfbTcDownload {
         process_name: String,
         limit_bytes_per_sec: u64,
         traffic_type: crate::process::TrafficType,
-    ) -> Result<()> {
+    ) -> ThrottleResult<()> {
         use crate::process::TrafficType;
 
         // IFB/TC operates at cgroup level and cannot filter by IP address
@@ -381,7 +407,8 @@ impl DownloadThrottleBackend for IfbTcDownload {
                  Traffic type '{:?}' requested but only 'All' is supported. \
                  Consider using nftables backend for traffic type filtering (if upload) or accept 'All' traffic throttling.",
                 traffic_type
-            ));
+            )
+            .into());
         }
 
         // Initialize IFB if not already done
@@ -406,8 +433,14 @@ impl DownloadThrottleBackend for IfbTcDownload {
                     // Use the classid from the cgroup handle for v1
                     // Note: For v1, create_cgroup already sets net_cls.classid
                     // We use this classid for TC class creation
-                    let rate_kbps = (limit_bytes_per_sec * 8 / 1000) as u32;
-                    create_tc_class(&self.ifb_device, handle_classid, rate_kbps, "2:")?;
+                    let rate_kbps = bytes_to_kbit(limit_bytes_per_sec);
+                    create_tc_class(
+                        &self.ifb_device,
+                        handle_classid,
+                        rate_kbps,
+                        Priority::Normal.htb_prio(),
+                        "2:",
+                    )?;
 
                     self.active_throttles.insert(
                         pid,
@@ -415,6 +448,7 @@ impl DownloadThrottleBackend for IfbTcDownload {
                             classid: handle_classid,
                             cgroup_handle,
                             limit_bytes_per_sec,
+                            priority: Priority::Normal,
                         },
                     );
                     return Ok(());
@@ -424,8 +458,14 @@ impl DownloadThrottleBackend for IfbTcDownload {
 
         // For v2 or if v1 parsing failed, use our own classid sequence
         // Create TC class on IFB device
-        let rate_kbps = (limit_bytes_per_sec * 8 / 1000) as u32;
-        create_tc_class(&self.ifb_device, classid, rate_kbps, "2:")?;
+        let rate_kbps = bytes_to_kbit(limit_bytes_per_sec);
+        create_tc_class(
+            &self.ifb_device,
+            classid,
+            rate_kbps,
+            Priority::Normal.htb_prio(),
+            "2:",
+        )?;
 
         // Track throttle
         self.active_throttles.insert(
@@ -434,13 +474,55 @@ impl DownloadThrottleBackend for IfbTcDownload {
                 classid,
                 cgroup_handle,
                 limit_bytes_per_sec,
+                priority: Priority::Normal,
             },
         );
 
         Ok(())
     }
 
-    fn remove_download_throttle(&mut self, pid: i32) -> Result<()> {
+    fn supports_interfaces(&self, interfaces: &[String]) -> bool {
+        // Only redirects ingress from a single interface
+        interfaces.len() == 1 && interfaces[0] == self.interface
+    }
+
+    fn throttle_download_on_interfaces(
+        &mut self,
+        pid: i32,
+        process_name: String,
+        limit_bytes_per_sec: u64,
+        traffic_type: crate::process::TrafficType,
+        interfaces: &[String],
+        _policy: crate::process::Policy,
+    ) -> ThrottleResult<()> {
+        if !self.supports_interfaces(interfaces) {
+            return Err(anyhow::anyhow!(
+                "{} backend only shapes {} (requested: {})",
+                self.name(),
+                self.interface,
+                interfaces.join(", ")
+            )
+            .into());
+        }
+        self.throttle_download(pid, process_name, limit_bytes_per_sec, traffic_type)
+    }
+
+    fn adopt_or_clean(&mut self, _adoptable: &HashMap<i32, u64>) -> ThrottleResult<AdoptionReport> {
+        // The IFB device is ours, but its classes don't say which process they were
+        // created for: remove them all (restored throttles get new ones)
+        let mut report = AdoptionReport::default();
+        for classid in list_htb_classids(&self.ifb_device, 2)? {
+            if self.active_throttles.values().any(|info| info.classid == classid) {
+                continue;
+            }
+            remove_tc_class(&self.ifb_device, classid, "2:")?;
+            log::info!("ifb_tc: removed stale class 2:{} on {}", classid, self.ifb_device);
+            report.removed += 1;
+        }
+        Ok(report)
+    }
+
+    fn remove_download_throttle(&mut self, pid: i32) -> ThrottleResult<()> {
         if let Some(info) = self.active_throttles.remove(&pid) {
             // Remove TC class on IFB
             let _ = remove_tc_class(&self.ifb_device, info.classid, "2:");
@@ -454,6 +536,17 @@ impl DownloadThrottleBackend for IfbTcDownload {
         Ok(())
     }
 
+    fn add_to_throttle(&mut self, pid: i32, member_pid: i32) -> ThrottleResult<()> {
+        let handle = self
+            .active_throttles
+            .get(&pid)
+            .map(|info| info.cgroup_handle.clone())
+            .ok_or(ThrottleError::NotFound)?;
+        self.get_cgroup_backend_mut()?
+            .add_process(&handle, member_pid)?;
+        Ok(())
+    }
+
     fn get_download_throttle(&self, pid: i32) -> Option<u64> {
         self.active_throttles
             .get(&pid)
@@ -467,7 +560,55 @@ impl DownloadThrottleBackend for IfbTcDownload {
             .collect()
     }
 
-    fn cleanup(&mut self) -> Result<()> {
+    fn max_throttles(&self) -> Option<usize> {
+        Some(SOFT_THROTTLE_LIMIT)
+    }
+
+    fn get_stats(&self, pid: i32) -> Option<BackendStats> {
+        let info = self.active_throttles.get(&pid)?;
+        let class = htb_class_stats(&self.ifb_device, 2, info.classid).ok()??;
+        Some(shaping_stats([class], info.limit_bytes_per_sec))
+    }
+
+    fn managed_interfaces(&self) -> Vec<String> {
+        if self.initialized {
+            vec![self.interface.clone(), self.ifb_device.clone()]
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn supports_throttle_priority(&self) -> bool {
+        true
+    }
+
+    fn set_download_throttle_priority(
+        &mut self,
+        pid: i32,
+        priority: Priority,
+    ) -> ThrottleResult<()> {
+        let info = self
+            .active_throttles
+            .get_mut(&pid)
+            .ok_or(ThrottleError::NotFound)?;
+        if info.priority == priority {
+            return Ok(());
+        }
+        info.priority = priority;
+
+        // Recreate the class with the new prio
+        let _ = remove_tc_class(&self.ifb_device, info.classid, "2:");
+        create_tc_class(
+            &self.ifb_device,
+            info.classid,
+            bytes_to_kbit(info.limit_bytes_per_sec),
+            priority.htb_prio(),
+            "2:",
+        )?;
+        Ok(())
+    }
+
+    fn cleanup(&mut self) -> ThrottleResult<()> {
         log::debug!("Cleaning up IFB throttling backend");
 
         // Remove all throttles
@@ -33,8 +33,10 @@
 
 use crate::backends::cgroup::{CgroupBackend, CgroupHandle};
 use crate::backends::throttle::linux_nft_utils::*;
-use crate::backends::throttle::DownloadThrottleBackend;
-use crate::backends::{BackendCapabilities, BackendPriority};
+use crate::backends::throttle::{
+    AdoptionReport, DownloadThrottleBackend, SOFT_THROTTLE_LIMIT, ThrottleError, ThrottleResult,
+};
+use crate::backends::{BackendCapabilities, BackendPriority, TrafficTypeSupport};
 use anyhow::{anyhow, Result};
 use std::collections::HashMap;
 
@@ -50,7 +52,8 @@ pub struct NftablesDownload {
 
 struct ThrottleInfo {
     cgroup_handle: CgroupHandle,
-    limit_bytes_per_sec: u64,
+    /// Limit object and map element in the nftables table
+    nft: NftThrottle,
 }
 
 impl NftablesDownload {
@@ -69,7 +72,7 @@ impl NftablesDownload {
             // Select best available cgroup backend
             self.cgroup_backend = crate::backends::cgroup::select_best_backend()?;
             if self.cgroup_backend.is_none() {
-                return Err(anyhow!("No cgroup backend available"));
+                return Err(ThrottleError::KernelUnsupported.into());
             }
 
             self.initialized = true;
@@ -118,17 +121,27 @@ impl DownloadThrottleBackend for NftablesDownload {
         false
     }
 
+    fn unavailable_reason() -> String {
+        "cgroup matching only works on output (no ingress support)".to_string()
+    }
+
     fn capabilities(&self) -> BackendCapabilities {
         BackendCapabilities {
             ipv4_support: true,
             ipv6_support: true,
             per_process: true,
             per_connection: false,
+            tcp_support: true,
+            udp_support: true,
+            traffic_types: TrafficTypeSupport::from_predicate(|t| self.supports_traffic_type(t)),
         }
     }
 
-    fn init(&mut self) -> Result<()> {
-        self.ensure_initialized()
+    fn init(&mut self) -> ThrottleResult<()> {
+        if !check_nft_available() {
+            return Err(ThrottleError::ToolMissing("nft".to_string()));
+        }
+        Ok(self.ensure_initialized()?)
     }
 
     fn throttle_download(
@@ -137,36 +150,55 @@ impl DownloadThrottleBackend for NftablesDownload {
         process_name: String,
         limit_bytes_per_sec: u64,
         _traffic_type: crate::process::TrafficType,
-    ) -> Result<()> {
+    ) -> ThrottleResult<()> {
         self.ensure_initialized()?;
 
         // Create cgroup for process using backend
         let backend = self.get_cgroup_backend_mut()?;
         let cgroup_handle = backend.create_cgroup(pid, &process_name)?;
 
-        // Add nftables rate limit rule using backend's filter expression
-        add_cgroup_rate_limit_with_handle(
+        // Add nftables rate limit for the cgroup
+        let nft = NftThrottle::new(
             &cgroup_handle,
             limit_bytes_per_sec,
             Direction::Download,
+            crate::process::TrafficType::All,
+            crate::process::AddressFamily::Any,
+            None,
         )?;
+        add_throttle(&nft)?;
 
         // Track throttle
-        self.active_throttles.insert(
-            pid,
-            ThrottleInfo {
-                cgroup_handle,
-                limit_bytes_per_sec,
-            },
-        );
+        self.active_throttles
+            .insert(pid, ThrottleInfo { cgroup_handle, nft });
 
         Ok(())
     }
 
-    fn remove_download_throttle(&mut self, pid: i32) -> Result<()> {
+    fn adopt_or_clean(&mut self, adoptable: &HashMap<i32, u64>) -> ThrottleResult<AdoptionReport> {
+        // Our limit objects are named after the PID they were created for
+        let cgroups = self.get_cgroup_backend()?.list_active_cgroups()?;
+        let (adopted, removed) =
+            adopt_or_clean_throttles(Direction::Download, adoptable, &cgroups)?;
+
+        let mut report = AdoptionReport {
+            adopted: Vec::new(),
+            removed,
+        };
+        for (cgroup_handle, nft) in adopted {
+            report.adopted.push(cgroup_handle.pid);
+            self.active_throttles
+                .insert(cgroup_handle.pid, ThrottleInfo { cgroup_handle, nft });
+        }
+        Ok(report)
+    }
+
+    fn remove_download_throttle(&mut self, pid: i32) -> ThrottleResult<()> {
         if let Some(info) = self.active_throttles.remove(&pid) {
-            // Remove nftables rules for this cgroup
-            let _ = remove_cgroup_rules_with_handle(&info.cgroup_handle, Direction::Download);
+            // Remove the limit object and map element for this cgroup
+            if let Err(e) = remove_throttle(&info.nft) {
+                log::warn!("{:#}", e);
+            }
 
             // Remove cgroup using backend
             if let Ok(backend) = self.get_cgroup_backend_mut() {
@@ -177,20 +209,35 @@ impl DownloadThrottleBackend for NftablesDownload {
         Ok(())
     }
 
+    fn add_to_throttle(&mut self, pid: i32, member_pid: i32) -> ThrottleResult<()> {
+        let handle = self
+            .active_throttles
+            .get(&pid)
+            .map(|info| info.cgroup_handle.clone())
+            .ok_or(ThrottleError::NotFound)?;
+        self.get_cgroup_backend_mut()?
+            .add_process(&handle, member_pid)?;
+        Ok(())
+    }
+
     fn get_download_throttle(&self, pid: i32) -> Option<u64> {
         self.active_throttles
             .get(&pid)
-            .map(|info| info.limit_bytes_per_sec)
+            .map(|info| info.nft.rate_bytes_per_sec)
     }
 
     fn get_all_throttles(&self) -> HashMap<i32, u64> {
         self.active_throttles
             .iter()
-            .map(|(&pid, info)| (pid, info.limit_bytes_per_sec))
+            .map(|(&pid, info)| (pid, info.nft.rate_bytes_per_sec))
             .collect()
     }
 
-    fn cleanup(&mut self) -> Result<()> {
+    fn max_throttles(&self) -> Option<usize> {
+        Some(SOFT_THROTTLE_LIMIT)
+    }
+
+    fn cleanup(&mut self) -> ThrottleResult<()> {
         // Remove all throttles
         let pids: Vec<i32> = self.active_throttles.keys().copied().collect();
         for pid in pids {
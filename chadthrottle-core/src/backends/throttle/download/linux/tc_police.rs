@@ -1,8 +1,10 @@
 // TC Police download throttling backend (no IFB required)
 
 use crate::backends::throttle::linux_tc_utils::*;
-use crate::backends::throttle::DownloadThrottleBackend;
-use crate::backends::{BackendCapabilities, BackendPriority};
+use crate::backends::throttle::{
+    DownloadThrottleBackend, SOFT_THROTTLE_LIMIT, ThrottleError, ThrottleResult,
+};
+use crate::backends::{BackendCapabilities, BackendPriority, TrafficTypeSupport};
 use anyhow::{anyhow, Context, Result};
 use std::collections::HashMap;
 use std::process::Command;
@@ -92,17 +94,27 @@ impl DownloadThrottleBackend for TcPoliceDownload {
         check_tc_available()
     }
 
+    fn unavailable_reason() -> String {
+        "tc command not found".to_string()
+    }
+
     fn capabilities(&self) -> BackendCapabilities {
         BackendCapabilities {
             ipv4_support: true,
             ipv6_support: false, // Police action has limited IPv6 support
             per_process: false,  // Cannot filter by process without cgroups
             per_connection: false,
+            tcp_support: true,
+            udp_support: true,
+            traffic_types: TrafficTypeSupport::from_predicate(|t| self.supports_traffic_type(t)),
         }
     }
 
-    fn init(&mut self) -> Result<()> {
-        self.setup_ingress()
+    fn init(&mut self) -> ThrottleResult<()> {
+        if !check_tc_available() {
+            return Err(ThrottleError::ToolMissing("tc".to_string()));
+        }
+        Ok(self.setup_ingress()?)
     }
 
     fn throttle_download(
@@ -111,7 +123,7 @@ impl DownloadThrottleBackend for TcPoliceDownload {
         process_name: String,
         limit_bytes_per_sec: u64,
         traffic_type: crate::process::TrafficType,
-    ) -> Result<()> {
+    ) -> ThrottleResult<()> {
         use crate::process::TrafficType;
 
         // TC Police operates at cgroup level and cannot filter by IP address
@@ -122,7 +134,8 @@ impl DownloadThrottleBackend for TcPoliceDownload {
                  Traffic type '{:?}' requested but only 'All' is supported. \
                  Consider using nftables backend for traffic type filtering (if upload) or accept 'All' traffic throttling.",
                 traffic_type
-            ));
+            )
+            .into());
         }
 
         // Initialize ingress if not already done
@@ -178,7 +191,7 @@ impl DownloadThrottleBackend for TcPoliceDownload {
             .context("Failed to add police filter")?;
 
         if !status.success() {
-            return Err(anyhow!("Failed to create TC police filter for PID {}", pid));
+            return Err(anyhow!("Failed to create TC police filter for PID {}", pid).into());
         }
 
         // Track throttle
@@ -194,7 +207,33 @@ impl DownloadThrottleBackend for TcPoliceDownload {
         Ok(())
     }
 
-    fn remove_download_throttle(&mut self, pid: i32) -> Result<()> {
+    fn supports_interfaces(&self, interfaces: &[String]) -> bool {
+        // Only polices ingress on a single interface
+        interfaces.len() == 1 && interfaces[0] == self.interface
+    }
+
+    fn throttle_download_on_interfaces(
+        &mut self,
+        pid: i32,
+        process_name: String,
+        limit_bytes_per_sec: u64,
+        traffic_type: crate::process::TrafficType,
+        interfaces: &[String],
+        _policy: crate::process::Policy,
+    ) -> ThrottleResult<()> {
+        if !self.supports_interfaces(interfaces) {
+            return Err(anyhow::anyhow!(
+                "{} backend only shapes {} (requested: {})",
+                self.name(),
+                self.interface,
+                interfaces.join(", ")
+            )
+            .into());
+        }
+        self.throttle_download(pid, process_name, limit_bytes_per_sec, traffic_type)
+    }
+
+    fn remove_download_throttle(&mut self, pid: i32) -> ThrottleResult<()> {
         if let Some(_info) = self.active_throttles.remove(&pid) {
             // Remove filter by handle
             // Note: TC police filters don't have a direct "delete by handle" command
@@ -229,7 +268,19 @@ impl DownloadThrottleBackend for TcPoliceDownload {
             .collect()
     }
 
-    fn cleanup(&mut self) -> Result<()> {
+    fn max_throttles(&self) -> Option<usize> {
+        Some(SOFT_THROTTLE_LIMIT)
+    }
+
+    fn managed_interfaces(&self) -> Vec<String> {
+        if self.initialized {
+            vec![self.interface.clone()]
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn cleanup(&mut self) -> ThrottleResult<()> {
         // Remove all throttles
         let pids: Vec<i32> = self.active_throttles.keys().copied().collect();
         for pid in pids {
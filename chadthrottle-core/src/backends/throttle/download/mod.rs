@@ -0,0 +1,16 @@
+// Download throttling backend implementations
+
+#[cfg(target_os = "linux")]
+pub mod linux;
+
+#[cfg(target_os = "macos")]
+pub mod macos;
+
+#[cfg(feature = "throttle-null")]
+pub mod null;
+
+#[cfg(feature = "throttle-proxy")]
+pub mod proxy;
+
+#[cfg(all(unix, feature = "throttle-signal"))]
+pub mod signal;
@@ -0,0 +1,122 @@
+// Null download throttling backend (throttle-null feature)
+//
+// The download half of upload/null.rs: records throttles in memory, reports
+// simulated statistics, and rate limits nothing.
+
+use crate::backends::throttle::upload::null::simulated_stats;
+use crate::backends::throttle::{BackendStats, DownloadThrottleBackend, ThrottleResult};
+use crate::backends::{BackendCapabilities, BackendPriority, PolicySupport, TrafficTypeSupport};
+use crate::process::{Policy, TrafficType};
+use anyhow::Result;
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// Download "throttling" that only remembers what it was asked to do
+#[derive(Default)]
+pub struct NullDownload {
+    active_throttles: HashMap<i32, (u64, Instant)>, // PID -> (bytes/sec, applied at)
+}
+
+impl NullDownload {
+    pub fn new() -> Result<Self> {
+        Ok(Self::default())
+    }
+}
+
+impl DownloadThrottleBackend for NullDownload {
+    fn name(&self) -> &'static str {
+        "null"
+    }
+
+    fn priority(&self) -> BackendPriority {
+        BackendPriority::Fallback
+    }
+
+    fn is_available() -> bool {
+        true // Never touches the system
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            ipv4_support: true,
+            ipv6_support: true,
+            per_process: true,
+            per_connection: false,
+            tcp_support: true,
+            udp_support: true,
+            traffic_types: TrafficTypeSupport::from_predicate(|t| self.supports_traffic_type(t)),
+        }
+    }
+
+    fn policy_support(&self) -> PolicySupport {
+        PolicySupport {
+            shape: true,
+            drop: true,
+            default: Policy::Shape,
+        }
+    }
+
+    fn init(&mut self) -> ThrottleResult<()> {
+        Ok(())
+    }
+
+    fn throttle_download(
+        &mut self,
+        pid: i32,
+        process_name: String,
+        limit_bytes_per_sec: u64,
+        traffic_type: TrafficType,
+    ) -> ThrottleResult<()> {
+        log::info!(
+            "null backend: recording download throttle for {} (PID {}) at {} bytes/sec ({:?})",
+            process_name,
+            pid,
+            limit_bytes_per_sec,
+            traffic_type
+        );
+        self.active_throttles
+            .insert(pid, (limit_bytes_per_sec, Instant::now()));
+        Ok(())
+    }
+
+    fn throttle_download_with_policy(
+        &mut self,
+        pid: i32,
+        process_name: String,
+        limit_bytes_per_sec: u64,
+        traffic_type: TrafficType,
+        _policy: Policy,
+    ) -> ThrottleResult<()> {
+        self.throttle_download(pid, process_name, limit_bytes_per_sec, traffic_type)
+    }
+
+    fn remove_download_throttle(&mut self, pid: i32) -> ThrottleResult<()> {
+        self.active_throttles.remove(&pid);
+        Ok(())
+    }
+
+    fn get_download_throttle(&self, pid: i32) -> Option<u64> {
+        self.active_throttles.get(&pid).map(|(limit, _)| *limit)
+    }
+
+    fn get_all_throttles(&self) -> HashMap<i32, u64> {
+        self.active_throttles
+            .iter()
+            .map(|(pid, (limit, _))| (*pid, *limit))
+            .collect()
+    }
+
+    fn cleanup(&mut self) -> ThrottleResult<()> {
+        self.active_throttles.clear();
+        Ok(())
+    }
+
+    fn get_stats(&self, pid: i32) -> Option<BackendStats> {
+        let (limit, applied_at) = self.active_throttles.get(&pid)?;
+        Some(simulated_stats(*limit, applied_at.elapsed()))
+    }
+
+    fn supports_traffic_type(&self, _traffic_type: TrafficType) -> bool {
+        true
+    }
+}
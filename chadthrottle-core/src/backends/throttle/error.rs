@@ -0,0 +1,151 @@
+// Errors returned by the throttle backend traits
+//
+// Backends are built on anyhow internally; the variants here are the failures a
+// caller can do something about (run as root, install a tool, pick another backend).
+// Everything else stays an `Other` with its original message and context.
+
+use std::fmt;
+use std::io;
+
+/// Result type of the throttle backend traits
+pub type ThrottleResult<T> = std::result::Result<T, ThrottleError>;
+
+#[derive(Debug)]
+pub enum ThrottleError {
+    /// The backend needs privileges we don't have (root / CAP_NET_ADMIN / CAP_BPF)
+    PermissionDenied,
+    /// The running kernel lacks a module or feature the backend needs
+    KernelUnsupported,
+    /// A command-line tool the backend drives isn't installed
+    ToolMissing(String),
+    /// The process already has a throttle on this backend
+    AlreadyThrottled,
+    /// No throttle (or process) with that PID
+    NotFound,
+    /// The backend holds as many throttles as it can (its maps or tables are full)
+    AtCapacity {
+        backend: String,
+        active: usize,
+        max: usize,
+    },
+    Other(anyhow::Error),
+}
+
+impl ThrottleError {
+    /// What the user can do about the error, if anything specific
+    pub fn guidance(&self) -> Option<&'static str> {
+        match self {
+            ThrottleError::PermissionDenied => Some("run chadthrottle as root (e.g. with sudo)"),
+            ThrottleError::KernelUnsupported => Some("try a different backend"),
+            ThrottleError::ToolMissing(_) => Some("install it or try a different backend"),
+            ThrottleError::AlreadyThrottled => Some("remove the existing throttle first"),
+            ThrottleError::AtCapacity { .. } => Some("remove one or switch backends"),
+            ThrottleError::NotFound | ThrottleError::Other(_) => None,
+        }
+    }
+
+    /// Describe an error for the user, adding guidance when a backend error is behind it
+    pub fn describe(error: &anyhow::Error) -> String {
+        match error
+            .downcast_ref::<ThrottleError>()
+            .and_then(ThrottleError::guidance)
+        {
+            Some(guidance) => format!("{} - {}", error, guidance),
+            None => error.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ThrottleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThrottleError::PermissionDenied => write!(f, "Permission denied"),
+            ThrottleError::KernelUnsupported => write!(f, "Not supported by the running kernel"),
+            ThrottleError::ToolMissing(tool) => write!(f, "'{}' not found", tool),
+            ThrottleError::AlreadyThrottled => write!(f, "Process is already throttled"),
+            ThrottleError::NotFound => write!(f, "No such throttle"),
+            ThrottleError::AtCapacity {
+                backend,
+                active,
+                max,
+            } => write!(f, "backend {} at capacity ({}/{})", backend, active, max),
+            ThrottleError::Other(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for ThrottleError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            // Display already shows the error itself, so its chain continues below it
+            ThrottleError::Other(error) => error.source(),
+            _ => None,
+        }
+    }
+}
+
+impl From<anyhow::Error> for ThrottleError {
+    fn from(error: anyhow::Error) -> Self {
+        // A ThrottleError that passed through anyhow (e.g. a helper's) keeps its variant
+        let error = match error.downcast::<ThrottleError>() {
+            Ok(error) => return error,
+            Err(error) => error,
+        };
+
+        // An OS error anywhere in the chain (EPERM/EACCES, ENOSYS/EOPNOTSUPP) decides it
+        let io_kind = error
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<io::Error>())
+            .map(io::Error::kind);
+        match io_kind {
+            Some(io::ErrorKind::PermissionDenied) => {
+                log::debug!("Backend permission error: {:#}", error);
+                ThrottleError::PermissionDenied
+            }
+            Some(io::ErrorKind::Unsupported) => {
+                log::debug!("Backend unsupported by kernel: {:#}", error);
+                ThrottleError::KernelUnsupported
+            }
+            _ => ThrottleError::Other(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_anyhow_errors_are_classified() {
+        let denied: anyhow::Result<()> = Err(io::Error::from(io::ErrorKind::PermissionDenied))
+            .context("Failed to create cgroup");
+        assert!(matches!(
+            ThrottleError::from(denied.unwrap_err()),
+            ThrottleError::PermissionDenied
+        ));
+
+        let unsupported: anyhow::Result<()> =
+            Err(io::Error::from(io::ErrorKind::Unsupported)).context("bpf() failed");
+        assert!(matches!(
+            ThrottleError::from(unsupported.unwrap_err()),
+            ThrottleError::KernelUnsupported
+        ));
+
+        let wrapped = anyhow::Error::from(ThrottleError::ToolMissing("tc".to_string()));
+        assert!(matches!(
+            ThrottleError::from(wrapped),
+            ThrottleError::ToolMissing(tool) if tool == "tc"
+        ));
+
+        let other = ThrottleError::from(anyhow::anyhow!("tc exited with status 2"));
+        assert_eq!(other.to_string(), "tc exited with status 2");
+
+        // Through the manager (anyhow again), the guidance is still found
+        let error = anyhow::Error::from(ThrottleError::PermissionDenied);
+        assert_eq!(
+            ThrottleError::describe(&error),
+            "Permission denied - run chadthrottle as root (e.g. with sudo)"
+        );
+    }
+}
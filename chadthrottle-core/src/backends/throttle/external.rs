@@ -0,0 +1,327 @@
+// Detection of traffic shaping set up outside chadthrottle (by hand, wondershaper,
+// trickle's helpers, a router script, ...)
+//
+// Foreign qdiscs on an interface chadthrottle shapes can make its throttles
+// ineffective or limit the same traffic twice. Detection only reads (`tc qdisc
+// show`, `tc class show`, `nft list ruleset`) and never touches foreign rules.
+
+use std::fmt;
+
+/// Qdiscs that don't limit bandwidth: the kernel's defaults and the ingress/clsact
+/// hooks (which only shape through filters)
+const NEUTRAL_QDISCS: [&str; 11] = [
+    "noqueue",
+    "noop",
+    "pfifo_fast",
+    "pfifo",
+    "bfifo",
+    "fq_codel",
+    "fq",
+    "mq",
+    "mqprio",
+    "ingress",
+    "clsact",
+];
+
+/// Qdiscs whose classes are worth counting (each class is usually one limit)
+const CLASSFUL_QDISCS: [&str; 6] = ["htb", "hfsc", "cbq", "drr", "qfq", "ets"];
+
+/// Handles of the HTB roots created by tc_htb (1:) and ifb_tc (2:)
+const OWN_HTB_HANDLES: [&str; 2] = ["1:", "2:"];
+
+/// Shaping found outside chadthrottle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalShaping {
+    /// Interface the qdisc is on (None for nftables, which isn't tied to one)
+    pub interface: Option<String>,
+    /// What was found, e.g. "cake qdisc" or "table inet filter with 2 rate limits"
+    pub description: String,
+}
+
+impl fmt::Display for ExternalShaping {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.interface {
+            Some(interface) => write!(
+                f,
+                "External shaping detected on {}: {} (not managed by chadthrottle)",
+                interface, self.description
+            ),
+            None => write!(
+                f,
+                "External shaping detected in nftables: {} (not managed by chadthrottle)",
+                self.description
+            ),
+        }
+    }
+}
+
+/// A qdisc from `tc qdisc show` that chadthrottle didn't create
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ForeignQdisc {
+    interface: String,
+    kind: String,
+    handle: String,
+}
+
+/// Parse `tc qdisc show` and keep the qdiscs on `interfaces` that neither the kernel
+/// nor chadthrottle set up. Leaf qdiscs under a foreign qdisc's classes belong to
+/// the same setup and aren't listed separately:
+///
+/// ```text
+/// qdisc htb 1: dev eth0 root refcnt 2 r2q 10 default 0x3e7 direct_packets_stat 0
+/// qdisc cake 8001: dev wlan0 root refcnt 2 bandwidth 20Mbit diffserv3 triple-isolate
+/// qdisc fq_codel 0: dev wlan0 parent :1 limit 10240p flows 1024 quantum 1514
+/// ```
+fn parse_foreign_qdiscs(listing: &str, interfaces: &[String]) -> Vec<ForeignQdisc> {
+    let mut parents = Vec::new();
+    let foreign: Vec<ForeignQdisc> = listing
+        .lines()
+        .filter_map(|line| {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let ["qdisc", kind, handle, "dev", interface, ..] = words.as_slice() else {
+                return None;
+            };
+            if !interfaces.iter().any(|name| name == interface) {
+                return None;
+            }
+            // Handle 0: is the kernel's own default qdisc
+            if *handle == "0:" || NEUTRAL_QDISCS.contains(kind) {
+                return None;
+            }
+            // chadthrottle's HTB roots send unclassified traffic to class 999
+            let own_htb = *kind == "htb"
+                && OWN_HTB_HANDLES.contains(handle)
+                && words
+                    .windows(2)
+                    .any(|pair| pair[0] == "default" && matches!(pair[1], "0x3e7" | "3e7"));
+            if own_htb {
+                return None;
+            }
+            let parent = words
+                .windows(2)
+                .find(|pair| pair[0] == "parent")
+                .and_then(|pair| pair[1].split_once(':'))
+                .map(|(major, _)| format!("{}:", major));
+            parents.push(parent);
+            Some(ForeignQdisc {
+                interface: interface.to_string(),
+                kind: kind.to_string(),
+                handle: handle.to_string(),
+            })
+        })
+        .collect();
+
+    foreign
+        .iter()
+        .zip(&parents)
+        .filter(|(qdisc, parent)| {
+            !parent.as_ref().is_some_and(|parent| {
+                foreign
+                    .iter()
+                    .any(|other| other.interface == qdisc.interface && &other.handle == parent)
+            })
+        })
+        .map(|(qdisc, _)| qdisc.clone())
+        .collect()
+}
+
+/// Count the classes under qdisc `handle` in `tc class show dev <interface>`
+/// ("class htb 1:10 parent 1:1 rate 2Mbit ceil 2Mbit burst 1600b cburst 1600b")
+fn count_classes(listing: &str, handle: &str) -> usize {
+    let major = handle.trim_end_matches(':');
+    listing
+        .lines()
+        .filter(|line| {
+            let mut words = line.split_whitespace().skip(2);
+            line.starts_with("class ")
+                && words
+                    .next()
+                    .and_then(|classid| classid.split_once(':'))
+                    .is_some_and(|(class_major, _)| class_major == major)
+        })
+        .count()
+}
+
+/// Parse `nft list ruleset` and report the tables of other tools that rate limit
+fn parse_nft_rate_limits(listing: &str) -> Vec<ExternalShaping> {
+    let mut found: Vec<(String, usize)> = Vec::new();
+    for line in listing.lines() {
+        let line = line.trim();
+        if let Some(table) = line
+            .strip_prefix("table ")
+            .and_then(|rest| rest.strip_suffix('{'))
+        {
+            found.push((table.trim().to_string(), 0));
+        } else if line.contains("limit rate")
+            && let Some((_, count)) = found.last_mut()
+        {
+            *count += 1;
+        }
+    }
+
+    found
+        .into_iter()
+        .filter(|(table, count)| {
+            *count > 0
+                && !table
+                    .rsplit(' ')
+                    .next()
+                    .is_some_and(|name| name.starts_with("chadthrottle"))
+        })
+        .map(|(table, count)| ExternalShaping {
+            interface: None,
+            description: format!(
+                "table {} with {} rate limit{}",
+                table,
+                count,
+                if count == 1 { "" } else { "s" }
+            ),
+        })
+        .collect()
+}
+
+/// Describe a foreign qdisc, with its class count for classful ones
+fn describe(qdisc: &ForeignQdisc, classes: usize) -> ExternalShaping {
+    let mut description = format!("{} qdisc {}", qdisc.kind, qdisc.handle);
+    if classes > 0 {
+        description += &format!(
+            " with {} class{}",
+            classes,
+            if classes == 1 { "" } else { "es" }
+        );
+    }
+    ExternalShaping {
+        interface: Some(qdisc.interface.clone()),
+        description,
+    }
+}
+
+/// Look for shaping chadthrottle didn't set up on `interfaces` (tc) and in nftables
+pub fn detect_external_shaping(interfaces: &[String]) -> Vec<ExternalShaping> {
+    #[cfg(target_os = "linux")]
+    {
+        use std::process::Command;
+
+        let read = |program: &str, args: &[&str]| -> Option<String> {
+            let output = Command::new(program).args(args).output().ok()?;
+            output
+                .status
+                .success()
+                .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+        };
+
+        let mut found = Vec::new();
+        if let Some(listing) = read("tc", &["qdisc", "show"]) {
+            for qdisc in parse_foreign_qdiscs(&listing, interfaces) {
+                let classes = if CLASSFUL_QDISCS.contains(&qdisc.kind.as_str()) {
+                    read("tc", &["class", "show", "dev", &qdisc.interface])
+                        .map(|listing| count_classes(&listing, &qdisc.handle))
+                        .unwrap_or(0)
+                } else {
+                    0
+                };
+                found.push(describe(&qdisc, classes));
+            }
+        }
+        if let Some(listing) = read("nft", &["list", "ruleset"]) {
+            found.extend(parse_nft_rate_limits(&listing));
+        }
+        found
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = interfaces;
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QDISCS: &str = "\
+qdisc noqueue 0: dev lo root refcnt 2
+qdisc htb 1: dev eth0 root refcnt 2 r2q 10 default 0x3e7 direct_packets_stat 0 direct_qlen 1000
+qdisc ingress ffff: dev eth0 parent ffff:fff1 ----------------
+qdisc mq 0: dev wlan0 root
+qdisc fq_codel 0: dev wlan0 parent :1 limit 10240p flows 1024 quantum 1514 target 5ms interval 100ms memory_limit 32Mb ecn drop_batch 64
+qdisc cake 8001: dev wlan0 root refcnt 2 bandwidth 20Mbit diffserv3 triple-isolate nonat nowash no-ack-filter split-gso rtt 100ms raw overhead 0
+qdisc htb 1: dev eth1 root refcnt 2 r2q 10 default 0x20 direct_packets_stat 0 direct_qlen 1000
+qdisc sfq 10: dev eth1 parent 1:10 limit 127p quantum 1514b depth 127 divisor 1024 perturb 10sec
+qdisc htb 2: dev ifb0 root refcnt 2 r2q 10 default 0x3e7 direct_packets_stat 0 direct_qlen 32
+qdisc tbf 8002: dev docker0 root refcnt 2 rate 1Mbit burst 32Kb lat 50ms
+";
+
+    fn interfaces(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_foreign_qdiscs() {
+        let found = parse_foreign_qdiscs(QDISCS, &interfaces(&["eth0", "eth1", "wlan0", "ifb0"]));
+        let summary: Vec<(&str, &str, &str)> = found
+            .iter()
+            .map(|q| (q.interface.as_str(), q.kind.as_str(), q.handle.as_str()))
+            .collect();
+        // Our HTB roots, the ingress hook and kernel defaults are left out, the sfq
+        // leaf belongs to the foreign HTB, and docker0 isn't one of the interfaces
+        // asked about
+        assert_eq!(
+            summary,
+            vec![("wlan0", "cake", "8001:"), ("eth1", "htb", "1:")]
+        );
+    }
+
+    #[test]
+    fn test_count_classes_and_describe() {
+        let classes = "\
+class htb 1:1 root rate 10Mbit ceil 10Mbit burst 1600b cburst 1600b
+class htb 1:10 parent 1:1 prio 0 rate 2Mbit ceil 10Mbit burst 1600b cburst 1600b
+class htb 1:20 parent 1:1 prio 0 rate 8Mbit ceil 10Mbit burst 1600b cburst 1600b
+class sfq 10:7b parent 10:
+";
+        assert_eq!(count_classes(classes, "1:"), 3);
+
+        let qdisc = ForeignQdisc {
+            interface: "eth1".to_string(),
+            kind: "htb".to_string(),
+            handle: "1:".to_string(),
+        };
+        assert_eq!(
+            describe(&qdisc, 3).to_string(),
+            "External shaping detected on eth1: htb qdisc 1: with 3 classes (not managed by chadthrottle)"
+        );
+    }
+
+    #[test]
+    fn test_parse_nft_rate_limits() {
+        let ruleset = "\
+table inet filter {
+\tchain input {
+\t\ttype filter hook input priority filter; policy accept;
+\t\tct state established accept
+\t}
+}
+table inet shaper {
+\tchain output {
+\t\ttype filter hook output priority filter; policy accept;
+\t\tip daddr 10.0.0.5 limit rate over 1 mbytes/second drop
+\t\tip daddr 10.0.0.6 limit rate over 512 kbytes/second drop
+\t}
+}
+table inet chadthrottle {
+\tchain output_limit {
+\t\tmeta cgroup 1234 limit rate over 100 kbytes/second drop
+\t}
+}
+";
+        assert_eq!(
+            parse_nft_rate_limits(ruleset),
+            vec![ExternalShaping {
+                interface: None,
+                description: "table inet shaper with 2 rate limits".to_string(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,1763 @@
+// eBPF utility functions for loading and managing eBPF programs
+#[cfg(feature = "throttle-ebpf")]
+use anyhow::{Context, Result};
+#[cfg(feature = "throttle-ebpf")]
+use std::fs;
+#[cfg(feature = "throttle-ebpf")]
+use std::os::unix::io::AsRawFd;
+#[cfg(feature = "throttle-ebpf")]
+use std::path::{Path, PathBuf};
+#[cfg(feature = "throttle-ebpf")]
+use std::sync::OnceLock;
+#[cfg(feature = "throttle-ebpf")]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "throttle-ebpf")]
+use aya::{
+    Ebpf, EbpfLoader,
+    maps::{
+        Array as BpfArray, HashMap as BpfHashMap, Map, MapData, MapInfo,
+        PerCpuHashMap as BpfPerCpuHashMap, PerCpuValues,
+    },
+    programs::{CgroupAttachMode, CgroupSkb, CgroupSkbAttachType, cgroup_skb::CgroupSkbLinkId},
+};
+#[cfg(feature = "throttle-ebpf")]
+use chadthrottle_common::{
+    CgroupThrottleConfig, MIN_BURST_SIZE, PIN_BOOT_ID_LEN, PIN_VERSION_LEN, PinMetadata,
+    ThrottleStats, TokenBucket,
+};
+
+/// Global BPF configuration
+#[cfg(feature = "throttle-ebpf")]
+static BPF_CONFIG: OnceLock<BpfConfig> = OnceLock::new();
+
+#[cfg(feature = "throttle-ebpf")]
+#[derive(Debug, Clone)]
+pub struct BpfConfig {
+    pub attach_method: BpfAttachMethod,
+    /// Entries in each per-cgroup map (None = `DEFAULT_MAP_CAPACITY`)
+    pub map_capacity: Option<u32>,
+}
+
+#[cfg(feature = "throttle-ebpf")]
+impl BpfConfig {
+    pub fn new(attach_method: BpfAttachMethod) -> Self {
+        Self {
+            attach_method,
+            map_capacity: None,
+        }
+    }
+
+    pub fn with_map_capacity(mut self, map_capacity: Option<u32>) -> Self {
+        self.map_capacity = map_capacity;
+        self
+    }
+
+    /// Entries the per-cgroup maps are created with
+    pub fn map_capacity(&self) -> u32 {
+        self.map_capacity.unwrap_or(DEFAULT_MAP_CAPACITY)
+    }
+}
+
+/// Entries the eBPF programs declare for their per-cgroup maps (MAX_CGROUPS)
+#[cfg(feature = "throttle-ebpf")]
+pub const DEFAULT_MAP_CAPACITY: u32 = 4096;
+
+/// Per-cgroup maps resized to `BpfConfig::map_capacity` at load time
+#[cfg(feature = "throttle-ebpf")]
+const SIZED_MAPS: [&str; 3] = ["CGROUP_BUCKETS", "CGROUP_CONFIGS", "CGROUP_STATS"];
+
+/// Initialize global BPF configuration (call once at startup)
+#[cfg(feature = "throttle-ebpf")]
+pub fn init_bpf_config(config: BpfConfig) {
+    BPF_CONFIG.get_or_init(|| config);
+}
+
+/// Get global BPF configuration
+#[cfg(feature = "throttle-ebpf")]
+pub fn get_bpf_config() -> BpfConfig {
+    BPF_CONFIG.get().cloned().unwrap_or_else(|| {
+        // Default config if not initialized
+        BpfConfig::new(BpfAttachMethod::from_env_and_arg(None))
+    })
+}
+
+/// Get the cgroup ID for a given PID
+#[cfg(feature = "throttle-ebpf")]
+pub fn get_cgroup_id(pid: i32) -> Result<u64> {
+    // DIAGNOSTIC: If CHADTHROTTLE_TEST_ROOT_CGROUP is set, always return root cgroup ID
+    if std::env::var("CHADTHROTTLE_TEST_ROOT_CGROUP").is_ok() {
+        log::warn!("🧪 TEST MODE: Using root cgroup ID (1) for all processes");
+        return Ok(1);
+    }
+
+    let cgroup_path = format!("/proc/{}/cgroup", pid);
+    let contents = fs::read_to_string(&cgroup_path)
+        .with_context(|| format!("Failed to read {}", cgroup_path))?;
+
+    // Parse cgroup v2 format: "0::/system.slice/some-service.service"
+    // We need to get the inode number of the cgroup directory
+    let cgroup_line = contents
+        .lines()
+        .find(|line| line.starts_with("0::"))
+        .ok_or_else(|| anyhow::anyhow!("No cgroup v2 entry found for PID {}", pid))?;
+
+    let cgroup_rel_path = cgroup_line
+        .strip_prefix("0::")
+        .ok_or_else(|| anyhow::anyhow!("Invalid cgroup v2 format"))?;
+
+    // Get the inode of the cgroup directory
+    let cgroup_full_path = if cgroup_rel_path.is_empty() {
+        PathBuf::from("/sys/fs/cgroup")
+    } else {
+        PathBuf::from("/sys/fs/cgroup").join(cgroup_rel_path.trim_start_matches('/'))
+    };
+
+    let metadata = fs::metadata(&cgroup_full_path)
+        .with_context(|| format!("Failed to get metadata for {:?}", cgroup_full_path))?;
+
+    // Get inode number using std::os::unix::fs::MetadataExt
+    use std::os::unix::fs::MetadataExt;
+    Ok(metadata.ino())
+}
+
+/// Find the cgroup path to attach to for a given PID
+///
+/// Returns the LEAF cgroup (the process's actual cgroup).
+/// We use AllowMultiple mode to avoid conflicts with other BPF programs.
+#[cfg(feature = "throttle-ebpf")]
+pub fn get_cgroup_path(pid: i32) -> Result<PathBuf> {
+    // DIAGNOSTIC: If CHADTHROTTLE_TEST_ROOT_CGROUP is set, use root cgroup for testing
+    if std::env::var("CHADTHROTTLE_TEST_ROOT_CGROUP").is_ok() {
+        let root_cgroup = PathBuf::from("/sys/fs/cgroup");
+        log::warn!(
+            "🧪 TEST MODE: Using root cgroup {:?} instead of process cgroup",
+            root_cgroup
+        );
+        return Ok(root_cgroup);
+    }
+
+    let cgroup_path = format!("/proc/{}/cgroup", pid);
+    let contents = fs::read_to_string(&cgroup_path)
+        .with_context(|| format!("Failed to read {}", cgroup_path))?;
+
+    // Parse cgroup v2 format: "0::/user.slice/user-1000.slice/user@1000.service/tmux-spawn-XXX.scope"
+    let cgroup_line = contents
+        .lines()
+        .find(|line| line.starts_with("0::"))
+        .ok_or_else(|| anyhow::anyhow!("No cgroup v2 entry found for PID {}", pid))?;
+
+    let cgroup_rel_path = cgroup_line
+        .strip_prefix("0::")
+        .ok_or_else(|| anyhow::anyhow!("Invalid cgroup v2 format"))?;
+
+    let cgroup_full_path = if cgroup_rel_path.is_empty() {
+        PathBuf::from("/sys/fs/cgroup")
+    } else {
+        PathBuf::from("/sys/fs/cgroup").join(cgroup_rel_path.trim_start_matches('/'))
+    };
+
+    Ok(cgroup_full_path)
+}
+
+/// Check if the system supports eBPF and cgroup v2
+#[cfg(feature = "throttle-ebpf")]
+pub fn check_ebpf_support() -> bool {
+    // Check if cgroup v2 is mounted
+    if !Path::new("/sys/fs/cgroup/cgroup.controllers").exists() {
+        log::debug!("cgroup v2 not mounted");
+        return false;
+    }
+
+    // Check kernel version (need 4.10+ for cgroup SKB programs)
+    if let Ok(contents) = fs::read_to_string("/proc/version") {
+        if let Some(version_str) = contents.split_whitespace().nth(2) {
+            if let Some(major_minor) = version_str.split('.').take(2).collect::<Vec<_>>().get(0..2)
+            {
+                if let (Ok(major), Ok(minor)) =
+                    (major_minor[0].parse::<u32>(), major_minor[1].parse::<u32>())
+                {
+                    if major < 4 || (major == 4 && minor < 10) {
+                        log::debug!(
+                            "Kernel version {}.{} is too old for eBPF cgroup SKB (need 4.10+)",
+                            major,
+                            minor
+                        );
+                        return false;
+                    }
+                }
+            }
+        }
+    }
+
+    true
+}
+
+/// Load eBPF program from embedded bytes
+#[cfg(feature = "throttle-ebpf")]
+pub fn load_ebpf_program(program_bytes: &[u8]) -> Result<Ebpf> {
+    sized_loader()
+        .load(program_bytes)
+        .inspect_err(|e| log::error!("Failed to load eBPF program: {e}"))
+        .context("Failed to load eBPF program")
+}
+
+/// A loader that creates the per-cgroup maps with the configured capacity
+#[cfg(feature = "throttle-ebpf")]
+fn sized_loader() -> EbpfLoader<'static> {
+    let capacity = get_bpf_config().map_capacity();
+    let mut loader = EbpfLoader::new();
+    for name in SIZED_MAPS {
+        loader.set_max_entries(name, capacity);
+    }
+    loader
+}
+
+/// Capacity of a loaded program's per-cgroup maps
+#[cfg(feature = "throttle-ebpf")]
+pub fn map_capacity(ebpf: &Ebpf) -> Option<u32> {
+    match ebpf.map("CGROUP_CONFIGS")? {
+        Map::HashMap(data) => data.info().ok().map(|info| info.max_entries()),
+        _ => None,
+    }
+}
+
+/// Root directory for pinned eBPF objects (one subdirectory per program)
+///
+/// Maps are pinned so a restarted ChadThrottle adopts them (token buckets keep
+/// their state), and the program is pinned so the next run can find and detach
+/// legacy attachments a crashed run left behind. `--cleanup` removes everything.
+#[cfg(feature = "throttle-ebpf")]
+pub const BPF_PIN_ROOT: &str = "/sys/fs/bpf/chadthrottle";
+
+/// Programs that get pinned, with the attach type they are attached with
+#[cfg(feature = "throttle-ebpf")]
+const PINNED_PROGRAMS: [(&str, CgroupSkbAttachType); 2] = [
+    ("chadthrottle_egress", CgroupSkbAttachType::Egress),
+    ("chadthrottle_ingress", CgroupSkbAttachType::Ingress),
+];
+
+/// File name of the pinned program inside its pin directory
+#[cfg(feature = "throttle-ebpf")]
+const PROGRAM_PIN_NAME: &str = "program";
+
+/// Map recording which version and boot the pinned maps belong to
+#[cfg(feature = "throttle-ebpf")]
+const PIN_METADATA_MAP: &str = "PIN_METADATA";
+
+#[cfg(feature = "throttle-ebpf")]
+fn pin_dir(program_name: &str) -> PathBuf {
+    Path::new(BPF_PIN_ROOT).join(program_name)
+}
+
+/// Check whether the BPF filesystem is mounted at /sys/fs/bpf
+#[cfg(feature = "throttle-ebpf")]
+fn bpffs_mounted() -> bool {
+    fs::read_to_string("/proc/mounts")
+        .map(|mounts| {
+            mounts.lines().any(|line| {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                fields.get(1) == Some(&"/sys/fs/bpf") && fields.get(2) == Some(&"bpf")
+            })
+        })
+        .unwrap_or(false)
+}
+
+/// Metadata identifying pins created by this binary in this boot
+#[cfg(feature = "throttle-ebpf")]
+fn current_pin_metadata() -> PinMetadata {
+    let mut metadata = PinMetadata {
+        boot_id: [0; PIN_BOOT_ID_LEN],
+        version: [0; PIN_VERSION_LEN],
+    };
+
+    let boot_id = fs::read_to_string("/proc/sys/kernel/random/boot_id").unwrap_or_default();
+    let boot_id = boot_id.trim().as_bytes();
+    let len = boot_id.len().min(PIN_BOOT_ID_LEN);
+    metadata.boot_id[..len].copy_from_slice(&boot_id[..len]);
+
+    let version = env!("CARGO_PKG_VERSION").as_bytes();
+    let len = version.len().min(PIN_VERSION_LEN);
+    metadata.version[..len].copy_from_slice(&version[..len]);
+
+    metadata
+}
+
+/// Read the metadata of an existing pin directory (None if missing or unreadable)
+#[cfg(feature = "throttle-ebpf")]
+fn read_pin_metadata(dir: &Path) -> Option<PinMetadata> {
+    let map_data = MapData::from_pin(dir.join(PIN_METADATA_MAP)).ok()?;
+    let map: BpfArray<MapData, PinMetadata> = BpfArray::try_from(Map::Array(map_data)).ok()?;
+    map.get(&0, 0).ok()
+}
+
+/// Load an eBPF object with its maps pinned under `BPF_PIN_ROOT/<program_name>`
+///
+/// Maps pinned by a previous run of the same version in the same boot are adopted;
+/// anything else is discarded. A program pinned by a previous run is detached from
+/// every cgroup it is still attached to, so restarts never stack a new program on
+/// top of a leftover one. Falls back to an unpinned load if pinning isn't possible.
+#[cfg(feature = "throttle-ebpf")]
+pub fn load_pinned_ebpf_program(program_bytes: &[u8], program_name: &str) -> Result<Ebpf> {
+    if !bpffs_mounted() {
+        log::warn!("BPF filesystem not mounted at /sys/fs/bpf - eBPF maps won't be pinned");
+        return load_ebpf_program(program_bytes);
+    }
+
+    match try_load_pinned(program_bytes, program_name) {
+        Ok(ebpf) => Ok(ebpf),
+        Err(e) => {
+            log::warn!(
+                "Failed to pin eBPF maps for {}: {:#} - loading unpinned",
+                program_name,
+                e
+            );
+            load_ebpf_program(program_bytes)
+        }
+    }
+}
+
+#[cfg(feature = "throttle-ebpf")]
+fn try_load_pinned(program_bytes: &[u8], program_name: &str) -> Result<Ebpf> {
+    let dir = pin_dir(program_name);
+
+    if let Some((_, attach_type)) = PINNED_PROGRAMS
+        .iter()
+        .find(|(name, _)| *name == program_name)
+    {
+        detach_stale_attachments(&dir, *attach_type)?;
+    }
+
+    if dir.exists() {
+        let current = current_pin_metadata();
+        // Pinned maps keep their size, so a changed capacity needs new ones
+        let capacity = get_bpf_config().map_capacity();
+        let adoptable = read_pin_metadata(&dir).is_some_and(|pinned| {
+            pinned.boot_id == current.boot_id && pinned.version == current.version
+        }) && MapInfo::from_pin(dir.join("CGROUP_CONFIGS"))
+            .is_ok_and(|info| info.max_entries() == capacity);
+
+        if adoptable {
+            log::info!("Adopting pinned eBPF maps from {:?}", dir);
+        } else {
+            log::info!(
+                "Discarding pinned eBPF maps from another version, boot or map size: {:?}",
+                dir
+            );
+            fs::remove_dir_all(&dir)
+                .with_context(|| format!("Failed to remove stale pins in {:?}", dir))?;
+        }
+    }
+
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {:?}", dir))?;
+
+    let mut ebpf = sized_loader()
+        .map_pin_path(&dir)
+        .load(program_bytes)
+        .inspect_err(|e| log::error!("Failed to load eBPF program: {e}"))
+        .context("Failed to load eBPF program")?;
+
+    match ebpf.map_mut(PIN_METADATA_MAP) {
+        Some(map) => {
+            let mut metadata: BpfArray<_, PinMetadata> = BpfArray::try_from(map)?;
+            metadata.set(0, current_pin_metadata(), 0)?;
+        }
+        // Programs built before pinning support have no metadata map
+        None => log::warn!(
+            "eBPF object has no {} map - rebuild the eBPF programs",
+            PIN_METADATA_MAP
+        ),
+    }
+
+    Ok(ebpf)
+}
+
+/// Pin a program that has been loaded into the kernel, so the next run can
+/// find it (see `load_pinned_ebpf_program`). Failures are logged, not fatal.
+#[cfg(feature = "throttle-ebpf")]
+pub fn pin_program(ebpf: &mut Ebpf, program_name: &str) {
+    let dir = pin_dir(program_name);
+    if !dir.is_dir() {
+        return; // Loaded unpinned
+    }
+
+    let Some(program) = ebpf.program_mut(program_name) else {
+        return;
+    };
+    let Ok(program) = <&mut CgroupSkb>::try_from(program) else {
+        return;
+    };
+
+    let pin_path = dir.join(PROGRAM_PIN_NAME);
+    let _ = fs::remove_file(&pin_path);
+    match program.pin(&pin_path) {
+        Ok(()) => log::debug!("Pinned {} at {:?}", program_name, pin_path),
+        Err(e) => log::warn!("Failed to pin {} at {:?}: {}", program_name, pin_path, e),
+    }
+}
+
+/// Detach a previously pinned program from every cgroup it is still attached to
+/// (legacy bpf_prog_attach attachments outlive the process that made them) and
+/// remove its pin. Returns the number of attachments removed.
+#[cfg(feature = "throttle-ebpf")]
+fn detach_stale_attachments(dir: &Path, attach_type: CgroupSkbAttachType) -> Result<usize> {
+    use std::os::fd::AsFd;
+
+    let pin_path = dir.join(PROGRAM_PIN_NAME);
+    if !pin_path.exists() {
+        return Ok(0);
+    }
+
+    let stale = CgroupSkb::from_pin(&pin_path, attach_type)
+        .with_context(|| format!("Failed to open pinned program {:?}", pin_path))?;
+    let stale_id = stale.info().context("Failed to query pinned program")?.id();
+    let stale_fd = stale
+        .fd()
+        .context("Pinned program has no fd")?
+        .as_fd()
+        .as_raw_fd();
+
+    let mut cgroups = Vec::new();
+    collect_cgroups(Path::new("/sys/fs/cgroup"), &mut cgroups);
+
+    let mut detached = 0;
+    for cgroup in cgroups {
+        let attached = query_cgroup_programs(&cgroup, attach_type).unwrap_or_default();
+        if !attached.contains(&stale_id) {
+            continue;
+        }
+        match detach_cgroup_skb_legacy(&cgroup, attach_type, stale_fd) {
+            Ok(()) => detached += 1,
+            Err(e) => log::warn!("Failed to detach stale program from {:?}: {}", cgroup, e),
+        }
+    }
+
+    if detached > 0 {
+        log::info!(
+            "Detached stale eBPF program (id {}) from {} cgroup(s)",
+            stale_id,
+            detached
+        );
+    }
+
+    fs::remove_file(&pin_path)
+        .with_context(|| format!("Failed to unpin stale program {:?}", pin_path))?;
+    Ok(detached)
+}
+
+/// Recursively collect every cgroup directory under `dir`
+#[cfg(feature = "throttle-ebpf")]
+fn collect_cgroups(dir: &Path, cgroups: &mut Vec<PathBuf>) {
+    cgroups.push(dir.to_path_buf());
+
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if entry.file_type().is_ok_and(|t| t.is_dir()) {
+            collect_cgroups(&entry.path(), cgroups);
+        }
+    }
+}
+
+/// List the IDs of the programs attached to a cgroup (bpf_prog_query)
+#[cfg(feature = "throttle-ebpf")]
+fn query_cgroup_programs(cgroup_path: &Path, attach_type: CgroupSkbAttachType) -> Result<Vec<u32>> {
+    let cgroup_file = fs::File::open(cgroup_path)
+        .with_context(|| format!("Failed to open cgroup {:?}", cgroup_path))?;
+
+    const BPF_CGROUP_INET_INGRESS: u32 = 0;
+    const BPF_CGROUP_INET_EGRESS: u32 = 1;
+    // Kernel limit on programs per cgroup and attach type (BPF_CGROUP_MAX_PROGS)
+    const MAX_PROGS: usize = 64;
+
+    // BPF_PROG_QUERY part of union bpf_attr
+    #[repr(C)]
+    struct bpf_attr_query {
+        target_fd: u32,
+        attach_type: u32,
+        query_flags: u32,
+        attach_flags: u32,
+        prog_ids: u64,
+        prog_cnt: u32,
+        _pad: u32,
+    }
+
+    let mut prog_ids = [0u32; MAX_PROGS];
+    let mut attr = bpf_attr_query {
+        target_fd: cgroup_file.as_raw_fd() as u32,
+        attach_type: match attach_type {
+            CgroupSkbAttachType::Ingress => BPF_CGROUP_INET_INGRESS,
+            CgroupSkbAttachType::Egress => BPF_CGROUP_INET_EGRESS,
+        },
+        query_flags: 0,
+        attach_flags: 0,
+        prog_ids: prog_ids.as_mut_ptr() as u64,
+        prog_cnt: MAX_PROGS as u32,
+        _pad: 0,
+    };
+
+    let ret = unsafe {
+        libc::syscall(
+            libc::SYS_bpf,
+            16, // BPF_PROG_QUERY
+            &mut attr as *mut _ as *mut libc::c_void,
+            std::mem::size_of::<bpf_attr_query>(),
+        )
+    };
+    if ret < 0 {
+        return Err(anyhow::anyhow!(
+            "bpf_prog_query failed on {:?}: {}",
+            cgroup_path,
+            std::io::Error::last_os_error()
+        ));
+    }
+
+    let count = (attr.prog_cnt as usize).min(MAX_PROGS);
+    Ok(prog_ids[..count].to_vec())
+}
+
+/// Detach leftover programs and remove everything under `BPF_PIN_ROOT` (--cleanup)
+///
+/// Returns the number of stale attachments that were detached.
+#[cfg(feature = "throttle-ebpf")]
+pub fn cleanup_pinned_objects() -> Result<usize> {
+    let root = Path::new(BPF_PIN_ROOT);
+    if !root.exists() {
+        return Ok(0);
+    }
+
+    let mut detached = 0;
+    for (program_name, attach_type) in PINNED_PROGRAMS {
+        match detach_stale_attachments(&pin_dir(program_name), attach_type) {
+            Ok(count) => detached += count,
+            Err(e) => log::warn!("Failed to detach pinned {}: {:#}", program_name, e),
+        }
+    }
+
+    fs::remove_dir_all(root).with_context(|| format!("Failed to remove {:?}", root))?;
+    Ok(detached)
+}
+
+/// Attach a cgroup SKB program using legacy method (bpf_prog_attach)
+#[cfg(feature = "throttle-ebpf")]
+pub fn attach_cgroup_skb_legacy(
+    ebpf: &mut Ebpf,
+    program_name: &str,
+    cgroup_path: &Path,
+    attach_type: CgroupSkbAttachType,
+) -> Result<()> {
+    use std::os::fd::{AsFd, BorrowedFd};
+
+    let program: &mut CgroupSkb = ebpf
+        .program_mut(program_name)
+        .ok_or_else(|| anyhow::anyhow!("Program {} not found", program_name))?
+        .try_into()
+        .context("Program is not a CgroupSkb program")?;
+
+    // Program should already be loaded in ensure_loaded()
+    // This ensures userspace and kernel use the SAME map instances
+    log::debug!(
+        "Program '{}' expected_attach_type: {:?}",
+        program_name,
+        program.expected_attach_type()
+    );
+
+    // Get program FD (should already exist from ensure_loaded)
+    let prog_fd = program
+        .fd()
+        .context("Program not loaded - call ensure_loaded() first")?;
+    let prog_fd_borrowed: BorrowedFd = prog_fd.as_fd();
+    let prog_fd_raw = prog_fd_borrowed.as_raw_fd();
+
+    log::debug!(
+        "Program FD obtained: {} (using legacy bpf_prog_attach)",
+        prog_fd_raw
+    );
+
+    // Open cgroup
+    let cgroup_file = fs::File::open(cgroup_path)
+        .with_context(|| format!("Failed to open cgroup {:?}", cgroup_path))?;
+    let cgroup_fd_raw = cgroup_file.as_raw_fd();
+
+    log::debug!(
+        "Opened cgroup file: {:?} (fd: {})",
+        cgroup_path,
+        cgroup_fd_raw
+    );
+
+    // Convert attach type to BPF constant
+    // These are stable kernel ABI constants from linux/bpf.h
+    const BPF_CGROUP_INET_INGRESS: u32 = 0;
+    const BPF_CGROUP_INET_EGRESS: u32 = 1;
+
+    let bpf_attach_type = match attach_type {
+        CgroupSkbAttachType::Ingress => BPF_CGROUP_INET_INGRESS,
+        CgroupSkbAttachType::Egress => BPF_CGROUP_INET_EGRESS,
+    };
+
+    // Call bpf_prog_attach syscall directly
+    // According to kernel headers, BPF_PROG_ATTACH uses this struct layout:
+    // union bpf_attr {
+    //     struct { /* anonymous struct used by BPF_MAP_* commands */
+    //         __u32 map_fd;
+    //         __aligned_u64 key;
+    //         union {
+    //             __aligned_u64 value;
+    //             __aligned_u64 next_key;
+    //         };
+    //         __u64 flags;
+    //     };
+    //     struct { /* anonymous struct used by BPF_PROG_LOAD command */
+    //         ...
+    //     };
+    //     struct { /* anonymous struct used by BPF_PROG_ATTACH/DETACH commands */
+    //         __u32 target_fd;     /* container object to attach to */
+    //         __u32 attach_bpf_fd; /* eBPF program to attach */
+    //         __u32 attach_type;
+    //         __u32 attach_flags;
+    //         ...
+    //     };
+    // };
+    // The PROG_ATTACH struct starts at offset 0 in the union.
+
+    #[repr(C)]
+    #[derive(Copy, Clone)]
+    struct bpf_attr_attach {
+        target_fd: u32,
+        attach_bpf_fd: u32,
+        attach_type: u32,
+        attach_flags: u32,
+    }
+
+    const BPF_F_ALLOW_MULTI: u32 = 1 << 1; // = 2
+
+    let mut attr = bpf_attr_attach {
+        target_fd: cgroup_fd_raw as u32,
+        attach_bpf_fd: prog_fd_raw as u32,
+        attach_type: bpf_attach_type,
+        attach_flags: BPF_F_ALLOW_MULTI,
+    };
+
+    log::warn!("🧪 Using LEGACY bpf_prog_attach method instead of bpf_link_create");
+    log::debug!(
+        "Calling bpf_prog_attach with: prog_fd={}, target_fd={}, attach_type={}, flags=BPF_F_ALLOW_MULTI",
+        prog_fd_raw,
+        cgroup_fd_raw,
+        bpf_attach_type as u32
+    );
+
+    let ret = unsafe {
+        libc::syscall(
+            libc::SYS_bpf,
+            8, // BPF_PROG_ATTACH
+            &attr as *const _ as *const libc::c_void,
+            std::mem::size_of::<bpf_attr_attach>(),
+        )
+    };
+
+    if ret < 0 {
+        let errno = std::io::Error::last_os_error();
+        log::error!(
+            "bpf_prog_attach failed: {} (errno: {:?})",
+            errno,
+            errno.raw_os_error()
+        );
+        return Err(anyhow::anyhow!("bpf_prog_attach failed: {}", errno));
+    }
+
+    log::info!("✅ Successfully attached using legacy method!");
+    Ok(())
+}
+
+/// Detach a cgroup SKB program using legacy method (bpf_prog_detach)
+#[cfg(feature = "throttle-ebpf")]
+pub fn detach_cgroup_skb_legacy(
+    cgroup_path: &Path,
+    attach_type: CgroupSkbAttachType,
+    program_fd: i32,
+) -> Result<()> {
+    use std::os::fd::AsRawFd;
+
+    // Open cgroup
+    let cgroup_file = fs::File::open(cgroup_path)
+        .with_context(|| format!("Failed to open cgroup {:?} for detach", cgroup_path))?;
+    let cgroup_fd_raw = cgroup_file.as_raw_fd();
+
+    log::debug!(
+        "Detaching from cgroup: {:?} (fd: {}), program_fd: {}",
+        cgroup_path,
+        cgroup_fd_raw,
+        program_fd
+    );
+
+    // Convert attach type to BPF constant
+    const BPF_CGROUP_INET_INGRESS: u32 = 0;
+    const BPF_CGROUP_INET_EGRESS: u32 = 1;
+
+    let bpf_attach_type = match attach_type {
+        CgroupSkbAttachType::Ingress => BPF_CGROUP_INET_INGRESS,
+        CgroupSkbAttachType::Egress => BPF_CGROUP_INET_EGRESS,
+    };
+
+    // BPF_PROG_DETACH to detach a specific program by FD
+    // When detaching a specific program (attach_bpf_fd != 0), attach_flags MUST be 0
+    // The BPF_F_ALLOW_MULTI flag is only used during BPF_PROG_ATTACH, not detach!
+    #[repr(C)]
+    #[derive(Copy, Clone)]
+    struct bpf_attr_detach {
+        target_fd: u32,
+        attach_bpf_fd: u32, // Specify which program to detach by FD
+        attach_type: u32,
+        attach_flags: u32, // MUST be 0 when detaching specific program
+    }
+
+    let attr = bpf_attr_detach {
+        target_fd: cgroup_fd_raw as u32,
+        attach_bpf_fd: program_fd as u32, // Specify which program to detach
+        attach_type: bpf_attach_type,
+        attach_flags: 0, // CRITICAL: Must be 0, not BPF_F_ALLOW_MULTI
+    };
+
+    log::debug!(
+        "Calling bpf_prog_detach with: target_fd={}, program_fd={}, attach_type={}, flags=0",
+        cgroup_fd_raw,
+        program_fd,
+        bpf_attach_type
+    );
+
+    let ret = unsafe {
+        libc::syscall(
+            libc::SYS_bpf,
+            9, // BPF_PROG_DETACH
+            &attr as *const _ as *const libc::c_void,
+            std::mem::size_of::<bpf_attr_detach>(),
+        )
+    };
+
+    if ret < 0 {
+        let errno = std::io::Error::last_os_error();
+        log::error!(
+            "bpf_prog_detach failed: {} (errno: {:?})",
+            errno,
+            errno.raw_os_error()
+        );
+        return Err(anyhow::anyhow!(
+            "Failed to detach BPF program (fd={}) from cgroup {:?}: {}",
+            program_fd,
+            cgroup_path,
+            errno
+        ));
+    }
+
+    log::info!(
+        "✅ Successfully detached BPF program (fd={}) using legacy method",
+        program_fd
+    );
+    Ok(())
+}
+
+/// BPF attach method selection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BpfAttachMethod {
+    /// Try bpf_link_create first, fallback to bpf_prog_attach on EINVAL
+    Auto,
+    /// Use modern bpf_link_create only
+    Link,
+    /// Use legacy bpf_prog_attach only
+    Legacy,
+}
+
+impl BpfAttachMethod {
+    pub fn parse(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "link" => Self::Link,
+            "legacy" => Self::Legacy,
+            _ => Self::Auto, // default to auto
+        }
+    }
+
+    pub fn from_env_and_arg(arg: Option<&str>) -> Self {
+        // CLI arg takes precedence
+        if let Some(method) = arg {
+            return Self::parse(method);
+        }
+
+        // Check environment variable (legacy support)
+        if std::env::var("CHADTHROTTLE_USE_LEGACY_ATTACH").is_ok() {
+            return Self::Legacy;
+        }
+
+        // Check new environment variable
+        if let Ok(method) = std::env::var("CHADTHROTTLE_BPF_ATTACH_METHOD") {
+            if !["auto", "link", "legacy"].contains(&method.to_lowercase().as_str()) {
+                log::warn!(
+                    "Unknown CHADTHROTTLE_BPF_ATTACH_METHOD {:?}, using auto (expected auto, link or legacy)",
+                    method
+                );
+            }
+            return Self::parse(&method);
+        }
+
+        // Default to auto
+        Self::Auto
+    }
+}
+
+/// How a program was attached to a cgroup, which decides how it is detached
+#[cfg(feature = "throttle-ebpf")]
+#[derive(Debug)]
+pub enum CgroupSkbAttachment {
+    /// bpf_link owned by the loaded program. The kernel drops it with our file
+    /// descriptor, so it can't outlive the process
+    Link(CgroupSkbLinkId),
+    /// bpf_prog_attach attachment. Stays in the cgroup until explicitly detached
+    /// (see `detach_stale_attachments` for the ones a crashed run left behind).
+    /// `fallback` is set when `auto` tried bpf_link_create first and it failed
+    Legacy { fallback: bool },
+}
+
+#[cfg(feature = "throttle-ebpf")]
+impl CgroupSkbAttachment {
+    /// The attach method that ended up working, for display
+    pub fn label(&self) -> &'static str {
+        match self {
+            CgroupSkbAttachment::Link(_) => "link",
+            CgroupSkbAttachment::Legacy { fallback: false } => "legacy",
+            CgroupSkbAttachment::Legacy { fallback: true } => "legacy fallback",
+        }
+    }
+}
+
+/// Attach a cgroup SKB program using configured method
+#[cfg(feature = "throttle-ebpf")]
+pub fn attach_cgroup_skb(
+    ebpf: &mut Ebpf,
+    program_name: &str,
+    cgroup_path: &Path,
+    attach_type: CgroupSkbAttachType,
+) -> Result<CgroupSkbAttachment> {
+    // Get attach method from global config
+    let config = get_bpf_config();
+    attach_cgroup_skb_with_method(
+        ebpf,
+        program_name,
+        cgroup_path,
+        attach_type,
+        config.attach_method,
+    )
+}
+
+/// Attach a cgroup SKB program with specified method
+#[cfg(feature = "throttle-ebpf")]
+pub fn attach_cgroup_skb_with_method(
+    ebpf: &mut Ebpf,
+    program_name: &str,
+    cgroup_path: &Path,
+    attach_type: CgroupSkbAttachType,
+    method: BpfAttachMethod,
+) -> Result<CgroupSkbAttachment> {
+    match method {
+        // An explicitly chosen method never falls back: say so rather than just failing
+        BpfAttachMethod::Legacy => {
+            log::info!("Using legacy BPF attach method (bpf_prog_attach)");
+            attach_cgroup_skb_legacy(ebpf, program_name, cgroup_path, attach_type).context(
+                "Legacy attach (bpf_prog_attach) failed and --bpf-attach-method legacy \
+                 doesn't fall back; use auto to allow bpf_link_create",
+            )?;
+            Ok(CgroupSkbAttachment::Legacy { fallback: false })
+        }
+        BpfAttachMethod::Link => {
+            log::debug!("Using modern BPF attach method (bpf_link_create)");
+            let link_id = attach_cgroup_skb_link(ebpf, program_name, cgroup_path, attach_type)
+                .context(
+                    "Link attach (bpf_link_create) failed and --bpf-attach-method link \
+                     doesn't fall back; use auto to allow bpf_prog_attach",
+                )?;
+            Ok(CgroupSkbAttachment::Link(link_id))
+        }
+        BpfAttachMethod::Auto => {
+            log::debug!("Auto-detecting best BPF attach method");
+            // Try modern method first
+            match attach_cgroup_skb_link(ebpf, program_name, cgroup_path, attach_type) {
+                Ok(link_id) => {
+                    log::info!("✅ Successfully attached using modern method (bpf_link_create)");
+                    Ok(CgroupSkbAttachment::Link(link_id))
+                }
+                Err(e) => {
+                    // Check if it's EINVAL (errno 22) by walking the error chain
+                    let is_einval = {
+                        let mut found = false;
+
+                        // Walk the anyhow error chain
+                        for cause in e.chain() {
+                            // Check if this error is an io::Error with errno 22
+                            if let Some(io_err) = cause.downcast_ref::<std::io::Error>() {
+                                if io_err.raw_os_error() == Some(22) {
+                                    log::debug!("Found io::Error with errno 22 in error chain");
+                                    found = true;
+                                    break;
+                                }
+                            }
+
+                            // Also check string representation as fallback
+                            let error_str = cause.to_string();
+                            if error_str.contains("errno=22")
+                                || error_str.contains("Invalid argument")
+                                || error_str.contains("os error 22")
+                            {
+                                log::debug!("Found EINVAL in error string: {}", error_str);
+                                found = true;
+                                break;
+                            }
+                        }
+
+                        found
+                    };
+
+                    if is_einval {
+                        log::warn!(
+                            "Modern attach failed with EINVAL, falling back to legacy method..."
+                        );
+                        attach_cgroup_skb_legacy(ebpf, program_name, cgroup_path, attach_type)?;
+                        Ok(CgroupSkbAttachment::Legacy { fallback: true })
+                    } else {
+                        // Other error, don't retry
+                        log::error!("Modern attach failed with non-EINVAL error, not retrying");
+                        Err(e)
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Attach using modern bpf_link_create method
+#[cfg(feature = "throttle-ebpf")]
+fn attach_cgroup_skb_link(
+    ebpf: &mut Ebpf,
+    program_name: &str,
+    cgroup_path: &Path,
+    attach_type: CgroupSkbAttachType,
+) -> Result<CgroupSkbLinkId> {
+    let program: &mut CgroupSkb = ebpf
+        .program_mut(program_name)
+        .ok_or_else(|| anyhow::anyhow!("Program {} not found", program_name))?
+        .try_into()
+        .context("Program is not a CgroupSkb program")?;
+
+    // Program should already be loaded in ensure_loaded()
+    // This ensures userspace and kernel use the SAME map instances
+    log::debug!(
+        "Program '{}' expected_attach_type: {:?}",
+        program_name,
+        program.expected_attach_type()
+    );
+
+    // Verify program FD is valid (should already exist from ensure_loaded)
+    match program.fd() {
+        Ok(prog_fd) => {
+            use std::os::fd::AsFd;
+            let raw_fd = prog_fd.as_fd().as_raw_fd();
+            log::debug!("Program FD obtained: valid (raw fd: {})", raw_fd);
+        }
+        Err(e) => {
+            log::error!("Failed to get program FD: {}", e);
+            return Err(anyhow::anyhow!(
+                "Program not loaded - call ensure_loaded() first: {}",
+                e
+            ));
+        }
+    }
+
+    // Open cgroup file (read-only is sufficient for BPF attachment)
+    let cgroup_file = fs::File::open(cgroup_path)
+        .with_context(|| format!("Failed to open cgroup {:?}", cgroup_path))?;
+
+    log::debug!(
+        "Opened cgroup file: {:?} (fd: {})",
+        cgroup_path,
+        cgroup_file.as_raw_fd()
+    );
+
+    // Use AllowMultiple mode to allow multiple BPF programs on the same cgroup
+    // This is CRITICAL because systemd or other tools may have already attached programs
+    // Single mode would fail silently if another program exists
+    // AllowMultiple sets BPF_F_ALLOW_MULTI flag which enables program stacking
+    log::debug!(
+        "Attempting to attach with: attach_type={:?}, mode=AllowMultiple, cgroup_fd={}",
+        attach_type,
+        cgroup_file.as_raw_fd()
+    );
+
+    let attach_result = program.attach(&cgroup_file, attach_type, CgroupAttachMode::AllowMultiple);
+
+    // Log detailed attach result before error handling
+    match &attach_result {
+        Ok(link_id) => {
+            log::debug!("Attachment succeeded! Link ID: {:?}", link_id);
+        }
+        Err(e) => {
+            log::error!("Attachment failed immediately: {}", e);
+        }
+    }
+
+    let link_id = attach_result
+        .inspect_err(|e| {
+            // Extract the underlying OS error if available
+            let error_details = if let Some(source) = std::error::Error::source(&e) {
+                format!("{} (source: {})", e, source)
+            } else {
+                format!("{}", e)
+            };
+
+            // Try to extract errno information
+            let errno_info = if let Some(source) = std::error::Error::source(&e) {
+                if let Some(io_err) = source.downcast_ref::<std::io::Error>() {
+                    if let Some(errno) = io_err.raw_os_error() {
+                        format!(" [errno={}]", errno)
+                    } else {
+                        String::new()
+                    }
+                } else {
+                    String::new()
+                }
+            } else {
+                String::new()
+            };
+
+            log::error!(
+                "Failed to attach program to cgroup: {}{}\nCgroup path: {:?}\nAttach type: {:?}\nMode: AllowMultiple",
+                error_details,
+                errno_info,
+                cgroup_path,
+                attach_type
+            );
+        })
+        .context("Failed to attach program to cgroup")?;
+
+    log::debug!(
+        "Attached {} to {:?} with mode: AllowMultiple (BPF_F_ALLOW_MULTI - allows coexistence)",
+        program_name,
+        cgroup_path
+    );
+
+    Ok(link_id)
+}
+
+/// Detach a cgroup SKB program the same way it was attached
+#[cfg(feature = "throttle-ebpf")]
+pub fn detach_cgroup_skb(
+    ebpf: Option<&mut Ebpf>,
+    program_name: &str,
+    cgroup_path: &Path,
+    attach_type: CgroupSkbAttachType,
+    attachment: CgroupSkbAttachment,
+    program_fd: i32,
+) -> Result<()> {
+    match attachment {
+        CgroupSkbAttachment::Link(link_id) => {
+            let ebpf = ebpf.context("eBPF program already unloaded (link is gone with it)")?;
+            let program: &mut CgroupSkb = ebpf
+                .program_mut(program_name)
+                .ok_or_else(|| anyhow::anyhow!("Program {} not found", program_name))?
+                .try_into()
+                .context("Program is not a CgroupSkb program")?;
+            program
+                .detach(link_id)
+                .with_context(|| format!("Failed to detach bpf_link from {:?}", cgroup_path))
+        }
+        CgroupSkbAttachment::Legacy { .. } => {
+            detach_cgroup_skb_legacy(cgroup_path, attach_type, program_fd)
+        }
+    }
+}
+
+/// Get a BPF map by name
+#[cfg(feature = "throttle-ebpf")]
+pub fn get_bpf_map<'a, K, V>(
+    ebpf: &'a mut Ebpf,
+    map_name: &str,
+) -> Result<BpfHashMap<&'a mut aya::maps::MapData, K, V>>
+where
+    K: aya::Pod,
+    V: aya::Pod,
+{
+    let map = ebpf
+        .map_mut(map_name)
+        .ok_or_else(|| anyhow::anyhow!("Map {} not found", map_name))?;
+
+    Ok(BpfHashMap::try_from(map)?)
+}
+
+/// Get a per-CPU BPF map by name
+#[cfg(feature = "throttle-ebpf")]
+pub fn get_bpf_per_cpu_map<'a, K, V>(
+    ebpf: &'a mut Ebpf,
+    map_name: &str,
+) -> Result<BpfPerCpuHashMap<&'a mut aya::maps::MapData, K, V>>
+where
+    K: aya::Pod,
+    V: aya::Pod,
+{
+    let map = ebpf
+        .map_mut(map_name)
+        .ok_or_else(|| anyhow::anyhow!("Map {} not found", map_name))?;
+
+    Ok(BpfPerCpuHashMap::try_from(map)?)
+}
+
+/// Read the throttle statistics a loaded program has recorded, summed over all CPUs
+///
+/// The programs use the same fixed key (0) for stats as for their config.
+#[cfg(feature = "throttle-ebpf")]
+pub fn read_throttle_stats(ebpf: &Ebpf) -> Option<ThrottleStats> {
+    read_cpu_stats(ebpf).map(|per_cpu| sum_cpu_stats(&per_cpu))
+}
+
+/// Read each CPU's throttle statistics (indexed by CPU number)
+#[cfg(feature = "throttle-ebpf")]
+fn read_cpu_stats(ebpf: &Ebpf) -> Option<Vec<ThrottleStats>> {
+    const MAP_KEY: u64 = 0;
+
+    let stats_map: BpfPerCpuHashMap<_, u64, ThrottleStats> =
+        BpfPerCpuHashMap::try_from(ebpf.map("CGROUP_STATS")?).ok()?;
+    stats_map
+        .get(&MAP_KEY, 0)
+        .ok()
+        .map(|values| values.to_vec())
+}
+
+/// Add up the statistics the programs keep per CPU
+#[cfg(feature = "throttle-ebpf")]
+fn sum_cpu_stats(per_cpu: &[ThrottleStats]) -> ThrottleStats {
+    per_cpu
+        .iter()
+        .fold(ThrottleStats::new(), |mut total, stats| {
+            total.packets_total += stats.packets_total;
+            total.bytes_total += stats.bytes_total;
+            total.packets_dropped += stats.packets_dropped;
+            total.bytes_dropped += stats.bytes_dropped;
+            total.program_calls += stats.program_calls;
+            total.config_misses += stats.config_misses;
+            total.cgroup_id_seen = total.cgroup_id_seen.max(stats.cgroup_id_seen);
+            total.passed_with_tokens += stats.passed_with_tokens;
+            total.dropped_no_tokens += stats.dropped_no_tokens;
+            total.passed_filter_mismatch += stats.passed_filter_mismatch;
+            total
+        })
+}
+
+/// Read each CPU's token bucket (indexed by CPU number)
+#[cfg(feature = "throttle-ebpf")]
+pub fn read_cpu_buckets(ebpf: &Ebpf) -> Option<Vec<TokenBucket>> {
+    const MAP_KEY: u64 = 0;
+
+    let bucket_map: BpfPerCpuHashMap<_, u64, TokenBucket> =
+        BpfPerCpuHashMap::try_from(ebpf.map("CGROUP_BUCKETS")?).ok()?;
+    bucket_map
+        .get(&MAP_KEY, 0)
+        .ok()
+        .map(|values| values.to_vec())
+}
+
+/// Part of a throttle's rate (1 / N) that is split evenly across the online CPUs,
+/// so a CPU the traffic moves to isn't starved until the next rebalance
+#[cfg(feature = "throttle-ebpf")]
+const EVEN_SHARE_DIVISOR: u64 = 10;
+
+/// Smallest burst a CPU's bucket gets when the throttle's burst allows it: a GSO
+/// packet can be up to 64 KiB, and a smaller bucket would never let it pass
+#[cfg(feature = "throttle-ebpf")]
+const MIN_CPU_BURST: u64 = 64 * 1024;
+
+/// Split `rate` across CPUs by their demand (bytes seen since the last rebalance):
+/// a tenth evenly, the rest in proportion to the demand (evenly if there was none).
+/// The shares add up to `rate` exactly.
+#[cfg(feature = "throttle-ebpf")]
+pub fn cpu_shares(rate: u64, demand: &[u64]) -> Vec<u64> {
+    if demand.is_empty() {
+        return Vec::new();
+    }
+    let cpus = demand.len() as u64;
+    let even = rate / EVEN_SHARE_DIVISOR;
+    let by_demand = rate - even;
+    let total_demand: u128 = demand.iter().map(|&bytes| bytes as u128).sum();
+
+    let mut shares: Vec<u64> = demand
+        .iter()
+        .map(|&bytes| {
+            let demand_share = (u128::from(by_demand) * u128::from(bytes))
+                .checked_div(total_demand)
+                .map_or(by_demand / cpus, |share| share as u64);
+            even / cpus + demand_share
+        })
+        .collect();
+
+    // Rounding leftovers go to the busiest CPU
+    let leftover = rate - shares.iter().sum::<u64>();
+    if let Some(busiest) = (0..demand.len()).max_by_key(|&cpu| demand[cpu]) {
+        shares[busiest] += leftover;
+    }
+    shares
+}
+
+/// Per-CPU token buckets for the given shares of a throttle's rate
+///
+/// Each bucket's capacity is its share of `burst` (see `cpu_capacities`). The tokens
+/// left in `previous`
+/// (a full burst for a new throttle) are pooled and handed out by share as well,
+/// so unused tokens follow the traffic instead of sitting on idle CPUs. A CPU
+/// without a share gets an empty bucket, which the program fills on first use.
+#[cfg(feature = "throttle-ebpf")]
+pub fn cpu_buckets(shares: &[u64], burst: u64, previous: &[TokenBucket]) -> Vec<TokenBucket> {
+    let rate = u128::from(shares.iter().sum::<u64>());
+    let pool = if previous.is_empty() {
+        burst
+    } else {
+        previous.iter().map(|bucket| bucket.tokens).sum()
+    };
+    let portion = |amount: u64, share: u64| {
+        (u128::from(amount) * u128::from(share))
+            .checked_div(rate)
+            .unwrap_or(0) as u64
+    };
+
+    let capacities = cpu_capacities(shares, burst);
+
+    shares
+        .iter()
+        .zip(capacities)
+        .enumerate()
+        .map(|(cpu, (&share, capacity))| {
+            if share == 0 {
+                return TokenBucket::new();
+            }
+            let (last_update_ns, next_departure_ns) = previous.get(cpu).map_or((0, 0), |bucket| {
+                (bucket.last_update_ns, bucket.next_departure_ns)
+            });
+            TokenBucket {
+                capacity,
+                tokens: portion(pool, share).min(capacity),
+                last_update_ns,
+                rate_bps: share,
+                next_departure_ns,
+            }
+        })
+        .collect()
+}
+
+/// Bucket capacities for the given shares of a throttle's rate
+///
+/// Each CPU with a share gets its share of `burst`, raised to a floor with the
+/// difference taken from the larger buckets. The CPU with the largest share (where
+/// the traffic is, once rebalanced) can hold `MIN_CPU_BURST` if half the burst
+/// allows it, and at least one full-size packet however small the burst; the others
+/// split what's left. The capacities never add up to more than `burst`, so CPUs that
+/// all sat idle can't release more than one burst together.
+#[cfg(feature = "throttle-ebpf")]
+fn cpu_capacities(shares: &[u64], burst: u64) -> Vec<u64> {
+    let rate = u128::from(shares.iter().sum::<u64>());
+    let sharing = shares.iter().filter(|&&share| share > 0).count() as u64;
+    let Some(largest) = (0..shares.len()).max_by_key(|&cpu| shares[cpu]) else {
+        return Vec::new();
+    };
+    if sharing == 0 {
+        return vec![0; shares.len()];
+    }
+    let largest_floor = MIN_CPU_BURST.min(burst / 2).max(MIN_BURST_SIZE.min(burst));
+    let other_floor = MIN_CPU_BURST.min((burst - largest_floor) / (sharing - 1).max(1));
+    let floors: Vec<u64> = (0..shares.len())
+        .map(|cpu| match shares[cpu] {
+            0 => 0,
+            _ if cpu == largest => largest_floor,
+            _ => other_floor,
+        })
+        .collect();
+
+    let mut capacities: Vec<u64> = shares
+        .iter()
+        .zip(&floors)
+        .map(|(&share, &floor)| {
+            let portion = (u128::from(burst) * u128::from(share) / rate) as u64;
+            portion.max(floor)
+        })
+        .collect();
+
+    // Take what the floors added from the room the buckets have above them (rounded
+    // up, so the total ends up at or under the burst)
+    let excess = capacities.iter().sum::<u64>().saturating_sub(burst);
+    let room: u64 = capacities
+        .iter()
+        .zip(&floors)
+        .map(|(&capacity, &floor)| capacity - floor)
+        .sum();
+    if excess > 0 && room > 0 {
+        for (capacity, &floor) in capacities.iter_mut().zip(&floors) {
+            let above = *capacity - floor;
+            let cut = (u128::from(excess) * u128::from(above)).div_ceil(u128::from(room)) as u64;
+            *capacity -= cut.min(above);
+        }
+    }
+    capacities
+}
+
+/// Online CPU numbers and the number of per-CPU map slots (possible CPUs)
+#[cfg(feature = "throttle-ebpf")]
+fn cpu_layout() -> Result<(Vec<usize>, usize)> {
+    let online = aya::util::online_cpus()
+        .map_err(|(path, e)| anyhow::anyhow!("Failed to read {}: {}", path, e))?;
+    let slots = aya::util::nr_cpus()
+        .map_err(|(path, e)| anyhow::anyhow!("Failed to read {}: {}", path, e))?;
+    let online: Vec<usize> = online
+        .into_iter()
+        .map(|cpu| cpu as usize)
+        .filter(|&cpu| cpu < slots)
+        .collect();
+    if online.is_empty() {
+        return Err(anyhow::anyhow!("No online CPUs found"));
+    }
+    Ok((online, slots))
+}
+
+/// Address family value for a throttle config
+#[cfg(feature = "throttle-ebpf")]
+pub fn address_family_value(family: crate::process::AddressFamily) -> u8 {
+    use crate::process::AddressFamily;
+    use chadthrottle_common::{ADDRESS_FAMILY_ANY, ADDRESS_FAMILY_V4, ADDRESS_FAMILY_V6};
+
+    match family {
+        AddressFamily::Any => ADDRESS_FAMILY_ANY,
+        AddressFamily::V4 => ADDRESS_FAMILY_V4,
+        AddressFamily::V6 => ADDRESS_FAMILY_V6,
+    }
+}
+
+/// Number of online CPUs a throttle's rate is split across
+#[cfg(feature = "throttle-ebpf")]
+pub fn online_cpu_count() -> u16 {
+    cpu_layout().map_or(1, |(online, _)| online.len().min(u16::MAX as usize) as u16)
+}
+
+/// Write per-CPU token buckets for `config`'s rate, splitting it across the online
+/// CPUs by `demand` (indexed by CPU number, missing CPUs count as idle)
+#[cfg(feature = "throttle-ebpf")]
+fn write_cpu_buckets(
+    ebpf: &mut Ebpf,
+    config: &CgroupThrottleConfig,
+    demand: &[u64],
+    previous: &[TokenBucket],
+) -> Result<()> {
+    const MAP_KEY: u64 = 0;
+
+    let (online, slots) = cpu_layout()?;
+    let online_demand: Vec<u64> = online
+        .iter()
+        .map(|&cpu| demand.get(cpu).copied().unwrap_or(0))
+        .collect();
+    let mut shares = vec![0; slots];
+    let online_shares = cpu_shares(config.rate_bps, &online_demand);
+    for (&cpu, share) in online.iter().zip(online_shares) {
+        shares[cpu] = share;
+    }
+
+    let buckets = cpu_buckets(&shares, config.burst_size, previous);
+    let mut bucket_map: BpfPerCpuHashMap<_, u64, TokenBucket> =
+        get_bpf_per_cpu_map(ebpf, "CGROUP_BUCKETS")?;
+    bucket_map
+        .insert(MAP_KEY, PerCpuValues::try_from(buckets)?, 0)
+        .context("Failed to store token buckets")?;
+    Ok(())
+}
+
+/// Set up the per-CPU token buckets for a new throttle, splitting its rate evenly
+///
+/// Buckets adopted from pinned maps (e.g. after a restart) are kept if their shares
+/// still add up to the limit and their capacities fit the burst, so the restart
+/// doesn't hand out a fresh burst.
+/// Returns the tokens available across all CPUs.
+#[cfg(feature = "throttle-ebpf")]
+pub fn init_cpu_buckets(ebpf: &mut Ebpf, config: &CgroupThrottleConfig) -> Result<u64> {
+    if let Some(existing) = read_cpu_buckets(ebpf) {
+        let rate: u64 = existing.iter().map(|bucket| bucket.rate_bps).sum();
+        let capacity: u64 = existing.iter().map(|bucket| bucket.capacity).sum();
+        if rate == config.rate_bps && capacity <= config.burst_size {
+            return Ok(existing.iter().map(|bucket| bucket.tokens).sum());
+        }
+    }
+
+    write_cpu_buckets(ebpf, config, &[], &[])?;
+    Ok(config.burst_size)
+}
+
+/// Moves the per-CPU shares of a throttle's rate to the CPUs its traffic is on
+///
+/// Each CPU's bucket only refills at its share of the rate, which keeps CPUs from
+/// racing on the same tokens, but a share that stays even would hold a throttle
+/// whose traffic is on a few CPUs well below its limit. Call `rebalance`
+/// periodically: the bytes each CPU saw since the last call decide the new shares.
+#[cfg(feature = "throttle-ebpf")]
+#[derive(Debug, Default)]
+pub struct CpuRebalancer {
+    // Per-CPU bytes_total at the previous rebalance
+    last_bytes: Vec<u64>,
+}
+
+#[cfg(feature = "throttle-ebpf")]
+impl CpuRebalancer {
+    /// Rebalance the throttle in `ebpf`'s maps, if there is one
+    pub fn rebalance(&mut self, ebpf: &mut Ebpf) -> Result<()> {
+        const MAP_KEY: u64 = 0;
+
+        let config = {
+            let config_map: BpfHashMap<_, u64, CgroupThrottleConfig> =
+                get_bpf_map(ebpf, "CGROUP_CONFIGS")?;
+            match config_map.get(&MAP_KEY, 0) {
+                Ok(config) => config,
+                Err(_) => {
+                    self.last_bytes.clear();
+                    return Ok(());
+                }
+            }
+        };
+        let bytes: Vec<u64> = read_cpu_stats(ebpf)
+            .unwrap_or_default()
+            .iter()
+            .map(|stats| stats.bytes_total)
+            .collect();
+        let demand = self.demand(bytes);
+
+        // Without traffic there's nothing to go by: keep the current shares
+        if demand.iter().all(|&bytes| bytes == 0) {
+            return Ok(());
+        }
+
+        let previous = read_cpu_buckets(ebpf).unwrap_or_default();
+        write_cpu_buckets(ebpf, &config, &demand, &previous)
+    }
+
+    /// Bytes each CPU saw since the last call, from the per-CPU `bytes_total`
+    fn demand(&mut self, bytes: Vec<u64>) -> Vec<u64> {
+        // Stats that went backwards were reset along with the throttle
+        let demand = bytes
+            .iter()
+            .enumerate()
+            .map(|(cpu, &total)| {
+                total.saturating_sub(self.last_bytes.get(cpu).copied().unwrap_or(0))
+            })
+            .collect();
+        self.last_bytes = bytes;
+        demand
+    }
+}
+
+#[cfg(feature = "throttle-ebpf")]
+impl From<ThrottleStats> for super::BackendStats {
+    fn from(stats: ThrottleStats) -> Self {
+        Self {
+            packets_total: stats.packets_total,
+            bytes_total: stats.bytes_total,
+            packets_dropped: stats.packets_dropped,
+            bytes_dropped: stats.bytes_dropped,
+            passed_with_tokens: stats.passed_with_tokens,
+            dropped_no_tokens: stats.dropped_no_tokens,
+            passed_filter_mismatch: stats.passed_filter_mismatch,
+            cgroup_repairs: 0, // Tracked by the backends, not the programs
+            reattachments: 0,
+            backlog_bytes: None, // Drops instead of queueing
+            queue_delay_estimate_ms: None,
+        }
+    }
+}
+
+/// Convert a traffic type from its eBPF config value (see `TRAFFIC_TYPE_*`)
+#[cfg(feature = "throttle-ebpf")]
+pub fn traffic_type_from_ebpf(value: u8) -> crate::process::TrafficType {
+    use chadthrottle_common::{TRAFFIC_TYPE_INTERNET, TRAFFIC_TYPE_LOCAL};
+
+    match value {
+        TRAFFIC_TYPE_INTERNET => crate::process::TrafficType::Internet,
+        TRAFFIC_TYPE_LOCAL => crate::process::TrafficType::Local,
+        _ => crate::process::TrafficType::All,
+    }
+}
+
+/// Convert an over-limit policy from its eBPF config value (see `POLICY_*`)
+#[cfg(feature = "throttle-ebpf")]
+pub fn policy_from_ebpf(value: u8) -> crate::process::Policy {
+    match value {
+        chadthrottle_common::POLICY_SHAPE => crate::process::Policy::Shape,
+        _ => crate::process::Policy::Drop,
+    }
+}
+
+/// Kernel (major, minor) version from /proc/version
+#[cfg(feature = "throttle-ebpf")]
+fn kernel_version() -> Option<(u32, u32)> {
+    let contents = fs::read_to_string("/proc/version").ok()?;
+    let release = contents.split_whitespace().nth(2)?;
+    let mut numbers = release.split(|c: char| !c.is_ascii_digit());
+    Some((numbers.next()?.parse().ok()?, numbers.next()?.parse().ok()?))
+}
+
+/// Check if cgroup_skb programs can pace packets by setting skb->tstamp as an
+/// Earliest Departure Time (writable since kernel 5.0, honoured by the fq qdisc)
+#[cfg(feature = "throttle-ebpf")]
+pub fn edt_pacing_supported() -> bool {
+    kernel_version().is_some_and(|version| version >= (5, 0))
+}
+
+/// Watches the throttle stats for a program that keeps running but can't find its
+/// config, i.e. the config map no longer matches the cgroup the program sees
+#[cfg(feature = "throttle-ebpf")]
+#[derive(Debug, Default)]
+pub struct CgroupIdWatch {
+    // (program_calls, config_misses) at the previous check
+    last: Option<(u64, u64)>,
+}
+
+#[cfg(feature = "throttle-ebpf")]
+impl CgroupIdWatch {
+    /// Record the latest stats, returning true if config misses rose along with
+    /// program calls since the previous check
+    pub fn misses_rising(&mut self, stats: &ThrottleStats) -> bool {
+        let current = (stats.program_calls, stats.config_misses);
+        let rising = self
+            .last
+            .is_some_and(|(calls, misses)| current.0 > calls && current.1 > misses);
+        self.last = Some(current);
+        rising
+    }
+}
+
+/// Watches the program calls for attachments that went stale: the throttled
+/// processes have traffic, but the program stopped seeing any of it (the cgroup
+/// was recreated, or an interface flap took a legacy attachment with it)
+#[cfg(feature = "throttle-ebpf")]
+#[derive(Debug, Default)]
+pub struct AttachmentWatch {
+    // program_calls at the previous check
+    last_calls: Option<u64>,
+}
+
+#[cfg(feature = "throttle-ebpf")]
+impl AttachmentWatch {
+    /// Record the latest call count, returning true if it hasn't moved since the
+    /// previous check although throttled processes had traffic
+    pub fn calls_frozen(&mut self, program_calls: u64, traffic_flowing: bool) -> bool {
+        let frozen = traffic_flowing && self.last_calls == Some(program_calls);
+        self.last_calls = Some(program_calls);
+        frozen
+    }
+}
+
+/// Put a throttle config back into CGROUP_CONFIGS if the program's entry is
+/// missing, returning whether it had to be rewritten
+#[cfg(feature = "throttle-ebpf")]
+pub fn rewrite_missing_config(ebpf: &mut Ebpf, config: CgroupThrottleConfig) -> Result<bool> {
+    const MAP_KEY: u64 = 0;
+
+    let mut config_map: BpfHashMap<_, u64, CgroupThrottleConfig> =
+        get_bpf_map(ebpf, "CGROUP_CONFIGS")?;
+    if config_map.get(&MAP_KEY, 0).is_ok() {
+        return Ok(false);
+    }
+    config_map.insert(MAP_KEY, config, 0)?;
+    Ok(true)
+}
+
+/// Get current time in nanoseconds since UNIX epoch
+/// This is used to initialize the token bucket timestamp to match what the eBPF program expects
+#[cfg(feature = "throttle-ebpf")]
+pub fn get_current_time_ns() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("System time before UNIX epoch")
+        .as_nanos() as u64
+}
+
+#[cfg(all(test, feature = "throttle-ebpf"))]
+mod tests {
+    use super::*;
+    use chadthrottle_common::burst_size_for;
+
+    /// 1 Gbit/s in bytes per second
+    const GIGABIT: u64 = 125_000_000;
+
+    /// Long-run throughput of per-CPU buckets: each CPU passes what it's offered,
+    /// up to its share of the rate
+    fn achieved_rate(shares: &[u64], offered: &[u64]) -> u64 {
+        shares
+            .iter()
+            .zip(offered)
+            .map(|(&share, &offered)| share.min(offered))
+            .sum()
+    }
+
+    #[test]
+    fn test_cpu_shares_add_up_to_rate() {
+        assert_eq!(cpu_shares(1000, &[0; 4]), vec![250; 4]);
+
+        let shares = cpu_shares(1001, &[7, 0, 300, 1]);
+        assert_eq!(shares.iter().sum::<u64>(), 1001);
+        // Every CPU keeps part of the even tenth, the busiest gets the most
+        assert!(shares.iter().all(|&share| share >= 1001 / 10 / 4));
+        assert_eq!(shares.iter().max(), Some(&shares[2]));
+
+        assert!(cpu_shares(1000, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_multi_core_rate_stays_within_ten_percent() {
+        const CPUS: usize = 16;
+
+        // Traffic spread over every CPU can't add up to more than the limit
+        let shares = cpu_shares(GIGABIT, &[0; CPUS]);
+        let achieved = achieved_rate(&shares, &[GIGABIT; CPUS]);
+        assert!(achieved <= GIGABIT);
+        assert!(achieved >= GIGABIT * 9 / 10);
+
+        // Traffic on one CPU gets (nearly) the whole rate once rebalanced
+        let mut demand = [0; CPUS];
+        demand[3] = 40_000_000;
+        let shares = cpu_shares(GIGABIT, &demand);
+        let mut offered = [0; CPUS];
+        offered[3] = GIGABIT * 2;
+        let achieved = achieved_rate(&shares, &offered);
+        assert!(achieved <= GIGABIT);
+        assert!(achieved >= GIGABIT * 9 / 10, "achieved {}", achieved);
+
+        // Uneven traffic on a few CPUs
+        let demand = [30_000_000, 10_000_000, 0, 0, 5_000_000, 0, 0, 0];
+        let shares = cpu_shares(GIGABIT, &demand);
+        let offered = demand.map(|bytes| bytes * 4);
+        let achieved = achieved_rate(&shares, &offered);
+        assert!(achieved <= GIGABIT);
+        assert!(achieved >= GIGABIT * 9 / 10, "achieved {}", achieved);
+    }
+
+    #[test]
+    fn test_rebalanced_cpu_buckets_stay_within_ten_percent() {
+        const CPUS: usize = 8;
+        const SECS: u64 = 60;
+        const STEP_NS: u64 = 1_000_000;
+        const STEPS_PER_SEC: u64 = 1_000_000_000 / STEP_NS;
+        const PACKET: u64 = 1500;
+        // 100 Mbit/s, offered about twice over: full-size packets per CPU per step
+        const RATE: u64 = GIGABIT / 10;
+        const OFFERED: [u64; CPUS] = [9, 4, 2, 0, 0, 1, 0, 1];
+
+        let burst = burst_size_for(RATE);
+        let mut buckets = cpu_buckets(&cpu_shares(RATE, &[0; CPUS]), burst, &[]);
+        let mut rebalancer = CpuRebalancer::default();
+        let mut seen = [0u64; CPUS];
+        let mut passed = 0;
+
+        for step in 1..=SECS * STEPS_PER_SEC {
+            let now_ns = step * STEP_NS;
+            // The traffic moves to other CPUs every 10 seconds
+            let shift = (step / (10 * STEPS_PER_SEC)) as usize;
+            for (cpu, bucket) in buckets.iter_mut().enumerate() {
+                for _ in 0..OFFERED[(cpu + shift) % CPUS] {
+                    bucket.refill(now_ns);
+                    if bucket.try_consume(PACKET) {
+                        passed += PACKET;
+                    }
+                    seen[cpu] += PACKET;
+                }
+            }
+
+            // Rebalance once a second, like the backends do
+            if step % STEPS_PER_SEC == 0 {
+                let demand = rebalancer.demand(seen.to_vec());
+                if demand.iter().any(|&bytes| bytes > 0) {
+                    buckets = cpu_buckets(&cpu_shares(RATE, &demand), burst, &buckets);
+                }
+            }
+        }
+
+        let target = RATE * SECS;
+        assert!(
+            passed.abs_diff(target) <= target / 10,
+            "passed {} bytes in {}s, target {}",
+            passed,
+            SECS,
+            target
+        );
+    }
+
+    #[test]
+    fn test_cpu_buckets_pool_unused_tokens() {
+        let idle = TokenBucket {
+            capacity: 500_000,
+            tokens: 500_000,
+            last_update_ns: 10,
+            rate_bps: 250_000,
+            next_departure_ns: 0,
+        };
+        let drained = TokenBucket {
+            tokens: 0,
+            last_update_ns: 20,
+            ..idle
+        };
+
+        // The idle CPU's tokens move to the busy one along with the rate
+        let buckets = cpu_buckets(&[100_000, 900_000, 0], 2_000_000, &[idle, drained, idle]);
+        assert_eq!(buckets[1].rate_bps, 900_000);
+        assert_eq!(buckets[1].capacity, 1_800_000);
+        assert_eq!(buckets[1].tokens, 900_000);
+        assert_eq!(buckets[1].last_update_ns, 20);
+        assert_eq!(buckets[0].tokens, 100_000);
+        assert_eq!(buckets[0].capacity, 200_000);
+
+        // A CPU without a share is left for the program to initialize
+        assert_eq!(buckets[2].rate_bps, 0);
+        assert_eq!(buckets[2].capacity, 0);
+
+        // A new throttle starts with a full burst
+        let buckets = cpu_buckets(&[500, 500], 200_000, &[]);
+        assert!(buckets.iter().all(|bucket| bucket.tokens == 100_000));
+
+        // With a burst of a couple of packets, one CPU still fits a full packet
+        let buckets = cpu_buckets(&[500, 500], 2000, &[]);
+        assert_eq!(buckets[0].capacity + buckets[1].capacity, 2000);
+        assert_eq!(buckets[0].capacity.max(buckets[1].capacity), MIN_BURST_SIZE);
+
+        // A small share still gets a bucket big enough for a full GSO packet, taken
+        // from the bigger bucket
+        let buckets = cpu_buckets(&[1_000, 99_000], 200_000, &[]);
+        assert_eq!(buckets[0].capacity, MIN_CPU_BURST);
+        assert_eq!(buckets[0].tokens, 2_000);
+        assert_eq!(buckets[1].capacity, 200_000 - MIN_CPU_BURST);
+    }
+
+    #[test]
+    fn test_cpu_buckets_never_exceed_burst() {
+        let total =
+            |buckets: &[TokenBucket]| -> u64 { buckets.iter().map(|bucket| bucket.capacity).sum() };
+        let cases: [(&[u64], u64); 6] = [
+            (&[8; 16], 1500),
+            (&[1; 64], burst_size_for(64)),
+            (&[1_000, 99_000], 200_000),
+            (&[10, 20, 0, 30_000_000], burst_size_for(30_000_030)),
+            (&[GIGABIT / 16; 16], burst_size_for(GIGABIT)),
+            (&[3, 0, 7, 0, 0, 1], 1500),
+        ];
+        for (shares, burst) in cases {
+            let buckets = cpu_buckets(shares, burst, &[]);
+            assert!(
+                total(&buckets) <= burst,
+                "{:?}: {} > {}",
+                shares,
+                total(&buckets),
+                burst
+            );
+            // The CPU with the largest share can hold a full-size packet
+            let largest = buckets.iter().map(|bucket| bucket.capacity).max();
+            assert!(largest >= Some(MIN_BURST_SIZE.min(burst)));
+            // Pooled tokens fit the capacities too
+            let tokens: u64 = buckets.iter().map(|bucket| bucket.tokens).sum();
+            assert!(tokens <= burst);
+        }
+    }
+}
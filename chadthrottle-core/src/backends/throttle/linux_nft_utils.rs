@@ -0,0 +1,999 @@
+// Shared utilities for Linux nftables operations
+//
+// Everything lives in one `inet chadthrottle` table with one chain per direction
+// (`upload` on the output hook, `download` on the input hook). A throttle is a named
+// limit object (`up_pid_1234`) and an element mapping its cgroup to that object.
+// Throttles with the same cgroup matcher and filters (interfaces, IP version,
+// Internet/Local) share a map, and each map is looked up by one rule:
+//
+// ```text
+// table inet chadthrottle {
+//     limit up_pid_1234 { rate over 1048576 bytes/second }
+//     map up_v2l2_all { typeof socket cgroupv2 level 2 : limit; elements = { "chadthrottle/pid_1234" : "up_pid_1234" } }
+//     chain upload {
+//         type filter hook output priority 0; policy accept;
+//         limit name socket cgroupv2 level 2 map @up_v2l2_all drop
+//     }
+// }
+// ```
+//
+// Adding or removing a throttle only adds or deletes a limit object and a map
+// element. A map and its rule are created with the first throttle that needs them
+// and stay (empty) after the last one is gone. Each change is one `nft -f` batch, so
+// it applies completely or not at all.
+
+use anyhow::{Context, Result, anyhow};
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::backends::cgroup::{CgroupBackendType, CgroupHandle};
+
+const NFT_TABLE: &str = "chadthrottle";
+const NFT_CHAIN_UPLOAD: &str = "upload";
+const NFT_CHAIN_DOWNLOAD: &str = "download";
+
+/// Where cgroup v2 paths in map elements are relative to
+const CGROUP_V2_ROOT: &str = "/sys/fs/cgroup";
+
+/// Chains of the layout before maps, with one rule per throttle (older versions)
+const LEGACY_CHAINS: [&str; 2] = ["output_limit", "input_limit"];
+
+/// Check if nftables is available
+pub fn check_nft_available() -> bool {
+    Command::new("nft").arg("--version").output().is_ok()
+}
+
+/// Direction for rate limiting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Upload,
+    Download,
+}
+
+impl Direction {
+    fn chain(self) -> &'static str {
+        match self {
+            Direction::Upload => NFT_CHAIN_UPLOAD,
+            Direction::Download => NFT_CHAIN_DOWNLOAD,
+        }
+    }
+
+    fn hook(self) -> &'static str {
+        match self {
+            Direction::Upload => "output",
+            Direction::Download => "input",
+        }
+    }
+
+    /// Prefix of the direction's limit object and map names
+    fn prefix(self) -> &'static str {
+        match self {
+            Direction::Upload => "up",
+            Direction::Download => "down",
+        }
+    }
+}
+
+/// How a packet's cgroup is looked up in a map
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CgroupKey {
+    /// cgroup v2 path relative to the hierarchy root, matched at its own depth
+    V2 { path: String },
+    /// cgroup v1 net_cls classid
+    V1 { classid: u32 },
+}
+
+impl CgroupKey {
+    pub fn from_handle(handle: &CgroupHandle) -> Result<Self> {
+        match handle.backend_type {
+            CgroupBackendType::V2Nftables | CgroupBackendType::V2Ebpf => Ok(CgroupKey::V2 {
+                path: handle.identifier.trim_matches('/').to_string(),
+            }),
+            CgroupBackendType::V1 => {
+                // "1:10" (major:minor, as the v1 backend writes them)
+                let (major, minor) = handle
+                    .identifier
+                    .split_once(':')
+                    .and_then(|(major, minor)| {
+                        Some((major.parse::<u32>().ok()?, minor.parse::<u32>().ok()?))
+                    })
+                    .ok_or_else(|| anyhow!("Invalid cgroup v1 classid '{}'", handle.identifier))?;
+                Ok(CgroupKey::V1 {
+                    classid: (major << 16) | minor,
+                })
+            }
+        }
+    }
+
+    /// The packet match the map is keyed by
+    fn expression(&self) -> String {
+        match self {
+            // The level is the path's depth: the socket's ancestor cgroup at that
+            // level is compared with the path
+            CgroupKey::V2 { path } => {
+                format!("socket cgroupv2 level {}", path.split('/').count())
+            }
+            CgroupKey::V1 { .. } => "meta cgroup".to_string(),
+        }
+    }
+
+    /// The key as written in a map element
+    fn element(&self) -> String {
+        match self {
+            CgroupKey::V2 { path } => format!("\"{}\"", path),
+            CgroupKey::V1 { classid } => format!("0x{:08x}", classid),
+        }
+    }
+
+    /// Part of the map name telling matchers apart
+    fn map_tag(&self) -> String {
+        match self {
+            CgroupKey::V2 { path } => format!("v2l{}", path.split('/').count()),
+            CgroupKey::V1 { .. } => "v1".to_string(),
+        }
+    }
+}
+
+/// A throttle as laid out in the table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NftThrottle {
+    pub pid: i32,
+    pub direction: Direction,
+    pub key: CgroupKey,
+    pub rate_bytes_per_sec: u64,
+    /// Interface, IP version and destination matches of the map's rule (empty for
+    /// none, as nft lists them for adopted throttles)
+    pub filter: String,
+    /// Map holding the throttle's element
+    pub map: String,
+}
+
+impl NftThrottle {
+    /// A throttle for the cgroup in `handle`, optionally restricted to one IP version,
+    /// the given interfaces and Internet or Local destinations
+    pub fn new(
+        handle: &CgroupHandle,
+        rate_bytes_per_sec: u64,
+        direction: Direction,
+        traffic_type: crate::process::TrafficType,
+        family: crate::process::AddressFamily,
+        interfaces: Option<&[String]>,
+    ) -> Result<Self> {
+        let key = CgroupKey::from_handle(handle)?;
+        let filter = format!(
+            "{}{}{}",
+            interface_filter(direction, interfaces),
+            family_filter(family),
+            traffic_type_filter(traffic_type)
+        );
+        let map = map_name(direction, &key, &filter);
+        Ok(Self {
+            pid: handle.pid,
+            direction,
+            key,
+            rate_bytes_per_sec,
+            filter,
+            map,
+        })
+    }
+
+    fn limit_name(&self) -> String {
+        limit_name(self.direction, self.pid)
+    }
+}
+
+fn limit_name(direction: Direction, pid: i32) -> String {
+    format!("{}_pid_{}", direction.prefix(), pid)
+}
+
+/// Name of the map for a matcher and filter: readable for the common unfiltered
+/// case, a hash of the filter otherwise (names are limited to 255 characters)
+fn map_name(direction: Direction, key: &CgroupKey, filter: &str) -> String {
+    let filter_tag = if filter.is_empty() {
+        "all".to_string()
+    } else {
+        // FNV-1a, stable across runs and builds
+        let hash = filter.bytes().fold(0x811c_9dc5u32, |hash, byte| {
+            (hash ^ byte as u32).wrapping_mul(0x0100_0193)
+        });
+        format!("{:08x}", hash)
+    };
+    format!("{}_{}_{}", direction.prefix(), key.map_tag(), filter_tag)
+}
+
+/// Commands creating the table and both chains (no-ops where they exist)
+fn table_script() -> String {
+    let mut script = format!("add table inet {}\n", NFT_TABLE);
+    for direction in [Direction::Upload, Direction::Download] {
+        script += &format!(
+            "add chain inet {} {} {{ type filter hook {} priority 0; policy accept; }}\n",
+            NFT_TABLE,
+            direction.chain(),
+            direction.hook()
+        );
+    }
+    script
+}
+
+/// Commands creating a throttle's map and the rule looking it up
+fn map_script(throttle: &NftThrottle) -> String {
+    let expression = throttle.key.expression();
+    format!(
+        "add map inet {table} {map} {{ typeof {expression} : limit; }}\n\
+         add rule inet {table} {chain} {filter}limit name {expression} map @{map} drop\n",
+        table = NFT_TABLE,
+        map = throttle.map,
+        chain = throttle.direction.chain(),
+        filter = throttle.filter,
+        expression = expression,
+    )
+}
+
+/// Command adding a throttle's map element
+fn element_command(throttle: &NftThrottle) -> String {
+    format!(
+        "add element inet {} {} {{ {} : \"{}\" }}\n",
+        NFT_TABLE,
+        throttle.map,
+        throttle.key.element(),
+        throttle.limit_name()
+    )
+}
+
+/// Commands adding a throttle's limit object and map element
+fn add_script(throttle: &NftThrottle) -> String {
+    format!(
+        "add limit inet {} {} {{ rate over {} bytes/second; }}\n{}",
+        NFT_TABLE,
+        throttle.limit_name(),
+        throttle.rate_bytes_per_sec,
+        element_command(throttle)
+    )
+}
+
+/// Commands removing a throttle's map element and limit object (the element first,
+/// it references the object)
+fn remove_script(throttle: &NftThrottle) -> String {
+    format!(
+        "delete element inet {table} {map} {{ {key} }}\n\
+         delete limit inet {table} {limit}\n",
+        table = NFT_TABLE,
+        map = throttle.map,
+        key = throttle.key.element(),
+        limit = throttle.limit_name(),
+    )
+}
+
+/// Everything that sets up the table for `throttles`, in order: the table and
+/// chains, then per throttle its map and rule (first throttle of the map only),
+/// limit object and element
+pub fn ruleset_script(throttles: &[NftThrottle]) -> String {
+    let mut script = table_script();
+    let mut maps = HashSet::new();
+    for throttle in throttles {
+        if maps.insert(throttle.map.as_str()) {
+            script += &map_script(throttle);
+        }
+        script += &add_script(throttle);
+    }
+    script
+}
+
+/// Apply a batch of commands with `nft -f -` (all of them or none)
+fn run_script(script: &str) -> Result<()> {
+    let mut child = Command::new("nft")
+        .args(["-f", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run nft")?;
+    child
+        .stdin
+        .take()
+        .context("no stdin")?
+        .write_all(script.as_bytes())
+        .context("Failed to write nftables commands")?;
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "nft rejected the commands: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// Our table as `nft list table` shows it (None if it doesn't exist)
+fn list_table() -> Result<Option<String>> {
+    let output = Command::new("nft")
+        .args(["list", "table", "inet", NFT_TABLE])
+        .output()
+        .context("Failed to list nftables table")?;
+    Ok(output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned()))
+}
+
+/// Whether a table listing has the chains of the old one-rule-per-throttle layout
+fn is_legacy_layout(listing: &str) -> bool {
+    listing.lines().any(|line| {
+        line.trim()
+            .strip_prefix("chain ")
+            .and_then(|rest| rest.split_whitespace().next())
+            .is_some_and(|chain| LEGACY_CHAINS.contains(&chain))
+    })
+}
+
+/// Initialize the table and chains. A table an older version left in the
+/// one-rule-per-throttle layout is flushed first: its rules can't be adopted, and
+/// the saved throttles are applied again in the new layout
+pub fn init_nft_table() -> Result<()> {
+    if let Some(listing) = list_table()?
+        && is_legacy_layout(&listing)
+    {
+        log::info!("Replacing the nftables table of an older chadthrottle version");
+        run_script(&format!("delete table inet {}\n", NFT_TABLE))
+            .context("Failed to flush the old nftables layout")?;
+    }
+
+    run_script(&table_script()).context("Failed to create nftables table and chains")?;
+    log::info!("Initialized nftables table and chains");
+    Ok(())
+}
+
+/// Whether a map exists in our table
+fn map_exists(map: &str) -> bool {
+    Command::new("nft")
+        .args(["list", "map", "inet", NFT_TABLE, map])
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+/// Add a throttle: its limit object and map element, plus the map and its rule when
+/// this is the first throttle with its matcher and filters
+pub fn add_throttle(throttle: &NftThrottle) -> Result<()> {
+    let mut script = String::new();
+    if !map_exists(&throttle.map) {
+        script += &map_script(throttle);
+    }
+    script += &add_script(throttle);
+    run_script(&script)
+        .with_context(|| format!("Failed to add nftables rate limit for PID {}", throttle.pid))?;
+
+    log::info!(
+        "Added nftables rate limit: {} bytes/sec for PID {} (map {})",
+        throttle.rate_bytes_per_sec,
+        throttle.pid,
+        throttle.map
+    );
+    Ok(())
+}
+
+/// Commands removing a throttle whose element can't be deleted by key: its map is
+/// flushed and refilled with the other elements in `listing` (skipping those whose
+/// cgroup v2 path no longer exists either, which nft would reject as well), then
+/// its limit object is deleted
+fn refill_script(
+    listing: &TableListing,
+    throttle: &NftThrottle,
+    cgroup_exists: impl Fn(&str) -> bool,
+) -> String {
+    let limit = throttle.limit_name();
+    let mut script = format!("flush map inet {} {}\n", NFT_TABLE, throttle.map);
+    for (map, key, element_limit) in &listing.elements {
+        let gone = key
+            .strip_prefix('"')
+            .and_then(|path| path.strip_suffix('"'))
+            .is_some_and(|path| !cgroup_exists(path));
+        if *map == throttle.map && *element_limit != limit && !gone {
+            script += &format!(
+                "add element inet {} {} {{ {} : \"{}\" }}\n",
+                NFT_TABLE, map, key, element_limit
+            );
+        }
+    }
+    script += &format!("delete limit inet {} {}\n", NFT_TABLE, limit);
+    script
+}
+
+/// Remove a throttle's map element and limit object
+///
+/// nft can only delete a cgroup v2 element by a path that still exists, and an
+/// in-place throttle's cgroup (the scope of a flatpak app or container) goes away
+/// when the process exits. If the removal fails, the map is flushed and refilled
+/// without the element instead, so neither the element nor the limit object leaks.
+pub fn remove_throttle(throttle: &NftThrottle) -> Result<()> {
+    if let Err(e) = run_script(&remove_script(throttle)) {
+        log::debug!(
+            "Removing nftables rate limit for PID {} by key failed ({:#}), refilling map {}",
+            throttle.pid,
+            e,
+            throttle.map
+        );
+        let listing = list_table()?
+            .map(|listing| parse_table(&listing))
+            .unwrap_or_default();
+        let cgroup_exists = |path: &str| std::path::Path::new(CGROUP_V2_ROOT).join(path).is_dir();
+        run_script(&refill_script(&listing, throttle, cgroup_exists)).with_context(|| {
+            format!(
+                "Failed to remove nftables rate limit for PID {}",
+                throttle.pid
+            )
+        })?;
+    }
+    log::debug!("Removed nftables rate limit for PID {}", throttle.pid);
+    Ok(())
+}
+
+/// Destinations excluded from Internet-only rules (RFC1918, loopback, link-local,
+/// multicast, reserved)
+const INTERNET_EXCLUDED_V4: &[&str] = &[
+    "10.0.0.0/8",
+    "172.16.0.0/12",
+    "192.168.0.0/16",
+    "127.0.0.0/8",
+    "169.254.0.0/16",
+    "224.0.0.0/4",
+    "240.0.0.0/4",
+];
+const INTERNET_EXCLUDED_V6: &[&str] = &["::1", "fe80::/10", "fc00::/7", "ff00::/8"];
+
+/// Destinations matched by Local-only rules (RFC1918 + link-local)
+const LOCAL_V4: &[&str] = &[
+    "10.0.0.0/8",
+    "172.16.0.0/12",
+    "192.168.0.0/16",
+    "169.254.0.0/16",
+];
+
+/// Build the destination filter for a traffic type, with the user's subnet
+/// overrides applied so rules agree with the monitor's classification
+fn traffic_type_filter(traffic_type: crate::process::TrafficType) -> String {
+    use crate::process::TrafficType;
+    use crate::traffic_classifier::{address_set_with_overrides, subnet_overrides};
+
+    let set = |base: &[&str], ipv4: bool| {
+        address_set_with_overrides(base, ipv4, subnet_overrides()).join(", ")
+    };
+
+    match traffic_type {
+        // No IP filtering - apply to all traffic
+        TrafficType::All => String::new(),
+        // Only throttle non-local IPs (internet traffic)
+        TrafficType::Internet => format!(
+            "ip daddr != {{ {} }} ip6 daddr != {{ {} }} ",
+            set(INTERNET_EXCLUDED_V4, true),
+            set(INTERNET_EXCLUDED_V6, false)
+        ),
+        // Only throttle local network IPs
+        TrafficType::Local => format!("ip daddr {{ {} }} ", set(LOCAL_V4, true)),
+    }
+}
+
+/// Build the interface match for a rule (`oifname`/`iifname` set), empty for all interfaces
+fn interface_filter(direction: Direction, interfaces: Option<&[String]>) -> String {
+    let Some(interfaces) = interfaces.filter(|i| !i.is_empty()) else {
+        return String::new();
+    };
+    let keyword = match direction {
+        Direction::Upload => "oifname",
+        Direction::Download => "iifname",
+    };
+    let names: Vec<String> = interfaces
+        .iter()
+        .map(|name| format!("\"{}\"", name))
+        .collect();
+    format!("{} {{ {} }} ", keyword, names.join(", "))
+}
+
+/// Build the IP version match for a rule (`meta nfproto`), empty for both versions
+fn family_filter(family: crate::process::AddressFamily) -> &'static str {
+    use crate::process::AddressFamily;
+
+    match family {
+        AddressFamily::Any => "",
+        AddressFamily::V4 => "meta nfproto ipv4 ",
+        AddressFamily::V6 => "meta nfproto ipv6 ",
+    }
+}
+
+/// What matters for adoption in a `nft list table` listing
+#[derive(Debug, Default, PartialEq, Eq)]
+struct TableListing {
+    /// Limit objects, with their rate in bytes/sec
+    limits: Vec<(String, Option<u64>)>,
+    /// Map elements: (map, key as listed, limit object)
+    elements: Vec<(String, String, String)>,
+    /// Matches before each map's lookup in its rule, by map
+    filters: HashMap<String, String>,
+}
+
+/// Parse the limit objects, map elements and map rules out of a table listing
+///
+/// ```text
+/// table inet chadthrottle {
+///     limit up_pid_1234 {
+///         rate over 1 mbytes/second
+///     }
+///     map up_v2l2_all {
+///         typeof socket cgroupv2 level 2 : limit
+///         elements = { "chadthrottle/pid_1234" : "up_pid_1234" }
+///     }
+///     chain upload {
+///         type filter hook output priority filter; policy accept;
+///         limit name socket cgroupv2 level 2 map @up_v2l2_all drop
+///     }
+/// }
+/// ```
+fn parse_table(listing: &str) -> TableListing {
+    let mut parsed = TableListing::default();
+    let mut current_limit: Option<usize> = None;
+    let mut current_map: Option<String> = None;
+    let mut elements: Option<String> = None;
+
+    for line in listing.lines().map(str::trim) {
+        // Elements can span several lines, up to the closing brace
+        if let Some(text) = elements.as_mut() {
+            text.push(' ');
+            text.push_str(line);
+        } else if let Some(name) = line
+            .strip_prefix("limit ")
+            .and_then(|rest| rest.strip_suffix('{'))
+        {
+            parsed.limits.push((name.trim().to_string(), None));
+            current_limit = Some(parsed.limits.len() - 1);
+        } else if let Some(name) = line
+            .strip_prefix("map ")
+            .and_then(|rest| rest.strip_suffix('{'))
+        {
+            current_map = Some(name.trim().to_string());
+        } else if let Some(rest) = line.strip_prefix("elements = {") {
+            elements = Some(rest.to_string());
+        } else if line == "}" {
+            current_limit = None;
+            current_map = None;
+        } else if let Some(index) = current_limit {
+            if let Some(rate) = parse_rate(line) {
+                parsed.limits[index].1 = Some(rate);
+            }
+        } else if let Some((filter, rest)) = line.split_once("limit name ")
+            && let Some((_, map)) = rest.split_once(" map @")
+            && let Some(map) = map.split_whitespace().next()
+        {
+            parsed.filters.insert(map.to_string(), filter.to_string());
+        }
+
+        // Elements end at the first closing brace
+        if let Some(text) = elements.as_ref()
+            && let Some((entries, _)) = text.split_once('}')
+        {
+            if let Some(map) = &current_map {
+                for entry in entries.split(',') {
+                    if let Some((key, limit)) = entry.split_once(" : ") {
+                        parsed.elements.push((
+                            map.clone(),
+                            key.trim().to_string(),
+                            limit.trim().trim_matches('"').to_string(),
+                        ));
+                    }
+                }
+            }
+            elements = None;
+        }
+    }
+    parsed
+}
+
+/// Rate of a `rate over N <unit>/second` limit (nft lists rates in the largest whole
+/// unit, e.g. 1048576 bytes/second as "1 mbytes/second")
+fn parse_rate(line: &str) -> Option<u64> {
+    let (_, rest) = line.split_once("rate over ")?;
+    let mut words = rest.split_whitespace();
+    let value: u64 = words.next()?.parse().ok()?;
+    let multiplier = match words.next()?.strip_suffix("/second")? {
+        "bytes" => 1,
+        "kbytes" => 1024,
+        "mbytes" => 1024 * 1024,
+        "gbytes" => 1024 * 1024 * 1024,
+        _ => return None,
+    };
+    value.checked_mul(multiplier)
+}
+
+/// Decide which of the throttles a previous run left in a table listing to keep.
+/// Returns the adopted throttles with their cgroup handles, the commands removing
+/// the rest, and how many were removed
+fn plan_adoption(
+    listing: &TableListing,
+    direction: Direction,
+    adoptable: &HashMap<i32, u64>,
+    cgroups: &[CgroupHandle],
+) -> (Vec<(CgroupHandle, NftThrottle)>, String, usize) {
+    let prefix = format!("{}_pid_", direction.prefix());
+    let mut adopted: Vec<(CgroupHandle, NftThrottle)> = Vec::new();
+    let mut stale: Vec<&str> = Vec::new();
+
+    for (name, rate) in &listing.limits {
+        let Some(pid) = name
+            .strip_prefix(&prefix)
+            .and_then(|pid| pid.parse::<i32>().ok())
+        else {
+            continue;
+        };
+        let references: Vec<&(String, String, String)> = listing
+            .elements
+            .iter()
+            .filter(|(_, _, limit)| limit == name)
+            .collect();
+
+        let adopt = (|| {
+            let limit = *adoptable.get(&pid)?;
+            if *rate != Some(limit)
+                || references.len() != 1
+                || adopted.iter().any(|(handle, _)| handle.pid == pid)
+            {
+                return None;
+            }
+            let (map, key, _) = references[0];
+            let handle = cgroups.iter().find(|handle| {
+                handle.pid == pid
+                    && handle.backend_type != CgroupBackendType::V1
+                    && CgroupKey::from_handle(handle).is_ok_and(|own| own.element() == *key)
+            })?;
+            let throttle = NftThrottle {
+                pid,
+                direction,
+                key: CgroupKey::from_handle(handle).ok()?,
+                rate_bytes_per_sec: limit,
+                filter: listing.filters.get(map).cloned().unwrap_or_default(),
+                map: map.clone(),
+            };
+            Some((handle.clone(), throttle))
+        })();
+
+        match adopt {
+            Some(throttle) => adopted.push(throttle),
+            None => stale.push(name),
+        }
+    }
+
+    // A stale element's cgroup may be gone, and nft can only delete a cgroup v2
+    // element by a path that still exists: the maps holding stale elements are
+    // flushed and refilled with their adopted elements instead
+    let mut script = String::new();
+    let mut flushed: Vec<&str> = Vec::new();
+    for (map, _, limit) in &listing.elements {
+        if stale.contains(&limit.as_str()) && !flushed.contains(&map.as_str()) {
+            flushed.push(map);
+            script += &format!("flush map inet {} {}\n", NFT_TABLE, map);
+            for (_, throttle) in adopted.iter().filter(|(_, t)| &t.map == map) {
+                script += &element_command(throttle);
+            }
+        }
+    }
+    for limit in &stale {
+        script += &format!("delete limit inet {} {}\n", NFT_TABLE, limit);
+    }
+
+    (adopted, script, stale.len())
+}
+
+/// Go through the throttles a previous run left in our table: a limit for a PID in
+/// `adoptable` with the same rate, whose cgroup v2 directory still exists, is kept
+/// and returned with its cgroup handle; the rest are deleted
+///
+/// Cgroup v1 throttles are always deleted: classids are allocated per run, so a new
+/// throttle could be given the one an adopted element matches.
+pub fn adopt_or_clean_throttles(
+    direction: Direction,
+    adoptable: &HashMap<i32, u64>,
+    cgroups: &[CgroupHandle],
+) -> Result<(Vec<(CgroupHandle, NftThrottle)>, usize)> {
+    let Some(listing) = list_table()? else {
+        return Ok((Vec::new(), 0));
+    };
+
+    let (adopted, script, removed) =
+        plan_adoption(&parse_table(&listing), direction, adoptable, cgroups);
+    if !script.is_empty() {
+        run_script(&script).context("Failed to remove stale nftables throttles")?;
+    }
+    for (_, throttle) in &adopted {
+        log::info!(
+            "Adopting leftover nftables throttle for PID {} ({} bytes/sec)",
+            throttle.pid,
+            throttle.rate_bytes_per_sec
+        );
+    }
+    if removed > 0 {
+        log::info!("Removed {} stale nftables throttle(s)", removed);
+    }
+
+    Ok((adopted, removed))
+}
+
+/// Cleanup nftables table
+pub fn cleanup_nft_table() -> Result<()> {
+    // Delete the entire table (ignore errors - may already be deleted by other backend)
+    let result = Command::new("nft")
+        .args(["delete", "table", "inet", NFT_TABLE])
+        .output();
+
+    match result {
+        Ok(output) if output.status.success() => {
+            log::info!("Cleaned up nftables table");
+        }
+        Ok(output) => {
+            // Table already deleted or doesn't exist - this is fine
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if stderr.contains("No such file or directory") {
+                log::debug!("nftables table already cleaned up");
+            } else {
+                log::warn!("nftables cleanup warning: {}", stderr.trim());
+            }
+        }
+        Err(e) => {
+            log::debug!("nftables cleanup error (likely already cleaned): {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::{AddressFamily, TrafficType};
+
+    fn handle(pid: i32, identifier: &str, backend_type: CgroupBackendType) -> CgroupHandle {
+        CgroupHandle {
+            pid,
+            identifier: identifier.to_string(),
+            backend_type,
+            original_cgroup: None,
+            in_place: false,
+        }
+    }
+
+    fn throttle(
+        pid: i32,
+        identifier: &str,
+        backend_type: CgroupBackendType,
+        rate_bytes_per_sec: u64,
+        direction: Direction,
+    ) -> NftThrottle {
+        NftThrottle::new(
+            &handle(pid, identifier, backend_type),
+            rate_bytes_per_sec,
+            direction,
+            TrafficType::All,
+            AddressFamily::Any,
+            None,
+        )
+        .unwrap()
+    }
+
+    fn sample_throttles() -> Vec<NftThrottle> {
+        let eth0 = ["eth0".to_string()];
+        vec![
+            throttle(
+                1234,
+                "chadthrottle/pid_1234",
+                CgroupBackendType::V2Nftables,
+                1024 * 1024,
+                Direction::Upload,
+            ),
+            NftThrottle::new(
+                &handle(99, "chadthrottle/pid_99", CgroupBackendType::V2Nftables),
+                1536,
+                Direction::Upload,
+                TrafficType::Local,
+                AddressFamily::V4,
+                Some(&eth0),
+            )
+            .unwrap(),
+            // Shares the first throttle's map
+            throttle(
+                1235,
+                "chadthrottle/pid_1235",
+                CgroupBackendType::V2Nftables,
+                512_000,
+                Direction::Upload,
+            ),
+            // A sandboxed process, matched in its own (deeper) scope
+            throttle(
+                4242,
+                "user.slice/user-1000.slice/app-flatpak-org.mozilla.firefox-4242.scope",
+                CgroupBackendType::V2Nftables,
+                2048,
+                Direction::Upload,
+            ),
+            throttle(7, "1:10", CgroupBackendType::V1, 4096, Direction::Download),
+        ]
+    }
+
+    #[test]
+    fn test_ruleset_script_golden() {
+        // Changing this text changes what every nftables throttle does: check the
+        // semantics, not just the test
+        let expected = r#"add table inet chadthrottle
+add chain inet chadthrottle upload { type filter hook output priority 0; policy accept; }
+add chain inet chadthrottle download { type filter hook input priority 0; policy accept; }
+add map inet chadthrottle up_v2l2_all { typeof socket cgroupv2 level 2 : limit; }
+add rule inet chadthrottle upload limit name socket cgroupv2 level 2 map @up_v2l2_all drop
+add limit inet chadthrottle up_pid_1234 { rate over 1048576 bytes/second; }
+add element inet chadthrottle up_v2l2_all { "chadthrottle/pid_1234" : "up_pid_1234" }
+add map inet chadthrottle up_v2l2_12743226 { typeof socket cgroupv2 level 2 : limit; }
+add rule inet chadthrottle upload oifname { "eth0" } meta nfproto ipv4 ip daddr { 10.0.0.0/8, 172.16.0.0/12, 192.168.0.0/16, 169.254.0.0/16 } limit name socket cgroupv2 level 2 map @up_v2l2_12743226 drop
+add limit inet chadthrottle up_pid_99 { rate over 1536 bytes/second; }
+add element inet chadthrottle up_v2l2_12743226 { "chadthrottle/pid_99" : "up_pid_99" }
+add limit inet chadthrottle up_pid_1235 { rate over 512000 bytes/second; }
+add element inet chadthrottle up_v2l2_all { "chadthrottle/pid_1235" : "up_pid_1235" }
+add map inet chadthrottle up_v2l3_all { typeof socket cgroupv2 level 3 : limit; }
+add rule inet chadthrottle upload limit name socket cgroupv2 level 3 map @up_v2l3_all drop
+add limit inet chadthrottle up_pid_4242 { rate over 2048 bytes/second; }
+add element inet chadthrottle up_v2l3_all { "user.slice/user-1000.slice/app-flatpak-org.mozilla.firefox-4242.scope" : "up_pid_4242" }
+add map inet chadthrottle down_v1_all { typeof meta cgroup : limit; }
+add rule inet chadthrottle download limit name meta cgroup map @down_v1_all drop
+add limit inet chadthrottle down_pid_7 { rate over 4096 bytes/second; }
+add element inet chadthrottle down_v1_all { 0x0001000a : "down_pid_7" }
+"#;
+        assert_eq!(ruleset_script(&sample_throttles()), expected);
+    }
+
+    #[test]
+    fn test_remove_script_only_touches_the_throttle() {
+        let throttles = sample_throttles();
+        assert_eq!(
+            remove_script(&throttles[2]),
+            "delete element inet chadthrottle up_v2l2_all { \"chadthrottle/pid_1235\" }\n\
+             delete limit inet chadthrottle up_pid_1235\n"
+        );
+    }
+
+    #[test]
+    fn test_refill_script_when_the_cgroup_is_gone() {
+        let listing = parse_table(LISTING);
+        let throttles = sample_throttles();
+        // 1235's cgroup is gone: its map is refilled with 1234's element only
+        let alive = |path: &str| path != "chadthrottle/pid_1235";
+        assert_eq!(
+            refill_script(&listing, &throttles[2], alive),
+            "flush map inet chadthrottle up_v2l2_all\n\
+             add element inet chadthrottle up_v2l2_all { \"chadthrottle/pid_1234\" : \"up_pid_1234\" }\n\
+             delete limit inet chadthrottle up_pid_1235\n"
+        );
+
+        // Elements of other throttles whose cgroup is gone too are left out, or nft
+        // would reject the batch for them
+        let none_alive = |_: &str| false;
+        assert_eq!(
+            refill_script(&listing, &throttles[2], none_alive),
+            "flush map inet chadthrottle up_v2l2_all\n\
+             delete limit inet chadthrottle up_pid_1235\n"
+        );
+    }
+
+    #[test]
+    fn test_legacy_layout_detected() {
+        let legacy = r#"table inet chadthrottle {
+	chain output_limit {
+		type filter hook output priority filter; policy accept;
+		socket cgroupv2 level 0 "chadthrottle/pid_1234" limit rate over 1 mbytes/second drop comment "chadthrottle pid 1234"
+	}
+}"#;
+        assert!(is_legacy_layout(legacy));
+        assert!(!is_legacy_layout(LISTING));
+    }
+
+    const LISTING: &str = r#"table inet chadthrottle {
+	limit up_pid_1234 {
+		rate over 1 mbytes/second
+	}
+	limit up_pid_1235 {
+		rate over 500 kbytes/second
+	}
+	limit up_pid_99 {
+		rate over 1536 bytes/second
+	}
+	limit down_pid_7 {
+		rate over 4 kbytes/second
+	}
+
+	map up_v2l2_all {
+		typeof socket cgroupv2 level 2 : limit
+		elements = { "chadthrottle/pid_1234" : "up_pid_1234",
+			     "chadthrottle/pid_1235" : "up_pid_1235" }
+	}
+
+	map up_v2l2_12743226 {
+		typeof socket cgroupv2 level 2 : limit
+		elements = { "chadthrottle/pid_99" : "up_pid_99" }
+	}
+
+	chain upload {
+		type filter hook output priority filter; policy accept;
+		limit name socket cgroupv2 level 2 map @up_v2l2_all drop
+		oifname "eth0" meta nfproto ipv4 limit name socket cgroupv2 level 2 map @up_v2l2_12743226 drop
+	}
+
+	chain download {
+		type filter hook input priority filter; policy accept;
+	}
+}"#;
+
+    #[test]
+    fn test_parse_table() {
+        let parsed = parse_table(LISTING);
+        assert_eq!(
+            parsed.limits,
+            vec![
+                ("up_pid_1234".to_string(), Some(1024 * 1024)),
+                ("up_pid_1235".to_string(), Some(512_000)),
+                ("up_pid_99".to_string(), Some(1536)),
+                ("down_pid_7".to_string(), Some(4096)),
+            ]
+        );
+        let element = |map: &str, key: &str, limit: &str| {
+            (map.to_string(), key.to_string(), limit.to_string())
+        };
+        assert_eq!(
+            parsed.elements,
+            vec![
+                element("up_v2l2_all", "\"chadthrottle/pid_1234\"", "up_pid_1234"),
+                element("up_v2l2_all", "\"chadthrottle/pid_1235\"", "up_pid_1235"),
+                element("up_v2l2_12743226", "\"chadthrottle/pid_99\"", "up_pid_99"),
+            ]
+        );
+        assert_eq!(
+            parsed.filters.get("up_v2l2_12743226").map(String::as_str),
+            Some("oifname \"eth0\" meta nfproto ipv4 ")
+        );
+        assert_eq!(parse_rate("rate over 3 kbytes/second"), Some(3072));
+        assert_eq!(parse_rate("rate 3 kbytes/second"), None);
+    }
+
+    #[test]
+    fn test_plan_adoption_keeps_matching_throttles() {
+        let listing = parse_table(LISTING);
+        // 1234 still matches; 1235's limit changed; 99's cgroup is gone
+        let adoptable = HashMap::from([(1234, 1024 * 1024), (1235, 1024), (99, 1536)]);
+        let cgroups = vec![
+            handle(1234, "chadthrottle/pid_1234", CgroupBackendType::V2Nftables),
+            handle(1235, "chadthrottle/pid_1235", CgroupBackendType::V2Nftables),
+        ];
+
+        let (adopted, script, removed) =
+            plan_adoption(&listing, Direction::Upload, &adoptable, &cgroups);
+        let adopted: Vec<(i32, NftThrottle)> = adopted
+            .into_iter()
+            .map(|(handle, throttle)| (handle.pid, throttle))
+            .collect();
+        assert_eq!(
+            adopted,
+            vec![(
+                1234,
+                throttle(
+                    1234,
+                    "chadthrottle/pid_1234",
+                    CgroupBackendType::V2Nftables,
+                    1024 * 1024,
+                    Direction::Upload,
+                )
+            )]
+        );
+        assert_eq!(removed, 2);
+        // The download limit belongs to the download backend
+        assert_eq!(
+            script,
+            "flush map inet chadthrottle up_v2l2_all\n\
+             add element inet chadthrottle up_v2l2_all { \"chadthrottle/pid_1234\" : \"up_pid_1234\" }\n\
+             flush map inet chadthrottle up_v2l2_12743226\n\
+             delete limit inet chadthrottle up_pid_1235\n\
+             delete limit inet chadthrottle up_pid_99\n"
+        );
+    }
+}
@@ -0,0 +1,496 @@
+// Shared utilities for Linux TC (traffic control) and cgroup operations
+
+use anyhow::{anyhow, Context, Result};
+use std::fs;
+use std::process::Command;
+
+pub const CGROUP_BASE: &str = "/sys/fs/cgroup/net_cls/chadthrottle";
+
+/// Detect the primary network interface
+/// Prefers interfaces with IPv4 addresses to match monitor behavior
+pub fn detect_interface() -> Result<String> {
+    use pnet::datalink;
+
+    let interfaces = datalink::interfaces();
+
+    // First priority: Interface with IPv4 address (most traffic is IPv4)
+    // This matches the monitor's interface selection logic
+    if let Some(iface) = interfaces.iter().find(|iface| {
+        iface.is_up() && !iface.is_loopback() && iface.ips.iter().any(|ip| ip.is_ipv4())
+    }) {
+        log::debug!("TC backends using IPv4 interface: {}", iface.name);
+        return Ok(iface.name.clone());
+    }
+
+    // Fallback: Any interface with IPs (even IPv6-only)
+    if let Some(iface) = interfaces
+        .into_iter()
+        .find(|iface| iface.is_up() && !iface.is_loopback() && !iface.ips.is_empty())
+    {
+        log::warn!("No IPv4 interface found, using: {}", iface.name);
+        return Ok(iface.name);
+    }
+
+    Err(anyhow!("No suitable network interface found"))
+}
+
+/// Check if TC (traffic control) is available
+pub fn check_tc_available() -> bool {
+    Command::new("tc").arg("qdisc").arg("show").output().is_ok()
+}
+
+/// Check if cgroups net_cls is available  
+pub fn check_cgroups_available() -> bool {
+    std::path::Path::new("/sys/fs/cgroup/net_cls").exists()
+}
+
+/// Create a cgroup for a process
+pub fn create_cgroup(pid: i32) -> Result<String> {
+    let cgroup_name = format!("pid_{}", pid);
+    let cgroup_path = format!("{}/{}", CGROUP_BASE, cgroup_name);
+
+    // Create base directory if it doesn't exist
+    fs::create_dir_all(CGROUP_BASE).context("Failed to create cgroup base directory")?;
+
+    // Create cgroup directory for this process
+    fs::create_dir_all(&cgroup_path)
+        .context(format!("Failed to create cgroup at {}", cgroup_path))?;
+
+    Ok(cgroup_path)
+}
+
+/// Set the network classid for a cgroup
+pub fn set_cgroup_classid(cgroup_path: &str, classid: u32) -> Result<()> {
+    let classid_file = format!("{}/net_cls.classid", cgroup_path);
+
+    // classid format: 0xAAAABBBB where AAAA is major, BBBB is minor
+    // We use 1:classid, so 0x0001XXXX
+    let classid_value = format!("{}", (1 << 16) | classid);
+
+    fs::write(&classid_file, classid_value)
+        .context(format!("Failed to set classid in {}", classid_file))?;
+
+    Ok(())
+}
+
+/// Move a process to a cgroup
+pub fn move_process_to_cgroup(pid: i32, cgroup_path: &str) -> Result<()> {
+    let procs_file = format!("{}/cgroup.procs", cgroup_path);
+
+    fs::write(&procs_file, format!("{}", pid))
+        .context(format!("Failed to move process {} to cgroup", pid))?;
+
+    Ok(())
+}
+
+/// Setup TC root HTB qdisc on an interface (for egress/upload)
+pub fn setup_tc_htb_root(interface: &str) -> Result<()> {
+    // Check if HTB qdisc already exists
+    let check_qdisc = Command::new("tc")
+        .args(&["qdisc", "show", "dev", interface])
+        .output()
+        .context("Failed to check existing qdiscs")?;
+
+    let output = String::from_utf8_lossy(&check_qdisc.stdout);
+
+    // If HTB not present, add it
+    if !output.contains("htb") {
+        // Remove any existing root qdisc first
+        let _ = Command::new("tc")
+            .args(&["qdisc", "del", "dev", interface, "root"])
+            .output();
+
+        // Create HTB (Hierarchical Token Bucket) qdisc
+        let status = Command::new("tc")
+            .args(&[
+                "qdisc", "add", "dev", interface, "root", "handle", "1:", "htb", "default", "999",
+            ])
+            .status()
+            .context("Failed to create HTB qdisc")?;
+
+        if !status.success() {
+            return Err(anyhow!("Failed to setup TC root qdisc"));
+        }
+    }
+
+    // Add IPv4 cgroup filter
+    let _ = Command::new("tc")
+        .args(&[
+            "filter", "add", "dev", interface, "parent", "1:", "protocol", "ip", "prio", "1",
+            "handle", "1:", "cgroup",
+        ])
+        .status();
+
+    // Add IPv6 cgroup filter
+    let _ = Command::new("tc")
+        .args(&[
+            "filter", "add", "dev", interface, "parent", "1:", "protocol", "ipv6", "prio", "1",
+            "handle", "2:", "cgroup",
+        ])
+        .status();
+
+    Ok(())
+}
+
+/// Convert bytes/sec to an HTB rate in kbit, rounded up so a slow limit never
+/// becomes 0 (which means "no limit" to `create_tc_class`)
+pub fn bytes_to_kbit(bytes_per_sec: u64) -> u32 {
+    u32::try_from(bytes_per_sec.saturating_mul(8).div_ceil(1000))
+        .unwrap_or(u32::MAX)
+        .max(1)
+}
+
+/// Create a TC HTB class for rate limiting on an interface (`prio` 0-7, lower is
+/// served first)
+pub fn create_tc_class(
+    interface: &str,
+    classid: u32,
+    rate_kbps: u32,
+    prio: u32,
+    parent_handle: &str,
+) -> Result<()> {
+    if rate_kbps == 0 {
+        return Ok(()); // No limit
+    }
+
+    let rate = format!("{}kbit", rate_kbps);
+
+    let status = Command::new("tc")
+        .args(&[
+            "class",
+            "add",
+            "dev",
+            interface,
+            "parent",
+            parent_handle,
+            "classid",
+            &format!("{}:{}", parent_handle.trim_end_matches(':'), classid),
+            "htb",
+            "rate",
+            &rate,
+            "ceil",
+            &rate, // Ceiling = no bursting above rate
+            "prio",
+            &prio.to_string(),
+        ])
+        .status()
+        .context("Failed to create TC class")?;
+
+    if !status.success() {
+        return Err(anyhow!("Failed to create TC class for classid {}", classid));
+    }
+
+    Ok(())
+}
+
+/// Create a TC HTB class that is guaranteed `rate_kbps` and may borrow up to `ceil_kbps`
+/// from its parent (`parent_handle` is a full class id such as "1:9999")
+pub fn create_tc_borrowing_class(
+    interface: &str,
+    classid: u32,
+    rate_kbps: u32,
+    ceil_kbps: u32,
+    prio: u32,
+    parent_handle: &str,
+) -> Result<()> {
+    if rate_kbps == 0 {
+        return Ok(()); // No limit
+    }
+
+    let status = Command::new("tc")
+        .args([
+            "class",
+            "add",
+            "dev",
+            interface,
+            "parent",
+            parent_handle,
+            "classid",
+            &format!("1:{}", classid),
+            "htb",
+            "rate",
+            &format!("{}kbit", rate_kbps),
+            "ceil",
+            &format!("{}kbit", ceil_kbps.max(rate_kbps)),
+            "prio",
+            &prio.to_string(),
+        ])
+        .status()
+        .context("Failed to create TC class")?;
+
+    if !status.success() {
+        return Err(anyhow!("Failed to create TC class for classid {}", classid));
+    }
+
+    Ok(())
+}
+
+/// Create or update an HTB class directly under the root qdisc with rate = ceil
+/// (used as a shared ceiling for child classes)
+pub fn replace_tc_root_class(interface: &str, classid: u32, rate_kbps: u32) -> Result<()> {
+    let rate = format!("{}kbit", rate_kbps);
+    let classid = format!("1:{}", classid);
+
+    let status = Command::new("tc")
+        .args([
+            "class", "replace", "dev", interface, "parent", "1:", "classid", &classid, "htb",
+            "rate", &rate, "ceil", &rate,
+        ])
+        .status()
+        .context("Failed to create TC class")?;
+
+    if !status.success() {
+        return Err(anyhow!("Failed to create TC class {}", classid));
+    }
+
+    Ok(())
+}
+
+/// Remove a TC class
+pub fn remove_tc_class(interface: &str, classid: u32, parent_handle: &str) -> Result<()> {
+    let _ = Command::new("tc")
+        .args(&[
+            "class",
+            "del",
+            "dev",
+            interface,
+            "parent",
+            parent_handle,
+            "classid",
+            &format!("{}:{}", parent_handle.trim_end_matches(':'), classid),
+        ])
+        .status();
+
+    Ok(())
+}
+
+/// Minor ids of the HTB classes under the `major:` qdisc on an interface
+pub fn list_htb_classids(interface: &str, major: u32) -> Result<Vec<u32>> {
+    let output = Command::new("tc")
+        .args(["class", "show", "dev", interface])
+        .output()
+        .context("Failed to list TC classes")?;
+    if !output.status.success() {
+        return Ok(Vec::new());
+    }
+
+    Ok(parse_htb_classids(
+        &String::from_utf8_lossy(&output.stdout),
+        major,
+    ))
+}
+
+/// Parse class ids out of `tc class show` ("class htb 1:100 root rate 8Mbit ceil ...")
+fn parse_htb_classids(listing: &str, major: u32) -> Vec<u32> {
+    listing
+        .lines()
+        .filter_map(|line| {
+            let mut words = line.split_whitespace();
+            if words.next()? != "class" || words.next()? != "htb" {
+                return None;
+            }
+            let (class_major, minor) = words.next()?.split_once(':')?;
+            if class_major.parse::<u32>().ok()? != major {
+                return None;
+            }
+            minor.parse().ok()
+        })
+        .collect()
+}
+
+/// Counters of one TC class from `tc -s class show`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TcClassStats {
+    pub sent_bytes: u64,
+    pub sent_packets: u64,
+    pub dropped_packets: u64,
+    pub backlog_bytes: u64,
+}
+
+/// Statistics of the HTB class `major:minor` on an interface (None if it doesn't exist)
+pub fn htb_class_stats(interface: &str, major: u32, minor: u32) -> Result<Option<TcClassStats>> {
+    let output = Command::new("tc")
+        .args([
+            "-s",
+            "class",
+            "show",
+            "dev",
+            interface,
+            "classid",
+            &format!("{}:{}", major, minor),
+        ])
+        .output()
+        .context("Failed to read TC class statistics")?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+
+    Ok(parse_class_stats(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parse the statistics of a single class:
+///
+/// ```text
+/// class htb 1:100 root prio 0 rate 8Mbit ceil 8Mbit burst 1600b cburst 1600b
+///  Sent 123456 bytes 321 pkt (dropped 2, overlimits 40 requeues 0)
+///  backlog 3028b 2p requeues 0
+/// ```
+fn parse_class_stats(listing: &str) -> Option<TcClassStats> {
+    let mut stats = None;
+    for line in listing.lines() {
+        let words: Vec<&str> = line
+            .split(|c: char| c.is_whitespace() || c == ',' || c == '(' || c == ')')
+            .filter(|word| !word.is_empty())
+            .collect();
+        match words.as_slice() {
+            ["class", ..] => stats = Some(TcClassStats::default()),
+            ["Sent", bytes, "bytes", packets, "pkt", rest @ ..] => {
+                let stats = stats.as_mut()?;
+                stats.sent_bytes = bytes.parse().ok()?;
+                stats.sent_packets = packets.parse().ok()?;
+                if let ["dropped", dropped, ..] = rest {
+                    stats.dropped_packets = dropped.parse().ok()?;
+                }
+            }
+            ["backlog", size, ..] => stats.as_mut()?.backlog_bytes = parse_tc_size(size)?,
+            _ => {}
+        }
+    }
+    stats
+}
+
+/// Parse a size as tc prints it ("1514b", "29Kb", "2Mb"; multiples of 1024)
+fn parse_tc_size(size: &str) -> Option<u64> {
+    let (number, multiplier) = if let Some(number) = size.strip_suffix("Gb") {
+        (number, 1024 * 1024 * 1024)
+    } else if let Some(number) = size.strip_suffix("Mb") {
+        (number, 1024 * 1024)
+    } else if let Some(number) = size.strip_suffix("Kb") {
+        (number, 1024)
+    } else {
+        (size.strip_suffix('b').unwrap_or(size), 1)
+    };
+    let number: f64 = number.parse().ok()?;
+    Some((number * multiplier as f64) as u64)
+}
+
+/// Estimated time in ms for a backlog to drain at the class limit
+pub fn queue_delay_ms(backlog_bytes: u64, limit_bytes_per_sec: u64) -> u64 {
+    backlog_bytes.saturating_mul(1000) / limit_bytes_per_sec.max(1)
+}
+
+/// Throttle statistics from the classes of one throttle (e.g. one per interface)
+pub fn shaping_stats(
+    classes: impl IntoIterator<Item = TcClassStats>,
+    limit_bytes_per_sec: u64,
+) -> super::BackendStats {
+    let mut stats = super::BackendStats::default();
+    let mut backlog_bytes = 0;
+    for class in classes {
+        stats.bytes_total += class.sent_bytes;
+        stats.packets_total += class.sent_packets + class.dropped_packets;
+        stats.packets_dropped += class.dropped_packets;
+        backlog_bytes += class.backlog_bytes;
+    }
+    stats.backlog_bytes = Some(backlog_bytes);
+    stats.queue_delay_estimate_ms = Some(queue_delay_ms(backlog_bytes, limit_bytes_per_sec));
+    stats
+}
+
+/// Remove a cgroup
+pub fn remove_cgroup(cgroup_path: &str) -> Result<()> {
+    if let Err(e) = fs::remove_dir(cgroup_path) {
+        log::error!("Warning: Failed to remove cgroup {}: {}", cgroup_path, e);
+    }
+    Ok(())
+}
+
+/// Check if IFB module is available
+pub fn check_ifb_availability() -> bool {
+    // Try to load the module first
+    let _ = Command::new("modprobe")
+        .arg("ifb")
+        .arg("numifbs=1")
+        .output();
+
+    // Check if we can create an IFB device (or if one exists)
+    let check = Command::new("ip")
+        .args(&["link", "add", "name", "ifb_test", "type", "ifb"])
+        .output();
+
+    if let Ok(output) = check {
+        if output.status.success() {
+            // Clean up test device
+            let _ = Command::new("ip")
+                .args(&["link", "del", "ifb_test"])
+                .output();
+            return true;
+        }
+    }
+
+    // Also check if IFB device already exists
+    let check_existing = Command::new("ip")
+        .args(&["link", "show", "type", "ifb"])
+        .output();
+
+    if let Ok(output) = check_existing {
+        if output.status.success() && !output.stdout.is_empty() {
+            return true;
+        }
+    }
+
+    log::debug!("IFB module not found");
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bytes_to_kbit_rounds_up() {
+        // 128 B/s = 1.024 kbit
+        assert_eq!(bytes_to_kbit(128), 2);
+        assert_eq!(bytes_to_kbit(125), 1);
+        assert_eq!(bytes_to_kbit(1), 1);
+        assert_eq!(bytes_to_kbit(0), 1);
+        assert_eq!(bytes_to_kbit(1024 * 1024), 8389);
+        assert_eq!(bytes_to_kbit(u64::MAX), u32::MAX);
+    }
+
+    #[test]
+    fn test_parse_htb_classids() {
+        let listing = "class htb 1:100 root prio 0 rate 8Mbit ceil 8Mbit burst 1600b cburst 1600b\n\
+                       class htb 1:9999 root rate 80Mbit ceil 80Mbit burst 1600b cburst 1600b\n\
+                       class htb 1:101 parent 1:9999 prio 0 rate 2Mbit ceil 80Mbit\n\
+                       class htb 2:100 root prio 0 rate 1Mbit ceil 1Mbit\n\
+                       class fq_codel 8001:1 parent 8001:\n";
+        assert_eq!(parse_htb_classids(listing, 1), vec![100, 9999, 101]);
+        assert_eq!(parse_htb_classids(listing, 2), vec![100]);
+    }
+
+    #[test]
+    fn test_parse_class_stats() {
+        let listing = "class htb 1:100 root leaf 8001: prio 0 rate 8Mbit ceil 8Mbit burst 1600b cburst 1600b \n \
+                       Sent 123456 bytes 321 pkt (dropped 2, overlimits 40 requeues 0) \n \
+                       backlog 29Kb 20p requeues 0\n \
+                       lended: 321 borrowed: 0 giants: 0\n";
+        assert_eq!(
+            parse_class_stats(listing),
+            Some(TcClassStats {
+                sent_bytes: 123456,
+                sent_packets: 321,
+                dropped_packets: 2,
+                backlog_bytes: 29 * 1024,
+            })
+        );
+        assert_eq!(parse_class_stats(""), None);
+
+        assert_eq!(parse_tc_size("1514b"), Some(1514));
+        assert_eq!(parse_tc_size("1.5Mb"), Some(1536 * 1024));
+        assert_eq!(parse_tc_size("0"), Some(0));
+
+        // 64 KiB queued at 128 KiB/s takes half a second to drain
+        assert_eq!(queue_delay_ms(64 * 1024, 128 * 1024), 500);
+    }
+}
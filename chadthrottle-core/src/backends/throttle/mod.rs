@@ -0,0 +1,1006 @@
+// Throttling backend traits and implementations
+
+use super::{
+    ActiveThrottle, BackendCapabilities, BackendPriority, PolicySupport, TrafficTypeSupport,
+};
+use crate::backends::cgroup::CgroupInfo;
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+
+pub mod download;
+pub mod error;
+pub mod external;
+pub mod manager;
+pub mod upload;
+
+#[cfg(target_os = "linux")]
+pub mod linux_tc_utils;
+
+#[cfg(target_os = "linux")]
+pub mod linux_nft_utils;
+
+#[cfg(all(target_os = "linux", feature = "throttle-ebpf"))]
+pub mod linux_ebpf_utils;
+
+#[cfg(feature = "throttle-proxy")]
+pub mod proxy_server;
+
+#[cfg(all(unix, feature = "throttle-signal"))]
+pub mod signal_pacer;
+
+#[cfg(all(target_os = "linux", feature = "throttle-ebpf"))]
+pub use linux_ebpf_utils::{init_bpf_config, BpfAttachMethod, BpfConfig};
+
+/// Throttle count tc and nftables backends report as their capacity. Neither has a
+/// fixed-size table, but every packet walks the classes or rules, so thousands of
+/// throttles slow down all traffic on the interface
+pub const SOFT_THROTTLE_LIMIT: usize = 10_000;
+
+// Re-export manager
+pub use error::{ThrottleError, ThrottleResult};
+pub use manager::ThrottleManager;
+
+/// Throttle statistics for a process/cgroup
+#[derive(Debug, Clone, Default)]
+pub struct BackendStats {
+    pub packets_total: u64,
+    pub bytes_total: u64,
+    pub packets_dropped: u64,
+    pub bytes_dropped: u64,
+    // Why packets passed or dropped (only collected by the eBPF backends)
+    /// Passed because the token bucket had enough tokens
+    pub passed_with_tokens: u64,
+    /// Dropped because the token bucket was out of tokens
+    pub dropped_no_tokens: u64,
+    /// Passed because they didn't match the throttle's traffic type
+    pub passed_filter_mismatch: u64,
+    /// Times the throttle was found with a stale cgroup id and repaired
+    pub cgroup_repairs: u64,
+    /// Times the program was re-attached after it stopped seeing traffic
+    pub reattachments: u64,
+    // Queue state (only reported by backends that shape, i.e. queue instead of drop)
+    /// Bytes waiting in the throttle's queue
+    pub backlog_bytes: Option<u64>,
+    /// How long the current backlog takes to drain at the limit
+    pub queue_delay_estimate_ms: Option<u64>,
+}
+
+/// What a backend found left installed by a previous run (e.g. after a crash)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AdoptionReport {
+    /// PIDs whose leftover throttle was taken over as an active throttle
+    pub adopted: Vec<i32>,
+    /// Leftover rules/classes that were removed
+    pub removed: usize,
+}
+
+/// Upload (egress) throttling backend trait
+pub trait UploadThrottleBackend: Send + Sync {
+    /// Backend name (e.g., "tc_htb", "ebpf_cgroup", "wfp")
+    fn name(&self) -> &'static str;
+
+    /// Backend priority for auto-selection
+    fn priority(&self) -> BackendPriority;
+
+    /// Check if this backend is available on the current system
+    fn is_available() -> bool
+    where
+        Self: Sized;
+
+    /// Human-readable reason this backend is unavailable (for diagnostics)
+    /// Only called if `is_available()` returns false
+    fn unavailable_reason() -> String
+    where
+        Self: Sized,
+    {
+        "unknown".to_string()
+    }
+
+    /// Get backend capabilities
+    fn capabilities(&self) -> BackendCapabilities;
+
+    /// Over-limit policies this backend can enforce
+    /// Default implementation: drop only (police/filter style backends)
+    fn policy_support(&self) -> PolicySupport {
+        PolicySupport::DROP_ONLY
+    }
+
+    /// Initialize the backend
+    fn init(&mut self) -> ThrottleResult<()>;
+
+    /// Apply upload throttle to a process
+    fn throttle_upload(
+        &mut self,
+        pid: i32,
+        process_name: String,
+        limit_bytes_per_sec: u64,
+        traffic_type: crate::process::TrafficType,
+    ) -> ThrottleResult<()>;
+
+    /// Find what a previous run of this backend left installed. Leftovers for a PID in
+    /// `adoptable` (PID -> limit in bytes/sec) with the same limit are taken over as
+    /// active throttles, everything else is removed. Called once, right after init()
+    /// Default implementation: nothing is left behind
+    fn adopt_or_clean(&mut self, _adoptable: &HashMap<i32, u64>) -> ThrottleResult<AdoptionReport> {
+        Ok(AdoptionReport::default())
+    }
+
+    /// Remove upload throttle from a process
+    fn remove_upload_throttle(&mut self, pid: i32) -> ThrottleResult<()>;
+
+    /// Add another process (e.g. a child) to a throttled process's cgroup so both
+    /// share the throttle's limit
+    /// Default implementation: backends without their own cgroups can't share throttles
+    fn add_to_throttle(&mut self, _pid: i32, _member_pid: i32) -> ThrottleResult<()> {
+        Err(anyhow::anyhow!(
+            "{} backend cannot share a throttle between processes",
+            self.name()
+        )
+        .into())
+    }
+
+    /// Get active upload throttle for a process
+    fn get_upload_throttle(&self, pid: i32) -> Option<u64>;
+
+    /// Get all active upload throttles
+    fn get_all_throttles(&self) -> HashMap<i32, u64>;
+
+    /// Cleanup on shutdown
+    fn cleanup(&mut self) -> ThrottleResult<()>;
+
+    /// Get statistics for a throttled process (if supported by backend)
+    fn get_stats(&self, _pid: i32) -> Option<BackendStats> {
+        None // Default implementation returns None for backends that don't support stats
+    }
+
+    /// Detect and repair throttles whose in-kernel state no longer matches what was
+    /// configured (e.g. a stale cgroup id or attachment), returning the repaired PIDs.
+    /// `flowing` holds the PIDs that had traffic in this direction since the last call
+    /// Default implementation does nothing - only the eBPF backend implements this
+    fn repair_throttles(&mut self, _flowing: &HashSet<i32>) -> ThrottleResult<Vec<i32>> {
+        Ok(Vec::new())
+    }
+
+    /// Move per-CPU throttle state (rate shares and unused tokens) to the CPUs the
+    /// traffic is on. Called about once a second while throttles are active
+    /// Default implementation does nothing - only the eBPF backend implements this
+    fn rebalance_throttles(&mut self) -> ThrottleResult<()> {
+        Ok(())
+    }
+
+    /// Feed the rates (bytes/sec) measured in this direction for throttled processes to
+    /// backends that enforce limits from userspace. Called on every monitor update
+    /// Default implementation does nothing - only the signal backend uses the rates
+    fn observe_rates(&mut self, _rates: &HashMap<i32, u64>) {}
+
+    /// How the backend's programs ended up attached (e.g. "link" or "legacy fallback")
+    /// while it has any attached. Only the eBPF backend attaches programs
+    fn attach_method(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Most throttles the backend can hold at once (None = no limit)
+    /// Default implementation: unlimited
+    fn max_throttles(&self) -> Option<usize> {
+        None
+    }
+
+    /// How many throttles the backend holds, counted against `max_throttles`
+    fn active_count(&self) -> usize {
+        self.get_all_throttles().len()
+    }
+
+    /// Check if this backend supports a specific traffic type
+    /// Default implementation: only supports TrafficType::All
+    fn supports_traffic_type(&self, traffic_type: crate::process::TrafficType) -> bool {
+        use crate::process::TrafficType;
+        traffic_type == TrafficType::All
+    }
+
+    /// Check if this backend can restrict a throttle to the given interfaces
+    /// Default implementation: interface-agnostic backends can't
+    fn supports_interfaces(&self, _interfaces: &[String]) -> bool {
+        false
+    }
+
+    /// Apply upload throttle to a process on the given interfaces only, with an
+    /// over-limit policy the backend's `policy_support` allows
+    fn throttle_upload_on_interfaces(
+        &mut self,
+        _pid: i32,
+        _process_name: String,
+        _limit_bytes_per_sec: u64,
+        _traffic_type: crate::process::TrafficType,
+        _interfaces: &[String],
+        _policy: crate::process::Policy,
+    ) -> ThrottleResult<()> {
+        Err(anyhow::anyhow!(
+            "{} backend cannot restrict throttles to specific interfaces",
+            self.name()
+        )
+        .into())
+    }
+
+    /// Apply upload throttle to a process with a specific over-limit policy
+    /// Default implementation: only the backend's default policy is supported
+    fn throttle_upload_with_policy(
+        &mut self,
+        pid: i32,
+        process_name: String,
+        limit_bytes_per_sec: u64,
+        traffic_type: crate::process::TrafficType,
+        policy: crate::process::Policy,
+    ) -> ThrottleResult<()> {
+        if policy != self.policy_support().default {
+            return Err(anyhow::anyhow!(
+                "{} backend cannot {} traffic over the limit",
+                self.name(),
+                policy
+            )
+            .into());
+        }
+        self.throttle_upload(pid, process_name, limit_bytes_per_sec, traffic_type)
+    }
+
+    /// Check if this backend can restrict a throttle to one IP version
+    /// Default implementation: only throttles covering both IPv4 and IPv6
+    fn supports_address_family(&self, family: crate::process::AddressFamily) -> bool {
+        family == crate::process::AddressFamily::Any
+    }
+
+    /// Apply upload throttle to a process's traffic of one IP version only, optionally
+    /// restricted to the given interfaces, with an over-limit policy the backend's
+    /// `policy_support` allows
+    #[allow(clippy::too_many_arguments)]
+    fn throttle_upload_for_family(
+        &mut self,
+        _pid: i32,
+        _process_name: String,
+        _limit_bytes_per_sec: u64,
+        _traffic_type: crate::process::TrafficType,
+        family: crate::process::AddressFamily,
+        _interfaces: Option<&[String]>,
+        _policy: crate::process::Policy,
+    ) -> ThrottleResult<()> {
+        Err(anyhow::anyhow!(
+            "{} backend cannot restrict throttles to {}",
+            self.name(),
+            family
+        )
+        .into())
+    }
+
+    /// Set per-interface ceilings (bytes/sec) shared by all throttled processes.
+    /// An interface missing from `ceilings` has its ceiling removed.
+    /// Default implementation: ignored by backends without a class hierarchy
+    fn set_interface_ceilings(&mut self, _ceilings: &HashMap<String, u64>) -> ThrottleResult<()> {
+        Ok(())
+    }
+
+    /// Ceiling in effect on the backend's interface: (interface, bytes/sec)
+    fn interface_ceiling(&self) -> Option<(String, u64)> {
+        None
+    }
+
+    /// Interfaces the backend has set up qdiscs on, checked for foreign shaping
+    /// Default implementation: none (cgroup and nftables backends aren't tied to one)
+    fn managed_interfaces(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Check if this backend can guarantee a throttled process a minimum rate
+    /// Default implementation: token bucket and police style backends only cap
+    fn supports_minimum_rate(&self) -> bool {
+        false
+    }
+
+    /// Guarantee a throttled process `minimum_bytes_per_sec` of its limit
+    /// (None = no guarantee)
+    fn set_upload_minimum(
+        &mut self,
+        _pid: i32,
+        _minimum_bytes_per_sec: Option<u64>,
+    ) -> ThrottleResult<()> {
+        Err(anyhow::anyhow!("{} backend cannot guarantee a minimum rate", self.name()).into())
+    }
+
+    /// Check if this backend can rank throttled processes against each other
+    /// Default implementation: token bucket and police style backends have no scheduler
+    fn supports_throttle_priority(&self) -> bool {
+        false
+    }
+
+    /// Change the priority of a throttled process's class
+    fn set_upload_throttle_priority(
+        &mut self,
+        _pid: i32,
+        _priority: crate::process::Priority,
+    ) -> ThrottleResult<()> {
+        Err(anyhow::anyhow!("{} backend cannot prioritize throttles", self.name()).into())
+    }
+}
+
+/// Download (ingress) throttling backend trait
+pub trait DownloadThrottleBackend: Send + Sync {
+    /// Backend name (e.g., "ifb_tc", "ebpf_cgroup", "ebpf_xdp")
+    fn name(&self) -> &'static str;
+
+    /// Backend priority for auto-selection
+    fn priority(&self) -> BackendPriority;
+
+    /// Check if this backend is available on the current system
+    fn is_available() -> bool
+    where
+        Self: Sized;
+
+    /// Human-readable reason this backend is unavailable (for diagnostics)
+    /// Only called if `is_available()` returns false
+    fn unavailable_reason() -> String
+    where
+        Self: Sized,
+    {
+        "unknown".to_string()
+    }
+
+    /// Get backend capabilities
+    fn capabilities(&self) -> BackendCapabilities;
+
+    /// Over-limit policies this backend can enforce
+    /// Default implementation: drop only (police/filter style backends)
+    fn policy_support(&self) -> PolicySupport {
+        PolicySupport::DROP_ONLY
+    }
+
+    /// Initialize the backend
+    fn init(&mut self) -> ThrottleResult<()>;
+
+    /// Apply download throttle to a process
+    fn throttle_download(
+        &mut self,
+        pid: i32,
+        process_name: String,
+        limit_bytes_per_sec: u64,
+        traffic_type: crate::process::TrafficType,
+    ) -> ThrottleResult<()>;
+
+    /// Find what a previous run of this backend left installed. Leftovers for a PID in
+    /// `adoptable` (PID -> limit in bytes/sec) with the same limit are taken over as
+    /// active throttles, everything else is removed. Called once, right after init()
+    /// Default implementation: nothing is left behind
+    fn adopt_or_clean(&mut self, _adoptable: &HashMap<i32, u64>) -> ThrottleResult<AdoptionReport> {
+        Ok(AdoptionReport::default())
+    }
+
+    /// Remove download throttle from a process
+    fn remove_download_throttle(&mut self, pid: i32) -> ThrottleResult<()>;
+
+    /// Add another process (e.g. a child) to a throttled process's cgroup so both
+    /// share the throttle's limit
+    /// Default implementation: backends without their own cgroups can't share throttles
+    fn add_to_throttle(&mut self, _pid: i32, _member_pid: i32) -> ThrottleResult<()> {
+        Err(anyhow::anyhow!(
+            "{} backend cannot share a throttle between processes",
+            self.name()
+        )
+        .into())
+    }
+
+    /// Get active download throttle for a process
+    fn get_download_throttle(&self, pid: i32) -> Option<u64>;
+
+    /// Get all active download throttles
+    fn get_all_throttles(&self) -> HashMap<i32, u64>;
+
+    /// Cleanup on shutdown
+    fn cleanup(&mut self) -> ThrottleResult<()>;
+
+    /// Get statistics for a throttled process (if supported by backend)
+    fn get_stats(&self, _pid: i32) -> Option<BackendStats> {
+        None // Default implementation returns None for backends that don't support stats
+    }
+
+    /// Detect and repair throttles whose in-kernel state no longer matches what was
+    /// configured (e.g. a stale cgroup id or attachment), returning the repaired PIDs.
+    /// `flowing` holds the PIDs that had traffic in this direction since the last call
+    /// Default implementation does nothing - only the eBPF backend implements this
+    fn repair_throttles(&mut self, _flowing: &HashSet<i32>) -> ThrottleResult<Vec<i32>> {
+        Ok(Vec::new())
+    }
+
+    /// Move per-CPU throttle state (rate shares and unused tokens) to the CPUs the
+    /// traffic is on. Called about once a second while throttles are active
+    /// Default implementation does nothing - only the eBPF backend implements this
+    fn rebalance_throttles(&mut self) -> ThrottleResult<()> {
+        Ok(())
+    }
+
+    /// Feed the rates (bytes/sec) measured in this direction for throttled processes to
+    /// backends that enforce limits from userspace. Called on every monitor update
+    /// Default implementation does nothing - only the signal backend uses the rates
+    fn observe_rates(&mut self, _rates: &HashMap<i32, u64>) {}
+
+    /// How the backend's programs ended up attached (e.g. "link" or "legacy fallback")
+    /// while it has any attached. Only the eBPF backend attaches programs
+    fn attach_method(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Most throttles the backend can hold at once (None = no limit)
+    /// Default implementation: unlimited
+    fn max_throttles(&self) -> Option<usize> {
+        None
+    }
+
+    /// How many throttles the backend holds, counted against `max_throttles`
+    fn active_count(&self) -> usize {
+        self.get_all_throttles().len()
+    }
+
+    /// Interfaces the backend has set up qdiscs on, checked for foreign shaping
+    /// Default implementation: none (cgroup and nftables backends aren't tied to one)
+    fn managed_interfaces(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Log diagnostic information for a throttled process (for debugging)
+    /// Default implementation does nothing - only eBPF backend implements this
+    fn log_diagnostics(&mut self, _pid: i32) -> ThrottleResult<()> {
+        Ok(())
+    }
+
+    /// Check if this backend supports a specific traffic type
+    /// Default implementation: only supports TrafficType::All
+    fn supports_traffic_type(&self, traffic_type: crate::process::TrafficType) -> bool {
+        use crate::process::TrafficType;
+        traffic_type == TrafficType::All
+    }
+
+    /// Check if this backend can restrict a throttle to the given interfaces
+    /// Default implementation: interface-agnostic backends can't
+    fn supports_interfaces(&self, _interfaces: &[String]) -> bool {
+        false
+    }
+
+    /// Apply download throttle to a process on the given interfaces only, with an
+    /// over-limit policy the backend's `policy_support` allows
+    fn throttle_download_on_interfaces(
+        &mut self,
+        _pid: i32,
+        _process_name: String,
+        _limit_bytes_per_sec: u64,
+        _traffic_type: crate::process::TrafficType,
+        _interfaces: &[String],
+        _policy: crate::process::Policy,
+    ) -> ThrottleResult<()> {
+        Err(anyhow::anyhow!(
+            "{} backend cannot restrict throttles to specific interfaces",
+            self.name()
+        )
+        .into())
+    }
+
+    /// Apply download throttle to a process with a specific over-limit policy
+    /// Default implementation: only the backend's default policy is supported
+    fn throttle_download_with_policy(
+        &mut self,
+        pid: i32,
+        process_name: String,
+        limit_bytes_per_sec: u64,
+        traffic_type: crate::process::TrafficType,
+        policy: crate::process::Policy,
+    ) -> ThrottleResult<()> {
+        if policy != self.policy_support().default {
+            return Err(anyhow::anyhow!(
+                "{} backend cannot {} traffic over the limit",
+                self.name(),
+                policy
+            )
+            .into());
+        }
+        self.throttle_download(pid, process_name, limit_bytes_per_sec, traffic_type)
+    }
+
+    /// Check if this backend can restrict a throttle to one IP version
+    /// Default implementation: only throttles covering both IPv4 and IPv6
+    fn supports_address_family(&self, family: crate::process::AddressFamily) -> bool {
+        family == crate::process::AddressFamily::Any
+    }
+
+    /// Apply download throttle to a process's traffic of one IP version only, optionally
+    /// restricted to the given interfaces, with an over-limit policy the backend's
+    /// `policy_support` allows
+    #[allow(clippy::too_many_arguments)]
+    fn throttle_download_for_family(
+        &mut self,
+        _pid: i32,
+        _process_name: String,
+        _limit_bytes_per_sec: u64,
+        _traffic_type: crate::process::TrafficType,
+        family: crate::process::AddressFamily,
+        _interfaces: Option<&[String]>,
+        _policy: crate::process::Policy,
+    ) -> ThrottleResult<()> {
+        Err(anyhow::anyhow!(
+            "{} backend cannot restrict throttles to {}",
+            self.name(),
+            family
+        )
+        .into())
+    }
+
+    /// Check if this backend can rank throttled processes against each other
+    /// Default implementation: token bucket and police style backends have no scheduler
+    fn supports_throttle_priority(&self) -> bool {
+        false
+    }
+
+    /// Change the priority of a throttled process's class
+    fn set_download_throttle_priority(
+        &mut self,
+        _pid: i32,
+        _priority: crate::process::Priority,
+    ) -> ThrottleResult<()> {
+        Err(anyhow::anyhow!("{} backend cannot prioritize throttles", self.name()).into())
+    }
+}
+
+/// Check that every requested interface exists (before any rules are installed)
+pub fn validate_interfaces(requested: &[String], known: &[String]) -> Result<()> {
+    if requested.is_empty() {
+        return Err(anyhow::anyhow!("No interfaces given"));
+    }
+
+    let unknown: Vec<&str> = requested
+        .iter()
+        .filter(|name| !known.contains(name))
+        .map(|name| name.as_str())
+        .collect();
+
+    if unknown.is_empty() {
+        Ok(())
+    } else {
+        let mut available = known.to_vec();
+        available.sort();
+        Err(anyhow::anyhow!(
+            "Unknown interface(s): {} (available: {})",
+            unknown.join(", "),
+            available.join(", ")
+        ))
+    }
+}
+
+/// Upload backend metadata for selection
+#[derive(Debug, Clone)]
+pub struct UploadBackendInfo {
+    pub name: &'static str,
+    pub priority: BackendPriority,
+    pub available: bool,
+    /// Why the backend is unavailable (None when available)
+    pub unavailable_reason: Option<String>,
+}
+
+impl UploadBackendInfo {
+    /// Check a compiled-in backend's availability
+    pub fn probe<B: UploadThrottleBackend>(name: &'static str, priority: BackendPriority) -> Self {
+        let available = B::is_available();
+        Self {
+            name,
+            priority,
+            available,
+            unavailable_reason: (!available).then(B::unavailable_reason),
+        }
+    }
+}
+
+/// Download backend metadata for selection
+#[derive(Debug, Clone)]
+pub struct DownloadBackendInfo {
+    pub name: &'static str,
+    pub priority: BackendPriority,
+    pub available: bool,
+    /// Why the backend is unavailable (None when available)
+    pub unavailable_reason: Option<String>,
+}
+
+impl DownloadBackendInfo {
+    /// Check a compiled-in backend's availability
+    pub fn probe<B: DownloadThrottleBackend>(
+        name: &'static str,
+        priority: BackendPriority,
+    ) -> Self {
+        let available = B::is_available();
+        Self {
+            name,
+            priority,
+            available,
+            unavailable_reason: (!available).then(B::unavailable_reason),
+        }
+    }
+}
+
+/// Complete backend information for UI display
+#[derive(Debug, Clone, Default)]
+pub struct BackendInfo {
+    pub active_upload: Option<String>,
+    pub active_download: Option<String>,
+    pub active_monitoring: Option<String>, // NEW: e.g., "pnet"
+    pub active_socket_mapper: Option<String>,
+    pub available_upload: Vec<(String, BackendPriority, bool)>,
+    pub available_download: Vec<(String, BackendPriority, bool)>,
+    pub available_monitors: Vec<(String, BackendPriority, bool)>,
+    pub available_socket_mappers: Vec<(String, BackendPriority, bool)>,
+    pub preferred_upload: Option<String>,
+    pub preferred_download: Option<String>,
+    pub preferred_socket_mapper: Option<String>,
+    pub upload_capabilities: Option<BackendCapabilities>,
+    pub download_capabilities: Option<BackendCapabilities>,
+    pub socket_mapper_capabilities: Option<BackendCapabilities>,
+    // Hit/miss counts of the socket mapper's owner cache (None if it doesn't cache)
+    pub socket_cache: Option<crate::backends::process::socket_mapper::SocketCacheStats>,
+    pub traffic_support: Option<TrafficTypeSupport>, // supported by both active backends
+    // backend_name -> capabilities, for loaded backends only (unloaded ones are unknown)
+    pub upload_capability_matrix: HashMap<String, BackendCapabilities>,
+    pub download_capability_matrix: HashMap<String, BackendCapabilities>,
+    // backend_name -> (interface, ceiling bytes/sec), for loaded upload backends with a ceiling
+    pub upload_ceilings: HashMap<String, (String, u64)>,
+    pub backend_stats: HashMap<String, usize>, // backend_name -> active throttle count
+    // backend_name -> (throttles held, most it can hold), for loaded backends with a limit
+    pub upload_capacity: HashMap<String, (usize, usize)>,
+    pub download_capacity: HashMap<String, (usize, usize)>,
+    // backend_name -> attach method that worked, for loaded backends that attach programs
+    pub upload_attach_methods: HashMap<String, &'static str>,
+    pub download_attach_methods: HashMap<String, &'static str>,
+    // Shaping set up by someone else on the interfaces we shape (empty when ignored)
+    pub external_shaping: Vec<external::ExternalShaping>,
+    // Cgroup backend used to isolate throttled processes (v1 vs v2 changes how they're matched)
+    pub cgroup: CgroupInfo,
+}
+
+/// Detect all available upload backends
+pub fn detect_upload_backends() -> Vec<UploadBackendInfo> {
+    let mut backends = Vec::new();
+
+    // Listed before everything else so any real backend wins auto-selection
+    #[cfg(feature = "throttle-null")]
+    {
+        backends.push(UploadBackendInfo::probe::<upload::null::NullUpload>(
+            "null",
+            BackendPriority::Fallback,
+        ));
+    }
+
+    // Approximate, so listed early: every other backend wins auto-selection
+    #[cfg(all(unix, feature = "throttle-signal"))]
+    {
+        backends.push(UploadBackendInfo::probe::<upload::signal::SignalUpload>(
+            "signal",
+            BackendPriority::Fallback,
+        ));
+    }
+
+    // Listed first so kernel backends of the same priority win auto-selection
+    #[cfg(feature = "throttle-proxy")]
+    {
+        backends.push(UploadBackendInfo::probe::<upload::proxy::ProxyUpload>(
+            "proxy",
+            BackendPriority::Fallback,
+        ));
+    }
+
+    #[cfg(feature = "throttle-ebpf")]
+    {
+        backends.push(UploadBackendInfo::probe::<upload::linux::ebpf::EbpfUpload>(
+            "ebpf",
+            BackendPriority::Best,
+        ));
+    }
+
+    #[cfg(feature = "throttle-nftables")]
+    {
+        backends.push(UploadBackendInfo::probe::<upload::linux::nftables::NftablesUpload>(
+            "nftables",
+            BackendPriority::Better,
+        ));
+    }
+
+    #[cfg(feature = "throttle-tc-htb")]
+    {
+        backends.push(UploadBackendInfo::probe::<upload::linux::tc_htb::TcHtbUpload>(
+            "tc_htb",
+            BackendPriority::Good,
+        ));
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        backends.push(UploadBackendInfo::probe::<upload::macos::DnctlUpload>(
+            "dnctl",
+            BackendPriority::Best,
+        ));
+    }
+
+    backends
+}
+
+/// Detect all available download backends
+pub fn detect_download_backends() -> Vec<DownloadBackendInfo> {
+    let mut backends = Vec::new();
+
+    // Listed before everything else so any real backend wins auto-selection
+    #[cfg(feature = "throttle-null")]
+    {
+        backends.push(DownloadBackendInfo::probe::<download::null::NullDownload>(
+            "null",
+            BackendPriority::Fallback,
+        ));
+    }
+
+    // Approximate, so listed early: every other backend wins auto-selection
+    #[cfg(all(unix, feature = "throttle-signal"))]
+    {
+        backends.push(DownloadBackendInfo::probe::<download::signal::SignalDownload>(
+            "signal",
+            BackendPriority::Fallback,
+        ));
+    }
+
+    // Listed first so kernel backends of the same priority win auto-selection
+    #[cfg(feature = "throttle-proxy")]
+    {
+        backends.push(DownloadBackendInfo::probe::<download::proxy::ProxyDownload>(
+            "proxy",
+            BackendPriority::Fallback,
+        ));
+    }
+
+    #[cfg(feature = "throttle-ebpf")]
+    {
+        backends.push(DownloadBackendInfo::probe::<download::linux::ebpf::EbpfDownload>(
+            "ebpf",
+            BackendPriority::Best,
+        ));
+    }
+
+    #[cfg(feature = "throttle-nftables")]
+    {
+        backends.push(DownloadBackendInfo::probe::<download::linux::nftables::NftablesDownload>(
+            "nftables",
+            BackendPriority::Better,
+        ));
+    }
+
+    #[cfg(feature = "throttle-ifb-tc")]
+    {
+        backends.push(DownloadBackendInfo::probe::<download::linux::ifb_tc::IfbTcDownload>(
+            "ifb_tc",
+            BackendPriority::Good,
+        ));
+    }
+
+    #[cfg(feature = "throttle-tc-police")]
+    {
+        backends.push(DownloadBackendInfo::probe::<download::linux::tc_police::TcPoliceDownload>(
+            "tc_police",
+            BackendPriority::Fallback,
+        ));
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        backends.push(DownloadBackendInfo::probe::<download::macos::DnctlDownload>(
+            "dnctl",
+            BackendPriority::Best,
+        ));
+    }
+
+    backends
+}
+
+/// Auto-select best available upload backend (returns None if unavailable)
+pub fn select_upload_backend(preference: Option<&str>) -> Option<Box<dyn UploadThrottleBackend>> {
+    if let Some(name) = preference {
+        log::info!("Using preferred upload backend: {}", name);
+        return create_upload_backend(name).ok();
+    }
+
+    let available = detect_upload_backends();
+
+    // Log all backends and their status
+    log::debug!("Available upload backends:");
+    for backend in &available {
+        log::debug!(
+            "  {} - priority: {:?}, available: {}",
+            backend.name,
+            backend.priority,
+            backend.available
+        );
+    }
+
+    // Auto-select best available
+    let selected = available
+        .iter()
+        .filter(|b| b.available)
+        .max_by_key(|b| b.priority)
+        .and_then(|info| {
+            log::info!("Auto-selected upload backend: {}", info.name);
+            create_upload_backend(info.name).ok()
+        });
+
+    if selected.is_none() {
+        log::warn!("No upload throttling backend available");
+    }
+
+    selected
+}
+
+/// Auto-select best available download backend (returns None if unavailable)
+pub fn select_download_backend(
+    preference: Option<&str>,
+) -> Option<Box<dyn DownloadThrottleBackend>> {
+    let available = detect_download_backends();
+
+    if let Some(name) = preference {
+        log::info!("Using preferred download backend: {}", name);
+        return create_download_backend(name).ok();
+    }
+
+    // Log all backends and their status
+    log::debug!("Available download backends:");
+    for backend in &available {
+        log::debug!(
+            "  {} - priority: {:?}, available: {}",
+            backend.name,
+            backend.priority,
+            backend.available
+        );
+    }
+
+    // Auto-select best available
+    let selected = available
+        .iter()
+        .filter(|b| b.available)
+        .max_by_key(|b| b.priority)
+        .and_then(|info| {
+            log::info!("Auto-selected download backend: {}", info.name);
+            create_download_backend(info.name).ok()
+        });
+
+    if selected.is_none() {
+        log::warn!("No download throttling backend available");
+    }
+
+    selected
+}
+
+/// Create an upload backend by name
+pub fn create_upload_backend(name: &str) -> Result<Box<dyn UploadThrottleBackend>> {
+    log::debug!("Creating upload backend {name}");
+    match name {
+        #[cfg(feature = "throttle-ebpf")]
+        "ebpf" => Ok(Box::new(upload::linux::ebpf::EbpfUpload::new()?)),
+
+        #[cfg(feature = "throttle-nftables")]
+        "nftables" => Ok(Box::new(upload::linux::nftables::NftablesUpload::new()?)),
+
+        #[cfg(feature = "throttle-tc-htb")]
+        "tc_htb" => Ok(Box::new(upload::linux::tc_htb::TcHtbUpload::new()?)),
+
+        #[cfg(target_os = "macos")]
+        "dnctl" => Ok(Box::new(upload::macos::DnctlUpload::new()?)),
+
+        #[cfg(feature = "throttle-proxy")]
+        "proxy" => Ok(Box::new(upload::proxy::ProxyUpload::new()?)),
+
+        #[cfg(feature = "throttle-null")]
+        "null" => Ok(Box::new(upload::null::NullUpload::new()?)),
+
+        #[cfg(all(unix, feature = "throttle-signal"))]
+        "signal" => Ok(Box::new(upload::signal::SignalUpload::new()?)),
+
+        _ => Err(anyhow::anyhow!("Unknown upload backend: {}", name)),
+    }
+}
+
+/// Create a download backend by name
+pub fn create_download_backend(name: &str) -> Result<Box<dyn DownloadThrottleBackend>> {
+    match name {
+        #[cfg(feature = "throttle-ebpf")]
+        "ebpf" => Ok(Box::new(download::linux::ebpf::EbpfDownload::new()?)),
+
+        #[cfg(feature = "throttle-nftables")]
+        "nftables" => Ok(Box::new(download::linux::nftables::NftablesDownload::new()?)),
+
+        #[cfg(feature = "throttle-ifb-tc")]
+        "ifb_tc" => Ok(Box::new(download::linux::ifb_tc::IfbTcDownload::new()?)),
+
+        #[cfg(feature = "throttle-tc-police")]
+        "tc_police" => Ok(Box::new(
+            download::linux::tc_police::TcPoliceDownload::new()?
+        )),
+
+        #[cfg(target_os = "macos")]
+        "dnctl" => Ok(Box::new(download::macos::DnctlDownload::new()?)),
+
+        #[cfg(feature = "throttle-proxy")]
+        "proxy" => Ok(Box::new(download::proxy::ProxyDownload::new()?)),
+
+        #[cfg(feature = "throttle-null")]
+        "null" => Ok(Box::new(download::null::NullDownload::new()?)),
+
+        #[cfg(all(unix, feature = "throttle-signal"))]
+        "signal" => Ok(Box::new(download::signal::SignalDownload::new()?)),
+
+        _ => Err(anyhow::anyhow!("Unknown download backend: {}", name)),
+    }
+}
+
+/// Source of throttle backends for `ThrottleManager`: which ones exist and how to create them
+///
+/// `SystemBackends` is the real implementation; tests substitute mock backends.
+pub trait BackendProvider: Send + Sync {
+    /// All known upload backends and their availability
+    fn upload_backends(&self) -> Vec<UploadBackendInfo>;
+
+    /// All known download backends and their availability
+    fn download_backends(&self) -> Vec<DownloadBackendInfo>;
+
+    /// Create (but don't initialize) an upload backend by name
+    fn create_upload_backend(&self, name: &str) -> Result<Box<dyn UploadThrottleBackend>>;
+
+    /// Create (but don't initialize) a download backend by name
+    fn create_download_backend(&self, name: &str) -> Result<Box<dyn DownloadThrottleBackend>>;
+
+    /// Names of the network interfaces on this system
+    fn network_interfaces(&self) -> Vec<String>;
+
+    /// The cgroup of a process in an app sandbox or container that must keep its own
+    /// cgroup (shared by every process in it), None if it isn't in one
+    fn sandbox_cgroup(&self, pid: i32) -> Option<String>;
+
+    /// Shaping someone else set up on these interfaces or in nftables (read only)
+    fn external_shaping(&self, interfaces: &[String]) -> Vec<external::ExternalShaping>;
+
+    /// Which cgroup backend the throttling backends would use, and where it's mounted
+    fn cgroup_info(&self) -> CgroupInfo;
+}
+
+/// Backends compiled into this build, detected on the running system
+pub struct SystemBackends;
+
+impl BackendProvider for SystemBackends {
+    fn upload_backends(&self) -> Vec<UploadBackendInfo> {
+        detect_upload_backends()
+    }
+
+    fn download_backends(&self) -> Vec<DownloadBackendInfo> {
+        detect_download_backends()
+    }
+
+    fn create_upload_backend(&self, name: &str) -> Result<Box<dyn UploadThrottleBackend>> {
+        create_upload_backend(name)
+    }
+
+    fn create_download_backend(&self, name: &str) -> Result<Box<dyn DownloadThrottleBackend>> {
+        create_download_backend(name)
+    }
+
+    fn network_interfaces(&self) -> Vec<String> {
+        sysinfo::Networks::new_with_refreshed_list()
+            .list()
+            .keys()
+            .cloned()
+            .collect()
+    }
+
+    fn sandbox_cgroup(&self, pid: i32) -> Option<String> {
+        crate::backends::cgroup::sandbox::sandbox_cgroup(std::path::Path::new("/proc"), pid)
+    }
+
+    fn external_shaping(&self, interfaces: &[String]) -> Vec<external::ExternalShaping> {
+        external::detect_external_shaping(interfaces)
+    }
+
+    fn cgroup_info(&self) -> CgroupInfo {
+        crate::backends::cgroup::detect_cgroup_info()
+    }
+}
@@ -0,0 +1,491 @@
+// Userspace rate-limiting proxy (throttle-proxy feature)
+//
+// A local SOCKS5/HTTP proxy that paces the connections passing through it with a
+// token bucket. It needs no privileges, but it only sees traffic from programs that
+// use it: `chadthrottle run` starts one for the command it launches and points the
+// command at it through the proxy environment variables. The proxy upload and
+// download backends can therefore only throttle processes started that way.
+//
+// Limitations:
+// - TCP only (no SOCKS5 UDP ASSOCIATE)
+// - Programs that ignore the proxy variables aren't throttled at all
+
+use anyhow::{Context, Result, anyhow, bail};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+/// Largest read relayed in one go (smaller at low limits, see `Pacer::chunk_size`)
+const MAX_CHUNK: usize = 16 * 1024;
+
+/// Smallest read relayed in one go
+const MIN_CHUNK: usize = 512;
+
+/// Tokens the bucket can hold, as a fraction of a second at the limit
+const BURST_SECS: f64 = 0.25;
+
+/// Largest HTTP request head accepted from a client
+const MAX_HTTP_HEAD: usize = 16 * 1024;
+
+const SOCKS5_VERSION: u8 = 0x05;
+
+/// Processes launched through a proxy: PID -> the limits of their proxy
+static LAUNCHED: OnceLock<Mutex<HashMap<i32, Arc<ProxyLimits>>>> = OnceLock::new();
+
+fn launched() -> &'static Mutex<HashMap<i32, Arc<ProxyLimits>>> {
+    LAUNCHED.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Record that `pid` was launched through the proxy enforcing `limits`
+pub fn register(pid: i32, limits: Arc<ProxyLimits>) {
+    launched().lock().unwrap().insert(pid, limits);
+}
+
+/// Forget a launched process once it has exited
+pub fn unregister(pid: i32) {
+    launched().lock().unwrap().remove(&pid);
+}
+
+/// Limits of the proxy `pid` was launched through
+pub fn limits_for(pid: i32) -> Result<Arc<ProxyLimits>> {
+    launched()
+        .lock()
+        .unwrap()
+        .get(&pid)
+        .cloned()
+        .ok_or_else(|| {
+            anyhow!(
+                "proxy backend can only throttle processes started with `chadthrottle run` \
+             (PID {} wasn't)",
+                pid
+            )
+        })
+}
+
+/// Upload and download pacing shared by all connections through one proxy
+#[derive(Default)]
+pub struct ProxyLimits {
+    pub upload: Pacer,
+    pub download: Pacer,
+}
+
+/// Token bucket pacing a stream of bytes
+///
+/// Connections sharing a pacer share its limit. A reservation larger than the
+/// available tokens puts the bucket in debt, and the caller waits it off.
+#[derive(Default)]
+pub struct Pacer {
+    bucket: Mutex<Bucket>,
+}
+
+#[derive(Default)]
+struct Bucket {
+    limit: Option<u64>, // bytes/sec, None = unlimited
+    tokens: f64,
+    last_refill: Option<Instant>,
+}
+
+impl Pacer {
+    /// Set the limit in bytes/sec (None removes it)
+    pub fn set_limit(&self, limit: Option<u64>) {
+        let mut bucket = self.bucket.lock().unwrap();
+        bucket.limit = limit.filter(|&limit| limit > 0);
+        bucket.tokens = bucket.limit.map_or(0.0, |limit| limit as f64 * BURST_SECS);
+        bucket.last_refill = None;
+    }
+
+    pub fn limit(&self) -> Option<u64> {
+        self.bucket.lock().unwrap().limit
+    }
+
+    /// How much to read at once: about a tenth of a second's worth at the limit
+    fn chunk_size(&self) -> usize {
+        match self.limit() {
+            Some(limit) => (limit as usize / 10).clamp(MIN_CHUNK, MAX_CHUNK),
+            None => MAX_CHUNK,
+        }
+    }
+
+    /// Take `bytes` tokens at `now`, returning how long to wait before sending them
+    fn reserve(&self, bytes: usize, now: Instant) -> Duration {
+        let mut bucket = self.bucket.lock().unwrap();
+        let Some(limit) = bucket.limit else {
+            return Duration::ZERO;
+        };
+        let rate = limit as f64;
+
+        if let Some(last) = bucket.last_refill {
+            let elapsed = now.saturating_duration_since(last).as_secs_f64();
+            bucket.tokens = (bucket.tokens + elapsed * rate).min(rate * BURST_SECS);
+        }
+        bucket.last_refill = Some(now);
+        bucket.tokens -= bytes as f64;
+
+        if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / rate)
+        }
+    }
+
+    async fn acquire(&self, bytes: usize) {
+        let wait = self.reserve(bytes, Instant::now());
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// A running proxy on localhost, stopped when dropped
+pub struct ProxyServer {
+    addr: SocketAddr,
+    accept_task: JoinHandle<()>,
+}
+
+impl ProxyServer {
+    /// Listen on an ephemeral localhost port, pacing every connection with `limits`
+    pub async fn start(limits: Arc<ProxyLimits>) -> Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", 0))
+            .await
+            .context("Failed to start throttling proxy")?;
+        let addr = listener.local_addr()?;
+        log::info!("Throttling proxy listening on {}", addr);
+
+        let accept_task = tokio::spawn(async move {
+            loop {
+                let (client, peer) = match listener.accept().await {
+                    Ok(accepted) => accepted,
+                    Err(e) => {
+                        log::warn!("Throttling proxy failed to accept a connection: {}", e);
+                        continue;
+                    }
+                };
+                let limits = limits.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = handle_client(client, &limits).await {
+                        log::debug!("Proxy connection from {} ended: {:#}", peer, e);
+                    }
+                });
+            }
+        });
+
+        Ok(Self { addr, accept_task })
+    }
+
+    /// Environment variables pointing a program at this proxy
+    pub fn env_vars(&self) -> Vec<(&'static str, String)> {
+        let http = format!("http://{}", self.addr);
+        // socks5h: let the proxy resolve names, so lookups don't bypass it
+        let socks = format!("socks5h://{}", self.addr);
+        vec![
+            ("http_proxy", http.clone()),
+            ("https_proxy", http.clone()),
+            ("HTTP_PROXY", http.clone()),
+            ("HTTPS_PROXY", http),
+            ("all_proxy", socks.clone()),
+            ("ALL_PROXY", socks),
+        ]
+    }
+}
+
+impl Drop for ProxyServer {
+    fn drop(&mut self) {
+        self.accept_task.abort();
+    }
+}
+
+/// Serve one client: SOCKS5 or HTTP depending on the first byte, then relay
+async fn handle_client(mut client: TcpStream, limits: &ProxyLimits) -> Result<()> {
+    let mut first = [0u8; 1];
+    if client.peek(&mut first).await? == 0 {
+        return Ok(());
+    }
+
+    let (upstream, pending) = if first[0] == SOCKS5_VERSION {
+        (socks5_connect(&mut client).await?, Vec::new())
+    } else {
+        http_connect(&mut client).await?
+    };
+
+    let (client_read, client_write) = client.into_split();
+    let (upstream_read, mut upstream_write) = upstream.into_split();
+    // Part of the request may already have been read along with the HTTP head
+    if !pending.is_empty() {
+        limits.upload.acquire(pending.len()).await;
+        upstream_write.write_all(&pending).await?;
+    }
+
+    tokio::try_join!(
+        relay(client_read, upstream_write, &limits.upload),
+        relay(upstream_read, client_write, &limits.download),
+    )?;
+    Ok(())
+}
+
+/// Copy `from` to `to` at the pacer's rate until EOF
+async fn relay(
+    mut from: impl AsyncRead + Unpin,
+    mut to: impl AsyncWrite + Unpin,
+    pacer: &Pacer,
+) -> Result<()> {
+    let mut buf = vec![0u8; MAX_CHUNK];
+    loop {
+        let n = from.read(&mut buf[..pacer.chunk_size()]).await?;
+        if n == 0 {
+            break;
+        }
+        pacer.acquire(n).await;
+        to.write_all(&buf[..n]).await?;
+    }
+    to.shutdown().await?;
+    Ok(())
+}
+
+/// SOCKS5 handshake (no authentication, CONNECT only), returning the upstream connection
+async fn socks5_connect(client: &mut TcpStream) -> Result<TcpStream> {
+    let mut header = [0u8; 2];
+    client.read_exact(&mut header).await?;
+    let mut methods = vec![0u8; header[1] as usize];
+    client.read_exact(&mut methods).await?;
+    if !methods.contains(&0x00) {
+        client.write_all(&[SOCKS5_VERSION, 0xFF]).await?;
+        bail!("SOCKS5 client doesn't offer 'no authentication'");
+    }
+    client.write_all(&[SOCKS5_VERSION, 0x00]).await?;
+
+    let mut request = [0u8; 4];
+    client.read_exact(&mut request).await?;
+    let [_, command, _, address_type] = request;
+    let host = match address_type {
+        0x01 => {
+            let mut ip = [0u8; 4];
+            client.read_exact(&mut ip).await?;
+            std::net::Ipv4Addr::from(ip).to_string()
+        }
+        0x03 => {
+            let len = client.read_u8().await? as usize;
+            let mut name = vec![0u8; len];
+            client.read_exact(&mut name).await?;
+            String::from_utf8(name).context("Invalid SOCKS5 host name")?
+        }
+        0x04 => {
+            let mut ip = [0u8; 16];
+            client.read_exact(&mut ip).await?;
+            std::net::Ipv6Addr::from(ip).to_string()
+        }
+        _ => {
+            socks5_reply(client, 0x08).await?;
+            bail!("Unsupported SOCKS5 address type {}", address_type);
+        }
+    };
+    let port = client.read_u16().await?;
+
+    if command != 0x01 {
+        socks5_reply(client, 0x07).await?;
+        bail!("Unsupported SOCKS5 command {} (only CONNECT)", command);
+    }
+
+    match TcpStream::connect((host.as_str(), port)).await {
+        Ok(upstream) => {
+            socks5_reply(client, 0x00).await?;
+            Ok(upstream)
+        }
+        Err(e) => {
+            socks5_reply(client, 0x05).await?;
+            Err(e).context(format!("Failed to connect to {}:{}", host, port))
+        }
+    }
+}
+
+async fn socks5_reply(client: &mut TcpStream, status: u8) -> Result<()> {
+    // Bound address 0.0.0.0:0 - clients don't need it for CONNECT
+    client
+        .write_all(&[SOCKS5_VERSION, status, 0, 0x01, 0, 0, 0, 0, 0, 0])
+        .await?;
+    Ok(())
+}
+
+/// HTTP proxy request: CONNECT tunnels, or plain requests forwarded with
+/// `Connection: close` (so a kept-alive client can't reach another host through
+/// the same upstream). Returns the upstream connection and bytes to send to it.
+async fn http_connect(client: &mut TcpStream) -> Result<(TcpStream, Vec<u8>)> {
+    let mut buf = Vec::new();
+    let head_len = loop {
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+        if buf.len() > MAX_HTTP_HEAD {
+            http_error(client, "431 Request Header Fields Too Large").await?;
+            bail!("HTTP request head too large");
+        }
+        let mut chunk = [0u8; 4096];
+        let n = client.read(&mut chunk).await?;
+        if n == 0 {
+            bail!("Client closed the connection before sending a request");
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    };
+
+    let head = String::from_utf8_lossy(&buf[..head_len]).into_owned();
+    let body = buf[head_len..].to_vec();
+    let mut lines = head.split("\r\n");
+    let request_line = lines.next().unwrap_or_default();
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target), Some(version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        http_error(client, "400 Bad Request").await?;
+        bail!("Malformed HTTP request line: {:?}", request_line);
+    };
+
+    if method.eq_ignore_ascii_case("CONNECT") {
+        let Some((host, port)) = split_host_port(target, 443) else {
+            http_error(client, "400 Bad Request").await?;
+            bail!("Malformed CONNECT target: {:?}", target);
+        };
+        let upstream = match TcpStream::connect((host.as_str(), port)).await {
+            Ok(upstream) => upstream,
+            Err(e) => {
+                http_error(client, "502 Bad Gateway").await?;
+                return Err(e).context(format!("Failed to connect to {}:{}", host, port));
+            }
+        };
+        client
+            .write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n")
+            .await?;
+        return Ok((upstream, body));
+    }
+
+    let Some((authority, path)) = split_absolute_url(target) else {
+        http_error(client, "400 Bad Request").await?;
+        bail!("Unsupported proxy request target: {:?}", target);
+    };
+    let Some((host, port)) = split_host_port(authority, 80) else {
+        http_error(client, "400 Bad Request").await?;
+        bail!("Malformed request host: {:?}", authority);
+    };
+    let upstream = match TcpStream::connect((host.as_str(), port)).await {
+        Ok(upstream) => upstream,
+        Err(e) => {
+            http_error(client, "502 Bad Gateway").await?;
+            return Err(e).context(format!("Failed to connect to {}:{}", host, port));
+        }
+    };
+
+    let mut request = format!("{} {} {}\r\n", method, path, version);
+    for line in lines.filter(|line| !line.is_empty()) {
+        let name = line.split(':').next().unwrap_or_default().trim();
+        let hop_by_hop = ["connection", "proxy-connection", "keep-alive"]
+            .iter()
+            .any(|header| name.eq_ignore_ascii_case(header));
+        if !hop_by_hop {
+            request.push_str(line);
+            request.push_str("\r\n");
+        }
+    }
+    request.push_str("Connection: close\r\n\r\n");
+
+    let mut pending = request.into_bytes();
+    pending.extend_from_slice(&body);
+    Ok((upstream, pending))
+}
+
+async fn http_error(client: &mut TcpStream, status: &str) -> Result<()> {
+    client
+        .write_all(format!("HTTP/1.1 {}\r\nConnection: close\r\n\r\n", status).as_bytes())
+        .await?;
+    Ok(())
+}
+
+/// Split `http://host[:port]/path` into the authority and the path
+fn split_absolute_url(url: &str) -> Option<(&str, &str)> {
+    let scheme_len = "http://".len();
+    if !url.get(..scheme_len)?.eq_ignore_ascii_case("http://") {
+        return None;
+    }
+    let rest = &url[scheme_len..];
+    match rest.find('/') {
+        Some(slash) => Some((&rest[..slash], &rest[slash..])),
+        None => Some((rest, "/")),
+    }
+}
+
+/// Split `host[:port]` or `[v6]:port` into host and port
+fn split_host_port(authority: &str, default_port: u16) -> Option<(String, u16)> {
+    if let Some(rest) = authority.strip_prefix('[') {
+        let (host, after) = rest.split_once(']')?;
+        let port = match after.strip_prefix(':') {
+            Some(port) => port.parse().ok()?,
+            None if after.is_empty() => default_port,
+            None => return None,
+        };
+        return Some((host.to_string(), port));
+    }
+
+    match authority.rsplit_once(':') {
+        Some((host, port)) if !host.is_empty() => Some((host.to_string(), port.parse().ok()?)),
+        Some(_) => None,
+        None if !authority.is_empty() => Some((authority.to_string(), default_port)),
+        None => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pacer_token_bucket() {
+        let pacer = Pacer::default();
+        let start = Instant::now();
+        let wait_ms = |bytes, now| (pacer.reserve(bytes, now).as_secs_f64() * 1000.0).round();
+        assert_eq!(wait_ms(1_000_000, start), 0.0);
+
+        // 1000 B/s with a quarter second of burst
+        pacer.set_limit(Some(1000));
+        assert_eq!(wait_ms(250, start), 0.0);
+        assert_eq!(wait_ms(500, start), 500.0);
+        // Half a second later the debt is paid off, but the bucket is empty
+        let later = start + Duration::from_millis(500);
+        assert_eq!(wait_ms(100, later), 100.0);
+        // A long idle period only refills up to the burst
+        let idle = later + Duration::from_secs(10);
+        assert_eq!(wait_ms(350, idle), 100.0);
+
+        pacer.set_limit(None);
+        assert_eq!(wait_ms(1_000_000, idle), 0.0);
+    }
+
+    #[test]
+    fn test_proxy_request_targets() {
+        assert_eq!(
+            split_absolute_url("http://example.com:8080/a?b=c"),
+            Some(("example.com:8080", "/a?b=c"))
+        );
+        assert_eq!(
+            split_absolute_url("HTTP://example.com"),
+            Some(("example.com", "/"))
+        );
+        assert_eq!(split_absolute_url("/relative"), None);
+        assert_eq!(split_absolute_url("https://example.com/"), None);
+
+        assert_eq!(
+            split_host_port("example.com:443", 80),
+            Some(("example.com".to_string(), 443))
+        );
+        assert_eq!(
+            split_host_port("example.com", 80),
+            Some(("example.com".to_string(), 80))
+        );
+        assert_eq!(
+            split_host_port("[::1]:8080", 80),
+            Some(("::1".to_string(), 8080))
+        );
+        assert_eq!(split_host_port("[::1]", 80), Some(("::1".to_string(), 80)));
+        assert_eq!(split_host_port("example.com:http", 80), None);
+        assert_eq!(split_host_port(":80", 80), None);
+    }
+}
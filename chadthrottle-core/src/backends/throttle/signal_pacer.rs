@@ -0,0 +1,241 @@
+// Signal-based pacing (throttle-signal feature)
+//
+// Last-resort throttling for systems where no kernel backend works (restricted
+// containers, no tc/nftables/eBPF, macOS without dnctl permissions). It can't shape
+// packets: it approximates a rate cap by pausing the whole process with SIGSTOP and
+// resuming it with SIGCONT, letting it run only for the share of each cycle (its
+// duty) that keeps its measured rate near the limit. The duty is corrected every
+// time the monitor reports a new rate.
+//
+// Limitations:
+// - Approximate: the rate overshoots until the duty has settled, and bursts
+//   within a cycle aren't limited at all
+// - The whole process freezes, not just its network I/O
+// - Only processes this user may signal (its own, or any as root)
+// - A process paused when chadthrottle is killed with SIGKILL stays stopped
+//   (`kill -CONT <pid>` resumes it, and so does restoring its throttle)
+// - A process stopped on purpose (e.g. with Ctrl+Z) is resumed every cycle
+
+use anyhow::{Result, bail};
+use std::collections::HashMap;
+use std::sync::{Mutex, Once, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Length of one run/pause cycle
+const CYCLE: Duration = Duration::from_millis(200);
+
+/// Smallest share of a cycle a throttled process runs for
+const MIN_DUTY: f64 = 0.05;
+
+/// Which of a process's limits a backend controls
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Upload,
+    Download,
+}
+
+/// One direction's limit and the run share currently enforcing it
+#[derive(Debug, Clone, Copy)]
+struct Cap {
+    limit: u64, // bytes/sec
+    duty: f64,
+}
+
+/// Pacing state of one process
+#[derive(Debug, Default)]
+struct Paced {
+    upload: Option<Cap>,
+    download: Option<Cap>,
+    /// Whether the process is currently stopped by the pacer
+    stopped: bool,
+}
+
+impl Paced {
+    fn cap_mut(&mut self, direction: Direction) -> &mut Option<Cap> {
+        match direction {
+            Direction::Upload => &mut self.upload,
+            Direction::Download => &mut self.download,
+        }
+    }
+
+    /// Run share of a cycle (the stricter of both directions)
+    fn duty(&self) -> f64 {
+        [self.upload, self.download]
+            .into_iter()
+            .flatten()
+            .map(|cap| cap.duty)
+            .fold(1.0, f64::min)
+    }
+}
+
+/// Processes being paced: PID -> pacing state
+static PACED: OnceLock<Mutex<HashMap<i32, Paced>>> = OnceLock::new();
+
+static PACER_THREAD: Once = Once::new();
+
+fn paced() -> &'static Mutex<HashMap<i32, Paced>> {
+    PACED.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn send_signal(pid: i32, signal: libc::c_int) -> std::io::Result<()> {
+    // SAFETY: kill() has no memory safety requirements
+    if unsafe { libc::kill(pid, signal) } == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error())
+    }
+}
+
+/// Check that `pid` may be paced: it exists, isn't init or chadthrottle itself, and
+/// this user may signal it
+pub fn check_signalable(pid: i32) -> Result<()> {
+    if pid <= 1 || pid == std::process::id() as i32 {
+        bail!("signal backend won't pause PID {}", pid);
+    }
+    match send_signal(pid, 0) {
+        Ok(()) => Ok(()),
+        Err(e) if e.raw_os_error() == Some(libc::EPERM) => bail!(
+            "signal backend can only throttle processes it may signal \
+             (PID {} belongs to another user; run as root)",
+            pid
+        ),
+        Err(e) => bail!("Failed to signal PID {}: {}", pid, e),
+    }
+}
+
+/// Start pacing `pid` in one direction (or change its limit)
+pub fn set_limit(pid: i32, direction: Direction, limit_bytes_per_sec: u64) -> Result<()> {
+    check_signalable(pid)?;
+    PACER_THREAD.call_once(|| {
+        std::thread::Builder::new()
+            .name("signal-pacer".to_string())
+            .spawn(run_pacer)
+            .expect("Failed to spawn signal pacer thread");
+    });
+
+    let mut paced = paced().lock().unwrap();
+    if !paced.contains_key(&pid) {
+        // A previous run killed mid-cycle may have left it stopped
+        let _ = send_signal(pid, libc::SIGCONT);
+    }
+    let cap = paced.entry(pid).or_default().cap_mut(direction);
+    // Keep the settled duty when only the limit changes
+    let duty = cap.map_or(1.0, |cap| cap.duty);
+    *cap = Some(Cap {
+        limit: limit_bytes_per_sec,
+        duty,
+    });
+    Ok(())
+}
+
+/// Stop pacing `pid` in one direction, resuming it once no limit is left
+pub fn remove_limit(pid: i32, direction: Direction) {
+    let mut paced = paced().lock().unwrap();
+    let Some(process) = paced.get_mut(&pid) else {
+        return;
+    };
+    *process.cap_mut(direction) = None;
+    if process.upload.is_none() && process.download.is_none() {
+        if process.stopped {
+            let _ = send_signal(pid, libc::SIGCONT);
+        }
+        paced.remove(&pid);
+    }
+}
+
+/// Correct a process's duty from its latest measured rate in one direction
+pub fn observe(pid: i32, direction: Direction, measured_bytes_per_sec: u64) {
+    let mut paced = paced().lock().unwrap();
+    if let Some(cap) = paced
+        .get_mut(&pid)
+        .and_then(|p| p.cap_mut(direction).as_mut())
+    {
+        cap.duty = next_duty(cap.duty, cap.limit, measured_bytes_per_sec);
+    }
+}
+
+/// Next run share for a process that ran `duty` of the time and moved `measured`
+/// bytes/sec against a limit of `limit` bytes/sec
+///
+/// The rate scales roughly with the run share, so the target is the share that
+/// would have hit the limit exactly. Moving halfway there damps the oscillation
+/// caused by bursty traffic; an idle process is let back up gradually.
+pub fn next_duty(duty: f64, limit: u64, measured: u64) -> f64 {
+    let target = if measured == 0 {
+        1.0
+    } else {
+        duty * limit as f64 / measured as f64
+    };
+    ((duty + target) / 2.0).clamp(MIN_DUTY, 1.0)
+}
+
+/// Pacer loop: resume every paced process at the start of a cycle, then pause each
+/// once its share of the cycle is used up
+fn run_pacer() {
+    loop {
+        let cycle_start = Instant::now();
+
+        let mut stops: Vec<(Duration, i32)> = {
+            let mut paced = paced().lock().unwrap();
+            paced
+                .iter_mut()
+                .filter_map(|(pid, process)| {
+                    if process.stopped {
+                        let _ = send_signal(*pid, libc::SIGCONT);
+                        process.stopped = false;
+                    }
+                    let duty = process.duty();
+                    (duty < 1.0).then(|| (CYCLE.mul_f64(duty), *pid))
+                })
+                .collect()
+        };
+        stops.sort_unstable();
+
+        for (run_for, pid) in stops {
+            sleep_until(cycle_start + run_for);
+            // Look the process up again: its throttle may have been removed meanwhile
+            let mut paced = paced().lock().unwrap();
+            if let Some(process) = paced.get_mut(&pid)
+                && process.duty() < 1.0
+                && send_signal(pid, libc::SIGSTOP).is_ok()
+            {
+                process.stopped = true;
+            }
+        }
+
+        sleep_until(cycle_start + CYCLE);
+    }
+}
+
+fn sleep_until(deadline: Instant) {
+    std::thread::sleep(deadline.saturating_duration_since(Instant::now()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_duty_converges_on_the_limit() {
+        // Runs at 1000 B/s unpaced, limited to 250 B/s
+        let free_rate = 1000.0;
+        let mut duty = 1.0;
+        for _ in 0..20 {
+            let measured = (free_rate * duty) as u64;
+            duty = next_duty(duty, 250, measured);
+        }
+        assert!((duty - 0.25).abs() < 0.01, "duty {}", duty);
+
+        // Idle processes are let back up, and the duty never leaves its bounds
+        assert_eq!(next_duty(0.5, 250, 0), 0.75);
+        assert_eq!(next_duty(0.06, 1, 1_000_000), MIN_DUTY);
+        assert_eq!(next_duty(1.0, 1000, 10), 1.0);
+    }
+
+    #[test]
+    fn test_refuses_unsignalable_processes() {
+        assert!(check_signalable(1).is_err());
+        assert!(check_signalable(std::process::id() as i32).is_err());
+        assert!(set_limit(0, Direction::Upload, 1000).is_err());
+    }
+}
@@ -19,7 +19,7 @@ use std::fs;
 
 #[cfg(feature = "throttle-ebpf")]
 use aya::{
-    maps::HashMap as BpfHashMap,
+    maps::{HashMap as BpfHashMap, PerCpuHashMap as BpfPerCpuHashMap},
     programs::{CgroupSkb, CgroupSkbAttachType},
     Ebpf,
 };
@@ -29,28 +29,32 @@ use std::path::PathBuf;
 
 #[cfg(feature = "throttle-ebpf")]
 /// Track attached programs for proper cleanup
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 struct AttachedProgram {
     cgroup_path: PathBuf,
     attach_type: CgroupSkbAttachType,
-    program_fd: i32, // Required for detaching with BPF_F_ALLOW_MULTI
-    cgroup_id: u64,  // Required to find this entry without querying /proc
+    attachment: CgroupSkbAttachment, // bpf_link or legacy, decides how to detach
+    program_fd: i32,                 // Required for detaching with BPF_F_ALLOW_MULTI
+    cgroup_id: u64,                  // Required to find this entry without querying /proc
 }
 
 #[cfg(feature = "throttle-ebpf")]
-use chadthrottle_common::{CgroupThrottleConfig, ThrottleStats, TokenBucket};
+use chadthrottle_common::{
+    CgroupThrottleConfig, POLICY_DROP, POLICY_SHAPE, ThrottleStats, TokenBucket,
+};
 
 #[cfg(feature = "throttle-ebpf")]
 use crate::backends::throttle::linux_ebpf_utils::*;
 #[cfg(feature = "throttle-ebpf")]
-use crate::backends::throttle::UploadThrottleBackend;
+use crate::backends::throttle::{ThrottleError, ThrottleResult, UploadThrottleBackend};
 #[cfg(feature = "throttle-ebpf")]
-use crate::backends::{BackendCapabilities, BackendPriority};
+use crate::backends::{BackendCapabilities, BackendPriority, PolicySupport, TrafficTypeSupport};
+use crate::process::{AddressFamily, Policy};
 
 #[cfg(not(feature = "throttle-ebpf"))]
-use crate::backends::throttle::UploadThrottleBackend;
+use crate::backends::throttle::{ThrottleResult, UploadThrottleBackend};
 #[cfg(not(feature = "throttle-ebpf"))]
-use crate::backends::{BackendCapabilities, BackendPriority};
+use crate::backends::{BackendCapabilities, BackendPriority, PolicySupport, TrafficTypeSupport};
 #[cfg(not(feature = "throttle-ebpf"))]
 use anyhow::{anyhow, Result};
 #[cfg(not(feature = "throttle-ebpf"))]
@@ -72,6 +76,24 @@ pub struct EbpfUpload {
     #[cfg(feature = "throttle-ebpf")]
     /// Track attached programs for proper cleanup (especially for legacy attach method)
     attached_programs: Vec<AttachedProgram>,
+    #[cfg(feature = "throttle-ebpf")]
+    /// Config written for each PID, kept to repair the config map
+    throttle_configs: HashMap<i32, CgroupThrottleConfig>,
+    #[cfg(feature = "throttle-ebpf")]
+    /// Watches for config misses that point at a stale cgroup id
+    cgroup_watch: CgroupIdWatch,
+    #[cfg(feature = "throttle-ebpf")]
+    /// How many times each PID's throttle was repaired
+    cgroup_repairs: HashMap<i32, u64>,
+    #[cfg(feature = "throttle-ebpf")]
+    /// Watches for a program that stopped seeing the throttled processes' traffic
+    attachment_watch: AttachmentWatch,
+    #[cfg(feature = "throttle-ebpf")]
+    /// How many times each PID's program was re-attached
+    reattachments: HashMap<i32, u64>,
+    #[cfg(feature = "throttle-ebpf")]
+    /// Moves the per-CPU token bucket shares to the CPUs with traffic
+    cpu_rebalancer: CpuRebalancer,
     active_throttles: HashMap<i32, u64>,
 }
 
@@ -94,6 +116,12 @@ impl EbpfUpload {
                 cgroup_refcount: HashMap::new(),
                 attached_cgroups: std::collections::HashSet::new(),
                 attached_programs: Vec::new(),
+                throttle_configs: HashMap::new(),
+                cgroup_watch: CgroupIdWatch::default(),
+                cgroup_repairs: HashMap::new(),
+                attachment_watch: AttachmentWatch::default(),
+                reattachments: HashMap::new(),
+                cpu_rebalancer: CpuRebalancer::default(),
                 active_throttles: HashMap::new(),
             })
         }
@@ -120,7 +148,8 @@ impl EbpfUpload {
                 const PROGRAM_BYTES: &[u8] =
                     aya::include_bytes_aligned!(concat!(env!("OUT_DIR"), "/chadthrottle-egress"));
 
-                let mut ebpf = load_ebpf_program(PROGRAM_BYTES)?;
+                // Maps are pinned, so a restart adopts them instead of creating new ones
+                let mut ebpf = load_pinned_ebpf_program(PROGRAM_BYTES, "chadthrottle_egress")?;
 
                 // Load the program into the kernel NOW to create map FDs
                 // This ensures there's only ONE set of maps that both
@@ -136,6 +165,7 @@ impl EbpfUpload {
                     .load()
                     .context("Failed to load chadthrottle_egress program into kernel")?;
                 log::info!("✅ Loaded chadthrottle_egress program into kernel (maps created)");
+                pin_program(&mut ebpf, "chadthrottle_egress");
 
                 self.ebpf = Some(ebpf);
                 return Ok(());
@@ -155,78 +185,170 @@ impl EbpfUpload {
         }
         Ok(())
     }
-}
-
-impl UploadThrottleBackend for EbpfUpload {
-    fn name(&self) -> &'static str {
-        "ebpf"
-    }
 
-    fn priority(&self) -> BackendPriority {
-        BackendPriority::Best // Highest priority when available
-    }
-
-    fn is_available() -> bool {
-        #[cfg(feature = "throttle-ebpf")]
-        {
-            // Check basic kernel support (cgroup v2, kernel version)
-            if !check_ebpf_support() {
-                return false;
-            }
+    /// Repair throttles whose program keeps missing its config
+    ///
+    /// When config misses rise while the program is being called, the config map
+    /// no longer matches the cgroup the program sees. Each throttled PID's cgroup
+    /// is re-read: a PID that moved cgroups is throttled again in its new cgroup,
+    /// otherwise a missing config is rewritten with the current cgroup id.
+    #[cfg(feature = "throttle-ebpf")]
+    fn repair_cgroup_ids(&mut self) -> Result<Vec<i32>> {
+        let Some(stats) = self.ebpf.as_ref().and_then(read_throttle_stats) else {
+            return Ok(Vec::new());
+        };
+        if !self.cgroup_watch.misses_rising(&stats) {
+            return Ok(Vec::new());
+        }
 
-            // Check if eBPF programs are actually built and embedded
-            #[cfg(not(ebpf_programs_built))]
-            {
-                log::debug!(
-                    "eBPF upload backend unavailable: programs not built.\n\
-                     Build eBPF programs first:\n\
-                     1. Install bpf-linker: cargo install bpf-linker\n\
-                     2. Add rust-src: rustup component add rust-src\n\
-                     3. Build programs: cargo xtask build-ebpf"
+        let mut repaired = Vec::new();
+        let tracked: Vec<(i32, u64)> = self.pid_to_cgroup.iter().map(|(p, c)| (*p, *c)).collect();
+        for (pid, old_cgroup_id) in tracked {
+            // Skip processes that have exited
+            let Ok(cgroup_id) = get_cgroup_id(pid) else {
+                continue;
+            };
+            let Some(mut config) = self.throttle_configs.get(&pid).copied() else {
+                continue;
+            };
+            let repairs = self.cgroup_repairs.get(&pid).copied().unwrap_or(0) + 1;
+
+            if cgroup_id != old_cgroup_id {
+                log::warn!(
+                    "eBPF upload throttle for PID {}: cgroup id changed ({} -> {}), re-attaching",
+                    pid,
+                    old_cgroup_id,
+                    cgroup_id
+                );
+                self.remove_upload_throttle(pid)?;
+                self.throttle_upload_with_policy(
+                    pid,
+                    String::new(),
+                    config.rate_bps,
+                    traffic_type_from_ebpf(config.traffic_type),
+                    policy_from_ebpf(config.policy),
+                )?;
+            } else {
+                config.cgroup_id = cgroup_id;
+                let Some(ref mut ebpf) = self.ebpf else {
+                    break;
+                };
+                if !rewrite_missing_config(ebpf, config)? {
+                    continue;
+                }
+                log::warn!(
+                    "eBPF upload throttle for PID {}: config was missing, rewrote it for cgroup {}",
+                    pid,
+                    cgroup_id
                 );
-                return false;
             }
 
-            // All checks passed
-            true
-        }
-
-        #[cfg(not(feature = "throttle-ebpf"))]
-        {
-            false
+            self.cgroup_repairs.insert(pid, repairs);
+            repaired.push(pid);
         }
-    }
 
-    fn capabilities(&self) -> BackendCapabilities {
-        BackendCapabilities {
-            ipv4_support: true,
-            ipv6_support: true,
-            per_process: true,
-            per_connection: false,
-        }
+        Ok(repaired)
     }
 
-    fn init(&mut self) -> Result<()> {
-        #[cfg(feature = "throttle-ebpf")]
+    /// Re-attach programs that stopped seeing traffic
+    ///
+    /// If the program calls stop rising while throttled processes (`flowing`) still
+    /// have traffic, the attachments went stale. Each one is detached and attached
+    /// again, to the cgroup path its processes are in now, with the configured
+    /// `BpfAttachMethod`. Returns the PIDs in the re-attached cgroups.
+    #[cfg(feature = "throttle-ebpf")]
+    fn reattach_stale_programs(
+        &mut self,
+        flowing: &std::collections::HashSet<i32>,
+    ) -> Result<Vec<i32>> {
+        let Some(ref mut ebpf) = self.ebpf else {
+            return Ok(Vec::new());
+        };
+        let program_calls = read_throttle_stats(ebpf).map_or(0, |stats| stats.program_calls);
+        let traffic_flowing = flowing
+            .iter()
+            .any(|pid| self.pid_to_cgroup.contains_key(pid));
+        if !self
+            .attachment_watch
+            .calls_frozen(program_calls, traffic_flowing)
         {
-            self.ensure_loaded()?;
-            log::info!("eBPF upload backend initialized");
-            Ok(())
+            return Ok(Vec::new());
         }
 
-        #[cfg(not(feature = "throttle-ebpf"))]
-        {
-            Err(anyhow!("eBPF backend not compiled"))
+        let mut reattached = Vec::new();
+        for attached in std::mem::take(&mut self.attached_programs) {
+            let pids: Vec<i32> = self
+                .pid_to_cgroup
+                .iter()
+                .filter(|(_, cgroup_id)| **cgroup_id == attached.cgroup_id)
+                .map(|(pid, _)| *pid)
+                .collect();
+            // The cgroup may have been recreated: attach where the processes are now
+            let cgroup_path = pids
+                .iter()
+                .find_map(|pid| get_cgroup_path(*pid).ok())
+                .unwrap_or_else(|| attached.cgroup_path.clone());
+            log::warn!(
+                "eBPF upload program stopped seeing traffic (program_calls stuck at {}), \
+                 re-attaching to {:?}",
+                program_calls,
+                cgroup_path
+            );
+
+            // The old attachment may already be gone with its cgroup
+            if let Err(e) = detach_cgroup_skb(
+                Some(&mut *ebpf),
+                "chadthrottle_egress",
+                &attached.cgroup_path,
+                attached.attach_type,
+                attached.attachment,
+                attached.program_fd,
+            ) {
+                log::debug!(
+                    "Detaching stale program from {:?}: {}",
+                    attached.cgroup_path,
+                    e
+                );
+            }
+            self.attached_cgroups.remove(&attached.cgroup_path);
+
+            let attachment = match attach_cgroup_skb(
+                ebpf,
+                "chadthrottle_egress",
+                &cgroup_path,
+                attached.attach_type,
+            ) {
+                Ok(attachment) => attachment,
+                Err(e) => {
+                    log::error!("Failed to re-attach program to {:?}: {:#}", cgroup_path, e);
+                    continue;
+                }
+            };
+            self.attached_cgroups.insert(cgroup_path.clone());
+            self.attached_programs.push(AttachedProgram {
+                cgroup_path,
+                attachment,
+                ..attached
+            });
+
+            for pid in pids {
+                *self.reattachments.entry(pid).or_insert(0) += 1;
+                reattached.push(pid);
+            }
         }
+
+        Ok(reattached)
     }
 
-    fn throttle_upload(
+    /// Throttle a process's egress traffic, optionally of one IP version only
+    fn throttle(
         &mut self,
         pid: i32,
-        _process_name: String,
         limit_bytes_per_sec: u64,
         traffic_type: crate::process::TrafficType,
-    ) -> Result<()> {
+        policy: Policy,
+        family: AddressFamily,
+    ) -> ThrottleResult<()> {
         use crate::process::TrafficType;
 
         // eBPF backend now supports all traffic types via IP classification in kernel
@@ -234,6 +356,20 @@ impl UploadThrottleBackend for EbpfUpload {
 
         #[cfg(feature = "throttle-ebpf")]
         {
+            // Shaping needs a kernel that lets cgroup_skb programs set the departure time
+            let policy_value = match policy {
+                Policy::Shape if edt_pacing_supported() => POLICY_SHAPE,
+                Policy::Shape => {
+                    log::warn!(
+                        "EDT pacing needs kernel 5.0+: PID {} will drop packets over the limit \
+                         instead of shaping them",
+                        pid
+                    );
+                    POLICY_DROP
+                }
+                Policy::Drop => POLICY_DROP,
+            };
+
             self.ensure_loaded()?;
 
             // Get cgroup ID and path for this PID
@@ -261,7 +397,7 @@ impl UploadThrottleBackend for EbpfUpload {
                         cgroup_id,
                         cgroup_path
                     );
-                    attach_cgroup_skb(
+                    let attachment = attach_cgroup_skb(
                         ebpf,
                         "chadthrottle_egress",
                         &cgroup_path,
@@ -293,6 +429,7 @@ impl UploadThrottleBackend for EbpfUpload {
                     self.attached_programs.push(AttachedProgram {
                         cgroup_path: cgroup_path.clone(),
                         attach_type: CgroupSkbAttachType::Egress,
+                        attachment,
                         program_fd,
                         cgroup_id,
                     });
@@ -316,8 +453,8 @@ impl UploadThrottleBackend for EbpfUpload {
                 let mut config_map: BpfHashMap<_, u64, CgroupThrottleConfig> =
                     get_bpf_map(ebpf, "CGROUP_CONFIGS")?;
 
-                // Allow bursts up to 2x the sustained rate (configurable)
-                let burst_size = limit_bytes_per_sec * 2;
+                // Allow bursts up to 2x the sustained rate, and at least one packet
+                let burst_size = chadthrottle_common::burst_size_for(limit_bytes_per_sec);
 
                 // Convert TrafficType to u8 for eBPF
                 use chadthrottle_common::{
@@ -333,35 +470,38 @@ impl UploadThrottleBackend for EbpfUpload {
                     cgroup_id, // Store for diagnostics
                     pid: pid as u32,
                     traffic_type: traffic_type_value,
-                    _padding: [0; 3],
+                    policy: policy_value,
+                    cpu_count: online_cpu_count(),
                     rate_bps: limit_bytes_per_sec,
                     burst_size,
+                    address_family: address_family_value(family),
+                    _padding: [0; 7],
                 };
 
-                config_map.insert(MAP_KEY, config, 0)?;
-
-                // Initialize token bucket
-                let mut bucket_map: BpfHashMap<_, u64, TokenBucket> =
-                    get_bpf_map(ebpf, "CGROUP_BUCKETS")?;
-
-                // NOTE: Set last_update_ns to 0 to let eBPF program initialize it on first packet
-                // This avoids clock mismatch between userspace (wall clock via SystemTime)
-                // and kernel (monotonic clock via bpf_ktime_get_ns)
-                let bucket = TokenBucket {
-                    capacity: burst_size,
-                    tokens: burst_size,
-                    last_update_ns: 0, // eBPF will initialize on first packet
-                    rate_bps: limit_bytes_per_sec,
-                };
+                config_map
+                    .insert(MAP_KEY, config, 0)
+                    .context("Failed to store throttle config")?;
+                self.throttle_configs.insert(pid, config);
+                if policy_value == POLICY_SHAPE {
+                    log::info!(
+                        "Shaping PID {} with EDT pacing (needs the fq qdisc on the egress interface)",
+                        pid
+                    );
+                }
 
-                bucket_map.insert(MAP_KEY, bucket, 0)?;
+                // Initialize the per-CPU token buckets (the rate is split evenly
+                // until the first rebalance). last_update_ns stays 0 so the eBPF
+                // program initializes it on first packet, avoiding a clock mismatch
+                // between userspace (wall clock) and kernel (monotonic clock)
+                let tokens = init_cpu_buckets(ebpf, &config)?;
 
                 log::debug!(
-                    "Initialized token bucket for cgroup {}: rate={} bytes/sec, burst={} bytes, tokens={}",
+                    "Initialized token buckets for cgroup {}: rate={} bytes/sec across {} CPUs, burst={} bytes, tokens={}",
                     cgroup_id,
                     limit_bytes_per_sec,
+                    config.cpu_count,
                     burst_size,
-                    bucket.tokens
+                    tokens
                 );
             }
 
@@ -372,11 +512,145 @@ impl UploadThrottleBackend for EbpfUpload {
 
         #[cfg(not(feature = "throttle-ebpf"))]
         {
-            Err(anyhow!("eBPF backend not compiled"))
+            Err(anyhow!("eBPF backend not compiled").into())
         }
     }
+}
+
+impl UploadThrottleBackend for EbpfUpload {
+    fn name(&self) -> &'static str {
+        "ebpf"
+    }
 
-    fn remove_upload_throttle(&mut self, pid: i32) -> Result<()> {
+    fn priority(&self) -> BackendPriority {
+        BackendPriority::Best // Highest priority when available
+    }
+
+    fn is_available() -> bool {
+        #[cfg(feature = "throttle-ebpf")]
+        {
+            // Check basic kernel support (cgroup v2, kernel version)
+            if !check_ebpf_support() {
+                return false;
+            }
+
+            // Check if eBPF programs are actually built and embedded
+            #[cfg(not(ebpf_programs_built))]
+            {
+                log::debug!(
+                    "eBPF upload backend unavailable: programs not built.\n\
+                     Build eBPF programs first:\n\
+                     1. Install bpf-linker: cargo install bpf-linker\n\
+                     2. Add rust-src: rustup component add rust-src\n\
+                     3. Build programs: cargo xtask build-ebpf"
+                );
+                return false;
+            }
+
+            // All checks passed
+            true
+        }
+
+        #[cfg(not(feature = "throttle-ebpf"))]
+        {
+            false
+        }
+    }
+
+    fn unavailable_reason() -> String {
+        if !check_ebpf_support() {
+            return "requires cgroup v2 and kernel 4.10+".to_string();
+        }
+        // Kernel support is there, so the embedded programs are missing
+        "eBPF programs not built (run: cargo xtask build-ebpf)".to_string()
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            ipv4_support: true,
+            ipv6_support: true,
+            per_process: true,
+            per_connection: false,
+            tcp_support: true,
+            udp_support: true,
+            traffic_types: TrafficTypeSupport::from_predicate(|t| self.supports_traffic_type(t)),
+        }
+    }
+
+    fn init(&mut self) -> ThrottleResult<()> {
+        #[cfg(feature = "throttle-ebpf")]
+        {
+            self.ensure_loaded()?;
+            log::info!("eBPF upload backend initialized");
+            Ok(())
+        }
+
+        #[cfg(not(feature = "throttle-ebpf"))]
+        {
+            Err(anyhow!("eBPF backend not compiled").into())
+        }
+    }
+
+    fn policy_support(&self) -> PolicySupport {
+        // Shaping paces packets with an Earliest Departure Time instead of dropping them
+        PolicySupport {
+            shape: true,
+            drop: true,
+            default: Policy::Drop,
+        }
+    }
+
+    fn throttle_upload(
+        &mut self,
+        pid: i32,
+        process_name: String,
+        limit_bytes_per_sec: u64,
+        traffic_type: crate::process::TrafficType,
+    ) -> ThrottleResult<()> {
+        self.throttle_upload_with_policy(
+            pid,
+            process_name,
+            limit_bytes_per_sec,
+            traffic_type,
+            Policy::Drop,
+        )
+    }
+
+    fn throttle_upload_with_policy(
+        &mut self,
+        pid: i32,
+        _process_name: String,
+        limit_bytes_per_sec: u64,
+        traffic_type: crate::process::TrafficType,
+        policy: Policy,
+    ) -> ThrottleResult<()> {
+        self.throttle(
+            pid,
+            limit_bytes_per_sec,
+            traffic_type,
+            policy,
+            AddressFamily::Any,
+        )
+    }
+
+    fn supports_address_family(&self, family: AddressFamily) -> bool {
+        family.supported_by(&self.capabilities()) // the program checks the EtherType
+    }
+
+    fn throttle_upload_for_family(
+        &mut self,
+        pid: i32,
+        _process_name: String,
+        limit_bytes_per_sec: u64,
+        traffic_type: crate::process::TrafficType,
+        family: AddressFamily,
+        _interfaces: Option<&[String]>,
+        policy: Policy,
+    ) -> ThrottleResult<()> {
+        self.throttle(pid, limit_bytes_per_sec, traffic_type, policy, family)
+    }
+
+    fn remove_upload_throttle(&mut self, pid: i32) -> ThrottleResult<()> {
         #[cfg(feature = "throttle-ebpf")]
         {
             // Get the cgroup ID for this PID
@@ -410,13 +684,13 @@ impl UploadThrottleBackend for EbpfUpload {
                             let _ = config_map.remove(&MAP_KEY);
                             log::debug!("Removed config from map[{}]", MAP_KEY);
 
-                            let mut bucket_map: BpfHashMap<_, u64, TokenBucket> =
-                                get_bpf_map(ebpf, "CGROUP_BUCKETS")?;
+                            let mut bucket_map: BpfPerCpuHashMap<_, u64, TokenBucket> =
+                                get_bpf_per_cpu_map(ebpf, "CGROUP_BUCKETS")?;
                             let _ = bucket_map.remove(&MAP_KEY);
                             log::debug!("Removed bucket from map[{}]", MAP_KEY);
 
-                            let mut stats_map: BpfHashMap<_, u64, ThrottleStats> =
-                                get_bpf_map(ebpf, "CGROUP_STATS")?;
+                            let mut stats_map: BpfPerCpuHashMap<_, u64, ThrottleStats> =
+                                get_bpf_per_cpu_map(ebpf, "CGROUP_STATS")?;
                             let _ = stats_map.remove(&MAP_KEY);
                             log::debug!("Removed stats from map[{}]", MAP_KEY);
                         }
@@ -435,9 +709,12 @@ impl UploadThrottleBackend for EbpfUpload {
                                 attached.cgroup_id,
                                 attached.program_fd
                             );
-                            if let Err(e) = detach_cgroup_skb_legacy(
+                            if let Err(e) = detach_cgroup_skb(
+                                self.ebpf.as_mut(),
+                                "chadthrottle_egress",
                                 &attached.cgroup_path,
                                 attached.attach_type,
+                                attached.attachment,
                                 attached.program_fd,
                             ) {
                                 log::error!(
@@ -465,12 +742,43 @@ impl UploadThrottleBackend for EbpfUpload {
             }
 
             self.active_throttles.remove(&pid);
+            self.throttle_configs.remove(&pid);
+            self.cgroup_repairs.remove(&pid);
+            self.reattachments.remove(&pid);
             Ok(())
         }
 
         #[cfg(not(feature = "throttle-ebpf"))]
         {
-            Err(anyhow!("eBPF backend not compiled"))
+            Err(anyhow!("eBPF backend not compiled").into())
+        }
+    }
+
+    fn add_to_throttle(&mut self, pid: i32, member_pid: i32) -> ThrottleResult<()> {
+        #[cfg(feature = "throttle-ebpf")]
+        {
+            // The program is attached to the process's own cgroup rather than one
+            // created for it, so a member is only covered if it already shares it
+            let cgroup_id = self
+                .pid_to_cgroup
+                .get(&pid)
+                .copied()
+                .ok_or(ThrottleError::NotFound)?;
+            if get_cgroup_id(member_pid)? == cgroup_id {
+                return Ok(());
+            }
+            Err(anyhow::anyhow!(
+                "PID {} is in a different cgroup than PID {} (eBPF can't move processes)",
+                member_pid,
+                pid
+            )
+            .into())
+        }
+
+        #[cfg(not(feature = "throttle-ebpf"))]
+        {
+            let _ = (pid, member_pid);
+            Err(anyhow!("eBPF backend not compiled").into())
         }
     }
 
@@ -482,7 +790,7 @@ impl UploadThrottleBackend for EbpfUpload {
         self.active_throttles.clone()
     }
 
-    fn cleanup(&mut self) -> Result<()> {
+    fn cleanup(&mut self) -> ThrottleResult<()> {
         #[cfg(feature = "throttle-ebpf")]
         {
             log::info!("Cleaning up eBPF upload backend");
@@ -503,16 +811,19 @@ impl UploadThrottleBackend for EbpfUpload {
                 );
 
                 // Detach any remaining programs
-                for attached in &self.attached_programs {
+                for attached in self.attached_programs.drain(..) {
                     log::warn!(
                         "Detaching orphaned program: {:?} (id: {}, fd: {})",
                         attached.cgroup_path,
                         attached.cgroup_id,
                         attached.program_fd
                     );
-                    if let Err(e) = detach_cgroup_skb_legacy(
+                    if let Err(e) = detach_cgroup_skb(
+                        self.ebpf.as_mut(),
+                        "chadthrottle_egress",
                         &attached.cgroup_path,
                         attached.attach_type,
+                        attached.attachment,
                         attached.program_fd,
                     ) {
                         log::error!("Failed to detach orphaned program: {}", e);
@@ -526,6 +837,9 @@ impl UploadThrottleBackend for EbpfUpload {
             self.pid_to_cgroup.clear();
             self.cgroup_refcount.clear();
             self.attached_cgroups.clear();
+            self.throttle_configs.clear();
+            self.cgroup_repairs.clear();
+            self.reattachments.clear();
 
             log::info!("eBPF upload backend cleanup complete");
             Ok(())
@@ -537,43 +851,98 @@ impl UploadThrottleBackend for EbpfUpload {
         }
     }
 
-    fn supports_traffic_type(&self, _traffic_type: crate::process::TrafficType) -> bool {
-        // eBPF backend supports all traffic types with IP filtering
-        true
+    fn supports_traffic_type(&self, traffic_type: crate::process::TrafficType) -> bool {
+        // eBPF backend supports all traffic types with IP filtering, but the
+        // in-kernel classifier only knows the built-in ranges, not subnet overrides
+        traffic_type == crate::process::TrafficType::All
+            || crate::traffic_classifier::subnet_overrides().is_empty()
     }
 
-    fn get_stats(&self, _pid: i32) -> Option<crate::backends::throttle::BackendStats> {
-        // Note: Reading from BPF maps requires mutable access (aya limitation)
-        // Stats are being collected in the eBPF program, but we can't read them
-        // from an immutable context. Use get_stats_mut() if you need statistics.
-        None
+    fn get_stats(&self, pid: i32) -> Option<crate::backends::throttle::BackendStats> {
+        #[cfg(feature = "throttle-ebpf")]
+        {
+            if !self.pid_to_cgroup.contains_key(&pid) {
+                return None;
+            }
+            let mut stats: crate::backends::throttle::BackendStats =
+                self.ebpf.as_ref().and_then(read_throttle_stats)?.into();
+            stats.cgroup_repairs = self.cgroup_repairs.get(&pid).copied().unwrap_or(0);
+            stats.reattachments = self.reattachments.get(&pid).copied().unwrap_or(0);
+            Some(stats)
+        }
+
+        #[cfg(not(feature = "throttle-ebpf"))]
+        {
+            let _ = pid;
+            None
+        }
     }
-}
 
-#[cfg(feature = "throttle-ebpf")]
-impl EbpfUpload {
-    /// Get statistics for a throttled process (requires mutable access)
-    /// This is a workaround for aya requiring mut access to read from maps
-    pub fn get_stats_mut(&mut self, pid: i32) -> Option<crate::backends::throttle::BackendStats> {
-        use crate::backends::throttle::BackendStats;
-
-        // Get the cgroup ID for this PID
-        let cgroup_id = *self.pid_to_cgroup.get(&pid)?;
-
-        // Try to read stats from BPF map
-        if let Some(ref mut ebpf) = self.ebpf {
-            let stats_map: BpfHashMap<_, u64, ThrottleStats> =
-                get_bpf_map(ebpf, "CGROUP_STATS").ok()?;
-
-            if let Ok(stats) = unsafe { stats_map.get(&cgroup_id, 0) } {
-                return Some(BackendStats {
-                    packets_total: stats.packets_total,
-                    bytes_total: stats.bytes_total,
-                    packets_dropped: stats.packets_dropped,
-                    bytes_dropped: stats.bytes_dropped,
-                });
+    fn repair_throttles(
+        &mut self,
+        flowing: &std::collections::HashSet<i32>,
+    ) -> ThrottleResult<Vec<i32>> {
+        #[cfg(feature = "throttle-ebpf")]
+        {
+            let mut repaired = self.repair_cgroup_ids()?;
+            repaired.extend(self.reattach_stale_programs(flowing)?);
+            Ok(repaired)
+        }
+
+        #[cfg(not(feature = "throttle-ebpf"))]
+        {
+            let _ = flowing;
+            Ok(Vec::new())
+        }
+    }
+
+    fn rebalance_throttles(&mut self) -> ThrottleResult<()> {
+        #[cfg(feature = "throttle-ebpf")]
+        {
+            if let Some(ref mut ebpf) = self.ebpf
+                && !self.active_throttles.is_empty()
+            {
+                self.cpu_rebalancer.rebalance(ebpf)?;
             }
+            Ok(())
+        }
+
+        #[cfg(not(feature = "throttle-ebpf"))]
+        {
+            Ok(())
+        }
+    }
+
+    fn attach_method(&self) -> Option<&'static str> {
+        #[cfg(feature = "throttle-ebpf")]
+        {
+            // With auto, the latest attachment shows what the kernel accepted
+            self.attached_programs
+                .last()
+                .map(|attached| attached.attachment.label())
+        }
+
+        #[cfg(not(feature = "throttle-ebpf"))]
+        {
+            None
+        }
+    }
+
+    fn max_throttles(&self) -> Option<usize> {
+        #[cfg(feature = "throttle-ebpf")]
+        {
+            // The loaded maps' size, or the size they will be created with
+            let capacity = self
+                .ebpf
+                .as_ref()
+                .and_then(map_capacity)
+                .unwrap_or_else(|| get_bpf_config().map_capacity());
+            Some(capacity as usize)
+        }
+
+        #[cfg(not(feature = "throttle-ebpf"))]
+        {
+            None
         }
-        None
     }
 }
@@ -15,8 +15,8 @@
 // - Implement rate limiting logic in eBPF
 // - Use BPF maps for per-cgroup rate limits
 
-use crate::backends::throttle::UploadThrottleBackend;
-use crate::backends::{BackendCapabilities, BackendPriority};
+use crate::backends::throttle::{ThrottleResult, UploadThrottleBackend};
+use crate::backends::{BackendCapabilities, BackendPriority, TrafficTypeSupport};
 use anyhow::{anyhow, Result};
 use std::collections::HashMap;
 
@@ -55,11 +55,14 @@ impl UploadThrottleBackend for EbpfCgroupUpload {
             ipv6_support: true,
             per_process: true,
             per_connection: false,
+            tcp_support: true,
+            udp_support: true,
+            traffic_types: TrafficTypeSupport::from_predicate(|t| self.supports_traffic_type(t)),
         }
     }
 
-    fn init(&mut self) -> Result<()> {
-        Err(anyhow!("Not implemented"))
+    fn init(&mut self) -> ThrottleResult<()> {
+        Err(anyhow!("Not implemented").into())
     }
 
     fn throttle_upload(
@@ -68,12 +71,12 @@ impl UploadThrottleBackend for EbpfCgroupUpload {
         _process_name: String,
         _limit_bytes_per_sec: u64,
         _traffic_type: crate::process::TrafficType,
-    ) -> Result<()> {
-        Err(anyhow!("Not implemented"))
+    ) -> ThrottleResult<()> {
+        Err(anyhow!("Not implemented").into())
     }
 
-    fn remove_upload_throttle(&mut self, _pid: i32) -> Result<()> {
-        Err(anyhow!("Not implemented"))
+    fn remove_upload_throttle(&mut self, _pid: i32) -> ThrottleResult<()> {
+        Err(anyhow!("Not implemented").into())
     }
 
     fn get_upload_throttle(&self, pid: i32) -> Option<u64> {
@@ -84,8 +87,8 @@ impl UploadThrottleBackend for EbpfCgroupUpload {
         self.active_throttles.clone()
     }
 
-    fn cleanup(&mut self) -> Result<()> {
-        Err(anyhow!("Not implemented"))
+    fn cleanup(&mut self) -> ThrottleResult<()> {
+        Err(anyhow!("Not implemented").into())
     }
 }
 
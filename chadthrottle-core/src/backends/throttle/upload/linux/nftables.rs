@@ -0,0 +1,294 @@
+// nftables Upload Throttling Backend
+
+use crate::backends::cgroup::{CgroupBackend, CgroupHandle};
+use crate::backends::throttle::linux_nft_utils::*;
+use crate::backends::throttle::{
+    AdoptionReport, SOFT_THROTTLE_LIMIT, ThrottleError, ThrottleResult, UploadThrottleBackend,
+};
+use crate::backends::{BackendCapabilities, BackendPriority, TrafficTypeSupport};
+use crate::process::AddressFamily;
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+
+/// nftables-based upload (egress) throttling backend
+pub struct NftablesUpload {
+    active_throttles: HashMap<i32, ThrottleInfo>,
+    initialized: bool,
+    cgroup_backend: Option<Box<dyn CgroupBackend>>,
+}
+
+struct ThrottleInfo {
+    cgroup_handle: CgroupHandle,
+    /// Limit object and map element in the nftables table
+    nft: NftThrottle,
+}
+
+impl NftablesUpload {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            active_throttles: HashMap::new(),
+            initialized: false,
+            cgroup_backend: None,
+        })
+    }
+
+    fn ensure_initialized(&mut self) -> Result<()> {
+        if !self.initialized {
+            init_nft_table()?;
+
+            // Select best available cgroup backend
+            self.cgroup_backend = crate::backends::cgroup::select_best_backend()?;
+            if self.cgroup_backend.is_none() {
+                return Err(ThrottleError::KernelUnsupported.into());
+            }
+
+            self.initialized = true;
+        }
+        Ok(())
+    }
+
+    fn get_cgroup_backend(&self) -> Result<&Box<dyn CgroupBackend>> {
+        self.cgroup_backend
+            .as_ref()
+            .ok_or_else(|| anyhow!("Cgroup backend not initialized"))
+    }
+
+    fn throttle(
+        &mut self,
+        pid: i32,
+        process_name: String,
+        limit_bytes_per_sec: u64,
+        traffic_type: crate::process::TrafficType,
+        family: crate::process::AddressFamily,
+        interfaces: Option<&[String]>,
+    ) -> ThrottleResult<()> {
+        self.ensure_initialized()?;
+
+        // Create cgroup for process using backend
+        let backend = self.get_cgroup_backend_mut()?;
+        let cgroup_handle = backend.create_cgroup(pid, &process_name)?;
+
+        // Add nftables rate limit with traffic type (and IP version/interface) filtering
+        let nft = NftThrottle::new(
+            &cgroup_handle,
+            limit_bytes_per_sec,
+            Direction::Upload,
+            traffic_type,
+            family,
+            interfaces,
+        )?;
+        add_throttle(&nft)?;
+
+        // Track throttle
+        self.active_throttles
+            .insert(pid, ThrottleInfo { cgroup_handle, nft });
+
+        Ok(())
+    }
+
+    fn get_cgroup_backend_mut(&mut self) -> Result<&mut Box<dyn CgroupBackend>> {
+        self.cgroup_backend
+            .as_mut()
+            .ok_or_else(|| anyhow!("Cgroup backend not initialized"))
+    }
+}
+
+impl UploadThrottleBackend for NftablesUpload {
+    fn name(&self) -> &'static str {
+        "nftables"
+    }
+
+    fn priority(&self) -> BackendPriority {
+        BackendPriority::Better // Better than TC, not as good as eBPF
+    }
+
+    fn is_available() -> bool {
+        // Check nftables
+        if !check_nft_available() {
+            return false;
+        }
+
+        // Check if any cgroup backend is available
+        if let Ok(Some(backend)) = crate::backends::cgroup::select_best_backend() {
+            if let Ok(available) = backend.is_available() {
+                return available;
+            }
+        }
+
+        false
+    }
+
+    fn unavailable_reason() -> String {
+        if !check_nft_available() {
+            return "nft command not found".to_string();
+        }
+        "no usable cgroup backend".to_string()
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            ipv4_support: true,
+            ipv6_support: true,
+            per_process: true,
+            per_connection: false,
+            tcp_support: true,
+            udp_support: true,
+            traffic_types: TrafficTypeSupport::from_predicate(|t| self.supports_traffic_type(t)),
+        }
+    }
+
+    fn init(&mut self) -> ThrottleResult<()> {
+        if !check_nft_available() {
+            return Err(ThrottleError::ToolMissing("nft".to_string()));
+        }
+        Ok(self.ensure_initialized()?)
+    }
+
+    fn throttle_upload(
+        &mut self,
+        pid: i32,
+        process_name: String,
+        limit_bytes_per_sec: u64,
+        traffic_type: crate::process::TrafficType,
+    ) -> ThrottleResult<()> {
+        self.throttle(
+            pid,
+            process_name,
+            limit_bytes_per_sec,
+            traffic_type,
+            AddressFamily::Any,
+            None,
+        )
+    }
+
+    fn supports_interfaces(&self, _interfaces: &[String]) -> bool {
+        true // rules can match on the output interface (oifname)
+    }
+
+    fn throttle_upload_on_interfaces(
+        &mut self,
+        pid: i32,
+        process_name: String,
+        limit_bytes_per_sec: u64,
+        traffic_type: crate::process::TrafficType,
+        interfaces: &[String],
+        _policy: crate::process::Policy,
+    ) -> ThrottleResult<()> {
+        self.throttle(
+            pid,
+            process_name,
+            limit_bytes_per_sec,
+            traffic_type,
+            AddressFamily::Any,
+            Some(interfaces),
+        )
+    }
+
+    fn supports_address_family(&self, family: AddressFamily) -> bool {
+        family.supported_by(&self.capabilities()) // rules can match on meta nfproto
+    }
+
+    fn throttle_upload_for_family(
+        &mut self,
+        pid: i32,
+        process_name: String,
+        limit_bytes_per_sec: u64,
+        traffic_type: crate::process::TrafficType,
+        family: AddressFamily,
+        interfaces: Option<&[String]>,
+        _policy: crate::process::Policy,
+    ) -> ThrottleResult<()> {
+        self.throttle(
+            pid,
+            process_name,
+            limit_bytes_per_sec,
+            traffic_type,
+            family,
+            interfaces,
+        )
+    }
+
+    fn adopt_or_clean(&mut self, adoptable: &HashMap<i32, u64>) -> ThrottleResult<AdoptionReport> {
+        // Our limit objects are named after the PID they were created for
+        let cgroups = self.get_cgroup_backend()?.list_active_cgroups()?;
+        let (adopted, removed) = adopt_or_clean_throttles(Direction::Upload, adoptable, &cgroups)?;
+
+        let mut report = AdoptionReport {
+            adopted: Vec::new(),
+            removed,
+        };
+        for (cgroup_handle, nft) in adopted {
+            report.adopted.push(cgroup_handle.pid);
+            self.active_throttles
+                .insert(cgroup_handle.pid, ThrottleInfo { cgroup_handle, nft });
+        }
+        Ok(report)
+    }
+
+    fn remove_upload_throttle(&mut self, pid: i32) -> ThrottleResult<()> {
+        if let Some(info) = self.active_throttles.remove(&pid) {
+            // Remove the limit object and map element for this cgroup
+            if let Err(e) = remove_throttle(&info.nft) {
+                log::warn!("{:#}", e);
+            }
+
+            // Remove cgroup using backend
+            if let Ok(backend) = self.get_cgroup_backend_mut() {
+                let _ = backend.remove_cgroup(&info.cgroup_handle);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn add_to_throttle(&mut self, pid: i32, member_pid: i32) -> ThrottleResult<()> {
+        let handle = self
+            .active_throttles
+            .get(&pid)
+            .map(|info| info.cgroup_handle.clone())
+            .ok_or(ThrottleError::NotFound)?;
+        self.get_cgroup_backend_mut()?
+            .add_process(&handle, member_pid)?;
+        Ok(())
+    }
+
+    fn get_upload_throttle(&self, pid: i32) -> Option<u64> {
+        self.active_throttles
+            .get(&pid)
+            .map(|info| info.nft.rate_bytes_per_sec)
+    }
+
+    fn get_all_throttles(&self) -> HashMap<i32, u64> {
+        self.active_throttles
+            .iter()
+            .map(|(&pid, info)| (pid, info.nft.rate_bytes_per_sec))
+            .collect()
+    }
+
+    fn max_throttles(&self) -> Option<usize> {
+        Some(SOFT_THROTTLE_LIMIT)
+    }
+
+    fn cleanup(&mut self) -> ThrottleResult<()> {
+        // Remove all throttles
+        let pids: Vec<i32> = self.active_throttles.keys().copied().collect();
+        for pid in pids {
+            let _ = self.remove_upload_throttle(pid);
+        }
+
+        // Cleanup nftables table
+        let _ = cleanup_nft_table();
+
+        Ok(())
+    }
+
+    fn supports_traffic_type(&self, _traffic_type: crate::process::TrafficType) -> bool {
+        true // nftables supports all traffic types (All, Internet, Local)
+    }
+}
+
+impl Drop for NftablesUpload {
+    fn drop(&mut self) {
+        let _ = self.cleanup();
+    }
+}
@@ -0,0 +1,558 @@
+// TC HTB (Hierarchical Token Bucket) upload throttling backend
+
+use crate::backends::cgroup::{CgroupBackend, CgroupBackendType, CgroupHandle};
+use crate::backends::throttle::linux_tc_utils::*;
+use crate::backends::throttle::{
+    AdoptionReport, BackendStats, SOFT_THROTTLE_LIMIT, ThrottleError, ThrottleResult,
+    UploadThrottleBackend,
+};
+use crate::backends::{BackendCapabilities, BackendPriority, PolicySupport, TrafficTypeSupport};
+use crate::process::Priority;
+use anyhow::{anyhow, Result};
+use std::collections::{HashMap, HashSet};
+
+/// Class id of the optional interface ceiling class (process classes start at 100)
+const CEILING_CLASSID: u32 = 9999;
+
+/// TC HTB upload (egress) throttling backend
+///
+/// Without an interface ceiling, each process gets its own class under the root
+/// with rate = ceil = its limit. With a ceiling, process classes become children of a
+/// shared ceiling class: each is guaranteed its own limit and may borrow unused
+/// bandwidth up to the ceiling, while all of them together never exceed it.
+///
+/// A process with a guaranteed minimum gets HTB's `rate` set to the minimum and its
+/// `ceil` to its limit, so under the ceiling it keeps the minimum when others compete
+/// for the link and can borrow up to its limit when they don't. Classes directly
+/// under the root can't borrow, so without a ceiling the minimum has no effect.
+///
+/// A throttle's priority becomes its class's HTB `prio`: higher priority classes
+/// are dequeued first (lower latency) and offered spare bandwidth first when
+/// borrowing under the ceiling. It never lets a class exceed its limit.
+///
+/// Throttles scoped to specific interfaces get their class only on those interfaces;
+/// traffic leaving through any other interface doesn't match a class and is unshaped.
+pub struct TcHtbUpload {
+    interface: String,
+    // Interfaces with an HTB root qdisc set up by this backend
+    root_interfaces: HashSet<String>,
+    active_throttles: HashMap<i32, ThrottleInfo>,
+    next_classid: u32,
+    initialized: bool,
+    cgroup_backend: Option<Box<dyn CgroupBackend>>,
+    ceiling_bytes_per_sec: Option<u64>,
+}
+
+struct ThrottleInfo {
+    classid: u32,
+    cgroup_handle: CgroupHandle,
+    limit_bytes_per_sec: u64,
+    minimum_bytes_per_sec: Option<u64>,
+    priority: Priority,
+    interfaces: Vec<String>,
+}
+
+impl TcHtbUpload {
+    pub fn new() -> Result<Self> {
+        let interface = detect_interface()?;
+
+        Ok(Self {
+            interface,
+            root_interfaces: HashSet::new(),
+            active_throttles: HashMap::new(),
+            next_classid: 100, // Start at 100 to avoid conflicts
+            initialized: false,
+            cgroup_backend: None,
+            ceiling_bytes_per_sec: None,
+        })
+    }
+
+    fn get_cgroup_backend_mut(&mut self) -> Result<&mut Box<dyn CgroupBackend>> {
+        self.cgroup_backend
+            .as_mut()
+            .ok_or_else(|| anyhow!("Cgroup backend not initialized"))
+    }
+
+    /// Set up the HTB root (and cgroup filters) on an interface once
+    fn ensure_root(&mut self, interface: &str) -> Result<()> {
+        if !self.root_interfaces.contains(interface) {
+            setup_tc_htb_root(interface)?;
+            self.root_interfaces.insert(interface.to_string());
+        }
+        Ok(())
+    }
+
+    /// Create a process class on an interface, under the ceiling class if one is
+    /// set (the ceiling only exists on the main interface)
+    fn create_process_class(
+        &self,
+        interface: &str,
+        classid: u32,
+        limit_bytes_per_sec: u64,
+        minimum_bytes_per_sec: Option<u64>,
+        priority: Priority,
+    ) -> Result<()> {
+        let rate_kbps = bytes_to_kbit(limit_bytes_per_sec);
+        let prio = priority.htb_prio();
+        match self.ceiling_bytes_per_sec {
+            Some(ceiling) if interface == self.interface => {
+                let ceiling_kbps = bytes_to_kbit(ceiling);
+                // With a minimum: guaranteed the minimum, capped at the limit.
+                // Without: guaranteed the limit, borrowing up to the ceiling
+                let (rate_kbps, ceil_kbps) = match minimum_bytes_per_sec {
+                    Some(minimum) => (bytes_to_kbit(minimum), rate_kbps.min(ceiling_kbps)),
+                    None => (rate_kbps, ceiling_kbps),
+                };
+                create_tc_borrowing_class(
+                    &self.interface,
+                    classid,
+                    rate_kbps.min(ceil_kbps),
+                    ceil_kbps,
+                    prio,
+                    &format!("1:{}", CEILING_CLASSID),
+                )
+            }
+            _ => create_tc_class(interface, classid, rate_kbps, prio, "1:"),
+        }
+    }
+
+    /// Create the process class on each interface, removing the ones already
+    /// created if any fails
+    fn create_process_classes(
+        &self,
+        interfaces: &[String],
+        classid: u32,
+        limit_bytes_per_sec: u64,
+    ) -> Result<()> {
+        for (i, interface) in interfaces.iter().enumerate() {
+            if let Err(e) = self.create_process_class(
+                interface,
+                classid,
+                limit_bytes_per_sec,
+                None,
+                Priority::Normal,
+            ) {
+                for created in &interfaces[..i] {
+                    let _ = remove_tc_class(created, classid, "1:");
+                }
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    fn throttle_on_interfaces(
+        &mut self,
+        pid: i32,
+        process_name: String,
+        limit_bytes_per_sec: u64,
+        traffic_type: crate::process::TrafficType,
+        interfaces: Vec<String>,
+    ) -> ThrottleResult<()> {
+        use crate::process::TrafficType;
+
+        // TC HTB operates at cgroup level and cannot filter by IP address
+        // Only TrafficType::All is supported
+        if traffic_type != TrafficType::All {
+            return Err(anyhow::anyhow!(
+                "TC HTB backend does not support traffic type filtering (Internet/Local only). \
+                 Traffic type '{:?}' requested but only 'All' is supported. \
+                 Use nftables backend for traffic type filtering.",
+                traffic_type
+            )
+            .into());
+        }
+
+        // Initialize if not already done
+        self.init()?;
+        for interface in &interfaces {
+            self.ensure_root(interface)?;
+        }
+
+        // Get next classid
+        let classid = self.next_classid;
+        self.next_classid += 1;
+
+        // Create cgroup using backend (supports both v1 and v2)
+        let backend = self.get_cgroup_backend_mut()?;
+        let cgroup_handle = backend.create_cgroup(pid, &process_name)?;
+
+        // For cgroup v1, use classid from handle (format like "1:X")
+        // For v2 or if v1 parsing failed, use our own classid sequence
+        let classid = if matches!(cgroup_handle.backend_type, CgroupBackendType::V1) {
+            cgroup_handle
+                .identifier
+                .split(':')
+                .nth(1)
+                .and_then(|classid_str| classid_str.parse::<u32>().ok())
+                .unwrap_or(classid)
+        } else {
+            classid
+        };
+
+        // Create TC class with rate limit on each interface
+        self.create_process_classes(&interfaces, classid, limit_bytes_per_sec)?;
+
+        // Track throttle
+        self.active_throttles.insert(
+            pid,
+            ThrottleInfo {
+                classid,
+                cgroup_handle,
+                limit_bytes_per_sec,
+                minimum_bytes_per_sec: None,
+                priority: Priority::Normal,
+                interfaces,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Replace a process's class on an interface (HTB can't re-parent a class or
+    /// change its guarantee under a different parent in place)
+    fn recreate_process_class(&self, interface: &str, info: &ThrottleInfo) -> Result<()> {
+        let _ = remove_tc_class(interface, info.classid, "1:");
+        self.create_process_class(
+            interface,
+            info.classid,
+            info.limit_bytes_per_sec,
+            info.minimum_bytes_per_sec,
+            info.priority,
+        )
+    }
+
+    /// Add, change or remove the ceiling class and move process classes accordingly
+    fn apply_ceiling(&mut self, ceiling: Option<u64>) -> Result<()> {
+        let previous = self.ceiling_bytes_per_sec;
+
+        // The parent must exist before children can be attached to it
+        if let Some(ceiling) = ceiling {
+            replace_tc_root_class(&self.interface, CEILING_CLASSID, bytes_to_kbit(ceiling))?;
+        }
+        self.ceiling_bytes_per_sec = ceiling;
+
+        // HTB can't re-parent a class in place, so recreate each process class with
+        // its original limit and minimum under the new parent (or the root)
+        for (pid, info) in &self.active_throttles {
+            if !info.interfaces.contains(&self.interface) {
+                continue;
+            }
+            if let Err(e) = self.recreate_process_class(&self.interface, info) {
+                log::warn!("Failed to move throttle class for PID {}: {}", pid, e);
+            }
+        }
+
+        // Only delete the old ceiling once it has no children left
+        if ceiling.is_none() && previous.is_some() {
+            let _ = remove_tc_class(&self.interface, CEILING_CLASSID, "1:");
+        }
+
+        match ceiling {
+            Some(ceiling) => log::info!(
+                "tc_htb: interface ceiling on {} set to {} bytes/sec ({} throttles)",
+                self.interface,
+                ceiling,
+                self.active_throttles.len()
+            ),
+            None => log::info!("tc_htb: interface ceiling on {} removed", self.interface),
+        }
+
+        Ok(())
+    }
+}
+
+impl UploadThrottleBackend for TcHtbUpload {
+    fn name(&self) -> &'static str {
+        "tc_htb"
+    }
+
+    fn priority(&self) -> BackendPriority {
+        BackendPriority::Good
+    }
+
+    fn is_available() -> bool {
+        // Check TC (traffic control)
+        if !check_tc_available() {
+            return false;
+        }
+
+        // Check if any cgroup backend is available (works with v1 or v2)
+        if let Ok(Some(backend)) = crate::backends::cgroup::select_best_backend() {
+            if let Ok(available) = backend.is_available() {
+                return available;
+            }
+        }
+
+        false
+    }
+
+    fn unavailable_reason() -> String {
+        if !check_tc_available() {
+            return "tc command not found".to_string();
+        }
+        "no usable cgroup backend".to_string()
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            ipv4_support: true,
+            ipv6_support: true,
+            per_process: true,
+            per_connection: false,
+            tcp_support: true,
+            udp_support: true,
+            traffic_types: TrafficTypeSupport::from_predicate(|t| self.supports_traffic_type(t)),
+        }
+    }
+
+    fn policy_support(&self) -> PolicySupport {
+        // HTB classes queue excess packets
+        PolicySupport::only(crate::process::Policy::Shape)
+    }
+
+    fn init(&mut self) -> ThrottleResult<()> {
+        if self.initialized {
+            return Ok(());
+        }
+        if !check_tc_available() {
+            return Err(ThrottleError::ToolMissing("tc".to_string()));
+        }
+
+        // Setup TC HTB root on main interface
+        let interface = self.interface.clone();
+        self.ensure_root(&interface)?;
+
+        // Shared ceiling class configured before init
+        if let Some(ceiling) = self.ceiling_bytes_per_sec {
+            replace_tc_root_class(&self.interface, CEILING_CLASSID, bytes_to_kbit(ceiling))?;
+        }
+
+        // Initialize cgroup backend
+        self.cgroup_backend = crate::backends::cgroup::select_best_backend()?;
+        if self.cgroup_backend.is_none() {
+            return Err(ThrottleError::KernelUnsupported);
+        }
+
+        self.initialized = true;
+        Ok(())
+    }
+
+    fn throttle_upload(
+        &mut self,
+        pid: i32,
+        process_name: String,
+        limit_bytes_per_sec: u64,
+        traffic_type: crate::process::TrafficType,
+    ) -> ThrottleResult<()> {
+        let interfaces = vec![self.interface.clone()];
+        self.throttle_on_interfaces(
+            pid,
+            process_name,
+            limit_bytes_per_sec,
+            traffic_type,
+            interfaces,
+        )
+    }
+
+    fn supports_interfaces(&self, _interfaces: &[String]) -> bool {
+        true
+    }
+
+    fn throttle_upload_on_interfaces(
+        &mut self,
+        pid: i32,
+        process_name: String,
+        limit_bytes_per_sec: u64,
+        traffic_type: crate::process::TrafficType,
+        interfaces: &[String],
+        _policy: crate::process::Policy,
+    ) -> ThrottleResult<()> {
+        self.throttle_on_interfaces(
+            pid,
+            process_name,
+            limit_bytes_per_sec,
+            traffic_type,
+            interfaces.to_vec(),
+        )
+    }
+
+    fn adopt_or_clean(&mut self, _adoptable: &HashMap<i32, u64>) -> ThrottleResult<AdoptionReport> {
+        // Classes don't say which process they were created for, so every leftover
+        // process class on the HTB root is removed (restored throttles get new ones).
+        // The ceiling class stays if one is configured: init() already updated it
+        let mut report = AdoptionReport::default();
+        let mut classids = list_htb_classids(&self.interface, 1)?;
+        classids.sort_by_key(|&classid| classid == CEILING_CLASSID); // children first
+        for classid in classids {
+            if (classid == CEILING_CLASSID && self.ceiling_bytes_per_sec.is_some())
+                || self.active_throttles.values().any(|info| info.classid == classid)
+            {
+                continue;
+            }
+            remove_tc_class(&self.interface, classid, "1:")?;
+            log::info!("tc_htb: removed stale class 1:{} on {}", classid, self.interface);
+            report.removed += 1;
+        }
+        Ok(report)
+    }
+
+    fn remove_upload_throttle(&mut self, pid: i32) -> ThrottleResult<()> {
+        if let Some(info) = self.active_throttles.remove(&pid) {
+            // Remove TC class from every interface it was created on
+            for interface in &info.interfaces {
+                let _ = remove_tc_class(interface, info.classid, "1:");
+            }
+
+            // Remove cgroup using backend
+            if let Ok(backend) = self.get_cgroup_backend_mut() {
+                let _ = backend.remove_cgroup(&info.cgroup_handle);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn add_to_throttle(&mut self, pid: i32, member_pid: i32) -> ThrottleResult<()> {
+        let handle = self
+            .active_throttles
+            .get(&pid)
+            .map(|info| info.cgroup_handle.clone())
+            .ok_or(ThrottleError::NotFound)?;
+        self.get_cgroup_backend_mut()?
+            .add_process(&handle, member_pid)?;
+        Ok(())
+    }
+
+    fn get_upload_throttle(&self, pid: i32) -> Option<u64> {
+        self.active_throttles
+            .get(&pid)
+            .map(|info| info.limit_bytes_per_sec)
+    }
+
+    fn get_all_throttles(&self) -> HashMap<i32, u64> {
+        self.active_throttles
+            .iter()
+            .map(|(&pid, info)| (pid, info.limit_bytes_per_sec))
+            .collect()
+    }
+
+    fn max_throttles(&self) -> Option<usize> {
+        Some(SOFT_THROTTLE_LIMIT)
+    }
+
+    fn get_stats(&self, pid: i32) -> Option<BackendStats> {
+        let info = self.active_throttles.get(&pid)?;
+        let classes: Vec<TcClassStats> = info
+            .interfaces
+            .iter()
+            .filter_map(|interface| htb_class_stats(interface, 1, info.classid).ok().flatten())
+            .collect();
+        if classes.is_empty() {
+            return None;
+        }
+        Some(shaping_stats(classes, info.limit_bytes_per_sec))
+    }
+
+    fn set_interface_ceilings(&mut self, ceilings: &HashMap<String, u64>) -> ThrottleResult<()> {
+        let ceiling = ceilings.get(&self.interface).copied().filter(|c| *c > 0);
+        if ceiling == self.ceiling_bytes_per_sec {
+            return Ok(());
+        }
+
+        if !self.initialized {
+            // Created together with the HTB root in init()
+            self.ceiling_bytes_per_sec = ceiling;
+            return Ok(());
+        }
+
+        Ok(self.apply_ceiling(ceiling)?)
+    }
+
+    fn interface_ceiling(&self) -> Option<(String, u64)> {
+        self.ceiling_bytes_per_sec
+            .map(|ceiling| (self.interface.clone(), ceiling))
+    }
+
+    fn managed_interfaces(&self) -> Vec<String> {
+        let mut interfaces: Vec<String> = self.root_interfaces.iter().cloned().collect();
+        interfaces.sort();
+        interfaces
+    }
+
+    fn supports_minimum_rate(&self) -> bool {
+        true
+    }
+
+    fn set_upload_minimum(
+        &mut self,
+        pid: i32,
+        minimum_bytes_per_sec: Option<u64>,
+    ) -> ThrottleResult<()> {
+        let info = self
+            .active_throttles
+            .get_mut(&pid)
+            .ok_or(ThrottleError::NotFound)?;
+        info.minimum_bytes_per_sec = minimum_bytes_per_sec;
+
+        let info = &self.active_throttles[&pid];
+        if !info.interfaces.contains(&self.interface) {
+            return Ok(());
+        }
+        if self.ceiling_bytes_per_sec.is_none() {
+            if minimum_bytes_per_sec.is_some() {
+                log::info!(
+                    "tc_htb: the minimum of PID {} takes effect once {} has an interface ceiling",
+                    pid,
+                    self.interface
+                );
+            }
+            return Ok(());
+        }
+        Ok(self.recreate_process_class(&self.interface, info)?)
+    }
+
+    fn supports_throttle_priority(&self) -> bool {
+        true
+    }
+
+    fn set_upload_throttle_priority(&mut self, pid: i32, priority: Priority) -> ThrottleResult<()> {
+        let info = self
+            .active_throttles
+            .get_mut(&pid)
+            .ok_or(ThrottleError::NotFound)?;
+        if info.priority == priority {
+            return Ok(());
+        }
+        info.priority = priority;
+
+        let info = &self.active_throttles[&pid];
+        for interface in &info.interfaces {
+            self.recreate_process_class(interface, info)?;
+        }
+        Ok(())
+    }
+
+    fn cleanup(&mut self) -> ThrottleResult<()> {
+        // Remove all throttles
+        let pids: Vec<i32> = self.active_throttles.keys().copied().collect();
+        for pid in pids {
+            let _ = self.remove_upload_throttle(pid);
+        }
+
+        // Remove TC qdiscs (cleanup)
+        for interface in self.root_interfaces.drain() {
+            let _ = std::process::Command::new("tc")
+                .args(["qdisc", "del", "dev", &interface, "root"])
+                .status();
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for TcHtbUpload {
+    fn drop(&mut self) {
+        let _ = self.cleanup();
+    }
+}
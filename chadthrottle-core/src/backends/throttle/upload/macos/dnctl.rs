@@ -16,8 +16,8 @@
 // - Rule management via pfctl (no native API)
 
 use crate::backends::process::{ConnectionEntry, ProcessUtils};
-use crate::backends::throttle::UploadThrottleBackend;
-use crate::backends::{BackendCapabilities, BackendPriority};
+use crate::backends::throttle::{ThrottleError, ThrottleResult, UploadThrottleBackend};
+use crate::backends::{BackendCapabilities, BackendPriority, PolicySupport, TrafficTypeSupport};
 use anyhow::{anyhow, Context, Result};
 use std::collections::{HashMap, HashSet};
 use std::process::Command;
@@ -502,25 +502,41 @@ impl UploadThrottleBackend for DnctlUpload {
         true
     }
 
+    fn unavailable_reason() -> String {
+        if !check_dnctl_available() {
+            return "dnctl command not found".to_string();
+        }
+        "pfctl command not found".to_string()
+    }
+
     fn capabilities(&self) -> BackendCapabilities {
         BackendCapabilities {
             ipv4_support: true,
             ipv6_support: true,
             per_process: true,
             per_connection: true, // We match at connection level
+            tcp_support: true,
+            udp_support: false, // pf rules are only generated for TCP connections
+            traffic_types: TrafficTypeSupport::from_predicate(|t| self.supports_traffic_type(t)),
         }
     }
 
-    fn init(&mut self) -> Result<()> {
+    fn policy_support(&self) -> PolicySupport {
+        // Dummynet pipes queue excess packets
+        PolicySupport::only(crate::process::Policy::Shape)
+    }
+
+    fn init(&mut self) -> ThrottleResult<()> {
         if self.initialized {
             return Ok(());
         }
 
         // Verify we can run dnctl and pfctl
-        if !Self::is_available() {
-            return Err(anyhow!(
-                "dnctl backend not available (requires dnctl and pfctl)"
-            ));
+        if !check_dnctl_available() {
+            return Err(ThrottleError::ToolMissing("dnctl".to_string()));
+        }
+        if !check_pfctl_available() {
+            return Err(ThrottleError::ToolMissing("pfctl".to_string()));
         }
 
         self.initialized = true;
@@ -537,7 +553,7 @@ impl UploadThrottleBackend for DnctlUpload {
         process_name: String,
         limit_bytes_per_sec: u64,
         _traffic_type: crate::process::TrafficType,
-    ) -> Result<()> {
+    ) -> ThrottleResult<()> {
         // Initialize if needed
         self.init()?;
 
@@ -545,7 +561,7 @@ impl UploadThrottleBackend for DnctlUpload {
         {
             let throttles = self.active_throttles.lock().unwrap();
             if throttles.contains_key(&pid) {
-                return Err(anyhow!("Process {} already throttled", pid));
+                return Err(ThrottleError::AlreadyThrottled);
             }
         }
 
@@ -615,12 +631,12 @@ impl UploadThrottleBackend for DnctlUpload {
         Ok(())
     }
 
-    fn remove_upload_throttle(&mut self, pid: i32) -> Result<()> {
+    fn remove_upload_throttle(&mut self, pid: i32) -> ThrottleResult<()> {
         let state = {
             let mut throttles = self.active_throttles.lock().unwrap();
             throttles
                 .remove(&pid)
-                .ok_or_else(|| anyhow!("No throttle found for PID {}", pid))?
+                .ok_or(ThrottleError::NotFound)?
         };
 
         // Delete pipe
@@ -652,7 +668,7 @@ impl UploadThrottleBackend for DnctlUpload {
             .collect()
     }
 
-    fn cleanup(&mut self) -> Result<()> {
+    fn cleanup(&mut self) -> ThrottleResult<()> {
         log::info!("Cleaning up macOS dnctl upload backend...");
 
         // Signal monitoring thread to stop
@@ -0,0 +1,182 @@
+// Null upload throttling backend (throttle-null feature)
+//
+// Records throttles in memory without touching the kernel and reports simulated
+// statistics, so the throttle dialog, compatibility checks and config persistence
+// can be exercised (or demoed) without root. Nothing is actually rate limited.
+
+use crate::backends::throttle::{BackendStats, ThrottleResult, UploadThrottleBackend};
+use crate::backends::{BackendCapabilities, BackendPriority, PolicySupport, TrafficTypeSupport};
+use crate::process::{Policy, TrafficType};
+use anyhow::Result;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Simulated average packet size (bytes)
+const SIMULATED_PACKET_SIZE: u64 = 1200;
+
+/// Share of simulated packets reported as over the limit (1 in N)
+const SIMULATED_DROP_RATIO: u64 = 50;
+
+/// Plausible stats for a throttle that has run at its limit for `elapsed`
+pub(crate) fn simulated_stats(limit_bytes_per_sec: u64, elapsed: Duration) -> BackendStats {
+    let bytes_total = (limit_bytes_per_sec as f64 * elapsed.as_secs_f64()) as u64;
+    let packets_total = bytes_total / SIMULATED_PACKET_SIZE;
+    let packets_dropped = packets_total / SIMULATED_DROP_RATIO;
+    BackendStats {
+        packets_total,
+        bytes_total,
+        packets_dropped,
+        bytes_dropped: packets_dropped * SIMULATED_PACKET_SIZE,
+        passed_with_tokens: packets_total - packets_dropped,
+        dropped_no_tokens: packets_dropped,
+        ..Default::default()
+    }
+}
+
+/// Upload "throttling" that only remembers what it was asked to do
+#[derive(Default)]
+pub struct NullUpload {
+    active_throttles: HashMap<i32, (u64, Instant)>, // PID -> (bytes/sec, applied at)
+}
+
+impl NullUpload {
+    pub fn new() -> Result<Self> {
+        Ok(Self::default())
+    }
+}
+
+impl UploadThrottleBackend for NullUpload {
+    fn name(&self) -> &'static str {
+        "null"
+    }
+
+    fn priority(&self) -> BackendPriority {
+        BackendPriority::Fallback
+    }
+
+    fn is_available() -> bool {
+        true // Never touches the system
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            ipv4_support: true,
+            ipv6_support: true,
+            per_process: true,
+            per_connection: false,
+            tcp_support: true,
+            udp_support: true,
+            traffic_types: TrafficTypeSupport::from_predicate(|t| self.supports_traffic_type(t)),
+        }
+    }
+
+    fn policy_support(&self) -> PolicySupport {
+        PolicySupport {
+            shape: true,
+            drop: true,
+            default: Policy::Shape,
+        }
+    }
+
+    fn init(&mut self) -> ThrottleResult<()> {
+        Ok(())
+    }
+
+    fn throttle_upload(
+        &mut self,
+        pid: i32,
+        process_name: String,
+        limit_bytes_per_sec: u64,
+        traffic_type: TrafficType,
+    ) -> ThrottleResult<()> {
+        log::info!(
+            "null backend: recording upload throttle for {} (PID {}) at {} bytes/sec ({:?})",
+            process_name,
+            pid,
+            limit_bytes_per_sec,
+            traffic_type
+        );
+        self.active_throttles
+            .insert(pid, (limit_bytes_per_sec, Instant::now()));
+        Ok(())
+    }
+
+    fn throttle_upload_with_policy(
+        &mut self,
+        pid: i32,
+        process_name: String,
+        limit_bytes_per_sec: u64,
+        traffic_type: TrafficType,
+        _policy: Policy,
+    ) -> ThrottleResult<()> {
+        self.throttle_upload(pid, process_name, limit_bytes_per_sec, traffic_type)
+    }
+
+    fn remove_upload_throttle(&mut self, pid: i32) -> ThrottleResult<()> {
+        self.active_throttles.remove(&pid);
+        Ok(())
+    }
+
+    fn get_upload_throttle(&self, pid: i32) -> Option<u64> {
+        self.active_throttles.get(&pid).map(|(limit, _)| *limit)
+    }
+
+    fn get_all_throttles(&self) -> HashMap<i32, u64> {
+        self.active_throttles
+            .iter()
+            .map(|(pid, (limit, _))| (*pid, *limit))
+            .collect()
+    }
+
+    fn cleanup(&mut self) -> ThrottleResult<()> {
+        self.active_throttles.clear();
+        Ok(())
+    }
+
+    fn get_stats(&self, pid: i32) -> Option<BackendStats> {
+        let (limit, applied_at) = self.active_throttles.get(&pid)?;
+        Some(simulated_stats(*limit, applied_at.elapsed()))
+    }
+
+    fn supports_traffic_type(&self, _traffic_type: TrafficType) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_records_throttles_and_reports_stats() {
+        let mut backend = NullUpload::new().unwrap();
+        backend
+            .throttle_upload_with_policy(
+                42,
+                "curl".into(),
+                10_000,
+                TrafficType::Internet,
+                Policy::Drop,
+            )
+            .unwrap();
+        assert_eq!(backend.get_upload_throttle(42), Some(10_000));
+        assert_eq!(backend.get_all_throttles(), HashMap::from([(42, 10_000)]));
+        assert!(backend.get_stats(42).is_some());
+
+        backend.remove_upload_throttle(42).unwrap();
+        assert_eq!(backend.get_upload_throttle(42), None);
+        assert!(backend.get_stats(42).is_none());
+    }
+
+    #[test]
+    fn test_simulated_stats_follow_the_limit() {
+        let stats = simulated_stats(120_000, Duration::from_secs(10));
+        assert_eq!(stats.bytes_total, 1_200_000);
+        assert_eq!(stats.packets_total, 1000);
+        assert_eq!(stats.packets_dropped, 20);
+        assert_eq!(
+            stats.passed_with_tokens + stats.dropped_no_tokens,
+            stats.packets_total
+        );
+    }
+}
@@ -0,0 +1,97 @@
+// Userspace proxy upload throttling backend (throttle-proxy feature)
+//
+// Paces what processes launched with `chadthrottle run` send through their proxy.
+// See proxy_server.rs for how the proxy works and what it can't see.
+
+use crate::backends::throttle::proxy_server;
+use crate::backends::throttle::{ThrottleResult, UploadThrottleBackend};
+use crate::backends::{BackendCapabilities, BackendPriority, PolicySupport, TrafficTypeSupport};
+use crate::process::Policy;
+use anyhow::Result;
+use std::collections::HashMap;
+
+/// Upload throttling through the `chadthrottle run` proxy
+#[derive(Default)]
+pub struct ProxyUpload {
+    active_throttles: HashMap<i32, u64>, // PID -> bytes/sec
+}
+
+impl ProxyUpload {
+    pub fn new() -> Result<Self> {
+        Ok(Self::default())
+    }
+}
+
+impl UploadThrottleBackend for ProxyUpload {
+    fn name(&self) -> &'static str {
+        "proxy"
+    }
+
+    fn priority(&self) -> BackendPriority {
+        BackendPriority::Fallback
+    }
+
+    fn is_available() -> bool {
+        true // Needs no privileges or kernel support
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            ipv4_support: true,
+            ipv6_support: true,
+            per_process: true,
+            per_connection: false,
+            tcp_support: true,
+            udp_support: false, // No SOCKS5 UDP relay
+            traffic_types: TrafficTypeSupport::from_predicate(|t| self.supports_traffic_type(t)),
+        }
+    }
+
+    fn policy_support(&self) -> PolicySupport {
+        // Reads from the client are delayed, never discarded
+        PolicySupport::only(Policy::Shape)
+    }
+
+    fn init(&mut self) -> ThrottleResult<()> {
+        Ok(())
+    }
+
+    fn throttle_upload(
+        &mut self,
+        pid: i32,
+        _process_name: String,
+        limit_bytes_per_sec: u64,
+        _traffic_type: crate::process::TrafficType,
+    ) -> ThrottleResult<()> {
+        proxy_server::limits_for(pid)?
+            .upload
+            .set_limit(Some(limit_bytes_per_sec));
+        self.active_throttles.insert(pid, limit_bytes_per_sec);
+        Ok(())
+    }
+
+    fn remove_upload_throttle(&mut self, pid: i32) -> ThrottleResult<()> {
+        if self.active_throttles.remove(&pid).is_some()
+            && let Ok(limits) = proxy_server::limits_for(pid)
+        {
+            limits.upload.set_limit(None);
+        }
+        Ok(())
+    }
+
+    fn get_upload_throttle(&self, pid: i32) -> Option<u64> {
+        self.active_throttles.get(&pid).copied()
+    }
+
+    fn get_all_throttles(&self) -> HashMap<i32, u64> {
+        self.active_throttles.clone()
+    }
+
+    fn cleanup(&mut self) -> ThrottleResult<()> {
+        let pids: Vec<i32> = self.active_throttles.keys().copied().collect();
+        for pid in pids {
+            self.remove_upload_throttle(pid)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,105 @@
+// Signal-based upload throttling backend (throttle-signal feature)
+//
+// Approximates upload limits by pausing the process with SIGSTOP/SIGCONT.
+// See signal_pacer.rs for how the pacing works and what it can't do.
+
+use crate::backends::throttle::signal_pacer::{self, Direction};
+use crate::backends::throttle::{ThrottleResult, UploadThrottleBackend};
+use crate::backends::{BackendCapabilities, BackendPriority, PolicySupport, TrafficTypeSupport};
+use crate::process::Policy;
+use anyhow::Result;
+use std::collections::HashMap;
+
+/// Upload throttling by pausing the throttled process (approximate)
+#[derive(Default)]
+pub struct SignalUpload {
+    active_throttles: HashMap<i32, u64>, // PID -> bytes/sec
+}
+
+impl SignalUpload {
+    pub fn new() -> Result<Self> {
+        Ok(Self::default())
+    }
+}
+
+impl UploadThrottleBackend for SignalUpload {
+    fn name(&self) -> &'static str {
+        "signal"
+    }
+
+    fn priority(&self) -> BackendPriority {
+        BackendPriority::Fallback
+    }
+
+    fn is_available() -> bool {
+        true // Needs no kernel support; permissions are checked per process
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            ipv4_support: true,
+            ipv6_support: true,
+            per_process: true,
+            per_connection: false,
+            tcp_support: true,
+            udp_support: true,
+            traffic_types: TrafficTypeSupport::from_predicate(|t| self.supports_traffic_type(t)),
+        }
+    }
+
+    fn policy_support(&self) -> PolicySupport {
+        // A paused process sends and reads nothing, so its traffic is delayed
+        PolicySupport::only(Policy::Shape)
+    }
+
+    fn init(&mut self) -> ThrottleResult<()> {
+        log::warn!(
+            "signal upload backend: limits are approximate and enforced by pausing \
+             the whole process (SIGSTOP/SIGCONT)"
+        );
+        Ok(())
+    }
+
+    fn throttle_upload(
+        &mut self,
+        pid: i32,
+        _process_name: String,
+        limit_bytes_per_sec: u64,
+        _traffic_type: crate::process::TrafficType,
+    ) -> ThrottleResult<()> {
+        signal_pacer::set_limit(pid, Direction::Upload, limit_bytes_per_sec)?;
+        self.active_throttles.insert(pid, limit_bytes_per_sec);
+        Ok(())
+    }
+
+    fn remove_upload_throttle(&mut self, pid: i32) -> ThrottleResult<()> {
+        if self.active_throttles.remove(&pid).is_some() {
+            signal_pacer::remove_limit(pid, Direction::Upload);
+        }
+        Ok(())
+    }
+
+    fn get_upload_throttle(&self, pid: i32) -> Option<u64> {
+        self.active_throttles.get(&pid).copied()
+    }
+
+    fn get_all_throttles(&self) -> HashMap<i32, u64> {
+        self.active_throttles.clone()
+    }
+
+    fn cleanup(&mut self) -> ThrottleResult<()> {
+        let pids: Vec<i32> = self.active_throttles.keys().copied().collect();
+        for pid in pids {
+            self.remove_upload_throttle(pid)?;
+        }
+        Ok(())
+    }
+
+    fn observe_rates(&mut self, rates: &HashMap<i32, u64>) {
+        for pid in self.active_throttles.keys() {
+            if let Some(rate) = rates.get(pid) {
+                signal_pacer::observe(*pid, Direction::Upload, *rate);
+            }
+        }
+    }
+}
@@ -0,0 +1,946 @@
+// Configuration save/restore functionality
+
+use crate::events::ThrottleEventKind;
+use crate::limits::parse_bandwidth_limit;
+use crate::process::{AddressFamily, Policy, Priority, TrafficType};
+use crate::traffic_classifier::{Cidr, SubnetOverride, TrafficCategory};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const CONFIG_DIR: &str = ".config/chadthrottle";
+const CONFIG_FILE: &str = "throttles.json";
+
+/// Current config schema version. Bump it and add a step to `MIGRATIONS` whenever
+/// the saved format changes in a way serde defaults can't paper over
+pub const CONFIG_VERSION: u32 = 1;
+
+/// Upgrade steps, indexed by the version they upgrade from (MIGRATIONS[0] turns v0 into v1)
+const MIGRATIONS: [fn(&mut Value); CONFIG_VERSION as usize] = [migrate_v0_to_v1];
+
+/// Largest `ebpf_map_capacity` accepted. Each backend creates three maps of this
+/// size (two per CPU), so much more would pin hundreds of MB of kernel memory
+pub const MAX_EBPF_MAP_CAPACITY: u32 = 1 << 20;
+
+/// Saved throttle configuration for a process
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedThrottle {
+    pub process_name: String,
+    pub upload_limit: Option<u64>,
+    pub download_limit: Option<u64>,
+    /// Upload rate guaranteed to the process (backends that can't guarantee one ignore it)
+    #[serde(default)]
+    pub upload_minimum: Option<u64>,
+    /// Interfaces the throttle is restricted to (None = all interfaces)
+    #[serde(default)]
+    pub interfaces: Option<Vec<String>>,
+    /// Which traffic the limits apply to
+    #[serde(default)]
+    pub traffic_type: TrafficType,
+    /// Backends that enforced the throttle, tried first when it is restored
+    #[serde(default)]
+    pub upload_backend: Option<String>,
+    #[serde(default)]
+    pub download_backend: Option<String>,
+    /// Over-limit policy requested for the throttle (None = backend default)
+    #[serde(default)]
+    pub policy: Option<Policy>,
+    /// IP version the limits apply to
+    #[serde(default)]
+    pub family: AddressFamily,
+    /// Rank against other throttled processes
+    #[serde(default)]
+    pub priority: Priority,
+    /// Whether the throttle follows the process's children
+    #[serde(default)]
+    pub include_children: bool,
+    /// Traffic type that was asked for when the throttle was downgraded to All
+    #[serde(default)]
+    pub downgraded_from: Option<TrafficType>,
+}
+
+impl SavedThrottle {
+    /// One-line summary of the limits, for import diffs
+    pub fn describe(&self) -> String {
+        let limit =
+            |limit: Option<u64>| limit.map_or("unlimited".to_string(), crate::format::format_rate);
+        let mut description = format!(
+            "down {}, up {}, {:?} traffic",
+            limit(self.download_limit),
+            limit(self.upload_limit),
+            self.traffic_type
+        );
+        if let Some(interfaces) = &self.interfaces {
+            description.push_str(&format!(" on {}", interfaces.join(",")));
+        }
+        if let Some(policy) = self.policy {
+            description.push_str(&format!(", {}", policy));
+        }
+        if self.family != AddressFamily::Any {
+            description.push_str(&format!(", {}", self.family));
+        }
+        if self.include_children {
+            description.push_str(", with children");
+        }
+        if let Some(requested) = self.downgraded_from {
+            description.push_str(&format!(" (downgraded from {:?})", requested));
+        }
+        description
+    }
+}
+
+/// Built-in TUI theme identifiers (persisted in config; the colors live in the binary)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemeName {
+    #[default]
+    Default,
+    HighContrast,
+    #[serde(alias = "mono")]
+    Monochrome,
+}
+
+/// Configuration file structure
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Config {
+    /// Schema version the file was written with (missing = 0, before versioning)
+    #[serde(default)]
+    pub version: u32,
+
+    /// Throttles by PID
+    #[serde(default)]
+    pub throttles: HashMap<i32, SavedThrottle>,
+
+    /// Auto-restore throttles on startup
+    #[serde(default = "default_auto_restore")]
+    pub auto_restore: bool,
+
+    /// Throttle chadthrottle's own shell/terminal/sshd without asking for confirmation
+    #[serde(default)]
+    pub allow_control_path_throttles: bool,
+
+    /// Don't look for (or warn about) traffic shaping set up outside chadthrottle
+    #[serde(default)]
+    pub ignore_external_shaping: bool,
+
+    /// When no backend can filter the requested traffic type (Internet/Local), apply
+    /// the throttle to All traffic instead of asking
+    #[serde(default)]
+    pub auto_downgrade_traffic_type: bool,
+
+    /// Ask for a second press before removing a throttle in the TUI
+    #[serde(default = "default_confirm_throttle_removal")]
+    pub confirm_throttle_removal: bool,
+
+    /// Preferred upload backend
+    #[serde(default)]
+    pub preferred_upload_backend: Option<String>,
+
+    /// Preferred download backend
+    #[serde(default)]
+    pub preferred_download_backend: Option<String>,
+
+    /// Preferred socket mapper backend
+    #[serde(default)]
+    pub preferred_socket_mapper: Option<String>,
+
+    /// Preferred monitor backend (e.g. "pnet", "nettop"; None = best available)
+    #[serde(default)]
+    pub preferred_monitor_backend: Option<String>,
+
+    /// Interface filter: None = show all, Some([]) = show nothing, Some([...]) = filter to these.
+    /// Entries are exact names, glob patterns ("tun*") or exclusions ("!tun3"), which win
+    #[serde(default)]
+    pub filtered_interfaces: Option<Vec<String>>,
+
+    /// Capture-level interface allowlist: None = capture on all interfaces.
+    /// Unlike `filtered_interfaces`, excluded interfaces are never captured at all
+    #[serde(default)]
+    pub capture_interfaces: Option<Vec<String>>,
+
+    /// Upload ceiling per interface shared by all throttled processes (e.g. "eth0": "40M").
+    /// Throttled processes may borrow unused bandwidth up to it (tc_htb only)
+    #[serde(default)]
+    pub interface_ceilings: HashMap<String, String>,
+
+    /// Traffic view mode: All, Internet, or Local
+    #[serde(default)]
+    pub traffic_view_mode: Option<crate::process::TrafficType>,
+
+    /// Color theme: default, high-contrast, or mono (NO_COLOR forces mono)
+    #[serde(default)]
+    pub theme: Option<ThemeName>,
+
+    /// Keybinding overrides: action name -> keys (e.g. "move-down": ["Down", "n"])
+    #[serde(default)]
+    pub keybindings: HashMap<String, Vec<String>>,
+
+    /// Show processes as a parent/child tree instead of a flat list
+    #[serde(default)]
+    pub process_tree_view: bool,
+
+    /// Show each docker/podman container as one row in the process list
+    #[serde(default)]
+    pub container_view: bool,
+
+    /// Show each process's command line instead of its name in the process list
+    #[serde(default)]
+    pub show_cmdline: bool,
+
+    /// Show TCP retransmits and RTT columns in the process list
+    #[serde(default)]
+    pub show_tcp_health: bool,
+
+    /// Label the bandwidth graph's time axis with the time of day instead of how long
+    /// ago
+    #[serde(default)]
+    pub graph_clock_time: bool,
+
+    /// List idle processes that only have listening sockets (sshd, nginx, ...)
+    #[serde(default)]
+    pub show_listen_only: bool,
+
+    /// Show the host names of remote addresses in the Connections tab (reverse DNS
+    /// lookups, off by default since they cause DNS traffic of their own)
+    #[serde(default)]
+    pub reverse_dns: bool,
+
+    /// Save bandwidth history to disk (per process name) and reload it on startup
+    #[serde(default)]
+    pub persist_history: bool,
+
+    /// How long persisted bandwidth history is kept, in minutes
+    #[serde(default = "default_history_retention_minutes")]
+    pub history_retention_minutes: u64,
+
+    /// Bandwidth samples kept per process for the graph and peak/average rates
+    /// (one per second, so 60 = the last minute)
+    #[serde(default = "default_history_samples")]
+    pub history_samples: usize,
+
+    /// Alert when any process's download or upload rate exceeds this (e.g. "50M")
+    #[serde(default)]
+    pub alert_threshold: Option<String>,
+
+    /// Command run through the shell on each alert (gets pid, name, rate, direction
+    /// as $1..$4 and CHADTHROTTLE_* environment variables)
+    #[serde(default)]
+    pub alert_command: Option<String>,
+
+    /// Minimum seconds between alerts for the same process
+    #[serde(default = "default_alert_cooldown_secs")]
+    pub alert_cooldown_secs: u64,
+
+    /// Daily per-process-name quotas, checked once a minute (the first matching rule
+    /// applies to a process)
+    #[serde(default)]
+    pub quotas: Vec<QuotaConfig>,
+
+    /// Throttles applied to every process matching a name or glob pattern as soon as
+    /// it starts, including processes that start later (the first matching rule
+    /// applies to a process)
+    #[serde(default)]
+    pub standing_rules: Vec<StandingRuleConfig>,
+
+    /// Watch for new processes between updates (Linux proc connector), so standing
+    /// rules apply right after a process starts instead of within a second
+    #[serde(default = "default_watch_new_processes")]
+    pub watch_new_processes: bool,
+
+    /// POST throttle events to a URL (e.g. a Slack incoming webhook)
+    #[serde(default)]
+    pub webhooks: Option<WebhookConfig>,
+
+    /// Subnets classified as "local" or "internet" regardless of the built-in
+    /// rules (e.g. "10.8.0.0/24": "internet" for a VPN range)
+    #[serde(default)]
+    pub subnet_overrides: HashMap<String, TrafficCategory>,
+
+    /// Count loopback traffic (127.0.0.0/8, ::1) in rates and totals, as local
+    /// traffic. Off leaves it out everywhere; it's never counted as internet
+    #[serde(default = "default_count_loopback")]
+    pub count_loopback: bool,
+
+    /// Seconds terminated processes stay listed (0 = remove immediately,
+    /// negative = keep forever)
+    #[serde(default = "default_terminated_retention_secs")]
+    pub terminated_retention_secs: i64,
+
+    /// Also write each process's connection opens/closes to the log
+    #[serde(default)]
+    pub log_connection_events: bool,
+
+    /// Entries in each eBPF backend map, i.e. how many cgroups the ebpf backends can
+    /// throttle at once (None = the 4096 the programs are built with). Pinned maps
+    /// of another size are recreated, which drops throttles a previous run left
+    #[serde(default)]
+    pub ebpf_map_capacity: Option<u32>,
+
+    /// Seconds between scans of the system's sockets and connections (None = every
+    /// second). Raise it on machines with tens of thousands of connections, where a
+    /// scan is expensive; slow scans also back off on their own
+    #[serde(default)]
+    pub connection_scan_interval_secs: Option<u64>,
+}
+
+/// A daily quota for processes matching a name or glob pattern
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuotaConfig {
+    #[serde(rename = "match")]
+    pub pattern: String,
+    /// Download + upload per day, e.g. "5G"
+    pub limit: String,
+    /// "alert", or "throttle:<rate>" to also throttle the process until midnight
+    #[serde(default = "default_quota_action")]
+    pub action: String,
+}
+
+fn default_quota_action() -> String {
+    "alert".to_string()
+}
+
+/// A throttle for every process matching a name or glob pattern, applied as it starts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StandingRuleConfig {
+    #[serde(rename = "match")]
+    pub pattern: String,
+    /// Download limit, e.g. "2M" (None = unlimited)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub download: Option<String>,
+    /// Upload limit (None = unlimited)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upload: Option<String>,
+}
+
+/// Webhook endpoint and the events sent to it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// Events to send: applied, removed, limit_exceeded, backend_failed, quota_exceeded
+    /// (default: all)
+    #[serde(default = "default_webhook_events")]
+    pub events: Vec<ThrottleEventKind>,
+}
+
+fn default_webhook_events() -> Vec<ThrottleEventKind> {
+    ThrottleEventKind::ALL.to_vec()
+}
+
+fn default_auto_restore() -> bool {
+    true
+}
+
+fn default_confirm_throttle_removal() -> bool {
+    true
+}
+
+fn default_count_loopback() -> bool {
+    true
+}
+
+fn default_watch_new_processes() -> bool {
+    true
+}
+
+fn default_history_retention_minutes() -> u64 {
+    60
+}
+
+fn default_history_samples() -> usize {
+    crate::history::DEFAULT_HISTORY_SAMPLES
+}
+
+fn default_alert_cooldown_secs() -> u64 {
+    60
+}
+
+fn default_terminated_retention_secs() -> i64 {
+    30
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            throttles: HashMap::new(),
+            auto_restore: true,
+            allow_control_path_throttles: false,
+            ignore_external_shaping: false,
+            auto_downgrade_traffic_type: false,
+            confirm_throttle_removal: default_confirm_throttle_removal(),
+            preferred_upload_backend: None,
+            preferred_download_backend: None,
+            preferred_socket_mapper: None,
+            preferred_monitor_backend: None,
+            filtered_interfaces: None,          // Show all by default
+            capture_interfaces: None,           // Capture on all interfaces by default
+            interface_ceilings: HashMap::new(), // No shared ceiling by default
+            traffic_view_mode: None,            // Use default (All) if not set
+            theme: None,                        // Use default theme if not set
+            keybindings: HashMap::new(),        // Use default keys if not set
+            process_tree_view: false,           // Flat list by default
+            container_view: false,              // Container processes listed individually
+            show_cmdline: false,                // Show process names by default
+            show_tcp_health: false,             // Bandwidth columns only by default
+            graph_clock_time: false,            // "30s ago" rather than "14:32:05"
+            show_listen_only: false,            // Idle daemons hidden by default
+            reverse_dns: false,                 // No extra DNS traffic by default
+            persist_history: false,             // In-memory history only by default
+            history_retention_minutes: default_history_retention_minutes(),
+            history_samples: default_history_samples(),
+            alert_threshold: None, // No alerts by default
+            alert_command: None,
+            alert_cooldown_secs: default_alert_cooldown_secs(),
+            quotas: Vec::new(),
+            standing_rules: Vec::new(),
+            watch_new_processes: default_watch_new_processes(),
+            webhooks: None,                   // No notifications by default
+            subnet_overrides: HashMap::new(), // Built-in classification only
+            count_loopback: default_count_loopback(),
+            terminated_retention_secs: default_terminated_retention_secs(),
+            log_connection_events: false, // Events only in the detail view
+            ebpf_map_capacity: None,
+            connection_scan_interval_secs: None,
+        }
+    }
+}
+
+impl Config {
+    /// Get the config file path
+    /// Uses platform-specific config directories:
+    /// - Linux: ~/.config/chadthrottle
+    /// - macOS: ~/Library/Application Support/chadthrottle
+    /// - Windows: C:\Users\{user}\AppData\Roaming\chadthrottle
+    pub fn config_path() -> Result<PathBuf> {
+        // Try to use platform-specific config directory first
+        let config_dir = if let Some(config_base) = dirs::config_dir() {
+            // Prefer XDG config directory on Linux, AppData on Windows, Library on macOS
+            config_base.join("chadthrottle")
+        } else if let Some(home) = dirs::home_dir() {
+            // Fallback: use ~/.config/chadthrottle on all platforms
+            home.join(CONFIG_DIR)
+        } else {
+            // Last resort fallback
+            anyhow::bail!("Could not determine config directory (no home directory found)");
+        };
+
+        // Create config directory if it doesn't exist
+        fs::create_dir_all(&config_dir).context(format!(
+            "Failed to create config directory: {:?}",
+            config_dir
+        ))?;
+
+        Ok(config_dir.join(CONFIG_FILE))
+    }
+
+    /// Load configuration from disk
+    pub fn load() -> Result<Self> {
+        let path = Self::config_path()?;
+
+        if !path.exists() {
+            log::debug!("Config file not found, using defaults");
+            return Ok(Config::default());
+        }
+
+        let contents =
+            fs::read_to_string(&path).context(format!("Failed to read config file: {:?}", path))?;
+
+        let (config, from_version) = Self::parse(&contents)?;
+
+        // Rewrite upgraded configs so the old format doesn't linger, keeping the original
+        if from_version < CONFIG_VERSION {
+            let backup = path.with_extension(format!("json.v{}.bak", from_version));
+            if let Err(e) = fs::write(&backup, &contents) {
+                log::warn!("Failed to back up config to {:?}: {}", backup, e);
+            }
+            match config.save() {
+                Ok(()) => log::info!(
+                    "Upgraded config from version {} to {} (previous file kept as {:?})",
+                    from_version,
+                    CONFIG_VERSION,
+                    backup
+                ),
+                Err(e) => log::warn!("Failed to write upgraded config: {}", e),
+            }
+        } else if from_version > CONFIG_VERSION {
+            log::warn!(
+                "Config file is version {} but this build only knows version {}; \
+                 settings it doesn't know about will be lost when the config is saved",
+                from_version,
+                CONFIG_VERSION
+            );
+        }
+
+        // Invalid overrides are reported (and ignored) rather than discarding the config
+        if let Err(e) = config.parse_subnet_overrides() {
+            log::warn!("{:#}", e);
+        }
+
+        log::info!("Loaded configuration from {:?}", path);
+        Ok(config)
+    }
+
+    /// Parse a config file's contents, upgrading older schema versions.
+    /// Returns the config and the version it was written with
+    fn parse(contents: &str) -> Result<(Self, u32)> {
+        let mut value: Value =
+            serde_json::from_str(contents).context("Failed to parse config file")?;
+        let from_version = migrate(&mut value);
+        let config = serde_json::from_value(value).context("Failed to parse config file")?;
+        Ok((config, from_version))
+    }
+
+    /// Read a config file to import. Stricter than `load`: problems startup
+    /// only warns about reject the file, before the real config is touched
+    pub fn read_import(path: &Path) -> Result<Self> {
+        let contents =
+            fs::read_to_string(path).context(format!("Failed to read config file: {:?}", path))?;
+        let (config, from_version) =
+            Self::parse(&contents).context(format!("Invalid config file: {:?}", path))?;
+
+        if from_version > CONFIG_VERSION {
+            anyhow::bail!(
+                "{:?} is config version {} but this build only knows version {}",
+                path,
+                from_version,
+                CONFIG_VERSION
+            );
+        }
+        config
+            .validate()
+            .context(format!("Invalid config file: {:?}", path))?;
+
+        Ok(config)
+    }
+
+    /// Check the settings startup would otherwise only warn about and skip: subnet
+    /// overrides, limits written as strings, saved limits too slow to enforce, and
+    /// eBPF map sizes the kernel can't create or shouldn't be asked to
+    pub fn validate(&self) -> Result<()> {
+        self.parse_subnet_overrides()?;
+
+        let mut invalid = Vec::new();
+        if let Some(capacity) = self.ebpf_map_capacity
+            && !(1..=MAX_EBPF_MAP_CAPACITY).contains(&capacity)
+        {
+            invalid.push(format!(
+                "ebpf_map_capacity {}: must be between 1 and {}",
+                capacity, MAX_EBPF_MAP_CAPACITY
+            ));
+        }
+        for (interface, ceiling) in &self.interface_ceilings {
+            if let Err(e) = parse_bandwidth_limit(ceiling) {
+                invalid.push(format!("ceiling of {}: {}", interface, e));
+            }
+        }
+        if let Some(threshold) = &self.alert_threshold
+            && let Err(e) = parse_bandwidth_limit(threshold)
+        {
+            invalid.push(format!("alert threshold: {}", e));
+        }
+        for quota in &self.quotas {
+            if let Err(e) = parse_bandwidth_limit(&quota.limit) {
+                invalid.push(format!("quota for '{}': {}", quota.pattern, e));
+            }
+        }
+        for rule in &self.standing_rules {
+            if let Err(e) = crate::standing_rules::StandingRule::parse(rule) {
+                invalid.push(format!("standing rule for '{}': {:#}", rule.pattern, e));
+            }
+        }
+        for (pid, throttle) in &self.throttles {
+            for limit in [throttle.upload_limit, throttle.download_limit]
+                .into_iter()
+                .flatten()
+            {
+                if let Err(e) = crate::process::check_limit(limit) {
+                    invalid.push(format!(
+                        "throttle of {} (PID {}): {}",
+                        throttle.process_name, pid, e
+                    ));
+                }
+            }
+            if let Some(minimum) = throttle.upload_minimum
+                && let Err(e) = crate::process::check_minimum(minimum, throttle.upload_limit)
+            {
+                invalid.push(format!(
+                    "throttle of {} (PID {}): {}",
+                    throttle.process_name, pid, e
+                ));
+            }
+        }
+
+        if !invalid.is_empty() {
+            invalid.sort();
+            anyhow::bail!("Invalid settings: {}", invalid.join("; "));
+        }
+        Ok(())
+    }
+
+    /// Copy the current config file aside (to `throttles.json.bak`) before it is replaced.
+    /// Returns the backup path, or None if there is no config file yet
+    pub fn backup() -> Result<Option<PathBuf>> {
+        let path = Self::config_path()?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        let backup = path.with_extension("json.bak");
+        fs::copy(&path, &backup).context(format!("Failed to back up config to {:?}", backup))?;
+        Ok(Some(backup))
+    }
+
+    /// Merge imported throttles into this config, matched by process name since PIDs
+    /// differ between machines. Imported settings win on conflict but keep the local
+    /// PIDs; new names are added under their imported PIDs.
+    /// Returns one line per change, prefixed with +, ~ or -
+    pub fn merge_throttles(&mut self, imported: &HashMap<i32, SavedThrottle>) -> Vec<String> {
+        let mut imported: Vec<_> = imported.iter().collect();
+        imported.sort_by(|(a_pid, a), (b_pid, b)| {
+            a.process_name.cmp(&b.process_name).then(a_pid.cmp(b_pid))
+        });
+
+        let mut changes = Vec::new();
+        let mut updated_names = std::collections::HashSet::new();
+        for (pid, throttle) in imported {
+            let name = &throttle.process_name;
+            if updated_names.contains(name) {
+                continue;
+            }
+
+            let mut local: Vec<(&i32, &mut SavedThrottle)> = self
+                .throttles
+                .iter_mut()
+                .filter(|(_, local)| local.process_name == *name)
+                .collect();
+            if !local.is_empty() {
+                local.sort_by_key(|(pid, _)| **pid);
+                if *local[0].1 != *throttle {
+                    changes.push(format!(
+                        "~ {}: {} -> {}",
+                        name,
+                        local[0].1.describe(),
+                        throttle.describe()
+                    ));
+                }
+                for (_, local) in local {
+                    *local = throttle.clone();
+                }
+                updated_names.insert(name.clone());
+                continue;
+            }
+
+            changes.push(format!("+ {}: {}", name, throttle.describe()));
+            if let Some(displaced) = self.throttles.insert(*pid, throttle.clone()) {
+                changes.push(format!(
+                    "- {}: PID {} is taken by the imported {} throttle",
+                    displaced.process_name, pid, name
+                ));
+            }
+        }
+        changes
+    }
+
+    /// How long terminated processes stay listed (None = forever)
+    pub fn terminated_retention(&self) -> Option<std::time::Duration> {
+        u64::try_from(self.terminated_retention_secs)
+            .ok()
+            .map(std::time::Duration::from_secs)
+    }
+
+    /// Parse and validate the subnet overrides
+    pub fn parse_subnet_overrides(&self) -> Result<Vec<SubnetOverride>> {
+        let mut overrides = Vec::new();
+        let mut invalid = Vec::new();
+
+        for (cidr, category) in &self.subnet_overrides {
+            match cidr.parse::<Cidr>() {
+                Ok(cidr) => overrides.push(SubnetOverride {
+                    cidr,
+                    category: *category,
+                }),
+                Err(e) => invalid.push(e.to_string()),
+            }
+        }
+
+        if !invalid.is_empty() {
+            invalid.sort();
+            anyhow::bail!("Invalid subnet overrides: {}", invalid.join("; "));
+        }
+        Ok(overrides)
+    }
+
+    /// Save configuration to disk
+    pub fn save(&self) -> Result<()> {
+        let path = Self::config_path()?;
+
+        let contents = serde_json::to_string_pretty(self).context("Failed to serialize config")?;
+
+        fs::write(&path, contents).context(format!("Failed to write config file: {:?}", path))?;
+
+        log::info!("Saved configuration to {:?}", path);
+        Ok(())
+    }
+
+    /// Add or update a throttle
+    pub fn set_throttle(&mut self, pid: i32, throttle: SavedThrottle) {
+        self.throttles.insert(pid, throttle);
+    }
+
+    /// Remove a throttle
+    pub fn remove_throttle(&mut self, pid: i32) {
+        self.throttles.remove(&pid);
+    }
+
+    /// Get all saved throttles
+    pub fn get_throttles(&self) -> &HashMap<i32, SavedThrottle> {
+        &self.throttles
+    }
+
+    /// Clear all throttles
+    pub fn clear_throttles(&mut self) {
+        self.throttles.clear();
+    }
+}
+
+/// Upgrade a parsed config to `CONFIG_VERSION`, returning the version it was written with
+fn migrate(value: &mut Value) -> u32 {
+    let from_version = value
+        .get("version")
+        .and_then(Value::as_u64)
+        .map_or(0, |v| v as u32);
+
+    for (version, step) in MIGRATIONS.iter().enumerate().skip(from_version as usize) {
+        log::debug!("Migrating config from version {}", version);
+        step(value);
+    }
+    if let Some(object) = value.as_object_mut() {
+        object.insert(
+            "version".to_string(),
+            from_version.max(CONFIG_VERSION).into(),
+        );
+    }
+    from_version
+}
+
+/// v0 -> v1: throttles record which traffic they apply to. Before that every
+/// restored throttle applied to all traffic, so say so explicitly
+fn migrate_v0_to_v1(value: &mut Value) {
+    let Some(throttles) = value.get_mut("throttles").and_then(Value::as_object_mut) else {
+        return;
+    };
+    for throttle in throttles.values_mut() {
+        if let Some(throttle) = throttle.as_object_mut() {
+            throttle
+                .entry("traffic_type")
+                .or_insert_with(|| serde_json::json!(TrafficType::All));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_serialization() {
+        let mut config = Config::default();
+        config.auto_restore = true;
+        config.set_throttle(
+            1234,
+            SavedThrottle {
+                process_name: "firefox".to_string(),
+                upload_limit: Some(1000000),
+                upload_minimum: None,
+                download_limit: Some(5000000),
+                interfaces: None,
+                traffic_type: TrafficType::Internet,
+                upload_backend: Some("tc_htb".to_string()),
+                download_backend: None,
+                policy: None,
+                family: AddressFamily::Any,
+                priority: Priority::Normal,
+                include_children: false,
+                downgraded_from: None,
+            },
+        );
+
+        let json = serde_json::to_string_pretty(&config).unwrap();
+        println!("{}", json);
+
+        let deserialized: Config = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.throttles.len(), 1);
+        assert_eq!(deserialized.auto_restore, true);
+    }
+
+    #[test]
+    fn test_config_theme() {
+        let config: Config = serde_json::from_str(r#"{"theme": "high-contrast"}"#).unwrap();
+        assert_eq!(config.theme, Some(ThemeName::HighContrast));
+
+        // Older configs without a theme still load
+        let config: Config = serde_json::from_str("{}").unwrap();
+        assert_eq!(config.theme, None);
+    }
+
+    #[test]
+    fn test_webhook_event_filter() {
+        let config: Config = serde_json::from_str(
+            r#"{"webhooks": {"url": "https://hooks.example/x", "events": ["applied", "limit_exceeded"]}}"#,
+        )
+        .unwrap();
+        let webhooks = config.webhooks.unwrap();
+        assert_eq!(
+            webhooks.events,
+            vec![ThrottleEventKind::Applied, ThrottleEventKind::LimitExceeded]
+        );
+
+        // Without a filter every event is sent
+        let config: Config =
+            serde_json::from_str(r#"{"webhooks": {"url": "https://hooks.example/x"}}"#).unwrap();
+        assert_eq!(config.webhooks.unwrap().events, ThrottleEventKind::ALL);
+    }
+
+    #[test]
+    fn test_subnet_overrides_validated() {
+        let config: Config = serde_json::from_str(
+            r#"{"subnet_overrides": {"10.8.0.0/24": "internet", "fd00::/8": "local"}}"#,
+        )
+        .unwrap();
+        assert_eq!(config.parse_subnet_overrides().unwrap().len(), 2);
+
+        let config: Config =
+            serde_json::from_str(r#"{"subnet_overrides": {"10.8.0.0/40": "internet"}}"#).unwrap();
+        let err = config.parse_subnet_overrides().unwrap_err();
+        assert!(err.to_string().contains("10.8.0.0/40"));
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_limits() {
+        let config: Config = serde_json::from_str(
+            r#"{"interface_ceilings": {"eth0": "40M"}, "alert_threshold": "50M",
+                "quotas": [{"match": "steam", "limit": "5G"}]}"#,
+        )
+        .unwrap();
+        assert!(config.validate().is_ok());
+
+        let config: Config = serde_json::from_str(
+            r#"{"interface_ceilings": {"eth0": "fast"},
+                "quotas": [{"match": "steam", "limit": "lots"}],
+                "standing_rules": [{"match": "apt*"}],
+                "throttles": {"42": {"process_name": "curl", "upload_limit": 10,
+                                     "download_limit": null}}}"#,
+        )
+        .unwrap();
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("ceiling of eth0"));
+        assert!(err.contains("quota for 'steam'"));
+        assert!(err.contains("standing rule for 'apt*'"));
+        assert!(err.contains("throttle of curl (PID 42)"));
+
+        // A minimum above the limit it is guaranteed under
+        let config: Config = serde_json::from_str(
+            r#"{"throttles": {"7": {"process_name": "voip", "upload_limit": 1024,
+                                    "upload_minimum": 4096, "download_limit": null}}}"#,
+        )
+        .unwrap();
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("above the upload limit"));
+
+        // eBPF maps with no entries, or too many to be a sane kernel allocation
+        for capacity in [0, MAX_EBPF_MAP_CAPACITY + 1, u32::MAX] {
+            let config = Config {
+                ebpf_map_capacity: Some(capacity),
+                ..Config::default()
+            };
+            let err = config.validate().unwrap_err().to_string();
+            assert!(err.contains("ebpf_map_capacity"));
+        }
+        for capacity in [1, 4096, MAX_EBPF_MAP_CAPACITY] {
+            let config = Config {
+                ebpf_map_capacity: Some(capacity),
+                ..Config::default()
+            };
+            assert!(config.validate().is_ok());
+        }
+    }
+
+    #[test]
+    fn test_terminated_retention() {
+        assert_eq!(
+            Config::default().terminated_retention(),
+            Some(std::time::Duration::from_secs(30))
+        );
+
+        let config: Config = serde_json::from_str(r#"{"terminated_retention_secs": 0}"#).unwrap();
+        assert_eq!(
+            config.terminated_retention(),
+            Some(std::time::Duration::ZERO)
+        );
+
+        // Negative keeps terminated processes forever
+        let config: Config = serde_json::from_str(r#"{"terminated_retention_secs": -1}"#).unwrap();
+        assert_eq!(config.terminated_retention(), None);
+    }
+
+    #[test]
+    fn test_merge_throttles_by_process_name() {
+        let throttle = |name: &str, download_limit: u64| SavedThrottle {
+            process_name: name.to_string(),
+            upload_limit: None,
+            upload_minimum: None,
+            download_limit: Some(download_limit),
+            interfaces: None,
+            traffic_type: TrafficType::All,
+            upload_backend: None,
+            download_backend: None,
+            policy: None,
+            family: AddressFamily::Any,
+            priority: Priority::Normal,
+            include_children: false,
+            downgraded_from: None,
+        };
+        let mut config = Config::default();
+        config.set_throttle(100, throttle("firefox", 1000));
+        config.set_throttle(200, throttle("steam", 5000));
+        config.set_throttle(300, throttle("curl", 2000));
+
+        let imported = HashMap::from([
+            (900, throttle("firefox", 3000)), // conflicts with the local firefox throttle
+            (200, throttle("discord", 4000)), // new, reusing steam's PID
+            (950, throttle("curl", 2000)),    // same limits as the local one
+        ]);
+        let changes = config.merge_throttles(&imported);
+
+        assert_eq!(changes.len(), 3);
+        assert!(changes.iter().any(|c| c.starts_with("+ discord")));
+        assert!(changes.iter().any(|c| c.starts_with("- steam")));
+        assert!(changes.iter().any(|c| c.starts_with("~ firefox")));
+        assert_eq!(config.throttles.len(), 3);
+        assert_eq!(config.throttles[&100].download_limit, Some(3000));
+        assert_eq!(config.throttles[&200].process_name, "discord");
+        assert_eq!(config.throttles[&300].process_name, "curl");
+    }
+
+    #[test]
+    fn test_migrate_unversioned_config() {
+        let mut value: Value = serde_json::from_str(
+            r#"{"throttles": {"42": {"process_name": "curl", "upload_limit": 1000,
+                "download_limit": null}}}"#,
+        )
+        .unwrap();
+        assert_eq!(migrate(&mut value), 0);
+
+        let config: Config = serde_json::from_value(value).unwrap();
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(config.throttles[&42].traffic_type, TrafficType::All);
+
+        // Current configs pass through untouched
+        let mut value = serde_json::to_value(Config::default()).unwrap();
+        let before = value.clone();
+        assert_eq!(migrate(&mut value), CONFIG_VERSION);
+        assert_eq!(value, before);
+    }
+}
@@ -0,0 +1,280 @@
+// Connection open/close events (the "Events" section of the Connections tab)
+//
+// Every update, each process's connections are diffed against the previous
+// update's. Connections are identified by (local port, remote address, remote
+// port, socket inode), so a reordered connection list never looks like churn. A
+// process seen for the first time only sets the baseline. Events are kept in a
+// ring buffer per process, so memory stays bounded however long a process runs.
+
+use crate::process::{ConnectionDetail, ProcessMap};
+use std::collections::{HashMap, VecDeque};
+use std::net::IpAddr;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// Events kept per process (the oldest are dropped first)
+pub const MAX_EVENTS_PER_PROCESS: usize = 100;
+
+static LOG_EVENTS: OnceLock<bool> = OnceLock::new();
+
+/// Also write connection events to the log (call once at startup, before monitoring)
+pub fn init_event_logging(enabled: bool) {
+    LOG_EVENTS.get_or_init(|| enabled);
+}
+
+fn event_logging() -> bool {
+    LOG_EVENTS.get().copied().unwrap_or(false)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionEventKind {
+    Opened,
+    Closed,
+}
+
+/// A connection that appeared or disappeared between two updates
+#[derive(Debug, Clone)]
+pub struct ConnectionEvent {
+    pub kind: ConnectionEventKind,
+    pub connection: ConnectionDetail,
+    /// When the update that noticed the change ran
+    pub at: Instant,
+}
+
+impl ConnectionEvent {
+    /// e.g. "+ 142.250.74.110:443 (ESTABLISHED)" or "- 10.0.0.5:22"
+    ///
+    /// Closed connections leave out the state, since it's the last one seen. Sockets
+    /// without a peer (listeners, unconnected UDP) show their local address instead.
+    pub fn summary(&self) -> String {
+        let conn = &self.connection;
+        let (sign, state) = match self.kind {
+            ConnectionEventKind::Opened => ('+', conn.state.to_uppercase()),
+            ConnectionEventKind::Closed => ('-', String::new()),
+        };
+        let target = if conn.remote_port == 0 {
+            format!(
+                "{} {}",
+                local_label(conn.protocol.as_str()),
+                endpoint(&conn.local_addr, conn.local_port)
+            )
+        } else {
+            endpoint(&conn.remote_addr, conn.remote_port)
+        };
+        if state.is_empty() {
+            format!("{} {}", sign, target)
+        } else {
+            format!("{} {} ({})", sign, target, state)
+        }
+    }
+
+    /// How long ago the event happened, e.g. "3s ago", "5m ago"
+    pub fn age_label(&self, now: Instant) -> String {
+        format!(
+            "{} ago",
+            compact_duration(now.saturating_duration_since(self.at))
+        )
+    }
+}
+
+fn local_label(protocol: &str) -> &'static str {
+    if protocol.starts_with("TCP") {
+        "listening on"
+    } else {
+        "bound to"
+    }
+}
+
+fn endpoint(addr: &IpAddr, port: u16) -> String {
+    match addr {
+        IpAddr::V6(_) => format!("[{}]:{}", addr, port),
+        IpAddr::V4(_) => format!("{}:{}", addr, port),
+    }
+}
+
+fn compact_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match secs {
+        0..60 => format!("{}s", secs),
+        60..3600 => format!("{}m", secs / 60),
+        _ => format!("{}h", secs / 3600),
+    }
+}
+
+/// What identifies a connection between updates
+type ConnectionId = (u16, IpAddr, u16, u64);
+
+fn connection_id(conn: &ConnectionDetail) -> ConnectionId {
+    (
+        conn.local_port,
+        conn.remote_addr,
+        conn.remote_port,
+        conn.inode,
+    )
+}
+
+/// Diffs each process's connections between updates and keeps its recent events
+#[derive(Debug, Default)]
+pub struct ConnectionEventTracker {
+    previous: HashMap<i32, HashMap<ConnectionId, ConnectionDetail>>,
+    events: HashMap<i32, VecDeque<ConnectionEvent>>,
+}
+
+impl ConnectionEventTracker {
+    /// Diff the processes' connections against the previous update, and give each
+    /// process its recent events (newest first)
+    pub fn update(&mut self, processes: &mut ProcessMap, now: Instant) {
+        // Forget processes that are gone so reused PIDs start fresh
+        self.previous.retain(|pid, _| processes.contains_key(pid));
+        self.events.retain(|pid, _| processes.contains_key(pid));
+
+        for process in processes.values_mut() {
+            let current: HashMap<ConnectionId, ConnectionDetail> = process
+                .connections
+                .iter()
+                .map(|conn| (connection_id(conn), conn.clone()))
+                .collect();
+
+            // The first sighting only sets the baseline: those connections aren't news
+            if let Some(previous) = self.previous.get(&process.pid) {
+                let mut changes: Vec<ConnectionEvent> = current
+                    .iter()
+                    .filter(|(id, _)| !previous.contains_key(id))
+                    .map(|(_, conn)| (ConnectionEventKind::Opened, conn))
+                    .chain(
+                        previous
+                            .iter()
+                            .filter(|(id, _)| !current.contains_key(id))
+                            .map(|(_, conn)| (ConnectionEventKind::Closed, conn)),
+                    )
+                    .map(|(kind, conn)| ConnectionEvent {
+                        kind,
+                        connection: conn.clone(),
+                        at: now,
+                    })
+                    .collect();
+                // Same-update events in a stable order
+                changes.sort_by_key(|event| {
+                    (
+                        event.kind == ConnectionEventKind::Opened,
+                        connection_id(&event.connection),
+                    )
+                });
+
+                if !changes.is_empty() {
+                    let events = self.events.entry(process.pid).or_default();
+                    for event in changes {
+                        if event_logging() {
+                            log::info!(
+                                "Connection {}: {} (PID {}) {}",
+                                match event.kind {
+                                    ConnectionEventKind::Opened => "opened",
+                                    ConnectionEventKind::Closed => "closed",
+                                },
+                                process.name,
+                                process.pid,
+                                event.summary()
+                            );
+                        }
+                        if events.len() == MAX_EVENTS_PER_PROCESS {
+                            events.pop_front();
+                        }
+                        events.push_back(event);
+                    }
+                }
+            }
+            self.previous.insert(process.pid, current);
+
+            process.connection_events = self
+                .events
+                .get(&process.pid)
+                .map(|events| events.iter().rev().cloned().collect())
+                .unwrap_or_default();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::ProcessInfo;
+
+    fn connection(remote_port: u16, inode: u64) -> ConnectionDetail {
+        ConnectionDetail {
+            protocol: "TCP".to_string(),
+            local_addr: "10.0.0.2".parse().unwrap(),
+            local_port: 40000 + remote_port,
+            remote_addr: "142.250.74.110".parse().unwrap(),
+            remote_port,
+            state: "Established".to_string(),
+            inode,
+        }
+    }
+
+    fn processes(connections: Vec<ConnectionDetail>) -> ProcessMap {
+        let mut process = ProcessInfo::new(42, "curl".to_string());
+        process.connections = connections;
+        ProcessMap::from([(42, process)])
+    }
+
+    #[test]
+    fn test_connection_events_diff_between_updates() {
+        let mut tracker = ConnectionEventTracker::default();
+        let start = Instant::now();
+
+        // The first sighting is only the baseline
+        let mut map = processes(vec![connection(443, 1), connection(22, 2)]);
+        tracker.update(&mut map, start);
+        assert!(map[&42].connection_events.is_empty());
+
+        // Reordering the same connections is no churn
+        let mut map = processes(vec![connection(22, 2), connection(443, 1)]);
+        tracker.update(&mut map, start + Duration::from_secs(1));
+        assert!(map[&42].connection_events.is_empty());
+
+        let mut map = processes(vec![connection(443, 1), connection(8443, 3)]);
+        tracker.update(&mut map, start + Duration::from_secs(2));
+        let summaries: Vec<String> = map[&42]
+            .connection_events
+            .iter()
+            .map(|event| event.summary())
+            .collect();
+        assert_eq!(
+            summaries,
+            ["+ 142.250.74.110:8443 (ESTABLISHED)", "- 142.250.74.110:22"]
+        );
+        assert_eq!(
+            map[&42].connection_events[0].age_label(start + Duration::from_secs(5)),
+            "3s ago"
+        );
+
+        // A reused socket inode for the same endpoint is a new connection
+        let mut map = processes(vec![connection(443, 4), connection(8443, 3)]);
+        tracker.update(&mut map, start + Duration::from_secs(3));
+        assert_eq!(map[&42].connection_events.len(), 4);
+    }
+
+    #[test]
+    fn test_connection_events_are_bounded() {
+        let mut tracker = ConnectionEventTracker::default();
+        let now = Instant::now();
+        let mut map = processes(Vec::new());
+        tracker.update(&mut map, now);
+
+        for port in 1..=MAX_EVENTS_PER_PROCESS as u16 {
+            let mut map = processes(vec![connection(port, port as u64)]);
+            tracker.update(&mut map, now);
+        }
+        let mut map = processes(Vec::new());
+        tracker.update(&mut map, now);
+
+        let events = &map[&42].connection_events;
+        assert_eq!(events.len(), MAX_EVENTS_PER_PROCESS);
+        // Newest first: the last connection closing
+        assert_eq!(events[0].kind, ConnectionEventKind::Closed);
+        assert_eq!(
+            events[0].connection.remote_port,
+            MAX_EVENTS_PER_PROCESS as u16
+        );
+    }
+}
@@ -0,0 +1,105 @@
+// Processes chadthrottle's own control path runs through (our shell, terminal, sshd, ...)
+//
+// Throttling one of these can lock the user out of the machine (e.g. limiting the
+// sshd session you're connected through), so they need explicit confirmation.
+
+use crate::backends::process::ProcessUtils;
+use std::collections::HashMap;
+
+/// How far up the process tree to look for our ancestors
+const MAX_ANCESTOR_DEPTH: usize = 32;
+
+/// PIDs on chadthrottle's control path, with why each one is on it
+#[derive(Debug, Clone, Default)]
+pub struct ControlPath {
+    protected: HashMap<i32, String>,
+}
+
+impl ControlPath {
+    /// Detect the control path of the running chadthrottle process
+    pub fn detect(process_utils: &dyn ProcessUtils) -> Self {
+        let over_ssh =
+            std::env::var_os("SSH_CONNECTION").is_some() || std::env::var_os("SSH_TTY").is_some();
+        Self::for_process(std::process::id() as i32, over_ssh, process_utils)
+    }
+
+    /// Control path of `pid`: the process itself, its ancestors and, when it runs
+    /// over SSH, every sshd (under tmux/screen the session's sshd isn't an ancestor)
+    pub fn for_process(pid: i32, over_ssh: bool, process_utils: &dyn ProcessUtils) -> Self {
+        let mut protected = HashMap::new();
+        protected.insert(pid, "it is chadthrottle itself".to_string());
+
+        let mut current = pid;
+        for _ in 0..MAX_ANCESTOR_DEPTH {
+            let Some(parent) = process_utils.get_parent_pid(current) else {
+                break;
+            };
+            if protected.contains_key(&parent) {
+                break;
+            }
+            let name = process_utils
+                .get_process_name(parent)
+                .unwrap_or_else(|_| format!("PID {}", parent));
+            protected.insert(
+                parent,
+                format!(
+                    "{} is an ancestor of chadthrottle (your shell/terminal)",
+                    name
+                ),
+            );
+            current = parent;
+        }
+
+        if over_ssh && let Ok(processes) = process_utils.get_all_processes() {
+            for entry in processes.iter().filter(|p| is_sshd(&p.name)) {
+                protected
+                    .entry(entry.pid)
+                    .or_insert_with(|| format!("{} may be carrying this SSH session", entry.name));
+            }
+        }
+
+        Self { protected }
+    }
+
+    /// Why throttling `pid` is risky, if it is on the control path
+    pub fn reason(&self, pid: i32) -> Option<&str> {
+        self.protected.get(&pid).map(String::as_str)
+    }
+}
+
+fn is_sshd(name: &str) -> bool {
+    name == "sshd" || name.starts_with("sshd:") || name.starts_with("sshd-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backends::mock::MockProcessUtils;
+
+    #[test]
+    fn test_control_path_covers_ancestors_and_sshd() {
+        let mut processes = MockProcessUtils::with_processes(&[
+            (1, "systemd"),
+            (500, "sshd"),
+            (600, "sshd"),
+            (700, "bash"),
+            (800, "chadthrottle"),
+            (900, "firefox"),
+        ]);
+        processes.parents = HashMap::from([(500, 1), (700, 600), (800, 700), (900, 1)]);
+
+        // Locally only our ancestors are protected
+        let local = ControlPath::for_process(800, false, &processes);
+        assert!(local.reason(800).is_some());
+        assert!(local.reason(700).unwrap().contains("bash"));
+        assert!(local.reason(600).is_some());
+        assert!(local.reason(500).is_none());
+        assert!(local.reason(900).is_none());
+
+        // Over SSH every sshd is, but unrelated processes still aren't
+        let remote = ControlPath::for_process(800, true, &processes);
+        assert!(remote.reason(500).is_some());
+        assert!(remote.reason(900).is_none());
+        assert!(remote.reason(1).is_none());
+    }
+}
@@ -0,0 +1,130 @@
+// Throttle events for consumers outside the throttle manager (webhooks, TUI alerts)
+//
+// The manager publishes an event whenever a throttle is applied or removed or a
+// backend fails; bandwidth alerts are published alongside them. Consumers drain the
+// bus once per tick, so every one of them sees the same stream in the same order.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// What happened
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThrottleEventKind {
+    /// A throttle was applied (or re-applied with new limits)
+    Applied,
+    /// A throttle was removed
+    Removed,
+    /// A process's rate crossed the alert threshold
+    LimitExceeded,
+    /// A backend failed to apply a throttle
+    BackendFailed,
+    /// A process name used more than its daily quota
+    QuotaExceeded,
+    /// A throttled process kept moving well over its limit
+    ThrottleNotWorking,
+}
+
+impl ThrottleEventKind {
+    pub const ALL: [ThrottleEventKind; 6] = [
+        ThrottleEventKind::Applied,
+        ThrottleEventKind::Removed,
+        ThrottleEventKind::LimitExceeded,
+        ThrottleEventKind::BackendFailed,
+        ThrottleEventKind::QuotaExceeded,
+        ThrottleEventKind::ThrottleNotWorking,
+    ];
+}
+
+impl fmt::Display for ThrottleEventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThrottleEventKind::Applied => write!(f, "applied"),
+            ThrottleEventKind::Removed => write!(f, "removed"),
+            ThrottleEventKind::LimitExceeded => write!(f, "limit_exceeded"),
+            ThrottleEventKind::BackendFailed => write!(f, "backend_failed"),
+            ThrottleEventKind::QuotaExceeded => write!(f, "quota_exceeded"),
+            ThrottleEventKind::ThrottleNotWorking => write!(f, "throttle_not_working"),
+        }
+    }
+}
+
+/// One event, serialized as the webhook payload
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThrottleEvent {
+    pub event: ThrottleEventKind,
+    pub pid: i32,
+    pub name: String,
+    /// Limits in bytes/sec (None = unlimited or not applicable)
+    pub download_limit: Option<u64>,
+    pub upload_limit: Option<u64>,
+    /// Backend(s) involved, e.g. "tc_htb" or "ebpf/ifb_tc" for upload/download
+    pub backend: Option<String>,
+    /// Unix timestamp (milliseconds)
+    pub timestamp: u64,
+    /// Error for backend_failed, rate and threshold for limit_exceeded, usage and
+    /// quota for quota_exceeded, share of the limit for throttle_not_working
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl ThrottleEvent {
+    pub fn new(event: ThrottleEventKind, pid: i32, name: impl Into<String>) -> Self {
+        Self {
+            event,
+            pid,
+            name: name.into(),
+            download_limit: None,
+            upload_limit: None,
+            backend: None,
+            timestamp: chrono::Utc::now().timestamp_millis() as u64,
+            detail: None,
+        }
+    }
+
+    pub fn with_limits(mut self, download_limit: Option<u64>, upload_limit: Option<u64>) -> Self {
+        self.download_limit = download_limit;
+        self.upload_limit = upload_limit;
+        self
+    }
+
+    /// Record the upload and download backends, joined when they differ
+    pub fn with_backends(mut self, upload: Option<&str>, download: Option<&str>) -> Self {
+        self.backend = match (upload, download) {
+            (Some(upload), Some(download)) if upload != download => {
+                Some(format!("{}/{}", upload, download))
+            }
+            (Some(backend), _) | (None, Some(backend)) => Some(backend.to_string()),
+            (None, None) => None,
+        };
+        self
+    }
+
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+}
+
+/// Events published since the last drain
+#[derive(Debug, Default)]
+pub struct EventBus {
+    pending: Vec<ThrottleEvent>,
+}
+
+impl EventBus {
+    pub fn publish(&mut self, event: ThrottleEvent) {
+        log::debug!(
+            "Event {} for {} (PID {})",
+            event.event,
+            event.name,
+            event.pid
+        );
+        self.pending.push(event);
+    }
+
+    /// Take every pending event, oldest first
+    pub fn drain(&mut self) -> Vec<ThrottleEvent> {
+        std::mem::take(&mut self.pending)
+    }
+}
@@ -0,0 +1,55 @@
+// Human-readable byte counts and rates, shared by the TUI and the CLI output
+//
+// Units are binary (1 KB = 1024 B). Below 1 KB the exact count is shown, above it
+// one decimal place.
+
+const UNITS: [&str; 3] = ["KB", "MB", "GB"];
+
+/// Format a byte count, e.g. "512 B", "1.5 MB"
+pub fn format_bytes(bytes: u64) -> String {
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    // Move up a unit when the rounded value would read 1024.0 (1048575 B is "1.0 MB",
+    // not "1024.0 KB")
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while unit + 1 < UNITS.len() && (value * 10.0).round() >= 1024.0 * 10.0 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+/// Format a rate in bytes per second, e.g. "512 B/s", "1.5 MB/s"
+pub fn format_rate(bytes_per_sec: u64) -> String {
+    format!("{}/s", format_bytes(bytes_per_sec))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_bytes_boundaries() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1024), "1.0 KB");
+        assert_eq!(format_bytes(1_048_575), "1.0 MB");
+        assert_eq!(format_bytes(1_048_576), "1.0 MB");
+        assert_eq!(format_bytes(1_572_864), "1.5 MB");
+        assert_eq!(format_bytes(1024 * 1_048_576), "1.0 GB");
+        assert_eq!(format_bytes(5000 * 1024 * 1_048_576), "5000.0 GB");
+    }
+
+    #[test]
+    fn test_format_rate_suffix() {
+        assert_eq!(format_rate(1023), "1023 B/s");
+        assert_eq!(format_rate(1024), "1.0 KB/s");
+        assert_eq!(format_rate(1_048_575), "1.0 MB/s");
+        assert_eq!(format_rate(1_048_576), "1.0 MB/s");
+        for value in [0, 1023, 1024, 1_048_575, 1_048_576, u64::MAX] {
+            assert_eq!(format_rate(value), format!("{}/s", format_bytes(value)));
+        }
+    }
+}
@@ -0,0 +1,466 @@
+// Bandwidth history tracking for graphing
+
+use crate::backends::monitor::MAX_SAMPLE_INTERVAL;
+use crate::history_store::HistoryStore;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// Default number of history samples to keep per process (60 samples = 1 minute at 1Hz)
+pub const DEFAULT_HISTORY_SAMPLES: usize = 60;
+
+/// Seconds a sample covers when there's nothing to measure against (the monitor's tick)
+const NOMINAL_SAMPLE_INTERVAL: f64 = 1.0;
+
+fn nominal_sample_interval() -> f64 {
+    NOMINAL_SAMPLE_INTERVAL
+}
+
+/// A single bandwidth measurement sample
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BandwidthSample {
+    pub timestamp: u64,     // Unix timestamp in seconds
+    pub download_rate: u64, // bytes per second
+    pub upload_rate: u64,   // bytes per second
+    // Seconds the rates were measured over (0 after a gap such as a suspend, when
+    // they carry no weight)
+    #[serde(default = "nominal_sample_interval")]
+    pub interval_secs: f64,
+}
+
+impl BandwidthSample {
+    /// Create a sample stamped with the current time
+    pub fn now(download_rate: u64, upload_rate: u64, interval_secs: f64) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        Self {
+            timestamp,
+            download_rate,
+            upload_rate,
+            interval_secs,
+        }
+    }
+}
+
+/// Bandwidth history for a single process
+#[derive(Debug, Clone)]
+pub struct ProcessHistory {
+    pub pid: i32,
+    pub process_name: String,
+    pub samples: VecDeque<BandwidthSample>,
+    // Number of samples kept (the window the graph and max/avg cover)
+    max_samples: usize,
+    // When the last sample was added, to measure the next one's interval
+    last_sample_at: Option<Instant>,
+}
+
+impl ProcessHistory {
+    pub fn new(pid: i32, process_name: String, max_samples: usize) -> Self {
+        let max_samples = max_samples.max(1);
+        Self {
+            pid,
+            process_name,
+            samples: VecDeque::with_capacity(max_samples),
+            max_samples,
+            last_sample_at: None,
+        }
+    }
+
+    /// Number of samples kept
+    pub fn max_samples(&self) -> usize {
+        self.max_samples
+    }
+
+    /// Seconds the kept samples span (one per second)
+    pub fn window_secs(&self) -> f64 {
+        self.max_samples as f64 * NOMINAL_SAMPLE_INTERVAL
+    }
+
+    /// Add a new sample, removing old ones if we exceed the limit
+    pub fn add_sample(&mut self, download_rate: u64, upload_rate: u64) {
+        self.add_sample_at(download_rate, upload_rate, Instant::now());
+    }
+
+    /// Add a sample taken at `at`, covering the time since the previous one. After a
+    /// gap longer than the monitor measures over (a suspend), the sample gets no weight
+    fn add_sample_at(&mut self, download_rate: u64, upload_rate: u64, at: Instant) {
+        let interval_secs = match self.last_sample_at {
+            None => NOMINAL_SAMPLE_INTERVAL,
+            Some(last) => {
+                let elapsed = at.saturating_duration_since(last);
+                if elapsed > MAX_SAMPLE_INTERVAL {
+                    0.0
+                } else {
+                    elapsed.as_secs_f64()
+                }
+            }
+        };
+        self.last_sample_at = Some(at);
+        self.push_sample(BandwidthSample::now(
+            download_rate,
+            upload_rate,
+            interval_secs,
+        ));
+    }
+
+    /// Add an existing sample (e.g. loaded from disk), removing old ones if we exceed the limit
+    pub fn push_sample(&mut self, sample: BandwidthSample) {
+        self.samples.push_back(sample);
+
+        self.truncate();
+    }
+
+    /// Remove the oldest samples beyond the window
+    fn truncate(&mut self) {
+        while self.samples.len() > self.max_samples {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Get the maximum download rate in history
+    pub fn max_download_rate(&self) -> u64 {
+        self.samples
+            .iter()
+            .map(|s| s.download_rate)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Get the maximum upload rate in history
+    pub fn max_upload_rate(&self) -> u64 {
+        self.samples
+            .iter()
+            .map(|s| s.upload_rate)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Get the average download rate (weighted by the time each sample covers)
+    pub fn avg_download_rate(&self) -> u64 {
+        self.time_weighted_average(|s| s.download_rate)
+    }
+
+    /// Get the average upload rate (weighted by the time each sample covers)
+    pub fn avg_upload_rate(&self) -> u64 {
+        self.time_weighted_average(|s| s.upload_rate)
+    }
+
+    /// Get the download rate `p` percent of the samples are at or below (nearest rank,
+    /// `p` from 0 to 100; 50 is the median)
+    pub fn percentile_download_rate(&self, p: f64) -> u64 {
+        self.percentile(p, |s| s.download_rate)
+    }
+
+    /// Get the upload rate `p` percent of the samples are at or below (nearest rank)
+    pub fn percentile_upload_rate(&self, p: f64) -> u64 {
+        self.percentile(p, |s| s.upload_rate)
+    }
+
+    fn percentile(&self, p: f64, rate: impl Fn(&BandwidthSample) -> u64) -> u64 {
+        let mut rates: Vec<u64> = self.samples.iter().map(rate).collect();
+        if rates.is_empty() {
+            return 0;
+        }
+        rates.sort_unstable();
+        let rank = (p.clamp(0.0, 100.0) / 100.0 * rates.len() as f64).ceil() as usize;
+        rates[rank.saturating_sub(1)]
+    }
+
+    fn time_weighted_average(&self, rate: impl Fn(&BandwidthSample) -> u64) -> u64 {
+        let total_secs: f64 = self.samples.iter().map(|s| s.interval_secs).sum();
+        if total_secs <= 0.0 {
+            return 0;
+        }
+        let total_bytes: f64 = self
+            .samples
+            .iter()
+            .map(|s| rate(s) as f64 * s.interval_secs)
+            .sum();
+        (total_bytes / total_secs) as u64
+    }
+
+    /// Get samples as vectors for graphing, placed at their age in seconds relative to
+    /// `now` (a Unix timestamp): the newest sample is at about 0, older ones below
+    pub fn get_graph_data(&self, now: u64) -> (Vec<(f64, f64)>, Vec<(f64, f64)>) {
+        let age = |s: &BandwidthSample| s.timestamp as f64 - now as f64;
+        let download_data: Vec<(f64, f64)> = self
+            .samples
+            .iter()
+            .map(|s| (age(s), s.download_rate as f64))
+            .collect();
+
+        let upload_data: Vec<(f64, f64)> = self
+            .samples
+            .iter()
+            .map(|s| (age(s), s.upload_rate as f64))
+            .collect();
+
+        (download_data, upload_data)
+    }
+}
+
+/// Global history tracker for all processes
+#[derive(Debug)]
+pub struct HistoryTracker {
+    pub histories: HashMap<i32, ProcessHistory>,
+    /// Persisted samples by process name, waiting for a process with that name to appear
+    seeded: HashMap<String, Vec<BandwidthSample>>,
+    /// On-disk history (None unless persistence is enabled)
+    store: Option<HistoryStore>,
+    /// Samples kept per process
+    max_samples: usize,
+}
+
+impl HistoryTracker {
+    pub fn new() -> Self {
+        Self {
+            histories: HashMap::new(),
+            seeded: HashMap::new(),
+            store: None,
+            max_samples: DEFAULT_HISTORY_SAMPLES,
+        }
+    }
+
+    /// Change how many samples are kept per process (at least 1), dropping the
+    /// oldest samples of existing histories if the window shrinks
+    pub fn set_window(&mut self, max_samples: usize) {
+        self.max_samples = max_samples.max(1);
+        for history in self.histories.values_mut() {
+            history.max_samples = self.max_samples;
+            history.truncate();
+        }
+    }
+
+    /// Persist samples through `store` and load its saved history.
+    /// Saved samples are matched by process name when a process first shows up
+    pub fn enable_persistence(&mut self, mut store: HistoryStore) -> anyhow::Result<()> {
+        self.seeded = store.load()?;
+        self.store = Some(store);
+        Ok(())
+    }
+
+    /// Write any buffered samples to disk (no-op without persistence)
+    pub fn flush_persisted(&mut self) {
+        if let Some(store) = self.store.as_mut()
+            && let Err(e) = store.flush()
+        {
+            log::warn!("Failed to persist bandwidth history: {}", e);
+        }
+    }
+
+    /// Update history for a process
+    pub fn update(&mut self, pid: i32, process_name: String, download_rate: u64, upload_rate: u64) {
+        let seeded = &mut self.seeded;
+        let max_samples = self.max_samples;
+        let history = self.histories.entry(pid).or_insert_with(|| {
+            let mut history = ProcessHistory::new(pid, process_name.clone(), max_samples);
+            // Continue where a previous run left off (first PID with this name wins)
+            for sample in seeded.remove(&process_name).unwrap_or_default() {
+                history.push_sample(sample);
+            }
+            history
+        });
+
+        history.add_sample(download_rate, upload_rate);
+        if let Some(store) = self.store.as_mut()
+            && let Some(sample) = history.samples.back()
+        {
+            store.record(&process_name, sample.clone());
+        }
+
+        // Update process name in case it changed
+        history.process_name = process_name;
+    }
+
+    /// Get history for a specific process
+    pub fn get_history(&self, pid: i32) -> Option<&ProcessHistory> {
+        self.histories.get(&pid)
+    }
+
+    /// Get mutable history for a specific process
+    pub fn get_history_mut(&mut self, pid: i32) -> Option<&mut ProcessHistory> {
+        self.histories.get_mut(&pid)
+    }
+
+    /// Remove history for a process (when it exits)
+    pub fn remove(&mut self, pid: i32) {
+        self.histories.remove(&pid);
+    }
+
+    /// Clear all histories
+    pub fn clear(&mut self) {
+        self.histories.clear();
+    }
+
+    /// Get the number of processes being tracked
+    pub fn len(&self) -> usize {
+        self.histories.len()
+    }
+
+    /// Check if tracking any processes
+    pub fn is_empty(&self) -> bool {
+        self.histories.is_empty()
+    }
+}
+
+impl Default for HistoryTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    #[test]
+    fn test_process_history() {
+        let mut history = ProcessHistory::new(1234, "test".to_string(), DEFAULT_HISTORY_SAMPLES);
+        let start = Instant::now();
+
+        history.add_sample_at(1000, 500, start);
+        history.add_sample_at(2000, 1000, start + Duration::from_secs(1));
+        history.add_sample_at(1500, 750, start + Duration::from_secs(2));
+
+        assert_eq!(history.samples.len(), 3);
+        assert_eq!(history.max_download_rate(), 2000);
+        assert_eq!(history.max_upload_rate(), 1000);
+        assert_eq!(history.avg_download_rate(), 1500);
+        assert_eq!(history.avg_upload_rate(), 750);
+    }
+
+    #[test]
+    fn test_percentiles() {
+        let mut history = ProcessHistory::new(1234, "test".to_string(), DEFAULT_HISTORY_SAMPLES);
+        assert_eq!(history.percentile_download_rate(50.0), 0);
+        assert_eq!(history.percentile_upload_rate(95.0), 0);
+
+        // A single sample is every percentile
+        history.add_sample(700, 70);
+        assert_eq!(history.percentile_download_rate(0.0), 700);
+        assert_eq!(history.percentile_download_rate(50.0), 700);
+        assert_eq!(history.percentile_upload_rate(95.0), 70);
+
+        // One burst drags the average up but leaves the median and p95 alone
+        history.samples.clear();
+        for rate in (1..=19).map(|i| i * 100).chain([100_000]) {
+            history.add_sample(rate, rate / 10);
+        }
+        assert_eq!(history.percentile_download_rate(50.0), 1000);
+        assert_eq!(history.percentile_download_rate(95.0), 1900);
+        assert_eq!(history.percentile_download_rate(100.0), 100_000);
+        assert_eq!(history.percentile_upload_rate(50.0), 100);
+
+        // Two samples: the median is the lower one, p95 the higher
+        history.samples.clear();
+        history.add_sample(300, 0);
+        history.add_sample(100, 0);
+        assert_eq!(history.percentile_download_rate(50.0), 100);
+        assert_eq!(history.percentile_download_rate(95.0), 300);
+        // Out-of-range percentiles are clamped
+        assert_eq!(history.percentile_download_rate(-5.0), 100);
+        assert_eq!(history.percentile_download_rate(250.0), 300);
+    }
+
+    #[test]
+    fn test_history_limit() {
+        let mut history = ProcessHistory::new(1234, "test".to_string(), DEFAULT_HISTORY_SAMPLES);
+
+        // Add more than DEFAULT_HISTORY_SAMPLES
+        for i in 0..(DEFAULT_HISTORY_SAMPLES + 10) {
+            history.add_sample(i as u64, i as u64);
+        }
+
+        assert_eq!(history.samples.len(), DEFAULT_HISTORY_SAMPLES);
+    }
+
+    #[test]
+    fn test_graph_data_is_placed_by_age() {
+        let mut history = ProcessHistory::new(1234, "test".to_string(), DEFAULT_HISTORY_SAMPLES);
+        for (timestamp, rate) in [(1000, 10), (1001, 20), (1005, 30)] {
+            history.push_sample(BandwidthSample {
+                timestamp,
+                download_rate: rate,
+                upload_rate: 0,
+                interval_secs: 1.0,
+            });
+        }
+
+        // A gap stays a gap instead of squeezing the samples together
+        let (download, _) = history.get_graph_data(1006);
+        assert_eq!(download, vec![(-6.0, 10.0), (-5.0, 20.0), (-1.0, 30.0)]);
+        assert_eq!(history.window_secs(), DEFAULT_HISTORY_SAMPLES as f64);
+    }
+
+    #[test]
+    fn test_configurable_window() {
+        let mut tracker = HistoryTracker::new();
+        tracker.set_window(5);
+        for rate in 1..=8 {
+            tracker.update(42, "curl".to_string(), rate * 100, 0);
+        }
+        let history = tracker.get_history(42).unwrap();
+        assert_eq!(history.samples.len(), 5);
+        assert_eq!(history.max_samples(), 5);
+        // Max covers only the samples in the window
+        assert_eq!(history.max_download_rate(), 800);
+        assert_eq!(history.samples[0].download_rate, 400);
+
+        // Shrinking drops the oldest samples; a window of 0 keeps one sample
+        tracker.set_window(0);
+        let history = tracker.get_history(42).unwrap();
+        assert_eq!(history.samples.len(), 1);
+        assert_eq!(history.max_download_rate(), 800);
+    }
+
+    #[test]
+    fn test_average_weighs_samples_by_real_interval() {
+        let mut history = ProcessHistory::new(1234, "test".to_string(), DEFAULT_HISTORY_SAMPLES);
+        let start = Instant::now();
+
+        // 1s at 1000 B/s, then a delayed tick: 3s at 3000 B/s
+        history.add_sample_at(1000, 0, start);
+        history.add_sample_at(3000, 0, start + Duration::from_secs(3));
+        assert_eq!(history.samples[1].interval_secs, 3.0);
+        assert_eq!(history.avg_download_rate(), 2500);
+
+        // After a suspend the sample is kept for the graph but carries no weight
+        history.add_sample_at(0, 0, start + Duration::from_secs(3600));
+        assert_eq!(history.samples[2].interval_secs, 0.0);
+        assert_eq!(history.avg_download_rate(), 2500);
+    }
+
+    #[test]
+    fn test_samples_without_interval_load_as_nominal() {
+        let sample: BandwidthSample =
+            serde_json::from_str(r#"{"timestamp":1,"download_rate":10,"upload_rate":5}"#).unwrap();
+        assert_eq!(sample.interval_secs, NOMINAL_SAMPLE_INTERVAL);
+    }
+
+    #[test]
+    fn test_seeded_history_matched_by_name() {
+        let mut tracker = HistoryTracker::new();
+        let old = BandwidthSample {
+            timestamp: 1,
+            download_rate: 4096,
+            upload_rate: 1024,
+            interval_secs: 1.0,
+        };
+        tracker.seeded.insert("curl".to_string(), vec![old]);
+
+        // A new PID with the same name picks up the saved samples
+        tracker.update(42, "curl".to_string(), 100, 50);
+        let history = tracker.get_history(42).unwrap();
+        assert_eq!(history.samples.len(), 2);
+        assert_eq!(history.samples[0].download_rate, 4096);
+
+        // ...but only once
+        tracker.update(43, "curl".to_string(), 100, 50);
+        assert_eq!(tracker.get_history(43).unwrap().samples.len(), 1);
+    }
+}
@@ -0,0 +1,256 @@
+// On-disk bandwidth history (optional, enabled with `persist_history` in the config)
+//
+// Samples are stored per process name rather than PID so a process's history
+// survives restarts. Each process gets one JSON-lines file in
+// <config dir>/history/, appended in batches and pruned to the retention window.
+
+use crate::config::Config;
+use crate::history::BandwidthSample;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const HISTORY_DIR: &str = "history";
+const HISTORY_EXT: &str = "jsonl";
+
+/// How often buffered samples are appended to disk
+const FLUSH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How often files are rewritten to drop samples older than the retention window
+const PRUNE_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Persistent per-process-name bandwidth history
+#[derive(Debug)]
+pub struct HistoryStore {
+    dir: PathBuf,
+    retention_secs: u64,
+    pending: HashMap<String, Vec<BandwidthSample>>,
+    last_flush: Instant,
+    last_prune: Instant,
+}
+
+impl HistoryStore {
+    /// Open the store in the config directory, creating it if needed
+    pub fn open(retention_minutes: u64) -> Result<Self> {
+        let config_path = Config::config_path()?;
+        let config_dir = config_path
+            .parent()
+            .context("Config path has no parent directory")?;
+        Self::open_in(&config_dir.join(HISTORY_DIR), retention_minutes)
+    }
+
+    /// Open the store in a specific directory
+    pub fn open_in(dir: &Path, retention_minutes: u64) -> Result<Self> {
+        fs::create_dir_all(dir)
+            .context(format!("Failed to create history directory: {:?}", dir))?;
+
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            retention_secs: retention_minutes.saturating_mul(60),
+            pending: HashMap::new(),
+            last_flush: Instant::now(),
+            last_prune: Instant::now(),
+        })
+    }
+
+    /// Load all persisted samples within the retention window, keyed by process name.
+    /// Files are compacted on the way (expired samples dropped, empty files removed)
+    pub fn load(&mut self) -> Result<HashMap<String, Vec<BandwidthSample>>> {
+        let cutoff = self.cutoff();
+        let mut loaded = HashMap::new();
+
+        let entries = fs::read_dir(&self.dir)
+            .context(format!("Failed to read history directory: {:?}", self.dir))?;
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(HISTORY_EXT) {
+                continue;
+            }
+            let Some(name) = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .and_then(decode_name)
+            else {
+                continue;
+            };
+
+            match prune_file(&path, cutoff) {
+                Ok(samples) if !samples.is_empty() => {
+                    loaded.insert(name, samples);
+                }
+                Ok(_) => {}
+                Err(e) => log::warn!("Skipping history file {:?}: {}", path, e),
+            }
+        }
+
+        self.last_prune = Instant::now();
+        log::info!(
+            "Loaded bandwidth history for {} process(es) from {:?}",
+            loaded.len(),
+            self.dir
+        );
+        Ok(loaded)
+    }
+
+    /// Buffer a sample for a process, flushing to disk when the flush interval has passed
+    pub fn record(&mut self, process_name: &str, sample: BandwidthSample) {
+        self.pending
+            .entry(process_name.to_string())
+            .or_default()
+            .push(sample);
+
+        if self.last_flush.elapsed() >= FLUSH_INTERVAL
+            && let Err(e) = self.flush()
+        {
+            log::warn!("Failed to persist bandwidth history: {}", e);
+        }
+    }
+
+    /// Append all buffered samples to disk (and prune periodically)
+    pub fn flush(&mut self) -> Result<()> {
+        self.last_flush = Instant::now();
+
+        for (name, samples) in self.pending.drain() {
+            let path = self
+                .dir
+                .join(format!("{}.{}", encode_name(&name), HISTORY_EXT));
+            let mut file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .context(format!("Failed to open history file: {:?}", path))?;
+
+            let mut contents = String::new();
+            for sample in &samples {
+                contents.push_str(&serde_json::to_string(sample)?);
+                contents.push('\n');
+            }
+            file.write_all(contents.as_bytes())
+                .context(format!("Failed to write history file: {:?}", path))?;
+        }
+
+        if self.last_prune.elapsed() >= PRUNE_INTERVAL {
+            self.load()?;
+        }
+        Ok(())
+    }
+
+    /// Oldest timestamp still within the retention window
+    fn cutoff(&self) -> u64 {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        now.saturating_sub(self.retention_secs)
+    }
+}
+
+/// Read a history file, rewrite it with only samples newer than `cutoff`
+/// (removing it if none are left) and return those samples
+fn prune_file(path: &Path, cutoff: u64) -> Result<Vec<BandwidthSample>> {
+    let contents = fs::read_to_string(path)?;
+    let total = contents.lines().count();
+
+    // Skip malformed lines (e.g. a partial write from a crash)
+    let samples: Vec<BandwidthSample> = contents
+        .lines()
+        .filter_map(|line| serde_json::from_str::<BandwidthSample>(line).ok())
+        .filter(|s| s.timestamp >= cutoff)
+        .collect();
+
+    if samples.is_empty() {
+        fs::remove_file(path)?;
+    } else if samples.len() != total {
+        let mut rewritten = String::new();
+        for sample in &samples {
+            rewritten.push_str(&serde_json::to_string(sample)?);
+            rewritten.push('\n');
+        }
+        fs::write(path, rewritten)?;
+    }
+
+    Ok(samples)
+}
+
+/// Encode a process name into a safe file stem (reversible percent-encoding)
+fn encode_name(name: &str) -> String {
+    let mut encoded = String::new();
+    for (i, byte) in name.bytes().enumerate() {
+        let safe =
+            byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_' || (byte == b'.' && i > 0);
+        if safe {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+/// Decode a file stem produced by `encode_name`
+fn decode_name(stem: &str) -> Option<String> {
+    let bytes = stem.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = stem.get(i + 1..i + 3)?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_name_encoding_roundtrip() {
+        for name in ["firefox", "kworker/0:1", ".hidden", "Web Content", "naïve"] {
+            let encoded = encode_name(name);
+            assert!(!encoded.contains('/') && !encoded.starts_with('.'));
+            assert_eq!(decode_name(&encoded).as_deref(), Some(name));
+        }
+    }
+
+    #[test]
+    fn test_persist_and_load_with_retention() {
+        let dir = std::env::temp_dir().join(format!("chadthrottle-history-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        let mut store = HistoryStore::open_in(&dir, 60).unwrap();
+        let now = store.cutoff() + 3600;
+        let sample = |timestamp, rate| BandwidthSample {
+            timestamp,
+            download_rate: rate,
+            upload_rate: rate / 2,
+            interval_secs: 1.0,
+        };
+        store.record("curl", sample(now - 7200, 1)); // expired
+        store.record("curl", sample(now - 10, 2000));
+        store.record("kworker/0:1", sample(now - 5000, 3)); // expired
+        store.flush().unwrap();
+
+        // A fresh store (i.e. after a restart) sees only samples inside the window
+        let loaded = HistoryStore::open_in(&dir, 60).unwrap().load().unwrap();
+        assert_eq!(loaded.len(), 1);
+        let curl = &loaded["curl"];
+        assert_eq!(curl.len(), 1);
+        assert_eq!(curl[0].download_rate, 2000);
+        assert_eq!(curl[0].upload_rate, 1000);
+
+        // Files with only expired samples are removed
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,100 @@
+// Interface filter entries (`filtered_interfaces` in the config)
+//
+// The filter is one list holding three kinds of entries:
+// - exact names: "eth0"
+// - glob patterns: "tun*", "wg?" (`*` matches any run of characters, `?` one
+//   character), which also cover interfaces that appear later (VPN tunnels)
+// - exclusions: "!tun3", hiding an interface a pattern would otherwise show
+//
+// An interface is shown if an exact name or a pattern matches it and no exclusion
+// names it: exclusions take precedence over everything else.
+
+/// Whether a filter entry is a glob pattern rather than an exact name
+pub fn is_pattern(entry: &str) -> bool {
+    !entry.starts_with('!') && entry.contains(['*', '?'])
+}
+
+/// The interface an exclusion entry ("!name") hides
+pub fn exclusion(entry: &str) -> Option<&str> {
+    entry.strip_prefix('!')
+}
+
+/// Match `name` against a glob pattern (`*` and `?` only)
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // Where the last `*` was, and how much of the name it has taken so far
+    let mut star: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                // Let the last `*` take one more character and retry
+                Some((star_p, star_n)) => {
+                    star = Some((star_p, star_n + 1));
+                    p = star_p + 1;
+                    n = star_n + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// The first pattern in `filters` that matches `name`
+pub fn covering_pattern<'a>(filters: &'a [String], name: &str) -> Option<&'a str> {
+    filters
+        .iter()
+        .map(String::as_str)
+        .find(|entry| is_pattern(entry) && glob_match(entry, name))
+}
+
+/// Whether `filters` show the interface `name`
+pub fn matches(filters: &[String], name: &str) -> bool {
+    if filters.iter().any(|entry| exclusion(entry) == Some(name)) {
+        return false;
+    }
+    filters.iter().any(|entry| entry == name) || covering_pattern(filters, name).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("tun*", "tun0"));
+        assert!(glob_match("tun*", "tun"));
+        assert!(glob_match("wg?", "wg1"));
+        assert!(!glob_match("wg?", "wg10"));
+        assert!(glob_match("*eth*", "veth1a2b"));
+        assert!(glob_match("br-*-1", "br-abc-def-1"));
+        assert!(!glob_match("tun*", "utun0"));
+        assert!(!glob_match("eth0", "eth01"));
+    }
+
+    #[test]
+    fn test_exclusions_take_precedence() {
+        let filters: Vec<String> = ["eth0", "tun*", "!tun3"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+
+        assert!(matches(&filters, "eth0"));
+        assert!(matches(&filters, "tun7")); // appeared after the filter was saved
+        assert!(!matches(&filters, "tun3"));
+        assert!(!matches(&filters, "wlan0"));
+        assert_eq!(covering_pattern(&filters, "tun7"), Some("tun*"));
+        assert!(!is_pattern("!tun*"));
+    }
+}
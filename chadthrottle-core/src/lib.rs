@@ -0,0 +1,435 @@
+//! The monitoring and throttling engine behind the `chadthrottle` TUI
+//!
+//! - [`monitor::NetworkMonitor`] samples per-process and per-interface bandwidth on a
+//!   background thread, using one of the [`backends::monitor`] backends
+//! - [`backends::throttle::ThrottleManager`] applies and removes per-process limits
+//!   through the upload/download backends in [`backends::throttle`], isolating
+//!   processes with the [`backends::cgroup`] backends where needed
+//! - [`backends::process`] maps sockets and connections to processes
+//! - [`config::Config`] is the saved configuration (`throttles.json`)
+//! - [`activity`] tells transferring processes from idle listen-only daemons
+//! - [`standing_rules`] are throttles the manager applies to processes as they start
+//! - [`limits`] parses limits like `"1.5M"`, [`format`] formats bytes and rates
+//!
+//! Backends are selected by the same cargo features as the binary (`throttle-tc-htb`,
+//! `throttle-ebpf`, `linux-full`, ...). Throttling needs root or CAP_NET_ADMIN on
+//! Linux. See `examples/throttle_pid.rs` for a minimal program that throttles one
+//! process.
+
+pub mod activity;
+pub mod backends;
+pub mod config;
+pub mod connection_events;
+pub mod control_path;
+pub mod events;
+pub mod format;
+pub mod history;
+pub mod history_store;
+pub mod interface_filter;
+pub mod limits;
+pub mod standing_rules;
+
+// NetworkMonitor module - conditionally compiled based on available backends
+#[cfg(feature = "monitor-pnet")]
+pub mod monitor;
+
+// Windows polling monitor wrapper when pnet is not available
+#[cfg(all(target_os = "windows", not(feature = "monitor-pnet")))]
+pub mod monitor {
+    use crate::backends::monitor::MonitorBackend;
+    use crate::backends::monitor::windows_poll::WindowsPollingMonitor;
+    use crate::process::{InterfaceMap, ProcessMap};
+    use anyhow::Result;
+    use std::collections::HashMap;
+    use std::time::{Duration, Instant};
+    use tokio::sync::mpsc;
+
+    pub use crate::backends::monitor::windows_poll::ProcessBandwidth;
+
+    /// Commands sent from UI thread to monitoring thread
+    pub enum MonitorCommand {
+        /// Switch to a different socket mapper backend
+        SwitchSocketMapper {
+            backend_name: String,
+            /// Channel to send back success or error (hot-swap, no thread restart)
+            response_tx: tokio::sync::oneshot::Sender<Result<()>>,
+        },
+        /// Switch to a different monitor backend
+        SwitchMonitorBackend {
+            backend_name: String,
+            response_tx: tokio::sync::oneshot::Sender<Result<()>>,
+        },
+        /// Drop loopback packets unprocessed (no effect without packet capture)
+        SetSkipLoopback(bool),
+        /// Signal to shutdown the monitoring thread
+        Shutdown,
+    }
+
+    /// Update data sent from monitoring thread to UI thread
+    #[derive(Debug, Clone)]
+    pub struct MonitorUpdateData {
+        pub process_map: ProcessMap,
+        pub interface_map: InterfaceMap,
+        pub monitor_backend_name: &'static str,
+        pub socket_mapper_name: String,
+        pub socket_mapper_capabilities: crate::backends::BackendCapabilities,
+        pub socket_cache: Option<crate::backends::process::socket_mapper::SocketCacheStats>,
+    }
+
+    /// Update messages sent from monitoring thread to UI thread
+    pub type MonitorUpdate = MonitorUpdateData;
+
+    pub struct NetworkMonitor {
+        backend: WindowsPollingMonitor,
+    }
+
+    impl NetworkMonitor {
+        pub fn with_backends(
+            monitor_preference: Option<&str>,
+            _: Option<&str>,
+            _: crate::backends::monitor::CaptureOptions,
+        ) -> Result<Self> {
+            if let Some(name) = monitor_preference
+                && name != "windows-poll"
+            {
+                anyhow::bail!("Monitor backend '{}' is not available in this build", name);
+            }
+            log::info!("Using Windows polling monitor backend");
+            Ok(NetworkMonitor {
+                backend: WindowsPollingMonitor::new()?,
+            })
+        }
+
+        pub fn get_monitoring_backend_name(&self) -> &'static str {
+            "windows-poll"
+        }
+
+        pub fn get_socket_mapper_info(&self) -> (&str, &crate::backends::BackendCapabilities) {
+            // Windows polling monitor uses iphelper socket mapper
+            static CAPS: crate::backends::BackendCapabilities =
+                crate::backends::BackendCapabilities {
+                    ipv4_support: true,
+                    ipv6_support: true,
+                    per_process: true,
+                    per_connection: true,
+                    tcp_support: true,
+                    udp_support: true,
+                    traffic_types: crate::backends::TrafficTypeSupport::ALL_ONLY,
+                };
+            ("iphelper", &CAPS)
+        }
+
+        pub fn update(&mut self) -> Result<(ProcessMap, InterfaceMap)> {
+            self.backend.update()
+        }
+
+        pub fn extract_bandwidth_data(
+            &self,
+        ) -> (HashMap<i32, ProcessBandwidth>, HashMap<i32, Instant>) {
+            self.backend.extract_bandwidth_data()
+        }
+
+        pub fn restore_bandwidth_data(
+            &mut self,
+            process_bandwidth: HashMap<i32, ProcessBandwidth>,
+            terminated_processes: HashMap<i32, Instant>,
+        ) {
+            self.backend
+                .restore_bandwidth_data(process_bandwidth, terminated_processes);
+        }
+
+        pub fn get_bandwidth_data(&self) -> HashMap<i32, Vec<crate::history::BandwidthSample>> {
+            self.backend.get_bandwidth_data()
+        }
+
+        /// Recreate the polling backend, carrying over totals, terminated processes
+        /// and graph samples. iphelper is the only socket mapper on Windows
+        fn switch_socket_mapper(&mut self, backend_name: &str) -> Result<()> {
+            if backend_name != "iphelper" {
+                return Err(anyhow::anyhow!(
+                    "Socket mapper '{}' is not supported by the windows-poll backend",
+                    backend_name
+                ));
+            }
+
+            let (process_bandwidth, terminated_processes) = self.extract_bandwidth_data();
+            let history = self.get_bandwidth_data();
+
+            let mut backend = WindowsPollingMonitor::new()?;
+            backend.restore_bandwidth_data(process_bandwidth, terminated_processes);
+            backend.restore_bandwidth_history(history);
+            self.backend = backend;
+            Ok(())
+        }
+
+        /// Run the monitoring loop in a background thread
+        pub fn run_monitoring_loop(
+            mut self,
+            mut cmd_rx: mpsc::UnboundedReceiver<MonitorCommand>,
+            update_tx: mpsc::UnboundedSender<MonitorUpdate>,
+        ) {
+            log::info!("Starting monitoring background thread (windows-poll)");
+            let mut last_update = Instant::now();
+            let mut activity = crate::activity::ActivityTracker::default();
+
+            loop {
+                // Check for commands (non-blocking)
+                match cmd_rx.try_recv() {
+                    Ok(MonitorCommand::Shutdown) => {
+                        log::info!("Monitoring thread received shutdown command");
+                        break;
+                    }
+                    Ok(MonitorCommand::SwitchSocketMapper {
+                        backend_name,
+                        response_tx,
+                    }) => {
+                        let result = self.switch_socket_mapper(&backend_name);
+                        match &result {
+                            Ok(()) => log::info!("Socket mapper switched to {}", backend_name),
+                            Err(e) => log::warn!("Socket mapper switch failed: {}", e),
+                        }
+                        let _ = response_tx.send(result);
+                    }
+                    Ok(MonitorCommand::SwitchMonitorBackend {
+                        backend_name,
+                        response_tx,
+                    }) => {
+                        // windows-poll is the only monitor backend without pnet
+                        let result = if backend_name == "windows-poll" {
+                            Ok(())
+                        } else {
+                            Err(anyhow::anyhow!(
+                                "Monitor backend '{}' is not available in this build",
+                                backend_name
+                            ))
+                        };
+                        let _ = response_tx.send(result);
+                    }
+                    Ok(MonitorCommand::SetSkipLoopback(_)) => {
+                        // Polling doesn't capture packets, nothing to skip
+                    }
+                    Err(mpsc::error::TryRecvError::Empty) => {
+                        // No command, continue monitoring
+                    }
+                    Err(mpsc::error::TryRecvError::Disconnected) => {
+                        log::warn!("Command channel disconnected, shutting down monitoring thread");
+                        break;
+                    }
+                }
+
+                // Perform update approximately once per second
+                let now = Instant::now();
+                if now.duration_since(last_update) >= Duration::from_secs(1) {
+                    match self.update() {
+                        Ok((mut process_map, interface_map)) => {
+                            activity.update(&mut process_map, Instant::now());
+
+                            // Send update to UI thread (non-blocking) with socket mapper info
+                            let update_data = MonitorUpdateData {
+                                process_map,
+                                interface_map,
+                                monitor_backend_name: "windows-poll",
+                                socket_mapper_name: "iphelper".to_string(),
+                                socket_mapper_capabilities: crate::backends::BackendCapabilities {
+                                    ipv4_support: true,
+                                    ipv6_support: true,
+                                    per_process: true,
+                                    per_connection: true,
+                                    tcp_support: true,
+                                    udp_support: true,
+                                    traffic_types: crate::backends::TrafficTypeSupport::ALL_ONLY,
+                                },
+                                socket_cache: None,
+                            };
+
+                            if update_tx.send(update_data).is_err() {
+                                log::warn!(
+                                    "Update channel disconnected, shutting down monitoring thread"
+                                );
+                                break;
+                            }
+                        }
+                        Err(e) => {
+                            log::error!("Monitor update failed: {}", e);
+                        }
+                    }
+
+                    last_update = now;
+                }
+
+                // Sleep briefly to avoid busy-waiting
+                std::thread::sleep(Duration::from_millis(100));
+            }
+
+            log::info!("Monitoring background thread exiting");
+        }
+    }
+}
+
+// Stub monitor module when no backends are available (non-Windows without pnet)
+#[cfg(not(any(feature = "monitor-pnet", target_os = "windows")))]
+pub mod monitor {
+    use crate::process::{InterfaceMap, ProcessMap};
+    use anyhow::Result;
+    use std::collections::HashMap;
+    use std::time::{Duration, Instant};
+    use tokio::sync::mpsc;
+
+    /// Commands sent from UI thread to monitoring thread
+    pub enum MonitorCommand {
+        /// Switch to a different socket mapper backend
+        SwitchSocketMapper {
+            backend_name: String,
+            /// Channel to send back success or error (hot-swap, no thread restart)
+            response_tx: tokio::sync::oneshot::Sender<Result<()>>,
+        },
+        /// Switch to a different monitor backend
+        SwitchMonitorBackend {
+            backend_name: String,
+            response_tx: tokio::sync::oneshot::Sender<Result<()>>,
+        },
+        /// Drop loopback packets unprocessed (no effect without packet capture)
+        SetSkipLoopback(bool),
+        /// Signal to shutdown the monitoring thread
+        Shutdown,
+    }
+
+    /// Update data sent from monitoring thread to UI thread
+    #[derive(Debug, Clone)]
+    pub struct MonitorUpdateData {
+        pub process_map: ProcessMap,
+        pub interface_map: InterfaceMap,
+        pub monitor_backend_name: &'static str,
+        pub socket_mapper_name: String,
+        pub socket_mapper_capabilities: crate::backends::BackendCapabilities,
+        pub socket_cache: Option<crate::backends::process::socket_mapper::SocketCacheStats>,
+    }
+
+    /// Update messages sent from monitoring thread to UI thread
+    pub type MonitorUpdate = MonitorUpdateData;
+
+    pub struct NetworkMonitor;
+
+    pub struct ProcessBandwidth {
+        name: String,
+        rx_bytes: u64,
+        tx_bytes: u64,
+        last_rx_bytes: u64,
+        last_tx_bytes: u64,
+    }
+
+    impl NetworkMonitor {
+        pub fn with_backends(
+            _: Option<&str>,
+            _: Option<&str>,
+            _: crate::backends::monitor::CaptureOptions,
+        ) -> Result<Self> {
+            log::warn!("No monitoring backend available - monitoring disabled");
+            Ok(NetworkMonitor)
+        }
+
+        pub fn get_socket_mapper_info(&self) -> (&str, &crate::backends::BackendCapabilities) {
+            static CAPS: crate::backends::BackendCapabilities =
+                crate::backends::BackendCapabilities {
+                    ipv4_support: false,
+                    ipv6_support: false,
+                    per_process: false,
+                    per_connection: false,
+                    tcp_support: true,
+                    udp_support: false,
+                    traffic_types: crate::backends::TrafficTypeSupport::ALL_ONLY,
+                };
+            ("none", &CAPS)
+        }
+
+        pub fn update(&mut self) -> Result<(ProcessMap, InterfaceMap)> {
+            Ok((HashMap::new(), HashMap::new()))
+        }
+
+        pub fn extract_bandwidth_data(
+            &self,
+        ) -> (HashMap<i32, ProcessBandwidth>, HashMap<i32, Instant>) {
+            (HashMap::new(), HashMap::new())
+        }
+
+        pub fn restore_bandwidth_data(
+            &mut self,
+            _: HashMap<i32, ProcessBandwidth>,
+            _: HashMap<i32, Instant>,
+        ) {
+            // No-op
+        }
+
+        pub fn get_bandwidth_data(&self) -> HashMap<i32, Vec<crate::history::BandwidthSample>> {
+            HashMap::new()
+        }
+
+        pub fn get_monitoring_backend_name(&self) -> &'static str {
+            "none"
+        }
+
+        /// Run the monitoring loop in a background thread (stub implementation)
+        pub fn run_monitoring_loop(
+            self,
+            mut cmd_rx: mpsc::UnboundedReceiver<MonitorCommand>,
+            update_tx: mpsc::UnboundedSender<MonitorUpdate>,
+        ) {
+            log::warn!("Monitoring background thread started but no backend available");
+
+            loop {
+                // Check for commands
+                match cmd_rx.try_recv() {
+                    Ok(MonitorCommand::Shutdown) => {
+                        break;
+                    }
+                    Ok(MonitorCommand::SwitchSocketMapper {
+                        backend_name: _,
+                        response_tx,
+                    }) => {
+                        let _ = response_tx.send(Err(anyhow::anyhow!("No backend available")));
+                    }
+                    Ok(MonitorCommand::SwitchMonitorBackend {
+                        backend_name: _,
+                        response_tx,
+                    }) => {
+                        let _ = response_tx.send(Err(anyhow::anyhow!("No backend available")));
+                    }
+                    Err(mpsc::error::TryRecvError::Disconnected) => {
+                        break;
+                    }
+                    _ => {}
+                }
+
+                // Send empty updates periodically
+                let _now = Instant::now();
+                std::thread::sleep(Duration::from_secs(1));
+
+                let update_data = MonitorUpdateData {
+                    process_map: HashMap::new(),
+                    interface_map: HashMap::new(),
+                    monitor_backend_name: "none",
+                    socket_mapper_name: "none".to_string(),
+                    socket_mapper_capabilities: crate::backends::BackendCapabilities {
+                        ipv4_support: false,
+                        ipv6_support: false,
+                        per_process: false,
+                        per_connection: false,
+                        tcp_support: true,
+                        udp_support: false,
+                        traffic_types: crate::backends::TrafficTypeSupport::ALL_ONLY,
+                    },
+                    socket_cache: None,
+                };
+
+                if update_tx.send(update_data).is_err() {
+                    break;
+                }
+            }
+
+            log::info!("Stub monitoring thread exiting");
+        }
+    }
+}
+
+pub mod process;
+pub mod traffic_classifier;
@@ -0,0 +1,123 @@
+// Parsing of bandwidth limits as written in the CLI and the config ("1M", "500K", ...)
+
+use anyhow::Result;
+
+/// Units a limit can be written in (case-insensitive), with their size in bytes/sec.
+/// Byte units are binary (1K = 1024 bytes), bit units decimal like tc's (1kbit = 1000 bits)
+const UNITS: [(&str, f64); 14] = [
+    ("", 1.0),
+    ("B", 1.0),
+    ("K", 1024.0),
+    ("KB", 1024.0),
+    ("KIB", 1024.0),
+    ("M", 1024.0 * 1024.0),
+    ("MB", 1024.0 * 1024.0),
+    ("MIB", 1024.0 * 1024.0),
+    ("G", 1024.0 * 1024.0 * 1024.0),
+    ("GB", 1024.0 * 1024.0 * 1024.0),
+    ("GIB", 1024.0 * 1024.0 * 1024.0),
+    ("KBIT", 1_000.0 / 8.0),
+    ("MBIT", 1_000_000.0 / 8.0),
+    ("GBIT", 1_000_000_000.0 / 8.0),
+];
+
+/// Parse bandwidth limit string (e.g., "1M", "500K", "1.5M", "2.5mbit", "64KB/s") to
+/// bytes per second, rounded to the nearest byte. A bare number is bytes/sec
+pub fn parse_bandwidth_limit(limit_str: &str) -> Result<u64> {
+    let limit_str = limit_str.trim().to_uppercase();
+    let without_per_sec = limit_str.strip_suffix("/S").unwrap_or(&limit_str);
+
+    // Split into number and unit
+    let unit_start = without_per_sec
+        .find(|c: char| c.is_ascii_alphabetic())
+        .unwrap_or(without_per_sec.len());
+    let (num_str, unit) = without_per_sec.split_at(unit_start);
+
+    let number: f64 = num_str
+        .trim()
+        .parse()
+        .ok()
+        .filter(|number: &f64| number.is_finite() && *number >= 0.0)
+        .ok_or_else(|| anyhow::anyhow!("Invalid bandwidth limit: {}", limit_str))?;
+
+    let (_, unit_size) = UNITS
+        .iter()
+        .find(|(name, _)| *name == unit)
+        .ok_or_else(|| anyhow::anyhow!("Unknown unit: {}", unit))?;
+
+    Ok((number * unit_size).round() as u64)
+}
+
+/// Whether `input` could still become a valid limit as more characters are typed:
+/// a number, optionally followed by the start of a unit
+pub fn is_limit_prefix(input: &str) -> bool {
+    let upper = input.to_uppercase();
+    let unit_start = upper
+        .find(|c: char| c.is_ascii_alphabetic() || c == '/')
+        .unwrap_or(upper.len());
+    let (num_str, unit) = upper.split_at(unit_start);
+    let number_ok = num_str.chars().all(|c| c.is_ascii_digit() || c == '.')
+        && num_str.matches('.').count() <= 1;
+    if !number_ok || (num_str.is_empty() && !unit.is_empty()) {
+        return false;
+    }
+    let unit = unit
+        .strip_suffix("/S")
+        .or(unit.strip_suffix('/'))
+        .unwrap_or(unit);
+    !unit.contains('/') && UNITS.iter().any(|(name, _)| name.starts_with(unit))
+}
+
+/// Parse a throttle limit, rejecting limits too slow to enforce
+pub fn parse_throttle_limit(limit_str: &str) -> Result<u64> {
+    crate::process::check_limit(parse_bandwidth_limit(limit_str)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bandwidth_limit_rounds() {
+        assert_eq!(parse_bandwidth_limit("1.5M").unwrap(), 1_572_864);
+        assert_eq!(parse_bandwidth_limit("500kb").unwrap(), 512_000);
+        assert_eq!(parse_bandwidth_limit("0.125K").unwrap(), 128);
+        // 0.1 KB = 102.4 bytes
+        assert_eq!(parse_bandwidth_limit("0.1K").unwrap(), 102);
+        assert_eq!(parse_bandwidth_limit("200.6").unwrap(), 201);
+        assert!(parse_bandwidth_limit("-1K").is_err());
+        assert!(parse_bandwidth_limit("fast").is_err());
+    }
+
+    #[test]
+    fn test_parse_bandwidth_limit_units() {
+        assert_eq!(parse_bandwidth_limit("5M").unwrap(), 5 * 1024 * 1024);
+        assert_eq!(parse_bandwidth_limit("64KiB/s").unwrap(), 64 * 1024);
+        assert_eq!(parse_bandwidth_limit("2.5mbit").unwrap(), 312_500);
+        assert_eq!(parse_bandwidth_limit("8 kbit").unwrap(), 1000);
+        assert_eq!(parse_bandwidth_limit("1gbit").unwrap(), 125_000_000);
+        assert!(parse_bandwidth_limit("5mbps").is_err());
+        assert!(parse_bandwidth_limit("M").is_err());
+    }
+
+    #[test]
+    fn test_is_limit_prefix() {
+        for input in [
+            "", "5", "2.", "2.5m", "2.5mbi", "2.5mbit", "64KB/", "64KB/s", "1b",
+        ] {
+            assert!(is_limit_prefix(input), "{input}");
+        }
+        for input in ["5t", "5i", "2.5.1", "k", "5mx", "5kb/x"] {
+            assert!(!is_limit_prefix(input), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_parse_throttle_limit_minimum() {
+        assert_eq!(parse_throttle_limit("128").unwrap(), 128);
+        assert_eq!(parse_throttle_limit("0.125K").unwrap(), 128);
+        assert!(parse_throttle_limit("127").is_err());
+        assert!(parse_throttle_limit("0.1K").is_err());
+        assert!(parse_throttle_limit("0").is_err());
+    }
+}
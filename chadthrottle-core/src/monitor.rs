@@ -1,4 +1,12 @@
+use crate::activity::ActivityTracker;
+use crate::backends::monitor::{
+    CaptureOptions, LinkInfo, MAX_SAMPLE_INTERVAL, MIN_CONNECTION_SCAN_INTERVAL, MonitorBackend,
+    connection_scan_wait, counter_rate, is_terminated_retained, sample_interval,
+};
+use crate::backends::process::socket_mapper::SocketCacheStats;
+use crate::backends::process::tcp_health::{TcpHealth, TcpHealthCollector};
 use crate::backends::process::{ConnectionMap, ProcessUtils};
+use crate::connection_events::ConnectionEventTracker;
 use crate::process::{InterfaceInfo, InterfaceMap, ProcessInfo, ProcessMap};
 use anyhow::{Context, Result};
 
@@ -27,8 +35,8 @@ use std::thread::{self, JoinHandle};
 use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 
-/// How long to keep terminated processes visible (in seconds)
-const TERMINATED_DISPLAY_DURATION: Duration = Duration::from_secs(5);
+/// How long a capture thread waits for a packet before checking for shutdown
+const CAPTURE_READ_TIMEOUT: Duration = Duration::from_millis(250);
 
 /// Commands sent from UI thread to monitoring thread
 pub enum MonitorCommand {
@@ -38,6 +46,14 @@ pub enum MonitorCommand {
         /// Channel to send back success or error (hot-swap, no thread restart)
         response_tx: tokio::sync::oneshot::Sender<Result<()>>,
     },
+    /// Switch to a different monitor backend (e.g. pnet or nettop)
+    SwitchMonitorBackend {
+        backend_name: String,
+        /// Channel to send back success or error (hot-swap, no thread restart)
+        response_tx: tokio::sync::oneshot::Sender<Result<()>>,
+    },
+    /// Drop loopback packets unprocessed (unless loopback is in the capture allowlist)
+    SetSkipLoopback(bool),
     /// Signal to shutdown the monitoring thread
     Shutdown,
 }
@@ -47,8 +63,10 @@ pub enum MonitorCommand {
 pub struct MonitorUpdateData {
     pub process_map: ProcessMap,
     pub interface_map: InterfaceMap,
+    pub monitor_backend_name: &'static str,
     pub socket_mapper_name: String,
     pub socket_mapper_capabilities: crate::backends::BackendCapabilities,
+    pub socket_cache: Option<SocketCacheStats>,
 }
 
 /// Update messages sent from monitoring thread to UI thread
@@ -78,11 +96,15 @@ pub struct NetworkMonitor {
     process_utils: Box<dyn ProcessUtils>,
     // Monitoring backend name (e.g., "pnet", "windows-poll")
     monitoring_backend_name: &'static str,
-    // Socket mapper backend information
+    // Socket mapper backend information (and what was asked for, None = auto)
+    socket_mapper_preference: Option<String>,
     socket_mapper_name: String,
     socket_mapper_capabilities: crate::backends::BackendCapabilities,
     // Shutdown signal for packet capture threads
     shutdown_flag: Arc<AtomicBool>,
+    // Runtime of the connection map task, entered again to rebuild the monitor from
+    // the monitoring thread
+    runtime: tokio::runtime::Handle,
     // Handles to packet capture threads (one per interface) - no underscore, we'll join them!
     capture_handles: Vec<thread::JoinHandle<()>>,
     last_update: Instant,
@@ -90,9 +112,21 @@ pub struct NetworkMonitor {
     cached_processed_data: Arc<Mutex<ProcessedConnectionData>>,
     // Cached interface list (doesn't change at runtime)
     cached_interfaces: Vec<NetworkInterface>,
+    // Capture allowlist, kept so a hot-swapped monitor captures the same interfaces
+    capture_interfaces: Option<Vec<String>>,
+    // Configured time between connection scans, kept for hot swaps too
+    connection_scan_interval: Duration,
+    // Read by capture threads on skippable (loopback) interfaces
+    skip_loopback: Arc<AtomicBool>,
     // Cached process existence checks (updated every update cycle)
     cached_process_exists: HashMap<i32, bool>,
+    // Cached parent PIDs (refreshed with the existence checks, looked up on first sight)
+    cached_parent_pids: HashMap<i32, Option<i32>>,
     last_process_check: Instant,
+    // Backend that replaces packet capture entirely (e.g. nettop on macOS)
+    capture_free_backend: Option<Box<dyn MonitorBackend>>,
+    // Previous connections per process, diffed into open/close events
+    connection_events: ConnectionEventTracker,
 }
 
 /// Pre-processed connection data ready for use by the UI thread
@@ -103,6 +137,8 @@ struct ProcessedConnectionData {
     connection_map: HashMap<ConnectionKey, i32>,
     pids_with_names: Vec<(i32, String)>,
     raw_connection_map: ConnectionMap, // Kept for populate_connections
+    tcp_health: HashMap<i32, TcpHealth>, // PID -> retransmits/RTT of its TCP sockets
+    socket_cache: Option<SocketCacheStats>, // Of the background task's socket mapper
 }
 
 struct BandwidthTracker {
@@ -140,6 +176,56 @@ pub struct ProcessBandwidth {
     last_internet_tx_bytes: u64,
     last_local_rx_bytes: u64,
     last_local_tx_bytes: u64,
+    // Protocol split
+    tcp_rx_bytes: u64,
+    tcp_tx_bytes: u64,
+    udp_rx_bytes: u64,
+    udp_tx_bytes: u64,
+    last_tcp_rx_bytes: u64,
+    last_tcp_tx_bytes: u64,
+    last_udp_rx_bytes: u64,
+    last_udp_tx_bytes: u64,
+    // When the last_* counters were read
+    last_sample: Instant,
+}
+
+impl ProcessBandwidth {
+    /// TCP and UDP (rx total, tx total, download rate, upload rate) since the last update
+    fn protocol_split(&self, interval: Option<f64>) -> ProtocolSplit {
+        let rate = |bytes: u64, last: u64| counter_rate(bytes, last, interval);
+        (
+            (
+                self.tcp_rx_bytes,
+                self.tcp_tx_bytes,
+                rate(self.tcp_rx_bytes, self.last_tcp_rx_bytes),
+                rate(self.tcp_tx_bytes, self.last_tcp_tx_bytes),
+            ),
+            (
+                self.udp_rx_bytes,
+                self.udp_tx_bytes,
+                rate(self.udp_rx_bytes, self.last_udp_rx_bytes),
+                rate(self.udp_tx_bytes, self.last_udp_tx_bytes),
+            ),
+        )
+    }
+}
+
+/// Per-protocol (rx total, tx total, download rate, upload rate) for TCP and UDP
+type ProtocolSplit = ((u64, u64, u64, u64), (u64, u64, u64, u64));
+
+/// Fill in a process's TCP/UDP split (rates are zeroed for terminated processes)
+fn apply_protocol_split(proc_info: &mut ProcessInfo, split: ProtocolSplit, live: bool) {
+    let ((tcp_rx, tcp_tx, tcp_dl, tcp_ul), (udp_rx, udp_tx, udp_dl, udp_ul)) = split;
+    proc_info.tcp_total_download = tcp_rx;
+    proc_info.tcp_total_upload = tcp_tx;
+    proc_info.udp_total_download = udp_rx;
+    proc_info.udp_total_upload = udp_tx;
+    if live {
+        proc_info.tcp_download_rate = tcp_dl;
+        proc_info.tcp_upload_rate = tcp_ul;
+        proc_info.udp_download_rate = udp_dl;
+        proc_info.udp_upload_rate = udp_ul;
+    }
 }
 
 #[derive(Clone)]
@@ -149,6 +235,7 @@ struct InterfaceBandwidth {
     tx_bytes: u64,
     last_rx_bytes: u64,
     last_tx_bytes: u64,
+    last_sample: Instant,
 }
 
 #[derive(Clone)]
@@ -166,14 +253,30 @@ struct ProcessInterfaceBandwidth {
     last_internet_tx_bytes: u64,
     last_local_rx_bytes: u64,
     last_local_tx_bytes: u64,
+    last_sample: Instant,
 }
 
 impl NetworkMonitor {
     pub fn new() -> Result<Self> {
-        Self::with_socket_mapper(None)
+        Self::with_socket_mapper(None, CaptureOptions::default())
     }
 
-    pub fn with_socket_mapper(socket_mapper_preference: Option<&str>) -> Result<Self> {
+    pub fn with_socket_mapper(
+        socket_mapper_preference: Option<&str>,
+        capture_options: CaptureOptions,
+    ) -> Result<Self> {
+        Self::with_backends(None, socket_mapper_preference, capture_options)
+    }
+
+    /// Create a monitor using the given monitor backend (None = best available) and
+    /// socket mapper
+    pub fn with_backends(
+        monitor_preference: Option<&str>,
+        socket_mapper_preference: Option<&str>,
+        capture_options: CaptureOptions,
+    ) -> Result<Self> {
+        let capture_free_backend = Self::select_capture_free_backend(monitor_preference)?;
+
         let bandwidth_tracker = Arc::new(Mutex::new(BandwidthTracker {
             connection_map: HashMap::new(),
             socket_map: HashMap::new(),
@@ -232,26 +335,48 @@ impl NetworkMonitor {
         // Initialize cached processed connection data
         let cached_processed_data = Arc::new(Mutex::new(ProcessedConnectionData::default()));
 
-        // Cache interface list once at startup
-        let cached_interfaces = Self::find_all_interfaces();
+        // Cache interface list once at startup (only interfaces we capture on)
+        let cached_interfaces: Vec<NetworkInterface> = Self::find_all_interfaces()
+            .into_iter()
+            .filter(|iface| capture_options.captures(&iface.name))
+            .collect();
         log::info!("Cached {} network interfaces", cached_interfaces.len());
+        let skip_loopback = Arc::new(AtomicBool::new(capture_options.skip_loopback));
 
         // Create monitor instance first (without starting capture thread yet)
         let mut monitor = Self {
             bandwidth_tracker,
             process_utils,
-            monitoring_backend_name: "pnet",
+            monitoring_backend_name: capture_free_backend
+                .as_ref()
+                .map_or("pnet", |backend| backend.name()),
+            socket_mapper_preference: socket_mapper_preference.map(str::to_string),
             socket_mapper_name,
             socket_mapper_capabilities,
             shutdown_flag: Arc::clone(&shutdown_flag),
+            runtime: tokio::runtime::Handle::current(),
             capture_handles: Vec::new(),
             last_update: Instant::now(),
             cached_processed_data: Arc::clone(&cached_processed_data),
             cached_interfaces: cached_interfaces.clone(),
+            capture_interfaces: capture_options.interfaces.clone(),
+            connection_scan_interval: capture_options.connection_scan_interval,
+            skip_loopback: Arc::clone(&skip_loopback),
             cached_process_exists: HashMap::new(),
+            cached_parent_pids: HashMap::new(),
             last_process_check: Instant::now(),
+            capture_free_backend,
+            connection_events: ConnectionEventTracker::default(),
         };
 
+        if monitor.capture_free_backend.is_some() {
+            log::info!(
+                "Monitoring with {} backend (no packet capture)",
+                monitor.monitoring_backend_name
+            );
+            return Ok(monitor);
+        }
+
         // Spawn background async task to update connection map
         // This prevents blocking the UI thread during connection map updates
         let cached_data_clone = Arc::clone(&cached_processed_data);
@@ -287,12 +412,19 @@ impl NetworkMonitor {
 
         // Spawn background async task to fetch AND process connection maps
         // This keeps ALL heavy computation out of the UI thread
-        tokio::spawn(async move {
+        let scan_interval = capture_options.connection_scan_interval;
+        monitor.runtime.spawn(async move {
+            let mut tcp_health = TcpHealthCollector::new();
+            let mut backing_off = false;
             while !shutdown_clone.load(Ordering::Relaxed) {
+                let scan_start = Instant::now();
+
                 // Fetch connection map (blocking I/O)
                 if let Ok(conn_map) = process_utils.get_connection_map() {
                     // Process it (heavy computation - done in background!)
-                    let processed = NetworkMonitor::process_connection_map(conn_map);
+                    let mut processed = NetworkMonitor::process_connection_map(conn_map);
+                    processed.tcp_health = tcp_health.collect(&processed.socket_map);
+                    processed.socket_cache = process_utils.socket_cache_stats();
 
                     // Store pre-processed results (fast!)
                     if let Ok(mut cached) = cached_data_clone.lock() {
@@ -300,24 +432,48 @@ impl NetworkMonitor {
                     }
                 }
 
-                // Sleep for 1 second before next update
-                tokio::time::sleep(Duration::from_secs(1)).await;
+                // Huge socket tables make scans slow: wait longer rather than keep a
+                // core busy parsing them
+                let took = scan_start.elapsed();
+                let wait = connection_scan_wait(scan_interval, took);
+                let slow = wait > scan_interval.max(MIN_CONNECTION_SCAN_INTERVAL);
+                if slow != backing_off {
+                    if slow {
+                        log::info!(
+                            "Connection scan took {:?}, scanning every {:.1}s instead",
+                            took,
+                            wait.as_secs_f64()
+                        );
+                    } else {
+                        log::info!("Connection scans are fast again");
+                    }
+                    backing_off = slow;
+                }
+                tokio::time::sleep(wait).await;
             }
         });
 
-        // Start packet capture threads for all interfaces
-        let interfaces = Self::find_all_interfaces();
-        log::info!("Starting packet capture on {} interfaces", interfaces.len());
+        // Start packet capture threads for all allowed interfaces
+        log::info!(
+            "Starting packet capture on {} interfaces",
+            cached_interfaces.len()
+        );
 
-        for interface in interfaces {
+        for interface in cached_interfaces {
             let tracker_clone = Arc::clone(&monitor.bandwidth_tracker);
             let shutdown_clone = Arc::clone(&shutdown_flag);
             let iface_name = interface.name.clone();
+            let skip_clone = capture_options
+                .is_skippable(&interface.name, interface.is_loopback())
+                .then(|| Arc::clone(&skip_loopback));
 
             let capture_handle = thread::spawn(move || {
-                if let Err(e) =
-                    Self::capture_packets_on_interface(interface, tracker_clone, shutdown_clone)
-                {
+                if let Err(e) = Self::capture_packets_on_interface(
+                    interface,
+                    tracker_clone,
+                    shutdown_clone,
+                    skip_clone,
+                ) {
                     log::error!("Packet capture error on {}: {}", iface_name, e);
                 }
             });
@@ -328,6 +484,52 @@ impl NetworkMonitor {
         Ok(monitor)
     }
 
+    /// A backend that monitors without packet capture, used instead of pnet: the
+    /// preferred one, or without a preference the one preferred over pnet if available.
+    /// Returned with the name it's listed under
+    fn select_capture_free_backend(
+        preference: Option<&str>,
+    ) -> Result<Option<Box<dyn MonitorBackend>>> {
+        if let Some(name) = preference {
+            if name == "pnet" {
+                return Ok(None);
+            }
+            let info = crate::backends::monitor::detect_available_backends()
+                .into_iter()
+                .find(|backend| backend.name == name)
+                .ok_or_else(|| anyhow::anyhow!("Unknown monitor backend: {}", name))?;
+            if !info.available {
+                anyhow::bail!("Monitor backend '{}' is not available on this system", name);
+            }
+            return crate::backends::monitor::select_monitor_backend(Some(name)).map(Some);
+        }
+
+        #[cfg(target_os = "macos")]
+        if crate::backends::monitor::macos_nettop::NettopMonitor::is_available() {
+            match crate::backends::monitor::select_monitor_backend(Some("nettop")) {
+                Ok(backend) => return Ok(Some(backend)),
+                Err(e) => log::warn!("nettop monitor unavailable, using packet capture: {}", e),
+            }
+        }
+        Ok(None)
+    }
+
+    /// Stop monitoring: stop the capture threads (waiting for them, they notice within
+    /// `CAPTURE_READ_TIMEOUT`) and the connection map task, and clean up a capture-free
+    /// backend
+    pub fn cleanup(&mut self) -> Result<()> {
+        self.shutdown_flag.store(true, Ordering::Relaxed);
+        for handle in self.capture_handles.drain(..) {
+            if handle.join().is_err() {
+                log::warn!("A packet capture thread panicked");
+            }
+        }
+        if let Some(backend) = &mut self.capture_free_backend {
+            backend.cleanup()?;
+        }
+        Ok(())
+    }
+
     /// Get monitoring backend name (e.g., "pnet")
     pub fn get_monitoring_backend_name(&self) -> &'static str {
         self.monitoring_backend_name
@@ -338,9 +540,33 @@ impl NetworkMonitor {
         (&self.socket_mapper_name, &self.socket_mapper_capabilities)
     }
 
+    /// Hit/miss counts of the socket mapper's owner cache (None if it doesn't cache)
+    pub fn socket_cache_stats(&self) -> Option<SocketCacheStats> {
+        self.cached_processed_data.lock().ok()?.socket_cache
+    }
+
+    /// Parent PID from cache, looked up immediately for newly seen processes
+    fn parent_pid(&mut self, pid: i32) -> Option<i32> {
+        if let Some(&parent) = self.cached_parent_pids.get(&pid) {
+            return parent;
+        }
+        let parent = self.process_utils.get_parent_pid(pid);
+        self.cached_parent_pids.insert(pid, parent);
+        parent
+    }
+
     pub fn update(&mut self) -> Result<(ProcessMap, InterfaceMap)> {
+        if let Some(backend) = &mut self.capture_free_backend {
+            return backend.update();
+        }
+
         let now = Instant::now();
-        let elapsed = now.duration_since(self.last_update).as_secs_f64();
+        if now.duration_since(self.last_update) > MAX_SAMPLE_INTERVAL {
+            log::info!(
+                "{:.0}s since the last update (suspended?), discarding this sample's rates",
+                now.duration_since(self.last_update).as_secs_f64()
+            );
+        }
 
         // CRITICAL OPTIMIZATION: Refresh process_utils caches ONCE per update
         // This refreshes the System instance on Windows, making all subsequent
@@ -363,17 +589,27 @@ impl NetworkMonitor {
             };
 
             // Check process existence WITHOUT holding the lock
+            // Parent PIDs are refreshed too since orphans get reparented
             let mut new_cache = HashMap::new();
+            let mut new_parents = HashMap::new();
             for pid in pids_to_check {
-                new_cache.insert(pid, self.process_utils.process_exists(pid));
+                let exists = self.process_utils.process_exists(pid);
+                new_cache.insert(pid, exists);
+                if exists {
+                    new_parents.insert(pid, self.process_utils.get_parent_pid(pid));
+                } else if let Some(&parent) = self.cached_parent_pids.get(&pid) {
+                    new_parents.insert(pid, parent);
+                }
             }
 
             self.cached_process_exists = new_cache;
+            self.cached_parent_pids = new_parents;
             self.last_process_check = now;
         }
 
         // PHASE 1: Collect data from tracker (hold lock briefly)
         let (
+            sampled_at,
             process_bandwidth_snapshot,
             interface_bandwidth_snapshot,
             process_interface_snapshot,
@@ -381,6 +617,8 @@ impl NetworkMonitor {
         ) = {
             let tracker = self.bandwidth_tracker.lock().unwrap();
             (
+                // The counters below are read at this instant
+                Instant::now(),
                 tracker.process_bandwidth.clone(),
                 tracker.interface_bandwidth.clone(),
                 tracker.process_interface_bandwidth.clone(),
@@ -395,55 +633,32 @@ impl NetworkMonitor {
         let mut process_data = Vec::new();
 
         for (&pid, bandwidth) in &process_bandwidth_snapshot {
-            let rx_diff = bandwidth.rx_bytes.saturating_sub(bandwidth.last_rx_bytes);
-            let tx_diff = bandwidth.tx_bytes.saturating_sub(bandwidth.last_tx_bytes);
-
-            let download_rate = if elapsed > 0.0 {
-                (rx_diff as f64 / elapsed) as u64
-            } else {
-                0
-            };
-
-            let upload_rate = if elapsed > 0.0 {
-                (tx_diff as f64 / elapsed) as u64
-            } else {
-                0
-            };
+            // Each entry has its own baseline (new ones start when first seen)
+            let interval = sample_interval(bandwidth.last_sample, sampled_at);
+            let download_rate = counter_rate(bandwidth.rx_bytes, bandwidth.last_rx_bytes, interval);
+            let upload_rate = counter_rate(bandwidth.tx_bytes, bandwidth.last_tx_bytes, interval);
 
             // NEW: Calculate categorized rates
-            let internet_rx_diff = bandwidth
-                .internet_rx_bytes
-                .saturating_sub(bandwidth.last_internet_rx_bytes);
-            let internet_tx_diff = bandwidth
-                .internet_tx_bytes
-                .saturating_sub(bandwidth.last_internet_tx_bytes);
-            let local_rx_diff = bandwidth
-                .local_rx_bytes
-                .saturating_sub(bandwidth.last_local_rx_bytes);
-            let local_tx_diff = bandwidth
-                .local_tx_bytes
-                .saturating_sub(bandwidth.last_local_tx_bytes);
-
-            let internet_download_rate = if elapsed > 0.0 {
-                (internet_rx_diff as f64 / elapsed) as u64
-            } else {
-                0
-            };
-            let internet_upload_rate = if elapsed > 0.0 {
-                (internet_tx_diff as f64 / elapsed) as u64
-            } else {
-                0
-            };
-            let local_download_rate = if elapsed > 0.0 {
-                (local_rx_diff as f64 / elapsed) as u64
-            } else {
-                0
-            };
-            let local_upload_rate = if elapsed > 0.0 {
-                (local_tx_diff as f64 / elapsed) as u64
-            } else {
-                0
-            };
+            let internet_download_rate = counter_rate(
+                bandwidth.internet_rx_bytes,
+                bandwidth.last_internet_rx_bytes,
+                interval,
+            );
+            let internet_upload_rate = counter_rate(
+                bandwidth.internet_tx_bytes,
+                bandwidth.last_internet_tx_bytes,
+                interval,
+            );
+            let local_download_rate = counter_rate(
+                bandwidth.local_rx_bytes,
+                bandwidth.last_local_rx_bytes,
+                interval,
+            );
+            let local_upload_rate = counter_rate(
+                bandwidth.local_tx_bytes,
+                bandwidth.last_local_tx_bytes,
+                interval,
+            );
 
             let process_exists = self
                 .cached_process_exists
@@ -467,6 +682,7 @@ impl NetworkMonitor {
                 bandwidth.local_tx_bytes,
                 local_download_rate,
                 local_upload_rate,
+                bandwidth.protocol_split(interval),
                 process_exists,
                 term_time,
             ));
@@ -492,6 +708,7 @@ impl NetworkMonitor {
             local_tx_bytes,
             local_download_rate,
             local_upload_rate,
+            protocol_split,
             process_exists,
             term_time,
         ) in process_data
@@ -499,6 +716,7 @@ impl NetworkMonitor {
             if process_exists {
                 // Process is alive - include it normally
                 let mut proc_info = ProcessInfo::new(pid, name);
+                proc_info.parent_pid = self.parent_pid(pid);
                 proc_info.download_rate = download_rate;
                 proc_info.upload_rate = upload_rate;
                 proc_info.total_download = rx_bytes;
@@ -514,59 +732,28 @@ impl NetworkMonitor {
                 proc_info.local_upload_rate = local_upload_rate;
                 proc_info.local_total_download = local_rx_bytes;
                 proc_info.local_total_upload = local_tx_bytes;
+                apply_protocol_split(&mut proc_info, protocol_split, true);
 
                 // Populate per-interface stats for this process
                 proc_info.interface_stats = process_interface_snapshot
                     .iter()
                     .filter(|((p, _), _)| *p == pid)
                     .map(|((_, iface), bw)| {
-                        let rx_diff = bw.rx_bytes.saturating_sub(bw.last_rx_bytes);
-                        let tx_diff = bw.tx_bytes.saturating_sub(bw.last_tx_bytes);
-
-                        let iface_download_rate = if elapsed > 0.0 {
-                            (rx_diff as f64 / elapsed) as u64
-                        } else {
-                            0
-                        };
-
-                        let iface_upload_rate = if elapsed > 0.0 {
-                            (tx_diff as f64 / elapsed) as u64
-                        } else {
-                            0
-                        };
+                        let interval = sample_interval(bw.last_sample, sampled_at);
+                        let iface_download_rate =
+                            counter_rate(bw.rx_bytes, bw.last_rx_bytes, interval);
+                        let iface_upload_rate =
+                            counter_rate(bw.tx_bytes, bw.last_tx_bytes, interval);
 
                         // NEW: Calculate categorized rates
-                        let internet_rx_diff = bw
-                            .internet_rx_bytes
-                            .saturating_sub(bw.last_internet_rx_bytes);
-                        let internet_tx_diff = bw
-                            .internet_tx_bytes
-                            .saturating_sub(bw.last_internet_tx_bytes);
-                        let local_rx_diff =
-                            bw.local_rx_bytes.saturating_sub(bw.last_local_rx_bytes);
-                        let local_tx_diff =
-                            bw.local_tx_bytes.saturating_sub(bw.last_local_tx_bytes);
-
-                        let internet_download_rate = if elapsed > 0.0 {
-                            (internet_rx_diff as f64 / elapsed) as u64
-                        } else {
-                            0
-                        };
-                        let internet_upload_rate = if elapsed > 0.0 {
-                            (internet_tx_diff as f64 / elapsed) as u64
-                        } else {
-                            0
-                        };
-                        let local_download_rate = if elapsed > 0.0 {
-                            (local_rx_diff as f64 / elapsed) as u64
-                        } else {
-                            0
-                        };
-                        let local_upload_rate = if elapsed > 0.0 {
-                            (local_tx_diff as f64 / elapsed) as u64
-                        } else {
-                            0
-                        };
+                        let internet_download_rate =
+                            counter_rate(bw.internet_rx_bytes, bw.last_internet_rx_bytes, interval);
+                        let internet_upload_rate =
+                            counter_rate(bw.internet_tx_bytes, bw.last_internet_tx_bytes, interval);
+                        let local_download_rate =
+                            counter_rate(bw.local_rx_bytes, bw.last_local_rx_bytes, interval);
+                        let local_upload_rate =
+                            counter_rate(bw.local_tx_bytes, bw.last_local_tx_bytes, interval);
 
                         (
                             iface.clone(),
@@ -586,57 +773,36 @@ impl NetworkMonitor {
 
                 process_map.insert(pid, proc_info);
             } else {
-                // Process terminated
-                if let Some(term_time) = term_time {
-                    let since_termination = now.duration_since(term_time);
-
-                    if since_termination < TERMINATED_DISPLAY_DURATION {
-                        // Still within display window - show with skull icon
-                        let mut proc_info = ProcessInfo::new(pid, name);
-                        proc_info.download_rate = 0;
-                        proc_info.upload_rate = 0;
-                        proc_info.total_download = rx_bytes;
-                        proc_info.total_upload = tx_bytes;
-                        proc_info.is_terminated = true;
-
-                        // Populate categorized traffic totals (rates are 0 for terminated)
-                        proc_info.internet_download_rate = 0;
-                        proc_info.internet_upload_rate = 0;
-                        proc_info.internet_total_download = internet_rx_bytes;
-                        proc_info.internet_total_upload = internet_tx_bytes;
-                        proc_info.local_download_rate = 0;
-                        proc_info.local_upload_rate = 0;
-                        proc_info.local_total_download = local_rx_bytes;
-                        proc_info.local_total_upload = local_tx_bytes;
-
-                        process_map.insert(pid, proc_info);
-                    } else {
-                        // Too old - mark for removal
-                        pids_to_remove.push(pid);
-                    }
-                } else {
-                    // Newly terminated - show it and record termination time
+                // Process terminated - show it with a skull icon until the retention expires
+                if term_time.is_none() {
                     newly_terminated.push(pid);
-
-                    let mut proc_info = ProcessInfo::new(pid, name);
-                    proc_info.download_rate = 0;
-                    proc_info.upload_rate = 0;
-                    proc_info.total_download = rx_bytes;
-                    proc_info.total_upload = tx_bytes;
-                    proc_info.is_terminated = true;
-
-                    // Populate categorized traffic totals (rates are 0 for terminated)
-                    proc_info.internet_download_rate = 0;
-                    proc_info.internet_upload_rate = 0;
-                    proc_info.internet_total_download = internet_rx_bytes;
-                    proc_info.internet_total_upload = internet_tx_bytes;
-                    proc_info.local_download_rate = 0;
-                    proc_info.local_upload_rate = 0;
-                    proc_info.local_total_download = local_rx_bytes;
-                    proc_info.local_total_upload = local_tx_bytes;
-
-                    process_map.insert(pid, proc_info);
                 }
+                let since_termination = term_time.map_or(Duration::ZERO, |t| now.duration_since(t));
+                if !is_terminated_retained(since_termination) {
+                    pids_to_remove.push(pid);
+                    continue;
+                }
+
+                let mut proc_info = ProcessInfo::new(pid, name);
+                proc_info.parent_pid = self.cached_parent_pids.get(&pid).copied().flatten();
+                proc_info.download_rate = 0;
+                proc_info.upload_rate = 0;
+                proc_info.total_download = rx_bytes;
+                proc_info.total_upload = tx_bytes;
+                proc_info.is_terminated = true;
+
+                // Populate categorized traffic totals (rates are 0 for terminated)
+                proc_info.internet_download_rate = 0;
+                proc_info.internet_upload_rate = 0;
+                proc_info.internet_total_download = internet_rx_bytes;
+                proc_info.internet_total_upload = internet_tx_bytes;
+                proc_info.local_download_rate = 0;
+                proc_info.local_upload_rate = 0;
+                proc_info.local_total_download = local_rx_bytes;
+                proc_info.local_total_upload = local_tx_bytes;
+                apply_protocol_split(&mut proc_info, protocol_split, false);
+
+                process_map.insert(pid, proc_info);
             }
         }
 
@@ -648,22 +814,11 @@ impl NetworkMonitor {
             let iface_bandwidth = interface_bandwidth_snapshot.get(&iface_name);
 
             let (total_download_rate, total_upload_rate) = if let Some(bw) = iface_bandwidth {
-                let rx_diff = bw.rx_bytes.saturating_sub(bw.last_rx_bytes);
-                let tx_diff = bw.tx_bytes.saturating_sub(bw.last_tx_bytes);
-
-                let download_rate = if elapsed > 0.0 {
-                    (rx_diff as f64 / elapsed) as u64
-                } else {
-                    0
-                };
-
-                let upload_rate = if elapsed > 0.0 {
-                    (tx_diff as f64 / elapsed) as u64
-                } else {
-                    0
-                };
-
-                (download_rate, upload_rate)
+                let interval = sample_interval(bw.last_sample, sampled_at);
+                (
+                    counter_rate(bw.rx_bytes, bw.last_rx_bytes, interval),
+                    counter_rate(bw.tx_bytes, bw.last_tx_bytes, interval),
+                )
             } else {
                 (0, 0)
             };
@@ -687,14 +842,22 @@ impl NetworkMonitor {
             // Extract IP addresses
             let ip_addresses: Vec<IpAddr> = interface.ips.iter().map(|ip| ip.ip()).collect();
 
+            // Read live - the cached interface flags are from startup
+            let link = LinkInfo::read(&iface_name);
+
             interface_map.insert(
                 iface_name.clone(),
                 InterfaceInfo {
                     name: iface_name,
                     mac_address,
                     ip_addresses,
-                    is_up: interface.is_up(),
+                    is_up: link.is_up.unwrap_or_else(|| interface.is_up()),
                     is_loopback: interface.is_loopback(),
+                    mtu: link.mtu,
+                    speed_mbps: link.speed_mbps,
+                    operstate: link.operstate,
+                    driver: link.driver,
+                    errors: link.errors,
                     total_download_rate,
                     total_upload_rate,
                     process_count,
@@ -706,30 +869,47 @@ impl NetworkMonitor {
         {
             let mut tracker = self.bandwidth_tracker.lock().unwrap();
 
-            // Update last values for process bandwidth
-            for (&pid, bandwidth) in &mut tracker.process_bandwidth {
-                bandwidth.last_rx_bytes = bandwidth.rx_bytes;
-                bandwidth.last_tx_bytes = bandwidth.tx_bytes;
-                bandwidth.last_internet_rx_bytes = bandwidth.internet_rx_bytes;
-                bandwidth.last_internet_tx_bytes = bandwidth.internet_tx_bytes;
-                bandwidth.last_local_rx_bytes = bandwidth.local_rx_bytes;
-                bandwidth.last_local_tx_bytes = bandwidth.local_tx_bytes;
+            // The next baselines are the counters as sampled, so packets counted while
+            // this update ran show up in the next sample instead of being lost
+            for (pid, bandwidth) in &mut tracker.process_bandwidth {
+                let Some(sampled) = process_bandwidth_snapshot.get(pid) else {
+                    continue;
+                };
+                bandwidth.last_rx_bytes = sampled.rx_bytes;
+                bandwidth.last_tx_bytes = sampled.tx_bytes;
+                bandwidth.last_internet_rx_bytes = sampled.internet_rx_bytes;
+                bandwidth.last_internet_tx_bytes = sampled.internet_tx_bytes;
+                bandwidth.last_local_rx_bytes = sampled.local_rx_bytes;
+                bandwidth.last_local_tx_bytes = sampled.local_tx_bytes;
+                bandwidth.last_tcp_rx_bytes = sampled.tcp_rx_bytes;
+                bandwidth.last_tcp_tx_bytes = sampled.tcp_tx_bytes;
+                bandwidth.last_udp_rx_bytes = sampled.udp_rx_bytes;
+                bandwidth.last_udp_tx_bytes = sampled.udp_tx_bytes;
+                bandwidth.last_sample = sampled_at;
             }
 
             // Update last values for interface bandwidth
-            for (_, bw) in &mut tracker.interface_bandwidth {
-                bw.last_rx_bytes = bw.rx_bytes;
-                bw.last_tx_bytes = bw.tx_bytes;
+            for (name, bw) in &mut tracker.interface_bandwidth {
+                let Some(sampled) = interface_bandwidth_snapshot.get(name) else {
+                    continue;
+                };
+                bw.last_rx_bytes = sampled.rx_bytes;
+                bw.last_tx_bytes = sampled.tx_bytes;
+                bw.last_sample = sampled_at;
             }
 
             // Update last values for process-interface bandwidth
-            for (_, bw) in &mut tracker.process_interface_bandwidth {
-                bw.last_rx_bytes = bw.rx_bytes;
-                bw.last_tx_bytes = bw.tx_bytes;
-                bw.last_internet_rx_bytes = bw.internet_rx_bytes;
-                bw.last_internet_tx_bytes = bw.internet_tx_bytes;
-                bw.last_local_rx_bytes = bw.local_rx_bytes;
-                bw.last_local_tx_bytes = bw.local_tx_bytes;
+            for (key, bw) in &mut tracker.process_interface_bandwidth {
+                let Some(sampled) = process_interface_snapshot.get(key) else {
+                    continue;
+                };
+                bw.last_rx_bytes = sampled.rx_bytes;
+                bw.last_tx_bytes = sampled.tx_bytes;
+                bw.last_internet_rx_bytes = sampled.internet_rx_bytes;
+                bw.last_internet_tx_bytes = sampled.internet_tx_bytes;
+                bw.last_local_rx_bytes = sampled.local_rx_bytes;
+                bw.last_local_tx_bytes = sampled.local_tx_bytes;
+                bw.last_sample = sampled_at;
             }
 
             // Record newly terminated processes
@@ -737,10 +917,15 @@ impl NetworkMonitor {
                 tracker.terminated_processes.insert(pid, now);
             }
 
-            // Clean up old terminated processes
-            for pid in pids_to_remove {
-                tracker.process_bandwidth.remove(&pid);
-                tracker.terminated_processes.remove(&pid);
+            // Clean up terminated processes past the retention
+            for pid in &pids_to_remove {
+                tracker.process_bandwidth.remove(pid);
+                tracker.terminated_processes.remove(pid);
+            }
+            if !pids_to_remove.is_empty() {
+                tracker
+                    .process_interface_bandwidth
+                    .retain(|(pid, _), _| !pids_to_remove.contains(pid));
             }
         }
         // Lock is released here!
@@ -757,7 +942,15 @@ impl NetworkMonitor {
 
         for process in process_map.values_mut() {
             process.populate_connections(&processed.raw_connection_map, &processed.socket_map);
+            let health = processed
+                .tcp_health
+                .get(&process.pid)
+                .copied()
+                .unwrap_or_default();
+            process.retransmit_rate = health.retransmit_rate;
+            process.avg_rtt_ms = health.avg_rtt_ms;
         }
+        self.connection_events.update(&mut process_map, now);
 
         Ok((process_map, interface_map))
     }
@@ -772,6 +965,8 @@ impl NetworkMonitor {
     ) {
         log::info!("Starting monitoring background thread");
         let mut last_update = Instant::now();
+        // Kept here rather than in the monitor so it survives hot swaps
+        let mut activity = ActivityTracker::default();
 
         loop {
             // Check for commands (non-blocking)
@@ -789,39 +984,37 @@ impl NetworkMonitor {
                         backend_name
                     );
 
-                    // Extract bandwidth data before replacing monitor
-                    let (process_bandwidth, terminated_processes) = self.extract_bandwidth_data();
-                    let process_count = process_bandwidth.len();
-                    let terminated_count = terminated_processes.len();
-
-                    // Create new monitor with different socket mapper
-                    match NetworkMonitor::with_socket_mapper(Some(&backend_name)) {
-                        Ok(mut new_monitor) => {
-                            // Restore bandwidth data
-                            new_monitor
-                                .restore_bandwidth_data(process_bandwidth, terminated_processes);
-
-                            log::info!(
-                                "Socket mapper switched successfully (preserved {} processes, {} terminated) - hot-swapping monitor",
-                                process_count,
-                                terminated_count
-                            );
-
-                            // HOT-SWAP: Replace self with new monitor
-                            // Old monitor will be dropped (cleanup threads), new one takes over
-                            self = new_monitor;
-
-                            // Send success response
-                            let _ = response_tx.send(Ok(()));
+                    let monitor_backend = self.monitoring_backend_name;
+                    let result = self.hot_swap(monitor_backend, Some(&backend_name));
+                    if let Err(e) = &result {
+                        // Continue with existing monitor
+                        log::error!("Failed to switch socket mapper: {}", e);
+                    }
+                    let _ = response_tx.send(result);
+                }
+                Ok(MonitorCommand::SwitchMonitorBackend {
+                    backend_name,
+                    response_tx,
+                }) => {
+                    log::info!(
+                        "Monitoring thread hot-swapping monitor backend to: {}",
+                        backend_name
+                    );
 
-                            // Continue monitoring with new backend!
-                        }
-                        Err(e) => {
-                            log::error!("Failed to switch socket mapper: {}", e);
-                            let _ = response_tx.send(Err(e));
-                            // Continue with existing monitor
-                        }
+                    let socket_mapper = self.socket_mapper_preference.clone();
+                    let result = self.hot_swap(&backend_name, socket_mapper.as_deref());
+                    if let Err(e) = &result {
+                        // Continue with existing monitor
+                        log::error!("Failed to switch monitor backend: {}", e);
                     }
+                    let _ = response_tx.send(result);
+                }
+                Ok(MonitorCommand::SetSkipLoopback(skip)) => {
+                    log::info!(
+                        "Loopback capture {}",
+                        if skip { "paused" } else { "resumed" }
+                    );
+                    self.skip_loopback.store(skip, Ordering::Relaxed);
                 }
                 Err(mpsc::error::TryRecvError::Empty) => {
                     // No command, continue monitoring
@@ -838,15 +1031,18 @@ impl NetworkMonitor {
                 let update_start = Instant::now();
 
                 match self.update() {
-                    Ok((process_map, interface_map)) => {
+                    Ok((mut process_map, interface_map)) => {
+                        activity.update(&mut process_map, Instant::now());
                         let update_time = update_start.elapsed();
 
                         // Send update to UI thread (non-blocking) with socket mapper info
                         let update_data = MonitorUpdateData {
                             process_map,
                             interface_map,
+                            monitor_backend_name: self.monitoring_backend_name,
                             socket_mapper_name: self.socket_mapper_name.clone(),
                             socket_mapper_capabilities: self.socket_mapper_capabilities.clone(),
+                            socket_cache: self.socket_cache_stats(),
                         };
 
                         if update_tx.send(update_data).is_err() {
@@ -877,6 +1073,45 @@ impl NetworkMonitor {
         log::info!("Monitoring background thread exiting");
     }
 
+    /// Replace this monitor with one using other backends, carrying over the bandwidth
+    /// totals. The new monitor is created first, so if that fails monitoring continues
+    /// unchanged; the old one is cleaned up before its final totals are taken over
+    fn hot_swap(&mut self, monitor_backend: &str, socket_mapper: Option<&str>) -> Result<()> {
+        let capture_options = CaptureOptions {
+            interfaces: self.capture_interfaces.clone(),
+            skip_loopback: self.skip_loopback.load(Ordering::Relaxed),
+            connection_scan_interval: self.connection_scan_interval,
+        };
+        let mut new_monitor = {
+            // The monitoring thread isn't a runtime thread
+            let _runtime = self.runtime.enter();
+            NetworkMonitor::with_backends(Some(monitor_backend), socket_mapper, capture_options)?
+        };
+
+        if let Err(e) = self.cleanup() {
+            log::warn!(
+                "Failed to clean up the {} monitor: {}",
+                self.monitoring_backend_name,
+                e
+            );
+        }
+        let (process_bandwidth, terminated_processes) = self.extract_bandwidth_data();
+        log::info!(
+            "Hot-swapping monitor ({} → {}, socket mapper {} → {}), preserving {} processes ({} terminated)",
+            self.monitoring_backend_name,
+            new_monitor.monitoring_backend_name,
+            self.socket_mapper_name,
+            new_monitor.socket_mapper_name,
+            process_bandwidth.len(),
+            terminated_processes.len()
+        );
+        new_monitor.restore_bandwidth_data(process_bandwidth, terminated_processes);
+
+        // The old monitor is dropped here, the new one takes over
+        *self = new_monitor;
+        Ok(())
+    }
+
     /// Process raw connection map into usable data structures
     /// This is the heavy computation that should run in the background task
     fn process_connection_map(conn_map: ConnectionMap) -> ProcessedConnectionData {
@@ -963,6 +1198,8 @@ impl NetworkMonitor {
             connection_map: new_connection_map,
             pids_with_names,
             raw_connection_map: raw_map, // Keep the raw map for populate_connections
+            tcp_health: HashMap::new(),  // Filled in by the background task
+            socket_cache: None,          // Filled in by the background task
         }
     }
 
@@ -1024,7 +1261,7 @@ impl NetworkMonitor {
             tracker
                 .process_bandwidth
                 .entry(pid)
-                .or_insert(ProcessBandwidth {
+                .or_insert_with(|| ProcessBandwidth {
                     name,
                     rx_bytes: 0,
                     tx_bytes: 0,
@@ -1038,6 +1275,15 @@ impl NetworkMonitor {
                     last_internet_tx_bytes: 0,
                     last_local_rx_bytes: 0,
                     last_local_tx_bytes: 0,
+                    tcp_rx_bytes: 0,
+                    tcp_tx_bytes: 0,
+                    udp_rx_bytes: 0,
+                    udp_tx_bytes: 0,
+                    last_tcp_rx_bytes: 0,
+                    last_tcp_tx_bytes: 0,
+                    last_udp_rx_bytes: 0,
+                    last_udp_tx_bytes: 0,
+                    last_sample: Instant::now(),
                 });
         }
 
@@ -1054,13 +1300,18 @@ impl NetworkMonitor {
         interface: NetworkInterface,
         tracker: Arc<Mutex<BandwidthTracker>>,
         shutdown: Arc<AtomicBool>,
+        skip: Option<Arc<AtomicBool>>,
     ) -> Result<()> {
         let iface_name = interface.name.clone();
         log::info!("Packet capture thread started on interface: {}", iface_name);
         log::debug!("Interface details: {:?}", interface);
 
-        // Create channel for packet capture
-        let (_, mut rx) = match datalink::channel(&interface, Default::default()) {
+        // Create channel for packet capture (timing out so shutdown is noticed when idle)
+        let config = datalink::Config {
+            read_timeout: Some(CAPTURE_READ_TIMEOUT),
+            ..Default::default()
+        };
+        let (_, mut rx) = match datalink::channel(&interface, config) {
             Ok(Channel::Ethernet(tx, rx)) => {
                 log::info!(
                     "Successfully created packet capture channel for {}",
@@ -1095,6 +1346,9 @@ impl NetworkMonitor {
             }
 
             match rx.next() {
+                Ok(_) if skip.as_ref().is_some_and(|s| s.load(Ordering::Relaxed)) => {
+                    // Drain without processing while this interface is skipped
+                }
                 Ok(packet) => {
                     if let Err(e) = Self::process_packet(packet, &iface_name, &tracker) {
                         // Don't spam errors, just continue
@@ -1103,6 +1357,9 @@ impl NetworkMonitor {
                         }
                     }
                 }
+                Err(e) if e.kind() == std::io::ErrorKind::TimedOut => {
+                    // No packet within the read timeout - just check for shutdown
+                }
                 Err(e) => {
                     log::error!("Packet receive error on {}: {}", iface_name, e);
                     thread::sleep(Duration::from_millis(100));
@@ -1438,6 +1695,11 @@ impl NetworkMonitor {
             }
         }
 
+        // Loopback traffic is left out entirely when it isn't counted
+        let pid_and_direction = pid_and_direction.filter(|&(_, is_outbound)| {
+            crate::traffic_classifier::is_counted(if is_outbound { &dst_addr } else { &src_addr })
+        });
+
         if let Some((pid, is_outbound)) = pid_and_direction {
             tracker.packets_matched += 1;
 
@@ -1453,24 +1715,45 @@ impl NetworkMonitor {
             let traffic_category = crate::traffic_classifier::categorize_traffic(&remote_ip);
 
             // Track overall process bandwidth
-            let bandwidth = tracker
-                .process_bandwidth
-                .entry(pid)
-                .or_insert(ProcessBandwidth {
-                    name,
-                    rx_bytes: 0,
-                    tx_bytes: 0,
-                    last_rx_bytes: 0,
-                    last_tx_bytes: 0,
-                    internet_rx_bytes: 0,
-                    internet_tx_bytes: 0,
-                    local_rx_bytes: 0,
-                    local_tx_bytes: 0,
-                    last_internet_rx_bytes: 0,
-                    last_internet_tx_bytes: 0,
-                    last_local_rx_bytes: 0,
-                    last_local_tx_bytes: 0,
-                });
+            let bandwidth =
+                tracker
+                    .process_bandwidth
+                    .entry(pid)
+                    .or_insert_with(|| ProcessBandwidth {
+                        name,
+                        rx_bytes: 0,
+                        tx_bytes: 0,
+                        last_rx_bytes: 0,
+                        last_tx_bytes: 0,
+                        internet_rx_bytes: 0,
+                        internet_tx_bytes: 0,
+                        local_rx_bytes: 0,
+                        local_tx_bytes: 0,
+                        last_internet_rx_bytes: 0,
+                        last_internet_tx_bytes: 0,
+                        last_local_rx_bytes: 0,
+                        last_local_tx_bytes: 0,
+                        tcp_rx_bytes: 0,
+                        tcp_tx_bytes: 0,
+                        udp_rx_bytes: 0,
+                        udp_tx_bytes: 0,
+                        last_tcp_rx_bytes: 0,
+                        last_tcp_tx_bytes: 0,
+                        last_udp_rx_bytes: 0,
+                        last_udp_tx_bytes: 0,
+                        last_sample: Instant::now(),
+                    });
+
+            // Protocol split, from the connection the packet was matched to
+            let (protocol_rx, protocol_tx) = match protocol {
+                Protocol::Tcp => (&mut bandwidth.tcp_rx_bytes, &mut bandwidth.tcp_tx_bytes),
+                Protocol::Udp => (&mut bandwidth.udp_rx_bytes, &mut bandwidth.udp_tx_bytes),
+            };
+            if is_outbound {
+                *protocol_tx += packet_len as u64;
+            } else {
+                *protocol_rx += packet_len as u64;
+            }
 
             if is_outbound {
                 bandwidth.tx_bytes += packet_len as u64;
@@ -1500,7 +1783,7 @@ impl NetworkMonitor {
             let proc_iface_bandwidth = tracker
                 .process_interface_bandwidth
                 .entry((pid, interface_name.to_string()))
-                .or_insert(ProcessInterfaceBandwidth {
+                .or_insert_with(|| ProcessInterfaceBandwidth {
                     rx_bytes: 0,
                     tx_bytes: 0,
                     last_rx_bytes: 0,
@@ -1513,6 +1796,7 @@ impl NetworkMonitor {
                     last_internet_tx_bytes: 0,
                     last_local_rx_bytes: 0,
                     last_local_tx_bytes: 0,
+                    last_sample: Instant::now(),
                 });
 
             if is_outbound {
@@ -1543,12 +1827,13 @@ impl NetworkMonitor {
             let iface_bandwidth = tracker
                 .interface_bandwidth
                 .entry(interface_name.to_string())
-                .or_insert(InterfaceBandwidth {
+                .or_insert_with(|| InterfaceBandwidth {
                     name: interface_name.to_string(),
                     rx_bytes: 0,
                     tx_bytes: 0,
                     last_rx_bytes: 0,
                     last_tx_bytes: 0,
+                    last_sample: Instant::now(),
                 });
 
             if is_outbound {
@@ -1565,12 +1850,13 @@ impl NetworkMonitor {
             let iface_bandwidth = tracker
                 .interface_bandwidth
                 .entry(interface_name.to_string())
-                .or_insert(InterfaceBandwidth {
+                .or_insert_with(|| InterfaceBandwidth {
                     name: interface_name.to_string(),
                     rx_bytes: 0,
                     tx_bytes: 0,
                     last_rx_bytes: 0,
                     last_tx_bytes: 0,
+                    last_sample: Instant::now(),
                 });
             iface_bandwidth.rx_bytes += packet_len as u64;
 
@@ -1662,18 +1948,14 @@ impl Drop for NetworkMonitor {
         // Signal all threads to stop
         self.shutdown_flag.store(true, Ordering::Relaxed);
 
-        // Don't wait for threads to finish - they will exit naturally when they process
-        // their next packet (or on program termination). This prevents UI freeze since
-        // threads are blocked in rx.next() and only check the shutdown flag between packets.
+        // Don't wait for threads to finish (cleanup() does) - they notice the flag
+        // within CAPTURE_READ_TIMEOUT and exit on their own.
         //
         // This is safe because:
-        // 1. Threads will exit within seconds (on next packet) or on program exit
-        // 2. They only read network data and write to bandwidth_tracker Arc
-        // 3. No resource leaks - datalink channels close when threads exit
-        // 4. bandwidth_tracker won't be freed until all thread references are dropped
+        // 1. They only read network data and write to bandwidth_tracker Arc
+        // 2. No resource leaks - datalink channels close when threads exit
+        // 3. bandwidth_tracker won't be freed until all thread references are dropped
 
-        log::info!(
-            "NetworkMonitor shutdown signaled (threads will exit on next packet or program termination)"
-        );
+        log::info!("NetworkMonitor shutdown signaled (capture threads exit within a read timeout)");
     }
 }
@@ -1,3 +1,5 @@
+use crate::backends::monitor::InterfaceErrors;
+use crate::connection_events::ConnectionEvent;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::net::IpAddr;
@@ -16,10 +18,156 @@ impl Default for TrafficType {
     }
 }
 
+impl std::fmt::Display for TrafficType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TrafficType::All => write!(f, "all"),
+            TrafficType::Internet => write!(f, "internet"),
+            TrafficType::Local => write!(f, "local"),
+        }
+    }
+}
+
+impl std::str::FromStr for TrafficType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "all" => Ok(TrafficType::All),
+            "internet" => Ok(TrafficType::Internet),
+            "local" => Ok(TrafficType::Local),
+            _ => Err(anyhow::anyhow!(
+                "Unknown traffic type '{}' (expected 'all', 'internet' or 'local')",
+                s
+            )),
+        }
+    }
+}
+
+/// What a throttle does with traffic over its limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Policy {
+    Shape, // Queue excess packets and release them at the limit (gentle on TCP)
+    Drop,  // Drop excess packets
+}
+
+impl std::fmt::Display for Policy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Policy::Shape => write!(f, "shape"),
+            Policy::Drop => write!(f, "drop"),
+        }
+    }
+}
+
+impl std::str::FromStr for Policy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "shape" => Ok(Policy::Shape),
+            "drop" => Ok(Policy::Drop),
+            _ => Err(anyhow::anyhow!(
+                "Unknown policy '{}' (expected 'shape' or 'drop')",
+                s
+            )),
+        }
+    }
+}
+
+/// How a throttled process ranks against other throttled processes: higher priority
+/// traffic is sent first and gets spare bandwidth first (limits still cap it)
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize,
+)]
+pub enum Priority {
+    High,
+    #[default]
+    Normal,
+    Low,
+}
+
+impl Priority {
+    /// HTB class priority (0 is served first, 7 last)
+    pub fn htb_prio(&self) -> u32 {
+        match self {
+            Priority::High => 0,
+            Priority::Normal => 4,
+            Priority::Low => 7,
+        }
+    }
+
+    /// Cycle normal -> high -> low
+    pub fn next(&self) -> Self {
+        match self {
+            Priority::Normal => Priority::High,
+            Priority::High => Priority::Low,
+            Priority::Low => Priority::Normal,
+        }
+    }
+}
+
+impl std::fmt::Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Priority::High => write!(f, "high"),
+            Priority::Normal => write!(f, "normal"),
+            Priority::Low => write!(f, "low"),
+        }
+    }
+}
+
+impl std::str::FromStr for Priority {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "high" => Ok(Priority::High),
+            "normal" => Ok(Priority::Normal),
+            "low" => Ok(Priority::Low),
+            _ => Err(anyhow::anyhow!(
+                "Unknown priority '{}' (expected 'high', 'normal' or 'low')",
+                s
+            )),
+        }
+    }
+}
+
+/// IP version a throttle applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AddressFamily {
+    #[default]
+    Any, // Both IPv4 and IPv6
+    V4, // IPv4 traffic only
+    V6, // IPv6 traffic only
+}
+
+impl AddressFamily {
+    /// Whether a backend with these capabilities can throttle this family
+    pub fn supported_by(&self, capabilities: &crate::backends::BackendCapabilities) -> bool {
+        match self {
+            AddressFamily::Any => true,
+            AddressFamily::V4 => capabilities.ipv4_support,
+            AddressFamily::V6 => capabilities.ipv6_support,
+        }
+    }
+}
+
+impl std::fmt::Display for AddressFamily {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AddressFamily::Any => write!(f, "IPv4 + IPv6"),
+            AddressFamily::V4 => write!(f, "IPv4 only"),
+            AddressFamily::V6 => write!(f, "IPv6 only"),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ProcessInfo {
     pub pid: i32,
     pub name: String,
+    pub parent_pid: Option<i32>, // parent PID (for the process tree view)
 
     // Aggregate rates (existing)
     pub download_rate: u64,  // bytes per second
@@ -39,10 +187,28 @@ pub struct ProcessInfo {
     pub local_total_download: u64,
     pub local_total_upload: u64,
 
+    // Protocol split (TCP vs UDP)
+    pub tcp_download_rate: u64,
+    pub tcp_upload_rate: u64,
+    pub tcp_total_download: u64,
+    pub tcp_total_upload: u64,
+    pub udp_download_rate: u64,
+    pub udp_upload_rate: u64,
+    pub udp_total_download: u64,
+    pub udp_total_upload: u64,
+
+    // TCP health (Linux only, zero/None elsewhere)
+    pub retransmit_rate: f64,    // TCP segments retransmitted per second
+    pub avg_rtt_ms: Option<f64>, // mean smoothed RTT of its TCP sockets
+
     pub throttle_limit: Option<ThrottleLimit>,
-    pub is_terminated: bool, // whether the process has terminated
+    pub throttle_sandboxed: bool, // throttled in place in its sandbox's or container's cgroup
+    pub is_terminated: bool,      // whether the process has terminated
+    pub has_active_transfer: bool, // moved bytes within the last minute (see activity.rs)
+    pub listen_only: bool,        // idle, with only listening/unconnected sockets
     pub interface_stats: HashMap<String, InterfaceStats>, // per-interface statistics
     pub connections: Vec<ConnectionDetail>, // active network connections
+    pub connection_events: Vec<ConnectionEvent>, // recent opens/closes, newest first
 }
 
 #[derive(Debug, Clone)]
@@ -67,16 +233,78 @@ pub struct InterfaceInfo {
     pub ip_addresses: Vec<IpAddr>,
     pub is_up: bool,
     pub is_loopback: bool,
+    pub mtu: Option<u32>,
+    pub speed_mbps: Option<u64>, // None when the link speed isn't reported (virtual, loopback)
+    pub operstate: Option<String>,
+    pub driver: Option<String>,
+    pub errors: Option<InterfaceErrors>,
     pub total_download_rate: u64,
     pub total_upload_rate: u64,
     pub process_count: usize,
 }
 
+impl InterfaceInfo {
+    /// Link speed for display (e.g. "100 Mb/s", "2.5 Gb/s")
+    pub fn speed_label(&self) -> Option<String> {
+        self.speed_mbps.map(|mbps| {
+            if mbps >= 1000 {
+                format!("{} Gb/s", mbps as f64 / 1000.0)
+            } else {
+                format!("{} Mb/s", mbps)
+            }
+        })
+    }
+
+    /// A rate in bytes/s as a percentage of the link speed (None without a known speed)
+    pub fn link_utilization(&self, bytes_per_sec: u64) -> Option<f64> {
+        self.speed_mbps
+            .map(|mbps| bytes_per_sec as f64 * 8.0 / (mbps as f64 * 1_000_000.0) * 100.0)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ThrottleLimit {
-    pub download_limit: Option<u64>, // bytes per second
-    pub upload_limit: Option<u64>,   // bytes per second
-    pub traffic_type: TrafficType,   // NEW: which traffic to throttle
+    pub download_limit: Option<u64>,     // bytes per second
+    pub upload_limit: Option<u64>,       // bytes per second
+    pub upload_minimum: Option<u64>,     // guaranteed upload rate (bytes/sec), where supported
+    pub traffic_type: TrafficType,       // NEW: which traffic to throttle
+    pub interfaces: Option<Vec<String>>, // None = all interfaces
+    pub policy: Option<Policy>,          // None = the backend's default policy
+    pub family: AddressFamily,           // Any = both IPv4 and IPv6
+    pub priority: Priority,              // rank against other throttled processes
+}
+
+/// Slowest limit that can be enforced: tc rates are set in whole bytes/sec (and
+/// HTB classes in kbit), so anything below this would round to "no limit"
+pub const MIN_LIMIT_BYTES_PER_SEC: u64 = 128;
+
+/// Reject limits below `MIN_LIMIT_BYTES_PER_SEC`
+pub fn check_limit(bytes_per_sec: u64) -> anyhow::Result<u64> {
+    if bytes_per_sec < MIN_LIMIT_BYTES_PER_SEC {
+        return Err(anyhow::anyhow!(
+            "{} B/s is below the minimum limit of {} B/s (0.125 KB/s)",
+            bytes_per_sec,
+            MIN_LIMIT_BYTES_PER_SEC
+        ));
+    }
+    Ok(bytes_per_sec)
+}
+
+/// Reject a guaranteed minimum that isn't a valid limit, or that has no upload
+/// limit to sit under
+pub fn check_minimum(minimum: u64, upload_limit: Option<u64>) -> anyhow::Result<u64> {
+    check_limit(minimum)?;
+    match upload_limit {
+        None => Err(anyhow::anyhow!(
+            "a guaranteed minimum needs an upload limit as its maximum"
+        )),
+        Some(limit) if minimum > limit => Err(anyhow::anyhow!(
+            "the guaranteed minimum ({} B/s) is above the upload limit ({} B/s)",
+            minimum,
+            limit
+        )),
+        Some(_) => Ok(minimum),
+    }
 }
 
 impl ProcessInfo {
@@ -84,6 +312,7 @@ impl ProcessInfo {
         Self {
             pid,
             name,
+            parent_pid: None,
             download_rate: 0,
             upload_rate: 0,
             total_download: 0,
@@ -96,38 +325,38 @@ impl ProcessInfo {
             local_upload_rate: 0,
             local_total_download: 0,
             local_total_upload: 0,
+            tcp_download_rate: 0,
+            tcp_upload_rate: 0,
+            tcp_total_download: 0,
+            tcp_total_upload: 0,
+            udp_download_rate: 0,
+            udp_upload_rate: 0,
+            udp_total_download: 0,
+            udp_total_upload: 0,
+            retransmit_rate: 0.0,
+            avg_rtt_ms: None,
             throttle_limit: None,
+            throttle_sandboxed: false,
             is_terminated: false,
+            has_active_transfer: false,
+            listen_only: false,
             interface_stats: HashMap::new(),
             connections: Vec::new(),
+            connection_events: Vec::new(),
         }
     }
 
-    pub fn format_rate(bytes_per_sec: u64) -> String {
-        if bytes_per_sec < 1024 {
-            format!("{} B/s", bytes_per_sec)
-        } else if bytes_per_sec < 1024 * 1024 {
-            format!("{:.1} KB/s", bytes_per_sec as f64 / 1024.0)
-        } else if bytes_per_sec < 1024 * 1024 * 1024 {
-            format!("{:.1} MB/s", bytes_per_sec as f64 / (1024.0 * 1024.0))
+    /// Fraction of the upload (or download) bytes seen so far that were UDP
+    ///
+    /// None until any TCP or UDP traffic has been attributed to the process.
+    pub fn udp_share(&self, upload: bool) -> Option<f64> {
+        let (tcp, udp) = if upload {
+            (self.tcp_total_upload, self.udp_total_upload)
         } else {
-            format!(
-                "{:.1} GB/s",
-                bytes_per_sec as f64 / (1024.0 * 1024.0 * 1024.0)
-            )
-        }
-    }
-
-    pub fn format_bytes(bytes: u64) -> String {
-        if bytes < 1024 {
-            format!("{} B", bytes)
-        } else if bytes < 1024 * 1024 {
-            format!("{:.1} KB", bytes as f64 / 1024.0)
-        } else if bytes < 1024 * 1024 * 1024 {
-            format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
-        } else {
-            format!("{:.1} GB", bytes as f64 / (1024.0 * 1024.0 * 1024.0))
-        }
+            (self.tcp_total_download, self.udp_total_download)
+        };
+        let total = tcp + udp;
+        (total > 0).then(|| udp as f64 / total as f64)
     }
 
     pub fn is_throttled(&self) -> bool {
@@ -155,6 +384,7 @@ impl ProcessInfo {
                             remote_addr: entry.remote_addr,
                             remote_port: entry.remote_port,
                             state: entry.state.clone(),
+                            inode: entry.inode,
                         });
                     }
                 }
@@ -180,6 +410,20 @@ pub struct ConnectionDetail {
     pub remote_addr: IpAddr,
     pub remote_port: u16,
     pub state: String, // ESTABLISHED, LISTEN, etc. (empty for UDP)
+    pub inode: u64,    // socket inode (tells a reused address apart from the old connection)
+}
+
+impl ConnectionDetail {
+    /// A listening TCP socket or a UDP socket without a peer: waits for traffic rather
+    /// than carrying any of its own
+    pub fn is_listener(&self) -> bool {
+        if self.protocol.starts_with("TCP") {
+            // "Listen" from procfs, "LISTEN" from the other socket mappers
+            self.state.eq_ignore_ascii_case("listen")
+        } else {
+            self.remote_port == 0
+        }
+    }
 }
 
 /// Extended process information including system details
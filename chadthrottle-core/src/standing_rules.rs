@@ -0,0 +1,109 @@
+// Standing rules: throttles for processes that haven't started yet
+//
+// A rule (`standing_rules` in the config) names a process or glob pattern and the
+// limits its processes get. The throttle manager checks every PID it hasn't seen
+// before against the rules, so a matching process is throttled within one update of
+// showing up in the process map, or right after its exec when an
+// `ExecWatcher` reports it. Rule throttles aren't saved with the other throttles:
+// the rule applies them again on the next run.
+
+use crate::config::StandingRuleConfig;
+use crate::format::format_rate;
+use crate::interface_filter::glob_match;
+use crate::limits::parse_throttle_limit;
+use crate::process::{AddressFamily, Priority, ThrottleLimit, TrafficType};
+use anyhow::{Context, Result, bail};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StandingRule {
+    /// Process name or glob pattern ("apt*")
+    pub pattern: String,
+    /// Bytes/sec (None = unlimited)
+    pub download_limit: Option<u64>,
+    pub upload_limit: Option<u64>,
+}
+
+impl StandingRule {
+    pub fn parse(config: &StandingRuleConfig) -> Result<Self> {
+        if config.pattern.trim().is_empty() {
+            bail!("A standing rule needs a process name or pattern to match");
+        }
+        let parse = |limit: &Option<String>| {
+            limit
+                .as_deref()
+                .map(|limit| {
+                    parse_throttle_limit(limit)
+                        .with_context(|| format!("Invalid limit '{}'", limit))
+                })
+                .transpose()
+        };
+        let rule = Self {
+            pattern: config.pattern.trim().to_string(),
+            download_limit: parse(&config.download)?,
+            upload_limit: parse(&config.upload)?,
+        };
+        if rule.download_limit.is_none() && rule.upload_limit.is_none() {
+            bail!(
+                "Standing rule '{}' has no download or upload limit",
+                rule.pattern
+            );
+        }
+        Ok(rule)
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        glob_match(&self.pattern, name)
+    }
+
+    /// The throttle a matching process gets: both IP versions, all traffic and
+    /// interfaces, each backend's default policy
+    pub fn limit(&self) -> ThrottleLimit {
+        ThrottleLimit {
+            upload_limit: self.upload_limit,
+            upload_minimum: None,
+            download_limit: self.download_limit,
+            traffic_type: TrafficType::All,
+            interfaces: None,
+            policy: None,
+            family: AddressFamily::Any,
+            priority: Priority::Normal,
+        }
+    }
+
+    /// The limits, e.g. "↓ 2.0 MB/s ↑ unlimited"
+    pub fn describe(&self) -> String {
+        let limit = |limit: Option<u64>| limit.map_or("unlimited".to_string(), format_rate);
+        format!(
+            "↓ {} ↑ {}",
+            limit(self.download_limit),
+            limit(self.upload_limit)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(pattern: &str, download: Option<&str>, upload: Option<&str>) -> StandingRuleConfig {
+        StandingRuleConfig {
+            pattern: pattern.to_string(),
+            download: download.map(str::to_string),
+            upload: upload.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_parse_standing_rule() {
+        let rule = StandingRule::parse(&config("apt*", Some("2M"), None)).unwrap();
+        assert_eq!(rule.download_limit, Some(2 * 1024 * 1024));
+        assert_eq!(rule.upload_limit, None);
+        assert!(rule.matches("apt-get"));
+        assert!(!rule.matches("snapd"));
+        assert_eq!(rule.limit().download_limit, Some(2 * 1024 * 1024));
+
+        assert!(StandingRule::parse(&config("apt*", None, None)).is_err());
+        assert!(StandingRule::parse(&config("apt*", Some("fast"), None)).is_err());
+        assert!(StandingRule::parse(&config(" ", Some("2M"), None)).is_err());
+    }
+}
@@ -0,0 +1,503 @@
+//! Traffic categorization utilities (internet vs local network)
+//!
+//! This module provides cross-platform IP address classification.
+//! All monitor backends use this to categorize traffic consistently.
+//!
+//! The built-in rules can be overridden per subnet (`subnet_overrides` in the
+//! config), e.g. to treat a VPN range inside 10.0.0.0/8 as internet traffic.
+//! Loopback traffic never leaves the machine: it is always local, whatever the
+//! overrides say, and can be left out of the counts entirely (`count_loopback`).
+
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+use std::sync::{OnceLock, RwLock};
+
+/// User-defined subnet overrides (set at startup, replaced when the config is reloaded).
+/// Replaced lists are leaked so readers can keep a `&'static` slice: they're tiny and
+/// only replaced when the config file is edited
+static SUBNET_OVERRIDES: RwLock<&'static [SubnetOverride]> = RwLock::new(&[]);
+
+/// Whether loopback traffic is counted (set once at startup, counted if unset)
+static COUNT_LOOPBACK: OnceLock<bool> = OnceLock::new();
+
+/// Traffic category - Internet vs Local network
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrafficCategory {
+    /// Internet/WAN traffic (public IPs)
+    Internet,
+    /// Local/LAN traffic (private IPs, loopback, link-local)
+    Local,
+}
+
+/// An IPv4 or IPv6 network in CIDR notation (e.g. "10.8.0.0/24")
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cidr {
+    network: IpAddr,
+    prefix_len: u8,
+}
+
+impl Cidr {
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    pub fn is_ipv4(&self) -> bool {
+        self.network.is_ipv4()
+    }
+
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        if ip.is_ipv4() != self.is_ipv4() {
+            return false;
+        }
+        let (start, end) = self.range();
+        (start..=end).contains(&ip_to_u128(ip))
+    }
+
+    /// First and last address of the network (in its family's numeric space)
+    fn range(&self) -> (u128, u128) {
+        let bits = family_bits(self.is_ipv4());
+        let host_bits = bits - u32::from(self.prefix_len);
+        let host_mask = if host_bits == 128 {
+            u128::MAX
+        } else {
+            (1u128 << host_bits) - 1
+        };
+        let start = ip_to_u128(&self.network) & !host_mask;
+        (start, start | host_mask)
+    }
+}
+
+impl FromStr for Cidr {
+    type Err = anyhow::Error;
+
+    /// Parse "addr/len" (a bare address is a single-host network)
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let (addr, prefix_len) = match s.split_once('/') {
+            Some((addr, len)) => (addr, Some(len)),
+            None => (s, None),
+        };
+        let network: IpAddr = addr
+            .parse()
+            .map_err(|_| anyhow!("Invalid CIDR '{}': bad address", s))?;
+        let max_len = family_bits(network.is_ipv4()) as u8;
+        let prefix_len = match prefix_len {
+            Some(len) => len
+                .parse::<u8>()
+                .ok()
+                .filter(|len| *len <= max_len)
+                .ok_or_else(|| anyhow!("Invalid CIDR '{}': prefix must be 0-{}", s, max_len))?,
+            None => max_len,
+        };
+        Ok(Self {
+            network,
+            prefix_len,
+        })
+    }
+}
+
+impl fmt::Display for Cidr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.network, self.prefix_len)
+    }
+}
+
+/// A subnet whose traffic is always classified as the given category
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubnetOverride {
+    pub cidr: Cidr,
+    pub category: TrafficCategory,
+}
+
+/// Install the user's subnet overrides (at startup before monitoring, and again
+/// when the config is reloaded)
+pub fn init_subnet_overrides(overrides: Vec<SubnetOverride>) {
+    if !overrides.is_empty() {
+        log::info!("Using {} subnet override(s)", overrides.len());
+    }
+    let overrides = Box::leak(overrides.into_boxed_slice());
+    *SUBNET_OVERRIDES
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = overrides;
+}
+
+/// The installed subnet overrides (empty if none)
+pub fn subnet_overrides() -> &'static [SubnetOverride] {
+    *SUBNET_OVERRIDES
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Set whether loopback traffic is counted in rates and totals (call once at startup)
+pub fn init_loopback_counting(count: bool) {
+    if !count {
+        log::info!("Not counting loopback traffic");
+    }
+    COUNT_LOOPBACK.get_or_init(|| count);
+}
+
+/// Whether traffic with this remote address is counted at all (only loopback
+/// traffic can be left out)
+pub fn is_counted(remote_ip: &IpAddr) -> bool {
+    !is_loopback(remote_ip) || COUNT_LOOPBACK.get().copied().unwrap_or(true)
+}
+
+/// 127.0.0.0/8 and ::1, including IPv4-mapped loopback (::ffff:127.0.0.1)
+fn is_loopback(ip: &IpAddr) -> bool {
+    ip.to_canonical().is_loopback()
+}
+
+/// Category forced by the most specific matching override, if any
+fn override_category(ip: &IpAddr, overrides: &[SubnetOverride]) -> Option<TrafficCategory> {
+    overrides
+        .iter()
+        .filter(|o| o.cidr.contains(ip))
+        .max_by_key(|o| o.cidr.prefix_len())
+        .map(|o| o.category)
+}
+
+/// Determines if an IP address represents local/private network traffic
+///
+/// Local traffic includes:
+/// - IPv4: RFC 1918 private ranges, loopback, link-local, etc.
+/// - IPv6: Loopback, link-local, unique local addresses
+///
+/// User subnet overrides take precedence over these rules, except for loopback.
+pub fn is_local_traffic(ip: &IpAddr) -> bool {
+    is_local_traffic_with(ip, subnet_overrides())
+}
+
+fn is_local_traffic_with(ip: &IpAddr, overrides: &[SubnetOverride]) -> bool {
+    if is_loopback(ip) {
+        return true;
+    }
+    if let Some(category) = override_category(ip, overrides) {
+        return category == TrafficCategory::Local;
+    }
+    match ip {
+        IpAddr::V4(ipv4) => is_local_ipv4(ipv4),
+        IpAddr::V6(ipv6) => is_local_ipv6(ipv6),
+    }
+}
+
+fn is_local_ipv4(ip: &Ipv4Addr) -> bool {
+    ip.is_private()           // 10.0.0.0/8, 172.16.0.0/12, 192.168.0.0/16
+        || ip.is_loopback()   // 127.0.0.0/8
+        || ip.is_link_local() // 169.254.0.0/16
+        || ip.is_broadcast()  // 255.255.255.255
+        || ip.is_documentation() // Test networks
+        || ip.is_unspecified() // 0.0.0.0
+}
+
+fn is_local_ipv6(ip: &Ipv6Addr) -> bool {
+    ip.is_loopback()                  // ::1
+        || ip.is_unicast_link_local() // fe80::/10
+        || ip.is_unspecified()        // ::
+        || is_unique_local_ipv6(ip) // fc00::/7
+}
+
+/// Check if IPv6 address is in Unique Local Address range (fc00::/7)
+/// This is IPv6's equivalent to RFC 1918 private addresses
+fn is_unique_local_ipv6(ip: &Ipv6Addr) -> bool {
+    (ip.segments()[0] & 0xfe00) == 0xfc00
+}
+
+/// Build an address set from built-in networks with the overrides applied:
+/// networks overridden as local are added, networks overridden as internet removed
+/// (loopback networks in `base` stay, like in `is_local_traffic`).
+///
+/// Entries are CIDRs or inclusive "first-last" ranges (nftables set syntax). Without
+/// overrides for the family the base networks are returned unchanged.
+pub fn address_set_with_overrides(
+    base: &[&str],
+    ipv4: bool,
+    overrides: &[SubnetOverride],
+) -> Vec<String> {
+    let family_overrides: Vec<&SubnetOverride> = overrides
+        .iter()
+        .filter(|o| o.cidr.is_ipv4() == ipv4)
+        .collect();
+    if family_overrides.is_empty() {
+        return base.iter().map(|cidr| cidr.to_string()).collect();
+    }
+
+    let ranges: Vec<(u128, u128)> = base
+        .iter()
+        .filter_map(|cidr| cidr.parse::<Cidr>().ok())
+        .chain(
+            family_overrides
+                .iter()
+                .filter(|o| o.category == TrafficCategory::Local)
+                .map(|o| o.cidr),
+        )
+        .map(|cidr| cidr.range())
+        .collect();
+    let mut merged = merge_ranges(ranges);
+
+    // Cut out the networks overridden as internet
+    for removed in family_overrides
+        .iter()
+        .filter(|o| o.category == TrafficCategory::Internet)
+    {
+        let (cut_start, cut_end) = removed.cidr.range();
+        merged = merged
+            .into_iter()
+            .flat_map(|(start, end)| {
+                if end < cut_start || start > cut_end {
+                    return vec![(start, end)];
+                }
+                let mut pieces = Vec::new();
+                if start < cut_start {
+                    pieces.push((start, cut_start - 1));
+                }
+                if end > cut_end {
+                    pieces.push((cut_end + 1, end));
+                }
+                pieces
+            })
+            .collect();
+    }
+
+    // Overrides never make loopback internet traffic
+    merged.extend(
+        base.iter()
+            .filter_map(|cidr| cidr.parse::<Cidr>().ok())
+            .filter(|cidr| is_loopback(&cidr.network))
+            .map(|cidr| cidr.range()),
+    );
+
+    merge_ranges(merged)
+        .into_iter()
+        .map(|(start, end)| {
+            let (first, last) = (u128_to_ip(start, ipv4), u128_to_ip(end, ipv4));
+            if start == end {
+                first.to_string()
+            } else {
+                format!("{}-{}", first, last)
+            }
+        })
+        .collect()
+}
+
+/// Sort ranges and merge the overlapping/adjacent ones
+fn merge_ranges(mut ranges: Vec<(u128, u128)>) -> Vec<(u128, u128)> {
+    ranges.sort();
+    let mut merged: Vec<(u128, u128)> = Vec::new();
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1.saturating_add(1) => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+fn family_bits(ipv4: bool) -> u32 {
+    if ipv4 { 32 } else { 128 }
+}
+
+fn ip_to_u128(ip: &IpAddr) -> u128 {
+    match ip {
+        IpAddr::V4(ipv4) => u128::from(u32::from(*ipv4)),
+        IpAddr::V6(ipv6) => u128::from(*ipv6),
+    }
+}
+
+fn u128_to_ip(value: u128, ipv4: bool) -> IpAddr {
+    if ipv4 {
+        IpAddr::V4(Ipv4Addr::from(value as u32))
+    } else {
+        IpAddr::V6(Ipv6Addr::from(value))
+    }
+}
+
+/// Categorize traffic based on remote IP address
+///
+/// This is the main entry point for monitor backends to use.
+pub fn categorize_traffic(remote_ip: &IpAddr) -> TrafficCategory {
+    if is_local_traffic(remote_ip) {
+        TrafficCategory::Local
+    } else {
+        TrafficCategory::Internet
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ipv4_private() {
+        assert_eq!(
+            categorize_traffic(&"192.168.1.1".parse().unwrap()),
+            TrafficCategory::Local
+        );
+        assert_eq!(
+            categorize_traffic(&"10.0.0.1".parse().unwrap()),
+            TrafficCategory::Local
+        );
+        assert_eq!(
+            categorize_traffic(&"172.16.0.1".parse().unwrap()),
+            TrafficCategory::Local
+        );
+        assert_eq!(
+            categorize_traffic(&"172.31.255.255".parse().unwrap()),
+            TrafficCategory::Local
+        );
+    }
+
+    #[test]
+    fn test_ipv4_loopback() {
+        assert_eq!(
+            categorize_traffic(&"127.0.0.1".parse().unwrap()),
+            TrafficCategory::Local
+        );
+        assert_eq!(
+            categorize_traffic(&"127.255.255.255".parse().unwrap()),
+            TrafficCategory::Local
+        );
+    }
+
+    #[test]
+    fn test_ipv4_link_local() {
+        assert_eq!(
+            categorize_traffic(&"169.254.1.1".parse().unwrap()),
+            TrafficCategory::Local
+        );
+    }
+
+    #[test]
+    fn test_ipv4_internet() {
+        assert_eq!(
+            categorize_traffic(&"8.8.8.8".parse().unwrap()),
+            TrafficCategory::Internet
+        );
+        assert_eq!(
+            categorize_traffic(&"1.1.1.1".parse().unwrap()),
+            TrafficCategory::Internet
+        );
+        assert_eq!(
+            categorize_traffic(&"140.82.112.4".parse().unwrap()),
+            TrafficCategory::Internet
+        );
+    }
+
+    #[test]
+    fn test_ipv6_loopback() {
+        assert_eq!(
+            categorize_traffic(&"::1".parse().unwrap()),
+            TrafficCategory::Local
+        );
+    }
+
+    #[test]
+    fn test_ipv6_link_local() {
+        assert_eq!(
+            categorize_traffic(&"fe80::1".parse().unwrap()),
+            TrafficCategory::Local
+        );
+        assert_eq!(
+            categorize_traffic(&"fe80::1cd4:a0ff:fed4:aa2a".parse().unwrap()),
+            TrafficCategory::Local
+        );
+    }
+
+    #[test]
+    fn test_ipv6_unique_local() {
+        assert_eq!(
+            categorize_traffic(&"fc00::1".parse().unwrap()),
+            TrafficCategory::Local
+        );
+        assert_eq!(
+            categorize_traffic(&"fd00::1".parse().unwrap()),
+            TrafficCategory::Local
+        );
+    }
+
+    #[test]
+    fn test_subnet_overrides() {
+        let overrides = vec![
+            SubnetOverride {
+                cidr: "10.8.0.0/24".parse().unwrap(),
+                category: TrafficCategory::Internet,
+            },
+            SubnetOverride {
+                cidr: "10.8.0.5".parse().unwrap(),
+                category: TrafficCategory::Local,
+            },
+            SubnetOverride {
+                cidr: "100.64.0.0/10".parse().unwrap(),
+                category: TrafficCategory::Local,
+            },
+        ];
+        let local = |ip: &str| is_local_traffic_with(&ip.parse().unwrap(), &overrides);
+
+        assert!(!local("10.8.0.1")); // VPN range treated as internet
+        assert!(local("10.8.0.5")); // Most specific override wins
+        assert!(local("10.9.0.1")); // Rest of 10/8 is still local
+        assert!(local("100.100.1.1")); // Added local range
+        assert!(!local("8.8.8.8"));
+
+        // Rule generation matches the classifier
+        assert_eq!(
+            address_set_with_overrides(&["10.0.0.0/8", "192.168.0.0/16"], true, &overrides[..1]),
+            vec![
+                "10.0.0.0-10.7.255.255",
+                "10.8.1.0-10.255.255.255",
+                "192.168.0.0-192.168.255.255"
+            ]
+        );
+        assert_eq!(
+            address_set_with_overrides(&["fc00::/7"], false, &overrides),
+            vec!["fc00::/7"]
+        );
+
+        assert!("10.0.0.0/33".parse::<Cidr>().is_err());
+        assert!("not-an-ip/8".parse::<Cidr>().is_err());
+    }
+
+    #[test]
+    fn test_loopback_ignores_internet_overrides() {
+        let overrides = vec![
+            SubnetOverride {
+                cidr: "0.0.0.0/0".parse().unwrap(),
+                category: TrafficCategory::Internet,
+            },
+            SubnetOverride {
+                cidr: "::/0".parse().unwrap(),
+                category: TrafficCategory::Internet,
+            },
+        ];
+        let local = |ip: &str| is_local_traffic_with(&ip.parse().unwrap(), &overrides);
+
+        assert!(local("127.0.0.1"));
+        assert!(local("::1"));
+        assert!(local("::ffff:127.0.0.1"));
+        assert!(!local("192.168.1.1"));
+
+        assert_eq!(
+            address_set_with_overrides(&["127.0.0.0/8", "10.0.0.0/8"], true, &overrides),
+            vec!["127.0.0.0-127.255.255.255"]
+        );
+        assert_eq!(
+            address_set_with_overrides(&["::1/128", "fc00::/7"], false, &overrides),
+            vec!["::1"]
+        );
+    }
+
+    #[test]
+    fn test_ipv6_internet() {
+        assert_eq!(
+            categorize_traffic(&"2001:4860:4860::8888".parse().unwrap()),
+            TrafficCategory::Internet
+        );
+        assert_eq!(
+            categorize_traffic(&"2606:4700:4700::1111".parse().unwrap()),
+            TrafficCategory::Internet
+        );
+    }
+}
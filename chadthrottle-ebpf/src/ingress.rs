@@ -11,11 +11,12 @@ use aya_ebpf::{
     bindings::BPF_F_NO_PREALLOC,
     helpers::bpf_ktime_get_ns,
     macros::{cgroup_skb, map},
-    maps::HashMap,
+    maps::{Array, HashMap, PerCpuHashMap},
     programs::SkBuffContext,
 };
 use chadthrottle_common::{
-    CgroupThrottleConfig, ThrottleStats, TokenBucket, TRAFFIC_TYPE_ALL, TRAFFIC_TYPE_INTERNET,
+    CgroupThrottleConfig, PinMetadata, ThrottleStats, TokenBucket, ADDRESS_FAMILY_V4,
+    ADDRESS_FAMILY_V6, ETH_P_IP, ETH_P_IPV6, TRAFFIC_TYPE_ALL, TRAFFIC_TYPE_INTERNET,
     TRAFFIC_TYPE_LOCAL,
 };
 
@@ -29,6 +30,18 @@ const MAX_CGROUPS: u32 = 4096;
 /// the wrong cgroup ID in softirq context.
 const THROTTLE_KEY: u64 = 0;
 
+/// Check the packet's IP version (from its EtherType) against the throttle's
+/// address family
+#[inline(always)]
+fn matches_address_family(ctx: &SkBuffContext, address_family: u8) -> bool {
+    let protocol = u16::from_be(ctx.skb.protocol() as u16);
+    match address_family {
+        ADDRESS_FAMILY_V4 => protocol == ETH_P_IP,
+        ADDRESS_FAMILY_V6 => protocol == ETH_P_IPV6,
+        _ => true,
+    }
+}
+
 /// Check if packet should be throttled based on traffic type filtering
 /// Returns true if packet should be throttled, false if it should be allowed
 ///
@@ -228,82 +241,62 @@ fn is_ipv6_local(ip: &[u8; 16]) -> bool {
     false
 }
 
-/// Map: cgroup_id -> TokenBucket
-/// Stores token bucket state for each throttled cgroup
+/// Map: cgroup_id -> TokenBucket (one per CPU)
+/// Stores token bucket state for each throttled cgroup. Each CPU only touches its
+/// own bucket, so packets handled concurrently can't race on the same tokens.
 #[map]
-static CGROUP_BUCKETS: HashMap<u64, TokenBucket> =
-    HashMap::with_max_entries(MAX_CGROUPS, BPF_F_NO_PREALLOC);
+static CGROUP_BUCKETS: PerCpuHashMap<u64, TokenBucket> =
+    PerCpuHashMap::pinned(MAX_CGROUPS, BPF_F_NO_PREALLOC);
 
 /// Map: cgroup_id -> CgroupThrottleConfig
 /// Stores configuration for each throttled cgroup
 #[map]
 static CGROUP_CONFIGS: HashMap<u64, CgroupThrottleConfig> =
-    HashMap::with_max_entries(MAX_CGROUPS, BPF_F_NO_PREALLOC);
+    HashMap::pinned(MAX_CGROUPS, BPF_F_NO_PREALLOC);
 
-/// Map: cgroup_id -> ThrottleStats
+/// Map: cgroup_id -> ThrottleStats (one per CPU, summed by userspace)
 /// Stores statistics for each throttled cgroup
 #[map]
-static CGROUP_STATS: HashMap<u64, ThrottleStats> =
-    HashMap::with_max_entries(MAX_CGROUPS, BPF_F_NO_PREALLOC);
+static CGROUP_STATS: PerCpuHashMap<u64, ThrottleStats> =
+    PerCpuHashMap::pinned(MAX_CGROUPS, BPF_F_NO_PREALLOC);
 
-/// Token bucket algorithm implementation
-/// Returns true if packet should be allowed, false if dropped
+/// Owner (version + boot id) of the pinned maps, written by userspace
+/// Maps are pinned by name so a restarted ChadThrottle adopts them
+#[map]
+static PIN_METADATA: Array<PinMetadata> = Array::pinned(1, 0);
+
+/// This CPU's value for the fixed key, inserting `initial` if the entry doesn't exist
+///
+/// An entry inserted from one CPU starts out zeroed on every other CPU.
 #[inline(always)]
-fn token_bucket_allow(bucket: &mut TokenBucket, packet_size: u64, now_ns: u64) -> bool {
-    // Handle first-time initialization when last_update_ns = 0
-    // This avoids clock mismatch between userspace (wall clock) and kernel (monotonic clock)
-    if bucket.last_update_ns == 0 {
-        bucket.last_update_ns = now_ns;
-        // First packet - allow if we have enough tokens (initial burst)
-        if bucket.tokens >= packet_size {
-            bucket.tokens = bucket.tokens.saturating_sub(packet_size);
-            return true;
-        } else {
-            return false;
-        }
+fn cpu_entry<T>(map: &PerCpuHashMap<u64, T>, initial: &T) -> Result<*mut T, i64> {
+    if let Some(value) = map.get_ptr_mut(&THROTTLE_KEY) {
+        return Ok(value);
     }
+    map.insert(&THROTTLE_KEY, initial, 0)?;
+    map.get_ptr_mut(&THROTTLE_KEY).ok_or(0)
+}
 
-    // Calculate time elapsed since last update in nanoseconds
-    let elapsed_ns = now_ns.saturating_sub(bucket.last_update_ns);
-
-    // Calculate tokens to add: (elapsed_ns * rate_bps) / 1_000_000_000
-    // eBPF doesn't support 128-bit math, so we must be very careful
-    // We'll do the calculation in a way that avoids large intermediate values
-
-    // Convert elapsed time to seconds (as a small fraction)
-    // elapsed_ns / 1_000_000_000 would give us seconds, but we need to keep precision
-    // So we'll work with elapsed_ns / 1000 (microseconds) and rate_bps / 1000 (KB/s)
-
-    let elapsed_us = elapsed_ns / 1000; // microseconds elapsed
-
-    // Now calculate: (elapsed_us * rate_bps) / 1_000_000
-    // But even this can overflow for large values
-    // So we'll divide first if possible
-    let tokens_to_add = if elapsed_us < 1_000_000 {
-        // Short time period - safe to multiply first
-        let product = elapsed_us.wrapping_mul(bucket.rate_bps);
-        product / 1_000_000
+/// Initial bucket for a CPU userspace didn't set one up for (e.g. one brought online
+/// after the throttle started): an even share of the rate and burst, until userspace
+/// rebalances the shares by where the traffic is
+///
+/// It starts empty and earns its tokens at its share of the rate: userspace already
+/// handed out the throttle's whole burst across the other CPUs.
+#[inline(always)]
+fn cpu_bucket(config: &CgroupThrottleConfig, now_ns: u64) -> TokenBucket {
+    let cpu_count = if config.cpu_count == 0 {
+        1
     } else {
-        // Long time period - divide elapsed_us first
-        let seconds = elapsed_us / 1_000_000;
-        seconds.wrapping_mul(bucket.rate_bps)
+        config.cpu_count as u64
     };
-
-    // Add new tokens, capped at capacity
-    bucket.tokens = bucket.tokens.saturating_add(tokens_to_add);
-    if bucket.tokens > bucket.capacity {
-        bucket.tokens = bucket.capacity;
-    }
-
-    // Update last update time
-    bucket.last_update_ns = now_ns;
-
-    // Check if we have enough tokens
-    if bucket.tokens >= packet_size {
-        bucket.tokens = bucket.tokens.saturating_sub(packet_size);
-        true
-    } else {
-        false
+    let rate_bps = config.rate_bps / cpu_count;
+    TokenBucket {
+        capacity: config.burst_size / cpu_count,
+        tokens: 0,
+        last_update_ns: now_ns,
+        rate_bps: if rate_bps == 0 { 1 } else { rate_bps },
+        next_departure_ns: 0,
     }
 }
 
@@ -328,11 +321,9 @@ fn try_chadthrottle_ingress(ctx: SkBuffContext) -> Result<i32, i64> {
     // fixed key and userspace inserts the config with this same key.
     const KEY: u64 = THROTTLE_KEY;
 
-    // Get or create statistics (need to track program calls even if not throttled)
-    let mut stats = match unsafe { CGROUP_STATS.get(&KEY) } {
-        Some(s) => *s,
-        None => ThrottleStats::new(),
-    };
+    // Get or create this CPU's statistics (need to track program calls even if not throttled)
+    // Per-CPU values are updated in place: no other CPU writes to them
+    let stats = unsafe { &mut *cpu_entry(&CGROUP_STATS, &ThrottleStats::new())? };
 
     // Update diagnostic fields
     stats.program_calls = stats.program_calls.saturating_add(1);
@@ -343,64 +334,51 @@ fn try_chadthrottle_ingress(ctx: SkBuffContext) -> Result<i32, i64> {
         None => {
             // Not throttled - increment config miss counter and allow
             stats.config_misses = stats.config_misses.saturating_add(1);
-            unsafe {
-                CGROUP_STATS.insert(&KEY, &stats, 0)?;
-            }
             return Ok(1); // Allow
         }
     };
 
-    // Check if we should throttle this packet based on traffic type
-    if !should_throttle_packet(&ctx, config.traffic_type) {
+    // Check if we should throttle this packet based on IP version and traffic type
+    if !matches_address_family(&ctx, config.address_family)
+        || !should_throttle_packet(&ctx, config.traffic_type)
+    {
         // This traffic type should not be throttled - allow
+        stats.passed_filter_mismatch = stats.passed_filter_mismatch.saturating_add(1);
         return Ok(1);
     }
 
     // Get packet size
     let packet_size = ctx.len() as u64;
 
-    // Get or create token bucket
-    let mut bucket = match unsafe { CGROUP_BUCKETS.get(&KEY) } {
-        Some(b) => *b,
-        None => {
-            // Initialize new bucket
-            let now_ns = unsafe { bpf_ktime_get_ns() };
-            TokenBucket {
-                capacity: config.burst_size,
-                tokens: config.burst_size, // Start with full bucket
-                last_update_ns: now_ns,
-                rate_bps: config.rate_bps,
-            }
-        }
-    };
-
     // Get current time
     let now_ns = unsafe { bpf_ktime_get_ns() };
 
-    // Apply token bucket algorithm
-    let allow = token_bucket_allow(&mut bucket, packet_size, now_ns);
-
-    // Update bucket in map
-    unsafe {
-        CGROUP_BUCKETS.insert(&KEY, &bucket, 0)?;
+    // Get or create this CPU's token bucket
+    let bucket = unsafe { &mut *cpu_entry(&CGROUP_BUCKETS, &TokenBucket::new())? };
+    if bucket.rate_bps == 0 {
+        // New entry (or one created from another CPU): start with this CPU's share
+        *bucket = cpu_bucket(config, now_ns);
     }
 
+    // Apply token bucket algorithm
+    bucket.refill(now_ns);
+    let allow = bucket.try_consume(packet_size);
+
     // Update traffic statistics
     stats.packets_total = stats.packets_total.saturating_add(1);
     stats.bytes_total = stats.bytes_total.saturating_add(packet_size);
 
-    if !allow {
+    if allow {
+        stats.passed_with_tokens = stats.passed_with_tokens.saturating_add(1);
+    } else {
         stats.packets_dropped = stats.packets_dropped.saturating_add(1);
         stats.bytes_dropped = stats.bytes_dropped.saturating_add(packet_size);
+        stats.dropped_no_tokens = stats.dropped_no_tokens.saturating_add(1);
     }
 
     // Store the actual cgroup ID from config for diagnostics
     stats.cgroup_id_seen = config.cgroup_id;
 
-    unsafe {
-        CGROUP_STATS.insert(&KEY, &stats, 0)?;
-    }
-
     // Return verdict: 1 = allow, 0 = drop
     Ok(if allow { 1 } else { 0 })
 }
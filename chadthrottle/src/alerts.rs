@@ -0,0 +1,187 @@
+// Bandwidth threshold alerts (`alert_threshold` / `alert_command` in the config)
+//
+// An alert fires when a process's download or upload rate rises above the
+// threshold. To avoid spam, a process must drop back below the threshold before it
+// can fire again, and never fires more than once per cooldown period.
+
+use crate::process::ProcessMap;
+use std::collections::{HashMap, HashSet};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// A process that crossed the alert threshold
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlertEvent {
+    pub pid: i32,
+    pub name: String,
+    /// "download" or "upload", whichever is higher
+    pub direction: &'static str,
+    /// Rate that crossed the threshold (bytes/sec)
+    pub rate: u64,
+}
+
+/// Watches process rates and raises debounced threshold alerts
+#[derive(Debug)]
+pub struct BandwidthAlerter {
+    threshold: u64,
+    command: Option<String>,
+    cooldown: Duration,
+    // Processes currently above the threshold (alerts fire on the rising edge)
+    over_threshold: HashSet<i32>,
+    last_fired: HashMap<i32, Instant>,
+}
+
+impl BandwidthAlerter {
+    pub fn new(threshold_bytes_per_sec: u64, command: Option<String>, cooldown: Duration) -> Self {
+        Self {
+            threshold: threshold_bytes_per_sec,
+            command: command.filter(|c| !c.trim().is_empty()),
+            cooldown,
+            over_threshold: HashSet::new(),
+            last_fired: HashMap::new(),
+        }
+    }
+
+    pub fn threshold(&self) -> u64 {
+        self.threshold
+    }
+
+    /// Check the latest rates, run the alert command for new alerts and return them
+    pub fn check(&mut self, processes: &ProcessMap) -> Vec<AlertEvent> {
+        let events = self.check_at(processes, Instant::now());
+        for event in &events {
+            self.run_command(event);
+        }
+        events
+    }
+
+    fn check_at(&mut self, processes: &ProcessMap, now: Instant) -> Vec<AlertEvent> {
+        // Forget processes that are gone so reused PIDs start fresh
+        self.over_threshold
+            .retain(|pid| processes.contains_key(pid));
+        self.last_fired.retain(|pid, fired| {
+            processes.contains_key(pid) || now.saturating_duration_since(*fired) < self.cooldown
+        });
+
+        let mut events = Vec::new();
+        for (pid, process) in processes {
+            let (direction, rate) = if process.download_rate >= process.upload_rate {
+                ("download", process.download_rate)
+            } else {
+                ("upload", process.upload_rate)
+            };
+
+            if rate <= self.threshold {
+                self.over_threshold.remove(pid);
+                continue;
+            }
+            if !self.over_threshold.insert(*pid) {
+                continue; // Still in the same burst
+            }
+
+            let cooled_down = self
+                .last_fired
+                .get(pid)
+                .is_none_or(|fired| now.saturating_duration_since(*fired) >= self.cooldown);
+            if cooled_down {
+                self.last_fired.insert(*pid, now);
+                events.push(AlertEvent {
+                    pid: *pid,
+                    name: process.name.clone(),
+                    direction,
+                    rate,
+                });
+            }
+        }
+
+        events.sort_by_key(|event| std::cmp::Reverse(event.rate));
+        events
+    }
+
+    /// Run the user's alert command in the background
+    ///
+    /// The command runs through the shell with pid, name, rate (bytes/sec) and
+    /// direction as positional arguments ($1..$4) and CHADTHROTTLE_* variables.
+    fn run_command(&self, event: &AlertEvent) {
+        let Some(command) = &self.command else {
+            return;
+        };
+
+        #[cfg(unix)]
+        let mut cmd = {
+            let mut cmd = Command::new("sh");
+            cmd.arg("-c").arg(command).arg("chadthrottle-alert");
+            cmd
+        };
+        #[cfg(windows)]
+        let mut cmd = {
+            let mut cmd = Command::new("cmd");
+            cmd.arg("/C").arg(command);
+            cmd
+        };
+
+        let spawned = cmd
+            .arg(event.pid.to_string())
+            .arg(&event.name)
+            .arg(event.rate.to_string())
+            .arg(event.direction)
+            .env("CHADTHROTTLE_PID", event.pid.to_string())
+            .env("CHADTHROTTLE_NAME", &event.name)
+            .env("CHADTHROTTLE_RATE", event.rate.to_string())
+            .env("CHADTHROTTLE_DIRECTION", event.direction)
+            .env("CHADTHROTTLE_THRESHOLD", self.threshold.to_string())
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+
+        match spawned {
+            // Reap the child without blocking the UI loop
+            Ok(mut child) => {
+                std::thread::spawn(move || {
+                    let _ = child.wait();
+                });
+            }
+            Err(e) => log::warn!("Failed to run alert command '{}': {}", command, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::ProcessInfo;
+
+    fn processes(rates: &[(i32, u64)]) -> ProcessMap {
+        rates
+            .iter()
+            .map(|(pid, rate)| {
+                let mut info = ProcessInfo::new(*pid, format!("proc{}", pid));
+                info.download_rate = *rate;
+                (*pid, info)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_alerts_are_debounced() {
+        let mut alerter = BandwidthAlerter::new(1000, None, Duration::from_secs(60));
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        let fired = alerter.check_at(&processes(&[(1, 5000), (2, 10)]), at(0));
+        assert_eq!(fired.len(), 1);
+        assert_eq!((fired[0].pid, fired[0].direction), (1, "download"));
+
+        // Same burst: no repeat
+        assert!(alerter.check_at(&processes(&[(1, 6000)]), at(1)).is_empty());
+
+        // Dropped and crossed again within the cooldown: still quiet
+        assert!(alerter.check_at(&processes(&[(1, 500)]), at(2)).is_empty());
+        assert!(alerter.check_at(&processes(&[(1, 5000)]), at(3)).is_empty());
+
+        // A new crossing after the cooldown fires again
+        assert!(alerter.check_at(&processes(&[(1, 500)]), at(61)).is_empty());
+        assert_eq!(alerter.check_at(&processes(&[(1, 5000)]), at(62)).len(), 1);
+    }
+}
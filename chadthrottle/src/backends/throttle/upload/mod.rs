@@ -1,7 +0,0 @@
-// Upload throttling backend implementations
-
-#[cfg(target_os = "linux")]
-pub mod linux;
-
-#[cfg(target_os = "macos")]
-pub mod macos;
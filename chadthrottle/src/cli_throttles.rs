@@ -0,0 +1,146 @@
+// Throttles held by running CLI-mode instances
+//
+// Backends keep a throttle's kernel handles (tc classes, cgroups, BPF map entries) in
+// memory, so a CLI-mode throttle is removed most cleanly by the process that applied
+// it. Each instance records its throttles here, and `--remove` asks the owner to
+// remove them and exit before removing what's left through the backends. The records
+// of an instance that died keep the limits the backends need to find its leftovers.
+
+use crate::backends::process::ProcessUtils;
+use crate::config::SavedThrottle;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Directory next to the config file holding one record per instance and throttled PID
+const REGISTRY_DIR: &str = "cli-throttles";
+
+/// How long `wait_removed` waits for the owner to finish removing a throttle
+const REMOVAL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A throttle applied by a running `chadthrottle --pid` instance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CliThrottle {
+    pub pid: i32,
+    pub owner_pid: i32, // chadthrottle instance holding the throttle
+    pub throttle: SavedThrottle,
+}
+
+impl CliThrottle {
+    /// Record that this process holds `throttle` on `pid`
+    pub fn register(pid: i32, throttle: SavedThrottle) -> Result<Self> {
+        let entry = Self {
+            pid,
+            owner_pid: std::process::id() as i32,
+            throttle,
+        };
+        let path = entry.path()?;
+        fs::write(&path, serde_json::to_string(&entry)?)
+            .context(format!("Failed to write {:?}", path))?;
+        Ok(entry)
+    }
+
+    /// Drop the record once the throttle is removed
+    pub fn unregister(&self) {
+        if let Ok(path) = self.path() {
+            let _ = fs::remove_file(path);
+        }
+    }
+
+    /// Throttles held by CLI instances that are still running (records of exited
+    /// instances are cleaned up), sorted by PID
+    pub fn list(process_utils: &dyn ProcessUtils) -> Vec<Self> {
+        Self::partition(process_utils).0
+    }
+
+    /// Throttles of CLI instances that exited without removing them (e.g. killed),
+    /// sorted by PID. Their records are cleaned up
+    pub fn take_stale(process_utils: &dyn ProcessUtils) -> Vec<Self> {
+        Self::partition(process_utils).1
+    }
+
+    /// Records of running and of exited instances. Unreadable and exited instances'
+    /// records are deleted
+    fn partition(process_utils: &dyn ProcessUtils) -> (Vec<Self>, Vec<Self>) {
+        let Ok(entries) = registry_dir().and_then(|dir| Ok(fs::read_dir(dir)?)) else {
+            return (Vec::new(), Vec::new());
+        };
+
+        let (mut running, mut stale) = (Vec::new(), Vec::new());
+        for path in entries.flatten().map(|entry| entry.path()) {
+            let record = fs::read_to_string(&path)
+                .ok()
+                .and_then(|contents| serde_json::from_str::<Self>(&contents).ok());
+            match record {
+                Some(record) if is_running_instance(process_utils, record.owner_pid) => {
+                    running.push(record)
+                }
+                record => {
+                    log::debug!("Removing stale CLI throttle record {:?}", path);
+                    let _ = fs::remove_file(&path);
+                    stale.extend(record);
+                }
+            }
+        }
+        running.sort_by_key(|t| t.pid);
+        stale.sort_by_key(|t| t.pid);
+        (running, stale)
+    }
+
+    /// Ask the owning instance to remove the throttle and exit
+    pub fn request_removal(&self) -> Result<()> {
+        #[cfg(unix)]
+        {
+            if unsafe { libc::kill(self.owner_pid, libc::SIGTERM) } != 0 {
+                return Err(std::io::Error::last_os_error()).context(format!(
+                    "Failed to signal chadthrottle (PID {})",
+                    self.owner_pid
+                ));
+            }
+            Ok(())
+        }
+
+        #[cfg(not(unix))]
+        anyhow::bail!(
+            "Stop the chadthrottle instance holding this throttle (PID {}) with Ctrl+C",
+            self.owner_pid
+        )
+    }
+
+    /// Wait until the owner has removed the throttle (and its record)
+    pub fn wait_removed(&self) -> bool {
+        let Ok(path) = self.path() else {
+            return false;
+        };
+        let start = Instant::now();
+        while path.exists() {
+            if start.elapsed() > REMOVAL_TIMEOUT {
+                return false;
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        true
+    }
+
+    /// Keyed by owner and PID, so instances throttling the same PID keep their own records
+    fn path(&self) -> Result<PathBuf> {
+        Ok(registry_dir()?.join(format!("{}-{}.json", self.owner_pid, self.pid)))
+    }
+}
+
+/// Whether `pid` is a running chadthrottle (the PID of an instance that exited may
+/// have been reused by an unrelated process)
+pub fn is_running_instance(process_utils: &dyn ProcessUtils, pid: i32) -> bool {
+    let own_name = process_utils
+        .get_process_name(std::process::id() as i32)
+        .ok();
+    process_utils.process_exists(pid) && process_utils.get_process_name(pid).ok() == own_name
+}
+
+fn registry_dir() -> Result<PathBuf> {
+    let dir = crate::config::Config::config_path()?.with_file_name(REGISTRY_DIR);
+    fs::create_dir_all(&dir).context(format!("Failed to create {:?}", dir))?;
+    Ok(dir)
+}
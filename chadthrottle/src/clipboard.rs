@@ -0,0 +1,241 @@
+// Copying text to the system clipboard from the TUI
+//
+// The OSC 52 escape sequence asks the terminal itself to set the clipboard, which
+// also works over SSH (and inside tmux, passed through to the outer terminal).
+// Terminals known not to support it (the Linux console, VTE-based ones) fall back
+// to a local clipboard tool: wl-copy, xclip or pbcopy, whichever can be used.
+
+use anyhow::{Context, Result, anyhow};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Terminals drop larger OSC 52 payloads (xterm's limit is about 100 KB of base64)
+const OSC52_MAX_BYTES: usize = 74_994;
+
+/// Clipboard tools that read the text from stdin: (program, arguments, environment
+/// variable that must be set for it to reach a clipboard)
+const TOOLS: [(&str, &[&str], Option<&str>); 3] = [
+    ("wl-copy", &[], Some("WAYLAND_DISPLAY")),
+    ("xclip", &["-selection", "clipboard"], Some("DISPLAY")),
+    ("pbcopy", &[], None),
+];
+
+/// A way of reaching the clipboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClipboardMethod {
+    /// OSC 52 written to the terminal (wrapped for passthrough inside tmux)
+    Osc52 { tmux: bool },
+    /// A clipboard tool from `TOOLS`
+    Tool(&'static str),
+}
+
+impl ClipboardMethod {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ClipboardMethod::Osc52 { .. } => "OSC 52",
+            ClipboardMethod::Tool(program) => program,
+        }
+    }
+}
+
+/// The clipboard methods available here, in the order they are tried
+pub fn detect() -> Vec<ClipboardMethod> {
+    detect_with(|name| std::env::var(name).ok(), is_installed)
+}
+
+fn detect_with(
+    env: impl Fn(&str) -> Option<String>,
+    installed: impl Fn(&str) -> bool,
+) -> Vec<ClipboardMethod> {
+    let mut methods = Vec::new();
+
+    // The Linux console and dumb terminals ignore OSC 52, and VTE (GNOME Terminal,
+    // Tilix, ...) doesn't implement it
+    let term = env("TERM").unwrap_or_default();
+    if !term.is_empty() && term != "dumb" && term != "linux" && env("VTE_VERSION").is_none() {
+        methods.push(ClipboardMethod::Osc52 {
+            tmux: env("TMUX").is_some(),
+        });
+    }
+
+    for (program, _, needs) in TOOLS {
+        if program == "pbcopy" && !cfg!(target_os = "macos") {
+            continue;
+        }
+        if needs.is_none_or(|name| env(name).is_some()) && installed(program) {
+            methods.push(ClipboardMethod::Tool(program));
+        }
+    }
+    methods
+}
+
+/// Whether `program` is an executable file on PATH
+fn is_installed(program: &str) -> bool {
+    std::env::var_os("PATH")
+        .is_some_and(|path| std::env::split_paths(&path).any(|dir| dir.join(program).is_file()))
+}
+
+/// Copy `text` with the first method that works, returning the one used
+pub fn copy(text: &str) -> Result<ClipboardMethod> {
+    let methods = detect();
+    if methods.is_empty() {
+        return Err(anyhow!(
+            "no clipboard: the terminal doesn't support OSC 52 and none of wl-copy, xclip \
+             or pbcopy can be used"
+        ));
+    }
+
+    let mut last_error = None;
+    for method in methods {
+        let result = match method {
+            ClipboardMethod::Osc52 { tmux } => write_osc52(text, tmux),
+            ClipboardMethod::Tool(program) => run_tool(program, text),
+        };
+        match result {
+            Ok(()) => return Ok(method),
+            Err(e) => {
+                log::debug!("Clipboard via {} failed: {:#}", method.label(), e);
+                last_error = Some(e);
+            }
+        }
+    }
+    Err(last_error.unwrap())
+}
+
+fn write_osc52(text: &str, tmux: bool) -> Result<()> {
+    if text.len() > OSC52_MAX_BYTES {
+        return Err(anyhow!(
+            "{} bytes is too much for OSC 52 (at most {})",
+            text.len(),
+            OSC52_MAX_BYTES
+        ));
+    }
+    let mut stdout = std::io::stdout().lock();
+    stdout
+        .write_all(osc52_sequence(text, tmux).as_bytes())
+        .and_then(|()| stdout.flush())
+        .context("writing OSC 52 to the terminal")
+}
+
+/// The OSC 52 sequence setting the clipboard to `text`; inside tmux it's wrapped in
+/// a DCS passthrough (with its ESCs doubled) so it reaches the outer terminal
+fn osc52_sequence(text: &str, tmux: bool) -> String {
+    let sequence = format!("\x1b]52;c;{}\x07", base64(text.as_bytes()));
+    if tmux {
+        format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"))
+    } else {
+        sequence
+    }
+}
+
+fn run_tool(program: &str, text: &str) -> Result<()> {
+    let (_, args, _) = TOOLS
+        .iter()
+        .find(|(name, _, _)| *name == program)
+        .context("unknown clipboard tool")?;
+    let mut child = Command::new(program)
+        .args(*args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("running {}", program))?;
+    // Dropping stdin closes it, which tells the tool the text is complete
+    child
+        .stdin
+        .take()
+        .context("no stdin")?
+        .write_all(text.as_bytes())
+        .with_context(|| format!("writing to {}", program))?;
+    let status = child.wait()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(anyhow!("{} exited with {}", program, status))
+    }
+}
+
+/// Standard base64 (with padding)
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &byte)| n | ((byte as u32) << (16 - 8 * i)));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[((n >> (18 - 6 * i)) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn detect_in(vars: &[(&str, &str)], installed: &[&str]) -> Vec<ClipboardMethod> {
+        let vars: HashMap<&str, &str> = vars.iter().copied().collect();
+        detect_with(
+            |name| vars.get(name).map(|value| value.to_string()),
+            |program| installed.contains(&program),
+        )
+    }
+
+    #[test]
+    fn test_base64() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"1234\tcurl"), "MTIzNAljdXJs");
+    }
+
+    #[test]
+    fn test_osc52_sequence() {
+        assert_eq!(osc52_sequence("foo", false), "\x1b]52;c;Zm9v\x07");
+        assert_eq!(
+            osc52_sequence("foo", true),
+            "\x1bPtmux;\x1b\x1b]52;c;Zm9v\x07\x1b\\"
+        );
+    }
+
+    #[test]
+    fn test_detect() {
+        // Over SSH only the terminal can reach the user's clipboard
+        assert_eq!(
+            detect_in(&[("TERM", "xterm-256color")], &["wl-copy", "xclip"]),
+            vec![ClipboardMethod::Osc52 { tmux: false }]
+        );
+        assert_eq!(
+            detect_in(
+                &[("TERM", "tmux-256color"), ("TMUX", "/tmp/tmux-0/default")],
+                &[]
+            ),
+            vec![ClipboardMethod::Osc52 { tmux: true }]
+        );
+
+        // GNOME Terminal has no OSC 52: the first installed tool with a display it needs
+        assert_eq!(
+            detect_in(
+                &[
+                    ("TERM", "xterm-256color"),
+                    ("VTE_VERSION", "7600"),
+                    ("WAYLAND_DISPLAY", "wayland-0"),
+                    ("DISPLAY", ":0"),
+                ],
+                &["xclip"],
+            ),
+            vec![ClipboardMethod::Tool("xclip")]
+        );
+
+        // The Linux console with nothing installed
+        assert!(detect_in(&[("TERM", "linux")], &["wl-copy"]).is_empty());
+    }
+}
@@ -0,0 +1,255 @@
+// Reloading the config file when it's edited while the TUI runs
+//
+// The file's modification time is polled every couple of seconds (cheap, and works
+// the same everywhere). A changed file is read as strictly as an import and compared
+// with the running config; the caller applies what can change live. Saves of our
+// own are re-read too, but match the running config so nothing changes.
+
+use crate::config::Config;
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// How often the config file's modification time is checked
+pub const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Settings only read at startup, by config key (changing them needs a restart)
+const RESTART_ONLY: [&str; 10] = [
+    "preferred_monitor_backend",
+    "preferred_socket_mapper",
+    "capture_interfaces",
+    "interface_ceilings",
+    "history_samples",
+    "persist_history",
+    "count_loopback",
+    "ebpf_map_capacity",
+    "connection_scan_interval_secs",
+    "watch_new_processes",
+];
+
+/// Watches the config file for edits
+#[derive(Debug)]
+pub struct ConfigWatcher {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    last_poll: Instant,
+}
+
+impl ConfigWatcher {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            modified: modified_time(&path),
+            path,
+            last_poll: Instant::now(),
+        }
+    }
+
+    /// The file's new contents if it changed since the last poll (None when it didn't,
+    /// was removed, or `POLL_INTERVAL` hasn't passed). An edit that doesn't parse is
+    /// returned as an error, and isn't read again until the file changes again
+    pub fn poll(&mut self) -> Option<Result<Config>> {
+        if self.last_poll.elapsed() < POLL_INTERVAL {
+            return None;
+        }
+        self.last_poll = Instant::now();
+
+        let modified = modified_time(&self.path);
+        if modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+        modified?;
+        Some(Config::read_import(&self.path))
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// A setting that differs between the running config and the reloaded one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigChange {
+    Theme,
+    Keybindings,
+    SubnetOverrides,
+    Quotas,
+    StandingRules,
+    Alerts,
+    Webhooks,
+    PreferredUploadBackend(Option<String>),
+    PreferredDownloadBackend(Option<String>),
+    /// Saved throttles (the running throttles win, these are ignored)
+    Throttles,
+    /// A setting only read at startup, by config key
+    NeedsRestart(&'static str),
+}
+
+impl ConfigChange {
+    /// What changed, for the status bar
+    pub fn label(&self) -> &'static str {
+        match self {
+            ConfigChange::Theme => "theme",
+            ConfigChange::Keybindings => "keybindings",
+            ConfigChange::SubnetOverrides => "subnet overrides",
+            ConfigChange::Quotas => "quotas",
+            ConfigChange::StandingRules => "standing rules",
+            ConfigChange::Alerts => "alerts",
+            ConfigChange::Webhooks => "webhooks",
+            ConfigChange::PreferredUploadBackend(_) => "preferred upload backend",
+            ConfigChange::PreferredDownloadBackend(_) => "preferred download backend",
+            ConfigChange::Throttles => "saved throttles",
+            ConfigChange::NeedsRestart(key) => key,
+        }
+    }
+}
+
+/// Compare the running config with a reloaded one
+pub fn diff(running: &Config, reloaded: &Config) -> Vec<ConfigChange> {
+    let mut changes = Vec::new();
+    let mut check = |changed: bool, change: ConfigChange| {
+        if changed {
+            changes.push(change);
+        }
+    };
+
+    check(running.theme != reloaded.theme, ConfigChange::Theme);
+    check(
+        running.keybindings != reloaded.keybindings,
+        ConfigChange::Keybindings,
+    );
+    check(
+        running.subnet_overrides != reloaded.subnet_overrides,
+        ConfigChange::SubnetOverrides,
+    );
+    check(running.quotas != reloaded.quotas, ConfigChange::Quotas);
+    check(
+        running.standing_rules != reloaded.standing_rules,
+        ConfigChange::StandingRules,
+    );
+    check(
+        running.alert_threshold != reloaded.alert_threshold
+            || running.alert_command != reloaded.alert_command
+            || running.alert_cooldown_secs != reloaded.alert_cooldown_secs,
+        ConfigChange::Alerts,
+    );
+    check(
+        running.webhooks != reloaded.webhooks,
+        ConfigChange::Webhooks,
+    );
+    check(
+        running.preferred_upload_backend != reloaded.preferred_upload_backend,
+        ConfigChange::PreferredUploadBackend(reloaded.preferred_upload_backend.clone()),
+    );
+    check(
+        running.preferred_download_backend != reloaded.preferred_download_backend,
+        ConfigChange::PreferredDownloadBackend(reloaded.preferred_download_backend.clone()),
+    );
+    check(
+        running.throttles != reloaded.throttles,
+        ConfigChange::Throttles,
+    );
+
+    let restart_only = [
+        running.preferred_monitor_backend != reloaded.preferred_monitor_backend,
+        running.preferred_socket_mapper != reloaded.preferred_socket_mapper,
+        running.capture_interfaces != reloaded.capture_interfaces,
+        running.interface_ceilings != reloaded.interface_ceilings,
+        running.history_samples != reloaded.history_samples,
+        running.persist_history != reloaded.persist_history,
+        running.count_loopback != reloaded.count_loopback,
+        running.ebpf_map_capacity != reloaded.ebpf_map_capacity,
+        running.connection_scan_interval_secs != reloaded.connection_scan_interval_secs,
+        running.watch_new_processes != reloaded.watch_new_processes,
+    ];
+    for (key, changed) in RESTART_ONLY.into_iter().zip(restart_only) {
+        check(changed, ConfigChange::NeedsRestart(key));
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{QuotaConfig, SavedThrottle, StandingRuleConfig, ThemeName};
+
+    #[test]
+    fn test_diff_lists_changed_settings() {
+        let running = Config::default();
+        assert!(diff(&running, &running.clone()).is_empty());
+
+        let mut reloaded = running.clone();
+        reloaded.theme = Some(ThemeName::HighContrast);
+        reloaded.quotas.push(QuotaConfig {
+            pattern: "steam*".to_string(),
+            limit: "5G".to_string(),
+            action: "alert".to_string(),
+        });
+        reloaded.standing_rules.push(StandingRuleConfig {
+            pattern: "apt*".to_string(),
+            download: Some("2M".to_string()),
+            upload: None,
+        });
+        reloaded.alert_threshold = Some("50M".to_string());
+        reloaded.preferred_download_backend = Some("nftables".to_string());
+        reloaded.ebpf_map_capacity = Some(64);
+        reloaded.throttles.insert(
+            42,
+            SavedThrottle {
+                process_name: "curl".to_string(),
+                upload_limit: None,
+                download_limit: Some(1024),
+                upload_minimum: None,
+                interfaces: None,
+                traffic_type: Default::default(),
+                upload_backend: None,
+                download_backend: None,
+                policy: None,
+                family: Default::default(),
+                priority: Default::default(),
+                include_children: false,
+                downgraded_from: None,
+            },
+        );
+
+        assert_eq!(
+            diff(&running, &reloaded),
+            vec![
+                ConfigChange::Theme,
+                ConfigChange::Quotas,
+                ConfigChange::StandingRules,
+                ConfigChange::Alerts,
+                ConfigChange::PreferredDownloadBackend(Some("nftables".to_string())),
+                ConfigChange::Throttles,
+                ConfigChange::NeedsRestart("ebpf_map_capacity"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_watcher_reports_edits_and_parse_errors() {
+        let dir = std::env::temp_dir().join(format!("chadthrottle-reload-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("throttles.json");
+        std::fs::write(&path, "{}").unwrap();
+
+        let mut watcher = ConfigWatcher::new(path.clone());
+        // Nothing changed (and the poll interval hasn't passed)
+        assert!(watcher.poll().is_none());
+
+        let mut edit = |contents: &str| {
+            std::fs::write(&path, contents).unwrap();
+            // Pretend the file was written later than before and the interval passed
+            watcher.modified = Some(SystemTime::UNIX_EPOCH);
+            watcher.last_poll -= POLL_INTERVAL;
+            watcher.poll()
+        };
+
+        let reloaded = edit(r#"{"theme": "mono"}"#).unwrap().unwrap();
+        assert_eq!(reloaded.theme, Some(ThemeName::Monochrome));
+        assert!(edit(r#"{"theme": "#).unwrap().is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
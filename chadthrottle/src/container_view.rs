@@ -0,0 +1,180 @@
+// One row per docker/podman container in the process list (container view)
+//
+// The row stands in for all of a container's processes: it carries their summed
+// traffic, and its PID is one of theirs, so throttling the row throttles that process,
+// which cgroup backends do in the container's own cgroup - covering the whole container.
+
+use crate::backends::cgroup::container::Container;
+use crate::process::ProcessInfo;
+use crate::process_tree::{RootOrder, add_traffic};
+use std::collections::HashMap;
+
+/// A container shown as a single row
+#[derive(Debug, Clone)]
+pub struct ContainerRow {
+    pub container: Container,
+    /// The container's processes in the current list (PID, name)
+    pub members: Vec<(i32, String)>,
+}
+
+/// Replace the processes of each container in `processes` with one row for the
+/// container, at the position of its first process.
+///
+/// The row is the container's throttled process if one is (so its throttle shows), else
+/// its lowest PID, named after the container and carrying the traffic of all of them.
+/// With `compare`, the list is sorted again on the summed traffic.
+pub fn group(
+    processes: Vec<ProcessInfo>,
+    containers: &HashMap<i32, Option<Container>>,
+    compare: Option<RootOrder>,
+) -> (Vec<ProcessInfo>, HashMap<i32, ContainerRow>) {
+    let container_of = |pid: i32| containers.get(&pid).and_then(Option::as_ref);
+
+    let mut members: HashMap<&str, Vec<&ProcessInfo>> = HashMap::new();
+    for process in &processes {
+        if let Some(container) = container_of(process.pid) {
+            members.entry(&container.id).or_default().push(process);
+        }
+    }
+
+    let mut list = Vec::with_capacity(processes.len());
+    let mut rows = HashMap::new();
+    for process in &processes {
+        let Some(container) = container_of(process.pid) else {
+            list.push(process.clone());
+            continue;
+        };
+        let Some(in_container) = members.remove(container.id.as_str()) else {
+            continue; // already grouped
+        };
+
+        let representative = in_container
+            .iter()
+            .copied()
+            .min_by_key(|p| (!p.is_throttled(), p.pid))
+            .unwrap_or(process);
+        let mut row = representative.clone();
+        for member in &in_container {
+            if member.pid != row.pid {
+                add_traffic(&mut row, member);
+            }
+        }
+        row.name = container.display_name().to_string();
+        row.parent_pid = None;
+        row.is_terminated = in_container.iter().all(|p| p.is_terminated);
+        row.has_active_transfer = in_container.iter().any(|p| p.has_active_transfer);
+        row.listen_only = in_container.iter().all(|p| p.listen_only);
+
+        rows.insert(
+            row.pid,
+            ContainerRow {
+                container: container.clone(),
+                members: in_container
+                    .iter()
+                    .map(|p| (p.pid, p.name.clone()))
+                    .collect(),
+            },
+        );
+        list.push(row);
+    }
+
+    if let Some(compare) = compare {
+        list.sort_by(|a, b| compare(a, b));
+    }
+    (list, rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backends::cgroup::container::ContainerRuntime;
+    use crate::process::{AddressFamily, Priority, ThrottleLimit, TrafficType};
+
+    fn process(pid: i32, rx: u64) -> ProcessInfo {
+        let mut p = ProcessInfo::new(pid, format!("proc{}", pid));
+        p.parent_pid = Some(1);
+        p.download_rate = rx;
+        p
+    }
+
+    fn container(id: char, name: Option<&str>) -> Container {
+        Container {
+            runtime: ContainerRuntime::Docker,
+            id: id.to_string().repeat(64),
+            cgroup: format!("system.slice/docker-{}.scope", id.to_string().repeat(64)),
+            name: name.map(str::to_string),
+        }
+    }
+
+    fn by_rate(a: &ProcessInfo, b: &ProcessInfo) -> std::cmp::Ordering {
+        b.download_rate.cmp(&a.download_rate)
+    }
+
+    #[test]
+    fn test_container_processes_become_one_row() {
+        let web = container('a', Some("web"));
+        let db = container('b', None);
+        let containers: HashMap<i32, Option<Container>> = [
+            (30, Some(web.clone())),
+            (31, Some(web.clone())),
+            (32, Some(web)),
+            (40, Some(db)),
+            (50, None),
+        ]
+        .into_iter()
+        .collect();
+        let procs = vec![
+            process(50, 700),
+            process(32, 100),
+            process(40, 50),
+            process(31, 200),
+            process(30, 300),
+        ];
+
+        let (list, rows) = group(procs.clone(), &containers, Some(&by_rate));
+        let pids: Vec<i32> = list.iter().map(|p| p.pid).collect();
+
+        // web's three processes sum to 600, below the uncontained 700
+        assert_eq!(pids, vec![50, 30, 40]);
+        assert_eq!(list[1].name, "web");
+        assert_eq!(list[1].download_rate, 600);
+        assert_eq!(list[1].parent_pid, None);
+        assert_eq!(rows[&30].members.len(), 3);
+        assert_eq!(list[2].name, "bbbbbbbbbbbb");
+        assert!(!rows.contains_key(&50));
+
+        // Frozen: each container's row takes its first process's place
+        let (list, _) = group(procs, &containers, None);
+        let pids: Vec<i32> = list.iter().map(|p| p.pid).collect();
+        assert_eq!(pids, vec![50, 30, 40]);
+    }
+
+    #[test]
+    fn test_throttled_member_represents_container() {
+        let web = container('a', Some("web"));
+        let containers: HashMap<i32, Option<Container>> =
+            [(30, Some(web.clone())), (31, Some(web))]
+                .into_iter()
+                .collect();
+        let mut throttled = process(31, 10);
+        throttled.throttle_limit = Some(ThrottleLimit {
+            download_limit: Some(1024),
+            upload_limit: None,
+            upload_minimum: None,
+            traffic_type: TrafficType::All,
+            interfaces: None,
+            policy: None,
+            family: AddressFamily::Any,
+            priority: Priority::Normal,
+        });
+        let mut exited = process(30, 0);
+        exited.is_terminated = true;
+
+        let (list, rows) = group(vec![exited, throttled], &containers, None);
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0].pid, 31);
+        assert!(list[0].is_throttled());
+        assert!(!list[0].is_terminated);
+        assert_eq!(rows[&31].members.len(), 2);
+    }
+}
@@ -0,0 +1,633 @@
+// System diagnostics report (--diagnose / --diagnostics)
+//
+// Collects everything needed to debug "throttling doesn't work" reports in one
+// block: build features, kernel, cgroup layout, eBPF support, external tools,
+// every compiled-in backend with the reason it is (un)available, the backends that
+// would be used, and a summary of the config. Printed as text or JSON (--json).
+
+use crate::backends::process::socket_mapper::{
+    SocketCacheStats, SocketMapperBackend, detect_socket_mappers, select_socket_mapper,
+};
+use crate::backends::throttle::{detect_download_backends, detect_upload_backends};
+use crate::config::Config;
+use serde::Serialize;
+use sysinfo::System;
+
+/// Which cgroup hierarchies are mounted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CgroupVersion {
+    /// Only cgroup v1 controllers
+    V1,
+    /// Unified cgroup v2 hierarchy only
+    V2,
+    /// Both v1 controllers and a v2 hierarchy
+    Hybrid,
+    /// No cgroup filesystem mounted (or not Linux)
+    None,
+}
+
+impl std::fmt::Display for CgroupVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CgroupVersion::V1 => write!(f, "v1"),
+            CgroupVersion::V2 => write!(f, "v2 (unified)"),
+            CgroupVersion::Hybrid => write!(f, "hybrid (v1 + v2)"),
+            CgroupVersion::None => write!(f, "not mounted"),
+        }
+    }
+}
+
+/// Determine the cgroup layout from the contents of /proc/mounts
+pub fn parse_cgroup_mounts(mounts: &str) -> (CgroupVersion, bool) {
+    let mut has_v1 = false;
+    let mut has_v2 = false;
+    let mut has_net_cls = false;
+
+    for line in mounts.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let (Some(fs_type), Some(options)) = (fields.get(2), fields.get(3)) else {
+            continue;
+        };
+        match *fs_type {
+            "cgroup2" => has_v2 = true,
+            "cgroup" => {
+                has_v1 = true;
+                if options.split(',').any(|opt| opt == "net_cls") {
+                    has_net_cls = true;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let version = match (has_v1, has_v2) {
+        (true, true) => CgroupVersion::Hybrid,
+        (true, false) => CgroupVersion::V1,
+        (false, true) => CgroupVersion::V2,
+        (false, false) => CgroupVersion::None,
+    };
+    (version, has_net_cls)
+}
+
+/// Detect the mounted cgroup layout and whether the v1 net_cls controller is present
+fn detect_cgroup_version() -> (CgroupVersion, bool) {
+    match std::fs::read_to_string("/proc/mounts") {
+        Ok(mounts) => parse_cgroup_mounts(&mounts),
+        Err(_) => (CgroupVersion::None, false),
+    }
+}
+
+/// Cargo features this binary was built with
+const FEATURES: &[(&str, bool)] = &[
+    ("monitor-pnet", cfg!(feature = "monitor-pnet")),
+    ("cgroup-v1", cfg!(feature = "cgroup-v1")),
+    ("cgroup-v2-nftables", cfg!(feature = "cgroup-v2-nftables")),
+    ("cgroup-v2-ebpf", cfg!(feature = "cgroup-v2-ebpf")),
+    ("throttle-tc-htb", cfg!(feature = "throttle-tc-htb")),
+    ("throttle-ifb-tc", cfg!(feature = "throttle-ifb-tc")),
+    ("throttle-tc-police", cfg!(feature = "throttle-tc-police")),
+    ("throttle-nftables", cfg!(feature = "throttle-nftables")),
+    ("throttle-ebpf", cfg!(feature = "throttle-ebpf")),
+    ("throttle-proxy", cfg!(feature = "throttle-proxy")),
+    ("throttle-null", cfg!(feature = "throttle-null")),
+    ("throttle-signal", cfg!(feature = "throttle-signal")),
+];
+
+/// External tools the backends shell out to, with the arguments printing their version
+#[cfg(target_os = "linux")]
+const TOOLS: &[(&str, &[&str])] = &[("tc", &["-V"]), ("nft", &["--version"]), ("ip", &["-V"])];
+#[cfg(not(target_os = "linux"))]
+const TOOLS: &[(&str, &[&str])] = &[];
+
+/// Everything the report covers
+#[derive(Debug, Serialize)]
+pub struct Diagnostics {
+    pub version: &'static str,
+    pub features: Vec<&'static str>,
+    pub system: SystemInfo,
+    pub tools: Vec<ToolInfo>,
+    pub cgroup_backends: Vec<BackendStatus>,
+    pub socket_mappers: Vec<BackendStatus>,
+    pub upload_backends: Vec<BackendStatus>,
+    pub download_backends: Vec<BackendStatus>,
+    pub selected: SelectedBackends,
+    pub config: ConfigSummary,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SystemInfo {
+    pub os: String,
+    pub kernel: String,
+    pub architecture: &'static str,
+    /// None where there's no notion of root (Windows)
+    pub running_as_root: Option<bool>,
+    pub cgroups: String,
+    pub net_cls_controller: bool,
+    pub ebpf: String,
+    pub bpf_syscall: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ToolInfo {
+    pub name: &'static str,
+    /// First line of the tool's version output (None if it isn't installed)
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BackendStatus {
+    pub name: String,
+    /// None for cgroup backends (they aren't ranked)
+    pub priority: Option<String>,
+    pub available: bool,
+    pub unavailable_reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SelectedBackends {
+    pub socket_mapper: String,
+    /// The socket mapper's owner cache over two connection maps in a row (None if it
+    /// doesn't cache)
+    pub socket_cache: Option<SocketCacheStats>,
+    pub upload: String,
+    pub download: String,
+}
+
+/// What the config contains, without its contents (no names, commands or URLs)
+#[derive(Debug, Default, Serialize)]
+pub struct ConfigSummary {
+    pub path: Option<String>,
+    pub exists: bool,
+    /// Why the config couldn't be loaded (defaults are used then)
+    pub error: Option<String>,
+    pub version: u32,
+    pub saved_throttles: usize,
+    pub interface_ceilings: usize,
+    pub subnet_overrides: usize,
+    pub keybinding_overrides: usize,
+    pub filtered_interfaces: usize,
+    pub capture_interfaces: usize,
+    pub alert_configured: bool,
+    pub webhook_configured: bool,
+}
+
+impl ConfigSummary {
+    fn new(config: &Config) -> Self {
+        Self {
+            version: config.version,
+            saved_throttles: config.throttles.len(),
+            interface_ceilings: config.interface_ceilings.len(),
+            subnet_overrides: config.subnet_overrides.len(),
+            keybinding_overrides: config.keybindings.len(),
+            filtered_interfaces: config.filtered_interfaces.as_ref().map_or(0, Vec::len),
+            capture_interfaces: config.capture_interfaces.as_ref().map_or(0, Vec::len),
+            alert_configured: config.alert_threshold.is_some(),
+            webhook_configured: config.webhooks.is_some(),
+            ..Default::default()
+        }
+    }
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value { "yes" } else { "no" }
+}
+
+fn status(available: bool) -> &'static str {
+    if available {
+        "✅ available"
+    } else {
+        "❌ unavailable"
+    }
+}
+
+/// Summary of eBPF support (kernel/cgroup prerequisites and whether it's compiled in)
+fn ebpf_support() -> String {
+    #[cfg(all(target_os = "linux", feature = "throttle-ebpf"))]
+    {
+        use crate::backends::throttle::linux_ebpf_utils::check_ebpf_support;
+
+        if check_ebpf_support() {
+            "supported (cgroup v2 + kernel 4.10+)".to_string()
+        } else {
+            "unsupported (requires cgroup v2 and kernel 4.10+)".to_string()
+        }
+    }
+
+    #[cfg(not(all(target_os = "linux", feature = "throttle-ebpf")))]
+    {
+        "not compiled in (build with --features throttle-ebpf)".to_string()
+    }
+}
+
+/// Whether this process may use the bpf() syscall, by creating (and closing) a
+/// one-entry array map - the cheapest operation that needs the permission
+#[cfg(target_os = "linux")]
+fn probe_bpf_syscall() -> String {
+    /// Leading fields of `union bpf_attr` for BPF_MAP_CREATE (the kernel zero-fills the rest)
+    #[repr(C)]
+    struct MapCreateAttr {
+        map_type: u32,
+        key_size: u32,
+        value_size: u32,
+        max_entries: u32,
+    }
+    const BPF_MAP_CREATE: libc::c_long = 0;
+    const BPF_MAP_TYPE_ARRAY: u32 = 2;
+
+    let attr = MapCreateAttr {
+        map_type: BPF_MAP_TYPE_ARRAY,
+        key_size: 4,
+        value_size: 4,
+        max_entries: 1,
+    };
+    // SAFETY: attr is a valid bpf_attr prefix and the size passed matches it
+    let fd = unsafe {
+        libc::syscall(
+            libc::SYS_bpf,
+            BPF_MAP_CREATE,
+            &attr as *const MapCreateAttr,
+            std::mem::size_of::<MapCreateAttr>(),
+        )
+    };
+    if fd >= 0 {
+        // SAFETY: fd was just returned by the kernel and isn't used anywhere else
+        unsafe { libc::close(fd as libc::c_int) };
+        return "permitted".to_string();
+    }
+
+    let error = std::io::Error::last_os_error();
+    match error.raw_os_error() {
+        Some(libc::EPERM) | Some(libc::EACCES) => "denied (needs root or CAP_BPF)".to_string(),
+        Some(libc::ENOSYS) => "not supported by the kernel".to_string(),
+        _ => format!("failed ({})", error),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn probe_bpf_syscall() -> String {
+    "not applicable (Linux only)".to_string()
+}
+
+/// First line of a tool's version output, None if it can't be run
+fn tool_version(name: &str, args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new(name).args(args).output().ok()?;
+    // Some tools print their version to stderr
+    let text = if output.stdout.is_empty() {
+        output.stderr
+    } else {
+        output.stdout
+    };
+    let text = String::from_utf8_lossy(&text);
+    Some(text.lines().next().unwrap_or("").trim().to_string())
+}
+
+fn running_as_root() -> Option<bool> {
+    #[cfg(unix)]
+    {
+        Some(unsafe { libc::geteuid() } == 0)
+    }
+
+    #[cfg(not(unix))]
+    {
+        None
+    }
+}
+
+fn config_summary() -> ConfigSummary {
+    let path = match Config::config_path() {
+        Ok(path) => path,
+        Err(e) => {
+            return ConfigSummary {
+                error: Some(format!("{:#}", e)),
+                ..Default::default()
+            };
+        }
+    };
+    let exists = path.exists();
+    let mut summary = match Config::load() {
+        Ok(config) => ConfigSummary::new(&config),
+        Err(e) => ConfigSummary {
+            error: Some(format!("{:#}", e)),
+            ..Default::default()
+        },
+    };
+    summary.path = Some(path.display().to_string());
+    summary.exists = exists;
+    summary
+}
+
+/// Collect the report
+pub fn collect() -> Diagnostics {
+    let config = Config::load().unwrap_or_default();
+    let (cgroup_version, has_net_cls) = detect_cgroup_version();
+
+    let system = SystemInfo {
+        os: System::long_os_version().unwrap_or_else(|| std::env::consts::OS.to_string()),
+        kernel: System::kernel_version().unwrap_or_else(|| "unknown".to_string()),
+        architecture: std::env::consts::ARCH,
+        running_as_root: running_as_root(),
+        cgroups: cgroup_version.to_string(),
+        net_cls_controller: has_net_cls,
+        ebpf: ebpf_support(),
+        bpf_syscall: probe_bpf_syscall(),
+    };
+
+    let tools = TOOLS
+        .iter()
+        .map(|(name, args)| ToolInfo {
+            name,
+            version: tool_version(name, args),
+        })
+        .collect();
+
+    // Cgroup backends (used by the Linux throttle backends for per-process matching)
+    let cgroup_backends = crate::backends::cgroup::list_all_backends()
+        .into_iter()
+        .map(|(backend_type, available, reason)| BackendStatus {
+            name: backend_type.to_string(),
+            priority: None,
+            available,
+            unavailable_reason: (!available).then_some(reason),
+        })
+        .collect();
+
+    let socket_mappers = detect_socket_mappers()
+        .into_iter()
+        .map(|b| BackendStatus {
+            name: b.name.to_string(),
+            priority: Some(format!("{:?}", b.priority)),
+            available: b.available,
+            unavailable_reason: b.unavailable_reason,
+        })
+        .collect();
+
+    let upload = detect_upload_backends();
+    let download = detect_download_backends();
+
+    // What a normal start would pick (config preference, else best available)
+    let best_upload = upload
+        .iter()
+        .filter(|b| b.available)
+        .max_by_key(|b| b.priority)
+        .map(|b| b.name);
+    let best_download = download
+        .iter()
+        .filter(|b| b.available)
+        .max_by_key(|b| b.priority)
+        .map(|b| b.name);
+    let (socket_mapper, socket_cache) =
+        match select_socket_mapper(config.preferred_socket_mapper.as_deref()) {
+            Ok(mapper) => (mapper.name().to_string(), sample_socket_cache(&*mapper)),
+            Err(e) => (format!("none ({})", e), None),
+        };
+    let selected = SelectedBackends {
+        socket_mapper,
+        socket_cache,
+        upload: selected_backend(config.preferred_upload_backend.as_deref(), best_upload),
+        download: selected_backend(config.preferred_download_backend.as_deref(), best_download),
+    };
+
+    Diagnostics {
+        version: env!("CARGO_PKG_VERSION"),
+        features: FEATURES
+            .iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(name, _)| *name)
+            .collect(),
+        system,
+        tools,
+        cgroup_backends,
+        socket_mappers,
+        upload_backends: upload
+            .into_iter()
+            .map(|b| BackendStatus {
+                name: b.name.to_string(),
+                priority: Some(format!("{:?}", b.priority)),
+                available: b.available,
+                unavailable_reason: b.unavailable_reason,
+            })
+            .collect(),
+        download_backends: download
+            .into_iter()
+            .map(|b| BackendStatus {
+                name: b.name.to_string(),
+                priority: Some(format!("{:?}", b.priority)),
+                available: b.available,
+                unavailable_reason: b.unavailable_reason,
+            })
+            .collect(),
+        selected,
+        config: config_summary(),
+    }
+}
+
+/// Build two connection maps in a row: the second should find nearly every owner in
+/// the cache
+fn sample_socket_cache(mapper: &dyn SocketMapperBackend) -> Option<SocketCacheStats> {
+    mapper.cache_stats()?;
+    for _ in 0..2 {
+        if let Err(e) = mapper.get_connection_map() {
+            log::debug!("Connection map for the socket cache sample failed: {}", e);
+        }
+    }
+    mapper.cache_stats()
+}
+
+/// Print the diagnostics report to stdout, as text or JSON
+pub fn print_diagnostics(json: bool) -> anyhow::Result<()> {
+    let report = collect();
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print!("{}", format_report(&report));
+    }
+    Ok(())
+}
+
+fn format_backends(out: &mut String, title: &str, backends: &[BackendStatus]) {
+    use std::fmt::Write;
+
+    let _ = writeln!(out, "{}:", title);
+    if backends.is_empty() {
+        let _ = writeln!(out, "  (none compiled in)");
+    }
+    for backend in backends {
+        let _ = write!(out, "  {:20} ", backend.name);
+        if let Some(priority) = &backend.priority {
+            let _ = write!(out, "[priority: {}] ", priority);
+        }
+        let _ = write!(out, "{}", status(backend.available));
+        if let Some(reason) = &backend.unavailable_reason {
+            let _ = write!(out, " - {}", reason);
+        }
+        let _ = writeln!(out);
+    }
+    let _ = writeln!(out);
+}
+
+/// Plain-text report (to paste into a bug report)
+fn format_report(report: &Diagnostics) -> String {
+    use std::fmt::Write;
+
+    let mut out = String::new();
+    let _ = writeln!(out, "ChadThrottle v{} - Diagnostics\n", report.version);
+    let features = if report.features.is_empty() {
+        "(none)".to_string()
+    } else {
+        report.features.join(", ")
+    };
+    let _ = writeln!(out, "  {:20} {}\n", "Features:", features);
+
+    let system = &report.system;
+    let _ = writeln!(out, "System:");
+    let _ = writeln!(out, "  {:20} {}", "OS:", system.os);
+    let _ = writeln!(out, "  {:20} {}", "Kernel:", system.kernel);
+    let _ = writeln!(out, "  {:20} {}", "Architecture:", system.architecture);
+    if let Some(root) = system.running_as_root {
+        let _ = writeln!(out, "  {:20} {}", "Running as root:", yes_no(root));
+    }
+    let _ = writeln!(out, "  {:20} {}", "Cgroups:", system.cgroups);
+    let _ = writeln!(
+        out,
+        "  {:20} {}",
+        "net_cls controller:",
+        yes_no(system.net_cls_controller)
+    );
+    let _ = writeln!(out, "  {:20} {}", "eBPF:", system.ebpf);
+    let _ = writeln!(out, "  {:20} {}", "bpf() syscall:", system.bpf_syscall);
+    let _ = writeln!(out);
+
+    if !report.tools.is_empty() {
+        let _ = writeln!(out, "Tools:");
+        for tool in &report.tools {
+            let version = tool.version.as_deref().unwrap_or("not found");
+            let _ = writeln!(out, "  {:20} {}", format!("{}:", tool.name), version);
+        }
+        let _ = writeln!(out);
+    }
+
+    format_backends(&mut out, "Cgroup Backends", &report.cgroup_backends);
+    format_backends(&mut out, "Socket Mapper Backends", &report.socket_mappers);
+    format_backends(&mut out, "Upload Backends", &report.upload_backends);
+    format_backends(&mut out, "Download Backends", &report.download_backends);
+
+    let _ = writeln!(out, "Selected:");
+    let _ = writeln!(
+        out,
+        "  {:20} {}",
+        "Socket mapper:", report.selected.socket_mapper
+    );
+    if let Some(cache) = &report.selected.socket_cache {
+        let _ = writeln!(
+            out,
+            "  {:20} {} hits, {} misses, {} fd scans over 2 connection maps",
+            "Socket cache:", cache.hits, cache.misses, cache.fd_scans
+        );
+    }
+    let _ = writeln!(out, "  {:20} {}", "Upload backend:", report.selected.upload);
+    let _ = writeln!(
+        out,
+        "  {:20} {}",
+        "Download backend:", report.selected.download
+    );
+    let _ = writeln!(out);
+
+    let config = &report.config;
+    let _ = writeln!(out, "Config:");
+    let path = config.path.as_deref().unwrap_or("unknown");
+    let exists = if config.exists {
+        ""
+    } else {
+        " (not created yet)"
+    };
+    let _ = writeln!(out, "  {:20} {}{}", "Path:", path, exists);
+    if let Some(error) = &config.error {
+        let _ = writeln!(out, "  {:20} {} (using defaults)", "Error:", error);
+    } else {
+        let _ = writeln!(out, "  {:20} {}", "Version:", config.version);
+        let _ = writeln!(
+            out,
+            "  {:20} {}",
+            "Saved throttles:", config.saved_throttles
+        );
+        let _ = writeln!(
+            out,
+            "  {:20} {}",
+            "Interface ceilings:", config.interface_ceilings
+        );
+        let _ = writeln!(
+            out,
+            "  {:20} {}",
+            "Subnet overrides:", config.subnet_overrides
+        );
+        let _ = writeln!(
+            out,
+            "  {:20} {}",
+            "Keybinding changes:", config.keybinding_overrides
+        );
+        let _ = writeln!(
+            out,
+            "  {:20} {} filtered, {} captured",
+            "Interfaces:", config.filtered_interfaces, config.capture_interfaces
+        );
+        let _ = writeln!(
+            out,
+            "  {:20} alerts {}, webhook {}",
+            "Notifications:",
+            yes_no(config.alert_configured),
+            yes_no(config.webhook_configured)
+        );
+    }
+    out
+}
+
+fn selected_backend(preferred: Option<&str>, best: Option<&str>) -> String {
+    match (preferred, best) {
+        (Some(name), _) => format!("{} (preferred in config)", name),
+        (None, Some(name)) => format!("{} (auto)", name),
+        (None, None) => "none (throttling unavailable)".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cgroup_mounts() {
+        let unified = "cgroup2 /sys/fs/cgroup cgroup2 rw,nosuid,nodev,noexec,relatime 0 0\n";
+        assert_eq!(parse_cgroup_mounts(unified), (CgroupVersion::V2, false));
+
+        let hybrid = "tmpfs /sys/fs/cgroup tmpfs ro,nosuid 0 0\n\
+                      cgroup2 /sys/fs/cgroup/unified cgroup2 rw,nosuid 0 0\n\
+                      cgroup /sys/fs/cgroup/net_cls,net_prio cgroup rw,net_cls,net_prio 0 0\n";
+        assert_eq!(parse_cgroup_mounts(hybrid), (CgroupVersion::Hybrid, true));
+
+        let legacy = "cgroup /sys/fs/cgroup/cpu cgroup rw,cpu,cpuacct 0 0\n";
+        assert_eq!(parse_cgroup_mounts(legacy), (CgroupVersion::V1, false));
+
+        assert_eq!(parse_cgroup_mounts(""), (CgroupVersion::None, false));
+    }
+
+    #[test]
+    fn test_config_summary_is_sanitized() {
+        let config: Config = serde_json::from_str(
+            r#"{
+                "alert_threshold": "10M",
+                "webhooks": {"url": "https://hooks.example/secret-token"},
+                "interface_ceilings": {"eth0": "5M"},
+                "filtered_interfaces": ["eth0", "wlan0"]
+            }"#,
+        )
+        .unwrap();
+        let summary = ConfigSummary::new(&config);
+        assert_eq!(summary.interface_ceilings, 1);
+        assert_eq!(summary.filtered_interfaces, 2);
+        assert_eq!(summary.capture_interfaces, 0);
+        assert!(summary.alert_configured && summary.webhook_configured);
+
+        let json = serde_json::to_string(&summary).unwrap();
+        assert!(!json.contains("hooks.example"));
+        assert!(!json.contains("eth0"));
+    }
+}
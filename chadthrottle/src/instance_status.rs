@@ -0,0 +1,127 @@
+// What running TUI instances are throttling, for `--status`
+//
+// A TUI's throttles only exist in its memory (and the backends' kernel state), so
+// another process can't ask for them. Each TUI instance writes its active throttles
+// to instances/<pid>.json next to the config whenever they change, and removes the
+// file on exit. `--status` reads these along with the CLI-mode records.
+
+use crate::backends::process::ProcessUtils;
+use crate::cli_throttles::is_running_instance;
+use crate::utilization::Utilization;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// Directory next to the config file holding one file per running TUI instance
+const INSTANCES_DIR: &str = "instances";
+
+/// One active throttle of a running instance
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThrottleStatus {
+    pub pid: i32,
+    pub process_name: String,
+    pub download_limit: Option<u64>,
+    pub upload_limit: Option<u64>,
+    /// Pattern of the standing rule that applied the throttle (None = applied by hand
+    /// or restored)
+    #[serde(default)]
+    pub standing_rule: Option<String>,
+    /// Share of the limits the process uses (None until there are enough samples)
+    #[serde(default)]
+    pub utilization: Option<Utilization>,
+}
+
+/// The throttles of one running TUI instance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstanceStatus {
+    pub owner_pid: i32,
+    pub throttles: Vec<ThrottleStatus>,
+}
+
+impl InstanceStatus {
+    /// Instances that are still running (files of exited ones are cleaned up),
+    /// sorted by PID
+    pub fn list(process_utils: &dyn ProcessUtils) -> Vec<Self> {
+        let Ok(entries) = instances_dir().and_then(|dir| Ok(fs::read_dir(dir)?)) else {
+            return Vec::new();
+        };
+
+        let mut instances = Vec::new();
+        for path in entries.flatten().map(|entry| entry.path()) {
+            let status = fs::read_to_string(&path)
+                .ok()
+                .and_then(|contents| serde_json::from_str::<Self>(&contents).ok());
+            match status {
+                Some(status) if is_running_instance(process_utils, status.owner_pid) => {
+                    instances.push(status)
+                }
+                _ => {
+                    log::debug!("Removing stale instance status {:?}", path);
+                    let _ = fs::remove_file(&path);
+                }
+            }
+        }
+        instances.sort_by_key(|status| status.owner_pid);
+        instances
+    }
+}
+
+/// This instance's status file, rewritten when its throttles change
+#[derive(Debug, Default)]
+pub struct StatusFile {
+    /// None when the config directory can't be used
+    path: Option<PathBuf>,
+    written: Option<Vec<ThrottleStatus>>,
+}
+
+impl StatusFile {
+    pub fn open() -> Self {
+        let path = instances_dir()
+            .map(|dir| dir.join(format!("{}.json", std::process::id())))
+            .map_err(|e| log::warn!("--status won't show this instance: {:#}", e))
+            .ok();
+        Self {
+            path,
+            written: None,
+        }
+    }
+
+    /// Write the throttles, if they changed since the last write
+    pub fn update(&mut self, mut throttles: Vec<ThrottleStatus>) {
+        throttles.sort_by_key(|throttle| throttle.pid);
+        if self.written.as_ref() == Some(&throttles) {
+            return;
+        }
+        if let Err(e) = self.write(&throttles) {
+            log::warn!("Failed to write instance status: {:#}", e);
+        }
+        self.written = Some(throttles);
+    }
+
+    fn write(&self, throttles: &[ThrottleStatus]) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let status = InstanceStatus {
+            owner_pid: std::process::id() as i32,
+            throttles: throttles.to_vec(),
+        };
+        fs::write(path, serde_json::to_string(&status)?)
+            .context(format!("Failed to write {:?}", path))
+    }
+}
+
+impl Drop for StatusFile {
+    fn drop(&mut self) {
+        if let Some(path) = &self.path {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+fn instances_dir() -> Result<PathBuf> {
+    let dir = crate::config::Config::config_path()?.with_file_name(INSTANCES_DIR);
+    fs::create_dir_all(&dir).context(format!("Failed to create {:?}", dir))?;
+    Ok(dir)
+}
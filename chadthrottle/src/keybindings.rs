@@ -1,9 +1,20 @@
-/// Centralized keybinding definitions for ChadThrottle
-/// This ensures the help menu, status bar, and actual key handlers stay in sync
+//! Centralized keybinding definitions for ChadThrottle
+//! This ensures the help menu, status bar, and actual key handlers stay in sync
+//!
+//! Keys are resolved to an [`Action`] through a [`KeyMap`]. The defaults can be
+//! overridden per action in the config file, e.g.:
+//!
+//! ```json
+//! "keybindings": { "move-down": ["Down", "n"], "throttle": ["T"] }
+//! ```
+
+use anyhow::{Context, Result, bail};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::HashMap;
 
 #[derive(Debug, Clone)]
 pub struct KeyBinding {
-    pub key: &'static str,
+    pub key: String,
     pub description: &'static str,
     pub category: KeyCategory,
 }
@@ -25,105 +36,600 @@ impl KeyCategory {
     }
 }
 
+/// Everything a key can be bound to in the main view
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    PageUp,
+    PageDown,
+    ToggleInterfaces,
+    CycleTrafficView,
+    ViewDetails,
+    NextTab,
+    PreviousTab,
+    ToggleInterfaceFilter,
+    ToggleAllInterfaces,
+    AddInterfacePattern,
+    ToggleTreeView,
+    ToggleContainerView,
+    ToggleCmdline,
+    ToggleTcpHealth,
+    ToggleListenOnly,
+    Expand,
+    Collapse,
+    Throttle,
+    RemoveThrottle,
+    Undo,
+    ToggleMark,
+    TogglePin,
+    ThrottleAllExcept,
+    StandingRules,
+    Yank,
+    CopyCommand,
+    ToggleGraph,
+    ToggleGraphTime,
+    FreezeSort,
+    Backends,
+    CycleUploadBackend,
+    CycleDownloadBackend,
+    ApplyConfigBackends,
+    Logs,
+    Help,
+    Quit,
+}
+
+impl Action {
+    /// All actions, in the order they are listed in the help menu
+    pub const ALL: [Action; 38] = [
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::PageUp,
+        Action::PageDown,
+        Action::ToggleInterfaces,
+        Action::CycleTrafficView,
+        Action::ViewDetails,
+        Action::NextTab,
+        Action::PreviousTab,
+        Action::ToggleInterfaceFilter,
+        Action::ToggleAllInterfaces,
+        Action::AddInterfacePattern,
+        Action::ToggleTreeView,
+        Action::ToggleContainerView,
+        Action::ToggleCmdline,
+        Action::ToggleTcpHealth,
+        Action::ToggleListenOnly,
+        Action::Expand,
+        Action::Collapse,
+        Action::Throttle,
+        Action::RemoveThrottle,
+        Action::Undo,
+        Action::ToggleMark,
+        Action::TogglePin,
+        Action::ThrottleAllExcept,
+        Action::StandingRules,
+        Action::Yank,
+        Action::CopyCommand,
+        Action::ToggleGraph,
+        Action::ToggleGraphTime,
+        Action::FreezeSort,
+        Action::Backends,
+        Action::CycleUploadBackend,
+        Action::CycleDownloadBackend,
+        Action::ApplyConfigBackends,
+        Action::Logs,
+        Action::Help,
+        Action::Quit,
+    ];
+
+    /// Name used for this action in the config file
+    pub fn config_name(&self) -> &'static str {
+        match self {
+            Action::MoveUp => "move-up",
+            Action::MoveDown => "move-down",
+            Action::PageUp => "page-up",
+            Action::PageDown => "page-down",
+            Action::ToggleInterfaces => "toggle-interfaces",
+            Action::CycleTrafficView => "cycle-traffic-view",
+            Action::ViewDetails => "view-details",
+            Action::NextTab => "next-tab",
+            Action::PreviousTab => "previous-tab",
+            Action::ToggleInterfaceFilter => "toggle-interface-filter",
+            Action::ToggleAllInterfaces => "toggle-all-interfaces",
+            Action::AddInterfacePattern => "add-interface-pattern",
+            Action::ToggleTreeView => "toggle-tree-view",
+            Action::ToggleContainerView => "toggle-container-view",
+            Action::ToggleCmdline => "toggle-cmdline",
+            Action::ToggleTcpHealth => "toggle-tcp-health",
+            Action::ToggleListenOnly => "toggle-listen-only",
+            Action::Expand => "expand",
+            Action::Collapse => "collapse",
+            Action::Throttle => "throttle",
+            Action::RemoveThrottle => "remove-throttle",
+            Action::Undo => "undo",
+            Action::ToggleMark => "toggle-mark",
+            Action::TogglePin => "toggle-pin",
+            Action::ThrottleAllExcept => "throttle-all-except",
+            Action::StandingRules => "standing-rules",
+            Action::Yank => "yank",
+            Action::CopyCommand => "copy-command",
+            Action::ToggleGraph => "toggle-graph",
+            Action::ToggleGraphTime => "toggle-graph-time",
+            Action::FreezeSort => "freeze-sort",
+            Action::Backends => "backends",
+            Action::CycleUploadBackend => "cycle-upload-backend",
+            Action::CycleDownloadBackend => "cycle-download-backend",
+            Action::ApplyConfigBackends => "apply-config-backends",
+            Action::Logs => "logs",
+            Action::Help => "help",
+            Action::Quit => "quit",
+        }
+    }
+
+    pub fn from_config_name(name: &str) -> Option<Action> {
+        Action::ALL.into_iter().find(|a| a.config_name() == name)
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::MoveUp => "Move selection up",
+            Action::MoveDown => "Move selection down",
+            Action::PageUp => "Move/scroll up by 10",
+            Action::PageDown => "Move/scroll down by 10",
+            Action::ToggleInterfaces => "Toggle interface view",
+            Action::CycleTrafficView => "Cycle traffic view (All/Internet/Local)",
+            Action::ViewDetails => "View details (process or interface)",
+            Action::NextTab => "Switch tabs (in detail view)",
+            Action::PreviousTab => "Switch tabs backwards (in detail view)",
+            Action::ToggleInterfaceFilter => "Toggle interface filter (in interface list)",
+            Action::ToggleAllInterfaces => "Toggle All/None interfaces (in interface list)",
+            Action::AddInterfacePattern => {
+                "Add/remove interface pattern, e.g. tun* (in interface list)"
+            }
+            Action::ToggleTreeView => "Toggle process tree view",
+            Action::ToggleContainerView => "Group container processes into one row (docker/podman)",
+            Action::ToggleCmdline => "Toggle process name/command line",
+            Action::ToggleTcpHealth => "Toggle TCP retransmits/RTT columns",
+            Action::ToggleListenOnly => "Show/hide idle listen-only processes (e.g. sshd)",
+            Action::Expand => "Expand process children (in tree view)",
+            Action::Collapse => "Collapse process children (in tree view)",
+            Action::Throttle => "Throttle selected process",
+            Action::RemoveThrottle => "Remove throttle (press again to confirm)",
+            Action::Undo => "Undo the last throttle change (up to 10)",
+            Action::ToggleMark => "Mark/unmark process (kept unthrottled by throttle-all-except)",
+            Action::TogglePin => "Pin/unpin process to the top of the list",
+            Action::ThrottleAllExcept => "Throttle all unmarked processes (toggle)",
+            Action::StandingRules => "Standing rules: throttle processes as they start",
+            Action::Yank => "Copy the selected process/connection (or modal text) to the clipboard",
+            Action::CopyCommand => "Show the CLI command for the selected throttle",
+            Action::ToggleGraph => "Toggle bandwidth graph",
+            Action::ToggleGraphTime => "Switch between time ago and clock time (in graph)",
+            Action::FreezeSort => "Freeze/unfreeze sort order",
+            Action::Backends => "View/switch backends",
+            Action::CycleUploadBackend => "Switch to the next upload backend (new throttles)",
+            Action::CycleDownloadBackend => "Switch to the next download backend (new throttles)",
+            Action::ApplyConfigBackends => "Switch to the backends the edited config prefers",
+            Action::Logs => "View recent log messages",
+            Action::Help => "Toggle this help",
+            Action::Quit => "Quit (or close modal if open)",
+        }
+    }
+
+    pub fn category(&self) -> KeyCategory {
+        match self {
+            Action::MoveUp
+            | Action::MoveDown
+            | Action::PageUp
+            | Action::PageDown
+            | Action::ToggleInterfaces
+            | Action::CycleTrafficView
+            | Action::ViewDetails
+            | Action::NextTab
+            | Action::PreviousTab
+            | Action::ToggleInterfaceFilter
+            | Action::ToggleAllInterfaces
+            | Action::AddInterfacePattern
+            | Action::ToggleTreeView
+            | Action::ToggleContainerView
+            | Action::ToggleCmdline
+            | Action::ToggleTcpHealth
+            | Action::ToggleListenOnly
+            | Action::Expand
+            | Action::Collapse => KeyCategory::Navigation,
+            Action::Throttle
+            | Action::RemoveThrottle
+            | Action::Undo
+            | Action::ToggleMark
+            | Action::TogglePin
+            | Action::ThrottleAllExcept
+            | Action::StandingRules
+            | Action::Yank
+            | Action::CopyCommand
+            | Action::ToggleGraph
+            | Action::ToggleGraphTime
+            | Action::FreezeSort => KeyCategory::Actions,
+            Action::Backends
+            | Action::CycleUploadBackend
+            | Action::CycleDownloadBackend
+            | Action::ApplyConfigBackends
+            | Action::Logs
+            | Action::Help
+            | Action::Quit => KeyCategory::System,
+        }
+    }
+
+    /// Default keys for this action
+    pub fn default_keys(&self) -> &'static [&'static str] {
+        match self {
+            Action::MoveUp => &["Up", "k"],
+            Action::MoveDown => &["Down", "j"],
+            Action::PageUp => &["PageUp"],
+            Action::PageDown => &["PageDown"],
+            Action::ToggleInterfaces => &["i"],
+            Action::CycleTrafficView => &["l"],
+            Action::ViewDetails => &["Enter"],
+            Action::NextTab => &["Tab"],
+            Action::PreviousTab => &["BackTab"],
+            Action::ToggleInterfaceFilter => &["Space"],
+            Action::ToggleAllInterfaces => &["A", "a"],
+            Action::AddInterfacePattern => &["/"],
+            Action::ToggleTreeView => &["T"],
+            Action::ToggleContainerView => &["C"],
+            Action::ToggleCmdline => &["c"],
+            Action::ToggleTcpHealth => &["R"],
+            Action::ToggleListenOnly => &["z"],
+            Action::Expand => &["Right"],
+            Action::Collapse => &["Left"],
+            Action::Throttle => &["t"],
+            Action::RemoveThrottle => &["r"],
+            Action::Undo => &["u"],
+            Action::ToggleMark => &["m"],
+            Action::TogglePin => &["P"],
+            Action::ThrottleAllExcept => &["E"],
+            Action::StandingRules => &["S"],
+            Action::Yank => &["y"],
+            Action::CopyCommand => &["Y"],
+            Action::ToggleGraph => &["g"],
+            Action::ToggleGraphTime => &["w"],
+            Action::FreezeSort => &["f"],
+            Action::Backends => &["b"],
+            Action::CycleUploadBackend => &["U"],
+            Action::CycleDownloadBackend => &["D"],
+            Action::ApplyConfigBackends => &["B"],
+            Action::Logs => &["L"],
+            Action::Help => &["h", "?"],
+            Action::Quit => &["q", "Esc"],
+        }
+    }
+}
+
+/// A single key (with optional Ctrl/Alt/Shift modifiers)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeySpec {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeySpec {
+    /// Parse a key like "k", "Enter", "PageDown", "ctrl+d" or "F5"
+    pub fn parse(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if s.is_empty() {
+            bail!("Empty key");
+        }
+
+        // Split off modifiers ("ctrl+d", "alt++", but a lone "+" is the plus key)
+        let (mod_str, key_str) = match s.strip_suffix("++") {
+            Some(prefix) => (prefix, "+"),
+            None => match s.rsplit_once('+') {
+                Some((m, k)) if !k.is_empty() => (m, k),
+                _ => ("", s),
+            },
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+        for m in mod_str.split('+').filter(|m| !m.is_empty()) {
+            modifiers |= match m.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => bail!("Unknown modifier '{}' in key '{}'", m, s),
+            };
+        }
+
+        let mut chars = key_str.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key_str.to_lowercase().as_str() {
+                "up" | "↑" => KeyCode::Up,
+                "down" | "↓" => KeyCode::Down,
+                "left" | "←" => KeyCode::Left,
+                "right" | "→" => KeyCode::Right,
+                "enter" | "return" => KeyCode::Enter,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "space" => KeyCode::Char(' '),
+                "esc" | "escape" => KeyCode::Esc,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n) if (1..=12).contains(&n) => KeyCode::F(n),
+                    _ => bail!("Unknown key '{}'", s),
+                },
+            },
+        };
+
+        Ok(Self::normalize(code, modifiers))
+    }
+
+    /// Terminals report Shift through the character itself (and Shift+Tab as BackTab),
+    /// so fold Shift into the key code where it applies
+    fn normalize(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let shift = modifiers.contains(KeyModifiers::SHIFT);
+        let code = match code {
+            KeyCode::Char(c) if shift => KeyCode::Char(c.to_ascii_uppercase()),
+            KeyCode::Tab if shift => KeyCode::BackTab,
+            other => other,
+        };
+        let modifiers = match code {
+            KeyCode::Char(_) | KeyCode::BackTab => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        Self { code, modifiers }
+    }
+
+    pub fn matches(&self, event: &KeyEvent) -> bool {
+        let pressed = Self::normalize(event.code, event.modifiers);
+        pressed.code == self.code && pressed.modifiers == self.modifiers
+    }
+
+    /// Human-readable label for help and status bar
+    pub fn label(&self) -> String {
+        let key = match self.code {
+            KeyCode::Up => "↑".to_string(),
+            KeyCode::Down => "↓".to_string(),
+            KeyCode::Left => "←".to_string(),
+            KeyCode::Right => "→".to_string(),
+            KeyCode::Enter => "Enter".to_string(),
+            KeyCode::Tab => "Tab".to_string(),
+            KeyCode::BackTab => "Shift+Tab".to_string(),
+            KeyCode::Char(' ') => "Space".to_string(),
+            KeyCode::Esc => "Esc".to_string(),
+            KeyCode::PageUp => "PgUp".to_string(),
+            KeyCode::PageDown => "PgDn".to_string(),
+            KeyCode::Char(c) => c.to_string(),
+            KeyCode::F(n) => format!("F{}", n),
+            other => format!("{:?}", other),
+        };
+
+        let mut label = String::new();
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            label.push_str("Ctrl+");
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            label.push_str("Alt+");
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            label.push_str("Shift+");
+        }
+        label.push_str(&key);
+        label
+    }
+
+    /// Ctrl+C always force quits and can't be rebound
+    fn is_reserved(&self) -> bool {
+        self.code == KeyCode::Char('c') && self.modifiers == KeyModifiers::CONTROL
+    }
+}
+
+/// Resolved action → keys map used for dispatch, help and status bar
+#[derive(Debug, Clone)]
+pub struct KeyMap {
+    /// Keys per action, in `Action::ALL` order
+    bindings: Vec<(Action, Vec<KeySpec>)>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        Self::from_overrides(&HashMap::new()).expect("default keybindings are valid")
+    }
+}
+
+impl KeyMap {
+    /// Build the key map from config overrides (action name → keys).
+    /// An overridden action replaces all of its default keys; an empty list unbinds it.
+    pub fn from_overrides(overrides: &HashMap<String, Vec<String>>) -> Result<Self> {
+        for name in overrides.keys() {
+            if Action::from_config_name(name).is_none() {
+                bail!("Unknown keybinding action '{}'", name);
+            }
+        }
+
+        let mut bindings = Vec::with_capacity(Action::ALL.len());
+        for action in Action::ALL {
+            let keys = match overrides.get(action.config_name()) {
+                Some(keys) => keys
+                    .iter()
+                    .map(|k| KeySpec::parse(k))
+                    .collect::<Result<Vec<_>>>()
+                    .with_context(|| format!("Invalid key for '{}'", action.config_name()))?,
+                None => action
+                    .default_keys()
+                    .iter()
+                    .map(|k| KeySpec::parse(k))
+                    .collect::<Result<Vec<_>>>()?,
+            };
+
+            if let Some(key) = keys.iter().find(|k| k.is_reserved()) {
+                bail!(
+                    "{} is reserved for force quit and can't be bound to '{}'",
+                    key.label(),
+                    action.config_name()
+                );
+            }
+
+            bindings.push((action, keys));
+        }
+
+        let keymap = Self { bindings };
+        keymap.validate()?;
+        Ok(keymap)
+    }
+
+    /// Reject keys bound to more than one action
+    fn validate(&self) -> Result<()> {
+        for (i, (action, keys)) in self.bindings.iter().enumerate() {
+            for (other, other_keys) in &self.bindings[i + 1..] {
+                if let Some(key) = keys.iter().find(|k| other_keys.contains(k)) {
+                    bail!(
+                        "Key '{}' is bound to both '{}' and '{}'",
+                        key.label(),
+                        action.config_name(),
+                        other.config_name()
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolve a pressed key to its action
+    pub fn action_for(&self, event: &KeyEvent) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(_, keys)| keys.iter().any(|k| k.matches(event)))
+            .map(|(action, _)| *action)
+    }
+
+    pub fn keys(&self, action: Action) -> &[KeySpec] {
+        self.bindings
+            .iter()
+            .find(|(a, _)| *a == action)
+            .map(|(_, keys)| keys.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// All keys for an action, e.g. "↑/k"
+    pub fn label(&self, action: Action) -> String {
+        let keys = self.keys(action);
+        if keys.is_empty() {
+            return "(unbound)".to_string();
+        }
+        keys.iter().map(|k| k.label()).collect::<Vec<_>>().join("/")
+    }
+
+    /// First key for an action, for compact displays
+    fn primary_label(&self, action: Action) -> String {
+        self.keys(action)
+            .first()
+            .map(|k| k.label())
+            .unwrap_or_else(|| "-".to_string())
+    }
+}
+
 /// Get all keybindings
-pub fn get_all_keybindings() -> Vec<KeyBinding> {
-    vec![
-        // Navigation
-        KeyBinding {
-            key: "↑/k",
-            description: "Move selection up",
-            category: KeyCategory::Navigation,
-        },
-        KeyBinding {
-            key: "↓/j",
-            description: "Move selection down",
-            category: KeyCategory::Navigation,
-        },
-        KeyBinding {
-            key: "i",
-            description: "Toggle interface view",
-            category: KeyCategory::Navigation,
-        },
-        KeyBinding {
-            key: "l",
-            description: "Cycle traffic view (All/Internet/Local)",
-            category: KeyCategory::Navigation,
-        },
-        KeyBinding {
-            key: "Enter",
-            description: "View details (process or interface)",
-            category: KeyCategory::Navigation,
-        },
-        KeyBinding {
-            key: "Tab",
-            description: "Switch tabs (in detail view)",
-            category: KeyCategory::Navigation,
-        },
-        KeyBinding {
-            key: "Space",
-            description: "Toggle interface filter (in interface list)",
-            category: KeyCategory::Navigation,
-        },
-        KeyBinding {
-            key: "A",
-            description: "Toggle All/None interfaces (in interface list)",
-            category: KeyCategory::Navigation,
-        },
-        // Actions
-        KeyBinding {
-            key: "t",
-            description: "Throttle selected process",
-            category: KeyCategory::Actions,
-        },
-        KeyBinding {
-            key: "r",
-            description: "Remove throttle",
-            category: KeyCategory::Actions,
-        },
-        KeyBinding {
-            key: "g",
-            description: "Toggle bandwidth graph",
-            category: KeyCategory::Actions,
-        },
-        KeyBinding {
-            key: "f",
-            description: "Freeze/unfreeze sort order",
-            category: KeyCategory::Actions,
-        },
-        // System
-        KeyBinding {
-            key: "b",
-            description: "View/switch backends",
-            category: KeyCategory::System,
-        },
-        KeyBinding {
-            key: "h/?",
-            description: "Toggle this help",
-            category: KeyCategory::System,
-        },
-        KeyBinding {
-            key: "q/Esc",
-            description: "Quit (or close modal if open)",
-            category: KeyCategory::System,
-        },
-        KeyBinding {
-            key: "Ctrl+C",
-            description: "Force quit (always exits)",
-            category: KeyCategory::System,
-        },
-    ]
+pub fn get_all_keybindings(keymap: &KeyMap) -> Vec<KeyBinding> {
+    let mut bindings: Vec<KeyBinding> = Action::ALL
+        .iter()
+        .map(|action| KeyBinding {
+            key: keymap.label(*action),
+            description: action.description(),
+            category: action.category(),
+        })
+        .collect();
+
+    bindings.push(KeyBinding {
+        key: "Ctrl+C".to_string(),
+        description: "Force quit (always exits)",
+        category: KeyCategory::System,
+    });
+
+    bindings
 }
 
 /// Get keybindings for the status bar (most common ones)
-pub fn get_status_bar_keybindings() -> Vec<(&'static str, &'static str)> {
+pub fn get_status_bar_keybindings(keymap: &KeyMap) -> Vec<(String, &'static str)> {
     vec![
-        ("↑↓", "Navigate"),
-        ("i", "Interfaces"),
-        ("l", "Traffic"),
-        ("t", "Throttle"),
-        ("f", "Freeze"),
-        ("b", "Backends"),
-        ("h", "Help"),
-        ("q/Ctrl+C", "Quit"),
+        (
+            format!(
+                "{}{}",
+                keymap.primary_label(Action::MoveUp),
+                keymap.primary_label(Action::MoveDown)
+            ),
+            "Navigate",
+        ),
+        (keymap.primary_label(Action::ToggleInterfaces), "Interfaces"),
+        (keymap.primary_label(Action::CycleTrafficView), "Traffic"),
+        (keymap.primary_label(Action::Throttle), "Throttle"),
+        (keymap.primary_label(Action::FreezeSort), "Freeze"),
+        (keymap.primary_label(Action::Backends), "Backends"),
+        (keymap.primary_label(Action::Help), "Help"),
+        (
+            format!("{}/Ctrl+C", keymap.primary_label(Action::Quit)),
+            "Quit",
+        ),
     ]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn test_default_keymap_resolves_actions() {
+        let keymap = KeyMap::default();
+        assert_eq!(
+            keymap.action_for(&press(KeyCode::Char('j'), KeyModifiers::NONE)),
+            Some(Action::MoveDown)
+        );
+        assert_eq!(
+            keymap.action_for(&press(KeyCode::Char('A'), KeyModifiers::SHIFT)),
+            Some(Action::ToggleAllInterfaces)
+        );
+        assert_eq!(
+            keymap.action_for(&press(KeyCode::Char('w'), KeyModifiers::NONE)),
+            Some(Action::ToggleGraphTime)
+        );
+        assert_eq!(keymap.label(Action::MoveUp), "↑/k");
+        assert_eq!(
+            keymap.action_for(&press(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_override_and_conflicts() {
+        let mut overrides = HashMap::new();
+        overrides.insert("page-down".to_string(), vec!["ctrl+d".to_string()]);
+        let keymap = KeyMap::from_overrides(&overrides).unwrap();
+        assert_eq!(
+            keymap.action_for(&press(KeyCode::Char('d'), KeyModifiers::CONTROL)),
+            Some(Action::PageDown)
+        );
+        assert_eq!(
+            keymap.action_for(&press(KeyCode::PageDown, KeyModifiers::NONE)),
+            None
+        );
+
+        // 'k' is already move-up
+        overrides.insert("throttle".to_string(), vec!["k".to_string()]);
+        assert!(KeyMap::from_overrides(&overrides).is_err());
+
+        let mut overrides = HashMap::new();
+        overrides.insert("quit".to_string(), vec!["ctrl+c".to_string()]);
+        assert!(KeyMap::from_overrides(&overrides).is_err());
+
+        let mut overrides = HashMap::new();
+        overrides.insert("no-such-action".to_string(), vec!["x".to_string()]);
+        assert!(KeyMap::from_overrides(&overrides).is_err());
+    }
+}
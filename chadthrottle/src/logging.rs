@@ -0,0 +1,247 @@
+// Log output
+//
+// The TUI logs to a size-rotated file (stderr would draw over the screen); the
+// non-interactive modes keep logging to stderr when RUST_LOG is set, unless
+// --log-file is given. The TUI also keeps the last messages in memory for its
+// log viewer.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use pretty_env_logger::env_logger;
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Rotate the log file before it grows past this
+const MAX_LOG_FILE_SIZE: u64 = 5 * 1024 * 1024;
+
+/// Log files kept, counting the active one (chadthrottle.log, .log.1, .log.2)
+const LOG_FILES_KEPT: usize = 3;
+
+/// Messages kept in memory for the log viewer
+const RECENT_LOG_LINES: usize = 500;
+
+/// Last `RECENT_LOG_LINES` messages that passed the filter, oldest first
+static RECENT: Mutex<VecDeque<LogLine>> = Mutex::new(VecDeque::new());
+
+/// A log message kept for the log viewer
+#[derive(Debug, Clone)]
+pub struct LogLine {
+    pub time: DateTime<Local>,
+    pub level: log::Level,
+    pub message: String,
+}
+
+/// The most recent log messages, oldest first
+pub fn recent() -> Vec<LogLine> {
+    RECENT.lock().unwrap().iter().cloned().collect()
+}
+
+/// Default log file for the TUI (~/.local/share/chadthrottle/chadthrottle.log on Linux)
+pub fn default_log_path() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|dir| dir.join("chadthrottle").join("chadthrottle.log"))
+}
+
+/// Log to `path`, rotating by size. RUST_LOG sets the filter (default: info)
+pub fn init_file(path: &Path) -> Result<()> {
+    let file = RotatingFile::open(path, MAX_LOG_FILE_SIZE)?;
+    init_recent(Box::new(file))
+}
+
+/// Only keep messages in memory for the log viewer (the log file couldn't be opened)
+pub fn init_memory_only() -> Result<()> {
+    init_recent(Box::new(io::sink()))
+}
+
+fn init_recent(target: Box<dyn Write + Send>) -> Result<()> {
+    let inner = pretty_env_logger::formatted_timed_builder()
+        .filter_level(log::LevelFilter::Info)
+        .parse_default_env()
+        .write_style(env_logger::WriteStyle::Never)
+        .target(env_logger::Target::Pipe(target))
+        .build();
+    let max_level = inner.filter();
+    log::set_boxed_logger(Box::new(RecentLogger { inner }))
+        .context("Logger already initialized")?;
+    log::set_max_level(max_level);
+    Ok(())
+}
+
+/// Log to stderr if RUST_LOG is set (CLI and headless modes)
+pub fn init_stderr() {
+    if std::env::var("RUST_LOG").is_ok() {
+        pretty_env_logger::formatted_builder()
+            .parse_default_env()
+            .init();
+    }
+}
+
+/// Passes records on to env_logger, keeping a copy of the last few in memory
+struct RecentLogger {
+    inner: env_logger::Logger,
+}
+
+impl log::Log for RecentLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.inner.matches(record) {
+            return;
+        }
+
+        let mut recent = RECENT.lock().unwrap();
+        if recent.len() == RECENT_LOG_LINES {
+            recent.pop_front();
+        }
+        recent.push_back(LogLine {
+            time: Local::now(),
+            level: record.level(),
+            message: record.args().to_string(),
+        });
+        drop(recent);
+
+        self.inner.log(record);
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+/// Append-only log file, rotated to `<path>.1`, `<path>.2`, ... when it gets too big
+struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_size: u64,
+}
+
+impl RotatingFile {
+    fn open(path: &Path, max_size: u64) -> Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).context(format!("Failed to create {:?}", dir))?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .context(format!("Failed to open log file {:?}", path))?;
+        let size = file.metadata()?.len();
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+            size,
+            max_size,
+        })
+    }
+
+    /// Shift the rotated files up by one (dropping the oldest) and start a new file
+    fn rotate(&mut self) -> io::Result<()> {
+        for index in (1..LOG_FILES_KEPT).rev() {
+            let from = if index == 1 {
+                self.path.clone()
+            } else {
+                rotated_path(&self.path, index - 1)
+            };
+            // Older files don't exist until the log has been rotated enough times
+            let _ = fs::rename(from, rotated_path(&self.path, index));
+        }
+
+        self.file = File::create(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // env_logger writes each record in one call, so records are never split
+        if self.size > 0 && self.size + buf.len() as u64 > self.max_size {
+            self.rotate()?;
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", index));
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotation_keeps_three_files() {
+        let dir = std::env::temp_dir().join(format!("chadthrottle-log-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("chadthrottle.log");
+
+        let mut file = RotatingFile::open(&path, 10).unwrap();
+        for record in ["first\n", "second\n", "third\n", "fourth\n"] {
+            file.write_all(record.as_bytes()).unwrap();
+        }
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "fourth\n");
+        assert_eq!(
+            fs::read_to_string(rotated_path(&path, 1)).unwrap(),
+            "third\n"
+        );
+        assert_eq!(
+            fs::read_to_string(rotated_path(&path, 2)).unwrap(),
+            "second\n"
+        );
+        assert!(!rotated_path(&path, 3).exists());
+
+        // Reopening appends to the existing file
+        let mut file = RotatingFile::open(&path, 10).unwrap();
+        file.write_all(b"5\n").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "fourth\n5\n");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_recent_logger_keeps_last_filtered_messages() {
+        use log::Log;
+
+        let logger = RecentLogger {
+            inner: env_logger::Builder::new()
+                .filter_level(log::LevelFilter::Warn)
+                .target(env_logger::Target::Pipe(Box::new(io::sink())))
+                .build(),
+        };
+        let log_at = |level, message: &str| {
+            logger.log(
+                &log::Record::builder()
+                    .level(level)
+                    .args(format_args!("{}", message))
+                    .build(),
+            )
+        };
+
+        log_at(log::Level::Info, "filtered out");
+        for i in 0..=RECENT_LOG_LINES {
+            log_at(log::Level::Warn, &i.to_string());
+        }
+
+        let recent = recent();
+        assert_eq!(recent.len(), RECENT_LOG_LINES);
+        assert_eq!(recent[0].message, "1");
+        assert_eq!(recent[RECENT_LOG_LINES - 1].level, log::Level::Warn);
+        assert!(recent.iter().all(|line| line.message != "filtered out"));
+    }
+}
@@ -1,370 +1,77 @@
-mod backends;
-mod config;
-mod history;
+mod alerts;
+mod cli_throttles;
+mod clipboard;
+mod config_reload;
+mod container_view;
+mod diagnostics;
+mod instance_status;
 mod keybindings;
-
-// NetworkMonitor module - conditionally compiled based on available backends
-#[cfg(feature = "monitor-pnet")]
-mod monitor;
-
-// Windows polling monitor wrapper when pnet is not available
-#[cfg(all(target_os = "windows", not(feature = "monitor-pnet")))]
-mod monitor {
-    use crate::backends::monitor::MonitorBackend;
-    use crate::backends::monitor::windows_poll::WindowsPollingMonitor;
-    use crate::process::{InterfaceMap, ProcessMap};
-    use anyhow::Result;
-    use std::collections::HashMap;
-    use std::time::{Duration, Instant};
-    use tokio::sync::mpsc;
-
-    /// Commands sent from UI thread to monitoring thread
-    pub enum MonitorCommand {
-        /// Switch to a different socket mapper backend
-        SwitchSocketMapper {
-            backend_name: String,
-            /// Channel to send back success or error (hot-swap, no thread restart)
-            response_tx: tokio::sync::oneshot::Sender<Result<()>>,
-        },
-        /// Signal to shutdown the monitoring thread
-        Shutdown,
-    }
-
-    /// Update data sent from monitoring thread to UI thread
-    #[derive(Debug, Clone)]
-    pub struct MonitorUpdateData {
-        pub process_map: ProcessMap,
-        pub interface_map: InterfaceMap,
-        pub socket_mapper_name: String,
-        pub socket_mapper_capabilities: crate::backends::BackendCapabilities,
-    }
-
-    /// Update messages sent from monitoring thread to UI thread
-    pub type MonitorUpdate = MonitorUpdateData;
-
-    pub struct NetworkMonitor {
-        backend: WindowsPollingMonitor,
-    }
-
-    pub struct ProcessBandwidth {
-        name: String,
-        rx_bytes: u64,
-        tx_bytes: u64,
-        last_rx_bytes: u64,
-        last_tx_bytes: u64,
-    }
-
-    impl NetworkMonitor {
-        pub fn with_socket_mapper(_: Option<&str>) -> Result<Self> {
-            log::info!("Using Windows polling monitor backend");
-            Ok(NetworkMonitor {
-                backend: WindowsPollingMonitor::new()?,
-            })
-        }
-
-        pub fn get_monitoring_backend_name(&self) -> &'static str {
-            "windows-poll"
-        }
-
-        pub fn get_socket_mapper_info(&self) -> (&str, &crate::backends::BackendCapabilities) {
-            // Windows polling monitor uses iphelper socket mapper
-            static CAPS: crate::backends::BackendCapabilities =
-                crate::backends::BackendCapabilities {
-                    ipv4_support: true,
-                    ipv6_support: true,
-                    per_process: true,
-                    per_connection: true,
-                };
-            ("iphelper", &CAPS)
-        }
-
-        pub fn update(&mut self) -> Result<(ProcessMap, InterfaceMap)> {
-            self.backend.update()
-        }
-
-        pub fn extract_bandwidth_data(
-            &self,
-        ) -> (HashMap<i32, ProcessBandwidth>, HashMap<i32, Instant>) {
-            // Not implemented for polling backend
-            (HashMap::new(), HashMap::new())
-        }
-
-        pub fn restore_bandwidth_data(
-            &mut self,
-            _: HashMap<i32, ProcessBandwidth>,
-            _: HashMap<i32, Instant>,
-        ) {
-            // No-op for polling backend
-        }
-
-        pub fn get_bandwidth_data(&self) -> HashMap<i32, Vec<crate::history::BandwidthSample>> {
-            // Not implemented for polling backend
-            HashMap::new()
-        }
-
-        /// Run the monitoring loop in a background thread
-        pub fn run_monitoring_loop(
-            mut self,
-            mut cmd_rx: mpsc::UnboundedReceiver<MonitorCommand>,
-            update_tx: mpsc::UnboundedSender<MonitorUpdate>,
-        ) {
-            log::info!("Starting monitoring background thread (windows-poll)");
-            let mut last_update = Instant::now();
-
-            loop {
-                // Check for commands (non-blocking)
-                match cmd_rx.try_recv() {
-                    Ok(MonitorCommand::Shutdown) => {
-                        log::info!("Monitoring thread received shutdown command");
-                        break;
-                    }
-                    Ok(MonitorCommand::SwitchSocketMapper {
-                        backend_name: _,
-                        response_tx,
-                    }) => {
-                        log::warn!("Socket mapper switching not supported in windows-poll backend");
-                        let _ = response_tx.send(Err(anyhow::anyhow!("Not supported")));
-                    }
-                    Err(mpsc::error::TryRecvError::Empty) => {
-                        // No command, continue monitoring
-                    }
-                    Err(mpsc::error::TryRecvError::Disconnected) => {
-                        log::warn!("Command channel disconnected, shutting down monitoring thread");
-                        break;
-                    }
-                }
-
-                // Perform update approximately once per second
-                let now = Instant::now();
-                if now.duration_since(last_update) >= Duration::from_secs(1) {
-                    match self.update() {
-                        Ok((process_map, interface_map)) => {
-                            // Send update to UI thread (non-blocking) with socket mapper info
-                            let update_data = MonitorUpdateData {
-                                process_map,
-                                interface_map,
-                                socket_mapper_name: "iphelper".to_string(),
-                                socket_mapper_capabilities: crate::backends::BackendCapabilities {
-                                    ipv4_support: true,
-                                    ipv6_support: true,
-                                    per_process: true,
-                                    per_connection: true,
-                                },
-                            };
-
-                            if update_tx.send(update_data).is_err() {
-                                log::warn!(
-                                    "Update channel disconnected, shutting down monitoring thread"
-                                );
-                                break;
-                            }
-                        }
-                        Err(e) => {
-                            log::error!("Monitor update failed: {}", e);
-                        }
-                    }
-
-                    last_update = now;
-                }
-
-                // Sleep briefly to avoid busy-waiting
-                std::thread::sleep(Duration::from_millis(100));
-            }
-
-            log::info!("Monitoring background thread exiting");
-        }
-    }
-}
-
-// Stub monitor module when no backends are available (non-Windows without pnet)
-#[cfg(not(any(feature = "monitor-pnet", target_os = "windows")))]
-mod monitor {
-    use crate::process::{InterfaceMap, ProcessMap};
-    use anyhow::Result;
-    use std::collections::HashMap;
-    use std::time::{Duration, Instant};
-    use tokio::sync::mpsc;
-
-    /// Commands sent from UI thread to monitoring thread
-    pub enum MonitorCommand {
-        /// Switch to a different socket mapper backend
-        SwitchSocketMapper {
-            backend_name: String,
-            /// Channel to send back success or error (hot-swap, no thread restart)
-            response_tx: tokio::sync::oneshot::Sender<Result<()>>,
-        },
-        /// Signal to shutdown the monitoring thread
-        Shutdown,
-    }
-
-    /// Update data sent from monitoring thread to UI thread
-    #[derive(Debug, Clone)]
-    pub struct MonitorUpdateData {
-        pub process_map: ProcessMap,
-        pub interface_map: InterfaceMap,
-        pub socket_mapper_name: String,
-        pub socket_mapper_capabilities: crate::backends::BackendCapabilities,
-    }
-
-    /// Update messages sent from monitoring thread to UI thread
-    pub type MonitorUpdate = MonitorUpdateData;
-
-    pub struct NetworkMonitor;
-
-    pub struct ProcessBandwidth {
-        name: String,
-        rx_bytes: u64,
-        tx_bytes: u64,
-        last_rx_bytes: u64,
-        last_tx_bytes: u64,
-    }
-
-    impl NetworkMonitor {
-        pub fn with_socket_mapper(_: Option<&str>) -> Result<Self> {
-            log::warn!("No monitoring backend available - monitoring disabled");
-            Ok(NetworkMonitor)
-        }
-
-        pub fn get_socket_mapper_info(&self) -> (&str, &crate::backends::BackendCapabilities) {
-            static CAPS: crate::backends::BackendCapabilities =
-                crate::backends::BackendCapabilities {
-                    ipv4_support: false,
-                    ipv6_support: false,
-                    per_process: false,
-                    per_connection: false,
-                };
-            ("none", &CAPS)
-        }
-
-        pub fn update(&mut self) -> Result<(ProcessMap, InterfaceMap)> {
-            Ok((HashMap::new(), HashMap::new()))
-        }
-
-        pub fn extract_bandwidth_data(
-            &self,
-        ) -> (HashMap<i32, ProcessBandwidth>, HashMap<i32, Instant>) {
-            (HashMap::new(), HashMap::new())
-        }
-
-        pub fn restore_bandwidth_data(
-            &mut self,
-            _: HashMap<i32, ProcessBandwidth>,
-            _: HashMap<i32, Instant>,
-        ) {
-            // No-op
-        }
-
-        pub fn get_bandwidth_data(&self) -> HashMap<i32, Vec<crate::history::BandwidthSample>> {
-            HashMap::new()
-        }
-
-        pub fn get_monitoring_backend_name(&self) -> &'static str {
-            "none"
-        }
-
-        /// Run the monitoring loop in a background thread (stub implementation)
-        pub fn run_monitoring_loop(
-            self,
-            mut cmd_rx: mpsc::UnboundedReceiver<MonitorCommand>,
-            update_tx: mpsc::UnboundedSender<MonitorUpdate>,
-        ) {
-            log::warn!("Monitoring background thread started but no backend available");
-
-            loop {
-                // Check for commands
-                match cmd_rx.try_recv() {
-                    Ok(MonitorCommand::Shutdown) => {
-                        break;
-                    }
-                    Ok(MonitorCommand::SwitchSocketMapper {
-                        backend_name: _,
-                        response_tx,
-                    }) => {
-                        let _ = response_tx.send(Err(anyhow::anyhow!("No backend available")));
-                    }
-                    Err(mpsc::error::TryRecvError::Disconnected) => {
-                        break;
-                    }
-                    _ => {}
-                }
-
-                // Send empty updates periodically
-                let _now = Instant::now();
-                std::thread::sleep(Duration::from_secs(1));
-
-                let update_data = MonitorUpdateData {
-                    process_map: HashMap::new(),
-                    interface_map: HashMap::new(),
-                    socket_mapper_name: "none".to_string(),
-                    socket_mapper_capabilities: crate::backends::BackendCapabilities {
-                        ipv4_support: false,
-                        ipv6_support: false,
-                        per_process: false,
-                        per_connection: false,
-                    },
-                };
-
-                if update_tx.send(update_data).is_err() {
-                    break;
-                }
-            }
-
-            log::info!("Stub monitoring thread exiting");
-        }
-    }
-}
-
-mod process;
-mod traffic_classifier;
+mod logging;
+mod privileges;
+mod process_tree;
+mod quotas;
+mod reverse_dns;
+mod snapshot;
+mod theme;
+mod throttle_command;
 mod ui;
+mod undo;
+mod utilization;
+mod webhooks;
+
+// The engine lives in chadthrottle-core; imported here so `crate::backends` etc. resolve
+use chadthrottle_core::limits::{parse_bandwidth_limit, parse_throttle_limit};
+use chadthrottle_core::{
+    backends, config, connection_events, control_path, events, format, history, history_store,
+    interface_filter, limits, monitor, process, standing_rules, traffic_classifier,
+};
 
-use anyhow::Result;
-use clap::Parser;
+use anyhow::{Context, Result};
+use clap::{ArgGroup, Parser, Subcommand};
 use crossterm::{
+    cursor,
     event::{
-        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, KeyModifiers,
-        MouseButton, MouseEvent, MouseEventKind,
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind,
+        KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
     },
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
 use ratatui::{Terminal, backend::CrosstermBackend};
-use std::io;
+use std::io::{self, IsTerminal};
 use std::time::Duration;
 use tokio::time::interval;
 
+use crate::backends::monitor::CaptureOptions;
 use crate::backends::throttle::ThrottleManager;
 use crate::backends::throttle::{
-    detect_download_backends, detect_upload_backends, select_download_backend,
+    ThrottleError, detect_download_backends, detect_upload_backends, select_download_backend,
     select_upload_backend,
 };
+use crate::cli_throttles::CliThrottle;
+use crate::control_path::ControlPath;
+use crate::events::{ThrottleEvent, ThrottleEventKind};
+use crate::keybindings::Action;
 use crate::monitor::{MonitorCommand, MonitorUpdate, NetworkMonitor};
 use crate::process::ThrottleLimit;
+use crate::standing_rules::StandingRule;
+use crate::throttle_command::ThrottleCommand;
 use crate::ui::AppState;
-
-/// Format bytes as human-readable string (e.g., "1.5 MB", "500 KB")
-fn human_readable(bytes: u64) -> String {
-    const KB: u64 = 1024;
-    const MB: u64 = KB * 1024;
-    const GB: u64 = MB * 1024;
-
-    if bytes >= GB {
-        format!("{:.1} GB", bytes as f64 / GB as f64)
-    } else if bytes >= MB {
-        format!("{:.1} MB", bytes as f64 / MB as f64)
-    } else if bytes >= KB {
-        format!("{:.1} KB", bytes as f64 / KB as f64)
-    } else {
-        format!("{} B", bytes)
-    }
-}
+use crate::undo::ThrottleMutation;
 
 /// ChadThrottle - A TUI network monitor and throttler for Linux
 #[derive(Parser, Debug)]
 #[command(name = "chadthrottle")]
 #[command(version = "0.6.0")]
 #[command(about = "Network monitor and throttler - like NetLimiter but chad", long_about = None)]
+#[command(group(ArgGroup::new("targets").args(["pid", "pids", "pid_file"]).multiple(true)))]
+#[command(group(ArgGroup::new("reports").args(["diagnose", "status"])))]
+#[command(group(ArgGroup::new("removal").args(["remove", "remove_all"])))]
+#[command(group(ArgGroup::new("throttled").args(["pid", "pids", "pid_file", "all_except"]).multiple(true)))]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Upload throttling backend to use
     #[arg(long, value_name = "BACKEND")]
     upload_backend: Option<String>,
@@ -377,10 +84,35 @@ struct Args {
     #[arg(long, value_name = "BACKEND")]
     socket_mapper: Option<String>,
 
+    /// Monitor backend to use (e.g. pnet, nettop; default: best available)
+    #[arg(long, value_name = "BACKEND")]
+    monitor_backend: Option<String>,
+
     /// List all available backends and exit
     #[arg(long)]
     list_backends: bool,
 
+    /// Print a system/backend diagnostics report (for bug reports) and exit
+    #[arg(long, alias = "diagnostics")]
+    diagnose: bool,
+
+    /// Print the report as JSON (with --diagnose or --status)
+    #[arg(long, requires = "reports")]
+    json: bool,
+
+    /// Write the whole config to FILE and exit (same as `config export -o FILE`)
+    #[arg(long, value_name = "FILE", conflicts_with = "import_config")]
+    export_config: Option<std::path::PathBuf>,
+
+    /// Replace the config with FILE and exit, backing up the current one first
+    /// (same as `config import --replace FILE`)
+    #[arg(long, value_name = "FILE")]
+    import_config: Option<std::path::PathBuf>,
+
+    /// Replace saved throttles without asking (--import-config, `config import --replace`)
+    #[arg(long, global = true)]
+    yes: bool,
+
     /// Don't restore saved throttles on startup (default: restore is enabled)
     #[arg(long)]
     no_restore: bool,
@@ -389,26 +121,252 @@ struct Args {
     #[arg(long)]
     no_save: bool,
 
+    /// Don't warn about traffic shaping set up outside chadthrottle (tc qdiscs, nftables limits)
+    #[arg(long, global = true)]
+    ignore_external: bool,
+
     // CLI mode arguments
-    /// PID to throttle (CLI mode - skips TUI)
+    /// PID to throttle (repeatable; CLI mode - skips TUI)
     #[arg(long, value_name = "PID")]
-    pid: Option<i32>,
-
-    /// Download limit (e.g., "1M", "500K", "1.5M") - requires --pid
+    pid: Vec<i32>,
+
+    /// Comma-separated PIDs to throttle (CLI mode)
+    #[arg(long, value_name = "PIDS", value_delimiter = ',')]
+    pids: Vec<i32>,
+
+    /// File listing PIDs to throttle, one per line (`#` starts a comment) (CLI mode)
+    #[arg(long, value_name = "FILE")]
+    pid_file: Option<std::path::PathBuf>,
+
+    /// Keep the throttles that could be applied when others fail (default: remove them and exit)
+    #[arg(long, requires = "targets")]
+    best_effort: bool,
+
+    /// Throttle every network-active process except these comma-separated names or glob
+    /// patterns (e.g. "zoom*,firefox"), including processes that start later (CLI mode)
+    #[arg(
+        long,
+        value_name = "NAMES",
+        value_delimiter = ',',
+        conflicts_with_all = ["targets", "remove", "remove_all", "monitor_only"]
+    )]
+    all_except: Vec<String>,
+
+    /// Download limit (e.g., "1M", "500K", "1.5M", "2.5mbit") - requires --pid or --all-except
     #[arg(long, value_name = "LIMIT")]
     download_limit: Option<String>,
 
-    /// Upload limit (e.g., "1M", "500K", "1.5M") - requires --pid
+    /// Upload limit (e.g., "1M", "500K", "1.5M", "2.5mbit") - requires --pid or --all-except
     #[arg(long, value_name = "LIMIT")]
     upload_limit: Option<String>,
 
+    /// Upload rate guaranteed to the process, up to --upload-limit (e.g., "64K"; ignored by
+    /// backends that can't guarantee one) - requires --pid
+    #[arg(
+        long,
+        value_name = "LIMIT",
+        requires = "targets",
+        requires = "upload_limit"
+    )]
+    upload_minimum: Option<String>,
+
     /// Duration to run throttle in seconds (default: run until Ctrl+C)
     #[arg(long, value_name = "SECONDS")]
     duration: Option<u64>,
 
+    /// Only throttle traffic on this interface (repeatable; default: all interfaces) - requires --pid
+    #[arg(long = "interface", value_name = "NAME", requires = "targets")]
+    interfaces: Vec<String>,
+
+    /// Which traffic to throttle: all, internet (non-local addresses) or local (private,
+    /// link-local and loopback addresses) (default: all) - requires --pid or --all-except
+    #[arg(long, value_name = "TYPE", requires = "throttled")]
+    traffic_type: Option<crate::process::TrafficType>,
+
+    /// What to do with traffic over the limit: shape (queue) or drop (default: backend's choice) - requires --pid
+    #[arg(long, value_name = "POLICY", requires = "targets")]
+    policy: Option<crate::process::Policy>,
+
+    /// Rank against other throttled processes: high, normal or low (default: normal; ignored by
+    /// backends without a class scheduler) - requires --pid
+    #[arg(long, value_name = "PRIORITY", requires = "targets")]
+    priority: Option<crate::process::Priority>,
+
+    /// Throttle even if the process is on chadthrottle's own control path (its shell, terminal or sshd) - requires --pid
+    #[arg(long, requires = "targets")]
+    force: bool,
+
+    /// Remove the throttles on --pid, whichever CLI-mode instance applied them (or a
+    /// crashed instance left behind)
+    #[arg(
+        long,
+        requires = "targets",
+        conflicts_with_all = ["download_limit", "upload_limit", "duration", "best_effort"]
+    )]
+    remove: bool,
+
+    /// Show the throttles held by running chadthrottle instances, including which
+    /// standing rules applied them, and exit
+    #[arg(long, conflicts_with_all = ["targets", "remove", "remove_all"])]
+    status: bool,
+
+    /// Remove all throttles applied by CLI-mode instances or left behind by crashed ones
+    #[arg(long, conflicts_with_all = ["targets", "remove"])]
+    remove_all: bool,
+
+    /// With --remove or --remove-all, also delete the matching saved throttles from the
+    /// config so the TUI doesn't restore them
+    #[arg(long, requires = "removal")]
+    forget: bool,
+
     /// BPF attach method: auto (try link, fallback to legacy), link (bpf_link_create), legacy (bpf_prog_attach)
-    #[arg(long, value_name = "METHOD")]
+    #[arg(long, value_name = "METHOD", value_parser = ["auto", "link", "legacy"])]
     bpf_attach_method: Option<String>,
+
+    /// Detach leftover eBPF programs and remove pinned eBPF objects, then exit
+    #[arg(long)]
+    cleanup: bool,
+
+    /// Write logs to this file, rotated at 5 MB (default for the TUI:
+    /// ~/.local/share/chadthrottle/chadthrottle.log; other modes log to stderr with RUST_LOG)
+    #[arg(long, value_name = "PATH")]
+    log_file: Option<std::path::PathBuf>,
+
+    // Headless monitor mode arguments
+    /// Stream per-process bandwidth as newline-delimited JSON to stdout (skips TUI)
+    #[arg(long)]
+    monitor_only: bool,
+
+    /// Seconds between JSON snapshots in --monitor-only mode
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value_t = 1,
+        requires = "monitor_only"
+    )]
+    interval: u64,
+
+    /// Only emit the N busiest processes per snapshot in --monitor-only mode
+    #[arg(long, value_name = "N", requires = "monitor_only")]
+    top: Option<usize>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Share the config (throttles, filters, preferences) between machines
+    Config {
+        #[command(subcommand)]
+        action: ConfigCommand,
+    },
+    /// Run a command through a local rate-limiting proxy (no root needed; only
+    /// traffic that honors the proxy environment variables is throttled)
+    #[cfg(feature = "throttle-proxy")]
+    Run {
+        /// Download limit (e.g., "1M", "500K")
+        #[arg(long, short = 'd')]
+        download_limit: Option<String>,
+
+        /// Upload limit (e.g., "1M", "500K")
+        #[arg(long, short = 'u')]
+        upload_limit: Option<String>,
+
+        /// Command to run, after `--`
+        #[arg(required = true, trailing_var_arg = true, value_name = "COMMAND")]
+        command: Vec<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigCommand {
+    /// Write the current config as JSON
+    Export {
+        /// File to write to (default: stdout)
+        #[arg(long, short, value_name = "FILE")]
+        output: Option<std::path::PathBuf>,
+    },
+    /// Import a config exported with `config export`
+    Import {
+        /// Config file to import
+        file: std::path::PathBuf,
+
+        /// Merge throttles by process name, imported entries winning (default)
+        #[arg(long, conflicts_with = "replace")]
+        merge: bool,
+
+        /// Replace the whole config, backing up the current one first
+        #[arg(long)]
+        replace: bool,
+    },
+}
+
+impl Args {
+    /// Whether these arguments start the interactive TUI (rather than a one-shot mode)
+    fn runs_tui(&self) -> bool {
+        self.command.is_none()
+            && self.export_config.is_none()
+            && self.import_config.is_none()
+            && !self.list_backends
+            && !self.diagnose
+            && !self.cleanup
+            && !self.remove
+            && !self.remove_all
+            && !self.status
+            && !self.has_targets()
+            && self.all_except.is_empty()
+            && !self.monitor_only
+    }
+
+    /// Whether PIDs were given (--pid, --pids or --pid-file)
+    fn has_targets(&self) -> bool {
+        !self.pid.is_empty() || !self.pids.is_empty() || self.pid_file.is_some()
+    }
+
+    /// PIDs from --pid, --pids and --pid-file, in order and without duplicates
+    fn target_pids(&self) -> Result<Vec<i32>> {
+        let mut pids: Vec<i32> = self.pid.iter().chain(&self.pids).copied().collect();
+        if let Some(path) = &self.pid_file {
+            let contents = std::fs::read_to_string(path)
+                .context(format!("Failed to read PID file {:?}", path))?;
+            pids.extend(parse_pid_list(&contents).context(format!("In PID file {:?}", path))?);
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        pids.retain(|pid| seen.insert(*pid));
+        Ok(pids)
+    }
+}
+
+/// Parse a PID list: one PID per line, blank lines and `#` comments ignored
+fn parse_pid_list(contents: &str) -> Result<Vec<i32>> {
+    let mut pids = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        match line.parse::<i32>() {
+            Ok(pid) if pid > 0 => pids.push(pid),
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Invalid PID {:?} on line {}",
+                    line,
+                    index + 1
+                ));
+            }
+        }
+    }
+    Ok(pids)
+}
+
+/// Check the privileges the selected backends need, logging what is missing
+fn check_backend_privileges(
+    upload: Option<&str>,
+    download: Option<&str>,
+) -> Option<privileges::MissingPrivileges> {
+    let backends: Vec<&str> = upload.into_iter().chain(download).collect();
+    let missing = privileges::check(&backends, &privileges::Privileges::current())?;
+    log::warn!("{}", missing.message());
+    Some(missing)
 }
 
 fn print_available_backends() {
@@ -501,102 +459,142 @@ fn print_available_backends() {
     println!("      legacy - Use bpf_prog_attach only");
 }
 
-/// Parse bandwidth limit string (e.g., "1M", "500K", "1.5M") to bytes per second
-fn parse_bandwidth_limit(limit_str: &str) -> Result<u64> {
-    let limit_str = limit_str.trim().to_uppercase();
+/// Remove eBPF state pinned under /sys/fs/bpf/chadthrottle (--cleanup)
+fn run_cleanup() -> Result<()> {
+    #[cfg(all(target_os = "linux", feature = "throttle-ebpf"))]
+    {
+        use crate::backends::throttle::linux_ebpf_utils::{BPF_PIN_ROOT, cleanup_pinned_objects};
 
-    // Try to split into number and unit
-    let (num_str, unit) = if limit_str.ends_with("M") || limit_str.ends_with("MB") {
-        if limit_str.ends_with("MB") {
-            (&limit_str[..limit_str.len() - 2], "M")
-        } else {
-            (&limit_str[..limit_str.len() - 1], "M")
-        }
-    } else if limit_str.ends_with("K") || limit_str.ends_with("KB") {
-        if limit_str.ends_with("KB") {
-            (&limit_str[..limit_str.len() - 2], "K")
-        } else {
-            (&limit_str[..limit_str.len() - 1], "K")
-        }
-    } else if limit_str.ends_with("G") || limit_str.ends_with("GB") {
-        if limit_str.ends_with("GB") {
-            (&limit_str[..limit_str.len() - 2], "G")
-        } else {
-            (&limit_str[..limit_str.len() - 1], "G")
-        }
-    } else {
-        // Assume bytes if no unit
-        return limit_str
-            .parse::<u64>()
-            .map_err(|_| anyhow::anyhow!("Invalid bandwidth limit: {}", limit_str));
-    };
+        let detached = cleanup_pinned_objects()?;
+        println!(
+            "Removed pinned eBPF objects in {} (detached {} leftover attachment(s))",
+            BPF_PIN_ROOT, detached
+        );
+    }
 
-    let number: f64 = num_str
-        .parse()
-        .map_err(|_| anyhow::anyhow!("Invalid bandwidth limit number: {}", num_str))?;
+    #[cfg(not(all(target_os = "linux", feature = "throttle-ebpf")))]
+    println!("Nothing to clean up (eBPF support not compiled in)");
 
-    let bytes_per_sec = match unit {
-        "K" => (number * 1024.0) as u64,
-        "M" => (number * 1024.0 * 1024.0) as u64,
-        "G" => (number * 1024.0 * 1024.0 * 1024.0) as u64,
-        _ => return Err(anyhow::anyhow!("Unknown unit: {}", unit)),
-    };
+    Ok(())
+}
+
+/// Run `config export` / `config import` - needs neither root nor the TUI.
+/// `yes` skips the confirmation before a replace drops or changes saved throttles
+fn run_config_command(command: &ConfigCommand, yes: bool) -> Result<()> {
+    match command {
+        ConfigCommand::Export { output } => {
+            let json = serde_json::to_string_pretty(&config::Config::load()?)
+                .context("Failed to serialize config")?;
+            match output {
+                Some(path) => {
+                    std::fs::write(path, json + "\n")
+                        .context(format!("Failed to write {:?}", path))?;
+                    eprintln!("Exported config to {}", path.display());
+                }
+                None => println!("{}", json),
+            }
+        }
+        ConfigCommand::Import { file, replace, .. } => {
+            // Validate fully before the real config is touched
+            let imported = config::Config::read_import(file)?;
+            keybindings::KeyMap::from_overrides(&imported.keybindings)
+                .context(format!("Invalid config file: {:?}", file))?;
+            for quota in &imported.quotas {
+                quotas::QuotaRule::parse(quota).context(format!(
+                    "Invalid quota for '{}' in {:?}",
+                    quota.pattern, file
+                ))?;
+            }
 
-    Ok(bytes_per_sec)
+            if *replace {
+                let current = config::Config::load()?;
+                let overwritten = overwritten_throttles(&current, &imported);
+                if !overwritten.is_empty() && !yes && !confirm_overwrite(&overwritten)? {
+                    println!("Import cancelled, config unchanged");
+                    return Ok(());
+                }
+                if let Some(backup) = config::Config::backup()? {
+                    println!("Backed up the current config to {}", backup.display());
+                }
+                imported.save()?;
+                println!("Replaced config with {}", file.display());
+            } else {
+                let mut config = config::Config::load()?;
+                let changes = config.merge_throttles(&imported.throttles);
+                if changes.is_empty() {
+                    println!("No throttle changes");
+                } else {
+                    config.save()?;
+                    println!("Merged throttles from {}:", file.display());
+                    for change in changes {
+                        println!("  {}", change);
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
 }
 
-/// Run CLI mode - apply throttle and wait
-async fn run_cli_mode(args: &Args) -> Result<()> {
-    use tokio::signal;
+/// Saved throttles a replace would drop or change, as "name (PID n)"
+fn overwritten_throttles(current: &config::Config, imported: &config::Config) -> Vec<String> {
+    let mut overwritten: Vec<String> = current
+        .throttles
+        .iter()
+        .filter(|(pid, throttle)| imported.throttles.get(pid) != Some(*throttle))
+        .map(|(pid, throttle)| format!("{} (PID {})", throttle.process_name, pid))
+        .collect();
+    overwritten.sort();
+    overwritten
+}
 
-    let pid = args.pid.unwrap();
+/// Ask on the terminal whether to overwrite saved throttles. Without a terminal
+/// (provisioning scripts) the import is refused unless --yes was given
+fn confirm_overwrite(overwritten: &[String]) -> Result<bool> {
+    if !io::stdin().is_terminal() {
+        anyhow::bail!(
+            "The import would replace {} saved throttle(s) ({}); pass --yes to replace them",
+            overwritten.len(),
+            overwritten.join(", ")
+        );
+    }
+    println!("The import replaces these saved throttles:");
+    for throttle in overwritten {
+        println!("  {}", throttle);
+    }
+    print!("Continue? [y/N] ");
+    io::Write::flush(&mut io::stdout())?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
 
-    // Parse bandwidth limits
+/// Run CLI mode - apply throttles and wait
+/// Parse --download-limit and --upload-limit (at least one is required), as (download, upload)
+fn parse_cli_limits(args: &Args) -> Result<(Option<u64>, Option<u64>)> {
     let download_limit = if let Some(ref limit_str) = args.download_limit {
-        Some(parse_bandwidth_limit(limit_str)?)
+        Some(parse_throttle_limit(limit_str)?)
     } else {
         None
     };
 
     let upload_limit = if let Some(ref limit_str) = args.upload_limit {
-        Some(parse_bandwidth_limit(limit_str)?)
+        Some(parse_throttle_limit(limit_str)?)
     } else {
         None
     };
 
     if download_limit.is_none() && upload_limit.is_none() {
         return Err(anyhow::anyhow!(
-            "At least one of --download-limit or --upload-limit is required with --pid"
+            "At least one of --download-limit or --upload-limit is required"
         ));
     }
+    Ok((download_limit, upload_limit))
+}
 
-    // Get process name using platform-specific utils
-    use crate::backends::process::create_process_utils;
-    let process_utils = create_process_utils();
-    let process_name = process_utils
-        .get_process_name(pid)
-        .unwrap_or_else(|_| format!("PID {}", pid));
-
-    println!("ChadThrottle v0.6.0 - CLI Mode");
-    println!();
-    println!("Throttling process: {} (PID {})", process_name, pid);
-    if let Some(dl) = download_limit {
-        println!("  Download limit: {}/s", human_readable(dl));
-    }
-    if let Some(ul) = upload_limit {
-        println!("  Upload limit:   {}/s", human_readable(ul));
-    }
-    if let Some(dur) = args.duration {
-        println!("  Duration:       {} seconds", dur);
-    } else {
-        println!("  Duration:       Until Ctrl+C");
-    }
-    println!();
-
-    // Load config to get backend preferences (CLI mode also respects config)
-    let config = config::Config::load().unwrap_or_default();
-
-    // Determine backend preferences: CLI args override config file preferences
+/// Select the backends for a CLI mode (CLI args override config file preferences),
+/// report them and any missing privileges
+fn create_cli_throttle_manager(args: &Args, config: &config::Config) -> ThrottleManager {
     let upload_preference = args
         .upload_backend
         .as_deref()
@@ -623,95 +621,1065 @@ async fn run_cli_mode(args: &Args) -> Result<()> {
     }
     println!();
 
-    // Create throttle manager
+    if let Some(missing) = check_backend_privileges(
+        upload_backend.as_ref().map(|b| b.name()),
+        download_backend.as_ref().map(|b| b.name()),
+    ) {
+        eprintln!("⚠️  {}", missing.message());
+        eprintln!();
+    }
+
     let mut throttle_manager = ThrottleManager::new(upload_backend, download_backend);
+    throttle_manager.set_ignore_external(args.ignore_external || config.ignore_external_shaping);
+    throttle_manager
+}
 
-    // Apply throttle
-    let limit = ThrottleLimit {
-        upload_limit,
-        download_limit,
-        traffic_type: crate::process::TrafficType::All, // Default to all traffic in CLI mode
-    };
+/// Whether another chadthrottle process (TUI or CLI mode) is running
+fn other_instance_running(process_utils: &dyn crate::backends::process::ProcessUtils) -> bool {
+    !other_instances(process_utils).is_empty()
+}
 
-    throttle_manager.throttle_process(pid, process_name.clone(), &limit)?;
-    println!("✅ Throttle applied successfully!");
-    println!();
+/// PIDs of the other chadthrottle processes (TUI or CLI mode)
+fn other_instances(process_utils: &dyn crate::backends::process::ProcessUtils) -> Vec<i32> {
+    let own_pid = std::process::id() as i32;
+    let Ok(own_name) = process_utils.get_process_name(own_pid) else {
+        return Vec::new();
+    };
+    process_utils
+        .get_all_processes()
+        .map(|processes| {
+            processes
+                .iter()
+                .filter(|process| process.pid != own_pid && process.name == own_name)
+                .map(|process| process.pid)
+                .collect()
+        })
+        .unwrap_or_default()
+}
 
-    // Wait for duration or Ctrl+C
-    if let Some(duration) = args.duration {
-        println!(
-            "Running for {} seconds... (Press Ctrl+C to stop early)",
-            duration
-        );
-        tokio::select! {
-            _ = tokio::time::sleep(Duration::from_secs(duration)) => {
-                println!("\n⏱️  Duration elapsed, removing throttle...");
-            }
-            _ = signal::ctrl_c() => {
-                println!("\n🛑 Received Ctrl+C, removing throttle...");
-            }
-        }
-    } else {
-        println!("Press Ctrl+C to stop and remove throttle...");
-        signal::ctrl_c().await?;
-        println!("\n🛑 Received Ctrl+C, removing throttle...");
+/// What to save (or record) for an active throttle to find or restore it later
+fn saved_throttle(throttle: crate::backends::ActiveThrottle) -> config::SavedThrottle {
+    config::SavedThrottle {
+        process_name: throttle.process_name,
+        upload_limit: throttle.upload_limit,
+        upload_minimum: throttle.upload_minimum,
+        download_limit: throttle.download_limit,
+        interfaces: throttle.interfaces,
+        traffic_type: throttle.traffic_type,
+        upload_backend: throttle.upload_backend,
+        download_backend: throttle.download_backend,
+        policy: throttle.policy,
+        family: throttle.family,
+        priority: throttle.priority,
+        include_children: throttle.children.is_some(),
+        downgraded_from: throttle.downgraded_from,
     }
+}
 
-    // Remove throttle
-    throttle_manager.remove_throttle(pid)?;
-    println!("✅ Throttle removed successfully!");
+/// Fall back to all traffic when a backend that will enforce a limit can't filter the
+/// requested traffic type, returning the type that was asked for
+fn downgrade_cli_traffic_type(
+    throttle_manager: &ThrottleManager,
+    limit: &mut ThrottleLimit,
+) -> Option<crate::process::TrafficType> {
+    let unsupported = (limit.upload_limit.is_some()
+        && !throttle_manager.current_upload_backend_supports(limit.traffic_type))
+        || (limit.download_limit.is_some()
+            && !throttle_manager.current_download_backend_supports(limit.traffic_type));
+    if !unsupported {
+        return None;
+    }
 
-    Ok(())
+    let requested = limit.traffic_type;
+    eprintln!(
+        "⚠️  The selected backends can't throttle {} traffic only, throttling all traffic instead",
+        requested
+    );
+    eprintln!(
+        "    Pick one that can with --upload-backend/--download-backend (e.g. nftables or ebpf)"
+    );
+    eprintln!();
+    limit.traffic_type = crate::process::TrafficType::All;
+    Some(requested)
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    // Parse CLI arguments
-    let args = Args::parse();
+async fn run_cli_mode(args: &Args) -> Result<()> {
+    use tokio::signal;
 
-    if std::env::var("RUST_LOG").is_ok() {
-        pretty_env_logger::formatted_builder()
-            .parse_default_env()
-            .init();
+    let pids = args.target_pids()?;
+    if pids.is_empty() {
+        return Err(anyhow::anyhow!("No PIDs to throttle"));
     }
 
-    // Initialize BPF configuration
-    #[cfg(feature = "throttle-ebpf")]
-    {
-        use crate::backends::throttle::{BpfAttachMethod, BpfConfig, init_bpf_config};
+    let (download_limit, upload_limit) = parse_cli_limits(args)?;
 
-        // Parse attach method from CLI arg or environment
-        let attach_method = BpfAttachMethod::from_env_and_arg(args.bpf_attach_method.as_deref());
-        init_bpf_config(BpfConfig::new(attach_method));
+    // Get process names using platform-specific utils
+    use crate::backends::process::create_process_utils;
+    let process_utils = create_process_utils();
+    let targets: Vec<(i32, String)> = pids
+        .iter()
+        .map(|&pid| {
+            let name = process_utils
+                .get_process_name(pid)
+                .unwrap_or_else(|_| format!("PID {}", pid));
+            (pid, name)
+        })
+        .collect();
 
-        log::info!("BPF attach method: {:?}", attach_method);
+    println!("ChadThrottle v0.6.0 - CLI Mode");
+    println!();
+    if let [(pid, process_name)] = targets.as_slice() {
+        println!("Throttling process: {} (PID {})", process_name, pid);
+    } else {
+        println!("Throttling {} processes", targets.len());
     }
-
-    // Handle --list-backends
-    if args.list_backends {
-        print_available_backends();
-        return Ok(());
+    if let Some(dl) = download_limit {
+        println!("  Download limit: {}", format::format_rate(dl));
     }
-
-    // Handle CLI mode (--pid specified)
-    if args.pid.is_some() {
-        return run_cli_mode(&args).await;
+    if let Some(ul) = upload_limit {
+        println!("  Upload limit:   {}", format::format_rate(ul));
     }
-
-    // Setup terminal for TUI mode
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
-
-    // Create app state
-    let mut app = AppState::new();
-
-    // Load config FIRST to get backend preferences
-    let mut config = config::Config::load().unwrap_or_default();
-
-    // Load interface filter from config
+    let upload_minimum = args
+        .upload_minimum
+        .as_deref()
+        .map(parse_throttle_limit)
+        .transpose()?
+        .map(|minimum| crate::process::check_minimum(minimum, upload_limit))
+        .transpose()?;
+    if let Some(minimum) = upload_minimum {
+        println!("  Upload minimum: {}", format::format_rate(minimum));
+    }
+    if !args.interfaces.is_empty() {
+        println!("  Interfaces:     {}", args.interfaces.join(", "));
+    }
+    if let Some(traffic_type) = args.traffic_type {
+        println!("  Traffic type:   {}", traffic_type);
+    }
+    if let Some(policy) = args.policy {
+        println!("  Policy:         {}", policy);
+    }
+    if let Some(priority) = args.priority {
+        println!("  Priority:       {}", priority);
+    }
+    if let Some(dur) = args.duration {
+        println!("  Duration:       {} seconds", dur);
+    } else {
+        println!("  Duration:       Until Ctrl+C");
+    }
+    println!();
+
+    // Load config to get backend preferences (CLI mode also respects config)
+    let config = config::Config::load().unwrap_or_default();
+    let mut throttle_manager = create_cli_throttle_manager(args, &config);
+    let mut webhook_notifier = create_webhook_notifier(&config);
+
+    // Throttling our own shell/terminal/sshd can lock the user out - require --force
+    let mut refused = Vec::new();
+    if !config.allow_control_path_throttles {
+        throttle_manager.set_control_path(ControlPath::detect(process_utils.as_ref()));
+        for (pid, process_name) in &targets {
+            if let Some(reason) = throttle_manager.control_path_risk(*pid) {
+                if args.force {
+                    println!(
+                        "⚠️  Throttling {} (PID {}) anyway (--force): {}",
+                        process_name, pid, reason
+                    );
+                    throttle_manager.confirm_control_path(*pid);
+                } else {
+                    refused.push((*pid, reason));
+                }
+            }
+        }
+    }
+
+    // Apply throttles
+    let mut limit = ThrottleLimit {
+        upload_limit,
+        upload_minimum,
+        download_limit,
+        traffic_type: args.traffic_type.unwrap_or_default(),
+        interfaces: (!args.interfaces.is_empty()).then(|| args.interfaces.clone()),
+        policy: args.policy,
+        family: crate::process::AddressFamily::Any,
+        priority: args.priority.unwrap_or_default(),
+    };
+    let downgraded_from = downgrade_cli_traffic_type(&throttle_manager, &mut limit);
+
+    let allowed: Vec<(i32, String)> = targets
+        .iter()
+        .filter(|(pid, _)| !refused.iter().any(|(refused_pid, _)| refused_pid == pid))
+        .cloned()
+        .collect();
+    let mut applied_results = throttle_manager
+        .throttle_processes(&allowed, &limit)
+        .into_iter();
+    send_webhook_events(&mut webhook_notifier, &mut throttle_manager);
+    let results: Vec<(i32, String, Result<()>)> = targets
+        .iter()
+        .map(|(pid, process_name)| {
+            let result = match refused.iter().find(|(refused_pid, _)| refused_pid == pid) {
+                Some((_, reason)) => Err(anyhow::anyhow!(
+                    "Refusing to throttle {} (PID {}): {}. This can cut off your own session; \
+                     pass --force to throttle it anyway",
+                    process_name,
+                    pid,
+                    reason
+                )),
+                None => applied_results.next().map(|(_, result)| result).unwrap(),
+            };
+            (*pid, process_name.clone(), result)
+        })
+        .collect();
+
+    let total = results.len();
+    let failed = results
+        .iter()
+        .filter(|(_, _, result)| result.is_err())
+        .count();
+    let applied: Vec<(i32, String)> = results
+        .iter()
+        .filter(|(_, _, result)| result.is_ok())
+        .map(|(pid, process_name, _)| (*pid, process_name.clone()))
+        .collect();
+    if let Some(requested) = downgraded_from {
+        for (pid, _) in &applied {
+            throttle_manager.mark_downgraded(*pid, requested);
+        }
+    }
+
+    if total == 1 {
+        if let Some((_, _, Err(e))) = results.into_iter().next() {
+            flush_webhook_events(&mut webhook_notifier, &mut throttle_manager).await;
+            return Err(e);
+        }
+    } else {
+        println!("{:<8} {:<24} Result", "PID", "Process");
+        for (pid, process_name, result) in &results {
+            match result {
+                Ok(()) => println!("{:<8} {:<24} ✅ throttled", pid, process_name),
+                Err(e) => println!(
+                    "{:<8} {:<24} ❌ {}",
+                    pid,
+                    process_name,
+                    ThrottleError::describe(e)
+                ),
+            }
+        }
+        println!();
+
+        if failed > 0 && (applied.is_empty() || !args.best_effort) {
+            for (pid, _) in &applied {
+                let _ = throttle_manager.remove_throttle(*pid);
+            }
+            flush_webhook_events(&mut webhook_notifier, &mut throttle_manager).await;
+            return Err(anyhow::anyhow!(
+                "{} of {} throttle(s) could not be applied{}",
+                failed,
+                total,
+                if applied.is_empty() {
+                    ""
+                } else {
+                    " - removed the others (pass --best-effort to keep them)"
+                }
+            ));
+        }
+    }
+    if applied.len() == 1 {
+        println!("✅ Throttle applied successfully!");
+    } else {
+        println!("✅ Applied {} of {} throttles", applied.len(), total);
+    }
+    for shaping in throttle_manager.external_shaping() {
+        println!("⚠️  {}", shaping);
+    }
+    println!();
+
+    // Let `--remove` find this instance
+    let registrations: Vec<CliThrottle> = applied
+        .iter()
+        .filter_map(|(pid, _)| {
+            let throttle = saved_throttle(throttle_manager.get_throttle(*pid)?);
+            match CliThrottle::register(*pid, throttle) {
+                Ok(registration) => Some(registration),
+                Err(e) => {
+                    log::warn!("Failed to register CLI throttle on PID {}: {:#}", pid, e);
+                    None
+                }
+            }
+        })
+        .collect();
+
+    let noun = if applied.len() == 1 {
+        "throttle"
+    } else {
+        "throttles"
+    };
+
+    // Wait for duration or Ctrl+C
+    match args.duration {
+        Some(duration) => println!(
+            "Running for {} seconds... (Press Ctrl+C to stop early)",
+            duration
+        ),
+        None => println!("Press Ctrl+C to stop and remove {}...", noun),
+    }
+    let deadline = async {
+        match args.duration {
+            Some(duration) => tokio::time::sleep(Duration::from_secs(duration)).await,
+            None => std::future::pending().await,
+        }
+    };
+    let ctrl_c = signal::ctrl_c();
+    let removal = removal_requested();
+    tokio::pin!(deadline, ctrl_c, removal);
+    // Move per-CPU eBPF rate shares to the CPUs the traffic is on while waiting
+    let mut rebalance = interval(Duration::from_secs(1));
+    loop {
+        tokio::select! {
+            _ = &mut deadline => {
+                println!("\n⏱️  Duration elapsed, removing {}...", noun);
+                break;
+            }
+            result = &mut ctrl_c => {
+                result?;
+                println!("\n🛑 Received Ctrl+C, removing {}...", noun);
+                break;
+            }
+            _ = &mut removal => {
+                println!("\n🛑 Removal requested (--remove), removing {}...", noun);
+                break;
+            }
+            _ = rebalance.tick() => throttle_manager.rebalance_throttles(),
+        }
+    }
+
+    // Remove every throttle, continuing past failures
+    let mut removal_errors = Vec::new();
+    for (pid, process_name) in &applied {
+        if let Err(e) = throttle_manager.remove_throttle(*pid) {
+            eprintln!(
+                "❌ Failed to remove throttle on {} (PID {}): {:#}",
+                process_name, pid, e
+            );
+            removal_errors.push(e);
+        }
+    }
+    for registration in registrations {
+        registration.unregister();
+    }
+    flush_webhook_events(&mut webhook_notifier, &mut throttle_manager).await;
+
+    if removal_errors.is_empty() {
+        if applied.len() == 1 {
+            println!("✅ Throttle removed successfully!");
+        } else {
+            println!("✅ Removed {} throttles", applied.len());
+        }
+    } else if applied.len() == 1 {
+        return Err(removal_errors.remove(0));
+    } else {
+        return Err(anyhow::anyhow!(
+            "{} of {} throttle(s) could not be removed",
+            removal_errors.len(),
+            applied.len()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Run `run` - launch a command through a throttling proxy and wait for it to exit
+#[cfg(feature = "throttle-proxy")]
+async fn run_proxied_command(
+    download_limit: Option<&str>,
+    upload_limit: Option<&str>,
+    command: &[String],
+) -> Result<()> {
+    use crate::backends::throttle::proxy_server::{self, ProxyLimits, ProxyServer};
+    use crate::backends::throttle::{create_download_backend, create_upload_backend};
+    use std::sync::Arc;
+
+    let download_limit = download_limit.map(parse_throttle_limit).transpose()?;
+    let upload_limit = upload_limit.map(parse_throttle_limit).transpose()?;
+    if download_limit.is_none() && upload_limit.is_none() {
+        return Err(anyhow::anyhow!(
+            "At least one of --download-limit or --upload-limit is required"
+        ));
+    }
+
+    let limits = Arc::new(ProxyLimits::default());
+    let proxy = ProxyServer::start(limits.clone()).await?;
+
+    // Status goes to stderr so the command's own output can be piped
+    let mut child = tokio::process::Command::new(&command[0])
+        .args(&command[1..])
+        .envs(proxy.env_vars())
+        .spawn()
+        .context(format!("Failed to run {}", command[0]))?;
+    let pid = child
+        .id()
+        .context("Command exited before it could be throttled")? as i32;
+    proxy_server::register(pid, limits);
+
+    let process_name = std::path::Path::new(&command[0]).file_name().map_or_else(
+        || command[0].clone(),
+        |name| name.to_string_lossy().into_owned(),
+    );
+    let mut throttle_manager = ThrottleManager::new(
+        Some(create_upload_backend("proxy")?),
+        Some(create_download_backend("proxy")?),
+    );
+    let limit = ThrottleLimit {
+        upload_limit,
+        upload_minimum: None,
+        download_limit,
+        traffic_type: crate::process::TrafficType::All,
+        interfaces: None,
+        policy: None,
+        family: crate::process::AddressFamily::Any,
+        priority: crate::process::Priority::Normal,
+    };
+    if let Err(e) = throttle_manager.throttle_process(pid, process_name.clone(), &limit) {
+        let _ = child.kill().await;
+        return Err(e);
+    }
+
+    eprint!("Throttling {} (PID {}) through proxy", process_name, pid);
+    if let Some(dl) = download_limit {
+        eprint!(" - download {}", format::format_rate(dl));
+    }
+    if let Some(ul) = upload_limit {
+        eprint!(" - upload {}", format::format_rate(ul));
+    }
+    eprintln!();
+
+    // Ctrl+C reaches the command too; keep the proxy up until it has exited
+    let status = tokio::select! {
+        status = child.wait() => status?,
+        _ = tokio::signal::ctrl_c() => child.wait().await?,
+    };
+
+    let _ = throttle_manager.remove_throttle(pid);
+    proxy_server::unregister(pid);
+    drop(proxy);
+
+    if !status.success() {
+        std::process::exit(status.code().unwrap_or(1));
+    }
+    Ok(())
+}
+
+/// Resolves when another chadthrottle asks this CLI instance to stop (SIGTERM from `--remove`)
+async fn removal_requested() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{SignalKind, signal};
+        if let Ok(mut terminate) = signal(SignalKind::terminate()) {
+            terminate.recv().await;
+            return;
+        }
+    }
+    std::future::pending::<()>().await
+}
+
+/// Print the throttles running instances hold and the config's standing rules (--status)
+fn run_status(json: bool) -> Result<()> {
+    use crate::backends::process::create_process_utils;
+    use crate::instance_status::InstanceStatus;
+
+    let process_utils = create_process_utils();
+    let limit = |limit: Option<u64>| limit.map_or("unlimited".to_string(), format::format_rate);
+
+    let instances = InstanceStatus::list(process_utils.as_ref());
+    let held = CliThrottle::list(process_utils.as_ref());
+    let config = config::Config::load()?;
+    if json {
+        let status = serde_json::json!({
+            "instances": instances,
+            "cli_throttles": held,
+            "standing_rules": config.standing_rules,
+        });
+        println!("{}", serde_json::to_string_pretty(&status)?);
+        return Ok(());
+    }
+
+    if instances.is_empty() && held.is_empty() {
+        println!("No chadthrottle instance is running");
+    }
+    for instance in &instances {
+        println!(
+            "chadthrottle (PID {}): {} throttle(s)",
+            instance.owner_pid,
+            instance.throttles.len()
+        );
+        for throttle in &instance.throttles {
+            let utilization = throttle
+                .utilization
+                .and_then(|utilization| utilization.highest())
+                .map(|percent| format!("  {:.0}% of limit", percent))
+                .unwrap_or_default();
+            println!(
+                "  {:>7}  {:20} ↓ {:>12}  ↑ {:>12}{}{}",
+                throttle.pid,
+                throttle.process_name,
+                limit(throttle.download_limit),
+                limit(throttle.upload_limit),
+                utilization,
+                throttle
+                    .standing_rule
+                    .as_ref()
+                    .map(|pattern| format!("  standing rule '{}'", pattern))
+                    .unwrap_or_default()
+            );
+        }
+    }
+    if !held.is_empty() {
+        println!("CLI mode: {} throttle(s)", held.len());
+        for throttle in &held {
+            println!(
+                "  {:>7}  {:20} held by chadthrottle (PID {})",
+                throttle.pid, throttle.throttle.process_name, throttle.owner_pid
+            );
+        }
+    }
+
+    if !config.standing_rules.is_empty() {
+        println!("Standing rules:");
+        for rule in &config.standing_rules {
+            match StandingRule::parse(rule) {
+                Ok(rule) => println!("  {:20} {}", rule.pattern, rule.describe()),
+                Err(e) => println!("  {:20} invalid: {:#}", rule.pattern, e),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Run removal mode (--remove / --remove-all). No `pids` removes everything.
+///
+/// CLI-mode instances holding a throttle are asked to remove it and exit first, since
+/// they hold its kernel state. What's left (throttles of instances that died, or of a
+/// TUI that crashed) is adopted through the backends that enforced it and removed
+/// there. Saved throttles are only deleted from the config with `--forget`.
+fn run_remove_mode(args: &Args, pids: &[i32]) -> Result<()> {
+    use crate::backends::process::create_process_utils;
+
+    let selected = |pid: i32| pids.is_empty() || pids.contains(&pid);
+    let process_utils = create_process_utils();
+    let mut config = config::Config::load()?;
+    let mut removed = std::collections::BTreeSet::new();
+    let mut failures = 0;
+
+    // Owners remove their own throttles (as on Ctrl+C)
+    let held: Vec<CliThrottle> = CliThrottle::list(process_utils.as_ref())
+        .into_iter()
+        .filter(|throttle| selected(throttle.pid))
+        .collect();
+    let mut stopped_owners = Vec::new();
+    for throttle in &held {
+        // An owner removes all of its throttles when asked to remove one
+        let result = if stopped_owners.contains(&throttle.owner_pid) {
+            Ok(throttle.wait_removed())
+        } else {
+            throttle.request_removal().map(|_| throttle.wait_removed())
+        };
+        match result {
+            Ok(true) => {
+                println!(
+                    "✅ Removed throttle on {} (PID {}) held by chadthrottle (PID {})",
+                    throttle.throttle.process_name, throttle.pid, throttle.owner_pid
+                );
+                removed.insert(throttle.pid);
+                stopped_owners.push(throttle.owner_pid);
+            }
+            Ok(false) => eprintln!(
+                "⚠️  chadthrottle (PID {}) did not remove the throttle on {} (PID {}) in time",
+                throttle.owner_pid, throttle.throttle.process_name, throttle.pid
+            ),
+            Err(e) => eprintln!(
+                "⚠️  Could not ask chadthrottle (PID {}) to remove the throttle on {} (PID {}): {:#}",
+                throttle.owner_pid, throttle.throttle.process_name, throttle.pid, e
+            ),
+        }
+    }
+
+    // Everything else goes through the backends. Saved throttles and the records of
+    // CLI instances that died say which limits (and backends) to look for
+    let mut leftovers: std::collections::HashMap<i32, config::SavedThrottle> = config
+        .get_throttles()
+        .iter()
+        .filter(|(pid, _)| selected(**pid) && !removed.contains(*pid))
+        .map(|(pid, throttle)| (*pid, throttle.clone()))
+        .collect();
+    for stale in CliThrottle::take_stale(process_utils.as_ref()) {
+        if selected(stale.pid) && !removed.contains(&stale.pid) {
+            leftovers.insert(stale.pid, stale.throttle);
+        }
+    }
+    for throttle in &held {
+        if !removed.contains(&throttle.pid) {
+            leftovers.insert(throttle.pid, throttle.throttle.clone());
+        }
+    }
+
+    if !leftovers.is_empty() {
+        let running: Vec<i32> = other_instances(process_utils.as_ref())
+            .into_iter()
+            .filter(|pid| !stopped_owners.contains(pid))
+            .collect();
+        if running.is_empty() {
+            let mut throttle_manager = create_cli_throttle_manager(args, &config);
+            throttle_manager.adopt_leftovers(&leftovers, true, process_utils.as_ref());
+            let mut adopted: Vec<i32> = leftovers
+                .keys()
+                .copied()
+                .filter(|pid| throttle_manager.get_throttle(*pid).is_some())
+                .collect();
+            adopted.sort();
+            for pid in adopted {
+                let process_name = &leftovers[&pid].process_name;
+                match throttle_manager.remove_throttle(pid) {
+                    Ok(()) => {
+                        println!("✅ Removed throttle on {} (PID {})", process_name, pid);
+                        removed.insert(pid);
+                    }
+                    Err(e) => {
+                        failures += 1;
+                        eprintln!(
+                            "⚠️  Could not remove throttle on {} (PID {}): {:#}",
+                            process_name, pid, e
+                        );
+                    }
+                }
+            }
+        } else {
+            // Their kernel state may belong to the running instances
+            failures += leftovers.len();
+            eprintln!(
+                "⚠️  chadthrottle (PID {}) is running and may hold the remaining throttles; remove them there or stop it first",
+                running
+                    .iter()
+                    .map(|pid| pid.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+    }
+
+    // Saved throttles would otherwise come back the next time the TUI starts
+    let mut forgotten: Vec<(i32, String)> = Vec::new();
+    if args.forget {
+        forgotten = config
+            .get_throttles()
+            .iter()
+            .filter(|(saved_pid, _)| selected(**saved_pid))
+            .map(|(saved_pid, throttle)| (*saved_pid, throttle.process_name.clone()))
+            .collect();
+        forgotten.sort();
+        if !forgotten.is_empty() {
+            for (saved_pid, process_name) in &forgotten {
+                config.remove_throttle(*saved_pid);
+                println!(
+                    "✅ Removed saved throttle on {} (PID {})",
+                    process_name, saved_pid
+                );
+            }
+            config.save()?;
+        }
+    }
+
+    if pids.is_empty() && removed.is_empty() && forgotten.is_empty() && failures == 0 {
+        println!("No throttles to remove");
+    }
+
+    if failures > 0 {
+        return Err(anyhow::anyhow!(
+            "{} throttle(s) could not be removed",
+            failures
+        ));
+    }
+
+    let missing: Vec<String> = pids
+        .iter()
+        .filter(|pid| {
+            !removed.contains(*pid) && !forgotten.iter().any(|(saved_pid, _)| saved_pid == *pid)
+        })
+        .map(|pid| pid.to_string())
+        .collect();
+    if !missing.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No throttle found for PID {}",
+            missing.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// Run headless monitor mode - stream bandwidth snapshots as NDJSON until interrupted
+async fn run_monitor_only_mode(args: &Args) -> Result<()> {
+    use crate::snapshot::MonitorSnapshot;
+    use std::io::Write;
+    use tokio::signal;
+
+    if args.interval == 0 {
+        return Err(anyhow::anyhow!("--interval must be at least 1 second"));
+    }
+
+    let config = config::Config::load().unwrap_or_default();
+    let socket_mapper_preference = args
+        .socket_mapper
+        .as_deref()
+        .or(config.preferred_socket_mapper.as_deref());
+    let monitor_preference = args
+        .monitor_backend
+        .as_deref()
+        .or(config.preferred_monitor_backend.as_deref());
+    let interface_filters = config.filtered_interfaces.clone();
+
+    let (monitor_cmd_tx, monitor_cmd_rx) = tokio::sync::mpsc::unbounded_channel::<MonitorCommand>();
+    let (monitor_update_tx, mut monitor_update_rx) =
+        tokio::sync::mpsc::unbounded_channel::<MonitorUpdate>();
+
+    let monitor = NetworkMonitor::with_backends(
+        monitor_preference,
+        socket_mapper_preference,
+        // Snapshots carry the local split, so loopback is always captured here
+        CaptureOptions {
+            skip_loopback: false,
+            ..CaptureOptions::from_config(&config)
+        },
+    )?;
+    let monitor_thread = std::thread::spawn(move || {
+        monitor.run_monitoring_loop(monitor_cmd_rx, monitor_update_tx);
+    });
+
+    let mut tick = interval(Duration::from_secs(args.interval));
+    // The first tick completes immediately - skip it so the first snapshot has real rates
+    tick.tick().await;
+
+    let mut latest: Option<MonitorUpdate> = None;
+    let mut stdout = io::stdout().lock();
+
+    let result = loop {
+        tokio::select! {
+            _ = signal::ctrl_c() => {
+                log::info!("Received Ctrl+C, stopping headless monitor");
+                break Ok(());
+            }
+            update = monitor_update_rx.recv() => {
+                match update {
+                    Some(update) => latest = Some(update),
+                    None => break Err(anyhow::anyhow!("Monitoring thread stopped unexpectedly")),
+                }
+            }
+            _ = tick.tick() => {
+                let Some(ref update) = latest else {
+                    continue;
+                };
+
+                let processes = update.process_map.values().filter(|proc| {
+                    match &interface_filters {
+                        None => true,
+                        Some(filters) => proc
+                            .interface_stats
+                            .keys()
+                            .any(|iface_name| interface_filter::matches(filters, iface_name)),
+                    }
+                });
+
+                let mut snapshot = MonitorSnapshot::from_processes(processes);
+                if let Some(top) = args.top {
+                    snapshot.truncate(top);
+                }
+
+                let line = serde_json::to_string(&snapshot)?;
+                // A write error (EPIPE) means the consumer went away - stop quietly
+                if writeln!(stdout, "{}", line).and_then(|_| stdout.flush()).is_err() {
+                    log::info!("stdout closed, stopping headless monitor");
+                    break Ok(());
+                }
+            }
+        }
+    };
+
+    let _ = monitor_cmd_tx.send(MonitorCommand::Shutdown);
+    let _ = monitor_thread.join();
+
+    result
+}
+
+/// Run `--all-except` - throttle every network-active process not matching the given
+/// names, including ones that start later, until Ctrl+C (or --duration)
+async fn run_all_except_mode(args: &Args) -> Result<()> {
+    use crate::backends::process::create_process_utils;
+    use tokio::signal;
+
+    let (download_limit, upload_limit) = parse_cli_limits(args)?;
+
+    println!("ChadThrottle v0.6.0 - CLI Mode");
+    println!();
+    println!(
+        "Throttling every process except: {}",
+        args.all_except.join(", ")
+    );
+    if let Some(dl) = download_limit {
+        println!("  Download limit: {} (each)", format::format_rate(dl));
+    }
+    if let Some(ul) = upload_limit {
+        println!("  Upload limit:   {} (each)", format::format_rate(ul));
+    }
+    if let Some(traffic_type) = args.traffic_type {
+        println!("  Traffic type:   {}", traffic_type);
+    }
+    if let Some(dur) = args.duration {
+        println!("  Duration:       {} seconds", dur);
+    } else {
+        println!("  Duration:       Until Ctrl+C");
+    }
+    println!();
+
+    let config = config::Config::load().unwrap_or_default();
+    let mut throttle_manager = create_cli_throttle_manager(args, &config);
+    let mut webhook_notifier = create_webhook_notifier(&config);
+
+    // Never throttle our own shell/terminal/sshd here (there is no --force for a batch)
+    if !config.allow_control_path_throttles {
+        let process_utils = create_process_utils();
+        throttle_manager.set_control_path(ControlPath::detect(process_utils.as_ref()));
+    }
+
+    let mut limit = ThrottleLimit {
+        upload_limit,
+        upload_minimum: None,
+        download_limit,
+        traffic_type: args.traffic_type.unwrap_or_default(),
+        interfaces: None,
+        policy: None,
+        family: crate::process::AddressFamily::Any,
+        priority: crate::process::Priority::Normal,
+    };
+    downgrade_cli_traffic_type(&throttle_manager, &mut limit);
+    throttle_manager.start_except_mode(args.all_except.clone(), limit);
+
+    let socket_mapper_preference = args
+        .socket_mapper
+        .as_deref()
+        .or(config.preferred_socket_mapper.as_deref());
+    let monitor_preference = args
+        .monitor_backend
+        .as_deref()
+        .or(config.preferred_monitor_backend.as_deref());
+    let (monitor_cmd_tx, monitor_cmd_rx) = tokio::sync::mpsc::unbounded_channel::<MonitorCommand>();
+    let (monitor_update_tx, mut monitor_update_rx) =
+        tokio::sync::mpsc::unbounded_channel::<MonitorUpdate>();
+    let monitor = NetworkMonitor::with_backends(
+        monitor_preference,
+        socket_mapper_preference,
+        CaptureOptions::from_config(&config),
+    )?;
+    let monitor_thread = std::thread::spawn(move || {
+        monitor.run_monitoring_loop(monitor_cmd_rx, monitor_update_tx);
+    });
+
+    println!("Press Ctrl+C to stop and remove the throttles...");
+    let deadline = async {
+        match args.duration {
+            Some(duration) => tokio::time::sleep(Duration::from_secs(duration)).await,
+            None => std::future::pending().await,
+        }
+    };
+    tokio::pin!(deadline);
+
+    let result = loop {
+        tokio::select! {
+            _ = signal::ctrl_c() => {
+                println!("\n🛑 Received Ctrl+C, removing throttles...");
+                break Ok(());
+            }
+            _ = &mut deadline => {
+                println!("\n⏱️  Duration elapsed, removing throttles...");
+                break Ok(());
+            }
+            update = monitor_update_rx.recv() => {
+                let Some(update) = update else {
+                    break Err(anyhow::anyhow!("Monitoring thread stopped unexpectedly"));
+                };
+                for (pid, name) in throttle_manager.sync_except_mode(update.process_map.values()) {
+                    println!("✅ Throttled {} (PID {})", name, pid);
+                }
+                throttle_manager.rebalance_throttles();
+                throttle_manager.observe_rates(update.process_map.values());
+                send_webhook_events(&mut webhook_notifier, &mut throttle_manager);
+            }
+        }
+    };
+
+    let _ = monitor_cmd_tx.send(MonitorCommand::Shutdown);
+    let _ = monitor_thread.join();
+
+    let removed = throttle_manager.stop_except_mode();
+    flush_webhook_events(&mut webhook_notifier, &mut throttle_manager).await;
+    println!("✅ Removed {} throttle(s)", removed);
+
+    result
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Parse CLI arguments
+    let args = Args::parse();
+
+    // Logging to stderr would draw over the TUI, so it logs to a file by default
+    let log_file = args
+        .log_file
+        .clone()
+        .or_else(|| args.runs_tui().then(logging::default_log_path).flatten());
+    let log_file = match log_file {
+        Some(path) => match logging::init_file(&path) {
+            Ok(()) => Some(path),
+            Err(e) if args.runs_tui() => {
+                eprintln!("Warning: {:#} - logging to the log viewer (L) only", e);
+                let _ = logging::init_memory_only();
+                None
+            }
+            Err(e) => {
+                eprintln!("Warning: {:#} - logging disabled", e);
+                None
+            }
+        },
+        None if args.runs_tui() => {
+            let _ = logging::init_memory_only();
+            None
+        }
+        None => {
+            logging::init_stderr();
+            None
+        }
+    };
+
+    if let Some(Command::Config { action }) = &args.command {
+        return run_config_command(action, args.yes);
+    }
+    if let Some(path) = &args.export_config {
+        return run_config_command(
+            &ConfigCommand::Export {
+                output: Some(path.clone()),
+            },
+            args.yes,
+        );
+    }
+    if let Some(path) = &args.import_config {
+        return run_config_command(
+            &ConfigCommand::Import {
+                file: path.clone(),
+                merge: false,
+                replace: true,
+            },
+            args.yes,
+        );
+    }
+
+    #[cfg(feature = "throttle-proxy")]
+    if let Some(Command::Run {
+        download_limit,
+        upload_limit,
+        command,
+    }) = &args.command
+    {
+        return run_proxied_command(download_limit.as_deref(), upload_limit.as_deref(), command)
+            .await;
+    }
+
+    // The BPF map sizes below come from the config too, so load it first
+    let startup_config = config::Config::load().unwrap_or_default();
+
+    // Initialize BPF configuration
+    #[cfg(feature = "throttle-ebpf")]
+    {
+        use crate::backends::throttle::{BpfAttachMethod, BpfConfig, init_bpf_config};
+
+        // Parse attach method from CLI arg or environment
+        let attach_method = BpfAttachMethod::from_env_and_arg(args.bpf_attach_method.as_deref());
+        init_bpf_config(
+            BpfConfig::new(attach_method).with_map_capacity(startup_config.ebpf_map_capacity),
+        );
+
+        log::info!("BPF attach method: {:?}", attach_method);
+    }
+
+    // Install subnet overrides before any monitor or backend classifies traffic
+    match startup_config.parse_subnet_overrides() {
+        Ok(overrides) => traffic_classifier::init_subnet_overrides(overrides),
+        Err(e) => eprintln!("Warning: {:#} - using built-in classification", e),
+    }
+    traffic_classifier::init_loopback_counting(startup_config.count_loopback);
+    crate::backends::monitor::init_terminated_retention(startup_config.terminated_retention());
+    connection_events::init_event_logging(startup_config.log_connection_events);
+
+    // Handle --list-backends
+    if args.list_backends {
+        print_available_backends();
+        return Ok(());
+    }
+
+    // Handle --diagnose
+    if args.diagnose {
+        return diagnostics::print_diagnostics(args.json);
+    }
+
+    // Handle --cleanup
+    if args.cleanup {
+        return run_cleanup();
+    }
+
+    // Handle --remove / --remove-all
+    if args.remove || args.remove_all {
+        return run_remove_mode(&args, &args.target_pids()?);
+    }
+
+    // Handle --status
+    if args.status {
+        return run_status(args.json);
+    }
+
+    // Handle "everything except" CLI mode (--all-except specified)
+    if !args.all_except.is_empty() {
+        return run_all_except_mode(&args).await;
+    }
+
+    // Handle CLI mode (--pid, --pids or --pid-file specified)
+    if args.has_targets() {
+        return run_cli_mode(&args).await;
+    }
+
+    // Handle headless monitor mode (--monitor-only specified)
+    if args.monitor_only {
+        return run_monitor_only_mode(&args).await;
+    }
+
+    // The TUI draws to stdout: refuse to start instead of failing halfway into raw mode
+    if !io::stdout().is_terminal() {
+        return Err(anyhow::anyhow!(
+            "stdout is not a terminal, so the interactive TUI can't start.\n\
+             For output that can be piped or redirected, use:\n  \
+             chadthrottle --monitor-only          stream per-process bandwidth as JSON\n  \
+             chadthrottle --pid <PID> --upload-limit <LIMIT>   throttle without the TUI\n  \
+             chadthrottle --list-backends         show the available backends"
+        ));
+    }
+
+    // Setup terminal for TUI mode (restored when the guard drops, including on errors)
+    let terminal_guard = TerminalGuard::enter()?;
+    let backend = CrosstermBackend::new(io::stdout());
+    let mut terminal = Terminal::new(backend)?;
+
+    // Create app state
+    let mut app = AppState::new();
+
+    // Load config FIRST to get backend preferences
+    let mut config = config::Config::load().unwrap_or_default();
+
+    // Load interface filter from config
     if let Some(ref filters) = config.filtered_interfaces {
         app.active_interface_filters = Some(filters.clone());
 
@@ -724,6 +1692,54 @@ async fn main() -> Result<()> {
         }
     }
 
+    if let Err(e) = config.parse_subnet_overrides() {
+        app.status_message = format!("{:#} - using built-in classification", e);
+    }
+
+    // Load color theme from config (NO_COLOR overrides)
+    app.theme = theme::Theme::resolve(config.theme);
+    log::info!("Using color theme: {:?}", app.theme.name);
+
+    // Size of the per-process history window (graph and peak/average rates)
+    if config.history_samples == 0 {
+        log::warn!("history_samples must be at least 1, keeping 1 sample per process");
+    }
+    app.history.set_window(config.history_samples);
+
+    // Load persisted bandwidth history (opt-in, adds periodic disk writes)
+    if config.persist_history {
+        match history_store::HistoryStore::open(config.history_retention_minutes)
+            .and_then(|store| app.history.enable_persistence(store))
+        {
+            Ok(()) => log::info!(
+                "Bandwidth history persistence enabled ({} min retention)",
+                config.history_retention_minutes
+            ),
+            Err(e) => log::warn!("Failed to enable bandwidth history persistence: {}", e),
+        }
+    }
+
+    // Load keybinding overrides from config (fall back to defaults on conflicts)
+    match keybindings::KeyMap::from_overrides(&config.keybindings) {
+        Ok(keymap) => app.keymap = keymap,
+        Err(e) => {
+            log::warn!("Invalid keybindings in config, using defaults: {:#}", e);
+            app.status_message = format!("Invalid keybindings in config, using defaults: {}", e);
+        }
+    }
+
+    // Load process tree view preference from config
+    app.tree_view = config.process_tree_view;
+    app.container_view = config.container_view;
+    app.show_cmdline = config.show_cmdline;
+    app.show_tcp_health = config.show_tcp_health;
+    app.graph_clock_time = config.graph_clock_time;
+    app.show_listen_only = config.show_listen_only;
+    app.confirm_throttle_removal = config.confirm_throttle_removal;
+    if config.reverse_dns {
+        app.reverse_dns = Some(reverse_dns::ReverseDns::new());
+    }
+
     // Load traffic view mode from config
     if let Some(traffic_type) = config.traffic_view_mode {
         app.traffic_view_mode = match traffic_type {
@@ -748,6 +1764,10 @@ async fn main() -> Result<()> {
         .clone()
         .or_else(|| config.preferred_socket_mapper.clone());
     let socket_mapper_preference = socket_mapper_preference_str.as_deref();
+    let monitor_preference = args
+        .monitor_backend
+        .as_deref()
+        .or(config.preferred_monitor_backend.as_deref());
 
     // Log which preference source is being used
     if let Some(pref) = upload_preference {
@@ -771,6 +1791,13 @@ async fn main() -> Result<()> {
             log::info!("Using socket mapper from config: {}", pref);
         }
     }
+    if let Some(pref) = monitor_preference {
+        if args.monitor_backend.is_some() {
+            log::info!("Using monitor backend from CLI: {}", pref);
+        } else {
+            log::info!("Using monitor backend from config: {}", pref);
+        }
+    }
     if let Some(pref) = socket_mapper_preference {
         log::info!("Using socket mapper from CLI: {}", pref);
     }
@@ -779,36 +1806,51 @@ async fn main() -> Result<()> {
     let upload_backend = select_upload_backend(upload_preference);
     let download_backend = select_download_backend(download_preference);
 
-    // Show backend status
-    log::error!("🔥 ChadThrottle v0.6.0 - Backend Status:");
-    log::error!("");
-
-    if let Some(ref backend) = upload_backend {
-        log::error!("  ✅ Upload throttling:   {} (available)", backend.name());
-    } else {
-        log::error!("  ⚠️  Upload throttling:   Not available");
-        log::error!("      → Install 'tc' (traffic control) and enable cgroups");
+    // Show backend status (logged, and in a modal until the first key press)
+    match upload_backend {
+        Some(ref backend) => log::info!("Upload throttling: {}", backend.name()),
+        None => log::info!("Upload throttling: not available"),
     }
-
-    if let Some(ref backend) = download_backend {
-        log::error!("  ✅ Download throttling: {} (available)", backend.name());
-    } else {
-        log::error!("  ⚠️  Download throttling: Not available");
-        log::error!("      → Enable 'ifb' kernel module (see IFB_SETUP.md)");
+    match download_backend {
+        Some(ref backend) => log::info!("Download throttling: {}", backend.name()),
+        None => log::info!("Download throttling: not available"),
     }
-
-    log::error!("");
-
     if upload_backend.is_none() && download_backend.is_none() {
-        log::error!("⚠️  Warning: No throttling backends available!");
-        log::error!(
-            "   Network monitoring will work, but you won't be able to throttle processes."
-        );
-        log::error!("");
+        log::warn!("No throttling backends available - monitoring only");
+    }
+    let missing_privileges = check_backend_privileges(
+        upload_backend.as_ref().map(|b| b.name()),
+        download_backend.as_ref().map(|b| b.name()),
+    );
+    if let Some(ref missing) = missing_privileges {
+        app.privilege_warning = Some(missing.short());
+        app.status_message = format!("⚠️  {}", missing.short());
     }
+    let privileged = missing_privileges.is_none();
+    app.startup_summary = Some(ui::StartupSummary {
+        upload_backend: upload_backend.as_ref().map(|b| b.name().to_string()),
+        download_backend: download_backend.as_ref().map(|b| b.name().to_string()),
+        privilege_warning: missing_privileges.map(|missing| missing.message()),
+        log_file,
+    });
 
     // Create managers with selected backends
     let mut throttle_manager = ThrottleManager::new(upload_backend, download_backend);
+    throttle_manager.set_ignore_external(args.ignore_external || config.ignore_external_shaping);
+
+    // Apply interface ceilings before restoring throttles so they start under them
+    let mut interface_ceilings = std::collections::HashMap::new();
+    for (interface, ceiling) in &config.interface_ceilings {
+        match parse_throttle_limit(ceiling) {
+            Ok(bytes_per_sec) => {
+                interface_ceilings.insert(interface.clone(), bytes_per_sec);
+            }
+            Err(e) => log::warn!("Ignoring interface ceiling for {}: {}", interface, e),
+        }
+    }
+    if let Err(e) = throttle_manager.set_interface_ceilings(interface_ceilings) {
+        log::warn!("Failed to apply interface ceilings: {}", e);
+    }
 
     // Create monitoring channels for async communication
     let (monitor_cmd_tx, monitor_cmd_rx) = tokio::sync::mpsc::unbounded_channel::<MonitorCommand>();
@@ -816,29 +1858,43 @@ async fn main() -> Result<()> {
         tokio::sync::mpsc::unbounded_channel::<MonitorUpdate>();
 
     // Create monitor and move it to background thread
-    let monitor = NetworkMonitor::with_socket_mapper(socket_mapper_preference)?;
+    let monitor = NetworkMonitor::with_backends(
+        monitor_preference,
+        socket_mapper_preference,
+        CaptureOptions::from_config(&config),
+    )?;
+
+    // Throttles on our own control path (shell, terminal, sshd) need confirmation,
+    // so saved ones for those PIDs are not restored
+    let process_utils = crate::backends::process::create_process_utils();
+    if !config.allow_control_path_throttles {
+        throttle_manager.set_control_path(ControlPath::detect(process_utils.as_ref()));
+    }
+
+    // Leftovers of a previous run that crashed: adopt the ones matching saved throttles
+    // (unless --no-restore) and remove the rest. Another running chadthrottle's rules
+    // aren't leftovers, so nothing is touched while one is
+    if privileged && !other_instance_running(process_utils.as_ref()) {
+        let adoption = throttle_manager.adopt_leftovers(
+            config.get_throttles(),
+            !args.no_restore,
+            process_utils.as_ref(),
+        );
+        if !adoption.is_empty() {
+            app.status_message = adoption.to_string();
+        }
+    }
 
     // Restore throttles before spawning monitor thread
     if !args.no_restore {
         log::info!("Restoring saved throttles...");
-        for (pid, saved_throttle) in config.get_throttles() {
-            let limit = ThrottleLimit {
-                upload_limit: saved_throttle.upload_limit,
-                download_limit: saved_throttle.download_limit,
-                traffic_type: crate::process::TrafficType::All, // Default for restored throttles
-            };
-            if let Err(e) =
-                throttle_manager.throttle_process(*pid, saved_throttle.process_name.clone(), &limit)
-            {
-                log::warn!("Failed to restore throttle for PID {}: {}", pid, e);
-            } else {
-                log::info!(
-                    "Restored throttle for {} (PID {})",
-                    saved_throttle.process_name,
-                    pid
-                );
-            }
-        }
+        let restored =
+            throttle_manager.restore_throttles(config.get_throttles(), process_utils.as_ref());
+        log::info!(
+            "Restored {} of {} saved throttle(s)",
+            restored,
+            config.get_throttles().len()
+        );
     } else {
         log::info!("Skipping throttle restoration (--no-restore flag)");
     }
@@ -866,18 +1922,21 @@ async fn main() -> Result<()> {
     let _ = monitor_thread.join();
     log::info!("Monitoring thread shut down");
 
+    // Write out any bandwidth history still buffered
+    app.history.flush_persisted();
+
+    // "Everything except" mode isn't persisted, drop its throttles before saving
+    throttle_manager.stop_except_mode();
+
     // Save config before exit (unless --no-save specified)
     if !args.no_save {
         config.clear_throttles();
         for (pid, throttle) in throttle_manager.get_all_throttles() {
-            config.set_throttle(
-                pid,
-                config::SavedThrottle {
-                    process_name: throttle.process_name,
-                    upload_limit: throttle.upload_limit,
-                    download_limit: throttle.download_limit,
-                },
-            );
+            // Standing rules apply theirs again on the next run
+            if throttle_manager.standing_rule_for(pid).is_some() {
+                continue;
+            }
+            config.set_throttle(pid, saved_throttle(throttle));
         }
 
         // Save interface filter
@@ -899,21 +1958,576 @@ async fn main() -> Result<()> {
     }
 
     // Restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+    drop(terminal_guard);
 
     if let Err(err) = res {
         log::error!("Error: {:?}", err);
+        eprintln!("Error: {:?}", err);
     }
 
     Ok(())
 }
 
+/// Keeps the terminal in raw mode on the alternate screen while the TUI runs, and
+/// puts it back when dropped, so an error that ends the TUI early (or a failed
+/// setup step) doesn't leave the shell garbled
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> Result<Self> {
+        install_panic_restore();
+        enable_raw_mode().context("Failed to enable raw mode")?;
+        // From here on, dropping the guard undoes whatever succeeded
+        let guard = TerminalGuard;
+        execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)
+            .context("Failed to enter the alternate screen")?;
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore_terminal();
+    }
+}
+
+/// Restore the terminal before a panic on the TUI thread is printed. Unwinding would
+/// drop the guard too, but only after the message went to the alternate screen.
+/// Panics on other threads (e.g. the monitor) leave the still-running TUI alone.
+fn install_panic_restore() {
+    let tui_thread = std::thread::current().id();
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if std::thread::current().id() == tui_thread {
+            restore_terminal();
+        }
+        default_hook(info);
+    }));
+}
+
+/// Leave raw mode and the alternate screen and show the cursor again (best effort)
+fn restore_terminal() {
+    let _ = disable_raw_mode();
+    let _ = execute!(
+        io::stdout(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        cursor::Show
+    );
+}
+
+/// Share a new throttle with the target's children (None = target only).
+/// Children that can't join the throttle's cgroup get the same limit of their own.
+/// Returns a status message suffix describing the result ("" without children).
+/// Threshold alerter from the config (None = not configured or invalid)
+fn create_alerter(config: &config::Config) -> Option<alerts::BandwidthAlerter> {
+    let threshold = config.alert_threshold.as_deref()?;
+    match parse_bandwidth_limit(threshold) {
+        Ok(bytes_per_sec) => Some(alerts::BandwidthAlerter::new(
+            bytes_per_sec,
+            config.alert_command.clone(),
+            Duration::from_secs(config.alert_cooldown_secs),
+        )),
+        Err(e) => {
+            log::warn!("Ignoring alert threshold: {}", e);
+            None
+        }
+    }
+}
+
+/// The config's quota rules (invalid ones are skipped)
+fn quota_rules(config: &config::Config) -> Vec<quotas::QuotaRule> {
+    config
+        .quotas
+        .iter()
+        .filter_map(|rule| match quotas::QuotaRule::parse(rule) {
+            Ok(rule) => Some(rule),
+            Err(e) => {
+                log::warn!("Ignoring quota for '{}': {:#}", rule.pattern, e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// The config's standing rules (invalid ones are skipped)
+fn standing_rules(config: &config::Config) -> Vec<StandingRule> {
+    config
+        .standing_rules
+        .iter()
+        .filter_map(|rule| match StandingRule::parse(rule) {
+            Ok(rule) => Some(rule),
+            Err(e) => {
+                log::warn!("Ignoring standing rule for '{}': {:#}", rule.pattern, e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Show the config's standing rules in the modal (if open), with how many processes
+/// each one throttles right now
+fn refresh_standing_rules_modal(
+    app: &mut AppState,
+    config: &config::Config,
+    throttle_manager: &ThrottleManager,
+) {
+    let Some(modal) = &mut app.standing_rules_modal else {
+        return;
+    };
+    modal.rules = config
+        .standing_rules
+        .iter()
+        .map(|rule| {
+            let active = throttle_manager.standing_rule_throttle_count(rule.pattern.trim());
+            (rule.clone(), active)
+        })
+        .collect();
+    modal.selected = modal.selected.min(modal.rules.len().saturating_sub(1));
+}
+
+/// Handle a key in the standing rules modal. Rules added or deleted there are saved
+/// to the config and applied to the running processes right away
+fn handle_standing_rules_key(
+    key: KeyCode,
+    app: &mut AppState,
+    config: &mut config::Config,
+    throttle_manager: &mut ThrottleManager,
+) {
+    let Some(modal) = &mut app.standing_rules_modal else {
+        return;
+    };
+    let mut changed = false;
+    let mut close = false;
+    match &mut modal.input {
+        Some(input) => match key {
+            KeyCode::Esc => {
+                modal.input = None;
+                modal.error = None;
+            }
+            KeyCode::Enter => match ui::StandingRulesModal::parse_input(input) {
+                Ok(rule) => {
+                    config.standing_rules.push(rule);
+                    modal.selected = config.standing_rules.len() - 1;
+                    modal.input = None;
+                    modal.error = None;
+                    changed = true;
+                }
+                Err(e) => modal.error = Some(e),
+            },
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char(c) => input.push(c),
+            _ => {}
+        },
+        None => match key {
+            KeyCode::Up | KeyCode::Char('k') => modal.move_selection(false),
+            KeyCode::Down | KeyCode::Char('j') => modal.move_selection(true),
+            KeyCode::Char('a') | KeyCode::Char('n') => {
+                modal.input = Some(String::new());
+                modal.error = None;
+            }
+            KeyCode::Char('d') | KeyCode::Delete
+                if modal.selected < config.standing_rules.len() =>
+            {
+                config.standing_rules.remove(modal.selected);
+                changed = true;
+            }
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('S') => close = true,
+            _ => {}
+        },
+    }
+
+    if changed {
+        if let Err(e) = config.save() {
+            log::error!("Failed to save standing rules: {}", e);
+        }
+        let removed = throttle_manager.set_standing_rules(standing_rules(config));
+        let throttled = throttle_manager
+            .sync_standing_rules(&app.unfiltered_process_list)
+            .len();
+        app.status_message = format!(
+            "Standing rules saved: throttled {} running process(es), removed {} throttle(s)",
+            throttled, removed
+        );
+    }
+    if close {
+        app.standing_rules_modal = None;
+    }
+    refresh_standing_rules_modal(app, config, throttle_manager);
+}
+
+/// Take over an edited config file: theme, keybindings, subnet overrides, quotas,
+/// standing rules, alerts and webhooks change right away, new preferred backends wait for the user
+/// to switch to them, and saved throttles are ignored (the running throttles win).
+/// Returns the status bar message (None when nothing changed)
+fn apply_config_reload(
+    reloaded: config::Config,
+    config: &mut config::Config,
+    app: &mut AppState,
+    throttle_manager: &mut ThrottleManager,
+    alerter: &mut Option<alerts::BandwidthAlerter>,
+    quota_tracker: &mut Option<quotas::QuotaTracker>,
+    webhook_notifier: &mut Option<webhooks::WebhookNotifier>,
+) -> Option<String> {
+    use config_reload::ConfigChange;
+
+    let changes = config_reload::diff(config, &reloaded);
+    if changes.is_empty() {
+        return None;
+    }
+
+    let mut applied = Vec::new();
+    let mut backend_prompts = Vec::new();
+    let mut restart_needed = Vec::new();
+    for change in &changes {
+        match change {
+            ConfigChange::Theme => app.theme = theme::Theme::resolve(reloaded.theme),
+            ConfigChange::Keybindings => {
+                match keybindings::KeyMap::from_overrides(&reloaded.keybindings) {
+                    Ok(keymap) => app.keymap = keymap,
+                    Err(e) => {
+                        return Some(format!(
+                            "❌ Config reload: invalid keybindings, keeping the running config: {}",
+                            e
+                        ));
+                    }
+                }
+            }
+            ConfigChange::SubnetOverrides => match reloaded.parse_subnet_overrides() {
+                Ok(overrides) => traffic_classifier::init_subnet_overrides(overrides),
+                Err(e) => {
+                    return Some(format!(
+                        "❌ Config reload: {:#} - keeping the running config",
+                        e
+                    ));
+                }
+            },
+            ConfigChange::Quotas => {
+                let rules = quota_rules(&reloaded);
+                match quota_tracker {
+                    Some(tracker) => tracker.set_rules(rules),
+                    None if !rules.is_empty() => {
+                        *quota_tracker = Some(quotas::QuotaTracker::open(
+                            rules,
+                            chrono::Local::now().date_naive(),
+                        ))
+                    }
+                    None => {}
+                }
+            }
+            ConfigChange::StandingRules => {
+                throttle_manager.set_standing_rules(standing_rules(&reloaded));
+            }
+            ConfigChange::Alerts => *alerter = create_alerter(&reloaded),
+            ConfigChange::Webhooks => *webhook_notifier = create_webhook_notifier(&reloaded),
+            ConfigChange::PreferredUploadBackend(Some(name)) => {
+                backend_prompts.push(format!("preferred upload backend to {}", name))
+            }
+            ConfigChange::PreferredDownloadBackend(Some(name)) => {
+                backend_prompts.push(format!("preferred download backend to {}", name))
+            }
+            // Back to automatic selection: the current backends stay
+            ConfigChange::PreferredUploadBackend(None)
+            | ConfigChange::PreferredDownloadBackend(None) => {}
+            ConfigChange::Throttles => {
+                log::info!(
+                    "Config reload: ignoring edited saved throttles, the running throttles are \
+                     kept (and saved on exit)"
+                );
+                continue;
+            }
+            ConfigChange::NeedsRestart(key) => {
+                restart_needed.push(*key);
+                continue;
+            }
+        }
+        applied.push(change.label());
+    }
+
+    // The running throttles replace the file's when the config is saved
+    let throttles = std::mem::take(&mut config.throttles);
+    *config = reloaded;
+    config.throttles = throttles;
+
+    log::info!("Config reloaded: {:?}", changes);
+    if !restart_needed.is_empty() {
+        log::info!(
+            "Config reload: {} take effect after a restart",
+            restart_needed.join(", ")
+        );
+    }
+
+    Some(if !backend_prompts.is_empty() {
+        format!(
+            "🔄 Config changed {} - press {} to switch now",
+            backend_prompts.join(" and "),
+            app.keymap.label(Action::ApplyConfigBackends)
+        )
+    } else if !applied.is_empty() {
+        format!("🔄 Config reloaded: {}", applied.join(", "))
+    } else if !restart_needed.is_empty() {
+        format!(
+            "🔄 Config reloaded: {} take effect after a restart",
+            restart_needed.join(", ")
+        )
+    } else {
+        "🔄 Config reloaded: edited saved throttles ignored (running throttles kept)".to_string()
+    })
+}
+
+/// Webhook notifier from the config (None = not configured or unusable)
+fn create_webhook_notifier(config: &config::Config) -> Option<webhooks::WebhookNotifier> {
+    let webhook = config.webhooks.as_ref()?;
+    match webhooks::WebhookNotifier::new(webhook) {
+        Ok(notifier) => Some(notifier),
+        Err(e) => {
+            log::warn!("Webhook notifications disabled: {:#}", e);
+            None
+        }
+    }
+}
+
+/// Send pending throttle events to the webhook in the background
+fn send_webhook_events(
+    notifier: &mut Option<webhooks::WebhookNotifier>,
+    throttle_manager: &mut ThrottleManager,
+) {
+    let events = throttle_manager.drain_events();
+    if let Some(notifier) = notifier {
+        for event in &events {
+            notifier.notify(event);
+        }
+    }
+}
+
+/// Send pending throttle events and wait briefly for delivery (before exiting)
+async fn flush_webhook_events(
+    notifier: &mut Option<webhooks::WebhookNotifier>,
+    throttle_manager: &mut ThrottleManager,
+) {
+    send_webhook_events(notifier, throttle_manager);
+    if let Some(notifier) = notifier {
+        notifier.flush(Duration::from_secs(10)).await;
+    }
+}
+
+/// Act on a quota check: remove yesterday's quota throttles, report the names newly
+/// over their quota and throttle the processes of names over a throttle quota
+fn apply_quota_check(
+    check: quotas::QuotaCheck,
+    quota_tracker: &mut quotas::QuotaTracker,
+    throttle_manager: &mut ThrottleManager,
+    app: &mut AppState,
+) {
+    if check.rolled_over {
+        app.quota_warning = None;
+    }
+    for (pid, rate) in check.expired {
+        // Left alone if the throttle was changed by hand since
+        let unchanged = throttle_manager
+            .get_throttle(pid)
+            .is_some_and(|t| t.download_limit == Some(rate) && t.upload_limit == Some(rate));
+        if unchanged && let Err(e) = throttle_manager.remove_throttle(pid) {
+            log::warn!("Failed to remove quota throttle on PID {}: {:#}", pid, e);
+        }
+    }
+
+    for exceeded in &check.exceeded {
+        let mut detail = format!(
+            "{} today exceeds the daily quota of {}",
+            format::format_bytes(exceeded.used),
+            format::format_bytes(exceeded.rule.limit)
+        );
+        if let quotas::QuotaAction::Throttle(rate) = exceeded.rule.action {
+            detail.push_str(&format!(
+                ", throttling to {} until midnight",
+                format::format_rate(rate)
+            ));
+        }
+        log::warn!("Quota exceeded by {}: {}", exceeded.name, detail);
+        throttle_manager.publish_event(
+            ThrottleEvent::new(
+                ThrottleEventKind::QuotaExceeded,
+                exceeded.pid,
+                exceeded.name.clone(),
+            )
+            .with_detail(detail),
+        );
+    }
+    if let Some(latest) = check.exceeded.last() {
+        let others = quota_tracker.exceeded_count().saturating_sub(1);
+        app.quota_warning = Some(format!(
+            "Daily quota: {} used {}{}",
+            latest.name,
+            format::format_bytes(latest.used),
+            if others > 0 {
+                format!(" (+{} more)", others)
+            } else {
+                String::new()
+            }
+        ));
+    }
+
+    for (pid, name, rate) in check.to_throttle {
+        // A throttle of the user's own, or the control path, is left alone
+        if throttle_manager.get_throttle(pid).is_some()
+            || throttle_manager.control_path_risk(pid).is_some()
+        {
+            quota_tracker.mark_handled(pid, None);
+            continue;
+        }
+        let limit = ThrottleLimit {
+            upload_limit: Some(rate),
+            upload_minimum: None,
+            download_limit: Some(rate),
+            traffic_type: crate::process::TrafficType::All,
+            interfaces: None,
+            policy: None,
+            family: crate::process::AddressFamily::Any,
+            priority: crate::process::Priority::Normal,
+        };
+        // Failures are logged by throttle_processes and not retried today
+        let throttled = throttle_manager
+            .throttle_processes(&[(pid, name)], &limit)
+            .into_iter()
+            .all(|(_, result)| result.is_ok());
+        quota_tracker.mark_handled(pid, throttled.then_some(rate));
+    }
+}
+
+/// Status bar alert for a batch of events: bandwidth alerts first, then throttles that
+/// don't hold and backend failures
+fn event_alert(events: &[ThrottleEvent]) -> Option<String> {
+    for (kind, prefix) in [
+        (ThrottleEventKind::LimitExceeded, "ALERT"),
+        (
+            ThrottleEventKind::ThrottleNotWorking,
+            "Throttle not working",
+        ),
+        (ThrottleEventKind::BackendFailed, "Throttle failed"),
+    ] {
+        let matching: Vec<&ThrottleEvent> = events.iter().filter(|e| e.event == kind).collect();
+        if let Some(top) = matching.first() {
+            return Some(format!(
+                "{}: {} (PID {}) {}{}",
+                prefix,
+                top.name,
+                top.pid,
+                top.detail.as_deref().unwrap_or_default(),
+                if matching.len() > 1 {
+                    format!(" (+{} more)", matching.len() - 1)
+                } else {
+                    String::new()
+                }
+            ));
+        }
+    }
+    None
+}
+
+/// Copy `text` to the clipboard, reporting how it went in the status bar
+fn copy_to_clipboard(app: &mut AppState, text: &str) {
+    app.status_message = match clipboard::copy(text) {
+        Ok(method) => format!("📋 Copied to clipboard ({})", method.label()),
+        Err(e) => format!("❌ Copy failed: {:#}", e),
+    };
+}
+
+/// Remove a throttle from the TUI, recording it so it can be undone
+fn remove_throttle_with_undo(
+    throttle_manager: &mut ThrottleManager,
+    app: &mut AppState,
+    pid: i32,
+    name: String,
+) {
+    let previous = throttle_manager.get_throttle(pid);
+    match throttle_manager.remove_throttle(pid) {
+        Ok(_) => {
+            app.status_message = format!(
+                "Throttle removed from {} (PID {}), press {} to undo",
+                name,
+                pid,
+                app.keymap.label(Action::Undo)
+            );
+            app.undo_stack
+                .push(ThrottleMutation::new(pid, name, previous.as_ref(), true));
+        }
+        Err(e) => {
+            app.status_message =
+                format!("Failed to remove throttle: {}", ThrottleError::describe(&e));
+        }
+    }
+}
+
+/// Reverse the most recent throttle change made in the TUI. The previous throttle
+/// is re-applied on the current default backends
+fn undo_throttle_change(
+    throttle_manager: &mut ThrottleManager,
+    process_utils: &dyn crate::backends::process::ProcessUtils,
+    app: &mut AppState,
+) {
+    let Some(mutation) = app.undo_stack.pop() else {
+        app.status_message = "Nothing to undo".to_string();
+        return;
+    };
+    if !process_utils.process_exists(mutation.pid) {
+        app.status_message = format!("Can't undo {}: the process has exited", mutation.describe());
+        return;
+    }
+
+    let result = match &mutation.previous {
+        Some(limit) => {
+            throttle_manager.throttle_process(mutation.pid, mutation.name.clone(), limit)
+        }
+        None => throttle_manager.remove_throttle(mutation.pid),
+    };
+    app.status_message = match result {
+        Ok(()) => format!("Undid {}", mutation.describe()),
+        Err(e) => format!(
+            "Failed to undo {}: {}",
+            mutation.describe(),
+            ThrottleError::describe(&e)
+        ),
+    };
+}
+
+fn throttle_child_processes(
+    throttle_manager: &mut ThrottleManager,
+    pid: i32,
+    children: &Option<Vec<(i32, String)>>,
+    limit: &ThrottleLimit,
+) -> String {
+    let Some(children) = children else {
+        return String::new();
+    };
+    if children.is_empty() {
+        return " (following its children)".to_string();
+    }
+
+    let separate = throttle_manager.include_children(pid, children);
+    let throttled_separately = separate
+        .iter()
+        .filter(
+            |(pid, name)| match throttle_manager.throttle_process(*pid, name.clone(), limit) {
+                Ok(_) => true,
+                Err(e) => {
+                    log::warn!("Failed to throttle child {} (PID {}): {}", name, pid, e);
+                    false
+                }
+            },
+        )
+        .count();
+    let applied = children.len() - separate.len() + throttled_separately;
+
+    if applied == children.len() {
+        format!(" + {} child processes", applied)
+    } else {
+        format!(" + {}/{} child processes", applied, children.len())
+    }
+}
+
 async fn run_app<B: ratatui::backend::Backend>(
     terminal: &mut Terminal<B>,
     app: &mut AppState,
@@ -925,6 +2539,49 @@ async fn run_app<B: ratatui::backend::Backend>(
 ) -> Result<()> {
     let mut bandwidth_log_counter = 0u32; // Log bandwidth every N updates
 
+    // Used to find the children of throttles that follow a whole subtree
+    let process_utils = crate::backends::process::create_process_utils();
+
+    // Threshold alerts (watchdog) - optional, from the config
+    let mut alerter = create_alerter(config);
+
+    // How much of their limits throttled processes use
+    let mut utilization_tracker = utilization::UtilizationTracker::default();
+
+    let mut webhook_notifier = create_webhook_notifier(config);
+
+    // Daily quotas - optional, from the config (invalid rules are skipped)
+    let quota_rules = quota_rules(config);
+    let mut quota_tracker = (!quota_rules.is_empty())
+        .then(|| quotas::QuotaTracker::open(quota_rules, chrono::Local::now().date_naive()));
+
+    // Standing rules throttle matching processes as they show up in the process map,
+    // or right after their exec where the proc connector can be watched (root only)
+    throttle_manager.set_standing_rules(standing_rules(config));
+    #[cfg(target_os = "linux")]
+    let exec_watcher = if config.watch_new_processes {
+        match crate::backends::process::exec_watcher::ExecWatcher::start() {
+            Ok(watcher) => Some(watcher),
+            Err(e) => {
+                log::info!(
+                    "Not watching for new processes ({}), standing rules apply on the next update",
+                    e
+                );
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    // Lets --status see this instance's throttles (the file is removed on return)
+    let mut status_file = instance_status::StatusFile::open();
+
+    // Edits to the config file are picked up while running
+    let mut config_watcher = config::Config::config_path()
+        .ok()
+        .map(config_reload::ConfigWatcher::new);
+
     // Cache socket mappers at startup - they don't change at runtime
     use crate::backends::process::socket_mapper::detect_socket_mappers;
     let cached_socket_mappers: Vec<(String, crate::backends::BackendPriority, bool)> =
@@ -933,13 +2590,22 @@ async fn run_app<B: ratatui::backend::Backend>(
             .map(|sm| (sm.name.to_string(), sm.priority, sm.available))
             .collect();
 
+    // Monitor backends are detected once too
+    let cached_monitors: Vec<(String, crate::backends::BackendPriority, bool)> =
+        crate::backends::monitor::detect_available_backends()
+            .iter()
+            .map(|m| (m.name.to_string(), m.priority, m.available))
+            .collect();
+
     // Cache backend info - only rebuild when throttles change
     let mut cached_backend_info: Option<crate::backends::throttle::BackendInfo> = None;
     let mut needs_backend_refresh = true;
 
-    // Track current socket mapper state (updated from MonitorUpdate)
+    // Track current monitor and socket mapper state (updated from MonitorUpdate)
+    let mut current_monitor_backend: Option<String> = None;
     let mut current_socket_mapper: Option<String> = socket_mapper_preference.map(|s| s.to_string());
     let mut current_socket_mapper_caps: Option<crate::backends::BackendCapabilities> = None;
+    let mut current_socket_cache = None;
 
     // Track if we need to redraw
     let mut needs_redraw = true;
@@ -954,36 +2620,81 @@ async fn run_app<B: ratatui::backend::Backend>(
         if event::poll(Duration::from_millis(16))? {
             // ~60fps response time
             match event::read()? {
-                Event::Key(key) => {
+                Event::Key(mut key) => {
                     // Only handle key press events (ignore release and repeat)
                     // This prevents modals from closing immediately on Windows
                     if key.kind != KeyEventKind::Press {
                         continue;
                     }
 
-                    // ALWAYS check Ctrl+C first - force quit regardless of modal state
-                    if key.modifiers.contains(KeyModifiers::CONTROL)
-                        && key.code == KeyCode::Char('c')
-                    {
-                        return Ok(());
+                    // ALWAYS check Ctrl+C first - force quit regardless of modal state
+                    if key.modifiers.contains(KeyModifiers::CONTROL)
+                        && key.code == KeyCode::Char('c')
+                    {
+                        return Ok(());
+                    }
+
+                    // Any key dismisses the startup summary
+                    if app.startup_summary.take().is_some() {
+                        continue;
+                    }
+
+                    // ... and the throttle command (copied first on yank)
+                    if let Some(command) = app.throttle_command.take() {
+                        if app.keymap.action_for(&key) == Some(Action::Yank) {
+                            copy_to_clipboard(app, &command.command);
+                        }
+                        continue;
+                    }
+
+                    // Typing an interface pattern takes every key until Enter/Esc
+                    if let Some(input) = &mut app.interface_pattern_input {
+                        match key.code {
+                            KeyCode::Esc => app.interface_pattern_input = None,
+                            KeyCode::Enter => {
+                                let pattern = std::mem::take(input);
+                                app.interface_pattern_input = None;
+                                app.toggle_interface_pattern(&pattern);
+                                config.filtered_interfaces = app.active_interface_filters.clone();
+                                if let Err(e) = config.save() {
+                                    log::error!("Failed to save filter config: {}", e);
+                                }
+                            }
+                            KeyCode::Backspace => {
+                                input.pop();
+                            }
+                            KeyCode::Char(c) if !c.is_whitespace() => input.push(c),
+                            _ => {}
+                        }
+                        continue;
+                    }
+
+                    // The standing rules modal takes every key while open
+                    if app.standing_rules_modal.is_some() {
+                        handle_standing_rules_key(key.code, app, config, throttle_manager);
+                        needs_backend_refresh = true;
+                        continue;
                     }
 
-                    // If help is shown, handle scroll or close it
-                    if app.show_help {
-                        match key.code {
-                            KeyCode::Up | KeyCode::Char('k') => {
+                    // Resolve the key through the (possibly user-configured) keymap
+                    let action = app.keymap.action_for(&key);
+
+                    // If help or the log viewer is shown, handle scroll or close it
+                    if app.show_help || app.show_logs {
+                        match action {
+                            Some(Action::MoveUp) => {
                                 app.scroll_help_up();
                             }
-                            KeyCode::Down | KeyCode::Char('j') => {
+                            Some(Action::MoveDown) => {
                                 app.scroll_help_down();
                             }
-                            KeyCode::PageUp => {
+                            Some(Action::PageUp) => {
                                 // Scroll up by 10 lines
                                 for _ in 0..10 {
                                     app.scroll_help_up();
                                 }
                             }
-                            KeyCode::PageDown => {
+                            Some(Action::PageDown) => {
                                 // Scroll down by 10 lines
                                 for _ in 0..10 {
                                     app.scroll_help_down();
@@ -991,6 +2702,7 @@ async fn run_app<B: ratatui::backend::Backend>(
                             }
                             _ => {
                                 app.show_help = false;
+                                app.show_logs = false;
                                 app.reset_help_scroll();
                             }
                         }
@@ -1007,6 +2719,91 @@ async fn run_app<B: ratatui::backend::Backend>(
                                     let backend_name = name.to_string();
 
                                     match group {
+                                        ui::BackendGroup::Monitor => {
+                                            let current_monitor = current_monitor_backend
+                                                .as_deref()
+                                                .unwrap_or("unknown");
+
+                                            if backend_name.as_str() != current_monitor {
+                                                log::info!(
+                                                    "Switching monitor backend: {} → {}",
+                                                    current_monitor,
+                                                    backend_name
+                                                );
+
+                                                let (response_tx, response_rx) =
+                                                    tokio::sync::oneshot::channel();
+
+                                                // The monitoring thread tears down the current
+                                                // backend and carries its totals over
+                                                if monitor_cmd_tx
+                                                    .send(MonitorCommand::SwitchMonitorBackend {
+                                                        backend_name: backend_name.clone(),
+                                                        response_tx,
+                                                    })
+                                                    .is_err()
+                                                {
+                                                    app.status_message = "❌ Failed to send command to monitoring thread".to_string();
+                                                    log::error!(
+                                                        "Command channel closed unexpectedly"
+                                                    );
+                                                    continue;
+                                                }
+
+                                                app.status_message =
+                                                    format!("⏳ Switching to {}...", backend_name);
+                                                needs_redraw = true;
+
+                                                match tokio::time::timeout(
+                                                    Duration::from_secs(5),
+                                                    response_rx,
+                                                )
+                                                .await
+                                                {
+                                                    Ok(Ok(Ok(()))) => {
+                                                        config.preferred_monitor_backend =
+                                                            Some(backend_name.clone());
+                                                        let _ = config.save();
+
+                                                        current_monitor_backend =
+                                                            Some(backend_name.clone());
+                                                        app.status_message = format!(
+                                                            "✅ Monitor backend → {}",
+                                                            backend_name
+                                                        );
+                                                    }
+                                                    Ok(Ok(Err(e))) => {
+                                                        app.status_message =
+                                                            format!("❌ Monitor backend: {}", e);
+                                                        log::error!(
+                                                            "Failed to switch monitor backend: {}",
+                                                            e
+                                                        );
+                                                    }
+                                                    Ok(Err(_)) => {
+                                                        app.status_message =
+                                                            "❌ Response channel closed"
+                                                                .to_string();
+                                                        log::error!(
+                                                            "Response channel closed unexpectedly"
+                                                        );
+                                                    }
+                                                    Err(_) => {
+                                                        app.status_message =
+                                                            "❌ Timeout waiting for switch (>5s)"
+                                                                .to_string();
+                                                        log::error!(
+                                                            "Timeout waiting for monitor backend switch"
+                                                        );
+                                                    }
+                                                }
+
+                                                needs_backend_refresh = true;
+                                            } else {
+                                                app.status_message =
+                                                    format!("'{}' is already active", name);
+                                            }
+                                        }
                                         ui::BackendGroup::SocketMapper => {
                                             // Get current socket mapper from cached state
                                             let current_sm = current_socket_mapper
@@ -1141,41 +2938,49 @@ async fn run_app<B: ratatui::backend::Backend>(
                                     );
                                     {
                                         // Use cached socket mappers and current backend state
+                                        backend_info.available_monitors = cached_monitors.clone();
                                         backend_info.available_socket_mappers =
                                             cached_socket_mappers.clone();
-                                        backend_info.active_monitoring = Some("pnet".to_string());
+                                        backend_info.active_monitoring =
+                                            current_monitor_backend.clone();
                                         backend_info.active_socket_mapper =
                                             current_socket_mapper.clone();
                                         backend_info.socket_mapper_capabilities =
                                             current_socket_mapper_caps.clone();
+                                        backend_info.socket_cache = current_socket_cache;
                                         backend_info.preferred_socket_mapper =
                                             config.preferred_socket_mapper.clone();
                                     }
                                     app.build_backend_items(&backend_info);
                                 }
                             }
-                            KeyCode::Up | KeyCode::Char('k') => {
+                            _ if action == Some(Action::MoveUp) => {
                                 app.select_previous_backend();
                             }
-                            KeyCode::Down | KeyCode::Char('j') => {
+                            _ if action == Some(Action::MoveDown) => {
                                 app.select_next_backend();
                             }
-                            KeyCode::PageUp => {
+                            _ if action == Some(Action::PageUp) => {
                                 // Scroll up by 10 lines
                                 for _ in 0..10 {
                                     app.scroll_backend_info_up();
                                 }
                             }
-                            KeyCode::PageDown => {
+                            _ if action == Some(Action::PageDown) => {
                                 // Scroll down by 10 lines
                                 for _ in 0..10 {
                                     app.scroll_backend_info_down();
                                 }
                             }
-                            KeyCode::Enter
-                            | KeyCode::Char('b')
-                            | KeyCode::Char('q')
-                            | KeyCode::Esc => {
+                            _ if action == Some(Action::Yank) => {
+                                let text = app.backend_info_text.clone();
+                                copy_to_clipboard(app, &text);
+                            }
+                            _ if matches!(
+                                action,
+                                Some(Action::ViewDetails | Action::Backends | Action::Quit)
+                            ) =>
+                            {
                                 app.show_backend_info = false;
                                 app.reset_backend_info_scroll();
                             }
@@ -1184,37 +2989,62 @@ async fn run_app<B: ratatui::backend::Backend>(
                         continue;
                     }
 
-                    // If graph is shown, g/Esc/q closes it
+                    // If graph is shown, g/Esc/q closes it and w switches the time axis
                     if app.show_graph {
-                        match key.code {
-                            KeyCode::Char('g') | KeyCode::Char('q') | KeyCode::Esc => {
-                                app.show_graph = false;
+                        if matches!(action, Some(Action::ToggleGraph | Action::Quit)) {
+                            app.show_graph = false;
+                        } else if action == Some(Action::ToggleGraphTime) {
+                            app.graph_clock_time = !app.graph_clock_time;
+                            config.graph_clock_time = app.graph_clock_time;
+                            if let Err(e) = config.save() {
+                                log::warn!("Failed to save graph time axis to config: {}", e);
                             }
-                            _ => {}
                         }
                         continue;
                     }
 
+                    // Confirm throttling a process on our own control path
+                    if let Some(warning) = app.control_path_warning.take() {
+                        match key.code {
+                            KeyCode::Char('y') | KeyCode::Char('Y') => {
+                                throttle_manager.confirm_control_path(warning.pid);
+                                // Submit the (still open) throttle dialog again
+                                key = KeyEvent::from(KeyCode::Enter);
+                            }
+                            KeyCode::Char('n') | KeyCode::Esc | KeyCode::Char('q') => {
+                                app.status_message = format!(
+                                    "Throttle of {} (PID {}) cancelled",
+                                    warning.name, warning.pid
+                                );
+                                continue;
+                            }
+                            _ => {
+                                app.control_path_warning = Some(warning);
+                                continue;
+                            }
+                        }
+                    }
+
                     // Handle backend compatibility dialog (highest priority)
                     if app.show_backend_compatibility_dialog {
                         match key.code {
-                            KeyCode::Up | KeyCode::Char('k') => {
+                            _ if action == Some(Action::MoveUp) => {
                                 if let Some(dialog) = &mut app.backend_compatibility_dialog {
                                     dialog.select_previous();
                                 }
                             }
-                            KeyCode::Down | KeyCode::Char('j') => {
+                            _ if action == Some(Action::MoveDown) => {
                                 if let Some(dialog) = &mut app.backend_compatibility_dialog {
                                     dialog.select_next();
                                 }
                             }
-                            KeyCode::PageUp => {
+                            _ if action == Some(Action::PageUp) => {
                                 // Scroll up by 10 lines
                                 for _ in 0..10 {
                                     app.scroll_backend_compat_up();
                                 }
                             }
-                            KeyCode::PageDown => {
+                            _ if action == Some(Action::PageDown) => {
                                 // Scroll down by 10 lines
                                 for _ in 0..10 {
                                     app.scroll_backend_compat_down();
@@ -1229,9 +3059,18 @@ async fn run_app<B: ratatui::backend::Backend>(
                                     // Get throttle info before we consume dialog
                                     let (download, upload) =
                                         app.throttle_dialog.parse_limits().unwrap_or((None, None));
+                                    let upload_minimum = app.throttle_dialog.upload_minimum();
                                     let pid = app.throttle_dialog.target_pid;
+                                    let previous =
+                                        pid.and_then(|pid| throttle_manager.get_throttle(pid));
                                     let process_name = app.throttle_dialog.target_name.clone();
                                     let traffic_type = app.throttle_dialog.get_traffic_type();
+                                    let interfaces = app.throttle_dialog.selected_interfaces();
+                                    let policy = app.throttle_dialog.policy;
+                                    let family = app.throttle_dialog.family;
+                                    let priority = app.throttle_dialog.priority;
+                                    let child_processes =
+                                        app.throttle_dialog.children_to_throttle();
 
                                     match &action {
                                         ui::BackendCompatibilityAction::Cancel => {
@@ -1271,12 +3110,265 @@ async fn run_app<B: ratatui::backend::Backend>(
                                                 continue;
                                             }
 
-                                            // Apply throttle
+                                            // Apply throttle
+                                            if let (Some(pid), Some(name)) = (pid, process_name) {
+                                                let limit = crate::process::ThrottleLimit {
+                                                    download_limit: download,
+                                                    upload_limit: upload,
+                                                    upload_minimum,
+                                                    traffic_type,
+                                                    interfaces: interfaces.clone(),
+                                                    policy,
+                                                    family,
+                                                    priority,
+                                                };
+
+                                                match throttle_manager.throttle_process(
+                                                    pid,
+                                                    name.clone(),
+                                                    &limit,
+                                                ) {
+                                                    Ok(_) => {
+                                                        needs_backend_refresh = true; // Throttle changed
+                                                        app.undo_stack.push(ThrottleMutation::new(
+                                                            pid,
+                                                            name.clone(),
+                                                            previous.as_ref(),
+                                                            false,
+                                                        ));
+                                                        let children = throttle_child_processes(
+                                                            throttle_manager,
+                                                            pid,
+                                                            &child_processes,
+                                                            &limit,
+                                                        );
+                                                        app.status_message = format!(
+                                                            "Throttle applied to {} using {} backend{}{}",
+                                                            name,
+                                                            backend_name,
+                                                            if make_default {
+                                                                " (now default)"
+                                                            } else {
+                                                                ""
+                                                            },
+                                                            children
+                                                        );
+
+                                                        // Update config if making default
+                                                        if make_default {
+                                                            if is_upload {
+                                                                config.preferred_upload_backend =
+                                                                    Some(backend_name.clone());
+                                                            } else {
+                                                                config.preferred_download_backend =
+                                                                    Some(backend_name.clone());
+                                                            }
+                                                            let _ = config.save();
+                                                        }
+                                                    }
+                                                    Err(e) => {
+                                                        app.status_message = format!(
+                                                            "Failed to apply throttle: {}",
+                                                            ThrottleError::describe(&e)
+                                                        );
+                                                    }
+                                                }
+                                            }
+
+                                            // Switch back if not making default
+                                            if !make_default {
+                                                if is_upload {
+                                                    if let Some(orig) = original_upload {
+                                                        let _ = throttle_manager
+                                                            .set_default_upload_backend(&orig);
+                                                    }
+                                                } else {
+                                                    if let Some(orig) = original_download {
+                                                        let _ = throttle_manager
+                                                            .set_default_download_backend(&orig);
+                                                    }
+                                                }
+                                            }
+
+                                            // Close both dialogs
+                                            app.show_backend_compatibility_dialog = false;
+                                            app.backend_compatibility_dialog = None;
+                                            app.show_throttle_dialog = false;
+                                            app.throttle_dialog.reset();
+                                        }
+                                        ui::BackendCompatibilityAction::ConvertToAll => {
+                                            // Apply throttle with All traffic type
+                                            if let (Some(pid), Some(name)) = (pid, process_name) {
+                                                let limit = crate::process::ThrottleLimit {
+                                                    download_limit: download,
+                                                    upload_limit: upload,
+                                                    upload_minimum,
+                                                    traffic_type: crate::process::TrafficType::All,
+                                                    interfaces: interfaces.clone(),
+                                                    policy,
+                                                    family,
+                                                    priority,
+                                                };
+
+                                                match throttle_manager.throttle_process(
+                                                    pid,
+                                                    name.clone(),
+                                                    &limit,
+                                                ) {
+                                                    Ok(_) => {
+                                                        needs_backend_refresh = true; // Throttle changed
+                                                        app.undo_stack.push(ThrottleMutation::new(
+                                                            pid,
+                                                            name.clone(),
+                                                            previous.as_ref(),
+                                                            false,
+                                                        ));
+                                                        throttle_manager
+                                                            .mark_downgraded(pid, traffic_type);
+                                                        let children = throttle_child_processes(
+                                                            throttle_manager,
+                                                            pid,
+                                                            &child_processes,
+                                                            &limit,
+                                                        );
+                                                        app.status_message = format!(
+                                                            "Throttle applied to {} as 'All Traffic'{}",
+                                                            name, children
+                                                        );
+                                                    }
+                                                    Err(e) => {
+                                                        app.status_message = format!(
+                                                            "Failed to apply throttle: {}",
+                                                            ThrottleError::describe(&e)
+                                                        );
+                                                    }
+                                                }
+                                            }
+
+                                            // Close both dialogs
+                                            app.show_backend_compatibility_dialog = false;
+                                            app.backend_compatibility_dialog = None;
+                                            app.show_throttle_dialog = false;
+                                            app.throttle_dialog.reset();
+                                        }
+                                        ui::BackendCompatibilityAction::ConvertToAllInterfaces => {
+                                            // Apply throttle without interface scoping
+                                            if let (Some(pid), Some(name)) = (pid, process_name) {
+                                                let limit = crate::process::ThrottleLimit {
+                                                    download_limit: download,
+                                                    upload_limit: upload,
+                                                    upload_minimum,
+                                                    traffic_type,
+                                                    interfaces: None,
+                                                    policy,
+                                                    family,
+                                                    priority,
+                                                };
+
+                                                match throttle_manager.throttle_process(
+                                                    pid,
+                                                    name.clone(),
+                                                    &limit,
+                                                ) {
+                                                    Ok(_) => {
+                                                        needs_backend_refresh = true; // Throttle changed
+                                                        app.undo_stack.push(ThrottleMutation::new(
+                                                            pid,
+                                                            name.clone(),
+                                                            previous.as_ref(),
+                                                            false,
+                                                        ));
+                                                        let children = throttle_child_processes(
+                                                            throttle_manager,
+                                                            pid,
+                                                            &child_processes,
+                                                            &limit,
+                                                        );
+                                                        app.status_message = format!(
+                                                            "Throttle applied to {} on all interfaces{}",
+                                                            name, children
+                                                        );
+                                                    }
+                                                    Err(e) => {
+                                                        app.status_message = format!(
+                                                            "Failed to apply throttle: {}",
+                                                            ThrottleError::describe(&e)
+                                                        );
+                                                    }
+                                                }
+                                            }
+
+                                            // Close both dialogs
+                                            app.show_backend_compatibility_dialog = false;
+                                            app.backend_compatibility_dialog = None;
+                                            app.show_throttle_dialog = false;
+                                            app.throttle_dialog.reset();
+                                        }
+                                        ui::BackendCompatibilityAction::ConvertToAnyFamily => {
+                                            // Apply throttle to both IP versions
+                                            if let (Some(pid), Some(name)) = (pid, process_name) {
+                                                let limit = crate::process::ThrottleLimit {
+                                                    download_limit: download,
+                                                    upload_limit: upload,
+                                                    upload_minimum,
+                                                    traffic_type,
+                                                    interfaces: interfaces.clone(),
+                                                    policy,
+                                                    family: crate::process::AddressFamily::Any,
+                                                    priority: crate::process::Priority::Normal,
+                                                };
+
+                                                match throttle_manager.throttle_process(
+                                                    pid,
+                                                    name.clone(),
+                                                    &limit,
+                                                ) {
+                                                    Ok(_) => {
+                                                        needs_backend_refresh = true; // Throttle changed
+                                                        app.undo_stack.push(ThrottleMutation::new(
+                                                            pid,
+                                                            name.clone(),
+                                                            previous.as_ref(),
+                                                            false,
+                                                        ));
+                                                        let children = throttle_child_processes(
+                                                            throttle_manager,
+                                                            pid,
+                                                            &child_processes,
+                                                            &limit,
+                                                        );
+                                                        app.status_message = format!(
+                                                            "Throttle applied to {} for both IPv4 and IPv6{}",
+                                                            name, children
+                                                        );
+                                                    }
+                                                    Err(e) => {
+                                                        app.status_message = format!(
+                                                            "Failed to apply throttle: {}",
+                                                            ThrottleError::describe(&e)
+                                                        );
+                                                    }
+                                                }
+                                            }
+
+                                            // Close both dialogs
+                                            app.show_backend_compatibility_dialog = false;
+                                            app.backend_compatibility_dialog = None;
+                                            app.show_throttle_dialog = false;
+                                            app.throttle_dialog.reset();
+                                        }
+                                        ui::BackendCompatibilityAction::ApplyAnyway => {
+                                            // Apply throttle with the current backends as requested
                                             if let (Some(pid), Some(name)) = (pid, process_name) {
                                                 let limit = crate::process::ThrottleLimit {
                                                     download_limit: download,
                                                     upload_limit: upload,
+                                                    upload_minimum,
                                                     traffic_type,
+                                                    interfaces: interfaces.clone(),
+                                                    policy,
+                                                    family,
+                                                    priority,
                                                 };
 
                                                 match throttle_manager.throttle_process(
@@ -1286,66 +3378,50 @@ async fn run_app<B: ratatui::backend::Backend>(
                                                 ) {
                                                     Ok(_) => {
                                                         needs_backend_refresh = true; // Throttle changed
+                                                        app.undo_stack.push(ThrottleMutation::new(
+                                                            pid,
+                                                            name.clone(),
+                                                            previous.as_ref(),
+                                                            false,
+                                                        ));
+                                                        let children = throttle_child_processes(
+                                                            throttle_manager,
+                                                            pid,
+                                                            &child_processes,
+                                                            &limit,
+                                                        );
                                                         app.status_message = format!(
-                                                            "Throttle applied to {} using {} backend{}",
-                                                            name,
-                                                            backend_name,
-                                                            if make_default {
-                                                                " (now default)"
-                                                            } else {
-                                                                ""
-                                                            }
+                                                            "Throttle applied to {} (UDP traffic not throttled){}",
+                                                            name, children
                                                         );
-
-                                                        // Update config if making default
-                                                        if make_default {
-                                                            if is_upload {
-                                                                config.preferred_upload_backend =
-                                                                    Some(backend_name.clone());
-                                                            } else {
-                                                                config.preferred_download_backend =
-                                                                    Some(backend_name.clone());
-                                                            }
-                                                            let _ = config.save();
-                                                        }
                                                     }
                                                     Err(e) => {
                                                         app.status_message = format!(
                                                             "Failed to apply throttle: {}",
-                                                            e
+                                                            ThrottleError::describe(&e)
                                                         );
                                                     }
                                                 }
                                             }
 
-                                            // Switch back if not making default
-                                            if !make_default {
-                                                if is_upload {
-                                                    if let Some(orig) = original_upload {
-                                                        let _ = throttle_manager
-                                                            .set_default_upload_backend(&orig);
-                                                    }
-                                                } else {
-                                                    if let Some(orig) = original_download {
-                                                        let _ = throttle_manager
-                                                            .set_default_download_backend(&orig);
-                                                    }
-                                                }
-                                            }
-
                                             // Close both dialogs
                                             app.show_backend_compatibility_dialog = false;
                                             app.backend_compatibility_dialog = None;
                                             app.show_throttle_dialog = false;
                                             app.throttle_dialog.reset();
                                         }
-                                        ui::BackendCompatibilityAction::ConvertToAll => {
-                                            // Apply throttle with All traffic type
+                                        ui::BackendCompatibilityAction::UseDefaultPolicy => {
+                                            // Apply throttle with each backend's default policy
                                             if let (Some(pid), Some(name)) = (pid, process_name) {
                                                 let limit = crate::process::ThrottleLimit {
                                                     download_limit: download,
                                                     upload_limit: upload,
-                                                    traffic_type: crate::process::TrafficType::All,
+                                                    upload_minimum,
+                                                    traffic_type,
+                                                    interfaces: interfaces.clone(),
+                                                    policy: None,
+                                                    family,
+                                                    priority,
                                                 };
 
                                                 match throttle_manager.throttle_process(
@@ -1355,15 +3431,27 @@ async fn run_app<B: ratatui::backend::Backend>(
                                                 ) {
                                                     Ok(_) => {
                                                         needs_backend_refresh = true; // Throttle changed
+                                                        app.undo_stack.push(ThrottleMutation::new(
+                                                            pid,
+                                                            name.clone(),
+                                                            previous.as_ref(),
+                                                            false,
+                                                        ));
+                                                        let children = throttle_child_processes(
+                                                            throttle_manager,
+                                                            pid,
+                                                            &child_processes,
+                                                            &limit,
+                                                        );
                                                         app.status_message = format!(
-                                                            "Throttle applied to {} as 'All Traffic'",
-                                                            name
+                                                            "Throttle applied to {} with the backend's default policy{}",
+                                                            name, children
                                                         );
                                                     }
                                                     Err(e) => {
                                                         app.status_message = format!(
                                                             "Failed to apply throttle: {}",
-                                                            e
+                                                            ThrottleError::describe(&e)
                                                         );
                                                     }
                                                 }
@@ -1398,41 +3486,188 @@ async fn run_app<B: ratatui::backend::Backend>(
                             KeyCode::Tab => {
                                 app.throttle_dialog.toggle_field();
                             }
+                            // Limits and their units come first, so "mbit" can be typed
+                            // (letters that can't continue a unit fall through to shortcuts)
+                            KeyCode::Char(c) if app.throttle_dialog.accepts_char(c) => {
+                                app.throttle_dialog.handle_char(c);
+                            }
                             KeyCode::Char('t') => {
                                 app.throttle_dialog.cycle_traffic_type();
                             }
-                            KeyCode::Char(c) if c.is_numeric() => {
-                                app.throttle_dialog.handle_char(c);
+                            KeyCode::Char('c') => {
+                                app.throttle_dialog.toggle_include_children();
+                            }
+                            KeyCode::Char('p') => {
+                                app.throttle_dialog.cycle_policy();
+                            }
+                            KeyCode::Char('i') => {
+                                app.throttle_dialog.cycle_family();
+                            }
+                            KeyCode::Char('r') => {
+                                app.throttle_dialog.cycle_priority();
+                            }
+                            KeyCode::Left
+                                if app.throttle_dialog.selected_field
+                                    == ui::ThrottleField::Interfaces =>
+                            {
+                                app.throttle_dialog.move_interface_cursor(false);
                             }
+                            KeyCode::Right
+                                if app.throttle_dialog.selected_field
+                                    == ui::ThrottleField::Interfaces =>
+                            {
+                                app.throttle_dialog.move_interface_cursor(true);
+                            }
+                            KeyCode::Char(' ') => {
+                                app.throttle_dialog.toggle_interface();
+                            }
+                            KeyCode::Char('y') => match app.throttle_dialog.command() {
+                                Some(command) => app.throttle_command = Some(command),
+                                None => {
+                                    app.status_message =
+                                        "Enter a valid limit to see the CLI command".to_string()
+                                }
+                            },
                             KeyCode::Backspace => {
                                 app.throttle_dialog.handle_backspace();
                             }
+                            KeyCode::Enter if app.throttle_dialog.parse_limits().is_none() => {
+                                // Keep the dialog open on the field that's wrong, it shows why
+                                if let Some(field) = app.throttle_dialog.invalid_field() {
+                                    app.throttle_dialog.selected_field = field;
+                                }
+                            }
                             KeyCode::Enter => {
                                 // Apply throttle
                                 if let Some((download, upload)) = app.throttle_dialog.parse_limits()
                                 {
-                                    if let Some(pid) = app.throttle_dialog.target_pid {
+                                    if let Some(excluded) =
+                                        app.throttle_dialog.excluded_names.take()
+                                    {
+                                        let mut limit = crate::process::ThrottleLimit {
+                                            download_limit: download,
+                                            upload_limit: upload,
+                                            upload_minimum: app.throttle_dialog.upload_minimum(),
+                                            traffic_type: app.throttle_dialog.get_traffic_type(),
+                                            interfaces: app.throttle_dialog.selected_interfaces(),
+                                            policy: app.throttle_dialog.policy,
+                                            family: app.throttle_dialog.family,
+                                            priority: app.throttle_dialog.priority,
+                                        };
+                                        // No compatibility dialog for a whole batch, fall back
+                                        // to All traffic instead
+                                        if (limit.upload_limit.is_some()
+                                            && !throttle_manager.current_upload_backend_supports(
+                                                limit.traffic_type,
+                                            ))
+                                            || (limit.download_limit.is_some()
+                                                && !throttle_manager
+                                                    .current_download_backend_supports(
+                                                        limit.traffic_type,
+                                                    ))
+                                        {
+                                            limit.traffic_type = crate::process::TrafficType::All;
+                                        }
+                                        // Likewise both IP versions
+                                        if (limit.upload_limit.is_some()
+                                            && !throttle_manager
+                                                .current_upload_backend_supports_family(
+                                                    limit.family,
+                                                ))
+                                            || (limit.download_limit.is_some()
+                                                && !throttle_manager
+                                                    .current_download_backend_supports_family(
+                                                        limit.family,
+                                                    ))
+                                        {
+                                            limit.family = crate::process::AddressFamily::Any;
+                                        }
+
+                                        throttle_manager.start_except_mode(excluded.clone(), limit);
+                                        let throttled = throttle_manager
+                                            .sync_except_mode(&app.unfiltered_process_list);
+                                        needs_backend_refresh = true;
+                                        app.status_message = format!(
+                                            "Throttled {} process(es), leaving alone: {} \
+                                             (press {} again to stop)",
+                                            throttled.len(),
+                                            excluded.join(", "),
+                                            app.keymap.label(Action::ThrottleAllExcept)
+                                        );
+                                        app.show_throttle_dialog = false;
+                                        app.throttle_dialog.reset();
+                                    } else if let Some(pid) = app.throttle_dialog.target_pid {
                                         let process_name = app
                                             .throttle_dialog
                                             .target_name
                                             .clone()
                                             .unwrap_or_default();
-                                        let limit = crate::process::ThrottleLimit {
+                                        let mut limit = crate::process::ThrottleLimit {
                                             download_limit: download,
                                             upload_limit: upload,
+                                            upload_minimum: app.throttle_dialog.upload_minimum(),
                                             traffic_type: app.throttle_dialog.get_traffic_type(),
+                                            interfaces: app.throttle_dialog.selected_interfaces(),
+                                            policy: app.throttle_dialog.policy,
+                                            family: app.throttle_dialog.family,
+                                            priority: app.throttle_dialog.priority,
                                         };
 
+                                        // Throttling our own shell/terminal/sshd needs confirmation
+                                        if let Some(reason) =
+                                            throttle_manager.control_path_risk(pid)
+                                        {
+                                            app.control_path_warning =
+                                                Some(ui::ControlPathWarning {
+                                                    pid,
+                                                    name: process_name,
+                                                    reason,
+                                                });
+                                            continue; // Keep the throttle dialog open
+                                        }
+
                                         // Check if backend compatibility dialog is needed
-                                        let needs_upload_compat = limit.upload_limit.is_some()
+                                        let mut needs_upload_compat = limit.upload_limit.is_some()
                                             && !throttle_manager.current_upload_backend_supports(
                                                 limit.traffic_type,
                                             );
-                                        let needs_download_compat = limit.download_limit.is_some()
+                                        let mut needs_download_compat = limit
+                                            .download_limit
+                                            .is_some()
                                             && !throttle_manager.current_download_backend_supports(
                                                 limit.traffic_type,
                                             );
 
+                                        // With auto-downgrade on, a traffic type no backend can
+                                        // filter becomes All instead of asking
+                                        let mut downgraded_from = None;
+                                        if config.auto_downgrade_traffic_type
+                                            && (needs_upload_compat || needs_download_compat)
+                                            && (!needs_upload_compat
+                                                || throttle_manager
+                                                    .find_compatible_upload_backends(
+                                                        limit.traffic_type,
+                                                    )
+                                                    .is_empty())
+                                            && (!needs_download_compat
+                                                || throttle_manager
+                                                    .find_compatible_download_backends(
+                                                        limit.traffic_type,
+                                                    )
+                                                    .is_empty())
+                                        {
+                                            log::info!(
+                                                "No backend can throttle {:?} traffic only, \
+                                                 throttling all traffic of PID {}",
+                                                limit.traffic_type,
+                                                pid
+                                            );
+                                            downgraded_from = Some(limit.traffic_type);
+                                            limit.traffic_type = crate::process::TrafficType::All;
+                                            needs_upload_compat = false;
+                                            needs_download_compat = false;
+                                        }
+
                                         if needs_upload_compat {
                                             // Show upload backend compatibility dialog
                                             let compatible = throttle_manager
@@ -1473,8 +3708,221 @@ async fn run_app<B: ratatui::backend::Backend>(
                                             continue; // Skip applying throttle for now
                                         }
 
+                                        // Interface scoping needs backends that shape per interface
+                                        if let Some(interfaces) = &limit.interfaces {
+                                            let needs_upload_scope = limit.upload_limit.is_some()
+                                                && !throttle_manager
+                                                    .current_upload_backend_supports_interfaces(
+                                                        interfaces,
+                                                    );
+                                            let needs_download_scope = limit
+                                                .download_limit
+                                                .is_some()
+                                                && !throttle_manager
+                                                    .current_download_backend_supports_interfaces(
+                                                        interfaces,
+                                                    );
+
+                                            if needs_upload_scope || needs_download_scope {
+                                                let (current_upload, current_download) =
+                                                    throttle_manager.get_default_backends();
+                                                let (current_backend, compatible) =
+                                                    if needs_upload_scope {
+                                                        (
+                                                            current_upload,
+                                                            throttle_manager
+                                                                .find_interface_capable_upload_backends(
+                                                                    limit.traffic_type,
+                                                                    interfaces,
+                                                                ),
+                                                        )
+                                                    } else {
+                                                        (
+                                                            current_download,
+                                                            throttle_manager
+                                                                .find_interface_capable_download_backends(
+                                                                    limit.traffic_type,
+                                                                    interfaces,
+                                                                ),
+                                                        )
+                                                    };
+                                                app.backend_compatibility_dialog = Some(
+                                                    ui::BackendCompatibilityDialog::for_interfaces(
+                                                        current_backend
+                                                            .unwrap_or("none".to_string()),
+                                                        limit.traffic_type,
+                                                        compatible,
+                                                        needs_upload_scope,
+                                                        interfaces.clone(),
+                                                    ),
+                                                );
+                                                app.reset_backend_compat_scroll();
+                                                app.show_backend_compatibility_dialog = true;
+                                                continue;
+                                            }
+                                        }
+
+                                        // One IP version needs backends that can tell them apart
+                                        if limit.family != crate::process::AddressFamily::Any {
+                                            let needs_upload_family = limit.upload_limit.is_some()
+                                                && !throttle_manager
+                                                    .current_upload_backend_supports_family(
+                                                        limit.family,
+                                                    );
+                                            let needs_download_family =
+                                                limit.download_limit.is_some()
+                                                    && !throttle_manager
+                                                        .current_download_backend_supports_family(
+                                                            limit.family,
+                                                        );
+
+                                            if needs_upload_family || needs_download_family {
+                                                let (current_upload, current_download) =
+                                                    throttle_manager.get_default_backends();
+                                                let (current_backend, compatible) =
+                                                    if needs_upload_family {
+                                                        (
+                                                            current_upload,
+                                                            throttle_manager
+                                                                .find_family_capable_upload_backends(
+                                                                    limit.traffic_type,
+                                                                    limit.family,
+                                                                ),
+                                                        )
+                                                    } else {
+                                                        (
+                                                            current_download,
+                                                            throttle_manager
+                                                                .find_family_capable_download_backends(
+                                                                    limit.traffic_type,
+                                                                    limit.family,
+                                                                ),
+                                                        )
+                                                    };
+                                                app.backend_compatibility_dialog = Some(
+                                                    ui::BackendCompatibilityDialog::for_family(
+                                                        current_backend
+                                                            .unwrap_or("none".to_string()),
+                                                        limit.traffic_type,
+                                                        compatible,
+                                                        needs_upload_family,
+                                                        limit.family,
+                                                    ),
+                                                );
+                                                app.reset_backend_compat_scroll();
+                                                app.show_backend_compatibility_dialog = true;
+                                                continue;
+                                            }
+                                        }
+
+                                        // A requested policy needs backends that can enforce it
+                                        if let Some(policy) = limit.policy {
+                                            let needs_upload_policy = limit.upload_limit.is_some()
+                                                && !throttle_manager
+                                                    .current_upload_backend_supports_policy(policy);
+                                            let needs_download_policy =
+                                                limit.download_limit.is_some()
+                                                    && !throttle_manager
+                                                        .current_download_backend_supports_policy(
+                                                            policy,
+                                                        );
+
+                                            if needs_upload_policy || needs_download_policy {
+                                                let (current_upload, current_download) =
+                                                    throttle_manager.get_default_backends();
+                                                let (current_backend, compatible) =
+                                                    if needs_upload_policy {
+                                                        (
+                                                            current_upload,
+                                                            throttle_manager
+                                                                .find_policy_capable_upload_backends(
+                                                                    limit.traffic_type,
+                                                                    policy,
+                                                                ),
+                                                        )
+                                                    } else {
+                                                        (
+                                                            current_download,
+                                                            throttle_manager
+                                                                .find_policy_capable_download_backends(
+                                                                    limit.traffic_type,
+                                                                    policy,
+                                                                ),
+                                                        )
+                                                    };
+                                                app.backend_compatibility_dialog = Some(
+                                                    ui::BackendCompatibilityDialog::for_policy(
+                                                        current_backend
+                                                            .unwrap_or("none".to_string()),
+                                                        limit.traffic_type,
+                                                        compatible,
+                                                        needs_upload_policy,
+                                                        policy,
+                                                    ),
+                                                );
+                                                app.reset_backend_compat_scroll();
+                                                app.show_backend_compatibility_dialog = true;
+                                                continue;
+                                            }
+                                        }
+
+                                        // TCP-only backends under-deliver on mostly-UDP processes
+                                        let udp_share = |upload: bool| {
+                                            app.unfiltered_process_list
+                                                .iter()
+                                                .find(|p| p.pid == pid)
+                                                .and_then(|p| p.udp_share(upload))
+                                                .filter(|share| *share >= ui::HIGH_UDP_SHARE)
+                                        };
+                                        let upload_udp_share = udp_share(true).filter(|_| {
+                                            limit.upload_limit.is_some()
+                                                && !throttle_manager
+                                                    .current_upload_backend_supports_udp()
+                                        });
+                                        let download_udp_share = udp_share(false).filter(|_| {
+                                            limit.download_limit.is_some()
+                                                && !throttle_manager
+                                                    .current_download_backend_supports_udp()
+                                        });
+                                        if let Some((share, is_upload)) = upload_udp_share
+                                            .map(|share| (share, true))
+                                            .or(download_udp_share.map(|share| (share, false)))
+                                        {
+                                            let (current_upload, current_download) =
+                                                throttle_manager.get_default_backends();
+                                            let (current_backend, compatible) = if is_upload {
+                                                (
+                                                    current_upload,
+                                                    throttle_manager
+                                                        .find_udp_capable_upload_backends(
+                                                            limit.traffic_type,
+                                                        ),
+                                                )
+                                            } else {
+                                                (
+                                                    current_download,
+                                                    throttle_manager
+                                                        .find_udp_capable_download_backends(
+                                                            limit.traffic_type,
+                                                        ),
+                                                )
+                                            };
+                                            app.backend_compatibility_dialog =
+                                                Some(ui::BackendCompatibilityDialog::for_udp(
+                                                    current_backend.unwrap_or("none".to_string()),
+                                                    limit.traffic_type,
+                                                    compatible,
+                                                    is_upload,
+                                                    share,
+                                                ));
+                                            app.reset_backend_compat_scroll();
+                                            app.show_backend_compatibility_dialog = true;
+                                            continue;
+                                        }
+
                                         // No compatibility issues or no compatible backends available
                                         // Proceed with throttle attempt
+                                        let previous = throttle_manager.get_throttle(pid);
                                         match throttle_manager.throttle_process(
                                             pid,
                                             process_name.clone(),
@@ -1482,15 +3930,38 @@ async fn run_app<B: ratatui::backend::Backend>(
                                         ) {
                                             Ok(_) => {
                                                 needs_backend_refresh = true; // Throttle changed
+                                                app.undo_stack.push(ThrottleMutation::new(
+                                                    pid,
+                                                    process_name.clone(),
+                                                    previous.as_ref(),
+                                                    false,
+                                                ));
+                                                if let Some(requested) = downgraded_from {
+                                                    throttle_manager
+                                                        .mark_downgraded(pid, requested);
+                                                }
+                                                let children = throttle_child_processes(
+                                                    throttle_manager,
+                                                    pid,
+                                                    &app.throttle_dialog.children_to_throttle(),
+                                                    &limit,
+                                                );
+                                                let downgraded = if downgraded_from.is_some() {
+                                                    " as 'All Traffic' (downgraded)"
+                                                } else {
+                                                    ""
+                                                };
                                                 app.status_message = format!(
-                                                    "Throttle applied to {} (PID {})",
-                                                    process_name, pid
+                                                    "Throttle applied to {} (PID {}){}{}",
+                                                    process_name, pid, downgraded, children
                                                 );
                                             }
                                             Err(e) => {
                                                 log::warn!("Failed to apply throttle: {e}");
-                                                app.status_message =
-                                                    format!("Failed to apply throttle: {}", e);
+                                                app.status_message = format!(
+                                                    "Failed to apply throttle: {}",
+                                                    ThrottleError::describe(&e)
+                                                );
                                             }
                                         }
                                     }
@@ -1503,8 +3974,19 @@ async fn run_app<B: ratatui::backend::Backend>(
                         continue;
                     }
 
-                    match key.code {
-                        KeyCode::Char('q') | KeyCode::Esc => {
+                    // A pending throttle removal goes ahead on a second press (or 'y'),
+                    // any other key cancels it and is handled as usual
+                    if let Some(pending) = app.take_pending_removal()
+                        && (action == Some(Action::RemoveThrottle)
+                            || key.code == KeyCode::Char('y'))
+                    {
+                        remove_throttle_with_undo(throttle_manager, app, pending.pid, pending.name);
+                        needs_backend_refresh = true; // Throttle changed
+                        continue;
+                    }
+
+                    match action {
+                        Some(Action::Quit) => {
                             // Special handling for interface views and process detail
                             match app.view_mode {
                                 ui::ViewMode::InterfaceDetail => {
@@ -1518,18 +4000,28 @@ async fn run_app<B: ratatui::backend::Backend>(
                                 }
                                 ui::ViewMode::ProcessDetail => {
                                     app.exit_process_detail();
-                                    app.status_message = "Back to process list".to_string();
+                                    app.status_message =
+                                        if app.view_mode == ui::ViewMode::InterfaceDetail {
+                                            "Back to interface processes".to_string()
+                                        } else {
+                                            "Back to process list".to_string()
+                                        };
                                 }
                                 ui::ViewMode::ProcessView => {
                                     return Ok(());
                                 }
                             }
                         }
-                        KeyCode::Char('h') | KeyCode::Char('?') => {
+                        Some(Action::Help) => {
                             app.reset_help_scroll();
                             app.show_help = true;
                         }
-                        KeyCode::Char('b') => {
+                        Some(Action::Logs) => {
+                            // Start at the newest messages (clamped when drawn)
+                            app.help_scroll_offset = usize::MAX;
+                            app.show_logs = true;
+                        }
+                        Some(Action::Backends) => {
                             if !app.show_backend_info {
                                 // Build backend items when opening modal
                                 let mut backend_info = throttle_manager.get_backend_info(
@@ -1540,9 +4032,11 @@ async fn run_app<B: ratatui::backend::Backend>(
                                 {
                                     use crate::backends::process::socket_mapper::detect_socket_mappers;
                                     let socket_mappers = detect_socket_mappers();
+                                    backend_info.available_monitors = cached_monitors.clone();
                                     backend_info.available_socket_mappers =
                                         cached_socket_mappers.clone();
-                                    backend_info.active_monitoring = Some("pnet".to_string());
+                                    backend_info.active_monitoring =
+                                        current_monitor_backend.clone();
                                     backend_info.active_socket_mapper = socket_mapper_preference
                                         .map(|s| s.to_string())
                                         .or_else(|| config.preferred_socket_mapper.clone());
@@ -1557,7 +4051,64 @@ async fn run_app<B: ratatui::backend::Backend>(
                                 app.reset_backend_info_scroll();
                             }
                         }
-                        KeyCode::Char('f') => {
+                        Some(Action::CycleUploadBackend) => {
+                            match throttle_manager.cycle_default_upload_backend() {
+                                Ok(name) => {
+                                    config.preferred_upload_backend = Some(name.to_string());
+                                    let _ = config.save();
+                                    app.status_message =
+                                        format!("✅ Upload backend → {} (for new throttles)", name);
+                                }
+                                Err(e) => app.status_message = format!("❌ Upload backend: {}", e),
+                            }
+                            needs_backend_refresh = true;
+                        }
+                        Some(Action::CycleDownloadBackend) => {
+                            match throttle_manager.cycle_default_download_backend() {
+                                Ok(name) => {
+                                    config.preferred_download_backend = Some(name.to_string());
+                                    let _ = config.save();
+                                    app.status_message = format!(
+                                        "✅ Download backend → {} (for new throttles)",
+                                        name
+                                    );
+                                }
+                                Err(e) => {
+                                    app.status_message = format!("❌ Download backend: {}", e)
+                                }
+                            }
+                            needs_backend_refresh = true;
+                        }
+                        Some(Action::ApplyConfigBackends) => {
+                            let (upload, download) = throttle_manager.get_default_backends();
+                            let mut switched = Vec::new();
+                            let mut failed = Vec::new();
+                            if let Some(name) = config.preferred_upload_backend.clone()
+                                && upload.as_deref() != Some(name.as_str())
+                            {
+                                match throttle_manager.set_default_upload_backend(&name) {
+                                    Ok(()) => switched.push(format!("upload → {}", name)),
+                                    Err(e) => failed.push(format!("upload: {}", e)),
+                                }
+                            }
+                            if let Some(name) = config.preferred_download_backend.clone()
+                                && download.as_deref() != Some(name.as_str())
+                            {
+                                match throttle_manager.set_default_download_backend(&name) {
+                                    Ok(()) => switched.push(format!("download → {}", name)),
+                                    Err(e) => failed.push(format!("download: {}", e)),
+                                }
+                            }
+                            app.status_message = if !failed.is_empty() {
+                                format!("❌ Backend switch failed: {}", failed.join(", "))
+                            } else if switched.is_empty() {
+                                "Already using the config's preferred backends".to_string()
+                            } else {
+                                format!("✅ Backends: {} (for new throttles)", switched.join(", "))
+                            };
+                            needs_backend_refresh = true;
+                        }
+                        Some(Action::FreezeSort) => {
                             app.toggle_sort_freeze();
                             app.status_message = if app.sort_frozen {
                                 "Sort order frozen ❄️ - Stats continue updating, order preserved"
@@ -1566,26 +4117,24 @@ async fn run_app<B: ratatui::backend::Backend>(
                                 "Sort order unfrozen - Dynamic sorting re-enabled".to_string()
                             };
                         }
-                        KeyCode::Char('g') => {
+                        Some(Action::ToggleGraph) => {
                             app.show_graph = !app.show_graph;
                         }
-                        KeyCode::Down | KeyCode::Char('j') => {
-                            match app.view_mode {
-                                ui::ViewMode::ProcessView => app.select_next(),
-                                ui::ViewMode::InterfaceList => app.select_next_interface(),
-                                ui::ViewMode::InterfaceDetail => {} // No selection in detail view
-                                ui::ViewMode::ProcessDetail => app.scroll_detail_down(),
-                            }
-                        }
-                        KeyCode::Up | KeyCode::Char('k') => {
-                            match app.view_mode {
-                                ui::ViewMode::ProcessView => app.select_previous(),
-                                ui::ViewMode::InterfaceList => app.select_previous_interface(),
-                                ui::ViewMode::InterfaceDetail => {} // No selection in detail view
-                                ui::ViewMode::ProcessDetail => app.scroll_detail_up(),
+                        Some(Action::MoveDown) => match app.view_mode {
+                            ui::ViewMode::ProcessView => app.select_next(),
+                            ui::ViewMode::InterfaceList => app.select_next_interface(),
+                            ui::ViewMode::InterfaceDetail => app.select_next_in_interface_detail(),
+                            ui::ViewMode::ProcessDetail => app.detail_move_down(),
+                        },
+                        Some(Action::MoveUp) => match app.view_mode {
+                            ui::ViewMode::ProcessView => app.select_previous(),
+                            ui::ViewMode::InterfaceList => app.select_previous_interface(),
+                            ui::ViewMode::InterfaceDetail => {
+                                app.select_previous_in_interface_detail()
                             }
-                        }
-                        KeyCode::PageUp => {
+                            ui::ViewMode::ProcessDetail => app.detail_move_up(),
+                        },
+                        Some(Action::PageUp) => {
                             match app.view_mode {
                                 ui::ViewMode::ProcessView => {
                                     // Select previous by 10
@@ -1599,7 +4148,11 @@ async fn run_app<B: ratatui::backend::Backend>(
                                         app.scroll_interface_modal_up();
                                     }
                                 }
-                                ui::ViewMode::InterfaceDetail => {}
+                                ui::ViewMode::InterfaceDetail => {
+                                    for _ in 0..10 {
+                                        app.select_previous_in_interface_detail();
+                                    }
+                                }
                                 ui::ViewMode::ProcessDetail => {
                                     // Scroll detail view up by 10 lines
                                     for _ in 0..10 {
@@ -1608,7 +4161,7 @@ async fn run_app<B: ratatui::backend::Backend>(
                                 }
                             }
                         }
-                        KeyCode::PageDown => {
+                        Some(Action::PageDown) => {
                             match app.view_mode {
                                 ui::ViewMode::ProcessView => {
                                     // Select next by 10
@@ -1622,7 +4175,11 @@ async fn run_app<B: ratatui::backend::Backend>(
                                         app.scroll_interface_modal_down();
                                     }
                                 }
-                                ui::ViewMode::InterfaceDetail => {}
+                                ui::ViewMode::InterfaceDetail => {
+                                    for _ in 0..10 {
+                                        app.select_next_in_interface_detail();
+                                    }
+                                }
                                 ui::ViewMode::ProcessDetail => {
                                     // Scroll detail view down by 10 lines
                                     for _ in 0..10 {
@@ -1631,7 +4188,7 @@ async fn run_app<B: ratatui::backend::Backend>(
                                 }
                             }
                         }
-                        KeyCode::Char('i') => {
+                        Some(Action::ToggleInterfaces) => {
                             app.toggle_view_mode();
                             app.status_message = match app.view_mode {
                                 ui::ViewMode::ProcessView => "Switched to process view".to_string(),
@@ -1645,7 +4202,7 @@ async fn run_app<B: ratatui::backend::Backend>(
                                 ui::ViewMode::ProcessDetail => "Back to process list".to_string(),
                             };
                         }
-                        KeyCode::Char('l') => {
+                        Some(Action::CycleTrafficView) => {
                             app.toggle_traffic_view_mode();
                             // status_message is set by toggle_traffic_view_mode()
 
@@ -1660,8 +4217,13 @@ async fn run_app<B: ratatui::backend::Backend>(
                             if let Err(e) = config.save() {
                                 log::warn!("Failed to save traffic view mode to config: {}", e);
                             }
+
+                            // Loopback traffic is all local, so don't capture it in the Internet view
+                            let _ = monitor_cmd_tx.send(MonitorCommand::SetSkipLoopback(
+                                app.traffic_view_mode == ui::TrafficViewMode::Internet,
+                            ));
                         }
-                        KeyCode::Enter => match app.view_mode {
+                        Some(Action::ViewDetails) => match app.view_mode {
                             ui::ViewMode::InterfaceList => {
                                 app.enter_interface_detail();
                                 if let Some(iface) = app.get_selected_interface() {
@@ -1669,15 +4231,15 @@ async fn run_app<B: ratatui::backend::Backend>(
                                         format!("Viewing processes on interface: {}", iface.name);
                                 }
                             }
-                            ui::ViewMode::ProcessView => {
-                                if app.get_selected_process().is_some() {
-                                    app.enter_process_detail();
-                                    app.status_message = "Viewing process details".to_string();
-                                }
+                            ui::ViewMode::ProcessView | ui::ViewMode::InterfaceDetail
+                                if app.get_target_process().is_some() =>
+                            {
+                                app.enter_process_detail();
+                                app.status_message = "Viewing process details".to_string();
                             }
                             _ => {}
                         },
-                        KeyCode::Tab => {
+                        Some(Action::NextTab) => {
                             // Tab switches tabs in process detail view
                             if app.view_mode == ui::ViewMode::ProcessDetail {
                                 app.next_detail_tab();
@@ -1690,7 +4252,7 @@ async fn run_app<B: ratatui::backend::Backend>(
                                 app.status_message = format!("Switched to {} tab", tab_name);
                             }
                         }
-                        KeyCode::BackTab => {
+                        Some(Action::PreviousTab) => {
                             // Shift+Tab switches tabs backwards in process detail view
                             if app.view_mode == ui::ViewMode::ProcessDetail {
                                 app.previous_detail_tab();
@@ -1703,7 +4265,7 @@ async fn run_app<B: ratatui::backend::Backend>(
                                 app.status_message = format!("Switched to {} tab", tab_name);
                             }
                         }
-                        KeyCode::Char(' ') => {
+                        Some(Action::ToggleInterfaceFilter) => {
                             // Space bar toggles filter in interface list view
                             if app.view_mode == ui::ViewMode::InterfaceList {
                                 if let Some(iface) = app.get_selected_interface() {
@@ -1719,7 +4281,7 @@ async fn run_app<B: ratatui::backend::Backend>(
                                 }
                             }
                         }
-                        KeyCode::Char('a') | KeyCode::Char('A') => {
+                        Some(Action::ToggleAllInterfaces) => {
                             // 'A' - Toggle all/none in interface list view
                             if app.view_mode == ui::ViewMode::InterfaceList {
                                 app.toggle_all_interface_filters();
@@ -1731,8 +4293,72 @@ async fn run_app<B: ratatui::backend::Backend>(
                                 }
                             }
                         }
-                        KeyCode::Char('t') => {
-                            if let Some(process) = app.get_selected_process() {
+                        Some(Action::AddInterfacePattern)
+                            if app.view_mode == ui::ViewMode::InterfaceList =>
+                        {
+                            app.interface_pattern_input = Some(String::new());
+                        }
+                        Some(Action::ToggleTreeView)
+                            if app.view_mode == ui::ViewMode::ProcessView =>
+                        {
+                            app.toggle_tree_view();
+
+                            // Save tree view preference to config
+                            config.process_tree_view = app.tree_view;
+                            if let Err(e) = config.save() {
+                                log::warn!("Failed to save tree view mode to config: {}", e);
+                            }
+                        }
+                        Some(Action::ToggleContainerView)
+                            if app.view_mode == ui::ViewMode::ProcessView =>
+                        {
+                            app.toggle_container_view();
+
+                            config.container_view = app.container_view;
+                            if let Err(e) = config.save() {
+                                log::warn!("Failed to save container view mode to config: {}", e);
+                            }
+                        }
+                        Some(Action::ToggleCmdline)
+                            if app.view_mode == ui::ViewMode::ProcessView =>
+                        {
+                            app.toggle_cmdline();
+                            app.refresh_cmdlines(process_utils.as_ref());
+
+                            // Save command line preference to config
+                            config.show_cmdline = app.show_cmdline;
+                            if let Err(e) = config.save() {
+                                log::warn!("Failed to save command line mode to config: {}", e);
+                            }
+                        }
+                        Some(Action::ToggleTcpHealth)
+                            if app.view_mode == ui::ViewMode::ProcessView =>
+                        {
+                            app.toggle_tcp_health();
+
+                            config.show_tcp_health = app.show_tcp_health;
+                            if let Err(e) = config.save() {
+                                log::warn!("Failed to save TCP health columns to config: {}", e);
+                            }
+                        }
+                        Some(Action::ToggleListenOnly)
+                            if app.view_mode == ui::ViewMode::ProcessView =>
+                        {
+                            app.toggle_listen_only();
+
+                            config.show_listen_only = app.show_listen_only;
+                            if let Err(e) = config.save() {
+                                log::warn!("Failed to save listen-only setting to config: {}", e);
+                            }
+                        }
+                        Some(Action::Expand) if app.view_mode == ui::ViewMode::ProcessView => {
+                            app.expand_selected();
+                        }
+                        Some(Action::Collapse) if app.view_mode == ui::ViewMode::ProcessView => {
+                            app.collapse_selected();
+                        }
+                        Some(Action::Throttle) => {
+                            if let Some(process) = app.get_target_process() {
                                 // Clone the values we need
                                 let pid = process.pid;
                                 let name = process.name.clone();
@@ -1740,29 +4366,129 @@ async fn run_app<B: ratatui::backend::Backend>(
                                 // Open throttle dialog
                                 app.throttle_dialog.target_pid = Some(pid);
                                 app.throttle_dialog.target_name = Some(name);
+                                app.throttle_dialog.traffic_support =
+                                    throttle_manager.current_traffic_support();
+                                app.throttle_dialog.family_support =
+                                    throttle_manager.current_family_support();
+                                app.throttle_dialog.minimum_support = Some(
+                                    throttle_manager.current_upload_backend_supports_minimum(),
+                                );
+                                app.throttle_dialog.priority_support = Some((
+                                    throttle_manager.current_upload_backend_supports_priority(),
+                                    throttle_manager.current_download_backend_supports_priority(),
+                                ));
+
+                                // Offer to throttle the whole subtree (on by default for collapsed tree rows)
+                                let collapsed_parent = app.view_mode == ui::ViewMode::ProcessView
+                                    && app
+                                        .get_selected_tree_row()
+                                        .is_some_and(|row| row.is_collapsed_parent());
+                                let children = process_utils
+                                    .get_descendants(pid)
+                                    .into_iter()
+                                    .map(|entry| (entry.pid, entry.name))
+                                    .collect();
+                                app.throttle_dialog
+                                    .set_child_processes(children, collapsed_parent);
+                                let interfaces = app
+                                    .interface_list
+                                    .iter()
+                                    .map(|iface| iface.name.clone())
+                                    .collect();
+                                app.throttle_dialog.set_available_interfaces(interfaces);
                                 app.show_throttle_dialog = true;
                             } else {
                                 app.status_message = "No process selected".to_string();
                             }
                         }
-                        KeyCode::Char('r') => {
-                            if let Some(process) = app.get_selected_process() {
-                                // Remove throttle
-                                match throttle_manager.remove_throttle(process.pid) {
-                                    Ok(_) => {
-                                        needs_backend_refresh = true; // Throttle changed
+                        Some(Action::ToggleMark) => {
+                            if let Some(pid) = app.get_target_process().map(|proc| proc.pid) {
+                                app.toggle_mark(pid);
+                            }
+                        }
+                        Some(Action::TogglePin) => {
+                            if let Some(process) = app.get_target_process() {
+                                let (pid, name) = (process.pid, process.name.clone());
+                                app.toggle_pin(pid, &name);
+                            }
+                        }
+                        Some(Action::ThrottleAllExcept) => {
+                            if throttle_manager.except_mode_exclusions().is_some() {
+                                let removed = throttle_manager.stop_except_mode();
+                                needs_backend_refresh = true;
+                                app.status_message = format!(
+                                    "Throttle-all-except stopped, removed {} throttle(s)",
+                                    removed
+                                );
+                            } else if app.marked_pids.is_empty() {
+                                app.status_message = format!(
+                                    "Mark the processes to leave alone with '{}' first",
+                                    app.keymap.label(Action::ToggleMark)
+                                );
+                            } else {
+                                app.throttle_dialog.excluded_names = Some(app.marked_names());
+                                app.throttle_dialog.traffic_support =
+                                    throttle_manager.current_traffic_support();
+                                app.throttle_dialog.family_support =
+                                    throttle_manager.current_family_support();
+                                app.throttle_dialog.minimum_support = Some(
+                                    throttle_manager.current_upload_backend_supports_minimum(),
+                                );
+                                app.throttle_dialog.priority_support = Some((
+                                    throttle_manager.current_upload_backend_supports_priority(),
+                                    throttle_manager.current_download_backend_supports_priority(),
+                                ));
+                                let interfaces = app
+                                    .interface_list
+                                    .iter()
+                                    .map(|iface| iface.name.clone())
+                                    .collect();
+                                app.throttle_dialog.set_available_interfaces(interfaces);
+                                app.show_throttle_dialog = true;
+                            }
+                        }
+                        Some(Action::StandingRules) => {
+                            app.standing_rules_modal = Some(ui::StandingRulesModal::default());
+                            refresh_standing_rules_modal(app, config, throttle_manager);
+                        }
+                        Some(Action::Yank) => match app.yank_text() {
+                            Some(text) => copy_to_clipboard(app, &text),
+                            None => app.status_message = "Nothing selected to copy".to_string(),
+                        },
+                        Some(Action::CopyCommand) => {
+                            if let Some(process) = app.get_target_process() {
+                                match throttle_manager.get_throttle(process.pid) {
+                                    Some(throttle) => {
+                                        app.throttle_command =
+                                            Some(ThrottleCommand::for_throttle(&throttle));
+                                    }
+                                    None => {
                                         app.status_message = format!(
-                                            "Throttle removed from {} (PID {})",
+                                            "{} (PID {}) isn't throttled",
                                             process.name, process.pid
                                         );
                                     }
-                                    Err(e) => {
-                                        app.status_message =
-                                            format!("Failed to remove throttle: {}", e);
-                                    }
                                 }
                             }
                         }
+                        Some(Action::RemoveThrottle) => {
+                            if let Some(process) = app.get_target_process() {
+                                let (pid, name) = (process.pid, process.name.clone());
+                                if throttle_manager.get_throttle(pid).is_none() {
+                                    app.status_message =
+                                        format!("{} (PID {}) isn't throttled", name, pid);
+                                } else if app.confirm_throttle_removal {
+                                    app.ask_removal(pid, name);
+                                } else {
+                                    remove_throttle_with_undo(throttle_manager, app, pid, name);
+                                    needs_backend_refresh = true; // Throttle changed
+                                }
+                            }
+                        }
+                        Some(Action::Undo) => {
+                            undo_throttle_change(throttle_manager, process_utils.as_ref(), app);
+                            needs_backend_refresh = true; // Throttle changed
+                        }
                         _ => {}
                     }
                 }
@@ -1771,7 +4497,7 @@ async fn run_app<B: ratatui::backend::Backend>(
                     match mouse.kind {
                         MouseEventKind::ScrollUp => {
                             // Handle scroll up based on current state (priority: modals first, then view modes)
-                            if app.show_help {
+                            if app.show_help || app.show_logs {
                                 app.scroll_help_up();
                             } else if app.show_backend_info {
                                 app.scroll_backend_info_up();
@@ -1803,7 +4529,7 @@ async fn run_app<B: ratatui::backend::Backend>(
                         }
                         MouseEventKind::ScrollDown => {
                             // Handle scroll down based on current state (priority: modals first, then view modes)
-                            if app.show_help {
+                            if app.show_help || app.show_logs {
                                 app.scroll_help_down();
                             } else if app.show_backend_info {
                                 app.scroll_backend_info_down();
@@ -1856,6 +4582,7 @@ async fn run_app<B: ratatui::backend::Backend>(
                                             if app.view_mode != ui::ViewMode::ProcessView
                                                 || app.show_backend_info
                                                 || app.show_help
+                                                || app.show_logs
                                                 || app.show_throttle_dialog
                                                 || app.show_graph
                                                 || app.show_backend_compatibility_dialog
@@ -1870,11 +4597,7 @@ async fn run_app<B: ratatui::backend::Backend>(
                                             let clicked_index =
                                                 first_visible_index + relative_y as usize;
 
-                                            // Validate index is within bounds
-                                            if clicked_index < app.process_list.len() {
-                                                app.selected_index = Some(clicked_index);
-                                                app.list_state.select(Some(clicked_index));
-                                            }
+                                            app.select_index(clicked_index);
                                         }
                                         ui::ClickableRegionType::ProcessDetailTabs {
                                             tab_ranges,
@@ -1968,6 +4691,21 @@ async fn run_app<B: ratatui::backend::Backend>(
             continue; // Skip to next iteration to handle more input if available
         }
 
+        // Processes that exec'd since the last pass get the standing rules right away
+        #[cfg(target_os = "linux")]
+        if let Some(watcher) = &exec_watcher {
+            for pid in watcher.new_pids() {
+                if throttle_manager.standing_rules().is_empty() {
+                    continue;
+                }
+                if let Ok(name) = process_utils.get_process_name(pid)
+                    && throttle_manager.apply_standing_rules(pid, &name).is_some()
+                {
+                    needs_backend_refresh = true;
+                }
+            }
+        }
+
         // PRIORITY 2: Check for network stats updates (non-blocking!)
         // The monitoring thread sends updates approximately once per second
         // We use try_recv() which never blocks, keeping UI responsive at all times
@@ -1977,8 +4715,10 @@ async fn run_app<B: ratatui::backend::Backend>(
             // Extract data and update backend state tracking
             let mut process_map = update_data.process_map;
             let interface_map = update_data.interface_map;
+            current_monitor_backend = Some(update_data.monitor_backend_name.to_string());
             current_socket_mapper = Some(update_data.socket_mapper_name);
             current_socket_mapper_caps = Some(update_data.socket_mapper_capabilities);
+            current_socket_cache = update_data.socket_cache;
 
             let monitor_update_time = update_start.elapsed();
 
@@ -1993,8 +4733,14 @@ async fn run_app<B: ratatui::backend::Backend>(
                     process_info.throttle_limit = Some(crate::process::ThrottleLimit {
                         download_limit: throttle.download_limit,
                         upload_limit: throttle.upload_limit,
+                        upload_minimum: throttle.upload_minimum,
                         traffic_type: crate::process::TrafficType::All, // Backend throttles use All for now
+                        interfaces: throttle.interfaces,
+                        policy: throttle.policy,
+                        family: throttle.family,
+                        priority: throttle.priority,
                     });
+                    process_info.throttle_sandboxed = throttle.sandboxed;
 
                     // Log bandwidth vs throttle limit periodically
                     if should_log_bandwidth {
@@ -2012,11 +4758,11 @@ async fn run_app<B: ratatui::backend::Backend>(
                             };
 
                             log::info!(
-                                "PID {} ({}) download: actual={}/s, limit={}/s, ratio={:.2}x {}",
+                                "PID {} ({}) download: actual={}, limit={}, ratio={:.2}x {}",
                                 pid,
                                 process_info.name,
-                                human_readable(actual_bps),
-                                human_readable(download_limit),
+                                format::format_rate(actual_bps),
+                                format::format_rate(download_limit),
                                 ratio,
                                 status
                             );
@@ -2039,9 +4785,92 @@ async fn run_app<B: ratatui::backend::Backend>(
 
             let throttle_update_time = throttle_start.elapsed();
 
+            if let Some(alerter) = &mut alerter {
+                for event in alerter.check(&process_map) {
+                    log::warn!(
+                        "Bandwidth alert: {} (PID {}) {} {}",
+                        event.name,
+                        event.pid,
+                        event.direction,
+                        format::format_rate(event.rate)
+                    );
+                    let throttle = throttle_manager.get_throttle(event.pid);
+                    throttle_manager.publish_event(
+                        ThrottleEvent::new(ThrottleEventKind::LimitExceeded, event.pid, event.name)
+                            .with_limits(
+                                throttle.as_ref().and_then(|t| t.download_limit),
+                                throttle.as_ref().and_then(|t| t.upload_limit),
+                            )
+                            .with_detail(format!(
+                                "{} {} exceeds {}",
+                                event.direction,
+                                format::format_rate(event.rate),
+                                format::format_rate(alerter.threshold())
+                            )),
+                    );
+                }
+            }
+
+            for event in utilization_tracker.update(&process_map) {
+                let detail = format!(
+                    "{} at {:.0}% of its {} limit for the last {} updates",
+                    event.direction,
+                    event.percent,
+                    format::format_rate(event.limit),
+                    utilization::WINDOW
+                );
+                log::warn!(
+                    "Throttle not working: {} (PID {}) {}",
+                    event.name,
+                    event.pid,
+                    detail
+                );
+                let throttle = throttle_manager.get_throttle(event.pid);
+                throttle_manager.publish_event(
+                    ThrottleEvent::new(
+                        ThrottleEventKind::ThrottleNotWorking,
+                        event.pid,
+                        event.name,
+                    )
+                    .with_limits(
+                        throttle.as_ref().and_then(|t| t.download_limit),
+                        throttle.as_ref().and_then(|t| t.upload_limit),
+                    )
+                    .with_backends(
+                        throttle.as_ref().and_then(|t| t.upload_backend.as_deref()),
+                        throttle
+                            .as_ref()
+                            .and_then(|t| t.download_backend.as_deref()),
+                    )
+                    .with_detail(detail),
+                );
+            }
+            app.throttle_utilization = utilization_tracker.all();
+
+            if let Some(quota_tracker) = &mut quota_tracker {
+                quota_tracker.record(&process_map);
+                if let Some(check) = quota_tracker.check(&process_map) {
+                    apply_quota_check(check, quota_tracker, throttle_manager, app);
+                }
+            }
+
             let app_update_start = std::time::Instant::now();
             app.update_processes(process_map);
+            app.refresh_cmdlines(process_utils.as_ref());
             app.update_interfaces(interface_map);
+            app.update_detail_throttle_stats(
+                app.selected_process_detail_pid
+                    .map(|pid| throttle_manager.get_stats(pid))
+                    .unwrap_or_default(),
+            );
+            let detail_throttle = app
+                .selected_process_detail_pid
+                .and_then(|pid| throttle_manager.get_throttle(pid));
+            app.detail_throttle_downgraded =
+                detail_throttle.as_ref().and_then(|t| t.downgraded_from);
+            app.detail_throttle_backends = detail_throttle
+                .map(|t| (t.upload_backend, t.download_backend))
+                .unwrap_or_default();
             let app_update_time = app_update_start.elapsed();
 
             // Update status with process count
@@ -2055,6 +4884,67 @@ async fn run_app<B: ratatui::backend::Backend>(
                 );
             }
 
+            // Throttle processes that appeared while throttling "everything except"
+            for (pid, name) in throttle_manager.sync_except_mode(&app.unfiltered_process_list) {
+                log::info!("Throttled new process {} (PID {})", name, pid);
+            }
+
+            // ... and the ones a standing rule matches
+            throttle_manager.sync_standing_rules(&app.unfiltered_process_list);
+            refresh_standing_rules_modal(app, config, throttle_manager);
+
+            // What --status shows for this instance
+            status_file.update(
+                throttle_manager
+                    .get_all_throttles()
+                    .into_values()
+                    .map(|throttle| instance_status::ThrottleStatus {
+                        pid: throttle.pid,
+                        standing_rule: throttle_manager
+                            .standing_rule_for(throttle.pid)
+                            .map(str::to_string),
+                        process_name: throttle.process_name,
+                        download_limit: throttle.download_limit,
+                        upload_limit: throttle.upload_limit,
+                        utilization: app.throttle_utilization.get(&throttle.pid).copied(),
+                    })
+                    .collect(),
+            );
+
+            // Add children spawned since the last update to throttles that follow them
+            throttle_manager.sync_subtrees(process_utils.as_ref());
+
+            // Move per-CPU eBPF rate shares to the CPUs the traffic is on
+            throttle_manager.rebalance_throttles();
+
+            // Let userspace-enforced throttles correct their pacing
+            throttle_manager.observe_rates(&app.unfiltered_process_list);
+
+            // Check for (and fix) throttles whose eBPF config or attachment went stale
+            // every 5 seconds
+            if should_log_bandwidth {
+                let uploading: std::collections::HashSet<i32> = app
+                    .process_list
+                    .iter()
+                    .filter(|p| p.upload_rate > 0)
+                    .map(|p| p.pid)
+                    .collect();
+                let downloading: std::collections::HashSet<i32> = app
+                    .process_list
+                    .iter()
+                    .filter(|p| p.download_rate > 0)
+                    .map(|p| p.pid)
+                    .collect();
+                let repaired = throttle_manager.repair_throttles(&uploading, &downloading);
+                if !repaired.is_empty() {
+                    let pids: Vec<String> = repaired.iter().map(|pid| pid.to_string()).collect();
+                    app.raise_alert(format!(
+                        "Repaired throttle for PID {} (stale eBPF state)",
+                        pids.join(", ")
+                    ));
+                }
+            }
+
             // Log performance metrics every 5 seconds
             if should_log_bandwidth {
                 log::info!(
@@ -2065,6 +4955,35 @@ async fn run_app<B: ratatui::backend::Backend>(
                 );
             }
 
+            // Foreign qdiscs can appear at any time (checked every 30s at most)
+            throttle_manager.refresh_external_shaping();
+
+            // Pick up edits to the config file (an edit that doesn't parse is reported
+            // and the running config kept)
+            match config_watcher.as_mut().and_then(|watcher| watcher.poll()) {
+                Some(Ok(reloaded)) => {
+                    if let Some(message) = apply_config_reload(
+                        reloaded,
+                        config,
+                        app,
+                        throttle_manager,
+                        &mut alerter,
+                        &mut quota_tracker,
+                        &mut webhook_notifier,
+                    ) {
+                        app.status_message = message;
+                    }
+                }
+                Some(Err(e)) => {
+                    log::warn!("Config reload failed: {:#}", e);
+                    app.status_message = format!(
+                        "❌ Config reload failed, keeping the running config: {:#}",
+                        e
+                    );
+                }
+                None => {}
+            }
+
             // Data updated - need to redraw and refresh backend info
             needs_redraw = true;
             needs_backend_refresh = true;
@@ -2079,10 +4998,12 @@ async fn run_app<B: ratatui::backend::Backend>(
             );
 
             // Use cached socket mappers and current backend state (no system calls!)
+            backend_info.available_monitors = cached_monitors.clone();
             backend_info.available_socket_mappers = cached_socket_mappers.clone();
-            backend_info.active_monitoring = Some("pnet".to_string());
+            backend_info.active_monitoring = current_monitor_backend.clone();
             backend_info.active_socket_mapper = current_socket_mapper.clone();
             backend_info.socket_mapper_capabilities = current_socket_mapper_caps.clone();
+            backend_info.socket_cache = current_socket_cache;
             backend_info.preferred_socket_mapper = config.preferred_socket_mapper.clone();
 
             cached_backend_info = Some(backend_info);
@@ -2094,6 +5015,18 @@ async fn run_app<B: ratatui::backend::Backend>(
             );
         }
 
+        // Hand throttle events to the webhook and the status bar
+        let events = throttle_manager.drain_events();
+        if let Some(notifier) = &mut webhook_notifier {
+            for event in &events {
+                notifier.notify(event);
+            }
+        }
+        if let Some(alert) = event_alert(&events) {
+            app.raise_alert(alert);
+            needs_redraw = true;
+        }
+
         // PRIORITY 4: Draw UI only when data changed
         if needs_redraw {
             let draw_start = std::time::Instant::now();
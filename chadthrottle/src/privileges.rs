@@ -0,0 +1,238 @@
+// Startup check of the privileges the selected throttle backends need
+//
+// Without them the backends fail on the first throttle with errors that don't say
+// why (EPERM from tc, a failed bpf() call). Checking the effective capabilities
+// up front lets us say exactly what is missing once; monitoring needs none of them.
+// On Linux root isn't enough on its own: containers often drop CAP_NET_ADMIN.
+
+use std::fmt;
+
+/// A capability a throttle backend needs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capability {
+    /// tc qdiscs/filters, nftables rules, attaching cgroup BPF programs
+    NetAdmin,
+    /// Loading BPF programs (CAP_SYS_ADMIN on kernels before 5.8)
+    Bpf,
+}
+
+impl Capability {
+    /// Bit in the kernel's capability sets
+    fn bit(self) -> u32 {
+        match self {
+            Capability::NetAdmin => 12,
+            Capability::Bpf => 39,
+        }
+    }
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Capability::NetAdmin => write!(f, "CAP_NET_ADMIN"),
+            Capability::Bpf => write!(f, "CAP_BPF"),
+        }
+    }
+}
+
+const CAP_SYS_ADMIN_BIT: u32 = 21;
+
+/// Capabilities a throttle backend needs, by backend name
+pub fn required_capabilities(backend: &str) -> &'static [Capability] {
+    match backend {
+        "tc_htb" | "ifb_tc" | "tc_police" | "nftables" | "dnctl" => &[Capability::NetAdmin],
+        "ebpf" | "ebpf_cgroup_upload" | "ebpf_cgroup_download" => {
+            &[Capability::Bpf, Capability::NetAdmin]
+        }
+        // The proxy only binds sockets and the null backend touches nothing
+        _ => &[],
+    }
+}
+
+/// Effective privileges of this process
+#[derive(Debug, Clone, Copy)]
+pub struct Privileges {
+    pub euid: u32,
+    /// Effective capability set (None where the platform has no capabilities)
+    pub effective: Option<u64>,
+}
+
+impl Privileges {
+    pub fn current() -> Self {
+        #[cfg(unix)]
+        let euid = unsafe { libc::geteuid() };
+        #[cfg(not(unix))]
+        let euid = 0;
+
+        #[cfg(target_os = "linux")]
+        let effective = std::fs::read_to_string("/proc/self/status")
+            .ok()
+            .and_then(|status| parse_cap_eff(&status));
+        #[cfg(not(target_os = "linux"))]
+        let effective = None;
+
+        Self { euid, effective }
+    }
+
+    /// Whether we hold `capability` (without a capability set, root holds them all)
+    pub fn has(&self, capability: Capability) -> bool {
+        let Some(effective) = self.effective else {
+            return self.euid == 0;
+        };
+        let held = |bit: u32| effective & (1 << bit) != 0;
+        held(capability.bit()) || (capability == Capability::Bpf && held(CAP_SYS_ADMIN_BIT))
+    }
+}
+
+/// Backends that will fail for lack of privileges, with what each is missing
+#[derive(Debug, Clone, PartialEq)]
+pub struct MissingPrivileges {
+    pub backends: Vec<(String, Vec<Capability>)>,
+    pub root: bool,
+    /// Whether the platform has capabilities (elsewhere only root counts)
+    pub per_capability: bool,
+}
+
+impl MissingPrivileges {
+    /// Every missing capability, once each
+    fn capabilities(&self) -> Vec<Capability> {
+        let mut capabilities = Vec::new();
+        for capability in self.backends.iter().flat_map(|(_, missing)| missing) {
+            if !capabilities.contains(capability) {
+                capabilities.push(*capability);
+            }
+        }
+        capabilities
+    }
+
+    fn capability_list(&self) -> String {
+        if !self.per_capability {
+            return "root privileges".to_string();
+        }
+        self.capabilities()
+            .iter()
+            .map(|capability| capability.to_string())
+            .collect::<Vec<_>>()
+            .join(" and ")
+    }
+
+    /// One-line status for the TUI status bar
+    pub fn short(&self) -> String {
+        format!("Missing {}: monitoring only", self.capability_list())
+    }
+
+    /// The full explanation, naming each backend and what it needs
+    pub fn message(&self) -> String {
+        let backends = self
+            .backends
+            .iter()
+            .map(|(backend, missing)| {
+                let missing: Vec<String> = missing.iter().map(|c| c.to_string()).collect();
+                if self.per_capability {
+                    format!("{} needs {}", backend, missing.join(" and "))
+                } else {
+                    format!("{} needs root", backend)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let fix = if self.root && self.per_capability {
+            // Root without the capability: a container or a restricted service
+            "grant them to the container or service (docker run --cap-add, systemd AmbientCapabilities=)"
+        } else {
+            "run chadthrottle as root (e.g. with sudo)"
+        };
+        format!(
+            "Throttling will fail: {} ({}). Monitoring still works; to throttle, {}.",
+            self.capability_list(),
+            backends,
+            fix
+        )
+    }
+}
+
+/// Check the selected backends against `privileges`
+pub fn check(backends: &[&str], privileges: &Privileges) -> Option<MissingPrivileges> {
+    let mut missing_by_backend: Vec<(String, Vec<Capability>)> = Vec::new();
+    for backend in backends {
+        if missing_by_backend.iter().any(|(name, _)| name == backend) {
+            continue; // same backend for upload and download
+        }
+        let missing: Vec<Capability> = required_capabilities(backend)
+            .iter()
+            .copied()
+            .filter(|capability| !privileges.has(*capability))
+            .collect();
+        if !missing.is_empty() {
+            missing_by_backend.push((backend.to_string(), missing));
+        }
+    }
+
+    (!missing_by_backend.is_empty()).then(|| MissingPrivileges {
+        backends: missing_by_backend,
+        root: privileges.euid == 0,
+        per_capability: privileges.effective.is_some(),
+    })
+}
+
+/// The CapEff line of /proc/<pid>/status
+#[cfg(target_os = "linux")]
+fn parse_cap_eff(status: &str) -> Option<u64> {
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("CapEff:"))
+        .and_then(|hex| u64::from_str_radix(hex.trim(), 16).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_parse_cap_eff() {
+        let status = "Name:\tchadthrottle\nUid:\t0\t0\t0\t0\nCapPrm:\t000001ffffffffff\nCapEff:\t00000000a80425fb\n";
+        let effective = parse_cap_eff(status).unwrap();
+        assert_eq!(effective, 0xa80425fb);
+
+        // Docker's default set: no CAP_NET_ADMIN, no CAP_BPF or CAP_SYS_ADMIN
+        let docker = Privileges {
+            euid: 0,
+            effective: Some(effective),
+        };
+        assert!(!docker.has(Capability::NetAdmin));
+        assert!(!docker.has(Capability::Bpf));
+    }
+
+    #[test]
+    fn test_check_reports_missing_capabilities_per_backend() {
+        let net_admin_only = Privileges {
+            euid: 1000,
+            effective: Some(1 << 12),
+        };
+        let missing = check(&["ebpf", "ebpf", "proxy"], &net_admin_only).unwrap();
+        assert_eq!(
+            missing.backends,
+            vec![("ebpf".to_string(), vec![Capability::Bpf])]
+        );
+        assert_eq!(missing.short(), "Missing CAP_BPF: monitoring only");
+
+        // CAP_SYS_ADMIN stands in for CAP_BPF on older kernels
+        let sys_admin = Privileges {
+            euid: 1000,
+            effective: Some((1 << 12) | (1 << 21)),
+        };
+        assert_eq!(check(&["ebpf", "tc_htb"], &sys_admin), None);
+
+        let unprivileged = Privileges {
+            euid: 1000,
+            effective: Some(0),
+        };
+        let message = check(&["tc_htb", "ifb_tc"], &unprivileged)
+            .unwrap()
+            .message();
+        assert!(message.contains("tc_htb needs CAP_NET_ADMIN, ifb_tc needs CAP_NET_ADMIN"));
+        assert!(message.contains("sudo"));
+        assert_eq!(check(&["proxy"], &unprivileged), None);
+    }
+}
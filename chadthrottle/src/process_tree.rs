@@ -0,0 +1,267 @@
+// Parent/child grouping of the process list for the tree view
+
+use crate::process::ProcessInfo;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+/// Ordering used for top-level rows
+pub type RootOrder<'a> = &'a dyn Fn(&ProcessInfo, &ProcessInfo) -> Ordering;
+
+/// Display metadata for one row of the tree view (parallel to the visible process list)
+#[derive(Debug, Clone)]
+pub struct TreeRow {
+    /// Nesting level (0 = top level)
+    pub depth: usize,
+    /// All descendants of this row in the current process set (PID, name), depth-first
+    pub descendants: Vec<(i32, String)>,
+    /// Whether children are shown below this row
+    pub expanded: bool,
+    /// Own traffic plus all descendants' traffic (only for rows with descendants)
+    pub subtree: Option<ProcessInfo>,
+}
+
+impl TreeRow {
+    pub fn has_children(&self) -> bool {
+        !self.descendants.is_empty()
+    }
+
+    /// Collapsed rows with children display their subtree totals
+    pub fn is_collapsed_parent(&self) -> bool {
+        self.has_children() && !self.expanded
+    }
+}
+
+/// Arrange `processes` (already in the desired flat order) as a forest and flatten it
+/// into the rows to display.
+///
+/// A process is nested under its parent when the parent is also in `processes`;
+/// otherwise it appears at top level. Children keep their relative input order.
+/// Top-level rows are ordered by `compare_roots` on their subtree totals, or keep the
+/// input order when `None` (e.g. while sorting is frozen). Only children of PIDs in
+/// `expanded` are emitted.
+pub fn flatten(
+    processes: Vec<ProcessInfo>,
+    expanded: &HashSet<i32>,
+    compare_roots: Option<RootOrder>,
+) -> (Vec<ProcessInfo>, Vec<TreeRow>) {
+    let order: Vec<i32> = processes.iter().map(|p| p.pid).collect();
+    let by_pid: HashMap<i32, ProcessInfo> = processes.into_iter().map(|p| (p.pid, p)).collect();
+
+    // Link each process to its parent if the parent is in the set
+    let mut roots = Vec::new();
+    let mut children: HashMap<i32, Vec<i32>> = HashMap::new();
+    for &pid in &order {
+        match by_pid[&pid].parent_pid {
+            Some(ppid) if ppid != pid && by_pid.contains_key(&ppid) => {
+                children.entry(ppid).or_default().push(pid);
+            }
+            _ => roots.push(pid),
+        }
+    }
+
+    // Stale parent info can form cycles that are unreachable from any root;
+    // surface those processes at top level instead of dropping them
+    let mut reachable = HashSet::new();
+    for &root in &roots {
+        collect_descendants(root, &children, &mut reachable, &mut Vec::new());
+    }
+    for &pid in &order {
+        if !reachable.contains(&pid) {
+            roots.push(pid);
+            collect_descendants(pid, &children, &mut reachable, &mut Vec::new());
+        }
+    }
+
+    if let Some(compare) = compare_roots {
+        let totals: HashMap<i32, ProcessInfo> = roots
+            .iter()
+            .map(|&pid| (pid, subtree_totals(pid, &by_pid, &children).0))
+            .collect();
+        roots.sort_by(|a, b| compare(&totals[a], &totals[b]));
+    }
+
+    let mut list = Vec::with_capacity(order.len());
+    let mut rows = Vec::with_capacity(order.len());
+    let mut emitted = HashSet::new();
+    for root in roots {
+        emit(
+            root,
+            0,
+            &by_pid,
+            &children,
+            expanded,
+            &mut emitted,
+            &mut list,
+            &mut rows,
+        );
+    }
+
+    (list, rows)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn emit(
+    pid: i32,
+    depth: usize,
+    by_pid: &HashMap<i32, ProcessInfo>,
+    children: &HashMap<i32, Vec<i32>>,
+    expanded: &HashSet<i32>,
+    emitted: &mut HashSet<i32>,
+    list: &mut Vec<ProcessInfo>,
+    rows: &mut Vec<TreeRow>,
+) {
+    if !emitted.insert(pid) {
+        return;
+    }
+
+    let (totals, descendants) = subtree_totals(pid, by_pid, children);
+    let is_expanded = expanded.contains(&pid);
+
+    list.push(by_pid[&pid].clone());
+    rows.push(TreeRow {
+        depth,
+        descendants: descendants
+            .iter()
+            .map(|d| (*d, by_pid[d].name.clone()))
+            .collect(),
+        expanded: is_expanded,
+        subtree: (!descendants.is_empty()).then_some(totals),
+    });
+
+    if is_expanded {
+        for &child in children.get(&pid).map(Vec::as_slice).unwrap_or_default() {
+            emit(
+                child,
+                depth + 1,
+                by_pid,
+                children,
+                expanded,
+                emitted,
+                list,
+                rows,
+            );
+        }
+    }
+}
+
+/// Depth-first list of all descendants of `pid` (cycle-safe)
+fn collect_descendants(
+    pid: i32,
+    children: &HashMap<i32, Vec<i32>>,
+    visited: &mut HashSet<i32>,
+    out: &mut Vec<i32>,
+) {
+    if !visited.insert(pid) {
+        return;
+    }
+    for &child in children.get(&pid).map(Vec::as_slice).unwrap_or_default() {
+        if !visited.contains(&child) {
+            out.push(child);
+            collect_descendants(child, children, visited, out);
+        }
+    }
+}
+
+/// Sum of a process's traffic and all of its descendants', plus the descendant PIDs
+fn subtree_totals(
+    pid: i32,
+    by_pid: &HashMap<i32, ProcessInfo>,
+    children: &HashMap<i32, Vec<i32>>,
+) -> (ProcessInfo, Vec<i32>) {
+    let mut descendants = Vec::new();
+    collect_descendants(pid, children, &mut HashSet::new(), &mut descendants);
+
+    let mut totals = by_pid[&pid].clone();
+    for d in &descendants {
+        add_traffic(&mut totals, &by_pid[d]);
+    }
+    (totals, descendants)
+}
+
+pub(crate) fn add_traffic(into: &mut ProcessInfo, from: &ProcessInfo) {
+    into.download_rate += from.download_rate;
+    into.upload_rate += from.upload_rate;
+    into.total_download += from.total_download;
+    into.total_upload += from.total_upload;
+    into.internet_download_rate += from.internet_download_rate;
+    into.internet_upload_rate += from.internet_upload_rate;
+    into.internet_total_download += from.internet_total_download;
+    into.internet_total_upload += from.internet_total_upload;
+    into.local_download_rate += from.local_download_rate;
+    into.local_upload_rate += from.local_upload_rate;
+    into.local_total_download += from.local_total_download;
+    into.local_total_upload += from.local_total_upload;
+    into.tcp_download_rate += from.tcp_download_rate;
+    into.tcp_upload_rate += from.tcp_upload_rate;
+    into.tcp_total_download += from.tcp_total_download;
+    into.tcp_total_upload += from.tcp_total_upload;
+    into.udp_download_rate += from.udp_download_rate;
+    into.udp_upload_rate += from.udp_upload_rate;
+    into.udp_total_download += from.udp_total_download;
+    into.udp_total_upload += from.udp_total_upload;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: i32, parent: Option<i32>, rx: u64) -> ProcessInfo {
+        let mut p = ProcessInfo::new(pid, format!("proc{}", pid));
+        p.parent_pid = parent;
+        p.download_rate = rx;
+        p
+    }
+
+    fn by_rate(a: &ProcessInfo, b: &ProcessInfo) -> Ordering {
+        b.download_rate.cmp(&a.download_rate)
+    }
+
+    #[test]
+    fn test_collapsed_parent_aggregates_children() {
+        // 10 -> 11 -> 12, 20 is an orphan (parent 99 is not network-active)
+        let procs = vec![
+            process(20, Some(99), 500),
+            process(12, Some(11), 300),
+            process(11, Some(10), 100),
+            process(10, Some(1), 200),
+        ];
+
+        let (list, rows) = flatten(procs, &HashSet::new(), Some(&by_rate));
+        let pids: Vec<i32> = list.iter().map(|p| p.pid).collect();
+
+        // Root 10 sorts above 20 by its subtree total (600 > 500)
+        assert_eq!(pids, vec![10, 20]);
+        assert_eq!(rows[0].subtree.as_ref().unwrap().download_rate, 600);
+        assert_eq!(rows[0].descendants.len(), 2);
+        assert!(rows[0].is_collapsed_parent());
+        assert!(rows[1].subtree.is_none());
+    }
+
+    #[test]
+    fn test_expanded_children_follow_parent() {
+        let procs = vec![
+            process(12, Some(10), 300),
+            process(20, None, 500),
+            process(11, Some(10), 100),
+            process(10, None, 200),
+        ];
+        let expanded: HashSet<i32> = [10].into_iter().collect();
+
+        let (list, rows) = flatten(procs, &expanded, None);
+        let pids: Vec<i32> = list.iter().map(|p| p.pid).collect();
+        let depths: Vec<usize> = rows.iter().map(|r| r.depth).collect();
+
+        // Frozen (no comparator): roots keep input order, children stay under parent
+        assert_eq!(pids, vec![20, 10, 12, 11]);
+        assert_eq!(depths, vec![0, 0, 1, 1]);
+    }
+
+    #[test]
+    fn test_parent_cycle_does_not_drop_processes() {
+        let procs = vec![process(1, Some(2), 0), process(2, Some(1), 0)];
+        let expanded: HashSet<i32> = [1, 2].into_iter().collect();
+
+        let (list, _) = flatten(procs, &expanded, None);
+        assert_eq!(list.len(), 2);
+    }
+}
@@ -0,0 +1,451 @@
+// Daily bandwidth quotas (`quotas` in the config)
+//
+// Every process name's download + upload is added up per local calendar day and kept
+// in <config dir>/usage.json, so a restart doesn't reset the day's count. Once a
+// minute the day's totals are checked against the rules; the first rule whose
+// pattern matches a name applies to it. A name over its quota is reported once per
+// day. An "alert" rule only reports it, a "throttle:<rate>" rule also throttles the
+// name's processes (including ones started later that day). Those throttles are
+// removed when the day rolls over at local midnight, and the counts start again.
+
+use crate::config::{Config, QuotaConfig};
+use crate::interface_filter::glob_match;
+use crate::process::ProcessMap;
+use anyhow::{Context, Result, bail};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+const USAGE_FILE: &str = "usage.json";
+
+/// How often usage is checked against the quotas (and saved)
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// What happens when a process name goes over its quota
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaAction {
+    Alert,
+    /// Throttle both directions to this rate (bytes/sec)
+    Throttle(u64),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuotaRule {
+    /// Process name or glob pattern ("steam*")
+    pub pattern: String,
+    /// Bytes per day
+    pub limit: u64,
+    pub action: QuotaAction,
+}
+
+impl QuotaRule {
+    pub fn parse(config: &QuotaConfig) -> Result<Self> {
+        let limit = crate::parse_bandwidth_limit(&config.limit)
+            .with_context(|| format!("Invalid quota limit '{}'", config.limit))?;
+        let action = match config.action.trim() {
+            "alert" => QuotaAction::Alert,
+            action => match action.strip_prefix("throttle:") {
+                Some(rate) => QuotaAction::Throttle(
+                    crate::parse_throttle_limit(rate)
+                        .with_context(|| format!("Invalid quota throttle rate '{}'", rate))?,
+                ),
+                None => bail!(
+                    "Unknown quota action '{}' (expected alert or throttle:<rate>)",
+                    action
+                ),
+            },
+        };
+        Ok(Self {
+            pattern: config.pattern.clone(),
+            limit,
+            action,
+        })
+    }
+}
+
+/// A process name that went over its quota
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuotaExceeded {
+    pub name: String,
+    /// A running process with that name (0 if none is running)
+    pub pid: i32,
+    /// Bytes used today
+    pub used: u64,
+    pub rule: QuotaRule,
+}
+
+/// Result of one quota check
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct QuotaCheck {
+    /// The day rolled over since the last check (counts and reports were reset)
+    pub rolled_over: bool,
+    /// Names newly over their quota today
+    pub exceeded: Vec<QuotaExceeded>,
+    /// Processes of names over a throttle quota that aren't handled yet: (pid, name, rate)
+    pub to_throttle: Vec<(i32, String, u64)>,
+    /// Quota throttles to remove because the day rolled over: (pid, rate)
+    pub expired: Vec<(i32, u64)>,
+}
+
+/// One day's usage, as saved in usage.json
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct UsageLedger {
+    /// Local date, YYYY-MM-DD
+    date: String,
+    /// Process name -> bytes (download + upload)
+    bytes: HashMap<String, u64>,
+}
+
+/// Adds up each process name's daily usage and checks it against the quota rules
+#[derive(Debug)]
+pub struct QuotaTracker {
+    rules: Vec<QuotaRule>,
+    /// Where the day's usage is saved (None = not saved)
+    path: Option<PathBuf>,
+    day: NaiveDate,
+    usage: HashMap<String, u64>,
+    /// Name and download + upload total of each PID at the last update, to add up
+    /// the deltas
+    last_totals: HashMap<i32, (String, u64)>,
+    /// Names already reported today
+    reported: HashSet<String>,
+    /// PIDs handled by a throttle quota today, with the rate it throttled them to
+    /// (None = left alone: it had a throttle of its own or throttling failed)
+    handled: HashMap<i32, Option<u64>>,
+    last_check: Option<Instant>,
+}
+
+impl QuotaTracker {
+    /// Track usage in the config directory, continuing today's saved counts
+    pub fn open(rules: Vec<QuotaRule>, today: NaiveDate) -> Self {
+        let path = Config::config_path()
+            .ok()
+            .and_then(|path| path.parent().map(|dir| dir.join(USAGE_FILE)));
+        Self::new(rules, path, today)
+    }
+
+    pub fn new(rules: Vec<QuotaRule>, path: Option<PathBuf>, today: NaiveDate) -> Self {
+        let mut tracker = Self {
+            rules,
+            path,
+            day: today,
+            usage: HashMap::new(),
+            last_totals: HashMap::new(),
+            reported: HashSet::new(),
+            handled: HashMap::new(),
+            last_check: None,
+        };
+        match tracker.load() {
+            Ok(Some(ledger)) if ledger.date == format_date(today) => tracker.usage = ledger.bytes,
+            Ok(_) => {}
+            Err(e) => log::warn!("Starting today's quota usage from zero: {:#}", e),
+        }
+        tracker
+    }
+
+    /// Replace the rules after the config was edited, keeping today's usage. Names
+    /// are checked against the new rules from scratch
+    pub fn set_rules(&mut self, rules: Vec<QuotaRule>) {
+        self.rules = rules;
+        self.reported.clear();
+        self.last_check = None;
+    }
+
+    /// The rule that applies to a process name (the first one matching it)
+    pub fn rule_for(&self, name: &str) -> Option<&QuotaRule> {
+        self.rules
+            .iter()
+            .find(|rule| glob_match(&rule.pattern, name))
+    }
+
+    /// Names over their quota today
+    pub fn exceeded_count(&self) -> usize {
+        self.reported.len()
+    }
+
+    /// Add the traffic since the last update to each process name's usage
+    pub fn record(&mut self, processes: &ProcessMap) {
+        self.last_totals
+            .retain(|pid, _| processes.contains_key(pid));
+        for (pid, process) in processes {
+            let total = process.total_download + process.total_upload;
+            let delta = match self.last_totals.insert(*pid, (process.name.clone(), total)) {
+                Some((name, last)) if name == process.name && total >= last => total - last,
+                // A new process, or a reused PID whose totals started over
+                _ => total,
+            };
+            if delta > 0 {
+                *self.usage.entry(process.name.clone()).or_default() += delta;
+            }
+        }
+    }
+
+    /// Remember how a process from `QuotaCheck::to_throttle` was handled
+    pub fn mark_handled(&mut self, pid: i32, throttled_to: Option<u64>) {
+        self.handled.insert(pid, throttled_to);
+    }
+
+    /// Check today's usage against the quotas, once per check interval
+    pub fn check(&mut self, processes: &ProcessMap) -> Option<QuotaCheck> {
+        self.check_at(Instant::now(), chrono::Local::now().date_naive(), processes)
+    }
+
+    fn check_at(
+        &mut self,
+        now: Instant,
+        today: NaiveDate,
+        processes: &ProcessMap,
+    ) -> Option<QuotaCheck> {
+        if self
+            .last_check
+            .is_some_and(|last| now.saturating_duration_since(last) < CHECK_INTERVAL)
+        {
+            return None;
+        }
+        self.last_check = Some(now);
+
+        let mut check = QuotaCheck::default();
+        if today != self.day {
+            log::info!("New day {}, quota usage starts over", today);
+            self.day = today;
+            self.usage.clear();
+            self.reported.clear();
+            check.rolled_over = true;
+            check.expired = self
+                .handled
+                .drain()
+                .filter_map(|(pid, rate)| rate.map(|rate| (pid, rate)))
+                .collect();
+            check.expired.sort_unstable();
+        }
+        self.handled.retain(|pid, _| processes.contains_key(pid));
+
+        let mut over: Vec<(&String, u64, &QuotaRule)> = self
+            .usage
+            .iter()
+            .filter_map(|(name, used)| {
+                let rule = self.rule_for(name)?;
+                (*used > rule.limit).then_some((name, *used, rule))
+            })
+            .collect();
+        over.sort_by(|a, b| a.0.cmp(b.0));
+
+        for (name, used, rule) in over {
+            if !self.reported.contains(name) {
+                let pid = processes
+                    .values()
+                    .filter(|p| &p.name == name)
+                    .map(|p| p.pid)
+                    .min()
+                    .unwrap_or(0);
+                check.exceeded.push(QuotaExceeded {
+                    name: name.clone(),
+                    pid,
+                    used,
+                    rule: rule.clone(),
+                });
+            }
+            if let QuotaAction::Throttle(rate) = rule.action {
+                check.to_throttle.extend(
+                    processes
+                        .values()
+                        .filter(|p| &p.name == name && !p.is_terminated)
+                        .filter(|p| !self.handled.contains_key(&p.pid))
+                        .map(|p| (p.pid, name.clone(), rate)),
+                );
+            }
+        }
+        self.reported
+            .extend(check.exceeded.iter().map(|exceeded| exceeded.name.clone()));
+        check.to_throttle.sort_unstable();
+
+        if let Err(e) = self.save() {
+            log::warn!("Failed to save quota usage: {:#}", e);
+        }
+        Some(check)
+    }
+
+    fn load(&self) -> Result<Option<UsageLedger>> {
+        let Some(path) = &self.path else {
+            return Ok(None);
+        };
+        if !path.exists() {
+            return Ok(None);
+        }
+        let contents = fs::read_to_string(path).context(format!("Failed to read {:?}", path))?;
+        let ledger = serde_json::from_str(&contents).context(format!("Invalid {:?}", path))?;
+        Ok(Some(ledger))
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let ledger = UsageLedger {
+            date: format_date(self.day),
+            bytes: self.usage.clone(),
+        };
+        fs::write(path, serde_json::to_string(&ledger)?)
+            .context(format!("Failed to write {:?}", path))
+    }
+}
+
+impl Drop for QuotaTracker {
+    fn drop(&mut self) {
+        // Keep the usage since the last check
+        if let Err(e) = self.save() {
+            log::warn!("Failed to save quota usage: {:#}", e);
+        }
+    }
+}
+
+fn format_date(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::ProcessInfo;
+
+    const GB: u64 = 1024 * 1024 * 1024;
+
+    fn rule(pattern: &str, limit: &str, action: &str) -> QuotaRule {
+        QuotaRule::parse(&QuotaConfig {
+            pattern: pattern.to_string(),
+            limit: limit.to_string(),
+            action: action.to_string(),
+        })
+        .unwrap()
+    }
+
+    fn processes(totals: &[(i32, &str, u64)]) -> ProcessMap {
+        totals
+            .iter()
+            .map(|(pid, name, total)| {
+                let mut info = ProcessInfo::new(*pid, name.to_string());
+                info.total_download = *total;
+                (*pid, info)
+            })
+            .collect()
+    }
+
+    fn used(tracker: &QuotaTracker, name: &str) -> u64 {
+        tracker.usage.get(name).copied().unwrap_or(0)
+    }
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, d).unwrap()
+    }
+
+    #[test]
+    fn test_first_matching_rule_applies() {
+        let tracker = QuotaTracker::new(
+            vec![
+                rule("steam*", "5G", "alert"),
+                rule("*", "10G", "throttle:512K"),
+            ],
+            None,
+            day(1),
+        );
+        assert_eq!(tracker.rule_for("steamwebhelper").unwrap().limit, 5 * GB);
+        assert_eq!(
+            tracker.rule_for("curl").unwrap().action,
+            QuotaAction::Throttle(512 * 1024)
+        );
+
+        let invalid = QuotaConfig {
+            pattern: "*".to_string(),
+            limit: "1G".to_string(),
+            action: "kill".to_string(),
+        };
+        assert!(QuotaRule::parse(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_usage_accumulates_per_name_and_checks_once_a_minute() {
+        let mut tracker = QuotaTracker::new(vec![rule("*", "1G", "alert")], None, day(1));
+        let start = Instant::now();
+
+        // Two processes of the same name add up
+        tracker.record(&processes(&[(1, "curl", GB / 2), (2, "curl", 0)]));
+        tracker.record(&processes(&[(1, "curl", GB / 2), (2, "curl", GB / 4)]));
+        assert_eq!(used(&tracker, "curl"), GB / 2 + GB / 4);
+        let check = tracker.check_at(start, day(1), &ProcessMap::new()).unwrap();
+        assert!(check.exceeded.is_empty());
+
+        // A reused PID starts over
+        tracker.record(&processes(&[(1, "wget", GB / 2)]));
+        assert_eq!(used(&tracker, "wget"), GB / 2);
+
+        // Over the quota now, but the next check is a minute after the last one
+        tracker.record(&processes(&[(1, "wget", GB / 2), (2, "curl", GB / 2)]));
+        let later = start + Duration::from_secs(30);
+        assert!(
+            tracker
+                .check_at(later, day(1), &ProcessMap::new())
+                .is_none()
+        );
+
+        let later = start + CHECK_INTERVAL;
+        let check = tracker.check_at(later, day(1), &ProcessMap::new()).unwrap();
+        assert_eq!(check.exceeded.len(), 1);
+        assert_eq!(
+            (check.exceeded[0].name.as_str(), check.exceeded[0].used),
+            ("curl", GB + GB / 4)
+        );
+
+        // Reported once per day
+        let later = later + CHECK_INTERVAL;
+        let check = tracker.check_at(later, day(1), &ProcessMap::new()).unwrap();
+        assert!(check.exceeded.is_empty());
+        assert_eq!(tracker.exceeded_count(), 1);
+    }
+
+    #[test]
+    fn test_throttle_quotas_are_removed_at_midnight() {
+        let mut tracker =
+            QuotaTracker::new(vec![rule("steam", "1G", "throttle:512K")], None, day(1));
+        let start = Instant::now();
+        let running = processes(&[(10, "steam", 2 * GB), (11, "steam", 0), (12, "curl", GB)]);
+
+        tracker.record(&running);
+        let check = tracker.check_at(start, day(1), &running).unwrap();
+        assert_eq!(
+            check.to_throttle,
+            vec![
+                (10, "steam".to_string(), 512 * 1024),
+                (11, "steam".to_string(), 512 * 1024)
+            ]
+        );
+        tracker.mark_handled(10, Some(512 * 1024));
+        // Already had a throttle of its own
+        tracker.mark_handled(11, None);
+
+        // A steam process started later is throttled too
+        let running = processes(&[(10, "steam", 2 * GB), (11, "steam", 0), (13, "steam", 0)]);
+        tracker.record(&running);
+        let check = tracker
+            .check_at(start + CHECK_INTERVAL, day(1), &running)
+            .unwrap();
+        assert_eq!(
+            check.to_throttle,
+            vec![(13, "steam".to_string(), 512 * 1024)]
+        );
+        assert!(check.exceeded.is_empty());
+        tracker.mark_handled(13, Some(512 * 1024));
+
+        // Midnight: counts reset, the quota's own throttles expire
+        let check = tracker
+            .check_at(start + 2 * CHECK_INTERVAL, day(2), &running)
+            .unwrap();
+        assert!(check.rolled_over);
+        assert_eq!(check.expired, vec![(10, 512 * 1024), (13, 512 * 1024)]);
+        assert!(check.to_throttle.is_empty());
+        assert_eq!(used(&tracker, "steam"), 0);
+        assert_eq!(tracker.exceeded_count(), 0);
+    }
+}
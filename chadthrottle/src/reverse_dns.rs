@@ -0,0 +1,230 @@
+// Reverse DNS for remote connection addresses (`reverse_dns` in the config)
+//
+// Lookups run on a few background threads so drawing never waits on DNS: the first
+// request for an address queues it and shows nothing, and the name shows up once it
+// is resolved. Results, including addresses without a name, are cached for a while;
+// an expired name keeps showing until it has been looked up again.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How long a resolved name is kept
+const NAME_TTL: Duration = Duration::from_secs(600);
+
+/// How long an address without a name is left alone before trying again
+const NO_NAME_TTL: Duration = Duration::from_secs(60);
+
+/// Lookups running at once (each can take seconds when a server doesn't answer)
+const WORKERS: usize = 4;
+
+type Resolver = fn(IpAddr) -> Option<String>;
+
+#[derive(Debug)]
+struct Cached {
+    name: Option<String>,
+    /// None while a lookup is pending
+    expires: Option<Instant>,
+}
+
+/// Cache of reverse lookups, resolved in the background
+#[derive(Debug)]
+pub struct ReverseDns {
+    cache: HashMap<IpAddr, Cached>,
+    requests: Sender<IpAddr>,
+    results: Receiver<(IpAddr, Option<String>)>,
+}
+
+impl ReverseDns {
+    pub fn new() -> Self {
+        Self::with_resolver(resolve)
+    }
+
+    fn with_resolver(resolver: Resolver) -> Self {
+        let (requests, request_rx) = mpsc::channel::<IpAddr>();
+        let (result_tx, results) = mpsc::channel();
+        let request_rx = Arc::new(Mutex::new(request_rx));
+
+        for i in 0..WORKERS {
+            let request_rx = Arc::clone(&request_rx);
+            let result_tx = result_tx.clone();
+            let spawned = std::thread::Builder::new()
+                .name(format!("reverse-dns-{}", i))
+                .spawn(move || {
+                    loop {
+                        // The lock is only held while waiting for the next request
+                        let request = request_rx.lock().unwrap().recv();
+                        let Ok(addr) = request else {
+                            break; // ReverseDns dropped
+                        };
+                        if result_tx.send((addr, resolver(addr))).is_err() {
+                            break;
+                        }
+                    }
+                });
+            if let Err(e) = spawned {
+                log::warn!("Failed to start reverse DNS thread: {}", e);
+            }
+        }
+
+        Self {
+            cache: HashMap::new(),
+            requests,
+            results,
+        }
+    }
+
+    /// The name of an address if it's known, queueing a lookup if it isn't (or has
+    /// expired). Never blocks
+    pub fn lookup(&mut self, addr: IpAddr) -> Option<&str> {
+        self.lookup_at(addr, Instant::now())
+    }
+
+    fn lookup_at(&mut self, addr: IpAddr, now: Instant) -> Option<&str> {
+        while let Ok((resolved, name)) = self.results.try_recv() {
+            let ttl = if name.is_some() {
+                NAME_TTL
+            } else {
+                NO_NAME_TTL
+            };
+            self.cache.insert(
+                resolved,
+                Cached {
+                    name,
+                    expires: Some(now + ttl),
+                },
+            );
+        }
+
+        if addr.is_unspecified() {
+            return None;
+        }
+
+        let entry = self.cache.entry(addr).or_insert(Cached {
+            name: None,
+            expires: Some(now),
+        });
+        if entry.expires.is_some_and(|expires| expires <= now) && self.requests.send(addr).is_ok() {
+            entry.expires = None;
+        }
+        entry.name.as_deref()
+    }
+}
+
+/// Look up the host name of an address (blocking)
+#[cfg(unix)]
+fn resolve(addr: IpAddr) -> Option<String> {
+    use std::ffi::CStr;
+
+    // NI_MAXHOST, which not every libc target defines
+    const MAX_HOST: usize = 1025;
+
+    // SAFETY: all-zero sockaddr_in/sockaddr_in6 are valid
+    let mut storage: libc::sockaddr_storage = unsafe { std::mem::zeroed() };
+    let len = match addr {
+        IpAddr::V4(v4) => {
+            let sin = &mut storage as *mut _ as *mut libc::sockaddr_in;
+            // SAFETY: sockaddr_storage is large and aligned enough for sockaddr_in
+            unsafe {
+                (*sin).sin_family = libc::AF_INET as libc::sa_family_t;
+                (*sin).sin_addr.s_addr = u32::from_ne_bytes(v4.octets());
+                #[cfg(any(target_os = "macos", target_os = "freebsd"))]
+                {
+                    (*sin).sin_len = std::mem::size_of::<libc::sockaddr_in>() as u8;
+                }
+            }
+            std::mem::size_of::<libc::sockaddr_in>()
+        }
+        IpAddr::V6(v6) => {
+            let sin6 = &mut storage as *mut _ as *mut libc::sockaddr_in6;
+            // SAFETY: sockaddr_storage is large and aligned enough for sockaddr_in6
+            unsafe {
+                (*sin6).sin6_family = libc::AF_INET6 as libc::sa_family_t;
+                (*sin6).sin6_addr.s6_addr = v6.octets();
+                #[cfg(any(target_os = "macos", target_os = "freebsd"))]
+                {
+                    (*sin6).sin6_len = std::mem::size_of::<libc::sockaddr_in6>() as u8;
+                }
+            }
+            std::mem::size_of::<libc::sockaddr_in6>()
+        }
+    };
+
+    let mut host = [0 as libc::c_char; MAX_HOST];
+    // SAFETY: storage holds a sockaddr of `len` bytes and host is MAX_HOST long
+    let rc = unsafe {
+        libc::getnameinfo(
+            &storage as *const _ as *const libc::sockaddr,
+            len as libc::socklen_t,
+            host.as_mut_ptr(),
+            MAX_HOST as libc::socklen_t,
+            std::ptr::null_mut(),
+            0,
+            libc::NI_NAMEREQD,
+        )
+    };
+    if rc != 0 {
+        return None;
+    }
+    // SAFETY: getnameinfo NUL-terminates the name on success
+    let name = unsafe { CStr::from_ptr(host.as_ptr()) };
+    Some(name.to_string_lossy().into_owned())
+}
+
+/// Reverse lookups aren't implemented here yet
+#[cfg(not(unix))]
+fn resolve(_addr: IpAddr) -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    fn fake_resolver(addr: IpAddr) -> Option<String> {
+        match addr {
+            IpAddr::V4(v4) if v4.octets()[3] == 1 => Some(format!("host{}.example", v4)),
+            _ => None,
+        }
+    }
+
+    /// Look an address up until the background lookup has finished
+    fn resolved(dns: &mut ReverseDns, addr: IpAddr, now: Instant) -> Option<String> {
+        for _ in 0..200 {
+            if dns.cache.get(&addr).is_some_and(|c| c.expires.is_some()) {
+                break;
+            }
+            dns.lookup_at(addr, now);
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        dns.lookup_at(addr, now).map(str::to_string)
+    }
+
+    #[test]
+    fn test_lookups_are_queued_and_cached() {
+        let mut dns = ReverseDns::with_resolver(fake_resolver);
+        let now = Instant::now();
+        let named = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let unnamed = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2));
+
+        // Nothing yet: the first lookup only queues the address
+        assert_eq!(dns.lookup_at(named, now), None);
+        assert_eq!(
+            resolved(&mut dns, named, now).as_deref(),
+            Some("host10.0.0.1.example")
+        );
+        assert_eq!(resolved(&mut dns, unnamed, now), None);
+        assert!(
+            dns.lookup_at(IpAddr::V4(Ipv4Addr::UNSPECIFIED), now)
+                .is_none()
+        );
+
+        // An expired name is shown while it's looked up again
+        let later = now + NAME_TTL + Duration::from_secs(1);
+        assert_eq!(dns.lookup_at(named, later), Some("host10.0.0.1.example"));
+        assert!(dns.cache[&named].expires.is_none());
+    }
+}
@@ -0,0 +1,149 @@
+// Serializable bandwidth snapshots for machine consumers (headless mode, daemon API)
+
+use crate::process::{InterfaceStats, ProcessInfo};
+use serde::{Deserialize, Serialize};
+
+/// One monitoring tick worth of per-process bandwidth data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitorSnapshot {
+    /// Unix timestamp (milliseconds) when the snapshot was taken
+    pub timestamp: u64,
+    pub processes: Vec<ProcessSnapshot>,
+}
+
+/// Bandwidth data for a single process
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessSnapshot {
+    pub pid: i32,
+    pub name: String,
+    pub rx_bps: u64,
+    pub tx_bps: u64,
+    pub rx_total: u64,
+    pub tx_total: u64,
+    pub internet: TrafficSplit,
+    pub local: TrafficSplit,
+    pub terminated: bool,
+    pub interfaces: Vec<InterfaceSnapshot>,
+}
+
+/// Rates and totals for one traffic category (Internet or Local)
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct TrafficSplit {
+    pub rx_bps: u64,
+    pub tx_bps: u64,
+    pub rx_total: u64,
+    pub tx_total: u64,
+}
+
+/// Per-interface bandwidth data for a process
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterfaceSnapshot {
+    pub name: String,
+    pub rx_bps: u64,
+    pub tx_bps: u64,
+    pub rx_total: u64,
+    pub tx_total: u64,
+}
+
+impl MonitorSnapshot {
+    /// Build a snapshot from monitor output, sorted by total rate (highest first)
+    pub fn from_processes<'a>(processes: impl IntoIterator<Item = &'a ProcessInfo>) -> Self {
+        let mut processes: Vec<ProcessSnapshot> =
+            processes.into_iter().map(ProcessSnapshot::from).collect();
+
+        processes.sort_by(|a, b| {
+            (b.rx_bps + b.tx_bps)
+                .cmp(&(a.rx_bps + a.tx_bps))
+                .then(a.pid.cmp(&b.pid))
+        });
+
+        Self {
+            timestamp: chrono::Utc::now().timestamp_millis() as u64,
+            processes,
+        }
+    }
+
+    /// Keep only the `n` busiest processes
+    pub fn truncate(&mut self, n: usize) {
+        self.processes.truncate(n);
+    }
+}
+
+impl From<&ProcessInfo> for ProcessSnapshot {
+    fn from(process: &ProcessInfo) -> Self {
+        let mut interfaces: Vec<InterfaceSnapshot> = process
+            .interface_stats
+            .iter()
+            .map(|(name, stats)| InterfaceSnapshot::new(name, stats))
+            .collect();
+        interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Self {
+            pid: process.pid,
+            name: process.name.clone(),
+            rx_bps: process.download_rate,
+            tx_bps: process.upload_rate,
+            rx_total: process.total_download,
+            tx_total: process.total_upload,
+            internet: TrafficSplit {
+                rx_bps: process.internet_download_rate,
+                tx_bps: process.internet_upload_rate,
+                rx_total: process.internet_total_download,
+                tx_total: process.internet_total_upload,
+            },
+            local: TrafficSplit {
+                rx_bps: process.local_download_rate,
+                tx_bps: process.local_upload_rate,
+                rx_total: process.local_total_download,
+                tx_total: process.local_total_upload,
+            },
+            terminated: process.is_terminated,
+            interfaces,
+        }
+    }
+}
+
+impl InterfaceSnapshot {
+    fn new(name: &str, stats: &InterfaceStats) -> Self {
+        Self {
+            name: name.to_string(),
+            rx_bps: stats.download_rate,
+            tx_bps: stats.upload_rate,
+            rx_total: stats.total_download,
+            tx_total: stats.total_upload,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: i32, rx: u64, tx: u64) -> ProcessInfo {
+        let mut p = ProcessInfo::new(pid, format!("proc{}", pid));
+        p.download_rate = rx;
+        p.upload_rate = tx;
+        p
+    }
+
+    #[test]
+    fn test_snapshot_sorted_and_truncated() {
+        let procs = vec![process(1, 10, 0), process(2, 500, 500), process(3, 0, 100)];
+        let mut snapshot = MonitorSnapshot::from_processes(&procs);
+        snapshot.truncate(2);
+
+        let pids: Vec<i32> = snapshot.processes.iter().map(|p| p.pid).collect();
+        assert_eq!(pids, vec![2, 3]);
+    }
+
+    #[test]
+    fn test_snapshot_serializes_to_single_line() {
+        let procs = vec![process(42, 1024, 2048)];
+        let snapshot = MonitorSnapshot::from_processes(&procs);
+        let json = serde_json::to_string(&snapshot).unwrap();
+
+        assert!(!json.contains('\n'));
+        assert!(json.contains("\"rx_bps\":1024"));
+        assert!(json.contains("\"internet\""));
+    }
+}
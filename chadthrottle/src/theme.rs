@@ -0,0 +1,212 @@
+// Color themes for the TUI
+
+use ratatui::style::{Color, Modifier, Style};
+
+pub use crate::config::ThemeName;
+
+/// Named color roles used by all widgets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub name: ThemeName,
+    /// Titles, headers and modal borders
+    pub title: Color,
+    /// Primary text
+    pub text: Color,
+    /// Secondary text (status messages, inactive entries, terminated processes)
+    pub secondary: Color,
+    /// Hints and de-emphasized text
+    pub muted: Color,
+    /// Keys, selection cursor and active markers
+    pub highlight: Color,
+    /// Background of the selected row
+    pub selection_bg: Color,
+    /// Extra modifiers for the selected row (used where color alone can't show selection)
+    pub selection_modifier: Modifier,
+    /// Background of modal dialogs
+    pub modal_bg: Color,
+    /// Download rates
+    pub download: Color,
+    /// Upload rates
+    pub upload: Color,
+    /// Download totals
+    pub download_total: Color,
+    /// Upload totals
+    pub upload_total: Color,
+    /// Available / enabled / supported
+    pub success: Color,
+    /// Warnings (downgrades, unavailable backends, growing queues)
+    pub warning: Color,
+    /// Errors, throttled status, unsupported
+    pub error: Color,
+    /// Miscellaneous accent (counts etc.)
+    pub accent: Color,
+}
+
+impl Theme {
+    pub const DEFAULT: Theme = Theme {
+        name: ThemeName::Default,
+        title: Color::Cyan,
+        text: Color::White,
+        secondary: Color::Gray,
+        muted: Color::DarkGray,
+        highlight: Color::Yellow,
+        selection_bg: Color::DarkGray,
+        selection_modifier: Modifier::BOLD,
+        modal_bg: Color::Black,
+        download: Color::Green,
+        upload: Color::Yellow,
+        download_total: Color::Cyan,
+        upload_total: Color::Magenta,
+        success: Color::Green,
+        warning: Color::Yellow,
+        error: Color::Red,
+        accent: Color::Magenta,
+    };
+
+    pub const HIGH_CONTRAST: Theme = Theme {
+        name: ThemeName::HighContrast,
+        title: Color::LightCyan,
+        text: Color::White,
+        secondary: Color::White,
+        muted: Color::Gray,
+        highlight: Color::LightYellow,
+        selection_bg: Color::Blue,
+        selection_modifier: Modifier::BOLD,
+        modal_bg: Color::Black,
+        download: Color::LightGreen,
+        upload: Color::LightYellow,
+        download_total: Color::LightCyan,
+        upload_total: Color::LightMagenta,
+        success: Color::LightGreen,
+        warning: Color::LightYellow,
+        error: Color::LightRed,
+        accent: Color::LightMagenta,
+    };
+
+    pub const MONOCHROME: Theme = Theme {
+        name: ThemeName::Monochrome,
+        title: Color::Reset,
+        text: Color::Reset,
+        secondary: Color::Reset,
+        muted: Color::Reset,
+        highlight: Color::Reset,
+        selection_bg: Color::Reset,
+        selection_modifier: Modifier::BOLD.union(Modifier::REVERSED),
+        modal_bg: Color::Reset,
+        download: Color::Reset,
+        upload: Color::Reset,
+        download_total: Color::Reset,
+        upload_total: Color::Reset,
+        success: Color::Reset,
+        warning: Color::Reset,
+        error: Color::Reset,
+        accent: Color::Reset,
+    };
+
+    pub fn from_name(name: ThemeName) -> Self {
+        match name {
+            ThemeName::Default => Self::DEFAULT,
+            ThemeName::HighContrast => Self::HIGH_CONTRAST,
+            ThemeName::Monochrome => Self::MONOCHROME,
+        }
+    }
+
+    /// Resolve the theme to use, honoring NO_COLOR (https://no-color.org) over config
+    pub fn resolve(configured: Option<ThemeName>) -> Self {
+        if no_color_requested() {
+            return Self::MONOCHROME;
+        }
+        Self::from_name(configured.unwrap_or_default())
+    }
+
+    /// Highlight style for selected rows in lists
+    pub fn list_highlight(&self) -> Style {
+        Style::default()
+            .bg(self.selection_bg)
+            .add_modifier(self.selection_modifier)
+    }
+
+    /// Style for the selected/cursor entry in custom-rendered lists
+    pub fn selected(&self) -> Style {
+        Style::default()
+            .fg(self.highlight)
+            .bg(self.selection_bg)
+            .add_modifier(self.selection_modifier)
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// NO_COLOR is honored when present and non-empty
+fn no_color_requested() -> bool {
+    std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::{
+        AddressFamily, Priority, ProcessInfo, ProcessMap, ThrottleLimit, TrafficType,
+    };
+    use crate::ui::{AppState, ViewMode};
+    use ratatui::buffer::Buffer;
+
+    /// Cells styled with any color (the terminal's own colors are `Reset`)
+    fn colored_cells(buffer: &Buffer) -> Vec<(u16, u16)> {
+        let area = buffer.area;
+        (area.top()..area.bottom())
+            .flat_map(|y| (area.left()..area.right()).map(move |x| (x, y)))
+            .filter(|&(x, y)| {
+                let cell = &buffer[(x, y)];
+                cell.fg != Color::Reset || cell.bg != Color::Reset
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_mono_theme_renders_without_color() {
+        let mut process = ProcessInfo::new(4242, "firefox".to_string());
+        process.download_rate = 1536;
+        process.throttle_limit = Some(ThrottleLimit {
+            download_limit: Some(1024),
+            upload_limit: None,
+            upload_minimum: None,
+            traffic_type: TrafficType::All,
+            interfaces: None,
+            policy: None,
+            family: AddressFamily::Any,
+            priority: Priority::Normal,
+        });
+        let mut app = AppState::new();
+        app.theme = Theme::from_name(ThemeName::Monochrome);
+        app.update_processes(ProcessMap::from([(4242, process)]));
+
+        for view_mode in [ViewMode::ProcessView, ViewMode::ProcessDetail] {
+            if view_mode == ViewMode::ProcessDetail {
+                app.select_next();
+                app.enter_process_detail();
+            }
+            assert_eq!(app.view_mode, view_mode);
+            let buffer = crate::ui::render_to_buffer(120, 40, |f| crate::ui::draw_ui(f, &mut app));
+            assert_eq!(colored_cells(&buffer), vec![], "{:?}", view_mode);
+        }
+
+        // The default theme does use color
+        app.theme = Theme::DEFAULT;
+        let buffer = crate::ui::render_to_buffer(120, 40, |f| crate::ui::draw_ui(f, &mut app));
+        assert!(!colored_cells(&buffer).is_empty());
+    }
+
+    #[test]
+    fn test_theme_names() {
+        let name = |json: &str| serde_json::from_str::<ThemeName>(json).unwrap();
+        assert_eq!(name(r#""mono""#), ThemeName::Monochrome);
+        assert_eq!(name(r#""monochrome""#), ThemeName::Monochrome);
+        assert_eq!(name(r#""high-contrast""#), ThemeName::HighContrast);
+        assert_eq!(Theme::from_name(name(r#""default""#)), Theme::DEFAULT);
+    }
+}
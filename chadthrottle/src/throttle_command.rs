@@ -0,0 +1,231 @@
+// CLI invocation reproducing a throttle (the "copy throttle command" modal)
+//
+// Built from a live throttle or from the throttle dialog's inputs, so a throttle set
+// up interactively can be reused in scripts. Settings the CLI can't express are
+// listed as notes instead of being silently dropped.
+
+use crate::backends::ActiveThrottle;
+use crate::process::{AddressFamily, Priority, ThrottleLimit, TrafficType};
+
+/// What the command throttles
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommandTarget {
+    Pid(i32),
+    AllExcept(Vec<String>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThrottleCommand {
+    pub command: String,
+    /// Parts of the throttle the command doesn't reproduce
+    pub notes: Vec<String>,
+}
+
+impl ThrottleCommand {
+    pub fn new(target: &CommandTarget, limit: &ThrottleLimit) -> Self {
+        let mut args = vec!["chadthrottle".to_string()];
+        let mut notes = Vec::new();
+
+        match target {
+            CommandTarget::Pid(pid) => args.push(format!("--pid {}", pid)),
+            CommandTarget::AllExcept(names) => {
+                args.push(format!("--all-except {}", shell_quote(&names.join(","))))
+            }
+        }
+        if let Some(download) = limit.download_limit {
+            args.push(format!("--download-limit {}", cli_limit(download)));
+        }
+        if let Some(upload) = limit.upload_limit {
+            args.push(format!("--upload-limit {}", cli_limit(upload)));
+        }
+
+        // --interface, --upload-minimum, --policy and --priority only apply to --pid targets
+        let pid_target = matches!(target, CommandTarget::Pid(_));
+        if let Some(interfaces) = &limit.interfaces {
+            if pid_target {
+                args.extend(
+                    interfaces
+                        .iter()
+                        .map(|name| format!("--interface {}", shell_quote(name))),
+                );
+            } else {
+                notes.push(format!(
+                    "Interfaces ({}) can't be combined with --all-except: the command throttles every interface",
+                    interfaces.join(", ")
+                ));
+            }
+        }
+        if let Some(minimum) = limit.upload_minimum {
+            if pid_target {
+                args.push(format!("--upload-minimum {}", cli_limit(minimum)));
+            } else {
+                notes.push(
+                    "The upload minimum can't be combined with --all-except: the command guarantees nothing"
+                        .to_string(),
+                );
+            }
+        }
+        if let Some(policy) = limit.policy {
+            if pid_target {
+                args.push(format!("--policy {}", policy));
+            } else {
+                notes.push(format!(
+                    "Policy {} can't be combined with --all-except: the command uses the backend's default",
+                    policy
+                ));
+            }
+        }
+        if limit.priority != Priority::Normal {
+            if pid_target {
+                args.push(format!("--priority {}", limit.priority));
+            } else {
+                notes.push(format!(
+                    "Priority {} can't be combined with --all-except: the command uses normal priority",
+                    limit.priority
+                ));
+            }
+        }
+        if limit.traffic_type != TrafficType::All {
+            args.push(format!("--traffic-type {}", limit.traffic_type));
+        }
+        if limit.family != AddressFamily::Any {
+            notes.push(format!(
+                "IP version ({}) can't be set from the CLI: the command throttles IPv4 and IPv6",
+                limit.family
+            ));
+        }
+
+        Self {
+            command: args.join(" "),
+            notes,
+        }
+    }
+
+    /// The command for a live throttle, pinned to the backends enforcing it
+    pub fn for_throttle(throttle: &ActiveThrottle) -> Self {
+        let limit = ThrottleLimit {
+            download_limit: throttle.download_limit,
+            upload_limit: throttle.upload_limit,
+            upload_minimum: throttle.upload_minimum,
+            traffic_type: throttle.traffic_type,
+            interfaces: throttle.interfaces.clone(),
+            policy: throttle.policy,
+            family: throttle.family,
+            priority: throttle.priority,
+        };
+        let mut command = Self::new(&CommandTarget::Pid(throttle.pid), &limit)
+            .with_children(throttle.children.is_some());
+        if let Some(backend) = &throttle.download_backend {
+            command.command += &format!(" --download-backend {}", backend);
+        }
+        if let Some(backend) = &throttle.upload_backend {
+            command.command += &format!(" --upload-backend {}", backend);
+        }
+        command
+    }
+
+    /// Note that the throttle also covers the target's children, which the CLI can't do
+    pub fn with_children(mut self, include_children: bool) -> Self {
+        if include_children {
+            self.notes.push(
+                "Children can't be included from the CLI: the command throttles the process only"
+                    .to_string(),
+            );
+        }
+        self
+    }
+}
+
+/// A limit as the CLI accepts it: "2M", "500K", or plain bytes/sec when it isn't a
+/// whole number of KB
+fn cli_limit(bytes_per_sec: u64) -> String {
+    const KB: u64 = 1024;
+    const MB: u64 = KB * 1024;
+    if bytes_per_sec >= MB && bytes_per_sec.is_multiple_of(MB) {
+        format!("{}M", bytes_per_sec / MB)
+    } else if bytes_per_sec >= KB && bytes_per_sec.is_multiple_of(KB) {
+        format!("{}K", bytes_per_sec / KB)
+    } else {
+        bytes_per_sec.to_string()
+    }
+}
+
+/// Single-quote an argument unless it only has characters the shell leaves alone
+fn shell_quote(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_.,:@/+".contains(c));
+    if plain {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::Policy;
+
+    fn limit(download: Option<u64>, upload: Option<u64>) -> ThrottleLimit {
+        ThrottleLimit {
+            download_limit: download,
+            upload_limit: upload,
+            upload_minimum: None,
+            traffic_type: TrafficType::All,
+            interfaces: None,
+            policy: None,
+            family: AddressFamily::Any,
+            priority: Priority::Normal,
+        }
+    }
+
+    #[test]
+    fn test_command_for_pid() {
+        let mut limit = limit(Some(2 * 1024 * 1024), Some(1536));
+        limit.interfaces = Some(vec!["wlan0".to_string()]);
+        limit.policy = Some(Policy::Shape);
+        limit.upload_minimum = Some(1024);
+        limit.priority = Priority::High;
+        let command = ThrottleCommand::new(&CommandTarget::Pid(1234), &limit);
+        assert_eq!(
+            command.command,
+            "chadthrottle --pid 1234 --download-limit 2M --upload-limit 1536 \
+             --interface wlan0 --upload-minimum 1K --policy shape --priority high"
+        );
+        assert!(command.notes.is_empty());
+
+        limit.traffic_type = TrafficType::Internet;
+        limit.family = AddressFamily::V6;
+        let command = ThrottleCommand::new(&CommandTarget::Pid(1234), &limit).with_children(true);
+        assert!(
+            command
+                .command
+                .ends_with("--priority high --traffic-type internet")
+        );
+        assert_eq!(command.notes.len(), 2);
+    }
+
+    #[test]
+    fn test_command_for_all_except() {
+        let mut limit = limit(None, Some(500 * 1024));
+        limit.policy = Some(Policy::Drop);
+        let target = CommandTarget::AllExcept(vec!["zoom*".to_string(), "firefox".to_string()]);
+        let command = ThrottleCommand::new(&target, &limit);
+        assert_eq!(
+            command.command,
+            "chadthrottle --all-except 'zoom*,firefox' --upload-limit 500K"
+        );
+        assert_eq!(command.notes.len(), 1);
+    }
+
+    #[test]
+    fn test_cli_limit_and_quoting() {
+        assert_eq!(cli_limit(128), "128");
+        assert_eq!(cli_limit(1024), "1K");
+        assert_eq!(cli_limit(1024 * 1024 + 512), "1049088");
+        assert_eq!(cli_limit(3 * 1024 * 1024), "3M");
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+    }
+}
@@ -0,0 +1,1320 @@
+// Process detail view and its tabs
+
+use crate::connection_events::ConnectionEventKind;
+use crate::format;
+use crate::process::{ConnectionDetail, Policy, ProcessInfo};
+use crate::ui::process_list::format_tcp_health;
+use crate::ui::{AppState, ClickableRegion, ClickableRegionType, ProcessDetailTab};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+};
+use std::net::IpAddr;
+use std::time::Instant;
+use unicode_width::UnicodeWidthStr;
+
+pub(super) fn draw_process_detail(f: &mut Frame, area: Rect, app: &mut AppState) {
+    let theme = app.theme;
+    // Get the process being detailed (still alive in process list?)
+    // Clone it to avoid borrow checker issues
+    let process = match app.get_detail_process() {
+        Some(p) => p.clone(),
+        None => {
+            // Process no longer exists - show message and return to process list
+            let message = Paragraph::new("Process no longer exists (press Esc to return)")
+                .style(Style::default().fg(theme.error))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title("Process Not Found"),
+                );
+            f.render_widget(message, area);
+            return;
+        }
+    };
+
+    // Split into combined header and content areas (saves 6 lines!)
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Combined title + tabs
+            Constraint::Min(10),   // Content
+        ])
+        .split(area);
+
+    // Draw combined title with tabs
+    draw_detail_header_with_tabs(f, chunks[0], &process, app.detail_tab, app);
+
+    // Draw content based on active tab
+    match app.detail_tab {
+        ProcessDetailTab::Overview => draw_detail_overview(f, chunks[1], &process, app),
+        ProcessDetailTab::Connections => draw_detail_connections(f, chunks[1], &process, app),
+        ProcessDetailTab::Traffic => draw_detail_traffic(f, chunks[1], &process, app),
+        ProcessDetailTab::System => draw_detail_system(f, chunks[1], &process, app),
+    }
+}
+
+pub(super) fn draw_detail_header_with_tabs(
+    f: &mut Frame,
+    area: Rect,
+    process: &ProcessInfo,
+    current_tab: ProcessDetailTab,
+    app: &mut AppState,
+) {
+    let theme = app.theme;
+    let tabs = vec!["Overview", "Connections", "Traffic", "System"];
+    let mut spans = vec![];
+    let mut tab_ranges = Vec::new();
+
+    // Track column position for click regions
+    let base_col = area.x + 1; // Account for border only (no padding)
+    let mut current_col = base_col;
+
+    // Add process name and PID first
+    let name_span = Span::styled(
+        process.name.clone(),
+        Style::default()
+            .fg(theme.title)
+            .add_modifier(Modifier::BOLD),
+    );
+    current_col += name_span.content.width() as u16;
+    spans.push(name_span);
+
+    let pid_text = format!(" (PID {})  ", process.pid);
+    current_col += pid_text.width() as u16;
+    spans.push(Span::raw(pid_text));
+
+    // Add tabs and track their positions for click handling
+    for (i, tab_name) in tabs.iter().enumerate() {
+        let tab_enum = match i {
+            0 => ProcessDetailTab::Overview,
+            1 => ProcessDetailTab::Connections,
+            2 => ProcessDetailTab::Traffic,
+            3 => ProcessDetailTab::System,
+            _ => ProcessDetailTab::Overview,
+        };
+
+        let is_active = tab_enum == current_tab;
+
+        // Add space before tab (except first)
+        if i > 0 {
+            spans.push(Span::raw(" "));
+            current_col += 1;
+        }
+
+        let style = if is_active {
+            theme.selected()
+        } else {
+            Style::default().fg(theme.secondary)
+        };
+
+        // Add opening bracket and start tracking click range
+        let tab_click_start = current_col; // Include opening bracket
+        spans.push(Span::raw("["));
+        current_col += 1;
+
+        // Add tab name
+        spans.push(Span::styled(tab_name.to_string(), style));
+        current_col += tab_name.width() as u16;
+
+        // Add closing bracket
+        spans.push(Span::raw("]"));
+        current_col += 1;
+        let tab_click_end = current_col; // Include closing bracket
+
+        // Store click range including brackets for easier clicking
+        tab_ranges.push((tab_click_start, tab_click_end, tab_enum));
+    }
+
+    let header_widget = Paragraph::new(Line::from(spans)).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Process Details"),
+    );
+    f.render_widget(header_widget, area);
+
+    // Store clickable region for mouse selection
+    app.clickable_regions.push(ClickableRegion {
+        area,
+        region_type: ClickableRegionType::ProcessDetailTabs { tab_ranges },
+    });
+}
+
+pub(super) fn draw_detail_overview(
+    f: &mut Frame,
+    area: Rect,
+    process: &ProcessInfo,
+    app: &mut AppState,
+) {
+    let theme = app.theme;
+    let history = &app.history;
+    let mut text = vec![];
+
+    // Basic Information
+    text.push(Line::from(vec![Span::styled(
+        "Basic Information:",
+        Style::default()
+            .fg(theme.title)
+            .add_modifier(Modifier::BOLD),
+    )]));
+    text.push(Line::from(""));
+    text.push(Line::from(format!("  PID:              {}", process.pid)));
+    text.push(Line::from(format!("  Name:             {}", process.name)));
+    if let Some(row) = app.container_rows.get(&process.pid) {
+        let members: Vec<String> = row
+            .members
+            .iter()
+            .map(|(pid, name)| format!("{} ({})", name, pid))
+            .collect();
+        text.push(Line::from(format!(
+            "  Container:        {} {} - {} process(es), traffic summed",
+            row.container.runtime.label(),
+            row.container.short_id(),
+            members.len()
+        )));
+        text.push(Line::from(format!(
+            "  Processes:        {}",
+            members.join(", ")
+        )));
+    }
+
+    // Get process details
+    let details = crate::process::ProcessDetails::from_pid(process.pid);
+
+    if let Some(ref cmdline) = details.cmdline {
+        let cmd_str = cmdline.join(" ");
+        let cmd_display = if cmd_str.len() > 80 {
+            format!("{}...", &cmd_str[..77])
+        } else {
+            cmd_str
+        };
+        text.push(Line::from(format!("  Command:          {}", cmd_display)));
+    }
+
+    if let Some(ref exe) = details.exe_path {
+        text.push(Line::from(format!("  Executable:       {}", exe)));
+    }
+
+    if let Some(ref cwd) = details.cwd {
+        text.push(Line::from(format!("  Working Dir:      {}", cwd)));
+    }
+
+    text.push(Line::from(format!(
+        "  State:            {}",
+        details.state_description()
+    )));
+
+    if let Some(ppid) = details.ppid {
+        text.push(Line::from(format!("  Parent PID:       {}", ppid)));
+    }
+
+    text.push(Line::from(""));
+
+    // Network Statistics
+    text.push(Line::from(vec![Span::styled(
+        "Network Statistics:",
+        Style::default()
+            .fg(theme.title)
+            .add_modifier(Modifier::BOLD),
+    )]));
+    text.push(Line::from(""));
+    text.push(Line::from(vec![
+        Span::raw("  Current Download: "),
+        Span::styled(
+            format!("↓ {:>10}", format::format_rate(process.download_rate)),
+            Style::default().fg(theme.download),
+        ),
+        Span::raw("    Upload: "),
+        Span::styled(
+            format!("↑ {:>10}", format::format_rate(process.upload_rate)),
+            Style::default().fg(theme.upload),
+        ),
+    ]));
+
+    text.push(Line::from(vec![
+        Span::raw("  Total Download:   "),
+        Span::styled(
+            format!("{:>10}", format::format_bytes(process.total_download)),
+            Style::default().fg(theme.download_total),
+        ),
+        Span::raw("      Upload: "),
+        Span::styled(
+            format!("{:>10}", format::format_bytes(process.total_upload)),
+            Style::default().fg(theme.upload_total),
+        ),
+    ]));
+
+    // Get history stats
+    if let Some(hist) = history.get_history(process.pid) {
+        text.push(Line::from(vec![
+            Span::raw("  Peak Download:    "),
+            Span::styled(
+                format!("{:>10}", format::format_rate(hist.max_download_rate())),
+                Style::default().fg(theme.download),
+            ),
+            Span::raw("      Upload: "),
+            Span::styled(
+                format!("{:>10}", format::format_rate(hist.max_upload_rate())),
+                Style::default().fg(theme.upload),
+            ),
+        ]));
+
+        text.push(Line::from(vec![
+            Span::raw("  Avg Download:     "),
+            Span::styled(
+                format!("{:>10}", format::format_rate(hist.avg_download_rate())),
+                Style::default().fg(theme.download),
+            ),
+            Span::raw("      Upload: "),
+            Span::styled(
+                format!("{:>10}", format::format_rate(hist.avg_upload_rate())),
+                Style::default().fg(theme.upload),
+            ),
+        ]));
+    }
+
+    // TCP health: a throttle that makes a flow retransmit more is hurting it
+    let (retransmits, rtt) = format_tcp_health(process);
+    let spike = app.retransmit_spike(process);
+    let mut health_line = vec![
+        Span::raw("  Retransmits:      "),
+        Span::styled(
+            format!("{:>8}/s", retransmits),
+            Style::default().fg(if spike { theme.error } else { theme.text }),
+        ),
+        Span::raw("     Avg RTT: "),
+        Span::styled(format!("{:>10}", rtt), Style::default().fg(theme.text)),
+    ];
+    if spike {
+        health_line.push(Span::styled(
+            "  (up since throttled)",
+            Style::default().fg(theme.error),
+        ));
+    }
+    text.push(Line::from(health_line));
+
+    text.push(Line::from(""));
+
+    // Internet/Local breakdown
+    text.push(Line::from(vec![Span::styled(
+        "Internet Traffic:",
+        Style::default()
+            .fg(theme.title)
+            .add_modifier(Modifier::BOLD),
+    )]));
+    text.push(Line::from(""));
+
+    let internet_pct = if process.download_rate > 0 {
+        (process.internet_download_rate as f64 / process.download_rate as f64 * 100.0) as u32
+    } else {
+        0
+    };
+
+    text.push(Line::from(vec![
+        Span::raw("  Download:         "),
+        Span::styled(
+            format!(
+                "↓ {:>10}",
+                format::format_rate(process.internet_download_rate)
+            ),
+            Style::default().fg(theme.download),
+        ),
+        Span::raw(format!(" ({}%)  Total: ", internet_pct)),
+        Span::styled(
+            format::format_bytes(process.internet_total_download),
+            Style::default().fg(theme.download_total),
+        ),
+    ]));
+
+    let upload_pct = if process.upload_rate > 0 {
+        (process.internet_upload_rate as f64 / process.upload_rate as f64 * 100.0) as u32
+    } else {
+        0
+    };
+
+    text.push(Line::from(vec![
+        Span::raw("  Upload:           "),
+        Span::styled(
+            format!(
+                "↑ {:>10}",
+                format::format_rate(process.internet_upload_rate)
+            ),
+            Style::default().fg(theme.upload),
+        ),
+        Span::raw(format!(" ({}%)  Total: ", upload_pct)),
+        Span::styled(
+            format::format_bytes(process.internet_total_upload),
+            Style::default().fg(theme.upload_total),
+        ),
+    ]));
+
+    text.push(Line::from(""));
+
+    // Local traffic
+    text.push(Line::from(vec![Span::styled(
+        "Local Traffic:",
+        Style::default()
+            .fg(theme.title)
+            .add_modifier(Modifier::BOLD),
+    )]));
+    text.push(Line::from(""));
+
+    let local_dl_pct = if process.download_rate > 0 {
+        (process.local_download_rate as f64 / process.download_rate as f64 * 100.0) as u32
+    } else {
+        0
+    };
+
+    text.push(Line::from(vec![
+        Span::raw("  Download:         "),
+        Span::styled(
+            format!("↓ {:>10}", format::format_rate(process.local_download_rate)),
+            Style::default().fg(theme.download),
+        ),
+        Span::raw(format!(" ({}%)   Total: ", local_dl_pct)),
+        Span::styled(
+            format::format_bytes(process.local_total_download),
+            Style::default().fg(theme.download_total),
+        ),
+    ]));
+
+    let local_ul_pct = if process.upload_rate > 0 {
+        (process.local_upload_rate as f64 / process.upload_rate as f64 * 100.0) as u32
+    } else {
+        0
+    };
+
+    text.push(Line::from(vec![
+        Span::raw("  Upload:           "),
+        Span::styled(
+            format!("↑ {:>10}", format::format_rate(process.local_upload_rate)),
+            Style::default().fg(theme.upload),
+        ),
+        Span::raw(format!(" ({}%)   Total: ", local_ul_pct)),
+        Span::styled(
+            format::format_bytes(process.local_total_upload),
+            Style::default().fg(theme.upload_total),
+        ),
+    ]));
+
+    text.push(Line::from(""));
+
+    // Throttle status
+    text.push(Line::from(vec![Span::styled(
+        "Throttle Status:",
+        Style::default()
+            .fg(theme.title)
+            .add_modifier(Modifier::BOLD),
+    )]));
+    text.push(Line::from(""));
+
+    if let Some(ref throttle) = process.throttle_limit {
+        let dl_text = if let Some(limit) = throttle.download_limit {
+            format::format_rate(limit)
+        } else {
+            "Unlimited".to_string()
+        };
+
+        let ul_text = if let Some(limit) = throttle.upload_limit {
+            format::format_rate(limit)
+        } else {
+            "Unlimited".to_string()
+        };
+
+        let traffic_type_text = match throttle.traffic_type {
+            crate::process::TrafficType::All => "All Traffic",
+            crate::process::TrafficType::Internet => "Internet Only",
+            crate::process::TrafficType::Local => "Local Only",
+        };
+
+        text.push(Line::from(vec![
+            Span::raw("  Download Limit:   "),
+            Span::styled(
+                dl_text,
+                Style::default()
+                    .fg(theme.error)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(format!(" ({})", traffic_type_text)),
+            Span::styled(" ⚡", Style::default().fg(theme.highlight)),
+        ]));
+
+        text.push(Line::from(vec![
+            Span::raw("  Upload Limit:     "),
+            Span::styled(
+                ul_text,
+                Style::default()
+                    .fg(theme.error)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]));
+
+        if let Some(policy) = throttle.policy {
+            text.push(Line::from(vec![
+                Span::raw("  Policy:           "),
+                Span::styled(
+                    match policy {
+                        Policy::Shape => "Shape (excess queued)",
+                        Policy::Drop => "Drop (excess discarded)",
+                    },
+                    Style::default().fg(theme.accent),
+                ),
+            ]));
+        }
+
+        if throttle.family != crate::process::AddressFamily::Any {
+            text.push(Line::from(vec![
+                Span::raw("  IP Version:       "),
+                Span::styled(
+                    throttle.family.to_string(),
+                    Style::default().fg(theme.accent),
+                ),
+            ]));
+        }
+
+        if throttle.priority != crate::process::Priority::Normal {
+            text.push(Line::from(vec![
+                Span::raw("  Priority:         "),
+                Span::styled(
+                    throttle.priority.to_string(),
+                    Style::default().fg(theme.accent),
+                ),
+            ]));
+        }
+
+        if process.throttle_sandboxed {
+            text.push(Line::from(vec![
+                Span::raw("  Sandbox:          "),
+                Span::styled(
+                    "📦 throttled in its own cgroup (covers the whole app or container)",
+                    Style::default().fg(theme.accent),
+                ),
+            ]));
+        }
+
+        if let Some(requested) = app.detail_throttle_downgraded {
+            let requested_text = match requested {
+                crate::process::TrafficType::All => "All Traffic",
+                crate::process::TrafficType::Internet => "Internet Only",
+                crate::process::TrafficType::Local => "Local Only",
+            };
+            text.push(Line::from(vec![
+                Span::raw("  Downgraded:       "),
+                Span::styled(
+                    format!(
+                        "from {} (backend can't filter by traffic type)",
+                        requested_text
+                    ),
+                    Style::default().fg(theme.warning),
+                ),
+            ]));
+        }
+
+        let enforced_by: Vec<String> = match &app.detail_throttle_backends {
+            (Some(upload), Some(download)) if upload == download => vec![upload.clone()],
+            (upload, download) => [("upload", upload), ("download", download)]
+                .into_iter()
+                .filter_map(|(direction, backend)| {
+                    backend.as_ref().map(|b| format!("{} ({})", b, direction))
+                })
+                .collect(),
+        };
+        if !enforced_by.is_empty() {
+            text.push(Line::from(vec![
+                Span::raw("  Enforced By:      "),
+                Span::styled(enforced_by.join(", "), Style::default().fg(theme.accent)),
+            ]));
+        }
+
+        if let Some(utilization) = app.throttle_utilization.get(&process.pid) {
+            let percent = |percent: Option<f64>| {
+                percent.map_or("-".to_string(), |percent| format!("{:.0}%", percent))
+            };
+            let (hint, hint_color) = if utilization.not_working() {
+                (
+                    "well over the limit: the throttle isn't holding",
+                    theme.error,
+                )
+            } else if utilization.stale {
+                (
+                    "idle for 10+ minutes: the throttle may be stale",
+                    theme.warning,
+                )
+            } else if utilization.highest().is_some_and(|p| p < 50.0) {
+                ("the limit isn't the bottleneck", theme.muted)
+            } else {
+                ("the limit is what holds it back", theme.muted)
+            };
+            text.push(Line::from(vec![
+                Span::raw("  Utilization:      "),
+                Span::styled(
+                    format!(
+                        "↓ {} ↑ {} of the limits",
+                        percent(utilization.download),
+                        percent(utilization.upload)
+                    ),
+                    Style::default().fg(theme.accent),
+                ),
+                Span::styled(
+                    format!(" (last {} samples)", utilization.samples),
+                    Style::default().fg(theme.muted),
+                ),
+            ]));
+            text.push(Line::from(vec![
+                Span::raw("                    "),
+                Span::styled(hint, Style::default().fg(hint_color)),
+            ]));
+        }
+    } else {
+        text.push(Line::from("  Not throttled"));
+    }
+
+    text.push(Line::from(""));
+
+    // Why throttled packets passed or dropped (backends that collect stats only)
+    let (upload_stats, download_stats) = &app.detail_throttle_stats;
+    if process.throttle_limit.is_some() && (upload_stats.is_some() || download_stats.is_some()) {
+        text.push(Line::from(vec![Span::styled(
+            "Throttle Diagnostics:",
+            Style::default()
+                .fg(theme.title)
+                .add_modifier(Modifier::BOLD),
+        )]));
+        text.push(Line::from(""));
+
+        let (upload_growing, download_growing) = app.detail_backlog_growing();
+        for (label, stats, growing) in [
+            ("Download:", download_stats, download_growing),
+            ("Upload:", upload_stats, upload_growing),
+        ] {
+            let Some(stats) = stats else {
+                continue;
+            };
+            let drop_pct = if stats.packets_total > 0 {
+                stats.packets_dropped as f64 / stats.packets_total as f64 * 100.0
+            } else {
+                0.0
+            };
+            text.push(Line::from(format!(
+                "  {:<18}{} packets ({}), {} dropped ({:.1}%, {})",
+                label,
+                stats.packets_total,
+                format::format_bytes(stats.bytes_total),
+                stats.packets_dropped,
+                drop_pct,
+                format::format_bytes(stats.bytes_dropped)
+            )));
+            // Shaping backends queue instead of dropping on empty buckets, so the
+            // pass/drop reasons only apply to the drop-based backends
+            if stats.backlog_bytes.is_none() {
+                text.push(Line::from(format!(
+                    "    Passed (tokens available):     {}",
+                    stats.passed_with_tokens
+                )));
+                text.push(Line::from(vec![
+                    Span::raw("    Dropped (no tokens):           "),
+                    Span::styled(
+                        stats.dropped_no_tokens.to_string(),
+                        Style::default().fg(theme.error),
+                    ),
+                ]));
+                text.push(Line::from(format!(
+                    "    Passed (traffic type filter):  {}",
+                    stats.passed_filter_mismatch
+                )));
+            }
+            if stats.cgroup_repairs > 0 {
+                text.push(Line::from(Span::styled(
+                    format!(
+                        "    Repaired (stale cgroup id):    {}",
+                        stats.cgroup_repairs
+                    ),
+                    Style::default().fg(theme.highlight),
+                )));
+            }
+            if stats.reattachments > 0 {
+                text.push(Line::from(Span::styled(
+                    format!("    Re-attached (stale attach):    {}", stats.reattachments),
+                    Style::default().fg(theme.highlight),
+                )));
+            }
+            if let (Some(backlog), Some(delay_ms)) =
+                (stats.backlog_bytes, stats.queue_delay_estimate_ms)
+            {
+                let queue = format!(
+                    "    Queued:                        {} (~{} ms delay)",
+                    format::format_bytes(backlog),
+                    delay_ms
+                );
+                if growing {
+                    text.push(Line::from(Span::styled(
+                        format!("{}, growing (bufferbloat)", queue),
+                        Style::default().fg(theme.warning),
+                    )));
+                } else {
+                    text.push(Line::from(queue));
+                }
+            }
+        }
+
+        text.push(Line::from(""));
+    }
+
+    // System resources
+    if details.memory_rss.is_some() || details.threads.is_some() {
+        text.push(Line::from(vec![Span::styled(
+            "System Resources:",
+            Style::default()
+                .fg(theme.title)
+                .add_modifier(Modifier::BOLD),
+        )]));
+        text.push(Line::from(""));
+
+        if let Some(rss) = details.memory_rss {
+            text.push(Line::from(format!(
+                "  Memory (RSS):     {}",
+                crate::process::ProcessDetails::format_memory(rss)
+            )));
+        }
+
+        if let Some(vms) = details.memory_vms {
+            text.push(Line::from(format!(
+                "  Memory (VMS):     {}",
+                crate::process::ProcessDetails::format_memory(vms)
+            )));
+        }
+
+        if let Some(threads) = details.threads {
+            text.push(Line::from(format!("  Threads:          {}", threads)));
+        }
+    }
+
+    text.push(Line::from(""));
+    text.push(Line::from(Span::styled(
+        "[↑↓] Scroll  [Tab] Switch tab  [t] Throttle  [g] Graph  [Esc] Back",
+        Style::default().fg(theme.muted),
+    )));
+
+    // Clamp scroll offset to content bounds
+    let content_lines = text.len();
+    let clamped_scroll =
+        AppState::clamp_scroll(app.detail_scroll_offset, content_lines, area.height);
+    app.detail_scroll_offset = clamped_scroll;
+
+    let paragraph = Paragraph::new(text)
+        .scroll((clamped_scroll as u16, 0))
+        .block(Block::default().borders(Borders::ALL).title("Overview"));
+    f.render_widget(paragraph, area);
+}
+
+pub(super) fn draw_detail_connections(
+    f: &mut Frame,
+    area: Rect,
+    process: &ProcessInfo,
+    app: &mut AppState,
+) {
+    let theme = app.theme;
+    let mut text = vec![];
+    let mut selected_line = None;
+
+    text.push(Line::from(""));
+    text.push(Line::from(vec![Span::styled(
+        format!("Active Network Connections ({})", process.connections.len()),
+        Style::default()
+            .fg(theme.title)
+            .add_modifier(Modifier::BOLD),
+    )]));
+    text.push(Line::from(""));
+
+    if process.connections.is_empty() {
+        text.push(Line::from("  No active connections"));
+    } else {
+        // Header
+        text.push(Line::from(vec![
+            Span::styled(
+                "  Protocol  ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                "Local Address            ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                "Remote Address           ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled("State     ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::styled(
+                if app.reverse_dns.is_some() {
+                    "Remote Host"
+                } else {
+                    ""
+                },
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+        ]));
+        text.push(Line::from(
+            "  ──────────────────────────────────────────────────────────────────────",
+        ));
+
+        // The cursor stays on the last connection when others close
+        app.detail_connection_index = app
+            .detail_connection_index
+            .min(process.connections.len() - 1);
+        selected_line = Some(text.len() + app.detail_connection_index);
+
+        // Render all connections (scrolling handled by Paragraph widget)
+        for (index, conn) in sorted_connections(process).into_iter().enumerate() {
+            let (local, remote) = connection_endpoints(conn);
+
+            let state_display = match conn.state.as_str() {
+                "Established" => "ESTAB",
+                "Listen" => "LISTEN",
+                "TimeWait" => "TIMEWT",
+                "CloseWait" => "CLOSWT",
+                "FinWait1" => "FIN1",
+                "FinWait2" => "FIN2",
+                "Closing" => "CLOSNG",
+                "SynSent" => "SYNSNT",
+                "SynRecv" => "SYNRCV",
+                s => s,
+            };
+
+            let proto_style = match conn.protocol.as_str() {
+                "TCP" | "TCP6" => Style::default().fg(theme.success),
+                "UDP" | "UDP6" => Style::default().fg(theme.highlight),
+                _ => Style::default(),
+            };
+
+            let state_style = match conn.state.as_str() {
+                "Established" => Style::default().fg(theme.success),
+                "Listen" => Style::default().fg(theme.title),
+                _ => Style::default().fg(theme.secondary),
+            };
+
+            let mut line = Line::from(vec![
+                Span::raw("  "),
+                Span::styled(format!("{:8}  ", conn.protocol), proto_style),
+                Span::raw(format!("{:24} ", local)),
+                Span::raw(format!("{:24} ", remote)),
+                Span::styled(format!("{:8}  ", state_display), state_style),
+            ]);
+            if index == app.detail_connection_index {
+                line = line.style(theme.selected());
+            }
+            text.push(line);
+            if conn.remote_port != 0
+                && let Some(host) = app
+                    .reverse_dns
+                    .as_mut()
+                    .and_then(|dns| dns.lookup(conn.remote_addr))
+                && let Some(line) = text.last_mut()
+            {
+                line.push_span(Span::styled(
+                    host.to_string(),
+                    Style::default().fg(theme.muted),
+                ));
+            }
+        }
+    }
+
+    text.push(Line::from(""));
+    text.push(Line::from(vec![Span::styled(
+        format!("Events ({})", process.connection_events.len()),
+        Style::default()
+            .fg(theme.title)
+            .add_modifier(Modifier::BOLD),
+    )]));
+    text.push(Line::from(""));
+
+    if process.connection_events.is_empty() {
+        text.push(Line::from("  No connections opened or closed yet"));
+    } else {
+        let now = Instant::now();
+        for event in &process.connection_events {
+            let style = match event.kind {
+                ConnectionEventKind::Opened => Style::default().fg(theme.success),
+                ConnectionEventKind::Closed => Style::default().fg(theme.secondary),
+            };
+            text.push(Line::from(vec![
+                Span::raw("  "),
+                Span::styled(event.summary(), style),
+                Span::styled(
+                    format!("  {}", event.age_label(now)),
+                    Style::default().fg(theme.muted),
+                ),
+            ]));
+        }
+    }
+
+    text.push(Line::from(""));
+    text.push(Line::from(""));
+    text.push(Line::from(Span::styled(
+        "[↑↓] Select  [y] Copy connection  [Tab] Switch tab  [Esc] Back",
+        Style::default().fg(theme.muted),
+    )));
+
+    // Bring the connection cursor into view when it moved
+    if let Some(line) = selected_line
+        && app.last_detail_connection_selection != Some(app.detail_connection_index)
+    {
+        app.detail_scroll_offset =
+            AppState::scroll_to_line(app.detail_scroll_offset, line, area.height);
+        app.last_detail_connection_selection = Some(app.detail_connection_index);
+    }
+
+    // Clamp scroll offset to content bounds
+    let content_lines = text.len();
+    let clamped_scroll =
+        AppState::clamp_scroll(app.detail_scroll_offset, content_lines, area.height);
+    app.detail_scroll_offset = clamped_scroll;
+
+    let paragraph = Paragraph::new(text)
+        .scroll((clamped_scroll as u16, 0))
+        .block(Block::default().borders(Borders::ALL).title("Connections"));
+    f.render_widget(paragraph, area);
+}
+
+/// Connections in the order the Connections tab lists them: established first,
+/// then listening, then the rest
+pub(super) fn sorted_connections(process: &ProcessInfo) -> Vec<&ConnectionDetail> {
+    let order = |conn: &ConnectionDetail| match conn.state.as_str() {
+        "Established" => 0,
+        "Listen" => 1,
+        _ => 2,
+    };
+    let mut connections: Vec<&ConnectionDetail> = process.connections.iter().collect();
+    connections.sort_by_key(|conn| order(conn));
+    connections
+}
+
+/// (local, remote) address:port of a connection, "*:*" for an unconnected remote
+pub(super) fn connection_endpoints(conn: &ConnectionDetail) -> (String, String) {
+    let local = format!("{}:{}", format_ip_addr(&conn.local_addr), conn.local_port);
+    let remote = if conn.remote_port == 0 {
+        "*:*".to_string()
+    } else {
+        format!("{}:{}", format_ip_addr(&conn.remote_addr), conn.remote_port)
+    };
+    (local, remote)
+}
+
+pub(super) fn format_ip_addr(addr: &IpAddr) -> String {
+    match addr {
+        IpAddr::V4(ipv4) if ipv4.is_unspecified() => "0.0.0.0".to_string(),
+        IpAddr::V6(ipv6) if ipv6.is_unspecified() => "::".to_string(),
+        _ => addr.to_string(),
+    }
+}
+
+pub(super) fn draw_detail_traffic(
+    f: &mut Frame,
+    area: Rect,
+    process: &ProcessInfo,
+    app: &mut AppState,
+) {
+    let theme = app.theme;
+    let mut text = vec![];
+
+    text.push(Line::from(""));
+    text.push(Line::from(vec![Span::styled(
+        "Traffic Breakdown by Interface:",
+        Style::default()
+            .fg(theme.title)
+            .add_modifier(Modifier::BOLD),
+    )]));
+    text.push(Line::from(""));
+
+    if process.interface_stats.is_empty() {
+        text.push(Line::from("  No interface data available"));
+    } else {
+        // Header
+        text.push(Line::from(vec![
+            Span::styled(
+                "  Interface    ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                "Download Rate    ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                "Upload Rate    ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                "Total DL     ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled("Total UL", Style::default().add_modifier(Modifier::BOLD)),
+        ]));
+
+        text.push(Line::from(
+            "  ─────────────────────────────────────────────────────────────────────",
+        ));
+
+        // Sort interfaces by download rate
+        let mut ifaces: Vec<_> = process.interface_stats.iter().collect();
+        ifaces.sort_by(|a, b| b.1.download_rate.cmp(&a.1.download_rate));
+
+        for (iface_name, stats) in ifaces.iter().take(10) {
+            // Show top 10
+            text.push(Line::from(vec![
+                Span::raw(format!("  {:12} ", iface_name)),
+                Span::styled(
+                    format!("↓ {:>10}     ", format::format_rate(stats.download_rate)),
+                    Style::default().fg(theme.download),
+                ),
+                Span::styled(
+                    format!("↑ {:>10}   ", format::format_rate(stats.upload_rate)),
+                    Style::default().fg(theme.upload),
+                ),
+                Span::styled(
+                    format!("{:>10}   ", format::format_bytes(stats.total_download)),
+                    Style::default().fg(theme.download_total),
+                ),
+                Span::styled(
+                    format!("{:>10}", format::format_bytes(stats.total_upload)),
+                    Style::default().fg(theme.upload_total),
+                ),
+            ]));
+        }
+    }
+
+    text.push(Line::from(""));
+    text.push(Line::from(""));
+
+    // Traffic by type
+    text.push(Line::from(vec![Span::styled(
+        "Traffic by Type:",
+        Style::default()
+            .fg(theme.title)
+            .add_modifier(Modifier::BOLD),
+    )]));
+    text.push(Line::from(""));
+
+    let total_dl = process.download_rate;
+    let total_ul = process.upload_rate;
+
+    if total_dl > 0 || total_ul > 0 {
+        let internet_pct = if total_dl > 0 {
+            (process.internet_download_rate as f64 / total_dl as f64 * 100.0) as u32
+        } else {
+            0
+        };
+
+        text.push(Line::from(vec![
+            Span::raw("  🌐 Internet:  "),
+            Span::styled(
+                format!("{}%", internet_pct),
+                Style::default()
+                    .fg(theme.highlight)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("  ("),
+            Span::styled(
+                format!("↓ {}", format::format_rate(process.internet_download_rate)),
+                Style::default().fg(theme.download),
+            ),
+            Span::raw(", "),
+            Span::styled(
+                format!("↑ {})", format::format_rate(process.internet_upload_rate)),
+                Style::default().fg(theme.upload),
+            ),
+        ]));
+
+        let local_pct = if total_dl > 0 {
+            (process.local_download_rate as f64 / total_dl as f64 * 100.0) as u32
+        } else {
+            0
+        };
+
+        text.push(Line::from(vec![
+            Span::raw("  🏠 Local:     "),
+            Span::styled(
+                format!("{}%", local_pct),
+                Style::default()
+                    .fg(theme.highlight)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("  ("),
+            Span::styled(
+                format!("↓ {}", format::format_rate(process.local_download_rate)),
+                Style::default().fg(theme.download),
+            ),
+            Span::raw(", "),
+            Span::styled(
+                format!("↑ {})", format::format_rate(process.local_upload_rate)),
+                Style::default().fg(theme.upload),
+            ),
+        ]));
+    } else {
+        text.push(Line::from("  No traffic"));
+    }
+
+    text.push(Line::from(""));
+    text.push(Line::from(""));
+
+    // Traffic by protocol (e.g. QUIC apps move most of their bytes over UDP)
+    text.push(Line::from(vec![Span::styled(
+        "Traffic by Protocol:",
+        Style::default()
+            .fg(theme.title)
+            .add_modifier(Modifier::BOLD),
+    )]));
+    text.push(Line::from(""));
+
+    let protocol_total = process.tcp_total_download
+        + process.tcp_total_upload
+        + process.udp_total_download
+        + process.udp_total_upload;
+    if protocol_total > 0 {
+        let protocols = [
+            (
+                "TCP",
+                process.tcp_download_rate,
+                process.tcp_upload_rate,
+                process.tcp_total_download,
+                process.tcp_total_upload,
+            ),
+            (
+                "UDP",
+                process.udp_download_rate,
+                process.udp_upload_rate,
+                process.udp_total_download,
+                process.udp_total_upload,
+            ),
+        ];
+        for (protocol, download_rate, upload_rate, total_download, total_upload) in protocols {
+            let pct = ((total_download + total_upload) as f64 / protocol_total as f64 * 100.0)
+                .round() as u32;
+            text.push(Line::from(vec![
+                Span::raw(format!("  {}:  ", protocol)),
+                Span::styled(
+                    format!("{:>3}%", pct),
+                    Style::default()
+                        .fg(theme.highlight)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw("  ("),
+                Span::styled(
+                    format!("↓ {}", format::format_rate(download_rate)),
+                    Style::default().fg(theme.download),
+                ),
+                Span::raw(", "),
+                Span::styled(
+                    format!("↑ {}", format::format_rate(upload_rate)),
+                    Style::default().fg(theme.upload),
+                ),
+                Span::raw(")  total "),
+                Span::styled(
+                    format!("↓ {}", format::format_bytes(total_download)),
+                    Style::default().fg(theme.download_total),
+                ),
+                Span::raw(", "),
+                Span::styled(
+                    format!("↑ {}", format::format_bytes(total_upload)),
+                    Style::default().fg(theme.upload_total),
+                ),
+            ]));
+        }
+    } else {
+        text.push(Line::from("  No traffic"));
+    }
+
+    text.push(Line::from(""));
+    text.push(Line::from(""));
+    text.push(Line::from(Span::styled(
+        "[↑↓] Scroll  [Tab] Switch tab  [Esc] Back",
+        Style::default().fg(theme.muted),
+    )));
+
+    // Clamp scroll offset to content bounds
+    let content_lines = text.len();
+    let clamped_scroll =
+        AppState::clamp_scroll(app.detail_scroll_offset, content_lines, area.height);
+    app.detail_scroll_offset = clamped_scroll;
+
+    let paragraph = Paragraph::new(text)
+        .scroll((clamped_scroll as u16, 0))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Traffic Details"),
+        );
+    f.render_widget(paragraph, area);
+}
+
+pub(super) fn draw_detail_system(
+    f: &mut Frame,
+    area: Rect,
+    process: &ProcessInfo,
+    app: &mut AppState,
+) {
+    let theme = app.theme;
+    let details = crate::process::ProcessDetails::from_pid(process.pid);
+
+    let mut text = vec![];
+
+    text.push(Line::from(""));
+    text.push(Line::from(vec![Span::styled(
+        "Process Information:",
+        Style::default()
+            .fg(theme.title)
+            .add_modifier(Modifier::BOLD),
+    )]));
+    text.push(Line::from(""));
+
+    text.push(Line::from(format!("  PID:                {}", details.pid)));
+    if let Some(ppid) = details.ppid {
+        text.push(Line::from(format!("  Parent PID:         {}", ppid)));
+    }
+    text.push(Line::from(format!(
+        "  State:              {}",
+        details.state_description()
+    )));
+
+    text.push(Line::from(""));
+
+    // User/Group
+    if details.uid.is_some() || details.gid.is_some() {
+        text.push(Line::from(vec![Span::styled(
+            "User/Group:",
+            Style::default()
+                .fg(theme.title)
+                .add_modifier(Modifier::BOLD),
+        )]));
+        text.push(Line::from(""));
+
+        if let Some(uid) = details.uid {
+            text.push(Line::from(format!("  UID:                {}", uid)));
+        }
+        if let Some(gid) = details.gid {
+            text.push(Line::from(format!("  GID:                {}", gid)));
+        }
+
+        text.push(Line::from(""));
+    }
+
+    // Memory
+    if details.memory_rss.is_some() || details.memory_vms.is_some() {
+        text.push(Line::from(vec![Span::styled(
+            "Memory:",
+            Style::default()
+                .fg(theme.title)
+                .add_modifier(Modifier::BOLD),
+        )]));
+        text.push(Line::from(""));
+
+        if let Some(vms) = details.memory_vms {
+            text.push(Line::from(format!(
+                "  Virtual Memory:     {}",
+                crate::process::ProcessDetails::format_memory(vms)
+            )));
+        }
+        if let Some(rss) = details.memory_rss {
+            text.push(Line::from(format!(
+                "  Resident Memory:    {}",
+                crate::process::ProcessDetails::format_memory(rss)
+            )));
+        }
+
+        text.push(Line::from(""));
+    }
+
+    // Threads
+    if let Some(threads) = details.threads {
+        text.push(Line::from(vec![Span::styled(
+            "Threads:",
+            Style::default()
+                .fg(theme.title)
+                .add_modifier(Modifier::BOLD),
+        )]));
+        text.push(Line::from(""));
+        text.push(Line::from(format!("  Thread Count:       {}", threads)));
+        text.push(Line::from(""));
+    }
+
+    // Executable
+    text.push(Line::from(vec![Span::styled(
+        "Executable:",
+        Style::default()
+            .fg(theme.title)
+            .add_modifier(Modifier::BOLD),
+    )]));
+    text.push(Line::from(""));
+
+    if let Some(ref exe) = details.exe_path {
+        text.push(Line::from(format!("  Path:               {}", exe)));
+    } else {
+        text.push(Line::from("  Path:               (not available)"));
+    }
+
+    if let Some(ref cwd) = details.cwd {
+        text.push(Line::from(format!("  Working Directory:  {}", cwd)));
+    }
+
+    text.push(Line::from(""));
+
+    // Command line
+    if let Some(ref cmdline) = details.cmdline {
+        text.push(Line::from(vec![Span::styled(
+            "Command Line:",
+            Style::default()
+                .fg(theme.title)
+                .add_modifier(Modifier::BOLD),
+        )]));
+        text.push(Line::from(""));
+
+        let cmd_str = cmdline.join(" ");
+        // Wrap long command lines at word/path boundaries
+        // Calculate available width: area width - borders (2) - indentation (2)
+        let max_width = area.width.saturating_sub(4) as usize;
+        let mut remaining = cmd_str.as_str();
+        while !remaining.is_empty() {
+            if remaining.len() <= max_width {
+                // Fits on one line
+                text.push(Line::from(format!("  {}", remaining)));
+                break;
+            }
+
+            // Try to find a good break point (space, slash, backslash)
+            let mut break_point = max_width;
+            if let Some(pos) = remaining[..max_width].rfind(|c| c == ' ' || c == '/' || c == '\\') {
+                // Break after the separator
+                break_point = pos + 1;
+            }
+
+            text.push(Line::from(format!(
+                "  {}",
+                &remaining[..break_point].trim_end()
+            )));
+            remaining = &remaining[break_point..].trim_start();
+        }
+    }
+
+    text.push(Line::from(""));
+    text.push(Line::from(""));
+    text.push(Line::from(Span::styled(
+        "[↑↓] Scroll  [Tab] Switch tab  [Esc] Back",
+        Style::default().fg(theme.muted),
+    )));
+
+    // Clamp scroll offset to content bounds
+    let content_lines = text.len();
+    let clamped_scroll =
+        AppState::clamp_scroll(app.detail_scroll_offset, content_lines, area.height);
+    app.detail_scroll_offset = clamped_scroll;
+
+    let paragraph = Paragraph::new(text)
+        .scroll((clamped_scroll as u16, 0))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("System Information"),
+        );
+    f.render_widget(paragraph, area);
+}
@@ -0,0 +1,181 @@
+// Bandwidth history graph overlay
+
+use crate::format;
+use crate::keybindings::Action;
+use crate::ui::AppState;
+use crate::ui::centered_rect;
+use chrono::{DateTime, Local};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Style,
+    symbols,
+    widgets::{Axis, Block, Borders, Chart, Clear, Dataset, GraphType, Paragraph},
+};
+
+pub(super) fn draw_bandwidth_graph(f: &mut Frame, area: Rect, app: &AppState) {
+    let theme = app.theme;
+    // Get selected process
+    let selected_proc = app.get_selected_process();
+    if selected_proc.is_none() {
+        return;
+    }
+
+    let proc = selected_proc.unwrap();
+    let history = app.history.get_history(proc.pid);
+
+    if history.is_none() || history.unwrap().samples.is_empty() {
+        // No history data available
+        let no_data = Paragraph::new("No historical data available yet...")
+            .style(Style::default().bg(theme.modal_bg).fg(theme.highlight))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("Bandwidth Graph: {} (PID {})", proc.name, proc.pid))
+                    .style(Style::default().fg(theme.title)),
+            );
+
+        let graph_area = centered_rect(80, 60, area);
+        f.render_widget(Clear, graph_area);
+        f.render_widget(no_data, graph_area);
+        return;
+    }
+
+    let history = history.unwrap();
+    let now = chrono::Local::now();
+    let (download_data, upload_data) = history.get_graph_data(now.timestamp().max(0) as u64);
+    let window = history.window_secs();
+
+    // Find max values for scaling
+    let max_download = history.max_download_rate() as f64;
+    let max_upload = history.max_upload_rate() as f64;
+    let max_value = max_download.max(max_upload).max(1.0); // Avoid division by zero
+
+    // Create datasets
+    let datasets = vec![
+        Dataset::default()
+            .name("Download")
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(theme.download))
+            .data(&download_data),
+        Dataset::default()
+            .name("Upload")
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(theme.upload))
+            .data(&upload_data),
+    ];
+
+    // Create chart
+    let chart = Chart::new(datasets)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(
+                    "Bandwidth Graph: {} (PID {}) | Max: ↓{} ↑{} | Avg: ↓{} ↑{} | Median: ↓{} ↑{} | p95: ↓{} ↑{}",
+                    proc.name,
+                    proc.pid,
+                    format::format_rate(history.max_download_rate()),
+                    format::format_rate(history.max_upload_rate()),
+                    format::format_rate(history.avg_download_rate()),
+                    format::format_rate(history.avg_upload_rate()),
+                    format::format_rate(history.percentile_download_rate(50.0)),
+                    format::format_rate(history.percentile_upload_rate(50.0)),
+                    format::format_rate(history.percentile_download_rate(95.0)),
+                    format::format_rate(history.percentile_upload_rate(95.0)),
+                ))
+                .style(Style::default().fg(theme.title)),
+        )
+        .x_axis(
+            Axis::default()
+                .title(if app.graph_clock_time {
+                    "Time"
+                } else {
+                    "Time ago"
+                })
+                .style(Style::default().fg(theme.secondary))
+                .bounds([-window, 0.0])
+                .labels(time_axis_labels(now, window, app.graph_clock_time)),
+        )
+        .y_axis(
+            Axis::default()
+                .title("Bandwidth (bytes/s)")
+                .style(Style::default().fg(theme.secondary))
+                .bounds([0.0, max_value * 1.1]), // Add 10% headroom
+        );
+
+    let graph_area = centered_rect(90, 70, area);
+    f.render_widget(Clear, graph_area);
+    f.render_widget(chart, graph_area);
+
+    // Draw instructions at bottom
+    let instructions = Paragraph::new(format!(
+        "Press '{}' for {}, '{}' or '{}' to close graph",
+        app.keymap.label(Action::ToggleGraphTime),
+        if app.graph_clock_time {
+            "time ago"
+        } else {
+            "clock time"
+        },
+        app.keymap.label(Action::ToggleGraph),
+        app.keymap.label(Action::Quit),
+    ))
+    .style(Style::default().fg(theme.muted))
+    .alignment(ratatui::layout::Alignment::Center);
+
+    let inst_area = Rect {
+        x: graph_area.x,
+        y: graph_area.y + graph_area.height - 2,
+        width: graph_area.width,
+        height: 1,
+    };
+    f.render_widget(instructions, inst_area);
+}
+
+/// Labels for five evenly spaced x-axis ticks over the last `window` seconds: how long
+/// ago ("45s ago", ..., "now") or the wall-clock time ("14:32:05")
+fn time_axis_labels(now: DateTime<Local>, window: f64, clock_time: bool) -> Vec<String> {
+    const TICKS: i64 = 5;
+    (0..TICKS)
+        .map(|tick| {
+            let ago = (window * (TICKS - 1 - tick) as f64 / (TICKS - 1) as f64).round() as i64;
+            if clock_time {
+                (now - chrono::Duration::seconds(ago))
+                    .format("%H:%M:%S")
+                    .to_string()
+            } else if ago == 0 {
+                "now".to_string()
+            } else if ago < 120 {
+                format!("{}s ago", ago)
+            } else if ago % 60 == 0 {
+                format!("{}m ago", ago / 60)
+            } else {
+                format!("{:.1}m ago", ago as f64 / 60.0)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_time_axis_labels() {
+        let now = Local.with_ymd_and_hms(2024, 5, 1, 14, 32, 0).unwrap();
+        assert_eq!(
+            time_axis_labels(now, 60.0, false),
+            ["60s ago", "45s ago", "30s ago", "15s ago", "now"]
+        );
+        assert_eq!(
+            time_axis_labels(now, 600.0, false),
+            ["10m ago", "7.5m ago", "5m ago", "2.5m ago", "now"]
+        );
+        assert_eq!(
+            time_axis_labels(now, 60.0, true),
+            ["14:31:00", "14:31:15", "14:31:30", "14:31:45", "14:32:00"]
+        );
+    }
+}
@@ -0,0 +1,483 @@
+// Interface views: the interface list and per-interface process detail
+
+use crate::backends::monitor::InterfaceErrors;
+use crate::format;
+use crate::interface_filter;
+use crate::process::InterfaceInfo;
+use crate::theme::Theme;
+use crate::ui::AppState;
+use crate::ui::pad_to_width;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+};
+
+pub(super) fn draw_interface_list(f: &mut Frame, area: Rect, app: &mut AppState) {
+    let theme = app.theme;
+    let items: Vec<ListItem> = app
+        .interface_list
+        .iter()
+        .enumerate()
+        .map(|(index, iface)| {
+            // Selection indicator
+            let selection_indicator = if Some(index) == app.interface_list_state.selected() {
+                "▶ "
+            } else {
+                "  "
+            };
+
+            // Status indicator: up (✓), down (✗), loopback (⟲)
+            let status_indicator = if iface.is_loopback {
+                "⟲"
+            } else if iface.is_up {
+                "✓"
+            } else {
+                "✗"
+            };
+
+            let status_color = if iface.is_loopback {
+                theme.title
+            } else if iface.is_up {
+                theme.success
+            } else {
+                theme.error
+            };
+
+            // Calculate visible process count (filtered or total)
+            let visible_count = if let Some(filters) = &app.active_interface_filters {
+                if filters.is_empty() {
+                    0 // Empty filter = show nothing
+                } else if interface_filter::matches(filters, &iface.name) {
+                    // Count processes that use this interface
+                    app.process_list
+                        .iter()
+                        .filter(|p| p.interface_stats.contains_key(&iface.name))
+                        .count()
+                } else {
+                    0 // Not in filter
+                }
+            } else {
+                iface.process_count // No filter = show total
+            };
+
+            // Format IP addresses
+            let ip_str = if iface.ip_addresses.is_empty() {
+                "No IP".to_string()
+            } else {
+                iface
+                    .ip_addresses
+                    .iter()
+                    .take(2) // Show up to 2 IPs
+                    .map(|ip| ip.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+
+            let content = Line::from(vec![
+                Span::styled(selection_indicator, Style::default().fg(theme.highlight)),
+                Span::styled(
+                    status_indicator,
+                    Style::default()
+                        .fg(status_color)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(" "),
+                Span::styled(
+                    format!("{:12} ", iface.name),
+                    Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    format!(
+                        "{:30} ",
+                        if ip_str.len() > 30 {
+                            format!("{}...", &ip_str[..27])
+                        } else {
+                            ip_str
+                        }
+                    ),
+                    Style::default().fg(theme.title),
+                ),
+                Span::styled(
+                    format!("↓{:>10} ", format::format_rate(iface.total_download_rate)),
+                    Style::default().fg(theme.download),
+                ),
+                Span::styled(
+                    format!("↑{:>10} ", format::format_rate(iface.total_upload_rate)),
+                    Style::default().fg(theme.upload),
+                ),
+                Span::styled(
+                    format!("{} proc", visible_count),
+                    Style::default().fg(theme.accent),
+                ),
+            ]);
+
+            ListItem::new(content)
+        })
+        .collect();
+
+    let header = Line::from(vec![
+        Span::styled("  ", Style::default()),
+        Span::styled(
+            "Interface    ",
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            "IP Address                     ",
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Span::styled("DL Rate    ", Style::default().add_modifier(Modifier::BOLD)),
+        Span::styled("UL Rate    ", Style::default().add_modifier(Modifier::BOLD)),
+        Span::styled("Processes", Style::default().add_modifier(Modifier::BOLD)),
+    ]);
+
+    // Split the area for header and list
+    let header_area = Rect {
+        x: area.x + 2,
+        y: area.y + 1,
+        width: area.width - 4,
+        height: 1,
+    };
+
+    let list_area = Rect {
+        x: area.x,
+        y: area.y + 2,
+        width: area.width,
+        height: area.height.saturating_sub(2),
+    };
+
+    // Render border and title
+    let border = Block::default()
+        .borders(Borders::ALL)
+        .title("Network Interfaces");
+    f.render_widget(border, area);
+
+    // Render header
+    f.render_widget(Paragraph::new(header), header_area);
+
+    // Render list
+    let list = List::new(items).highlight_style(theme.list_highlight());
+
+    let inner_list_area = Rect {
+        x: list_area.x + 1,
+        y: list_area.y,
+        width: list_area.width.saturating_sub(2),
+        height: list_area.height.saturating_sub(1),
+    };
+
+    f.render_stateful_widget(list, inner_list_area, &mut app.interface_list_state);
+}
+
+pub(super) fn draw_interface_detail(f: &mut Frame, area: Rect, app: &mut AppState) {
+    let theme = app.theme;
+    // Get the selected interface name
+    let interface_name = match &app.selected_interface_name {
+        Some(name) => name.clone(),
+        None => {
+            // No interface selected, show error
+            let error = Paragraph::new("No interface selected")
+                .block(Block::default().borders(Borders::ALL).title("Error"));
+            f.render_widget(error, area);
+            return;
+        }
+    };
+
+    // Show ALL processes on this interface, regardless of the interface filter
+    let filtered_processes = app.interface_detail_processes();
+
+    // Rates are shown as a share of the link speed when the interface reports one
+    let interface = app
+        .interface_list
+        .iter()
+        .find(|iface| iface.name == interface_name);
+    let link_share = |rate: u64| interface.and_then(|iface| iface.link_utilization(rate));
+    let show_link_share = interface.is_some_and(|iface| iface.speed_mbps.is_some());
+
+    let items: Vec<ListItem> = filtered_processes
+        .iter()
+        .map(|proc| {
+            // Get interface-specific stats, rates for the current traffic view
+            let iface_stats = proc.interface_stats.get(&interface_name);
+
+            let (dl_rate, ul_rate, dl_total, ul_total) = if let Some(stats) = iface_stats {
+                let (dl_rate, ul_rate) = app.get_interface_rates(stats);
+                (dl_rate, ul_rate, stats.total_download, stats.total_upload)
+            } else {
+                (0, 0, 0, 0)
+            };
+
+            // Status indicator
+            let status_indicator = if proc.is_throttled() && proc.throttle_sandboxed {
+                "📦"
+            } else if proc.is_throttled() {
+                "⚡"
+            } else if proc.is_terminated {
+                "💀"
+            } else {
+                " "
+            };
+
+            let terminated_color = theme.secondary;
+            let name_color = if proc.is_terminated {
+                terminated_color
+            } else {
+                theme.text
+            };
+            let dl_rate_color = if proc.is_terminated {
+                terminated_color
+            } else {
+                theme.download
+            };
+            let ul_rate_color = if proc.is_terminated {
+                terminated_color
+            } else {
+                theme.upload
+            };
+            let dl_total_color = if proc.is_terminated {
+                terminated_color
+            } else {
+                theme.download_total
+            };
+            let ul_total_color = if proc.is_terminated {
+                terminated_color
+            } else {
+                theme.upload_total
+            };
+            let status_color = if proc.is_terminated {
+                terminated_color
+            } else {
+                theme.error
+            };
+
+            let mut spans = vec![
+                Span::raw("  "),
+                Span::raw(format!("{:7} ", proc.pid)),
+                Span::styled(
+                    format!(
+                        "{:20} ",
+                        if proc.name.len() > 20 {
+                            format!("{}...", &proc.name[..17])
+                        } else {
+                            proc.name.clone()
+                        }
+                    ),
+                    Style::default().fg(name_color),
+                ),
+                Span::styled(
+                    format!("↓{:>10} ", format::format_rate(dl_rate)),
+                    Style::default().fg(dl_rate_color),
+                ),
+                Span::styled(
+                    format!("↑{:>10} ", format::format_rate(ul_rate)),
+                    Style::default().fg(ul_rate_color),
+                ),
+            ];
+            if show_link_share {
+                // Bar and percentage of the link speed, turning warm as the link saturates
+                for (rate, color) in [(dl_rate, dl_rate_color), (ul_rate, ul_rate_color)] {
+                    let percent = link_share(rate).unwrap_or(0.0);
+                    let bar_color = if proc.is_terminated || percent < 50.0 {
+                        color
+                    } else if percent < 90.0 {
+                        theme.highlight
+                    } else {
+                        theme.error
+                    };
+                    spans.push(Span::styled(
+                        saturation_bar(percent, SATURATION_BAR_WIDTH),
+                        Style::default().fg(bar_color),
+                    ));
+                    spans.push(Span::styled(
+                        format!(" {:>5.1}% ", percent.min(999.9)),
+                        Style::default().fg(color),
+                    ));
+                }
+            }
+            spans.extend([
+                Span::styled(
+                    format!("{:>10} ", format::format_bytes(dl_total)),
+                    Style::default().fg(dl_total_color),
+                ),
+                Span::styled(
+                    format!("{:>10} ", format::format_bytes(ul_total)),
+                    Style::default().fg(ul_total_color),
+                ),
+                Span::styled(
+                    status_indicator,
+                    Style::default()
+                        .fg(status_color)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ]);
+            let content = Line::from(spans);
+
+            ListItem::new(content)
+        })
+        .collect();
+
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let mut header_spans = vec![
+        Span::styled("PID     ", bold),
+        Span::styled("Process              ", bold),
+        Span::styled("DL Rate     ", bold),
+        Span::styled("UL Rate     ", bold),
+    ];
+    if show_link_share {
+        header_spans.push(Span::styled("DL Link      UL Link      ", bold));
+    }
+    header_spans.extend([
+        Span::styled("Total DL   ", bold),
+        Span::styled("Total UL   ", bold),
+        Span::styled("Status", bold),
+    ]);
+    let header = Line::from(header_spans);
+
+    // Split the area: link stats, column header, then the process list
+    let stats_area = Rect {
+        x: area.x + 1,
+        y: area.y + 1,
+        width: area.width.saturating_sub(2),
+        height: 1,
+    };
+
+    let header_area = Rect {
+        x: area.x + 3,
+        y: area.y + 2,
+        width: area.width.saturating_sub(4),
+        height: 1,
+    };
+
+    let list_area = Rect {
+        x: area.x,
+        y: area.y + 3,
+        width: area.width,
+        height: area.height.saturating_sub(3),
+    };
+
+    // Render border and title
+    let title = format!(
+        "Interface: {} [t] Throttle  [r] Remove  [Enter] Details  [Esc] Back",
+        interface_name
+    );
+    let border = Block::default().borders(Borders::ALL).title(title);
+    f.render_widget(border, area);
+
+    // Render link stats and header
+    if let Some(iface) = interface {
+        let increases = app.interface_error_increases.get(&interface_name);
+        f.render_widget(
+            Paragraph::new(link_stats_line(iface, increases, &theme)),
+            stats_area,
+        );
+    }
+    f.render_widget(Paragraph::new(header), header_area);
+
+    // Render list
+    let list = List::new(items).highlight_style(theme.list_highlight());
+    let inner_list_area = Rect {
+        x: list_area.x + 1,
+        y: list_area.y,
+        width: list_area.width.saturating_sub(2),
+        height: list_area.height.saturating_sub(1),
+    };
+
+    f.render_stateful_widget(list, inner_list_area, &mut app.interface_detail_state);
+}
+
+/// Width in cells of the link saturation bars in the interface detail view
+const SATURATION_BAR_WIDTH: usize = 5;
+
+/// Bar for a 0-100% value in eighth-cell steps, padded to `width` cells
+pub(super) fn saturation_bar(percent: f64, width: usize) -> String {
+    const PARTIAL: [&str; 8] = ["", "▏", "▎", "▍", "▌", "▋", "▊", "▉"];
+    let eighths = (percent.clamp(0.0, 100.0) / 100.0 * (width * 8) as f64).round() as usize;
+    let bar = format!("{}{}", "█".repeat(eighths / 8), PARTIAL[eighths % 8]);
+    pad_to_width(&bar, width)
+}
+
+/// Link state, speed, MTU, driver and error counters for the interface detail view,
+/// with counters that grew since the last update in the error color
+fn link_stats_line(
+    iface: &InterfaceInfo,
+    increases: Option<&InterfaceErrors>,
+    theme: &Theme,
+) -> Line<'static> {
+    const NA: &str = "n/a";
+    let label = Style::default().fg(theme.muted);
+    let value = Style::default().fg(theme.text);
+    let state = iface
+        .operstate
+        .clone()
+        .unwrap_or_else(|| if iface.is_up { "up" } else { "down" }.to_string());
+    let state_color = if iface.is_up {
+        theme.success
+    } else {
+        theme.warning
+    };
+
+    let mut spans = vec![
+        Span::styled(" State ", label),
+        Span::styled(state, Style::default().fg(state_color)),
+        Span::styled("  Speed ", label),
+        Span::styled(iface.speed_label().unwrap_or_else(|| NA.to_string()), value),
+        Span::styled("  MTU ", label),
+        Span::styled(
+            iface
+                .mtu
+                .map(|mtu| mtu.to_string())
+                .unwrap_or_else(|| NA.to_string()),
+            value,
+        ),
+        Span::styled("  Driver ", label),
+        Span::styled(
+            iface.driver.clone().unwrap_or_else(|| NA.to_string()),
+            value,
+        ),
+    ];
+
+    let increases = increases.copied().unwrap_or_default();
+    let counters = iface.errors.map(|errors| {
+        [
+            ("  RX err ", errors.rx_errors, increases.rx_errors),
+            (" drop ", errors.rx_dropped, increases.rx_dropped),
+            ("  TX err ", errors.tx_errors, increases.tx_errors),
+            (" drop ", errors.tx_dropped, increases.tx_dropped),
+        ]
+    });
+    match counters {
+        Some(counters) => {
+            for (name, total, increase) in counters {
+                let style = if increase > 0 {
+                    Style::default()
+                        .fg(theme.error)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    value
+                };
+                spans.push(Span::styled(name, label));
+                spans.push(Span::styled(total.to_string(), style));
+            }
+        }
+        None => {
+            spans.push(Span::styled("  Errors ", label));
+            spans.push(Span::styled(NA, value));
+        }
+    }
+    Line::from(spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_saturation_bar() {
+        assert_eq!(saturation_bar(0.0, 5), "     ");
+        assert_eq!(saturation_bar(50.0, 5), "██▌  ");
+        assert_eq!(saturation_bar(100.0, 5), "█████");
+        // Bursts over the link speed don't overflow the column
+        assert_eq!(saturation_bar(250.0, 5), "█████");
+    }
+}
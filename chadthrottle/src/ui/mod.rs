@@ -0,0 +1,235 @@
+// Terminal UI
+//
+// `state` holds everything the views draw from; the other modules each draw one
+// part of the screen. `draw_ui_with_backend_info` lays out a frame and draws the
+// current view with its overlays on top.
+
+mod detail;
+mod graph;
+mod interfaces;
+mod modals;
+mod process_list;
+mod state;
+mod status_bar;
+
+pub use state::{
+    AppState, BackendCompatibilityAction, BackendCompatibilityDialog, BackendGroup,
+    BackendSelectorItem, ClickableRegion, ClickableRegionType, ControlPathWarning, HIGH_UDP_SHARE,
+    ProcessDetailTab, StandingRulesModal, StartupSummary, ThrottleDialog, ThrottleField,
+    TrafficViewMode, ViewMode,
+};
+
+use detail::draw_process_detail;
+use graph::draw_bandwidth_graph;
+use interfaces::draw_interface_detail;
+use modals::{
+    draw_backend_compatibility_dialog, draw_backend_info, draw_control_path_warning,
+    draw_help_overlay, draw_interface_modal, draw_log_viewer, draw_standing_rules,
+    draw_startup_summary, draw_throttle_command, draw_throttle_dialog,
+};
+use process_list::draw_process_list;
+use status_bar::draw_status_bar;
+
+use crate::backends::throttle::BackendInfo;
+use crate::theme::Theme;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+};
+use unicode_width::UnicodeWidthStr;
+
+pub fn draw_ui(f: &mut Frame, app: &mut AppState) {
+    draw_ui_with_backend_info(f, app, None);
+}
+
+pub fn draw_ui_with_backend_info(
+    f: &mut Frame,
+    app: &mut AppState,
+    backend_info: Option<&BackendInfo>,
+) {
+    // Clear clickable regions from previous frame
+    app.clickable_regions.clear();
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Header
+            Constraint::Min(10),   // Main content area
+            Constraint::Length(5), // Status bar (allows wrapping to 2-3 lines)
+        ])
+        .split(f.area());
+
+    // Header (hide in ProcessDetail view to save space)
+    if app.view_mode != ViewMode::ProcessDetail {
+        draw_header(f, chunks[0], &app.theme, app.terminated_count());
+    }
+
+    // Main content area - render based on view mode
+    match app.view_mode {
+        ViewMode::ProcessView => {
+            draw_process_list(f, chunks[1], app);
+        }
+        ViewMode::InterfaceList => {
+            // Draw process list in background so we can see live updates
+            draw_process_list(f, chunks[1], app);
+            // Draw interface modal overlay on top
+            draw_interface_modal(f, f.area(), app);
+        }
+        ViewMode::InterfaceDetail => {
+            draw_interface_detail(f, chunks[1], app);
+        }
+        ViewMode::ProcessDetail => {
+            // Use full area including header space for detail view
+            let detail_area = Rect {
+                x: chunks[0].x,
+                y: chunks[0].y,
+                width: chunks[0].width,
+                height: chunks[0].height + chunks[1].height,
+            };
+            draw_process_detail(f, detail_area, app);
+        }
+    }
+
+    // Status bar
+    draw_status_bar(f, chunks[2], app);
+
+    // Help overlay
+    if app.show_help {
+        draw_help_overlay(f, f.area(), app);
+    }
+
+    if app.show_logs {
+        draw_log_viewer(f, f.area(), app);
+    }
+
+    // Throttle dialog
+    if app.show_throttle_dialog {
+        draw_throttle_dialog(f, f.area(), app);
+    }
+
+    // Bandwidth graph overlay
+    if app.show_graph {
+        draw_bandwidth_graph(f, f.area(), app);
+    }
+
+    // Backend info modal (highest priority, renders on top)
+    if app.show_backend_info {
+        if let Some(info) = backend_info {
+            draw_backend_info(f, f.area(), app, info);
+        }
+    }
+
+    // Backend compatibility dialog (highest priority - renders on top of everything)
+    if app.show_backend_compatibility_dialog {
+        if let Some(dialog) = app.backend_compatibility_dialog.clone() {
+            draw_backend_compatibility_dialog(f, f.area(), app, &dialog);
+        }
+    }
+
+    if let Some(summary) = &app.startup_summary {
+        draw_startup_summary(f, f.area(), &app.theme, summary);
+    }
+
+    if let Some(modal) = &app.standing_rules_modal {
+        draw_standing_rules(f, f.area(), &app.theme, modal);
+    }
+
+    if let Some(command) = &app.throttle_command {
+        draw_throttle_command(f, f.area(), &app.theme, command);
+    }
+
+    // Control path confirmation (asked before anything else about the throttle)
+    if let Some(warning) = &app.control_path_warning {
+        draw_control_path_warning(f, f.area(), &app.theme, warning);
+    }
+}
+
+fn draw_header(f: &mut Frame, area: Rect, theme: &Theme, terminated_count: usize) {
+    let mut spans = vec![Span::styled(
+        "🔥 ChadThrottle v0.1.0 - Network Monitor & Throttler 🔥",
+        Style::default()
+            .fg(theme.title)
+            .add_modifier(Modifier::BOLD),
+    )];
+    if terminated_count > 0 {
+        spans.push(Span::styled(
+            format!("   💀 {} terminated", terminated_count),
+            Style::default().fg(theme.secondary),
+        ));
+    }
+    let header = Paragraph::new(Line::from(spans)).block(Block::default().borders(Borders::ALL));
+
+    f.render_widget(header, area);
+}
+
+/// Pad text with spaces to `width` terminal columns
+fn pad_to_width(text: &str, width: usize) -> String {
+    format!("{}{}", text, " ".repeat(width.saturating_sub(text.width())))
+}
+
+fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+    let popup_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(r);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(popup_layout[1])[1]
+}
+
+/// Render into an off-screen buffer of `width` x `height` cells
+#[cfg(test)]
+pub(crate) fn render_to_buffer(
+    width: u16,
+    height: u16,
+    draw: impl FnOnce(&mut Frame),
+) -> ratatui::buffer::Buffer {
+    let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(width, height))
+        .expect("test terminal");
+    terminal.draw(draw).expect("draw");
+    terminal.backend().buffer().clone()
+}
+
+/// Rows of a rendered buffer as text, one char per terminal column
+/// (the cell after a wide character is skipped so columns line up with widths)
+#[cfg(test)]
+fn buffer_lines(buffer: &ratatui::buffer::Buffer) -> Vec<String> {
+    let area = buffer.area;
+    (area.top()..area.bottom())
+        .map(|y| {
+            let mut line = String::new();
+            let mut x = area.left();
+            while x < area.right() {
+                let symbol = buffer[(x, y)].symbol();
+                line.push_str(symbol);
+                x += symbol.width().max(1) as u16;
+            }
+            line
+        })
+        .collect()
+}
+
+/// Buffer position of the first cell of `text`
+#[cfg(test)]
+fn find_in_buffer(buffer: &ratatui::buffer::Buffer, text: &str) -> Option<(u16, u16)> {
+    buffer_lines(buffer)
+        .iter()
+        .enumerate()
+        .find_map(|(y, line)| {
+            let x = line[..line.find(text)?].width();
+            Some((buffer.area.x + x as u16, buffer.area.y + y as u16))
+        })
+}
@@ -0,0 +1,851 @@
+// Backend info modal: available backends, selection and active throttles
+
+use crate::backends::throttle::BackendInfo;
+use crate::format;
+use crate::ui::centered_rect;
+use crate::ui::{
+    AppState, BackendGroup, BackendSelectorItem, ClickableRegion, ClickableRegionType,
+};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+use std::collections::HashMap;
+
+pub(crate) fn draw_backend_info(
+    f: &mut Frame,
+    area: Rect,
+    app: &mut AppState,
+    backend_info: &BackendInfo,
+) {
+    let theme = app.theme;
+    let mut text = vec![Line::from("")];
+
+    text.push(Line::from(Span::styled(
+        "ChadThrottle - Backends",
+        Style::default()
+            .fg(theme.title)
+            .add_modifier(Modifier::BOLD),
+    )));
+    text.push(Line::from(""));
+
+    // Show currently active monitoring backend
+    if let Some(monitoring_backend) = &backend_info.active_monitoring {
+        text.push(Line::from(vec![
+            Span::styled(
+                "📡 Monitoring: ",
+                Style::default()
+                    .fg(theme.highlight)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                monitoring_backend,
+                Style::default()
+                    .fg(theme.title)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                if monitoring_backend == "pnet" {
+                    " (packet capture)"
+                } else {
+                    " (polling)"
+                },
+                Style::default().fg(theme.muted),
+            ),
+        ]));
+    }
+
+    if let Some(socket_mapper) = &backend_info.active_socket_mapper {
+        text.push(Line::from(vec![
+            Span::styled(
+                "🔌 Socket Mapper: ",
+                Style::default()
+                    .fg(theme.highlight)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                socket_mapper,
+                Style::default()
+                    .fg(theme.title)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" (PID mapping)", Style::default().fg(theme.muted)),
+        ]));
+    }
+
+    if let Some(cache) = &backend_info.socket_cache
+        && let Some(hit_rate) = cache.hit_rate()
+    {
+        text.push(Line::from(vec![
+            Span::styled("   Socket cache: ", Style::default().fg(theme.muted)),
+            Span::styled(
+                format!("{:.1}% hits", hit_rate * 100.0),
+                Style::default().fg(theme.text),
+            ),
+            Span::styled(
+                format!(
+                    " ({} hits, {} misses, {} fd scans)",
+                    cache.hits, cache.misses, cache.fd_scans
+                ),
+                Style::default().fg(theme.muted),
+            ),
+        ]));
+    }
+
+    text.push(Line::from(""));
+
+    // Get backend stats from backend_info
+    let backend_stats = &backend_info.backend_stats;
+
+    // Track line numbers for auto-scroll and click handling
+    let mut current_line = text.len();
+    let mut selected_line: Option<usize> = None;
+    let mut line_to_item: HashMap<usize, usize> = HashMap::new();
+
+    // Render all items (group headers and backends) with radio buttons
+    for (index, item) in app.backend_items.iter().enumerate() {
+        match item {
+            BackendSelectorItem::GroupHeader(group) => {
+                // Add spacing before groups (except first)
+                if index > 0 {
+                    text.push(Line::from(""));
+                    current_line += 1;
+                }
+
+                let header = match group {
+                    BackendGroup::Monitor => "Monitor Backends:",
+                    BackendGroup::SocketMapper => "Socket Mapper Backends:",
+                    BackendGroup::Upload => "Upload Backends:",
+                    BackendGroup::Download => "Download Backends:",
+                };
+                text.push(Line::from(Span::styled(
+                    header,
+                    Style::default()
+                        .fg(theme.highlight)
+                        .add_modifier(Modifier::BOLD),
+                )));
+                current_line += 1;
+            }
+            BackendSelectorItem::Backend {
+                name,
+                group,
+                priority,
+                available,
+                is_current_default,
+            } => {
+                let is_selected = index == app.backend_selected_index;
+
+                // Map this line to the backend item index for click handling
+                line_to_item.insert(current_line, index);
+
+                // Track this line if it's selected
+                if is_selected {
+                    selected_line = Some(current_line);
+                }
+
+                // Radio button shows active backend (not pending, since Space applies immediately)
+                let is_active = *is_current_default;
+                let radio = if is_active { "◉" } else { "○" };
+                let radio_style = if is_active {
+                    Style::default()
+                        .fg(theme.title)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(theme.muted)
+                };
+
+                // Status indicator
+                let (status_symbol, status_color) = if *is_current_default {
+                    ("⭐", theme.highlight)
+                } else if *available {
+                    ("✅", theme.success)
+                } else {
+                    ("❌", theme.error)
+                };
+
+                // Name style
+                let name_style = if is_selected {
+                    theme.selected()
+                } else if !available {
+                    Style::default().fg(theme.secondary)
+                } else {
+                    Style::default().fg(theme.text)
+                };
+
+                let priority_str = format!("{:?}", priority);
+                let throttle_count = backend_stats.get(name).copied().unwrap_or(0);
+                let attach_method = match group {
+                    BackendGroup::Upload => backend_info.upload_attach_methods.get(name),
+                    BackendGroup::Download => backend_info.download_attach_methods.get(name),
+                    BackendGroup::Monitor | BackendGroup::SocketMapper => None,
+                };
+                let throttle_info = throttle_info(throttle_count, attach_method.copied());
+
+                let mut line_spans = vec![
+                    Span::raw("  "),
+                    Span::styled(radio, radio_style),
+                    Span::raw(" "),
+                    Span::styled(format!("{:18}", name), name_style),
+                    Span::styled(
+                        format!(" [{:8}]", priority_str),
+                        Style::default().fg(theme.title),
+                    ),
+                    Span::raw("  "),
+                    Span::styled(status_symbol, Style::default().fg(status_color)),
+                ];
+
+                // Add status text
+                if *is_current_default {
+                    line_spans.push(Span::styled(
+                        " ACTIVE",
+                        Style::default().fg(theme.highlight),
+                    ));
+                } else if !available {
+                    line_spans.push(Span::styled(
+                        " (unavailable)",
+                        Style::default().fg(theme.secondary),
+                    ));
+                }
+
+                // Add throttle info if available
+                if !throttle_info.is_empty() {
+                    line_spans.push(Span::styled(
+                        throttle_info,
+                        Style::default().fg(theme.secondary),
+                    ));
+                }
+
+                text.push(Line::from(line_spans));
+                current_line += 1;
+            }
+        }
+    }
+
+    text.push(Line::from(""));
+
+    // Cgroup Backends Section (how throttled processes are isolated: v1 net_cls or v2)
+    text.push(Line::from(Span::styled(
+        "Cgroup Backends:",
+        Style::default()
+            .fg(theme.title)
+            .add_modifier(Modifier::BOLD),
+    )));
+
+    let cgroup = &backend_info.cgroup;
+    if cgroup.backends.is_empty() {
+        text.push(Line::from(Span::styled(
+            "  ⚪ (none compiled)",
+            Style::default().fg(theme.secondary),
+        )));
+    } else {
+        for (backend_type, available, reason) in &cgroup.backends {
+            let is_selected = cgroup.selected == Some(*backend_type);
+            let (symbol, color, status) = if is_selected {
+                ("⭐", theme.highlight, "[SELECTED]")
+            } else if *available {
+                ("✅", theme.success, "Available")
+            } else {
+                ("❌", theme.error, "Unavailable")
+            };
+
+            let mut spans = vec![
+                Span::raw("  "),
+                Span::styled(symbol, Style::default().fg(color)),
+                Span::raw(" "),
+                Span::styled(
+                    format!("{:18}", backend_type.to_string()),
+                    if is_selected {
+                        Style::default()
+                            .fg(theme.highlight)
+                            .add_modifier(Modifier::BOLD)
+                    } else {
+                        Style::default().fg(theme.text)
+                    },
+                ),
+                Span::raw(" "),
+                Span::styled(
+                    format!("{:12}", status),
+                    Style::default().fg(if is_selected {
+                        theme.highlight
+                    } else {
+                        theme.secondary
+                    }),
+                ),
+            ];
+            if !available {
+                spans.push(Span::styled(
+                    reason.clone(),
+                    Style::default().fg(theme.secondary),
+                ));
+            }
+            text.push(Line::from(spans));
+        }
+    }
+
+    text.push(Line::from(vec![
+        Span::raw("  Mounted at:           "),
+        Span::styled(
+            cgroup
+                .mount_path
+                .as_ref()
+                .map(|path| path.display().to_string())
+                .unwrap_or_else(|| "(no cgroup hierarchy found)".to_string()),
+            Style::default().fg(theme.text),
+        ),
+    ]));
+    if cgroup.selected.is_none() && !cgroup.backends.is_empty() {
+        text.push(Line::from(Span::styled(
+            "    No cgroup backend is usable: tc_htb, ifb_tc and nftables can't isolate processes.",
+            Style::default().fg(theme.error),
+        )));
+    }
+
+    text.push(Line::from(""));
+
+    // Configuration Section
+    text.push(Line::from(Span::styled(
+        "Configuration:",
+        Style::default()
+            .fg(theme.title)
+            .add_modifier(Modifier::BOLD),
+    )));
+
+    let preferred_upload_display = backend_info
+        .preferred_upload
+        .as_ref()
+        .map(|s| s.as_str())
+        .unwrap_or("Auto");
+    let active_upload_display = backend_info
+        .active_upload
+        .as_ref()
+        .map(|s| format!(" ({} selected)", s))
+        .unwrap_or_else(|| " (none available)".to_string());
+
+    text.push(Line::from(vec![
+        Span::raw("  Preferred Upload:     "),
+        Span::styled(
+            format!("{}{}", preferred_upload_display, active_upload_display),
+            Style::default().fg(theme.text),
+        ),
+    ]));
+
+    let preferred_download_display = backend_info
+        .preferred_download
+        .as_ref()
+        .map(|s| s.as_str())
+        .unwrap_or("Auto");
+    let active_download_display = backend_info
+        .active_download
+        .as_ref()
+        .map(|s| format!(" ({} selected)", s))
+        .unwrap_or_else(|| " (none available)".to_string());
+
+    text.push(Line::from(vec![
+        Span::raw("  Preferred Download:   "),
+        Span::styled(
+            format!("{}{}", preferred_download_display, active_download_display),
+            Style::default().fg(theme.text),
+        ),
+    ]));
+
+    let preferred_socket_mapper_display = backend_info
+        .preferred_socket_mapper
+        .as_ref()
+        .map(|s| s.as_str())
+        .unwrap_or("Auto");
+    let active_socket_mapper_display = backend_info
+        .active_socket_mapper
+        .as_ref()
+        .map(|s| format!(" ({} selected)", s))
+        .unwrap_or_else(|| " (none available)".to_string());
+
+    text.push(Line::from(vec![
+        Span::raw("  Preferred Socket Map: "),
+        Span::styled(
+            format!(
+                "{}{}",
+                preferred_socket_mapper_display, active_socket_mapper_display
+            ),
+            Style::default().fg(theme.text),
+        ),
+    ]));
+
+    text.push(Line::from(vec![
+        Span::raw("  Config File:          "),
+        Span::styled(
+            "~/.config/chadthrottle/throttles.json",
+            Style::default().fg(theme.secondary),
+        ),
+    ]));
+
+    text.push(Line::from(""));
+
+    // Capabilities Section (only if we have active backends)
+    if backend_info.upload_capabilities.is_some() || backend_info.download_capabilities.is_some() {
+        text.push(Line::from(Span::styled(
+            "Capabilities (Active Backends):",
+            Style::default()
+                .fg(theme.title)
+                .add_modifier(Modifier::BOLD),
+        )));
+
+        // Get capabilities - prefer upload, fall back to download
+        let caps = backend_info
+            .upload_capabilities
+            .as_ref()
+            .or(backend_info.download_capabilities.as_ref());
+
+        if let Some(capabilities) = caps {
+            text.push(Line::from(vec![
+                Span::raw("  IPv4:              "),
+                Span::styled(
+                    if capabilities.ipv4_support {
+                        "✅"
+                    } else {
+                        "❌"
+                    },
+                    Style::default().fg(if capabilities.ipv4_support {
+                        theme.success
+                    } else {
+                        theme.error
+                    }),
+                ),
+                Span::raw("   IPv6:            "),
+                Span::styled(
+                    if capabilities.ipv6_support {
+                        "✅"
+                    } else {
+                        "❌"
+                    },
+                    Style::default().fg(if capabilities.ipv6_support {
+                        theme.success
+                    } else {
+                        theme.error
+                    }),
+                ),
+            ]));
+
+            text.push(Line::from(vec![
+                Span::raw("  TCP:               "),
+                Span::styled(
+                    if capabilities.tcp_support {
+                        "✅"
+                    } else {
+                        "❌"
+                    },
+                    Style::default().fg(if capabilities.tcp_support {
+                        theme.success
+                    } else {
+                        theme.error
+                    }),
+                ),
+                Span::raw("   UDP:             "),
+                Span::styled(
+                    if capabilities.udp_support {
+                        "✅"
+                    } else {
+                        "❌"
+                    },
+                    Style::default().fg(if capabilities.udp_support {
+                        theme.success
+                    } else {
+                        theme.error
+                    }),
+                ),
+            ]));
+
+            text.push(Line::from(vec![
+                Span::raw("  Per-Process:       "),
+                Span::styled(
+                    if capabilities.per_process {
+                        "✅"
+                    } else {
+                        "❌"
+                    },
+                    Style::default().fg(if capabilities.per_process {
+                        theme.success
+                    } else {
+                        theme.error
+                    }),
+                ),
+                Span::raw("   Per-Connection:  "),
+                Span::styled(
+                    if capabilities.per_connection {
+                        "✅"
+                    } else {
+                        "❌"
+                    },
+                    Style::default().fg(if capabilities.per_connection {
+                        theme.success
+                    } else {
+                        theme.error
+                    }),
+                ),
+            ]));
+
+            // Traffic-type filtering must be supported by both active backends
+            let traffic = backend_info.traffic_support.unwrap_or_default();
+
+            text.push(Line::from(vec![
+                Span::raw("  Internet Filter:   "),
+                Span::styled(
+                    if traffic.supports_internet_filter {
+                        "✅"
+                    } else {
+                        "❌"
+                    },
+                    Style::default().fg(if traffic.supports_internet_filter {
+                        theme.success
+                    } else {
+                        theme.error
+                    }),
+                ),
+                Span::raw("   Local Filter:    "),
+                Span::styled(
+                    if traffic.supports_local_filter {
+                        "✅"
+                    } else {
+                        "❌"
+                    },
+                    Style::default().fg(if traffic.supports_local_filter {
+                        theme.success
+                    } else {
+                        theme.error
+                    }),
+                ),
+            ]));
+        }
+
+        text.push(Line::from(""));
+    }
+
+    // Shaping set up by something else fights with (or doubles) our limits
+    if !backend_info.external_shaping.is_empty() {
+        text.push(Line::from(Span::styled(
+            "External Shaping:",
+            Style::default()
+                .fg(theme.error)
+                .add_modifier(Modifier::BOLD),
+        )));
+        for shaping in &backend_info.external_shaping {
+            text.push(Line::from(vec![
+                Span::styled("  ⚠ ", Style::default().fg(theme.error)),
+                Span::styled(shaping.to_string(), Style::default().fg(theme.text)),
+            ]));
+        }
+        text.push(Line::from(Span::styled(
+            "    Throttles on these may not take effect. Silence with --ignore-external",
+            Style::default().fg(theme.secondary),
+        )));
+        text.push(Line::from(Span::styled(
+            "    or \"ignore_external_shaping\": true in the config.",
+            Style::default().fg(theme.secondary),
+        )));
+        text.push(Line::from(""));
+    }
+
+    // Throttle capacity of loaded backends (eBPF maps are fixed-size, tc/nftables soft)
+    if !backend_info.upload_capacity.is_empty() || !backend_info.download_capacity.is_empty() {
+        text.push(Line::from(Span::styled(
+            "Throttle Capacity:",
+            Style::default()
+                .fg(theme.title)
+                .add_modifier(Modifier::BOLD),
+        )));
+
+        for (arrow, capacity) in [
+            ("↑", &backend_info.upload_capacity),
+            ("↓", &backend_info.download_capacity),
+        ] {
+            let mut capacity: Vec<_> = capacity.iter().collect();
+            capacity.sort();
+            for (name, (active, max)) in capacity {
+                let color = if active >= max {
+                    theme.error
+                } else {
+                    theme.highlight
+                };
+                text.push(Line::from(vec![
+                    Span::raw(format!("  {} ", arrow)),
+                    Span::styled(format!("{}: ", name), Style::default().fg(theme.text)),
+                    Span::styled(format!("{}/{}", active, max), Style::default().fg(color)),
+                ]));
+            }
+        }
+        text.push(Line::from(""));
+    }
+
+    // Interface ceilings (two-level hierarchy: shared ceiling over per-process classes)
+    if !backend_info.upload_ceilings.is_empty() {
+        text.push(Line::from(Span::styled(
+            "Interface Ceilings:",
+            Style::default()
+                .fg(theme.title)
+                .add_modifier(Modifier::BOLD),
+        )));
+
+        let mut ceilings: Vec<_> = backend_info.upload_ceilings.iter().collect();
+        ceilings.sort();
+        for (name, (interface, ceiling)) in ceilings {
+            text.push(Line::from(vec![
+                Span::raw("  ↑ "),
+                Span::styled(format!("{:18} ", name), Style::default().fg(theme.text)),
+                Span::styled(
+                    format!("{}: {}", interface, format::format_rate(*ceiling)),
+                    Style::default().fg(theme.highlight),
+                ),
+            ]));
+        }
+        text.push(Line::from(Span::styled(
+            "    Each throttled process is guaranteed its own limit and may borrow",
+            Style::default().fg(theme.secondary),
+        )));
+        text.push(Line::from(Span::styled(
+            "    unused bandwidth up to the ceiling; together they never exceed it.",
+            Style::default().fg(theme.secondary),
+        )));
+        text.push(Line::from(""));
+    }
+
+    // Capability Matrix Section (only if we have throttle backends)
+    if !backend_info.available_upload.is_empty() || !backend_info.available_download.is_empty() {
+        text.push(Line::from(Span::styled(
+            "Capability Matrix:",
+            Style::default()
+                .fg(theme.title)
+                .add_modifier(Modifier::BOLD),
+        )));
+        text.push(Line::from(Span::styled(
+            format!(
+                "    {:18} {:6}{:6}{:6}{:6}{:6}{:6}{:6}{:6}",
+                "Backend", "IPv4", "IPv6", "TCP", "UDP", "Proc", "Conn", "Inet", "Local"
+            ),
+            Style::default().fg(theme.secondary),
+        )));
+
+        let rows = backend_info
+            .available_upload
+            .iter()
+            .map(|(name, _, _)| ("↑", name, &backend_info.upload_capability_matrix))
+            .chain(
+                backend_info
+                    .available_download
+                    .iter()
+                    .map(|(name, _, _)| ("↓", name, &backend_info.download_capability_matrix)),
+            );
+
+        for (direction, name, matrix) in rows {
+            let mut line_spans = vec![
+                Span::raw(format!("  {} ", direction)),
+                Span::styled(format!("{:18} ", name), Style::default().fg(theme.text)),
+            ];
+
+            if let Some(capabilities) = matrix.get(name) {
+                for supported in [
+                    capabilities.ipv4_support,
+                    capabilities.ipv6_support,
+                    capabilities.tcp_support,
+                    capabilities.udp_support,
+                    capabilities.per_process,
+                    capabilities.per_connection,
+                    capabilities.traffic_types.supports_internet_filter,
+                    capabilities.traffic_types.supports_local_filter,
+                ] {
+                    line_spans.push(Span::styled(
+                        if supported { "✅    " } else { "❌    " },
+                        Style::default().fg(if supported {
+                            theme.success
+                        } else {
+                            theme.error
+                        }),
+                    ));
+                }
+            } else {
+                line_spans.push(Span::styled(
+                    "(not loaded - capabilities known after first use)",
+                    Style::default().fg(theme.muted),
+                ));
+            }
+
+            text.push(Line::from(line_spans));
+        }
+
+        text.push(Line::from(""));
+    }
+
+    // Instructions
+    text.push(Line::from(Span::styled(
+        "[↑↓] Navigate  [Space] Apply  [y] Copy text  [Enter/b/Esc] Close",
+        Style::default().fg(theme.muted),
+    )));
+
+    app.backend_info_text = plain_text(&text);
+
+    let backend_area = centered_rect(80, 80, area);
+
+    // Auto-scroll to keep selected backend visible
+    if let Some(line) = selected_line {
+        app.backend_info_scroll_offset =
+            AppState::scroll_to_line(app.backend_info_scroll_offset, line, backend_area.height);
+    }
+
+    // Clamp scroll offset to content bounds
+    let content_lines = text.len();
+    let clamped_scroll = AppState::clamp_scroll(
+        app.backend_info_scroll_offset,
+        content_lines,
+        backend_area.height,
+    );
+    app.backend_info_scroll_offset = clamped_scroll;
+
+    let backend_widget = Paragraph::new(text)
+        .style(Style::default().bg(theme.modal_bg).fg(theme.text))
+        .scroll((clamped_scroll as u16, 0))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Backends (↑↓ to scroll)")
+                .style(Style::default().fg(theme.title)),
+        );
+
+    f.render_widget(Clear, backend_area);
+    f.render_widget(backend_widget, backend_area);
+
+    // Store clickable region for mouse selection
+    app.clickable_regions.push(ClickableRegion {
+        area: backend_area,
+        region_type: ClickableRegionType::BackendModal { line_to_item },
+    });
+}
+
+/// The modal's text without styling, one line per line (for copying it)
+fn plain_text(text: &[Line]) -> String {
+    text.iter()
+        .map(|line| {
+            line.spans
+                .iter()
+                .map(|span| span.content.as_ref())
+                .collect::<String>()
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// Active throttle count and, for eBPF, how its programs got attached,
+/// e.g. " (2 active, legacy fallback)"
+fn throttle_info(throttle_count: usize, attach_method: Option<&str>) -> String {
+    let mut parts = Vec::new();
+    if throttle_count > 0 {
+        parts.push(format!("{} active", throttle_count));
+    }
+    if let Some(method) = attach_method {
+        parts.push(method.to_string());
+    }
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", parts.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backends::BackendPriority;
+    use crate::ui::render_to_buffer;
+
+    #[test]
+    fn test_render_lists_each_backend_once() {
+        let mut backend_info = BackendInfo {
+            active_upload: Some("tc_htb".to_string()),
+            active_download: Some("ifb_tc".to_string()),
+            active_monitoring: Some("pnet".to_string()),
+            active_socket_mapper: Some("procfs".to_string()),
+            available_upload: vec![
+                ("tc_htb".to_string(), BackendPriority::Good, true),
+                ("ebpf".to_string(), BackendPriority::Best, false),
+            ],
+            available_download: vec![("ifb_tc".to_string(), BackendPriority::Good, true)],
+            available_monitors: vec![("pnet".to_string(), BackendPriority::Best, true)],
+            available_socket_mappers: vec![("procfs".to_string(), BackendPriority::Good, true)],
+            ..Default::default()
+        };
+        backend_info.backend_stats.insert("tc_htb".to_string(), 2);
+        let mut app = AppState::new();
+        app.build_backend_items(&backend_info);
+        render_to_buffer(120, 80, |f| {
+            draw_backend_info(f, f.area(), &mut app, &backend_info)
+        });
+
+        // Each group is listed once, as the selectable radio list
+        let expected = "\
+ChadThrottle - Backends
+
+📡 Monitoring: pnet (packet capture)
+🔌 Socket Mapper: procfs (PID mapping)
+
+Monitor Backends:
+  ◉ pnet               [Best    ]  ⭐ ACTIVE
+
+Socket Mapper Backends:
+  ◉ procfs             [Good    ]  ⭐ ACTIVE
+
+Upload Backends:
+  ◉ tc_htb             [Good    ]  ⭐ ACTIVE (2 active)
+  ○ ebpf               [Best    ]  ❌ (unavailable)
+
+Download Backends:
+  ◉ ifb_tc             [Good    ]  ⭐ ACTIVE
+
+Cgroup Backends:
+  ⚪ (none compiled)
+  Mounted at:           (no cgroup hierarchy found)
+
+Configuration:
+  Preferred Upload:     Auto (tc_htb selected)
+  Preferred Download:   Auto (ifb_tc selected)
+  Preferred Socket Map: Auto (procfs selected)
+  Config File:          ~/.config/chadthrottle/throttles.json
+
+Capability Matrix:
+    Backend            IPv4  IPv6  TCP   UDP   Proc  Conn  Inet  Local
+  ↑ tc_htb             (not loaded - capabilities known after first use)
+  ↑ ebpf               (not loaded - capabilities known after first use)
+  ↓ ifb_tc             (not loaded - capabilities known after first use)
+
+[↑↓] Navigate  [Space] Apply  [y] Copy text  [Enter/b/Esc] Close";
+        assert_eq!(app.backend_info_text, expected);
+    }
+
+    #[test]
+    fn test_render_shows_backend_capacity() {
+        let mut backend_info = BackendInfo {
+            active_upload: Some("ebpf".to_string()),
+            available_upload: vec![("ebpf".to_string(), BackendPriority::Best, true)],
+            ..Default::default()
+        };
+        backend_info
+            .upload_capacity
+            .insert("ebpf".to_string(), (12, 64));
+        backend_info
+            .download_capacity
+            .insert("nftables".to_string(), (3, 10_000));
+        let mut app = AppState::new();
+        app.build_backend_items(&backend_info);
+        render_to_buffer(120, 80, |f| {
+            draw_backend_info(f, f.area(), &mut app, &backend_info)
+        });
+
+        assert!(
+            app.backend_info_text
+                .contains("Throttle Capacity:\n  ↑ ebpf: 12/64\n  ↓ nftables: 3/10000\n")
+        );
+    }
+}
@@ -0,0 +1,206 @@
+// Backend compatibility dialog (traffic type, interfaces, IP version, policy, UDP)
+
+use crate::process::Policy;
+use crate::ui::centered_rect;
+use crate::ui::{AppState, BackendCompatibilityDialog};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+pub(crate) fn draw_backend_compatibility_dialog(
+    f: &mut Frame,
+    area: Rect,
+    app: &mut AppState,
+    dialog: &BackendCompatibilityDialog,
+) {
+    let theme = app.theme;
+    // Build option list
+    let mut options = vec!["Cancel - don't apply throttle".to_string()];
+
+    for backend in &dialog.compatible_backends {
+        options.push(format!("Switch to '{}' for this throttle only", backend));
+        options.push(format!("Switch to '{}' and make it default", backend));
+    }
+
+    options.push(if dialog.udp_share.is_some() {
+        format!(
+            "Apply with '{}' anyway (UDP stays unthrottled)",
+            dialog.current_backend
+        )
+    } else if dialog.policy.is_some() {
+        "Apply with the backend's default policy instead".to_string()
+    } else if dialog.family.is_some() {
+        "Apply to both IPv4 and IPv6 instead".to_string()
+    } else if dialog.interfaces.is_some() {
+        "Apply on all interfaces instead".to_string()
+    } else {
+        "Apply as 'All Traffic' instead".to_string()
+    });
+
+    let direction = if dialog.is_upload {
+        "upload"
+    } else {
+        "download"
+    };
+    let (problem, explanation) = if let Some(udp_share) = dialog.udp_share {
+        (
+            format!(
+                "{}% of this process's {} traffic is UDP, which {} backend '{}' does not throttle",
+                udp_share, direction, direction, dialog.current_backend
+            ),
+            if dialog.compatible_backends.is_empty() {
+                "No available backends throttle UDP; the throttle will under-deliver."
+            } else {
+                "UDP traffic (QUIC, games, VoIP) would pass through at full speed."
+            },
+        )
+    } else if let Some(policy) = dialog.policy {
+        (
+            format!(
+                "{} {} backend '{}' cannot {} traffic over the limit",
+                if dialog.compatible_backends.is_empty() {
+                    "No available"
+                } else {
+                    "Current"
+                },
+                direction,
+                dialog.current_backend,
+                policy
+            ),
+            match policy {
+                Policy::Shape => "Shaping queues excess packets; this backend can only drop them.",
+                Policy::Drop => {
+                    "Dropping discards excess packets; this backend can only queue them."
+                }
+            },
+        )
+    } else if let Some(family) = dialog.family {
+        (
+            format!(
+                "{} {} backend '{}' cannot restrict throttles to {}",
+                if dialog.compatible_backends.is_empty() {
+                    "No available"
+                } else {
+                    "Current"
+                },
+                direction,
+                dialog.current_backend,
+                family
+            ),
+            if dialog.compatible_backends.is_empty() {
+                "No backends on this system can tell IPv4 and IPv6 traffic apart."
+            } else {
+                "This backend throttles IPv4 and IPv6 traffic together."
+            },
+        )
+    } else if let Some(interfaces) = &dialog.interfaces {
+        (
+            format!(
+                "{} {} backend '{}' cannot restrict throttles to: {}",
+                if dialog.compatible_backends.is_empty() {
+                    "No available"
+                } else {
+                    "Current"
+                },
+                direction,
+                dialog.current_backend,
+                interfaces.join(", ")
+            ),
+            if dialog.compatible_backends.is_empty() {
+                "No backends on this system can throttle per interface for this process."
+            } else {
+                "This backend applies throttles independently of the network interface."
+            },
+        )
+    } else {
+        (
+            format!(
+                "{} {} backend '{}' does not support '{:?}' traffic filtering.",
+                if dialog.compatible_backends.is_empty() {
+                    "No available"
+                } else {
+                    "Current"
+                },
+                direction,
+                dialog.current_backend,
+                dialog.traffic_type
+            ),
+            if dialog.compatible_backends.is_empty() {
+                "No backends on this system support IP-based traffic filtering."
+            } else {
+                "Only 'All Traffic' throttling is supported by this backend."
+            },
+        )
+    };
+
+    // Build styled lines with radio buttons
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(problem),
+        Line::from(""),
+        Line::from(explanation),
+        Line::from(""),
+        Line::from(Span::styled(
+            "What would you like to do?",
+            Style::default().add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+
+    for (i, option) in options.iter().enumerate() {
+        let radio = if i == dialog.selected_action {
+            "●"
+        } else {
+            "○"
+        };
+        let style = if i == dialog.selected_action {
+            Style::default()
+                .fg(theme.highlight)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.text)
+        };
+
+        lines.push(Line::from(vec![
+            Span::raw("  "),
+            Span::styled(radio, style),
+            Span::raw(" "),
+            Span::styled(option, style),
+        ]));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "[Enter] Confirm  [↑↓] Navigate  [Esc/q] Cancel",
+        Style::default().fg(theme.muted),
+    )));
+
+    let dialog_area = centered_rect(80, 50, area);
+
+    // Clamp scroll offset to content bounds
+    let content_lines = lines.len();
+    let clamped_scroll = AppState::clamp_scroll(
+        app.backend_compat_scroll_offset,
+        content_lines,
+        dialog_area.height,
+    );
+    app.backend_compat_scroll_offset = clamped_scroll;
+
+    // Render paragraph
+    let paragraph = Paragraph::new(lines)
+        .scroll((clamped_scroll as u16, 0))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Backend Incompatibility (↑↓ to scroll)")
+                .style(Style::default().fg(theme.error)),
+        );
+
+    f.render_widget(Clear, dialog_area);
+    f.render_widget(paragraph, dialog_area);
+}
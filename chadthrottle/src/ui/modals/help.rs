@@ -0,0 +1,56 @@
+// Help overlay, generated from the key bindings
+
+use crate::ui::AppState;
+use crate::ui::centered_rect;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+};
+
+pub(crate) fn draw_help_overlay(f: &mut Frame, area: Rect, app: &mut AppState) {
+    let theme = app.theme;
+    // Auto-generate help text from centralized keybindings
+    let mut help_text = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            "ChadThrottle - Keyboard Shortcuts",
+            Style::default().add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+
+    // Get all keybindings and generate help lines
+    for binding in crate::keybindings::get_all_keybindings(&app.keymap) {
+        help_text.push(Line::from(format!(
+            "  {:12} - {}",
+            binding.key, binding.description
+        )));
+    }
+
+    help_text.push(Line::from(""));
+    help_text.push(Line::from("Use ↑↓ to scroll, any other key to close"));
+
+    let help_area = centered_rect(60, 50, area);
+
+    // Clamp scroll offset to content bounds
+    let content_lines = help_text.len();
+    let clamped_scroll =
+        AppState::clamp_scroll(app.help_scroll_offset, content_lines, help_area.height);
+    app.help_scroll_offset = clamped_scroll;
+
+    let help = Paragraph::new(help_text)
+        .style(Style::default().bg(theme.modal_bg).fg(theme.text))
+        .scroll((clamped_scroll as u16, 0))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Help")
+                .style(Style::default().fg(theme.title)),
+        );
+
+    f.render_widget(ratatui::widgets::Clear, help_area);
+    f.render_widget(help, help_area);
+}
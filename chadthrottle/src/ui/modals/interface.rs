@@ -0,0 +1,230 @@
+// Interface filter modal
+
+use crate::interface_filter;
+use crate::ui::centered_rect;
+use crate::ui::{AppState, ClickableRegion, ClickableRegionType};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+pub(crate) fn draw_interface_modal(f: &mut Frame, area: Rect, app: &mut AppState) {
+    let theme = app.theme;
+    let mut text = vec![Line::from("")];
+
+    // Title
+    text.push(Line::from(Span::styled(
+        "Network Interfaces - Filter Selection",
+        Style::default()
+            .fg(theme.title)
+            .add_modifier(Modifier::BOLD),
+    )));
+    text.push(Line::from(""));
+
+    // Show current filter state
+    let filter_state = match &app.active_interface_filters {
+        None => "Current: All interfaces (no filter)",
+        Some(filters) if filters.is_empty() => "Current: No interfaces (empty filter)",
+        Some(filters) => {
+            if filters.len() <= 3 {
+                &format!("Current: {}", filters.join(", "))
+            } else {
+                "Current: Multiple interfaces"
+            }
+        }
+    };
+    text.push(Line::from(Span::styled(
+        filter_state,
+        Style::default().fg(theme.highlight),
+    )));
+
+    // Patterns and exclusions aren't interfaces, so they get their own lines
+    let filters = app.active_interface_filters.as_deref().unwrap_or_default();
+    let patterns: Vec<&str> = filters
+        .iter()
+        .map(String::as_str)
+        .filter(|f| interface_filter::is_pattern(f))
+        .collect();
+    let exclusions: Vec<&str> = filters
+        .iter()
+        .filter_map(|f| interface_filter::exclusion(f))
+        .collect();
+    if !patterns.is_empty() {
+        text.push(Line::from(vec![
+            Span::styled("Patterns: ", Style::default().fg(theme.secondary)),
+            Span::styled(
+                patterns.join(", "),
+                Style::default()
+                    .fg(theme.highlight)
+                    .add_modifier(Modifier::ITALIC),
+            ),
+        ]));
+    }
+    if !exclusions.is_empty() {
+        text.push(Line::from(vec![
+            Span::styled("Excluded: ", Style::default().fg(theme.secondary)),
+            Span::styled(exclusions.join(", "), Style::default().fg(theme.muted)),
+        ]));
+    }
+    text.push(Line::from(""));
+
+    text.push(Line::from("Select interfaces to show (updates live):"));
+    text.push(Line::from(""));
+    let header_lines = text.len();
+
+    // List interfaces with checkboxes
+    for (index, iface) in app.interface_list.iter().enumerate() {
+        let is_cursor = Some(index) == app.selected_interface_index;
+        let is_filtered = app.is_interface_filtered(&iface.name);
+        // Shown through a pattern rather than by name
+        let via_pattern = app
+            .active_interface_filters
+            .as_deref()
+            .filter(|filters| is_filtered && !filters.contains(&iface.name))
+            .and_then(|filters| interface_filter::covering_pattern(filters, &iface.name));
+
+        let checkbox = if is_filtered { "[✓]" } else { "[ ]" };
+        let cursor = if is_cursor { "▶ " } else { "  " };
+
+        let checkbox_style = if is_filtered {
+            Style::default().fg(theme.success)
+        } else {
+            Style::default().fg(theme.muted)
+        };
+
+        let name_style = if is_cursor {
+            theme.selected()
+        } else {
+            Style::default().fg(theme.text)
+        };
+
+        // Calculate total count from unfiltered list (all processes using this interface)
+        let total_count = app
+            .unfiltered_process_list
+            .iter()
+            .filter(|p| p.interface_stats.contains_key(&iface.name))
+            .count();
+
+        // Calculate filtered count (how many are currently visible in the filtered view)
+        let filtered_count = app
+            .process_list
+            .iter()
+            .filter(|p| p.interface_stats.contains_key(&iface.name))
+            .count();
+
+        let (state, state_color) = if iface.is_up {
+            ("up", theme.success)
+        } else {
+            ("down", theme.error)
+        };
+        let speed = iface.speed_label().unwrap_or_else(|| "-".to_string());
+        let mtu = iface
+            .mtu
+            .map(|mtu| format!("MTU {}", mtu))
+            .unwrap_or_default();
+
+        text.push(Line::from(vec![
+            Span::raw(cursor),
+            Span::styled(checkbox, checkbox_style),
+            Span::raw(" "),
+            Span::styled(format!("{:12}", iface.name), name_style),
+            Span::styled(format!(" {:4}", state), Style::default().fg(state_color)),
+            Span::styled(
+                format!(" {:>9} {:9}", speed, mtu),
+                Style::default().fg(theme.secondary),
+            ),
+            Span::styled(
+                format!(" ({}/{} processes)", filtered_count, total_count),
+                Style::default().fg(theme.secondary),
+            ),
+            Span::styled(
+                via_pattern
+                    .map(|pattern| format!(" via {}", pattern))
+                    .unwrap_or_default(),
+                Style::default()
+                    .fg(theme.highlight)
+                    .add_modifier(Modifier::ITALIC),
+            ),
+        ]));
+    }
+
+    text.push(Line::from(""));
+    if let Some(input) = &app.interface_pattern_input {
+        text.push(Line::from(vec![
+            Span::styled("Pattern: ", Style::default().fg(theme.secondary)),
+            Span::styled(
+                format!("{}█", input),
+                Style::default()
+                    .fg(theme.highlight)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]));
+    } else {
+        text.push(Line::from(""));
+    }
+
+    // Instructions
+    if app.interface_pattern_input.is_some() {
+        text.push(Line::from(Span::styled(
+            "Type a pattern (* = anything, ? = one character), e.g. tun* or wg?",
+            Style::default().fg(theme.muted),
+        )));
+        text.push(Line::from(Span::styled(
+            "[Enter] Add (or remove if present)  [Esc] Cancel",
+            Style::default().fg(theme.muted),
+        )));
+    } else {
+        text.push(Line::from(Span::styled(
+            "[↑↓] Navigate  [Space] Toggle (applies live)  [A] Toggle All/None  [/] Pattern",
+            Style::default().fg(theme.muted),
+        )));
+        text.push(Line::from(Span::styled(
+            "[Enter] View details  [Esc/i] Close and return to process view",
+            Style::default().fg(theme.muted),
+        )));
+    }
+
+    let modal_area = centered_rect(70, 60, area);
+
+    // Auto-scroll to keep selected interface visible
+    if let Some(selected_idx) = app.selected_interface_index {
+        let selected_line = header_lines + selected_idx;
+        app.interface_modal_scroll_offset = AppState::scroll_to_line(
+            app.interface_modal_scroll_offset,
+            selected_line,
+            modal_area.height,
+        );
+    }
+
+    // Clamp scroll offset to content bounds
+    let content_lines = text.len();
+    let clamped_scroll = AppState::clamp_scroll(
+        app.interface_modal_scroll_offset,
+        content_lines,
+        modal_area.height,
+    );
+    app.interface_modal_scroll_offset = clamped_scroll;
+
+    let widget = Paragraph::new(text)
+        .style(Style::default().bg(theme.modal_bg).fg(theme.text))
+        .scroll((clamped_scroll as u16, 0))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Interface Filter (↑↓ to scroll)")
+                .style(Style::default().fg(theme.title)),
+        );
+
+    f.render_widget(Clear, modal_area);
+    f.render_widget(widget, modal_area);
+
+    // Store clickable region for mouse selection
+    app.clickable_regions.push(ClickableRegion {
+        area: modal_area,
+        region_type: ClickableRegionType::InterfaceModal { header_lines },
+    });
+}
+// Process Detail View Rendering
@@ -0,0 +1,68 @@
+// In-app log viewer
+
+use crate::ui::AppState;
+use crate::ui::centered_rect;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+};
+
+pub(crate) fn draw_log_viewer(f: &mut Frame, area: Rect, app: &mut AppState) {
+    let theme = app.theme;
+    let recent = crate::logging::recent();
+
+    let mut log_text: Vec<Line> = recent
+        .iter()
+        .map(|line| {
+            let color = match line.level {
+                log::Level::Error => theme.error,
+                log::Level::Warn => theme.warning,
+                log::Level::Info => theme.text,
+                log::Level::Debug | log::Level::Trace => theme.muted,
+            };
+            Line::from(vec![
+                Span::styled(
+                    format!("{} ", line.time.format("%H:%M:%S")),
+                    Style::default().fg(theme.muted),
+                ),
+                Span::styled(
+                    format!("{:5} ", line.level),
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(line.message.clone(), Style::default().fg(color)),
+            ])
+        })
+        .collect();
+    if log_text.is_empty() {
+        log_text.push(Line::from(Span::styled(
+            "No log messages yet",
+            Style::default().fg(theme.muted),
+        )));
+    }
+
+    let log_area = centered_rect(80, 70, area);
+
+    // Opened at the newest messages (offset past the end, clamped here)
+    let clamped_scroll =
+        AppState::clamp_scroll(app.help_scroll_offset, log_text.len(), log_area.height);
+    app.help_scroll_offset = clamped_scroll;
+
+    let logs = Paragraph::new(log_text)
+        .style(Style::default().bg(theme.modal_bg).fg(theme.text))
+        .scroll((clamped_scroll as u16, 0))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(
+                    "Log ({} messages) - ↑↓ to scroll, any other key to close",
+                    recent.len()
+                ))
+                .style(Style::default().fg(theme.title)),
+        );
+
+    f.render_widget(ratatui::widgets::Clear, log_area);
+    f.render_widget(logs, log_area);
+}
@@ -0,0 +1,19 @@
+// Dialogs and overlays drawn on top of the current view
+
+mod backend;
+mod compat;
+mod help;
+mod interface;
+mod log;
+mod standing_rules;
+mod startup;
+mod throttle;
+
+pub(crate) use backend::draw_backend_info;
+pub(crate) use compat::draw_backend_compatibility_dialog;
+pub(crate) use help::draw_help_overlay;
+pub(crate) use interface::draw_interface_modal;
+pub(crate) use log::draw_log_viewer;
+pub(crate) use standing_rules::draw_standing_rules;
+pub(crate) use startup::draw_startup_summary;
+pub(crate) use throttle::{draw_control_path_warning, draw_throttle_command, draw_throttle_dialog};
@@ -0,0 +1,110 @@
+// Standing rules modal: list, add and delete the rules throttling processes as they start
+
+use crate::theme::Theme;
+use crate::ui::StandingRulesModal;
+use crate::ui::centered_rect;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+pub(crate) fn draw_standing_rules(
+    f: &mut Frame,
+    area: Rect,
+    theme: &Theme,
+    modal: &StandingRulesModal,
+) {
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            "Standing Rules",
+            Style::default()
+                .fg(theme.title)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(
+            "Processes matching a rule are throttled as soon as they start",
+            Style::default().fg(theme.muted),
+        )),
+        Line::from(""),
+    ];
+
+    if modal.rules.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "  No standing rules yet",
+            Style::default().fg(theme.muted),
+        )));
+    }
+    let limit = |limit: &Option<String>| limit.clone().unwrap_or_else(|| "-".to_string());
+    for (index, (rule, active)) in modal.rules.iter().enumerate() {
+        let is_cursor = index == modal.selected && modal.input.is_none();
+        let name_style = if is_cursor {
+            theme.selected()
+        } else {
+            Style::default().fg(theme.text)
+        };
+        lines.push(Line::from(vec![
+            Span::raw(if is_cursor { "▶ " } else { "  " }),
+            Span::styled(format!("{:20}", rule.pattern), name_style),
+            Span::styled(
+                format!(" ↓ {:8} ↑ {:8}", limit(&rule.download), limit(&rule.upload)),
+                Style::default().fg(theme.highlight),
+            ),
+            Span::styled(
+                format!(" {} throttled", active),
+                Style::default().fg(theme.secondary),
+            ),
+        ]));
+    }
+
+    lines.push(Line::from(""));
+    if let Some(input) = &modal.input {
+        lines.push(Line::from(vec![
+            Span::styled("New rule: ", Style::default().fg(theme.secondary)),
+            Span::styled(
+                format!("{}█", input),
+                Style::default()
+                    .fg(theme.highlight)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]));
+    }
+    if let Some(error) = &modal.error {
+        lines.push(Line::from(Span::styled(
+            format!("❌ {}", error),
+            Style::default().fg(theme.error),
+        )));
+    }
+    lines.push(Line::from(""));
+    let hints: &[&str] = if modal.input.is_some() {
+        &[
+            "<name or pattern> <download> [upload], \"-\" = unlimited (e.g. apt* 2M, steam - 1M)",
+            "[Enter] Add  [Esc] Cancel",
+        ]
+    } else {
+        &["[↑↓] Navigate  [a] Add  [d] Delete  [Esc/S] Close"]
+    };
+    for hint in hints {
+        lines.push(Line::from(Span::styled(
+            *hint,
+            Style::default().fg(theme.muted),
+        )));
+    }
+
+    let dialog_area = centered_rect(70, 50, area);
+    let paragraph = Paragraph::new(lines)
+        .wrap(ratatui::widgets::Wrap { trim: false })
+        .style(Style::default().bg(theme.modal_bg).fg(theme.text))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Standing Rules")
+                .style(Style::default().fg(theme.title)),
+        );
+
+    f.render_widget(Clear, dialog_area);
+    f.render_widget(paragraph, dialog_area);
+}
@@ -0,0 +1,97 @@
+// Backend status summary shown at startup
+
+use crate::theme::Theme;
+use crate::ui::StartupSummary;
+use crate::ui::centered_rect;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+pub(crate) fn draw_startup_summary(
+    f: &mut Frame,
+    area: Rect,
+    theme: &Theme,
+    summary: &StartupSummary,
+) {
+    let backend_line = |label: &str, backend: &Option<String>, hint: &str| match backend {
+        Some(name) => vec![Line::from(Span::styled(
+            format!("  ✅ {:22}{}", label, name),
+            Style::default().fg(theme.success),
+        ))],
+        None => vec![
+            Line::from(Span::styled(
+                format!("  ⚠️  {:21}Not available", label),
+                Style::default().fg(theme.warning),
+            )),
+            Line::from(Span::styled(
+                format!("      → {}", hint),
+                Style::default().fg(theme.muted),
+            )),
+        ],
+    };
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            "Backend Status",
+            Style::default()
+                .fg(theme.title)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+    lines.extend(backend_line(
+        "Upload throttling:",
+        &summary.upload_backend,
+        "Install 'tc' (traffic control) and enable cgroups",
+    ));
+    lines.extend(backend_line(
+        "Download throttling:",
+        &summary.download_backend,
+        "Enable 'ifb' kernel module (see IFB_SETUP.md)",
+    ));
+    if summary.upload_backend.is_none() && summary.download_backend.is_none() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "No throttling backends available: monitoring works, throttling doesn't.",
+            Style::default().fg(theme.warning),
+        )));
+    }
+    if let Some(warning) = &summary.privilege_warning {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("⚠️  {}", warning),
+            Style::default()
+                .fg(theme.error)
+                .add_modifier(Modifier::BOLD),
+        )));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(match &summary.log_file {
+        Some(path) => format!("Log file: {}", path.display()),
+        None => "Log file: none".to_string(),
+    }));
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Press any key to continue",
+        Style::default().fg(theme.muted),
+    )));
+
+    let dialog_area = centered_rect(70, 40, area);
+    let paragraph = Paragraph::new(lines)
+        .wrap(ratatui::widgets::Wrap { trim: false })
+        .style(Style::default().bg(theme.modal_bg).fg(theme.text))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("🔥 ChadThrottle v0.6.0")
+                .style(Style::default().fg(theme.title)),
+        );
+
+    f.render_widget(Clear, dialog_area);
+    f.render_widget(paragraph, dialog_area);
+}
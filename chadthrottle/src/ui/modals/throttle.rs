@@ -0,0 +1,558 @@
+// Throttle dialog and the confirmation before throttling our own session
+
+use crate::format;
+use crate::process::{Policy, Priority};
+use crate::theme::Theme;
+use crate::throttle_command::ThrottleCommand;
+use crate::ui::centered_rect;
+use crate::ui::{AppState, ControlPathWarning, ThrottleDialog, ThrottleField};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+pub(crate) fn draw_throttle_dialog(f: &mut Frame, area: Rect, app: &AppState) {
+    let theme = app.theme;
+    let dialog = &app.throttle_dialog;
+
+    let title = if let Some(excluded) = &dialog.excluded_names {
+        format!("Throttle everything except: {} (each)", excluded.join(", "))
+    } else if let (Some(pid), Some(name)) = (dialog.target_pid, &dialog.target_name) {
+        format!("Throttle: {} (PID {})", name, pid)
+    } else {
+        "Throttle Process".to_string()
+    };
+
+    let download_style = if dialog.selected_field == ThrottleField::Download {
+        Style::default()
+            .fg(theme.highlight)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(theme.text)
+    };
+
+    let upload_style = if dialog.selected_field == ThrottleField::Upload {
+        Style::default()
+            .fg(theme.highlight)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(theme.text)
+    };
+
+    let minimum_style = if dialog.selected_field == ThrottleField::Minimum {
+        Style::default()
+            .fg(theme.highlight)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(theme.text)
+    };
+    let minimum_note = if dialog.is_minimum_supported() {
+        ""
+    } else {
+        "  (ignored by current backend)"
+    };
+
+    let traffic_type = dialog.get_traffic_type();
+    let traffic_type_display = match traffic_type {
+        crate::process::TrafficType::All => "All Traffic",
+        crate::process::TrafficType::Internet => "Internet Only",
+        crate::process::TrafficType::Local => "Local Only",
+    };
+
+    // Annotate traffic type with what the current backends can actually enforce
+    let traffic_type_style = if dialog.is_traffic_type_supported() {
+        Style::default()
+            .fg(theme.title)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(theme.muted)
+    };
+    let traffic_type_note = if dialog.is_all_traffic_only() {
+        "  (current backend: All only)"
+    } else if !dialog.is_traffic_type_supported() {
+        "  (unsupported by current backend)"
+    } else {
+        ""
+    };
+
+    // Same annotation for the IP version
+    let family_style = if dialog.is_family_supported() {
+        Style::default()
+            .fg(theme.title)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(theme.muted)
+    };
+    let family_note = if dialog.is_family_supported() {
+        ""
+    } else {
+        "  (unsupported by current backend)"
+    };
+
+    // Priority only ranks throttles on backends with a class scheduler (HTB)
+    let priority_note = match dialog.priority_ignored_by().as_slice() {
+        [] => String::new(),
+        [direction] => format!("  (ignored by {} backend)", direction),
+        _ => "  (ignored by current backends)".to_string(),
+    };
+
+    let mut dialog_text = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("Download Limit (KB/s): ", download_style),
+            Span::styled(
+                if dialog.download_input.is_empty() {
+                    "unlimited"
+                } else {
+                    &dialog.download_input
+                },
+                download_style,
+            ),
+        ]),
+        preview_line(dialog, ThrottleField::Download, &theme),
+        Line::from(vec![
+            Span::styled("Upload Limit (KB/s):   ", upload_style),
+            Span::styled(
+                if dialog.upload_input.is_empty() {
+                    "unlimited"
+                } else {
+                    &dialog.upload_input
+                },
+                upload_style,
+            ),
+        ]),
+        preview_line(dialog, ThrottleField::Upload, &theme),
+        Line::from(vec![
+            Span::styled("Upload Minimum (KB/s): ", minimum_style),
+            Span::styled(
+                if dialog.minimum_input.is_empty() {
+                    "none"
+                } else {
+                    &dialog.minimum_input
+                },
+                minimum_style,
+            ),
+            Span::styled(minimum_note, Style::default().fg(theme.muted)),
+        ]),
+        preview_line(dialog, ThrottleField::Minimum, &theme),
+        Line::from(vec![
+            Span::styled("Traffic Type:          ", Style::default().fg(theme.text)),
+            Span::styled(traffic_type_display, traffic_type_style),
+            Span::styled(traffic_type_note, Style::default().fg(theme.muted)),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("IP Version:            ", Style::default().fg(theme.text)),
+            Span::styled(dialog.family.to_string(), family_style),
+            Span::styled(family_note, Style::default().fg(theme.muted)),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("Policy:                ", Style::default().fg(theme.text)),
+            Span::styled(
+                match dialog.policy {
+                    None => "Backend default",
+                    Some(Policy::Shape) => "Shape (queue excess traffic)",
+                    Some(Policy::Drop) => "Drop (discard excess traffic)",
+                },
+                Style::default()
+                    .fg(theme.title)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("Priority:              ", Style::default().fg(theme.text)),
+            Span::styled(
+                match dialog.priority {
+                    Priority::High => "High",
+                    Priority::Normal => "Normal",
+                    Priority::Low => "Low",
+                },
+                Style::default()
+                    .fg(theme.title)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(priority_note, Style::default().fg(theme.muted)),
+        ]),
+        Line::from(""),
+    ];
+
+    dialog_text.push(Line::from(vec![
+        Span::styled("Include Children:      ", Style::default().fg(theme.text)),
+        Span::styled(
+            format!(
+                "{} {} child process{}",
+                if dialog.include_children {
+                    "[x]"
+                } else {
+                    "[ ]"
+                },
+                dialog.child_processes.len(),
+                if dialog.child_processes.len() == 1 {
+                    ""
+                } else {
+                    "es"
+                }
+            ),
+            Style::default()
+                .fg(theme.title)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            if dialog.include_children {
+                "  (new children join too)"
+            } else {
+                ""
+            },
+            Style::default().fg(theme.muted),
+        ),
+    ]));
+    dialog_text.push(Line::from(""));
+
+    if !dialog.available_interfaces.is_empty() {
+        let interfaces_selected = dialog.selected_field == ThrottleField::Interfaces;
+        let label_style = if interfaces_selected {
+            Style::default()
+                .fg(theme.highlight)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.text)
+        };
+        let mut spans = vec![Span::styled("Interfaces:            ", label_style)];
+        for (i, (name, selected)) in dialog.available_interfaces.iter().enumerate() {
+            let style = if interfaces_selected && i == dialog.interface_cursor {
+                Style::default()
+                    .fg(theme.highlight)
+                    .add_modifier(Modifier::BOLD | Modifier::REVERSED)
+            } else if *selected {
+                Style::default()
+                    .fg(theme.title)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.muted)
+            };
+            spans.push(Span::styled(
+                format!("{} {}", if *selected { "[x]" } else { "[ ]" }, name),
+                style,
+            ));
+            spans.push(Span::raw("  "));
+        }
+        if dialog.selected_interfaces().is_none() {
+            spans.push(Span::styled("(all)", Style::default().fg(theme.muted)));
+        }
+        dialog_text.push(Line::from(spans));
+        dialog_text.push(Line::from(""));
+    }
+
+    if let Some(error) = dialog.limit_error() {
+        dialog_text.push(Line::from(Span::styled(
+            error,
+            Style::default().fg(theme.error),
+        )));
+        dialog_text.push(Line::from(""));
+    }
+
+    if dialog.selected_field == ThrottleField::Interfaces {
+        dialog_text.push(Line::from(Span::styled(
+            "[←→] Move  [Space] Toggle interface  (none selected = all interfaces)",
+            Style::default().fg(theme.muted),
+        )));
+    }
+    dialog_text.push(Line::from(Span::styled(
+        "Plain numbers are KB/s; units: 500k, 5M, 2.5mbit",
+        Style::default().fg(theme.muted),
+    )));
+    dialog_text.push(Line::from(Span::styled(
+        "[Tab] Switch field  [t] Traffic type  [i] IP version  [p] Policy  [r] Priority  [c] Children  [Enter] Apply  [Esc] Cancel",
+        Style::default().fg(theme.muted),
+    )));
+
+    let dialog_widget = Paragraph::new(dialog_text)
+        .style(Style::default().bg(theme.modal_bg).fg(theme.text))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+                .style(Style::default().fg(theme.title)),
+        );
+
+    let mut dialog_height = 55;
+    if !dialog.available_interfaces.is_empty() {
+        dialog_height += 5;
+    }
+    let dialog_area = centered_rect(60, dialog_height, area);
+    f.render_widget(Clear, dialog_area);
+    f.render_widget(dialog_widget, dialog_area);
+}
+
+/// What a limit field parses to, under the field: "= 5.0 MB/s (5,242,880 B/s)", or
+/// "invalid" while it doesn't parse (blank while the field is empty)
+fn preview_line(dialog: &ThrottleDialog, field: ThrottleField, theme: &Theme) -> Line<'static> {
+    const INDENT: &str = "                       "; // Under the field's value
+    match dialog.preview(field) {
+        None => Line::from(""),
+        Some(Ok(bytes_per_sec)) => Line::from(Span::styled(
+            format!(
+                "{}= {} ({} B/s)",
+                INDENT,
+                format::format_rate(bytes_per_sec),
+                group_digits(bytes_per_sec)
+            ),
+            Style::default().fg(theme.muted),
+        )),
+        Some(Err(_)) => Line::from(Span::styled(
+            format!("{}invalid", INDENT),
+            Style::default()
+                .fg(theme.error)
+                .add_modifier(Modifier::BOLD),
+        )),
+    }
+}
+
+/// A number with thousands separators, e.g. 5,242,880
+fn group_digits(n: u64) -> String {
+    let digits = n.to_string();
+    let mut grouped = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+pub(crate) fn draw_control_path_warning(
+    f: &mut Frame,
+    area: Rect,
+    theme: &Theme,
+    warning: &ControlPathWarning,
+) {
+    let lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            format!("Throttle {} (PID {})?", warning.name, warning.pid),
+            Style::default().add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(format!(
+            "This process is on chadthrottle's own control path: {}.",
+            warning.reason
+        )),
+        Line::from(""),
+        Line::from("Limiting it can make this terminal or SSH session unusable,"),
+        Line::from("possibly locking you out of the machine."),
+        Line::from(""),
+        Line::from(Span::styled(
+            "[y] Throttle anyway  [n/Esc] Cancel",
+            Style::default().fg(theme.muted),
+        )),
+    ];
+
+    let dialog_area = centered_rect(70, 35, area);
+    let paragraph = Paragraph::new(lines)
+        .wrap(ratatui::widgets::Wrap { trim: false })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("⚠️  Confirm Throttle")
+                .style(Style::default().fg(theme.error)),
+        );
+
+    f.render_widget(Clear, dialog_area);
+    f.render_widget(paragraph, dialog_area);
+}
+
+pub(crate) fn draw_throttle_command(
+    f: &mut Frame,
+    area: Rect,
+    theme: &Theme,
+    command: &ThrottleCommand,
+) {
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            "Equivalent CLI command:",
+            Style::default()
+                .fg(theme.title)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            command.command.clone(),
+            Style::default().add_modifier(Modifier::BOLD),
+        )),
+    ];
+    if !command.notes.is_empty() {
+        lines.push(Line::from(""));
+        lines.extend(command.notes.iter().map(|note| {
+            Line::from(Span::styled(
+                format!("⚠️  {}", note),
+                Style::default().fg(theme.warning),
+            ))
+        }));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "[y] Copy to clipboard  [any key] Close",
+        Style::default().fg(theme.muted),
+    )));
+
+    let dialog_area = centered_rect(80, 35, area);
+    let paragraph = Paragraph::new(lines)
+        .wrap(ratatui::widgets::Wrap { trim: false })
+        .style(Style::default().bg(theme.modal_bg).fg(theme.text))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Throttle Command")
+                .style(Style::default().fg(theme.title)),
+        );
+
+    f.render_widget(Clear, dialog_area);
+    f.render_widget(paragraph, dialog_area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::{find_in_buffer, render_to_buffer};
+
+    #[test]
+    fn test_render_highlights_selected_field() {
+        let mut app = AppState::new();
+        app.throttle_dialog.available_interfaces =
+            vec![("eth0".to_string(), false), ("wlan0".to_string(), true)];
+        app.throttle_dialog.interface_cursor = 1;
+        let theme = app.theme;
+
+        let labels = [
+            "Download Limit",
+            "Upload Limit",
+            "Upload Minimum",
+            "Interfaces:",
+        ];
+        for (selected, field) in [
+            ThrottleField::Download,
+            ThrottleField::Upload,
+            ThrottleField::Minimum,
+            ThrottleField::Interfaces,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            assert_eq!(app.throttle_dialog.selected_field, field);
+            let buffer = render_to_buffer(120, 50, |f| draw_throttle_dialog(f, f.area(), &app));
+            for (i, label) in labels.iter().enumerate() {
+                let (x, y) = find_in_buffer(&buffer, label).unwrap();
+                let style = buffer[(x, y)].style();
+                if i == selected {
+                    assert_eq!(style.fg, Some(theme.highlight), "{label} selected");
+                    assert!(style.add_modifier.contains(Modifier::BOLD));
+                } else {
+                    assert_eq!(style.fg, Some(theme.text), "{label} not selected");
+                    assert!(!style.add_modifier.contains(Modifier::BOLD));
+                }
+            }
+
+            // The interface under the cursor is only marked while the row has focus
+            let (x, y) = find_in_buffer(&buffer, "[x] wlan0").unwrap();
+            assert_eq!(
+                buffer[(x, y)]
+                    .style()
+                    .add_modifier
+                    .contains(Modifier::REVERSED),
+                field == ThrottleField::Interfaces
+            );
+            app.throttle_dialog.toggle_field();
+        }
+    }
+
+    #[test]
+    fn test_command_from_dialog_inputs() {
+        let mut app = AppState::new();
+        app.throttle_dialog.target_pid = Some(42);
+        app.throttle_dialog.upload_input = "0.1".to_string();
+        // Invalid limits give no command
+        assert!(app.throttle_dialog.command().is_none());
+
+        app.throttle_dialog.upload_input = "512".to_string();
+        app.throttle_command = app.throttle_dialog.command();
+        let command = app.throttle_command.as_ref().unwrap();
+        let buffer = render_to_buffer(120, 50, |f| {
+            draw_throttle_command(f, f.area(), &app.theme, command)
+        });
+        assert!(find_in_buffer(&buffer, "chadthrottle --pid 42 --upload-limit 512K").is_some());
+    }
+
+    #[test]
+    fn test_render_previews_parsed_limits() {
+        let mut app = AppState::new();
+        app.throttle_dialog.download_input = "5M".to_string();
+        app.throttle_dialog.upload_input = "5x".to_string();
+        let buffer = render_to_buffer(120, 50, |f| draw_throttle_dialog(f, f.area(), &app));
+        assert!(find_in_buffer(&buffer, "= 5.0 MB/s (5,242,880 B/s)").is_some());
+        let (x, y) = find_in_buffer(&buffer, "invalid").unwrap();
+        assert_eq!(buffer[(x, y)].style().fg, Some(app.theme.error));
+    }
+
+    #[test]
+    fn test_group_digits() {
+        assert_eq!(group_digits(0), "0");
+        assert_eq!(group_digits(999), "999");
+        assert_eq!(group_digits(1000), "1,000");
+        assert_eq!(group_digits(5_242_880), "5,242,880");
+    }
+
+    #[test]
+    fn test_render_flags_ignored_minimum() {
+        let mut app = AppState::new();
+        app.throttle_dialog.minimum_support = Some(false);
+        let buffer = render_to_buffer(120, 50, |f| draw_throttle_dialog(f, f.area(), &app));
+        // Nothing to ignore until a minimum is entered
+        assert!(find_in_buffer(&buffer, "ignored by current backend").is_none());
+
+        app.throttle_dialog.upload_input = "256".to_string();
+        app.throttle_dialog.minimum_input = "64".to_string();
+        let buffer = render_to_buffer(120, 50, |f| draw_throttle_dialog(f, f.area(), &app));
+        assert!(find_in_buffer(&buffer, "ignored by current backend").is_some());
+    }
+
+    #[test]
+    fn test_render_flags_unsupported_family() {
+        let mut app = AppState::new();
+        app.throttle_dialog.family_support = Some((true, false));
+        app.throttle_dialog.cycle_family();
+        let buffer = render_to_buffer(120, 50, |f| draw_throttle_dialog(f, f.area(), &app));
+        assert!(find_in_buffer(&buffer, "IPv4 only").is_some());
+        assert!(find_in_buffer(&buffer, "unsupported by current backend").is_none());
+
+        app.throttle_dialog.cycle_family();
+        let buffer = render_to_buffer(120, 50, |f| draw_throttle_dialog(f, f.area(), &app));
+        let (x, y) = find_in_buffer(&buffer, "IPv6 only").unwrap();
+        assert_eq!(buffer[(x, y)].style().fg, Some(app.theme.muted));
+        assert!(find_in_buffer(&buffer, "unsupported by current backend").is_some());
+    }
+
+    #[test]
+    fn test_render_flags_ignored_priority() {
+        let mut app = AppState::new();
+        app.throttle_dialog.priority_support = Some((true, false));
+        let buffer = render_to_buffer(120, 50, |f| draw_throttle_dialog(f, f.area(), &app));
+        // Normal priority is what every backend does anyway
+        assert!(find_in_buffer(&buffer, "ignored by").is_none());
+
+        app.throttle_dialog.cycle_priority();
+        let buffer = render_to_buffer(120, 50, |f| draw_throttle_dialog(f, f.area(), &app));
+        assert!(find_in_buffer(&buffer, "Priority:              High").is_some());
+        assert!(find_in_buffer(&buffer, "ignored by download backend").is_some());
+
+        app.throttle_dialog.priority_support = Some((false, false));
+        let buffer = render_to_buffer(120, 50, |f| draw_throttle_dialog(f, f.area(), &app));
+        assert!(find_in_buffer(&buffer, "ignored by current backends").is_some());
+    }
+}
@@ -0,0 +1,559 @@
+// The main process list
+
+use crate::backends::cgroup::container::ContainerRuntime;
+use crate::format;
+use crate::process::ProcessInfo;
+use crate::ui::pad_to_width;
+use crate::ui::{AppState, ClickableRegion, ClickableRegionType, TrafficViewMode};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+pub(super) fn draw_process_list(f: &mut Frame, area: Rect, app: &mut AppState) {
+    let theme = app.theme;
+    // Select which rates to display based on traffic view mode
+    let get_rates = |proc: &ProcessInfo| -> (u64, u64, u64, u64) {
+        match app.traffic_view_mode {
+            TrafficViewMode::All => (
+                proc.download_rate,
+                proc.upload_rate,
+                proc.total_download,
+                proc.total_upload,
+            ),
+            TrafficViewMode::Internet => (
+                proc.internet_download_rate,
+                proc.internet_upload_rate,
+                proc.internet_total_download,
+                proc.internet_total_upload,
+            ),
+            TrafficViewMode::Local => (
+                proc.local_download_rate,
+                proc.local_upload_rate,
+                proc.local_total_download,
+                proc.local_total_upload,
+            ),
+        }
+    };
+
+    let health_width = if app.show_tcp_health {
+        TCP_HEALTH_COLUMNS_WIDTH
+    } else {
+        0
+    };
+
+    // Command lines get whatever width the other columns leave over
+    let name_width = if app.show_cmdline {
+        (area.width as usize)
+            .saturating_sub(PROCESS_LIST_FIXED_WIDTH + health_width)
+            .clamp(20, 80)
+    } else {
+        20
+    };
+
+    let items: Vec<ListItem> = app
+        .process_list
+        .iter()
+        .enumerate()
+        .map(|(index, proc)| {
+            let tree_row = if app.tree_view {
+                app.tree_rows.get(index)
+            } else {
+                None
+            };
+
+            let container_row = app.container_rows.get(&proc.pid);
+
+            // Determine status indicator: throttled (⚡, 📦 in an app sandbox or
+            // container), terminated (💀), a container's row (🐳 docker, 🦭 podman),
+            // only listening (👂), or nothing
+            let status_indicator = if proc.is_throttled() && proc.throttle_sandboxed {
+                "📦"
+            } else if proc.is_throttled() {
+                "⚡"
+            } else if proc.is_terminated {
+                "💀"
+            } else if let Some(row) = container_row {
+                match row.container.runtime {
+                    ContainerRuntime::Docker => "🐳",
+                    ContainerRuntime::Podman => "🦭",
+                }
+            } else if proc.listen_only {
+                "👂"
+            } else {
+                " "
+            };
+
+            // Manual selection indicator and "throttle everything except" mark (◆ when
+            // pinned, ◈ when both) - always present for consistent alignment
+            let selection_indicator = format!(
+                "{}{}",
+                if Some(index) == app.list_state.selected() {
+                    "▶"
+                } else {
+                    " "
+                },
+                match (
+                    app.marked_pids.contains(&proc.pid),
+                    app.pinned_pids.contains(&proc.pid),
+                ) {
+                    (true, true) => "◈",
+                    (true, false) => "●",
+                    (false, true) => "◆",
+                    (false, false) => " ",
+                }
+            );
+
+            // Use gray colors for terminated processes (and the names of listen-only ones)
+            let terminated_color = theme.secondary;
+
+            let name_color = if proc.is_terminated || proc.listen_only {
+                terminated_color
+            } else {
+                theme.text
+            };
+            let dl_rate_color = if proc.is_terminated {
+                terminated_color
+            } else {
+                theme.download
+            };
+            let ul_rate_color = if proc.is_terminated {
+                terminated_color
+            } else {
+                theme.upload
+            };
+            let dl_total_color = if proc.is_terminated {
+                terminated_color
+            } else {
+                theme.download_total
+            };
+            let ul_total_color = if proc.is_terminated {
+                terminated_color
+            } else {
+                theme.upload_total
+            };
+            let status_color = if proc.is_terminated {
+                terminated_color
+            } else {
+                theme.error
+            };
+
+            // Get the appropriate rates based on traffic view mode
+            // (collapsed tree rows show their whole subtree)
+            let shown = tree_row
+                .filter(|row| row.is_collapsed_parent())
+                .and_then(|row| row.subtree.as_ref())
+                .unwrap_or(proc);
+            let (download_rate, upload_rate, total_download, total_upload) = get_rates(shown);
+
+            // Indent tree rows and mark rows that have children
+            let (prefix, suffix) = match tree_row {
+                Some(row) => {
+                    let marker = if !row.has_children() {
+                        "  "
+                    } else if row.expanded {
+                        "▼ "
+                    } else {
+                        "▶ "
+                    };
+                    let child_count = if row.is_collapsed_parent() {
+                        format!(" (+{})", row.descendants.len())
+                    } else {
+                        String::new()
+                    };
+                    (format!("{}{}", "  ".repeat(row.depth), marker), child_count)
+                }
+                None => (String::new(), String::new()),
+            };
+            // Container rows show how many processes they stand for, never a command line
+            let suffix = match container_row {
+                Some(row) => format!(" ({}){}", row.members.len(), suffix),
+                None => suffix,
+            };
+            let name = match app
+                .cmdline_for(proc.pid)
+                .filter(|_| container_row.is_none())
+            {
+                // Command lines keep their tail - that's what tells e.g. two pythons apart
+                Some(cmdline) => {
+                    let room = name_width.saturating_sub(prefix.width() + suffix.width());
+                    format!(
+                        "{}{}{}",
+                        prefix,
+                        truncate_start_to_width(cmdline, room),
+                        suffix
+                    )
+                }
+                None => {
+                    let name = format!("{}{}{}", prefix, proc.name, suffix);
+                    if name.chars().count() > 20 {
+                        format!("{}...", name.chars().take(17).collect::<String>())
+                    } else {
+                        name
+                    }
+                }
+            };
+
+            let mut spans = vec![
+                Span::styled(selection_indicator, Style::default().fg(theme.highlight)),
+                Span::raw(format!("{:7} ", proc.pid)),
+                Span::styled(
+                    format!("{} ", pad_to_width(&name, name_width)),
+                    Style::default().fg(name_color),
+                ),
+                Span::styled(
+                    format!("↓{:>10} ", format::format_rate(download_rate)),
+                    Style::default().fg(dl_rate_color),
+                ),
+                Span::styled(
+                    format!("↑{:>10} ", format::format_rate(upload_rate)),
+                    Style::default().fg(ul_rate_color),
+                ),
+                Span::styled(
+                    format!("{:>10} ", format::format_bytes(total_download)),
+                    Style::default().fg(dl_total_color),
+                ),
+                Span::styled(
+                    format!("{:>10} ", format::format_bytes(total_upload)),
+                    Style::default().fg(ul_total_color),
+                ),
+            ];
+            if app.show_tcp_health {
+                let (retransmits, rtt) = format_tcp_health(proc);
+                let retransmit_color = if proc.is_terminated {
+                    terminated_color
+                } else if app.retransmit_spike(proc) {
+                    theme.error
+                } else {
+                    theme.text
+                };
+                spans.push(Span::styled(
+                    format!("{:>6} ", retransmits),
+                    Style::default().fg(retransmit_color),
+                ));
+                spans.push(Span::styled(
+                    format!("{:>8} ", rtt),
+                    Style::default().fg(name_color),
+                ));
+            }
+            spans.push(Span::styled(
+                status_indicator,
+                Style::default()
+                    .fg(status_color)
+                    .add_modifier(Modifier::BOLD),
+            ));
+            // Share of the limit in use, e.g. "⚡97%" (red when the throttle isn't holding)
+            if proc.throttle_limit.is_some()
+                && let Some(utilization) = app.throttle_utilization.get(&proc.pid)
+                && let Some(percent) = utilization.highest()
+            {
+                spans.push(Span::styled(
+                    format!("{:.0}%", percent),
+                    Style::default().fg(if utilization.not_working() {
+                        theme.error
+                    } else {
+                        theme.muted
+                    }),
+                ));
+            }
+            // Throttles ranked above or below the rest
+            if let Some(limit) = &proc.throttle_limit
+                && limit.priority != crate::process::Priority::Normal
+            {
+                spans.push(Span::styled(
+                    format!(" {}", limit.priority),
+                    Style::default().fg(theme.muted),
+                ));
+            }
+
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let name_header = if app.show_cmdline {
+        "Command"
+    } else {
+        "Process"
+    };
+    let mut header_spans = vec![
+        Span::styled("PID     ", Style::default().add_modifier(Modifier::BOLD)),
+        Span::styled(
+            format!("{} ", pad_to_width(name_header, name_width)),
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            "DL Rate     ",
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            "UL Rate     ",
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Span::styled("Total DL   ", Style::default().add_modifier(Modifier::BOLD)),
+        Span::styled("Total UL   ", Style::default().add_modifier(Modifier::BOLD)),
+    ];
+    if app.show_tcp_health {
+        header_spans.push(Span::styled(
+            "Retx/s RTT      ",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+    }
+    header_spans.push(Span::styled(
+        "Status",
+        Style::default().add_modifier(Modifier::BOLD),
+    ));
+    let header = Line::from(header_spans);
+
+    // Split the area: header takes first row inside border, list gets the rest
+    let header_area = Rect {
+        x: area.x + 3, // +1 for border, +2 for manual selection indicator
+        y: area.y + 1,
+        width: area.width.saturating_sub(4),
+        height: 1,
+    };
+
+    let list_area = Rect {
+        x: area.x,
+        y: area.y + 2, // Start below the header
+        width: area.width,
+        height: area.height.saturating_sub(2),
+    };
+
+    // Render the border and title separately
+    let title = match (app.sort_frozen, app.tree_view) {
+        (true, true) => "Network Activity [TREE] [FROZEN ❄️]",
+        (true, false) => "Network Activity [FROZEN ❄️]",
+        (false, true) => "Network Activity [TREE]",
+        (false, false) => "Network Activity",
+    };
+
+    let border = Block::default().borders(Borders::ALL).title(title);
+    f.render_widget(border, area);
+
+    // Render header
+    f.render_widget(Paragraph::new(header), header_area);
+
+    // Render list without its own border (since we drew it above)
+    let list = List::new(items).highlight_style(theme.list_highlight());
+
+    // Adjust list_state rendering to account for the inner area (inside borders)
+    let inner_list_area = Rect {
+        x: list_area.x + 1,
+        y: list_area.y,
+        width: list_area.width.saturating_sub(2),
+        height: list_area.height.saturating_sub(1),
+    };
+
+    f.render_stateful_widget(list, inner_list_area, &mut app.list_state);
+
+    // Store clickable region for mouse selection
+    let first_visible = app.list_state.offset();
+    let visible_count = inner_list_area.height.saturating_sub(1) as usize; // -1 for potential scrollbar
+    app.clickable_regions.push(ClickableRegion {
+        area: inner_list_area,
+        region_type: ClickableRegionType::ProcessList {
+            first_visible_index: first_visible,
+            visible_count,
+        },
+    });
+}
+
+/// Width of the process list columns other than the name (borders, selection, PID, rates, status)
+const PROCESS_LIST_FIXED_WIDTH: usize = 62;
+
+/// Width of the optional retransmits and RTT columns
+const TCP_HEALTH_COLUMNS_WIDTH: usize = 16;
+
+/// Retransmits/s and average RTT of a process's TCP sockets ("-" when no RTT is known)
+pub(super) fn format_tcp_health(process: &ProcessInfo) -> (String, String) {
+    let rtt = match process.avg_rtt_ms {
+        Some(ms) if ms < 10.0 => format!("{:.1}ms", ms),
+        Some(ms) => format!("{:.0}ms", ms),
+        None => "-".to_string(),
+    };
+    (format!("{:.1}", process.retransmit_rate), rtt)
+}
+
+/// Fit text into `max_width` terminal columns by dropping its start, cutting at a path
+/// or argument boundary where possible (e.g. "…/manage.py runserver")
+fn truncate_start_to_width(text: &str, max_width: usize) -> String {
+    if text.width() <= max_width {
+        return text.to_string();
+    }
+    if max_width == 0 {
+        return String::new();
+    }
+
+    // Longest tail that fits next to the ellipsis
+    let budget = max_width - 1;
+    let mut start = text.len();
+    let mut width = 0;
+    for (idx, ch) in text.char_indices().rev() {
+        width += ch.width().unwrap_or(0);
+        if width > budget {
+            break;
+        }
+        start = idx;
+    }
+    let tail = &text[start..];
+
+    // Don't start mid-word if the tail has a boundary to start at
+    let tail = if text[..start].ends_with(['/', ' ']) {
+        tail
+    } else {
+        match tail.find(['/', ' ']) {
+            Some(pos) if pos + 1 < tail.len() => tail[pos..].trim_start_matches(' '),
+            _ => tail,
+        }
+    };
+    format!("…{}", tail)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_start_keeps_distinguishing_tail() {
+        let cmdline = "/usr/bin/python3 /home/user/project/manage.py runserver";
+        assert_eq!(truncate_start_to_width(cmdline, 80), cmdline);
+        assert_eq!(
+            truncate_start_to_width(cmdline, 22),
+            "…/manage.py runserver"
+        );
+        assert_eq!(truncate_start_to_width("abcdefghij", 5), "…ghij");
+    }
+
+    #[test]
+    fn test_truncate_start_counts_terminal_columns() {
+        // Each CJK character takes two columns
+        let truncated = truncate_start_to_width("日本語", 5);
+        assert_eq!(truncated, "…本語");
+        assert!(truncated.width() <= 5);
+        assert_eq!(pad_to_width("日本", 6).width(), 6);
+    }
+
+    /// Column of `text` in a rendered line
+    fn column(line: &str, text: &str) -> Option<usize> {
+        line.find(text).map(|idx| line[..idx].width())
+    }
+
+    #[test]
+    fn test_render_header_aligns_with_rows() {
+        let mut process = ProcessInfo::new(4242, "firefox".to_string());
+        process.download_rate = 1536;
+        process.throttle_limit = Some(crate::process::ThrottleLimit {
+            download_limit: Some(1024),
+            upload_limit: None,
+            upload_minimum: None,
+            traffic_type: crate::process::TrafficType::All,
+            interfaces: None,
+            policy: None,
+            family: crate::process::AddressFamily::Any,
+            priority: crate::process::Priority::Normal,
+        });
+        let mut app = AppState::new();
+        app.update_processes(crate::process::ProcessMap::from([(4242, process)]));
+
+        let buffer =
+            crate::ui::render_to_buffer(100, 8, |f| draw_process_list(f, f.area(), &mut app));
+        let lines = crate::ui::buffer_lines(&buffer);
+        let (header, row) = (&lines[1], &lines[2]);
+
+        // Each column's values start under its header
+        assert_eq!(column(row, "firefox"), column(header, "Process"));
+        assert_eq!(column(row, "↓"), column(header, "DL Rate"));
+        assert_eq!(column(row, "↑"), column(header, "UL Rate"));
+        assert_eq!(column(row, "⚡"), column(header, "Status"));
+        // The right-aligned PID ends where its header's column does
+        assert_eq!(
+            column(row, "4242").map(|c| c + "4242 ".len()),
+            column(header, "Process")
+        );
+        // The header stays inside the border
+        assert!(header.ends_with("│"));
+    }
+
+    #[test]
+    fn test_render_marks_throttle_priority() {
+        let mut process = ProcessInfo::new(4242, "backup".to_string());
+        process.throttle_limit = Some(crate::process::ThrottleLimit {
+            download_limit: Some(1024),
+            upload_limit: None,
+            upload_minimum: None,
+            traffic_type: crate::process::TrafficType::All,
+            interfaces: None,
+            policy: None,
+            family: crate::process::AddressFamily::Any,
+            priority: crate::process::Priority::Low,
+        });
+        let mut app = AppState::new();
+        app.update_processes(crate::process::ProcessMap::from([(4242, process)]));
+
+        let buffer =
+            crate::ui::render_to_buffer(100, 8, |f| draw_process_list(f, f.area(), &mut app));
+        assert!(crate::ui::buffer_lines(&buffer)[2].contains("⚡ low"));
+    }
+
+    #[test]
+    fn test_render_shows_throttle_utilization() {
+        let mut process = ProcessInfo::new(4242, "steam".to_string());
+        process.throttle_limit = Some(crate::process::ThrottleLimit {
+            download_limit: Some(1024),
+            upload_limit: None,
+            upload_minimum: None,
+            traffic_type: crate::process::TrafficType::All,
+            interfaces: None,
+            policy: None,
+            family: crate::process::AddressFamily::Any,
+            priority: crate::process::Priority::Normal,
+        });
+        let mut app = AppState::new();
+        app.update_processes(crate::process::ProcessMap::from([(4242, process)]));
+        app.throttle_utilization.insert(
+            4242,
+            crate::utilization::Utilization {
+                download: Some(97.2),
+                upload: None,
+                samples: 30,
+                stale: false,
+            },
+        );
+
+        let buffer =
+            crate::ui::render_to_buffer(100, 8, |f| draw_process_list(f, f.area(), &mut app));
+        assert!(crate::ui::buffer_lines(&buffer)[2].contains("⚡97%"));
+    }
+
+    #[test]
+    fn test_render_truncates_long_command_lines() {
+        let mut app = AppState::new();
+        app.update_processes(crate::process::ProcessMap::from([(
+            7,
+            ProcessInfo::new(7, "python3".to_string()),
+        )]));
+        app.show_cmdline = true;
+        let cmdline = "/usr/bin/python3 /home/user/project/manage.py runserver 0.0.0.0:8000";
+        app.cmdlines.insert(7, Some(cmdline.to_string()));
+
+        let buffer =
+            crate::ui::render_to_buffer(90, 6, |f| draw_process_list(f, f.area(), &mut app));
+        let lines = crate::ui::buffer_lines(&buffer);
+        let (header, row) = (&lines[1], &lines[2]);
+
+        // The name column takes what the other columns leave, keeping the command's tail
+        let name_width = 90 - PROCESS_LIST_FIXED_WIDTH;
+        assert!(header.contains(&pad_to_width("Command", name_width)));
+        assert!(row.contains(&format!(
+            " {} ",
+            pad_to_width("…runserver 0.0.0.0:8000", name_width)
+        )));
+        assert!(!row.contains("/usr/bin"));
+        assert_eq!(column(row, "↓"), column(header, "DL Rate"));
+        assert!(header.ends_with("│") && row.ends_with("│"));
+    }
+}
@@ -0,0 +1,3221 @@
+// TUI state: the process and interface lists, selections, dialogs and view modes
+
+use crate::backends::cgroup::container::{Container, container_of};
+use crate::backends::monitor::{InterfaceErrors, is_terminated_retained};
+use crate::backends::process::ProcessUtils;
+use crate::backends::throttle::{BackendInfo, BackendStats};
+use crate::backends::{BackendPriority, TrafficTypeSupport};
+use crate::config::StandingRuleConfig;
+use crate::container_view::ContainerRow;
+use crate::history::HistoryTracker;
+use crate::interface_filter;
+use crate::keybindings::KeyMap;
+use crate::limits::{is_limit_prefix, parse_bandwidth_limit};
+use crate::process::{
+    AddressFamily, ConnectionDetail, InterfaceInfo, InterfaceMap, Policy, Priority, ProcessInfo,
+    ProcessMap, ThrottleLimit, check_limit, check_minimum,
+};
+use crate::process_tree::TreeRow;
+use crate::standing_rules::StandingRule;
+use crate::theme::Theme;
+use crate::throttle_command::{CommandTarget, ThrottleCommand};
+use crate::ui::detail::{connection_endpoints, sorted_connections};
+use crate::undo::UndoStack;
+use crate::utilization::Utilization;
+use ratatui::{layout::Rect, widgets::ListState};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+/// A process as a tab-separated line for other tools
+fn process_summary(process: &ProcessInfo) -> String {
+    format!(
+        "{}\t{}\t{}\t{}\t{}\t{}",
+        process.pid,
+        process.name,
+        process.download_rate,
+        process.upload_rate,
+        process.total_download,
+        process.total_upload
+    )
+}
+
+/// A connection the way the Connections tab lists it ("TCP 10.0.0.2:51234 1.1.1.1:443 Established")
+fn connection_summary(connection: &ConnectionDetail) -> String {
+    let (local, remote) = connection_endpoints(connection);
+    format!(
+        "{} {} {} {}",
+        connection.protocol, local, remote, connection.state
+    )
+    .trim_end()
+    .to_string()
+}
+
+/// How long a bandwidth alert stays in the status bar
+const ALERT_DISPLAY_DURATION: Duration = Duration::from_secs(15);
+
+/// How long a throttle removal waits for its confirming key press
+const REMOVAL_CONFIRM_WINDOW: Duration = Duration::from_secs(3);
+
+pub struct AppState {
+    pub process_list: Vec<ProcessInfo>,
+    pub unfiltered_process_list: Vec<ProcessInfo>, // Full list before interface filtering
+    pub selected_index: Option<usize>,
+    // Process the cursor is on: the selected row follows it as the list re-sorts
+    pub selected_pid: Option<i32>,
+    pub list_state: ListState,
+    pub show_help: bool,
+    pub show_logs: bool, // Log viewer (scrolls with the help overlay's offset)
+    pub show_throttle_dialog: bool,
+    pub show_backend_info: bool,
+    pub throttle_dialog: ThrottleDialog,
+    pub status_message: String,
+    // Latest bandwidth alert and when it was raised (shown highlighted for a while)
+    pub alert_message: Option<(String, Instant)>,
+    pub history: HistoryTracker,
+    pub show_graph: bool,
+    pub sort_frozen: bool,
+    frozen_order: HashMap<i32, usize>, // PID -> position index
+    frozen_process_snapshot: Vec<ProcessInfo>, // Frozen snapshot of process list
+    frozen_terminated_at: HashMap<i32, Instant>, // PID -> when it was first seen terminated
+    // Interface view state
+    pub view_mode: ViewMode,
+    pub interface_list: Vec<InterfaceInfo>,
+    pub interface_list_state: ListState,
+    // Interface name -> how much its error/drop counters grew in the last update
+    pub interface_error_increases: HashMap<String, InterfaceErrors>,
+    pub selected_interface_index: Option<usize>,
+    pub selected_interface_name: Option<String>,
+    // Selection within the InterfaceDetail process list (separate from the main list)
+    pub interface_detail_state: ListState,
+    pub interface_detail_index: Option<usize>,
+    // Interface filter state
+    pub active_interface_filters: Option<Vec<String>>, // None = show all, Some([]) = show nothing, Some([...]) = filter
+    pub interface_pattern_input: Option<String>,       // Pattern being typed in the interface modal
+    // Traffic categorization view state
+    pub traffic_view_mode: TrafficViewMode,
+    // Backend compatibility dialog state
+    pub show_backend_compatibility_dialog: bool,
+    pub backend_compatibility_dialog: Option<BackendCompatibilityDialog>,
+    // Backend selection state (for interactive backend modal)
+    pub backend_items: Vec<BackendSelectorItem>,
+    pub backend_selected_index: usize,
+    // Process detail view state
+    pub selected_process_detail_pid: Option<i32>, // PID of process being detailed
+    process_detail_return: ViewMode,              // View to go back to from process detail
+    pub detail_scroll_offset: usize,              // For scrolling long content
+    pub detail_tab: ProcessDetailTab,             // Which tab is active
+    pub detail_connection_index: usize,           // Connection cursor on the Connections tab
+    pub last_detail_connection_selection: Option<usize>, // Cursor the view last scrolled to
+    pub detail_throttle_stats: (Option<BackendStats>, Option<BackendStats>), // (upload, download)
+    detail_backlog_growth: (u32, u32), // Polls in a row the (upload, download) backlog grew
+    pub detail_throttle_backends: (Option<String>, Option<String>), // (upload, download)
+    pub detail_throttle_downgraded: Option<crate::process::TrafficType>, // type asked for before falling back to All
+    // Modal scroll offsets
+    pub help_scroll_offset: usize,         // For help overlay scrolling
+    pub backend_info_scroll_offset: usize, // For backend info modal scrolling
+    pub backend_info_text: String,         // Backend info modal as last drawn, for copying
+    pub interface_modal_scroll_offset: usize, // For interface filter modal scrolling
+    pub backend_compat_scroll_offset: usize, // For backend compatibility dialog scrolling
+    // Auto-scroll optimization - track last selection to avoid redundant scrolling
+    pub last_interface_selection: Option<usize>,
+    pub last_backend_selection: usize,
+    // Mouse click selection - track clickable regions for each frame
+    pub clickable_regions: Vec<ClickableRegion>,
+    // Color theme used by all widgets
+    pub theme: Theme,
+    // Resolved keybindings (defaults + config overrides)
+    pub keymap: KeyMap,
+    // Process tree view state
+    pub tree_view: bool,
+    pub expanded_pids: HashSet<i32>, // Tree rows showing their children
+    pub tree_rows: Vec<TreeRow>,     // Parallel to process_list when tree_view is on
+    // Container view: one row per docker/podman container
+    pub container_view: bool,
+    containers: HashMap<i32, Option<Container>>, // Container of each listed PID (None = not in one)
+    pub container_rows: HashMap<i32, ContainerRow>, // Rows standing in for a container, by PID
+    // Processes left alone by "throttle everything except"
+    pub marked_pids: HashSet<i32>,
+    // Processes kept at the top of the list (for this session)
+    pub pinned_pids: HashSet<i32>,
+    // Pending confirmation for throttling our own shell/terminal/sshd
+    pub control_path_warning: Option<ControlPathWarning>,
+    // Backend status shown once at startup (any key dismisses it)
+    pub startup_summary: Option<StartupSummary>,
+    // CLI command reproducing a throttle (any key dismisses it)
+    pub throttle_command: Option<ThrottleCommand>,
+    // Standing rules modal, while open
+    pub standing_rules_modal: Option<StandingRulesModal>,
+    // Missing privileges for the selected backends (kept in the status bar)
+    pub privilege_warning: Option<String>,
+    // Daily quotas exceeded today (kept in the status bar until midnight)
+    pub quota_warning: Option<String>,
+    // Ask for a second press before removing a throttle
+    pub confirm_throttle_removal: bool,
+    // Throttle removal waiting for that press
+    pending_removal: Option<PendingRemoval>,
+    // Recent throttle changes made here, undone most recent first
+    pub undo_stack: UndoStack,
+    // Command line display state
+    pub show_cmdline: bool,
+    pub cmdlines: HashMap<i32, Option<String>>, // Fetched once per PID (None if unavailable)
+    // TCP health column (retransmits/RTT)
+    pub show_tcp_health: bool,
+    // Bandwidth graph time axis: time of day instead of how long ago
+    pub graph_clock_time: bool,
+    // List idle daemons that only listen (hidden unless throttled or pinned)
+    pub show_listen_only: bool,
+    // Share of their limits throttled processes use, by PID (once there are enough samples)
+    pub throttle_utilization: HashMap<i32, Utilization>,
+    // Host names of remote addresses (None = reverse DNS disabled)
+    pub reverse_dns: Option<crate::reverse_dns::ReverseDns>,
+    retransmit_baselines: HashMap<i32, f64>, // Usual retransmits/s while unthrottled
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ViewMode {
+    ProcessView,     // Show all processes
+    InterfaceList,   // Show list of interfaces
+    InterfaceDetail, // Show processes on selected interface
+    ProcessDetail,   // Show detailed info about a single process
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProcessDetailTab {
+    Overview,    // General info + bandwidth stats
+    Connections, // Active network connections
+    Traffic,     // Detailed traffic breakdown
+    System,      // System/proc info
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrafficViewMode {
+    All,      // Show combined traffic
+    Internet, // Show only internet traffic
+    Local,    // Show only local network traffic
+}
+
+#[derive(Debug, Clone)]
+pub struct ClickableRegion {
+    pub area: Rect,
+    pub region_type: ClickableRegionType,
+}
+
+#[derive(Debug, Clone)]
+pub enum ClickableRegionType {
+    ProcessList {
+        first_visible_index: usize, // Index of first visible item in list
+        visible_count: usize,       // Number of visible items
+    },
+    ProcessDetailTabs {
+        // Column ranges for each tab: (start_col, end_col, tab)
+        tab_ranges: Vec<(u16, u16, ProcessDetailTab)>,
+    },
+    InterfaceModal {
+        header_lines: usize, // Number of header lines before interface list starts
+    },
+    BackendModal {
+        // Maps visual line (accounting for scroll) to backend item index
+        line_to_item: HashMap<usize, usize>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BackendSelectorItem {
+    GroupHeader(BackendGroup),
+    Backend {
+        name: String,
+        group: BackendGroup,
+        priority: BackendPriority,
+        available: bool,
+        is_current_default: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BackendGroup {
+    Monitor,
+    SocketMapper,
+    Upload,
+    Download,
+}
+
+pub struct ThrottleDialog {
+    pub download_input: String,
+    pub upload_input: String,
+    // Guaranteed upload rate (empty = none)
+    pub minimum_input: String,
+    pub selected_field: ThrottleField,
+    pub target_pid: Option<i32>,
+    pub target_name: Option<String>,
+    pub traffic_type_index: usize, // NEW: 0=All, 1=Internet, 2=Local
+    // Traffic types the current backends support (None = no backend selected)
+    pub traffic_support: Option<TrafficTypeSupport>,
+    // Descendants of the target (PID, name)
+    pub child_processes: Vec<(i32, String)>,
+    // Throttle the whole subtree as one unit, following new children
+    pub include_children: bool,
+    // Interfaces the throttle can be scoped to, with their selection state
+    pub available_interfaces: Vec<(String, bool)>,
+    pub interface_cursor: usize,
+    // Over-limit policy (None = the backend's default)
+    pub policy: Option<Policy>,
+    // IP version the throttle applies to
+    pub family: AddressFamily,
+    // Whether the current backends can throttle (IPv4 only, IPv6 only) (None = no backend selected)
+    pub family_support: Option<(bool, bool)>,
+    // Whether the current upload backend can guarantee a minimum (None = no backend selected)
+    pub minimum_support: Option<bool>,
+    // Rank against other throttled processes
+    pub priority: Priority,
+    // Whether the current backends can prioritize (upload, download) (None = no backend selected)
+    pub priority_support: Option<(bool, bool)>,
+    // Throttle everything except these process names (instead of the target)
+    pub excluded_names: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ThrottleField {
+    Download,
+    Upload,
+    Minimum,
+    Interfaces,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BackendCompatibilityAction {
+    Cancel,
+    SwitchTemporary(String),      // backend name
+    SwitchAndMakeDefault(String), // backend name
+    ConvertToAll,
+    ConvertToAllInterfaces,
+    ConvertToAnyFamily,
+    UseDefaultPolicy,
+    ApplyAnyway, // Keep the current backend despite the warning
+}
+
+/// UDP share of a process's traffic above which TCP-only backends get a warning
+pub const HIGH_UDP_SHARE: f64 = 0.25;
+
+/// Retransmits/s of a throttled process that count as a spike when they are also
+/// `RETRANSMIT_SPIKE_FACTOR` times its unthrottled baseline
+const RETRANSMIT_SPIKE_MIN_RATE: f64 = 1.0;
+const RETRANSMIT_SPIKE_FACTOR: f64 = 2.0;
+
+/// How quickly the unthrottled retransmit baseline follows the current rate
+const RETRANSMIT_BASELINE_WEIGHT: f64 = 0.2;
+
+/// Confirmation asked before throttling a process on chadthrottle's own control path
+#[derive(Debug, Clone)]
+pub struct ControlPathWarning {
+    pub pid: i32,
+    pub name: String,
+    pub reason: String,
+}
+
+/// Throttle removal waiting for a confirming key press
+#[derive(Debug, Clone)]
+pub struct PendingRemoval {
+    pub pid: i32,
+    pub name: String,
+    asked: Instant,
+}
+
+/// The standing rules modal: the config's rules and the one being typed
+#[derive(Debug, Clone, Default)]
+pub struct StandingRulesModal {
+    /// Each rule, with how many processes it throttles right now
+    pub rules: Vec<(StandingRuleConfig, usize)>,
+    pub selected: usize,
+    /// A rule being typed ("apt* 2M 512K"), None while browsing
+    pub input: Option<String>,
+    /// Why the last rule typed was rejected
+    pub error: Option<String>,
+}
+
+impl StandingRulesModal {
+    pub fn move_selection(&mut self, down: bool) {
+        self.selected = if down {
+            (self.selected + 1).min(self.rules.len().saturating_sub(1))
+        } else {
+            self.selected.saturating_sub(1)
+        };
+    }
+
+    /// Parse a typed rule: a name or pattern, the download limit and optionally the
+    /// upload limit, "-" for unlimited ("apt* 2M", "steam - 1M")
+    pub fn parse_input(input: &str) -> Result<StandingRuleConfig, String> {
+        let parts: Vec<&str> = input.split_whitespace().collect();
+        let (pattern, download, upload) = match parts[..] {
+            [pattern, download] => (pattern, download, None),
+            [pattern, download, upload] => (pattern, download, Some(upload)),
+            _ => return Err("Type a name or pattern and its limits, e.g. apt* 2M".to_string()),
+        };
+        let limit = |limit: &str| (limit != "-").then(|| limit.to_string());
+        let rule = StandingRuleConfig {
+            pattern: pattern.to_string(),
+            download: limit(download),
+            upload: upload.and_then(limit),
+        };
+        StandingRule::parse(&rule).map_err(|e| format!("{:#}", e))?;
+        Ok(rule)
+    }
+}
+
+/// Backends selected at startup and where the log is written
+#[derive(Debug, Clone)]
+pub struct StartupSummary {
+    pub upload_backend: Option<String>,
+    pub download_backend: Option<String>,
+    pub privilege_warning: Option<String>,
+    pub log_file: Option<std::path::PathBuf>,
+}
+
+#[derive(Clone)]
+pub struct BackendCompatibilityDialog {
+    pub current_backend: String,
+    pub traffic_type: crate::process::TrafficType,
+    pub compatible_backends: Vec<String>,
+    pub selected_action: usize, // Index into available options
+    pub is_upload: bool,        // true if upload backend issue, false if download
+    // Set when the mismatch is interface scoping rather than traffic type
+    pub interfaces: Option<Vec<String>>,
+    // Set when the mismatch is the over-limit policy
+    pub policy: Option<Policy>,
+    // Set when the mismatch is the IP version
+    pub family: Option<AddressFamily>,
+    // Set (as a percentage) when the backend won't throttle the process's UDP traffic
+    pub udp_share: Option<u8>,
+}
+
+impl BackendCompatibilityDialog {
+    pub fn new(
+        current_backend: String,
+        traffic_type: crate::process::TrafficType,
+        compatible_backends: Vec<String>,
+        is_upload: bool,
+    ) -> Self {
+        Self {
+            current_backend,
+            traffic_type,
+            compatible_backends: compatible_backends.clone(),
+            selected_action: if compatible_backends.is_empty() { 0 } else { 1 },
+            is_upload,
+            interfaces: None,
+            policy: None,
+            family: None,
+            udp_share: None,
+        }
+    }
+
+    /// Dialog for a backend that can't restrict throttles to the given interfaces
+    pub fn for_interfaces(
+        current_backend: String,
+        traffic_type: crate::process::TrafficType,
+        compatible_backends: Vec<String>,
+        is_upload: bool,
+        interfaces: Vec<String>,
+    ) -> Self {
+        Self {
+            interfaces: Some(interfaces),
+            ..Self::new(
+                current_backend,
+                traffic_type,
+                compatible_backends,
+                is_upload,
+            )
+        }
+    }
+
+    /// Dialog for a backend that can't handle over-limit traffic with the given policy
+    pub fn for_policy(
+        current_backend: String,
+        traffic_type: crate::process::TrafficType,
+        compatible_backends: Vec<String>,
+        is_upload: bool,
+        policy: Policy,
+    ) -> Self {
+        Self {
+            policy: Some(policy),
+            ..Self::new(
+                current_backend,
+                traffic_type,
+                compatible_backends,
+                is_upload,
+            )
+        }
+    }
+
+    /// Dialog for a backend that can't restrict throttles to one IP version
+    pub fn for_family(
+        current_backend: String,
+        traffic_type: crate::process::TrafficType,
+        compatible_backends: Vec<String>,
+        is_upload: bool,
+        family: AddressFamily,
+    ) -> Self {
+        Self {
+            family: Some(family),
+            ..Self::new(
+                current_backend,
+                traffic_type,
+                compatible_backends,
+                is_upload,
+            )
+        }
+    }
+
+    /// Warning for a backend that leaves a process's substantial UDP traffic unthrottled
+    pub fn for_udp(
+        current_backend: String,
+        traffic_type: crate::process::TrafficType,
+        compatible_backends: Vec<String>,
+        is_upload: bool,
+        udp_share: f64,
+    ) -> Self {
+        Self {
+            udp_share: Some((udp_share * 100.0).round() as u8),
+            ..Self::new(
+                current_backend,
+                traffic_type,
+                compatible_backends,
+                is_upload,
+            )
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        let total = self.get_total_options();
+        if total > 0 {
+            self.selected_action = (self.selected_action + 1) % total;
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        let total = self.get_total_options();
+        if total > 0 {
+            self.selected_action = if self.selected_action == 0 {
+                total - 1
+            } else {
+                self.selected_action - 1
+            };
+        }
+    }
+
+    pub fn get_total_options(&self) -> usize {
+        // Cancel + (2 options per compatible backend) + Convert to All
+        1 + (self.compatible_backends.len() * 2) + 1
+    }
+
+    pub fn get_action(&self) -> BackendCompatibilityAction {
+        if self.selected_action == 0 {
+            return BackendCompatibilityAction::Cancel;
+        }
+
+        let last_option = self.get_total_options() - 1;
+        if self.selected_action == last_option {
+            return if self.udp_share.is_some() {
+                BackendCompatibilityAction::ApplyAnyway
+            } else if self.policy.is_some() {
+                BackendCompatibilityAction::UseDefaultPolicy
+            } else if self.family.is_some() {
+                BackendCompatibilityAction::ConvertToAnyFamily
+            } else if self.interfaces.is_some() {
+                BackendCompatibilityAction::ConvertToAllInterfaces
+            } else {
+                BackendCompatibilityAction::ConvertToAll
+            };
+        }
+
+        // Options are: Cancel, [Switch temp, Switch default] * N, Convert to All
+        let backend_option_index = self.selected_action - 1;
+        let backend_index = backend_option_index / 2;
+        let is_make_default = backend_option_index % 2 == 1;
+
+        if let Some(backend_name) = self.compatible_backends.get(backend_index) {
+            if is_make_default {
+                BackendCompatibilityAction::SwitchAndMakeDefault(backend_name.clone())
+            } else {
+                BackendCompatibilityAction::SwitchTemporary(backend_name.clone())
+            }
+        } else {
+            BackendCompatibilityAction::Cancel
+        }
+    }
+}
+
+impl ThrottleDialog {
+    pub fn new() -> Self {
+        Self {
+            download_input: String::new(),
+            upload_input: String::new(),
+            minimum_input: String::new(),
+            selected_field: ThrottleField::Download,
+            target_pid: None,
+            target_name: None,
+            traffic_type_index: 0, // Default to "All"
+            traffic_support: None,
+            child_processes: Vec::new(),
+            include_children: false,
+            available_interfaces: Vec::new(),
+            interface_cursor: 0,
+            policy: None,
+            family: AddressFamily::Any,
+            family_support: None,
+            minimum_support: None,
+            priority: Priority::Normal,
+            priority_support: None,
+            excluded_names: None,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.download_input.clear();
+        self.upload_input.clear();
+        self.minimum_input.clear();
+        self.selected_field = ThrottleField::Download;
+        self.target_pid = None;
+        self.target_name = None;
+        self.traffic_type_index = 0; // Reset to "All"
+        self.traffic_support = None;
+        self.child_processes.clear();
+        self.include_children = false;
+        self.available_interfaces.clear();
+        self.interface_cursor = 0;
+        self.policy = None;
+        self.family = AddressFamily::Any;
+        self.family_support = None;
+        self.minimum_support = None;
+        self.priority = Priority::Normal;
+        self.priority_support = None;
+        self.excluded_names = None;
+    }
+
+    /// Offer the given interfaces for scoping (none selected = all interfaces)
+    pub fn set_available_interfaces(&mut self, mut names: Vec<String>) {
+        names.sort();
+        self.available_interfaces = names.into_iter().map(|name| (name, false)).collect();
+        self.interface_cursor = 0;
+    }
+
+    pub fn move_interface_cursor(&mut self, forward: bool) {
+        let count = self.available_interfaces.len();
+        if count == 0 {
+            return;
+        }
+        self.interface_cursor = if forward {
+            (self.interface_cursor + 1) % count
+        } else {
+            (self.interface_cursor + count - 1) % count
+        };
+    }
+
+    pub fn toggle_interface(&mut self) {
+        if let Some((_, selected)) = self.available_interfaces.get_mut(self.interface_cursor) {
+            *selected = !*selected;
+        }
+    }
+
+    /// Interfaces the throttle is restricted to (None = all interfaces)
+    pub fn selected_interfaces(&self) -> Option<Vec<String>> {
+        let selected: Vec<String> = self
+            .available_interfaces
+            .iter()
+            .filter(|(_, selected)| *selected)
+            .map(|(name, _)| name.clone())
+            .collect();
+        (!selected.is_empty()).then_some(selected)
+    }
+
+    /// Offer to throttle the target's whole subtree
+    pub fn set_child_processes(&mut self, children: Vec<(i32, String)>, include: bool) {
+        self.include_children = include;
+        self.child_processes = children;
+    }
+
+    pub fn toggle_include_children(&mut self) {
+        self.include_children = !self.include_children;
+    }
+
+    /// Child processes that should share the target's throttle
+    /// (None = throttle the target alone)
+    pub fn children_to_throttle(&self) -> Option<Vec<(i32, String)>> {
+        self.include_children.then(|| self.child_processes.clone())
+    }
+
+    /// Cycle backend default -> shape -> drop
+    pub fn cycle_policy(&mut self) {
+        self.policy = match self.policy {
+            None => Some(Policy::Shape),
+            Some(Policy::Shape) => Some(Policy::Drop),
+            Some(Policy::Drop) => None,
+        };
+    }
+
+    /// Cycle IPv4 + IPv6 -> IPv4 only -> IPv6 only
+    pub fn cycle_family(&mut self) {
+        self.family = match self.family {
+            AddressFamily::Any => AddressFamily::V4,
+            AddressFamily::V4 => AddressFamily::V6,
+            AddressFamily::V6 => AddressFamily::Any,
+        };
+    }
+
+    /// Check if the current backends can restrict a throttle to the selected IP version
+    pub fn is_family_supported(&self) -> bool {
+        match (self.family, self.family_support) {
+            (_, None) | (AddressFamily::Any, _) => true,
+            (AddressFamily::V4, Some((v4, _))) => v4,
+            (AddressFamily::V6, Some((_, v6))) => v6,
+        }
+    }
+
+    /// Cycle normal -> high -> low
+    pub fn cycle_priority(&mut self) {
+        self.priority = self.priority.next();
+    }
+
+    /// Directions whose backend can't prioritize, when a non-normal priority is
+    /// selected ("upload", "download" or both; empty when it takes effect everywhere)
+    pub fn priority_ignored_by(&self) -> Vec<&'static str> {
+        match (self.priority, self.priority_support) {
+            (Priority::Normal, _) | (_, None) => Vec::new(),
+            (_, Some((upload, download))) => [(upload, "upload"), (download, "download")]
+                .into_iter()
+                .filter(|(supported, _)| !supported)
+                .map(|(_, direction)| direction)
+                .collect(),
+        }
+    }
+
+    pub fn cycle_traffic_type(&mut self) {
+        self.traffic_type_index = (self.traffic_type_index + 1) % 3;
+    }
+
+    pub fn get_traffic_type(&self) -> crate::process::TrafficType {
+        use crate::process::TrafficType;
+        match self.traffic_type_index {
+            0 => TrafficType::All,
+            1 => TrafficType::Internet,
+            2 => TrafficType::Local,
+            _ => TrafficType::All,
+        }
+    }
+
+    /// Check if the current backends support the selected traffic type
+    pub fn is_traffic_type_supported(&self) -> bool {
+        self.traffic_support
+            .is_none_or(|support| support.supports(self.get_traffic_type()))
+    }
+
+    /// Check if the current backends can only throttle all traffic
+    pub fn is_all_traffic_only(&self) -> bool {
+        self.traffic_support.is_some_and(|support| {
+            !support.supports_internet_filter && !support.supports_local_filter
+        })
+    }
+
+    /// Input of a limit field (None for the interface list)
+    fn input(&self, field: ThrottleField) -> Option<&str> {
+        match field {
+            ThrottleField::Download => Some(&self.download_input),
+            ThrottleField::Upload => Some(&self.upload_input),
+            ThrottleField::Minimum => Some(&self.minimum_input),
+            ThrottleField::Interfaces => None,
+        }
+    }
+
+    /// Whether typing `c` continues the selected limit (a digit, or the next letter of
+    /// a unit such as "2.5mbit"). Other keys are left to the dialog's shortcuts
+    pub fn accepts_char(&self, c: char) -> bool {
+        self.input(self.selected_field)
+            .is_some_and(|input| is_limit_prefix(&format!("{}{}", input, c)))
+    }
+
+    pub fn handle_char(&mut self, c: char) {
+        match self.selected_field {
+            ThrottleField::Download => self.download_input.push(c),
+            ThrottleField::Upload => self.upload_input.push(c),
+            ThrottleField::Minimum => self.minimum_input.push(c),
+            ThrottleField::Interfaces => {}
+        }
+    }
+
+    pub fn handle_backspace(&mut self) {
+        match self.selected_field {
+            ThrottleField::Download => {
+                self.download_input.pop();
+            }
+            ThrottleField::Upload => {
+                self.upload_input.pop();
+            }
+            ThrottleField::Minimum => {
+                self.minimum_input.pop();
+            }
+            ThrottleField::Interfaces => {}
+        }
+    }
+
+    pub fn toggle_field(&mut self) {
+        self.selected_field = match self.selected_field {
+            ThrottleField::Download => ThrottleField::Upload,
+            ThrottleField::Upload => ThrottleField::Minimum,
+            ThrottleField::Minimum if !self.available_interfaces.is_empty() => {
+                ThrottleField::Interfaces
+            }
+            ThrottleField::Minimum | ThrottleField::Interfaces => ThrottleField::Download,
+        };
+    }
+
+    /// Limits in bytes/sec (None = unlimited), or None if any input is invalid
+    pub fn parse_limits(&self) -> Option<(Option<u64>, Option<u64>)> {
+        let download = parse_dialog_limit(&self.download_input).ok()?;
+        let upload = parse_dialog_limit(&self.upload_input).ok()?;
+        self.parse_minimum(upload).ok()?;
+        Some((download, upload))
+    }
+
+    /// Guaranteed upload rate in bytes/sec (None = none, or the inputs are invalid)
+    pub fn upload_minimum(&self) -> Option<u64> {
+        let upload = parse_dialog_limit(&self.upload_input).ok()?;
+        self.parse_minimum(upload).ok()?
+    }
+
+    fn parse_minimum(&self, upload: Option<u64>) -> anyhow::Result<Option<u64>> {
+        parse_dialog_limit(&self.minimum_input)?
+            .map(|minimum| check_minimum(minimum, upload))
+            .transpose()
+    }
+
+    /// Check if the current upload backend honors the minimum (true without one)
+    pub fn is_minimum_supported(&self) -> bool {
+        self.minimum_input.is_empty() || self.minimum_support.unwrap_or(true)
+    }
+
+    /// The CLI command for the throttle being set up (None without a target or valid limits)
+    pub fn command(&self) -> Option<ThrottleCommand> {
+        let target = match (&self.excluded_names, self.target_pid) {
+            (Some(excluded), _) => CommandTarget::AllExcept(excluded.clone()),
+            (None, Some(pid)) => CommandTarget::Pid(pid),
+            (None, None) => return None,
+        };
+        let (download_limit, upload_limit) = self.parse_limits()?;
+        if download_limit.is_none() && upload_limit.is_none() {
+            return None;
+        }
+        let limit = ThrottleLimit {
+            download_limit,
+            upload_limit,
+            upload_minimum: self.upload_minimum(),
+            traffic_type: self.get_traffic_type(),
+            interfaces: self.selected_interfaces(),
+            policy: self.policy,
+            family: self.family,
+            priority: self.priority,
+        };
+        Some(ThrottleCommand::new(&target, &limit).with_children(self.include_children))
+    }
+
+    /// The value a limit field parses to, for the live preview under it
+    /// (None while the field is empty)
+    pub fn preview(&self, field: ThrottleField) -> Option<Result<u64, String>> {
+        let input = self.input(field).filter(|input| !input.is_empty())?;
+        let parsed = match field {
+            ThrottleField::Minimum => {
+                parse_dialog_limit(&self.upload_input).and_then(|upload| self.parse_minimum(upload))
+            }
+            _ => parse_dialog_limit(input),
+        };
+        Some(
+            parsed
+                .map(Option::unwrap_or_default)
+                .map_err(|e| e.to_string()),
+        )
+    }
+
+    /// First limit field that doesn't parse, for focusing it when Enter is refused
+    pub fn invalid_field(&self) -> Option<ThrottleField> {
+        [
+            ThrottleField::Download,
+            ThrottleField::Upload,
+            ThrottleField::Minimum,
+        ]
+        .into_iter()
+        .find(|field| matches!(self.preview(*field), Some(Err(_))))
+    }
+
+    /// Why the limits can't be applied, shown inline in the dialog
+    pub fn limit_error(&self) -> Option<String> {
+        parse_dialog_limit(&self.download_input)
+            .map_err(|e| format!("Download: {}", e))
+            .and(parse_dialog_limit(&self.upload_input).map_err(|e| format!("Upload: {}", e)))
+            .and_then(|upload| {
+                self.parse_minimum(upload)
+                    .map_err(|e| format!("Minimum: {}", e))
+            })
+            .err()
+    }
+}
+
+/// Parse a limit input with the CLI's parser ("5M", "500k", "2.5mbit"), except that a
+/// bare number is KB/s (fractions allowed, e.g. "0.5"). Empty means unlimited.
+fn parse_dialog_limit(input: &str) -> anyhow::Result<Option<u64>> {
+    if input.is_empty() {
+        return Ok(None);
+    }
+    let with_unit = if input.chars().all(|c| c.is_ascii_digit() || c == '.') {
+        format!("{}K", input)
+    } else {
+        input.to_string()
+    };
+    let bytes_per_sec = parse_bandwidth_limit(&with_unit)
+        .map_err(|_| anyhow::anyhow!("\"{}\" is not a valid limit", input))?;
+    check_limit(bytes_per_sec).map(Some)
+}
+
+impl AppState {
+    pub fn new() -> Self {
+        let mut list_state = ListState::default();
+        list_state.select(None); // Nothing selected initially
+
+        let mut interface_list_state = ListState::default();
+        interface_list_state.select(None);
+
+        Self {
+            process_list: Vec::new(),
+            unfiltered_process_list: Vec::new(),
+            selected_index: None, // Nothing selected initially
+            selected_pid: None,
+            list_state,
+            history: HistoryTracker::new(),
+            show_graph: false,
+            show_help: false,
+            show_logs: false,
+            show_throttle_dialog: false,
+            show_backend_info: false,
+            throttle_dialog: ThrottleDialog::new(),
+            status_message: String::from("ChadThrottle started. Press 'h' for help."),
+            alert_message: None,
+            sort_frozen: false,
+            frozen_order: HashMap::new(),
+            frozen_process_snapshot: Vec::new(),
+            frozen_terminated_at: HashMap::new(),
+            view_mode: ViewMode::ProcessView,
+            interface_list: Vec::new(),
+            interface_list_state,
+            interface_error_increases: HashMap::new(),
+            selected_interface_index: None,
+            selected_interface_name: None,
+            interface_detail_state: ListState::default(),
+            interface_detail_index: None,
+            active_interface_filters: None, // Show all by default
+            interface_pattern_input: None,
+            traffic_view_mode: TrafficViewMode::All, // Show all traffic by default
+            show_backend_compatibility_dialog: false,
+            backend_compatibility_dialog: None,
+            backend_items: Vec::new(),
+            backend_selected_index: 0,
+            selected_process_detail_pid: None,
+            process_detail_return: ViewMode::ProcessView,
+            detail_scroll_offset: 0,
+            detail_tab: ProcessDetailTab::Overview,
+            detail_connection_index: 0,
+            last_detail_connection_selection: None,
+            detail_throttle_stats: (None, None),
+            detail_backlog_growth: (0, 0),
+            detail_throttle_downgraded: None,
+            detail_throttle_backends: (None, None),
+            help_scroll_offset: 0,
+            backend_info_scroll_offset: 0,
+            backend_info_text: String::new(),
+            interface_modal_scroll_offset: 0,
+            backend_compat_scroll_offset: 0,
+            last_interface_selection: None,
+            last_backend_selection: 0,
+            clickable_regions: Vec::new(),
+            theme: Theme::default(),
+            keymap: KeyMap::default(),
+            tree_view: false,
+            expanded_pids: HashSet::new(),
+            marked_pids: HashSet::new(),
+            pinned_pids: HashSet::new(),
+            tree_rows: Vec::new(),
+            container_view: false,
+            containers: HashMap::new(),
+            container_rows: HashMap::new(),
+            control_path_warning: None,
+            startup_summary: None,
+            throttle_command: None,
+            standing_rules_modal: None,
+            privilege_warning: None,
+            quota_warning: None,
+            confirm_throttle_removal: true,
+            pending_removal: None,
+            undo_stack: UndoStack::default(),
+            show_cmdline: false,
+            cmdlines: HashMap::new(),
+            show_tcp_health: false,
+            graph_clock_time: false,
+            show_listen_only: false,
+            throttle_utilization: HashMap::new(),
+            reverse_dns: None,
+            retransmit_baselines: HashMap::new(),
+        }
+    }
+
+    /// Show a highlighted alert in the status bar
+    pub fn raise_alert(&mut self, message: String) {
+        self.alert_message = Some((message, Instant::now()));
+    }
+
+    /// The alert to show, if one was raised recently
+    pub fn active_alert(&self) -> Option<&str> {
+        self.alert_message
+            .as_ref()
+            .filter(|(_, raised)| raised.elapsed() < ALERT_DISPLAY_DURATION)
+            .map(|(message, _)| message.as_str())
+    }
+
+    /// Ask for a second press before removing the throttle of `pid`
+    pub fn ask_removal(&mut self, pid: i32, name: String) {
+        self.pending_removal = Some(PendingRemoval {
+            pid,
+            name,
+            asked: Instant::now(),
+        });
+    }
+
+    /// The removal waiting for confirmation, unless it has timed out
+    pub fn pending_removal(&self) -> Option<&PendingRemoval> {
+        self.pending_removal
+            .as_ref()
+            .filter(|pending| pending.asked.elapsed() < REMOVAL_CONFIRM_WINDOW)
+    }
+
+    /// Clear the pending removal, returning it if it was still waiting
+    pub fn take_pending_removal(&mut self) -> Option<PendingRemoval> {
+        self.pending_removal
+            .take()
+            .filter(|pending| pending.asked.elapsed() < REMOVAL_CONFIRM_WINDOW)
+    }
+
+    pub fn update_interfaces(&mut self, interface_map: InterfaceMap) {
+        let mut interfaces: Vec<InterfaceInfo> = interface_map.into_values().collect();
+
+        // Sort by name for consistent display
+        interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+
+        self.interface_error_increases = interfaces
+            .iter()
+            .filter_map(|iface| {
+                let current = iface.errors?;
+                let previous = self
+                    .interface_list
+                    .iter()
+                    .find(|old| old.name == iface.name)?
+                    .errors?;
+                Some((iface.name.clone(), current.increase_since(&previous)))
+            })
+            .collect();
+        self.interface_list = interfaces;
+
+        // Adjust selection if out of bounds
+        if let Some(index) = self.selected_interface_index {
+            if index >= self.interface_list.len() && !self.interface_list.is_empty() {
+                self.selected_interface_index = Some(self.interface_list.len() - 1);
+                self.interface_list_state
+                    .select(Some(self.interface_list.len() - 1));
+            }
+        }
+    }
+
+    pub fn toggle_view_mode(&mut self) {
+        self.view_mode = match self.view_mode {
+            ViewMode::ProcessView => {
+                // Switch to interface list view
+                if !self.interface_list.is_empty() {
+                    self.selected_interface_index = Some(0);
+                    self.interface_list_state.select(Some(0));
+                }
+                self.reset_interface_modal_scroll();
+                ViewMode::InterfaceList
+            }
+            ViewMode::InterfaceList | ViewMode::InterfaceDetail | ViewMode::ProcessDetail => {
+                // Switch back to process view
+                ViewMode::ProcessView
+            }
+        };
+    }
+
+    pub fn toggle_traffic_view_mode(&mut self) {
+        self.traffic_view_mode = match self.traffic_view_mode {
+            TrafficViewMode::All => TrafficViewMode::Internet,
+            TrafficViewMode::Internet => TrafficViewMode::Local,
+            TrafficViewMode::Local => TrafficViewMode::All,
+        };
+
+        // Update status message to inform user
+        let mode_str = match self.traffic_view_mode {
+            TrafficViewMode::All => "All Traffic",
+            TrafficViewMode::Internet => "Internet Traffic Only",
+            TrafficViewMode::Local => "Local Traffic Only",
+        };
+        self.status_message = format!("Traffic View: {}", mode_str);
+    }
+
+    pub fn select_next_interface(&mut self) {
+        if self.interface_list.is_empty() {
+            return;
+        }
+
+        let new_index = match self.selected_interface_index {
+            None => 0,
+            Some(idx) => (idx + 1) % self.interface_list.len(),
+        };
+
+        self.selected_interface_index = Some(new_index);
+        self.interface_list_state.select(Some(new_index));
+    }
+
+    pub fn select_previous_interface(&mut self) {
+        if self.interface_list.is_empty() {
+            return;
+        }
+
+        let new_index = match self.selected_interface_index {
+            None => 0,
+            Some(0) => self.interface_list.len() - 1,
+            Some(idx) => idx - 1,
+        };
+
+        self.selected_interface_index = Some(new_index);
+        self.interface_list_state.select(Some(new_index));
+    }
+
+    pub fn get_selected_interface(&self) -> Option<&InterfaceInfo> {
+        self.selected_interface_index
+            .and_then(|idx| self.interface_list.get(idx))
+    }
+
+    pub fn enter_interface_detail(&mut self) {
+        if let Some(interface) = self.get_selected_interface() {
+            self.selected_interface_name = Some(interface.name.clone());
+            self.view_mode = ViewMode::InterfaceDetail;
+            self.select_interface_detail_index(Some(0));
+        }
+    }
+
+    pub fn exit_interface_detail(&mut self) {
+        self.view_mode = ViewMode::InterfaceList;
+        self.selected_interface_name = None;
+        self.select_interface_detail_index(None);
+    }
+
+    /// Processes shown in InterfaceDetail: all processes with traffic on the selected interface
+    pub fn interface_detail_processes(&self) -> Vec<&ProcessInfo> {
+        let Some(interface_name) = &self.selected_interface_name else {
+            return Vec::new();
+        };
+        self.unfiltered_process_list
+            .iter()
+            .filter(|p| p.interface_stats.contains_key(interface_name))
+            .collect()
+    }
+
+    /// Select a row in the InterfaceDetail list, clamped to its length
+    fn select_interface_detail_index(&mut self, index: Option<usize>) {
+        let len = self.interface_detail_processes().len();
+        let index = index.filter(|_| len > 0).map(|idx| idx.min(len - 1));
+        self.interface_detail_index = index;
+        self.interface_detail_state.select(index);
+    }
+
+    pub fn select_next_in_interface_detail(&mut self) {
+        let len = self.interface_detail_processes().len();
+        if len == 0 {
+            return;
+        }
+        let index = self.interface_detail_index.map_or(0, |idx| (idx + 1) % len);
+        self.select_interface_detail_index(Some(index));
+    }
+
+    pub fn select_previous_in_interface_detail(&mut self) {
+        let len = self.interface_detail_processes().len();
+        if len == 0 {
+            return;
+        }
+        let index = match self.interface_detail_index {
+            None | Some(0) => len - 1,
+            Some(idx) => idx - 1,
+        };
+        self.select_interface_detail_index(Some(index));
+    }
+
+    pub fn get_interface_detail_process(&self) -> Option<&ProcessInfo> {
+        self.interface_detail_index
+            .and_then(|idx| self.interface_detail_processes().get(idx).copied())
+    }
+
+    /// The process actions (throttle, remove, details) apply to in the current view
+    pub fn get_target_process(&self) -> Option<&ProcessInfo> {
+        if self.view_mode == ViewMode::InterfaceDetail {
+            self.get_interface_detail_process()
+        } else {
+            self.get_selected_process()
+        }
+    }
+
+    /// Check if an interface is in the current filter (i.e., should be shown)
+    pub fn is_interface_filtered(&self, interface_name: &str) -> bool {
+        match &self.active_interface_filters {
+            None => true, // No filter = all shown
+            Some(filters) => interface_filter::matches(filters, interface_name),
+        }
+    }
+
+    /// Toggle a single interface in/out of the filter
+    pub fn toggle_interface_filter(&mut self, interface_name: String) {
+        match &mut self.active_interface_filters {
+            None => {
+                // No filter active - create one with just this interface unchecked
+                // Collect all OTHER interfaces
+                let all_others: Vec<String> = self
+                    .interface_list
+                    .iter()
+                    .filter(|i| i.name != interface_name)
+                    .map(|i| i.name.clone())
+                    .collect();
+
+                if all_others.is_empty() {
+                    self.status_message = "Filter: showing nothing".to_string();
+                } else {
+                    self.status_message = format!("Filter: hiding {}", interface_name);
+                }
+
+                self.active_interface_filters = Some(all_others);
+            }
+            Some(filters) => {
+                let exclusion = format!("!{}", interface_name);
+                if interface_filter::matches(filters, &interface_name) {
+                    // Remove from filter (hide this interface)
+                    filters.retain(|f| f != &interface_name);
+
+                    // A pattern still covering it needs an explicit exclusion
+                    if let Some(pattern) =
+                        interface_filter::covering_pattern(filters, &interface_name)
+                    {
+                        self.status_message = format!(
+                            "Filter: hiding {} (excluded from {})",
+                            interface_name, pattern
+                        );
+                        filters.push(exclusion);
+                    } else if filters.is_empty() {
+                        self.status_message = "Filter: showing nothing".to_string();
+                    } else {
+                        self.status_message = format!("Filter: {}", filters.join(", "));
+                    }
+                } else {
+                    // Add to filter (show this interface), dropping an exclusion first
+                    filters.retain(|f| f != &exclusion);
+                    if !interface_filter::matches(filters, &interface_name) {
+                        filters.push(interface_name.clone());
+                    }
+                    filters.sort(); // Keep sorted
+
+                    // Check if all interfaces now selected (patterns keep the filter,
+                    // since they decide about interfaces that appear later)
+                    let all_exact = !filters.iter().any(|f| {
+                        interface_filter::is_pattern(f) || interface_filter::exclusion(f).is_some()
+                    });
+                    if all_exact && filters.len() == self.interface_list.len() {
+                        self.active_interface_filters = None;
+                        self.status_message = "Filter cleared (all selected)".to_string();
+                    } else {
+                        self.status_message = format!("Filter: {}", filters.join(", "));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Add a glob pattern (e.g. "tun*") to the filter, or remove it if it's already there
+    pub fn toggle_interface_pattern(&mut self, pattern: &str) {
+        let pattern = pattern.trim();
+        if !interface_filter::is_pattern(pattern) {
+            // A plain name is just a toggle of that interface
+            if !pattern.is_empty() {
+                self.toggle_interface_filter(pattern.to_string());
+            }
+            return;
+        }
+
+        // Starting from "show all", keep showing the current interfaces
+        let filters = self.active_interface_filters.get_or_insert_with(|| {
+            self.interface_list
+                .iter()
+                .map(|iface| iface.name.clone())
+                .collect()
+        });
+        if filters.iter().any(|f| f == pattern) {
+            filters.retain(|f| f != pattern);
+            self.status_message = format!("Removed interface pattern {}", pattern);
+        } else {
+            filters.push(pattern.to_string());
+            filters.sort();
+            self.status_message = format!("Added interface pattern {}", pattern);
+        }
+    }
+
+    /// Clear filter (show all)
+    pub fn clear_interface_filters(&mut self) {
+        self.active_interface_filters = None;
+        self.status_message = "Filter cleared - showing all interfaces".to_string();
+    }
+
+    /// Set empty filter (show nothing)
+    pub fn set_empty_filter(&mut self) {
+        self.active_interface_filters = Some(vec![]);
+        self.status_message = "Filter: showing no interfaces".to_string();
+    }
+
+    /// Toggle between all interfaces selected and none selected
+    pub fn toggle_all_interface_filters(&mut self) {
+        // Check if all interfaces are currently selected
+        let all_selected = self
+            .interface_list
+            .iter()
+            .all(|iface| self.is_interface_filtered(&iface.name));
+
+        if all_selected {
+            // Deselect all
+            self.active_interface_filters = Some(vec![]);
+            self.status_message = "Filter: showing no interfaces".to_string();
+        } else {
+            // Select all
+            self.active_interface_filters = None;
+            self.status_message = "Filter cleared - showing all interfaces".to_string();
+        }
+    }
+
+    /// Per-interface (download_rate, upload_rate) for the current traffic view mode
+    pub fn get_interface_rates(&self, stats: &crate::process::InterfaceStats) -> (u64, u64) {
+        match self.traffic_view_mode {
+            TrafficViewMode::All => (stats.download_rate, stats.upload_rate),
+            TrafficViewMode::Internet => (stats.internet_download_rate, stats.internet_upload_rate),
+            TrafficViewMode::Local => (stats.local_download_rate, stats.local_upload_rate),
+        }
+    }
+
+    /// Helper function to get the appropriate rates for sorting based on traffic view mode
+    /// Returns (download_rate, total_download, upload_rate, total_upload)
+    fn get_sort_rates(&self, process: &ProcessInfo) -> (u64, u64, u64, u64) {
+        match self.traffic_view_mode {
+            TrafficViewMode::All => (
+                process.download_rate,
+                process.total_download,
+                process.upload_rate,
+                process.total_upload,
+            ),
+            TrafficViewMode::Internet => (
+                process.internet_download_rate,
+                process.internet_total_download,
+                process.internet_upload_rate,
+                process.internet_total_upload,
+            ),
+            TrafficViewMode::Local => (
+                process.local_download_rate,
+                process.local_total_download,
+                process.local_upload_rate,
+                process.local_total_upload,
+            ),
+        }
+    }
+
+    /// Deterministic multi-level ordering used for the process list
+    fn compare_processes(&self, a: &ProcessInfo, b: &ProcessInfo) -> std::cmp::Ordering {
+        use std::cmp::Ordering;
+
+        // 0. Pinned processes always go to top
+        match (
+            self.pinned_pids.contains(&a.pid),
+            self.pinned_pids.contains(&b.pid),
+        ) {
+            (true, false) => return Ordering::Less,
+            (false, true) => return Ordering::Greater,
+            _ => {}
+        }
+
+        // 1. Terminated processes always go to bottom
+        match (a.is_terminated, b.is_terminated) {
+            (true, false) => return Ordering::Greater, // a terminated, b active -> a goes after b
+            (false, true) => return Ordering::Less,    // a active, b terminated -> a goes before b
+            _ => {}                                    // Both same state, continue to next criteria
+        }
+
+        // 1b. High-priority throttles above everything else, low-priority ones below
+        // (unthrottled processes count as normal)
+        let priority = |p: &ProcessInfo| {
+            p.throttle_limit
+                .as_ref()
+                .map(|limit| limit.priority)
+                .unwrap_or_default()
+        };
+        match priority(a).cmp(&priority(b)) {
+            Ordering::Equal => {}
+            other => return other,
+        }
+
+        // Get rates based on current traffic view mode
+        let (a_dl_rate, a_dl_total, a_ul_rate, a_ul_total) = self.get_sort_rates(a);
+        let (b_dl_rate, b_dl_total, b_ul_rate, b_ul_total) = self.get_sort_rates(b);
+
+        // 2. Download rate (descending - higher rates first)
+        match b_dl_rate.cmp(&a_dl_rate) {
+            Ordering::Equal => {} // Continue to next criteria
+            other => return other,
+        }
+
+        // 3. Total download (descending - higher totals first)
+        match b_dl_total.cmp(&a_dl_total) {
+            Ordering::Equal => {}
+            other => return other,
+        }
+
+        // 4. Upload rate (descending - higher rates first)
+        match b_ul_rate.cmp(&a_ul_rate) {
+            Ordering::Equal => {}
+            other => return other,
+        }
+
+        // 5. Total upload (descending - higher totals first)
+        match b_ul_total.cmp(&a_ul_total) {
+            Ordering::Equal => {}
+            other => return other,
+        }
+
+        // 6. Throttle status (throttled processes first for visibility)
+        match (a.is_throttled(), b.is_throttled()) {
+            (true, false) => return Ordering::Less, // a throttled, b not -> a goes first
+            (false, true) => return Ordering::Greater, // a not throttled, b is -> b goes first
+            _ => {}                                 // Both same throttle state, continue
+        }
+
+        // 7. Process name (alphabetical)
+        match a.name.cmp(&b.name) {
+            Ordering::Equal => {}
+            other => return other,
+        }
+
+        // 8. PID (ascending - smaller PIDs first for determinism)
+        a.pid.cmp(&b.pid)
+    }
+
+    pub fn update_processes(&mut self, process_map: ProcessMap) {
+        let mut processes: Vec<ProcessInfo>;
+        let interface_detail_pid = self.get_interface_detail_process().map(|p| p.pid);
+        self.update_retransmit_baselines(&process_map);
+
+        // Sort first (applies to both filtered and unfiltered lists)
+        if self.sort_frozen {
+            // FREEZE MODE: Use frozen snapshot, only update stats
+
+            // Start with the frozen snapshot
+            let mut frozen_processes = self.frozen_process_snapshot.clone();
+
+            // Update existing processes with new data from process_map
+            for frozen_proc in &mut frozen_processes {
+                if let Some(updated_proc) = process_map.get(&frozen_proc.pid) {
+                    // Process still exists - update its stats
+                    frozen_proc.download_rate = updated_proc.download_rate;
+                    frozen_proc.upload_rate = updated_proc.upload_rate;
+                    frozen_proc.total_download = updated_proc.total_download;
+                    frozen_proc.total_upload = updated_proc.total_upload;
+                    frozen_proc.internet_download_rate = updated_proc.internet_download_rate;
+                    frozen_proc.internet_upload_rate = updated_proc.internet_upload_rate;
+                    frozen_proc.internet_total_download = updated_proc.internet_total_download;
+                    frozen_proc.internet_total_upload = updated_proc.internet_total_upload;
+                    frozen_proc.local_download_rate = updated_proc.local_download_rate;
+                    frozen_proc.local_upload_rate = updated_proc.local_upload_rate;
+                    frozen_proc.local_total_download = updated_proc.local_total_download;
+                    frozen_proc.local_total_upload = updated_proc.local_total_upload;
+                    frozen_proc.tcp_download_rate = updated_proc.tcp_download_rate;
+                    frozen_proc.tcp_upload_rate = updated_proc.tcp_upload_rate;
+                    frozen_proc.tcp_total_download = updated_proc.tcp_total_download;
+                    frozen_proc.tcp_total_upload = updated_proc.tcp_total_upload;
+                    frozen_proc.udp_download_rate = updated_proc.udp_download_rate;
+                    frozen_proc.udp_upload_rate = updated_proc.udp_upload_rate;
+                    frozen_proc.udp_total_download = updated_proc.udp_total_download;
+                    frozen_proc.udp_total_upload = updated_proc.udp_total_upload;
+                    frozen_proc.retransmit_rate = updated_proc.retransmit_rate;
+                    frozen_proc.avg_rtt_ms = updated_proc.avg_rtt_ms;
+                    frozen_proc.throttle_limit = updated_proc.throttle_limit.clone();
+                    frozen_proc.interface_stats = updated_proc.interface_stats.clone();
+                    frozen_proc.connections = updated_proc.connections.clone();
+                    frozen_proc.parent_pid = updated_proc.parent_pid;
+                    frozen_proc.is_terminated = updated_proc.is_terminated;
+                    frozen_proc.has_active_transfer = updated_proc.has_active_transfer;
+                    frozen_proc.listen_only = updated_proc.listen_only;
+                } else {
+                    // Process no longer exists - mark as terminated but keep in list
+                    frozen_proc.is_terminated = true;
+                    frozen_proc.download_rate = 0;
+                    frozen_proc.upload_rate = 0;
+                    frozen_proc.internet_download_rate = 0;
+                    frozen_proc.internet_upload_rate = 0;
+                    frozen_proc.local_download_rate = 0;
+                    frozen_proc.local_upload_rate = 0;
+                    frozen_proc.tcp_download_rate = 0;
+                    frozen_proc.tcp_upload_rate = 0;
+                    frozen_proc.udp_download_rate = 0;
+                    frozen_proc.udp_upload_rate = 0;
+                    frozen_proc.retransmit_rate = 0.0;
+                }
+            }
+
+            // Terminated processes leave the snapshot once they're past the retention
+            let now = Instant::now();
+            for frozen_proc in &frozen_processes {
+                if frozen_proc.is_terminated {
+                    self.frozen_terminated_at
+                        .entry(frozen_proc.pid)
+                        .or_insert(now);
+                } else {
+                    self.frozen_terminated_at.remove(&frozen_proc.pid);
+                }
+            }
+            let terminated_at = &self.frozen_terminated_at;
+            frozen_processes.retain(|p| {
+                terminated_at
+                    .get(&p.pid)
+                    .is_none_or(|at| is_terminated_retained(now.duration_since(*at)))
+            });
+
+            // Find and add any NEW processes (not in snapshot) to the end
+            let frozen_pids: HashSet<i32> = frozen_processes.iter().map(|p| p.pid).collect();
+            self.frozen_terminated_at
+                .retain(|pid, _| frozen_pids.contains(pid));
+            self.frozen_order.retain(|pid, _| frozen_pids.contains(pid));
+            let mut new_processes: Vec<ProcessInfo> = process_map
+                .into_values()
+                .filter(|p| !frozen_pids.contains(&p.pid))
+                .collect();
+
+            // Add new processes to frozen_order map
+            let current_max = self.frozen_order.values().max().copied().unwrap_or(0);
+            let mut next_pos = current_max + 1;
+            for process in &new_processes {
+                self.frozen_order.insert(process.pid, next_pos);
+                next_pos += 1;
+            }
+
+            // Append new processes to the end
+            frozen_processes.append(&mut new_processes);
+
+            // Pinned processes stay above the frozen order
+            frozen_processes.sort_by_key(|p| !self.pinned_pids.contains(&p.pid));
+
+            // Update the frozen snapshot
+            self.frozen_process_snapshot = frozen_processes.clone();
+
+            processes = frozen_processes;
+        } else {
+            // NORMAL MODE: Use fresh data from process_map
+            processes = process_map.into_values().collect();
+            // NORMAL MODE: Deterministic multi-level sort to prevent UI jumping
+            // Priority: terminated status -> DL rate -> total DL -> UL rate -> total UL -> throttle status -> name -> PID
+            processes.sort_by(|a, b| self.compare_processes(a, b));
+        }
+
+        // Drop graph history of processes no longer listed (terminated past the retention)
+        let listed: HashSet<i32> = processes.iter().map(|p| p.pid).collect();
+        let purged: Vec<i32> = self
+            .history
+            .histories
+            .keys()
+            .filter(|pid| !listed.contains(pid))
+            .copied()
+            .collect();
+        for pid in purged {
+            self.history.remove(pid);
+        }
+
+        // Store sorted unfiltered list (for InterfaceDetail view)
+        self.unfiltered_process_list = processes.clone();
+
+        // Keep the InterfaceDetail cursor on the same process as the list re-sorts
+        if self.interface_detail_index.is_some() {
+            let index = interface_detail_pid
+                .and_then(|pid| {
+                    self.interface_detail_processes()
+                        .iter()
+                        .position(|p| p.pid == pid)
+                })
+                .or(self.interface_detail_index);
+            self.select_interface_detail_index(index);
+        }
+
+        // Apply interface filter for ProcessView/InterfaceList views
+        processes = self.apply_process_filter(processes);
+
+        self.set_visible_processes(processes);
+
+        // Keep the cursor on the same process wherever it moved to
+        self.relocate_selection();
+    }
+
+    /// Set the displayed process list, arranged as a tree when tree view is on (after
+    /// grouping containers into one row each when container view is on)
+    fn set_visible_processes(&mut self, mut processes: Vec<ProcessInfo>) {
+        self.container_rows.clear();
+        if self.container_view {
+            self.update_containers();
+            let compare = |a: &ProcessInfo, b: &ProcessInfo| self.compare_processes(a, b);
+            let compare_rows: Option<crate::process_tree::RootOrder> =
+                if self.sort_frozen || self.tree_view {
+                    None
+                } else {
+                    Some(&compare)
+                };
+            let (list, rows) =
+                crate::container_view::group(processes, &self.containers, compare_rows);
+            processes = list;
+            self.container_rows = rows;
+        }
+
+        if self.tree_view {
+            // Top-level rows sort by subtree totals; frozen mode keeps the snapshot order
+            let compare = |a: &ProcessInfo, b: &ProcessInfo| self.compare_processes(a, b);
+            let compare_roots: Option<crate::process_tree::RootOrder> = if self.sort_frozen {
+                None
+            } else {
+                Some(&compare)
+            };
+            let (list, rows) =
+                crate::process_tree::flatten(processes, &self.expanded_pids, compare_roots);
+            self.process_list = list;
+            self.tree_rows = rows;
+        } else {
+            self.process_list = processes;
+            self.tree_rows.clear();
+        }
+    }
+
+    /// Look up the container of listed PIDs not seen before, forgetting unlisted ones
+    fn update_containers(&mut self) {
+        let listed: HashSet<i32> = self.unfiltered_process_list.iter().map(|p| p.pid).collect();
+        self.containers.retain(|pid, _| listed.contains(pid));
+        for pid in listed {
+            self.containers
+                .entry(pid)
+                .or_insert_with(|| container_of(std::path::Path::new("/proc"), pid));
+        }
+    }
+
+    /// Rebuild the visible list from the last update (after tree changes), keeping selection
+    fn rebuild_visible_processes(&mut self) {
+        let processes = self.apply_process_filter(self.unfiltered_process_list.clone());
+        self.set_visible_processes(processes);
+        self.relocate_selection();
+    }
+
+    /// Select the row for a PID (falls back to clamping the current selection)
+    fn select_pid(&mut self, pid: Option<i32>) {
+        if pid.is_some() {
+            self.selected_pid = pid;
+        }
+        self.relocate_selection();
+    }
+
+    /// Move the cursor to the selected process's row after the list changed; when it's
+    /// no longer listed (exited, filtered out) the cursor stays at the nearest row and
+    /// follows the process now there. An empty list keeps the selection for later.
+    fn relocate_selection(&mut self) {
+        if self.process_list.is_empty() {
+            return;
+        }
+        let index = self
+            .selected_pid
+            .and_then(|pid| self.process_list.iter().position(|p| p.pid == pid))
+            .or_else(|| {
+                self.selected_index
+                    .map(|idx| idx.min(self.process_list.len() - 1))
+            });
+        self.set_selected_index(index);
+    }
+
+    /// Select a visible row (e.g. clicked), anchoring the selection to its process
+    pub fn select_index(&mut self, index: usize) {
+        if index < self.process_list.len() {
+            self.set_selected_index(Some(index));
+        }
+    }
+
+    fn set_selected_index(&mut self, index: Option<usize>) {
+        self.selected_index = index;
+        self.selected_pid = index
+            .and_then(|idx| self.process_list.get(idx))
+            .map(|p| p.pid);
+        self.list_state.select(index);
+    }
+
+    /// Toggle between the flat process list and the process tree
+    pub fn toggle_tree_view(&mut self) {
+        self.tree_view = !self.tree_view;
+        self.rebuild_visible_processes();
+        self.status_message = if self.tree_view {
+            "Tree view - ←/→ to collapse/expand children".to_string()
+        } else {
+            "Flat process list".to_string()
+        };
+    }
+
+    /// Toggle grouping each container's processes into one row
+    pub fn toggle_container_view(&mut self) {
+        self.container_view = !self.container_view;
+        self.rebuild_visible_processes();
+        self.status_message = if self.container_view {
+            match self.container_rows.len() {
+                0 => "Container view - no docker/podman containers in the list".to_string(),
+                count => format!("Container view - {} container(s), one row each", count),
+            }
+        } else {
+            "Container processes listed individually".to_string()
+        };
+    }
+
+    /// Toggle between process names and command lines in the process list
+    pub fn toggle_cmdline(&mut self) {
+        self.show_cmdline = !self.show_cmdline;
+        self.status_message = if self.show_cmdline {
+            "Showing command lines".to_string()
+        } else {
+            "Showing process names".to_string()
+        };
+    }
+
+    /// Mark or unmark a process to leave it alone in "throttle everything except"
+    pub fn toggle_mark(&mut self, pid: i32) {
+        if !self.marked_pids.remove(&pid) {
+            self.marked_pids.insert(pid);
+        }
+    }
+
+    /// Pin or unpin a process to the top of the list, moving it right away
+    pub fn toggle_pin(&mut self, pid: i32, name: &str) {
+        if self.pinned_pids.remove(&pid) {
+            self.status_message = format!("Unpinned {} (PID {})", name, pid);
+        } else {
+            self.pinned_pids.insert(pid);
+            self.status_message = format!("Pinned {} (PID {}) to the top", name, pid);
+        }
+
+        let mut processes = std::mem::take(&mut self.unfiltered_process_list);
+        if self.sort_frozen {
+            processes.sort_by_key(|p| !self.pinned_pids.contains(&p.pid));
+        } else {
+            processes.sort_by(|a, b| self.compare_processes(a, b));
+        }
+        self.unfiltered_process_list = processes;
+        self.rebuild_visible_processes();
+    }
+
+    /// Names of the marked processes still running, sorted and deduplicated
+    pub fn marked_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .unfiltered_process_list
+            .iter()
+            .filter(|proc| self.marked_pids.contains(&proc.pid) && !proc.is_terminated)
+            .map(|proc| proc.name.clone())
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Toggle the TCP health (retransmits/RTT) column in the process list
+    pub fn toggle_tcp_health(&mut self) {
+        self.show_tcp_health = !self.show_tcp_health;
+        self.status_message = if self.show_tcp_health {
+            "Showing TCP retransmits and RTT".to_string()
+        } else {
+            "Hiding TCP retransmits and RTT".to_string()
+        };
+    }
+
+    /// Toggle listing idle processes that only have listening sockets
+    pub fn toggle_listen_only(&mut self) {
+        self.show_listen_only = !self.show_listen_only;
+        self.rebuild_visible_processes();
+        self.status_message = if self.show_listen_only {
+            "Showing listen-only processes (👂)".to_string()
+        } else {
+            format!(
+                "Hiding {} listen-only process(es)",
+                self.hidden_listen_only_count()
+            )
+        };
+    }
+
+    /// Live processes that moved bytes within the last minute
+    pub fn active_process_count(&self) -> usize {
+        self.unfiltered_process_list
+            .iter()
+            .filter(|p| p.has_active_transfer && !p.is_terminated)
+            .count()
+    }
+
+    /// Listen-only processes left out of the list
+    pub fn hidden_listen_only_count(&self) -> usize {
+        if self.show_listen_only {
+            return 0;
+        }
+        self.unfiltered_process_list
+            .iter()
+            .filter(|p| self.is_hidden_listen_only(p))
+            .count()
+    }
+
+    /// Idle listeners are hidden unless asked for, or throttled or pinned (so they can
+    /// still be managed)
+    fn is_hidden_listen_only(&self, process: &ProcessInfo) -> bool {
+        !self.show_listen_only
+            && process.listen_only
+            && !process.is_throttled()
+            && !self.pinned_pids.contains(&process.pid)
+    }
+
+    /// Follow each process's retransmit rate while it's unthrottled, so a throttle that
+    /// makes it retransmit more stands out. Throttled processes keep their last baseline
+    fn update_retransmit_baselines(&mut self, process_map: &ProcessMap) {
+        self.retransmit_baselines
+            .retain(|pid, _| process_map.contains_key(pid));
+        for process in process_map.values() {
+            if !process.is_throttled() {
+                let baseline = self
+                    .retransmit_baselines
+                    .entry(process.pid)
+                    .or_insert(process.retransmit_rate);
+                *baseline += (process.retransmit_rate - *baseline) * RETRANSMIT_BASELINE_WEIGHT;
+            }
+        }
+    }
+
+    /// Whether a throttled process retransmits well above its unthrottled baseline
+    /// (a drop-based backend hurting its flows)
+    pub fn retransmit_spike(&self, process: &ProcessInfo) -> bool {
+        let baseline = self
+            .retransmit_baselines
+            .get(&process.pid)
+            .copied()
+            .unwrap_or(0.0);
+        process.is_throttled()
+            && process.retransmit_rate >= RETRANSMIT_SPIKE_MIN_RATE
+            && process.retransmit_rate > baseline * RETRANSMIT_SPIKE_FACTOR
+    }
+
+    /// Fetch command lines for PIDs seen for the first time and forget PIDs that are gone
+    pub fn refresh_cmdlines(&mut self, process_utils: &dyn ProcessUtils) {
+        if !self.show_cmdline {
+            return;
+        }
+        let pids: HashSet<i32> = self.unfiltered_process_list.iter().map(|p| p.pid).collect();
+        self.cmdlines.retain(|pid, _| pids.contains(pid));
+        for pid in pids {
+            self.cmdlines
+                .entry(pid)
+                .or_insert_with(|| process_utils.get_cmdline(pid).ok());
+        }
+    }
+
+    /// Command line to show for a process, if command lines are on and it has one
+    pub(super) fn cmdline_for(&self, pid: i32) -> Option<&str> {
+        if !self.show_cmdline {
+            return None;
+        }
+        self.cmdlines.get(&pid)?.as_deref()
+    }
+
+    /// Tree row of the selected process (tree view only)
+    pub fn get_selected_tree_row(&self) -> Option<&TreeRow> {
+        if !self.tree_view {
+            return None;
+        }
+        self.selected_index.and_then(|idx| self.tree_rows.get(idx))
+    }
+
+    /// Show the children of the selected tree row
+    pub fn expand_selected(&mut self) {
+        let Some(pid) = self.get_selected_process().map(|p| p.pid) else {
+            return;
+        };
+        if self
+            .get_selected_tree_row()
+            .is_some_and(|row| row.has_children() && !row.expanded)
+        {
+            self.expanded_pids.insert(pid);
+            self.rebuild_visible_processes();
+        }
+    }
+
+    /// Hide the children of the selected tree row, or jump to its parent row
+    pub fn collapse_selected(&mut self) {
+        let Some(process) = self.get_selected_process() else {
+            return;
+        };
+        let (pid, parent_pid) = (process.pid, process.parent_pid);
+
+        match self.get_selected_tree_row() {
+            Some(row) if row.expanded && row.has_children() => {
+                self.expanded_pids.remove(&pid);
+                self.rebuild_visible_processes();
+            }
+            Some(row) if row.depth > 0 => self.select_pid(parent_pid),
+            _ => {}
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        if self.process_list.is_empty() {
+            return;
+        }
+
+        let new_index = match self.selected_index {
+            None => 0, // If nothing selected, select first item
+            Some(idx) => (idx + 1) % self.process_list.len(),
+        };
+
+        self.set_selected_index(Some(new_index));
+    }
+
+    pub fn select_previous(&mut self) {
+        if self.process_list.is_empty() {
+            return;
+        }
+
+        let new_index = match self.selected_index {
+            None => 0, // If nothing selected, select first item
+            Some(0) => self.process_list.len() - 1,
+            Some(idx) => idx - 1,
+        };
+
+        self.set_selected_index(Some(new_index));
+    }
+
+    pub fn get_selected_process(&self) -> Option<&ProcessInfo> {
+        self.selected_index
+            .and_then(|idx| self.process_list.get(idx))
+    }
+
+    /// Terminated processes still listed (they're dropped once past the retention)
+    pub fn terminated_count(&self) -> usize {
+        self.unfiltered_process_list
+            .iter()
+            .filter(|p| p.is_terminated)
+            .count()
+    }
+
+    /// Toggle sort freeze mode
+    pub fn toggle_sort_freeze(&mut self) {
+        self.sort_frozen = !self.sort_frozen;
+
+        if self.sort_frozen {
+            // Entering freeze mode - capture current process list as snapshot
+            self.frozen_order.clear();
+            for (index, process) in self.process_list.iter().enumerate() {
+                self.frozen_order.insert(process.pid, index);
+            }
+
+            // Store complete snapshot of current process list
+            self.frozen_process_snapshot = self.process_list.clone();
+        } else {
+            // Exiting freeze mode - clear frozen data
+            self.frozen_order.clear();
+            self.frozen_process_snapshot.clear();
+            self.frozen_terminated_at.clear();
+        }
+    }
+
+    /// Apply interface filter to process list (hiding listen-only processes first)
+    fn apply_process_filter(&self, mut processes: Vec<ProcessInfo>) -> Vec<ProcessInfo> {
+        processes.retain(|proc| !self.is_hidden_listen_only(proc));
+
+        match &self.active_interface_filters {
+            None => {
+                // No filter - show all processes
+                processes
+            }
+            Some(filters) if filters.is_empty() => {
+                // Empty filter - show nothing
+                vec![]
+            }
+            Some(filters) => {
+                // Filter to processes using these interfaces
+                processes.retain(|proc| {
+                    // Keep process if it uses ANY of the filtered interfaces
+                    proc.interface_stats
+                        .keys()
+                        .any(|iface_name| interface_filter::matches(filters, iface_name))
+                });
+                processes
+            }
+        }
+    }
+
+    /// Build backend items list for interactive backend modal
+    pub fn build_backend_items(&mut self, backend_info: &BackendInfo) {
+        // Save current selection if any (to preserve cursor position across rebuilds)
+        let current_backend_name = self
+            .backend_items
+            .get(self.backend_selected_index)
+            .and_then(|item| match item {
+                BackendSelectorItem::Backend { name, .. } => Some(name.clone()),
+                _ => None,
+            });
+
+        self.backend_items.clear();
+
+        // Monitor group
+        if !backend_info.available_monitors.is_empty() {
+            self.backend_items
+                .push(BackendSelectorItem::GroupHeader(BackendGroup::Monitor));
+            for (name, priority, available) in &backend_info.available_monitors {
+                let is_current = backend_info.active_monitoring.as_ref() == Some(name);
+                self.backend_items.push(BackendSelectorItem::Backend {
+                    name: name.clone(),
+                    group: BackendGroup::Monitor,
+                    priority: *priority,
+                    available: *available,
+                    is_current_default: is_current,
+                });
+            }
+        }
+
+        // Socket Mapper group
+        if !backend_info.available_socket_mappers.is_empty() {
+            self.backend_items
+                .push(BackendSelectorItem::GroupHeader(BackendGroup::SocketMapper));
+            for (name, priority, available) in &backend_info.available_socket_mappers {
+                let is_current = backend_info.active_socket_mapper.as_ref() == Some(name);
+                self.backend_items.push(BackendSelectorItem::Backend {
+                    name: name.clone(),
+                    group: BackendGroup::SocketMapper,
+                    priority: *priority,
+                    available: *available,
+                    is_current_default: is_current,
+                });
+            }
+        }
+
+        // Upload group
+        if !backend_info.available_upload.is_empty() {
+            self.backend_items
+                .push(BackendSelectorItem::GroupHeader(BackendGroup::Upload));
+            for (name, priority, available) in &backend_info.available_upload {
+                let is_current = backend_info.active_upload.as_ref() == Some(name);
+                self.backend_items.push(BackendSelectorItem::Backend {
+                    name: name.clone(),
+                    group: BackendGroup::Upload,
+                    priority: *priority,
+                    available: *available,
+                    is_current_default: is_current,
+                });
+            }
+        }
+
+        // Download group
+        if !backend_info.available_download.is_empty() {
+            self.backend_items
+                .push(BackendSelectorItem::GroupHeader(BackendGroup::Download));
+            for (name, priority, available) in &backend_info.available_download {
+                let is_current = backend_info.active_download.as_ref() == Some(name);
+                self.backend_items.push(BackendSelectorItem::Backend {
+                    name: name.clone(),
+                    group: BackendGroup::Download,
+                    priority: *priority,
+                    available: *available,
+                    is_current_default: is_current,
+                });
+            }
+        }
+
+        // Try to restore previous selection (preserves cursor position)
+        if let Some(name) = current_backend_name {
+            if let Some(index) = self.backend_items.iter().position(
+                |item| matches!(item, BackendSelectorItem::Backend { name: n, .. } if n == &name),
+            ) {
+                self.backend_selected_index = index;
+                return; // Found it, keep cursor there!
+            }
+        }
+
+        // Fallback: find first available backend item and select it
+        self.backend_selected_index = self
+            .backend_items
+            .iter()
+            .position(|item| {
+                matches!(
+                    item,
+                    BackendSelectorItem::Backend {
+                        available: true,
+                        ..
+                    }
+                )
+            })
+            .unwrap_or(0);
+    }
+
+    pub fn select_next_backend(&mut self) {
+        if self.backend_items.is_empty() {
+            return;
+        }
+
+        // Move down, skip group headers and unavailable backends
+        let start_index = self.backend_selected_index;
+        loop {
+            self.backend_selected_index =
+                (self.backend_selected_index + 1) % self.backend_items.len();
+
+            if let Some(BackendSelectorItem::Backend { available, .. }) =
+                self.backend_items.get(self.backend_selected_index)
+            {
+                if *available {
+                    break;
+                }
+            }
+
+            // Prevent infinite loop if no available backends
+            if self.backend_selected_index == start_index {
+                break;
+            }
+        }
+    }
+
+    pub fn select_previous_backend(&mut self) {
+        if self.backend_items.is_empty() {
+            return;
+        }
+
+        // Move up, skip group headers and unavailable backends
+        let start_index = self.backend_selected_index;
+        loop {
+            self.backend_selected_index = if self.backend_selected_index == 0 {
+                self.backend_items.len() - 1
+            } else {
+                self.backend_selected_index - 1
+            };
+
+            if let Some(BackendSelectorItem::Backend { available, .. }) =
+                self.backend_items.get(self.backend_selected_index)
+            {
+                if *available {
+                    break;
+                }
+            }
+
+            // Prevent infinite loop if no available backends
+            if self.backend_selected_index == start_index {
+                break;
+            }
+        }
+    }
+
+    /// Get the currently selected backend (for immediate apply)
+    pub fn get_selected_backend(&self) -> Option<(&str, BackendGroup)> {
+        if let Some(BackendSelectorItem::Backend {
+            name,
+            group,
+            available,
+            ..
+        }) = self.backend_items.get(self.backend_selected_index)
+        {
+            if *available {
+                return Some((name.as_str(), *group));
+            }
+        }
+        None
+    }
+
+    // Process detail view navigation methods
+
+    /// Enter process detail view for the selected process
+    pub fn enter_process_detail(&mut self) {
+        if let Some(process) = self.get_target_process() {
+            self.selected_process_detail_pid = Some(process.pid);
+            self.detail_throttle_stats = (None, None);
+            self.detail_backlog_growth = (0, 0);
+            self.detail_scroll_offset = 0;
+            self.detail_tab = ProcessDetailTab::Overview;
+            self.detail_connection_index = 0;
+            self.last_detail_connection_selection = None;
+            self.process_detail_return = self.view_mode;
+            self.view_mode = ViewMode::ProcessDetail;
+        }
+    }
+
+    /// Store freshly polled throttle stats of the detailed process, tracking
+    /// whether its queues keep growing
+    pub fn update_detail_throttle_stats(
+        &mut self,
+        stats: (Option<BackendStats>, Option<BackendStats>),
+    ) {
+        fn growth(
+            previous: &Option<BackendStats>,
+            current: &Option<BackendStats>,
+            polls: u32,
+        ) -> u32 {
+            let backlog =
+                |stats: &Option<BackendStats>| stats.as_ref().and_then(|s| s.backlog_bytes);
+            match (backlog(previous), backlog(current)) {
+                (Some(previous), Some(current)) if current > previous => polls + 1,
+                // An unchanged non-empty queue isn't draining either
+                (Some(previous), Some(current)) if current == previous && current > 0 => polls,
+                _ => 0,
+            }
+        }
+
+        let (upload_polls, download_polls) = self.detail_backlog_growth;
+        self.detail_backlog_growth = (
+            growth(&self.detail_throttle_stats.0, &stats.0, upload_polls),
+            growth(&self.detail_throttle_stats.1, &stats.1, download_polls),
+        );
+        self.detail_throttle_stats = stats;
+    }
+
+    /// Whether the (upload, download) queues of the detailed process have grown for
+    /// several polls in a row, i.e. shaping is building up latency (bufferbloat)
+    pub fn detail_backlog_growing(&self) -> (bool, bool) {
+        const GROWING_POLLS: u32 = 3;
+        (
+            self.detail_backlog_growth.0 >= GROWING_POLLS,
+            self.detail_backlog_growth.1 >= GROWING_POLLS,
+        )
+    }
+
+    /// Exit process detail view and return to the list it was opened from
+    pub fn exit_process_detail(&mut self) {
+        self.view_mode = self.process_detail_return;
+        self.selected_process_detail_pid = None;
+        self.detail_scroll_offset = 0;
+    }
+
+    /// Move to the next detail tab
+    pub fn next_detail_tab(&mut self) {
+        self.detail_tab = match self.detail_tab {
+            ProcessDetailTab::Overview => ProcessDetailTab::Connections,
+            ProcessDetailTab::Connections => ProcessDetailTab::Traffic,
+            ProcessDetailTab::Traffic => ProcessDetailTab::System,
+            ProcessDetailTab::System => ProcessDetailTab::Overview,
+        };
+        self.detail_scroll_offset = 0; // Reset scroll when changing tabs
+        self.last_detail_connection_selection = None;
+    }
+
+    /// Move to the previous detail tab
+    pub fn previous_detail_tab(&mut self) {
+        self.detail_tab = match self.detail_tab {
+            ProcessDetailTab::Overview => ProcessDetailTab::System,
+            ProcessDetailTab::Connections => ProcessDetailTab::Overview,
+            ProcessDetailTab::Traffic => ProcessDetailTab::Connections,
+            ProcessDetailTab::System => ProcessDetailTab::Traffic,
+        };
+        self.detail_scroll_offset = 0; // Reset scroll when changing tabs
+        self.last_detail_connection_selection = None;
+    }
+
+    /// Scroll detail view up
+    pub fn scroll_detail_up(&mut self) {
+        self.detail_scroll_offset = self.detail_scroll_offset.saturating_sub(1);
+    }
+
+    /// Scroll detail view down
+    pub fn scroll_detail_down(&mut self) {
+        self.detail_scroll_offset = self.detail_scroll_offset.saturating_add(1);
+    }
+
+    /// Move the connection cursor up on the Connections tab, scroll on the others
+    pub fn detail_move_up(&mut self) {
+        if self.detail_tab == ProcessDetailTab::Connections && self.detail_connection_index > 0 {
+            self.detail_connection_index -= 1;
+        } else {
+            self.scroll_detail_up();
+        }
+    }
+
+    /// Move the connection cursor down on the Connections tab (scrolling on past the
+    /// last connection, to the events), scroll on the others
+    pub fn detail_move_down(&mut self) {
+        let connections = self
+            .get_detail_process()
+            .map_or(0, |process| process.connections.len());
+        if self.detail_tab == ProcessDetailTab::Connections
+            && self.detail_connection_index + 1 < connections
+        {
+            self.detail_connection_index += 1;
+        } else {
+            self.scroll_detail_down();
+        }
+    }
+
+    /// What yank (`y`) copies from the current view: the selected connection on the
+    /// Connections tab as "proto local remote state", otherwise the selected process
+    /// as "pid name dl_rate ul_rate total_dl total_ul" (tab-separated, bytes/s and bytes)
+    pub fn yank_text(&self) -> Option<String> {
+        if self.view_mode == ViewMode::ProcessDetail {
+            let process = self.get_detail_process()?;
+            if self.detail_tab == ProcessDetailTab::Connections {
+                let connection = sorted_connections(process)
+                    .into_iter()
+                    .nth(self.detail_connection_index)?;
+                return Some(connection_summary(connection));
+            }
+            return Some(process_summary(process));
+        }
+        self.get_target_process().map(process_summary)
+    }
+
+    /// Get the process being detailed (if still exists in process list)
+    pub fn get_detail_process(&self) -> Option<&ProcessInfo> {
+        if let Some(pid) = self.selected_process_detail_pid {
+            self.process_list.iter().find(|p| p.pid == pid)
+        } else {
+            None
+        }
+    }
+
+    // Modal scroll methods
+
+    /// Reset help overlay scroll when opened
+    pub fn reset_help_scroll(&mut self) {
+        self.help_scroll_offset = 0;
+    }
+
+    /// Scroll help overlay up
+    pub fn scroll_help_up(&mut self) {
+        self.help_scroll_offset = self.help_scroll_offset.saturating_sub(1);
+    }
+
+    /// Scroll help overlay down
+    pub fn scroll_help_down(&mut self) {
+        self.help_scroll_offset = self.help_scroll_offset.saturating_add(1);
+    }
+
+    /// Reset backend info scroll when opened
+    pub fn reset_backend_info_scroll(&mut self) {
+        self.backend_info_scroll_offset = 0;
+    }
+
+    /// Scroll backend info modal up
+    pub fn scroll_backend_info_up(&mut self) {
+        self.backend_info_scroll_offset = self.backend_info_scroll_offset.saturating_sub(1);
+    }
+
+    /// Scroll backend info modal down
+    pub fn scroll_backend_info_down(&mut self) {
+        self.backend_info_scroll_offset = self.backend_info_scroll_offset.saturating_add(1);
+    }
+
+    /// Reset interface modal scroll when opened
+    pub fn reset_interface_modal_scroll(&mut self) {
+        self.interface_modal_scroll_offset = 0;
+    }
+
+    /// Scroll interface modal up
+    pub fn scroll_interface_modal_up(&mut self) {
+        self.interface_modal_scroll_offset = self.interface_modal_scroll_offset.saturating_sub(1);
+    }
+
+    /// Scroll interface modal down
+    pub fn scroll_interface_modal_down(&mut self) {
+        self.interface_modal_scroll_offset = self.interface_modal_scroll_offset.saturating_add(1);
+    }
+
+    /// Reset backend compatibility dialog scroll when opened
+    pub fn reset_backend_compat_scroll(&mut self) {
+        self.backend_compat_scroll_offset = 0;
+    }
+
+    /// Scroll backend compatibility dialog up
+    pub fn scroll_backend_compat_up(&mut self) {
+        self.backend_compat_scroll_offset = self.backend_compat_scroll_offset.saturating_sub(1);
+    }
+
+    /// Scroll backend compatibility dialog down
+    pub fn scroll_backend_compat_down(&mut self) {
+        self.backend_compat_scroll_offset = self.backend_compat_scroll_offset.saturating_add(1);
+    }
+
+    /// Clamp scroll offset to content bounds
+    /// content_lines: Total number of lines in content
+    /// visible_height: Height of visible area (including borders)
+    pub fn clamp_scroll(scroll_offset: usize, content_lines: usize, visible_height: u16) -> usize {
+        // Account for borders (top + bottom = 2) and padding
+        let usable_height = visible_height.saturating_sub(3) as usize;
+        let max_scroll = content_lines.saturating_sub(usable_height);
+        scroll_offset.min(max_scroll)
+    }
+
+    /// Adjust scroll offset to ensure a specific line is visible
+    /// Returns the adjusted scroll offset
+    pub fn scroll_to_line(current_scroll: usize, target_line: usize, visible_height: u16) -> usize {
+        let usable_height = visible_height.saturating_sub(3) as usize;
+
+        // If target is above visible area, scroll up to show it
+        if target_line < current_scroll {
+            return target_line;
+        }
+
+        // If target is below visible area, scroll down to show it
+        let last_visible_line = current_scroll + usable_height.saturating_sub(1);
+        if target_line > last_visible_line {
+            return target_line.saturating_sub(usable_height.saturating_sub(1));
+        }
+
+        // Target is already visible
+        current_scroll
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interface_detail_selection_follows_process() {
+        let process = |pid: i32, wlan_rate: u64| {
+            let mut process = ProcessInfo::new(pid, format!("proc{}", pid));
+            process.download_rate = wlan_rate;
+            let stats = crate::process::InterfaceStats {
+                download_rate: wlan_rate,
+                upload_rate: 0,
+                total_download: 0,
+                total_upload: 0,
+                internet_download_rate: wlan_rate / 2,
+                internet_upload_rate: 0,
+                local_download_rate: wlan_rate / 2,
+                local_upload_rate: 0,
+            };
+            process.interface_stats.insert("wlan0".to_string(), stats);
+            (pid, process)
+        };
+        let mut app = AppState::new();
+        app.update_processes(ProcessMap::from([process(1, 100), process(2, 50)]));
+        app.selected_interface_name = Some("wlan0".to_string());
+        app.view_mode = ViewMode::InterfaceDetail;
+        app.select_interface_detail_index(Some(0));
+        app.select_next_in_interface_detail();
+        assert_eq!(app.get_target_process().map(|p| p.pid), Some(2));
+        // The main list selection is untouched
+        assert_eq!(app.selected_index, None);
+
+        // PID 2 becomes the busiest and moves to the top; the cursor follows it
+        app.update_processes(ProcessMap::from([process(1, 100), process(2, 500)]));
+        assert_eq!(app.interface_detail_index, Some(0));
+        assert_eq!(app.get_target_process().map(|p| p.pid), Some(2));
+
+        app.traffic_view_mode = TrafficViewMode::Internet;
+        let stats = &app.get_target_process().unwrap().interface_stats["wlan0"];
+        assert_eq!(app.get_interface_rates(stats), (250, 0));
+    }
+
+    #[test]
+    fn test_toggling_pattern_covered_interface_adds_exclusion() {
+        let mut app = app_with_interfaces(&["eth0", "tun0", "tun1"]);
+
+        // Adding a pattern from "show all" keeps the current interfaces listed
+        app.toggle_interface_pattern("tun*");
+        assert!(app.is_interface_filtered("tun5"));
+
+        app.toggle_interface_filter("tun1".to_string());
+        app.toggle_interface_filter("tun0".to_string());
+        assert!(!app.is_interface_filtered("tun1"));
+        assert!(app.is_interface_filtered("tun5"));
+        assert!(
+            app.active_interface_filters
+                .as_ref()
+                .unwrap()
+                .contains(&"!tun1".to_string())
+        );
+
+        // Checking it again drops the exclusion instead of adding the name
+        app.toggle_interface_filter("tun1".to_string());
+        assert!(app.is_interface_filtered("tun1"));
+        assert!(!app.is_interface_filtered("tun0"));
+        assert_eq!(
+            app.active_interface_filters,
+            Some(vec![
+                "!tun0".to_string(),
+                "eth0".to_string(),
+                "tun*".to_string()
+            ])
+        );
+    }
+
+    fn app_with_interfaces(names: &[&str]) -> AppState {
+        let mut app = AppState::new();
+        app.interface_list = names
+            .iter()
+            .map(|name| InterfaceInfo {
+                name: name.to_string(),
+                mac_address: None,
+                ip_addresses: Vec::new(),
+                is_up: true,
+                is_loopback: false,
+                mtu: None,
+                speed_mbps: None,
+                operstate: None,
+                driver: None,
+                errors: None,
+                total_download_rate: 0,
+                total_upload_rate: 0,
+                process_count: 0,
+            })
+            .collect();
+        app
+    }
+
+    #[test]
+    fn test_interface_error_increases_between_updates() {
+        let mut app = AppState::new();
+        let update = |rx_errors| {
+            let mut iface = app_with_interfaces(&["eth0"]).interface_list.remove(0);
+            iface.errors = Some(InterfaceErrors {
+                rx_errors,
+                ..InterfaceErrors::default()
+            });
+            InterfaceMap::from([(iface.name.clone(), iface)])
+        };
+
+        // Nothing to compare against on the first update
+        app.update_interfaces(update(5));
+        assert!(app.interface_error_increases.is_empty());
+
+        app.update_interfaces(update(8));
+        assert_eq!(app.interface_error_increases["eth0"].rx_errors, 3);
+
+        app.update_interfaces(update(8));
+        assert_eq!(app.interface_error_increases["eth0"].rx_errors, 0);
+    }
+
+    #[test]
+    fn test_toggling_only_interface_off_shows_nothing() {
+        let mut app = app_with_interfaces(&["eth0"]);
+        assert_eq!(app.active_interface_filters, None);
+
+        app.toggle_interface_filter("eth0".to_string());
+        assert_eq!(app.active_interface_filters, Some(vec![]));
+        assert!(!app.is_interface_filtered("eth0"));
+
+        // Checking it again means every interface is selected, i.e. no filter
+        app.toggle_interface_filter("eth0".to_string());
+        assert_eq!(app.active_interface_filters, None);
+        assert!(app.is_interface_filtered("eth0"));
+    }
+
+    #[test]
+    fn test_toggling_last_interface_back_on_collapses_to_all() {
+        let mut app = app_with_interfaces(&["eth0", "wlan0"]);
+
+        // Unchecking one from "show all" keeps the others listed explicitly
+        app.toggle_interface_filter("wlan0".to_string());
+        assert_eq!(app.active_interface_filters, Some(vec!["eth0".to_string()]));
+
+        app.toggle_interface_filter("eth0".to_string());
+        assert_eq!(app.active_interface_filters, Some(vec![]));
+
+        app.toggle_interface_filter("wlan0".to_string());
+        assert_eq!(
+            app.active_interface_filters,
+            Some(vec!["wlan0".to_string()])
+        );
+        assert!(!app.is_interface_filtered("eth0"));
+
+        app.toggle_interface_filter("eth0".to_string());
+        assert_eq!(app.active_interface_filters, None);
+    }
+
+    #[test]
+    fn test_toggle_all_interfaces_flips_between_all_and_none() {
+        let mut app = app_with_interfaces(&["eth0", "wlan0"]);
+
+        app.toggle_all_interface_filters();
+        assert_eq!(app.active_interface_filters, Some(vec![]));
+        assert!(!app.is_interface_filtered("eth0"));
+
+        app.toggle_all_interface_filters();
+        assert_eq!(app.active_interface_filters, None);
+
+        // A partial selection counts as "not all", so toggle-all selects everything
+        app.toggle_interface_filter("eth0".to_string());
+        app.toggle_all_interface_filters();
+        assert_eq!(app.active_interface_filters, None);
+    }
+
+    #[test]
+    fn test_clear_and_empty_interface_filters() {
+        let mut app = app_with_interfaces(&["eth0", "wlan0"]);
+
+        app.set_empty_filter();
+        assert_eq!(app.active_interface_filters, Some(vec![]));
+        assert!(!app.is_interface_filtered("wlan0"));
+
+        app.clear_interface_filters();
+        assert_eq!(app.active_interface_filters, None);
+        assert!(app.is_interface_filtered("wlan0"));
+    }
+
+    #[test]
+    fn test_frozen_snapshot_counts_terminated_processes() {
+        let process = |pid: i32| (pid, ProcessInfo::new(pid, format!("proc{}", pid)));
+        let mut app = AppState::new();
+        app.update_processes(ProcessMap::from([process(1), process(2)]));
+        app.history.update(2, "proc2".to_string(), 100, 0);
+        app.toggle_sort_freeze();
+        assert_eq!(app.terminated_count(), 0);
+
+        // PID 2 exits: it stays in the frozen list (within the retention) with its history
+        app.update_processes(ProcessMap::from([process(1)]));
+        assert_eq!(app.process_list.len(), 2);
+        assert_eq!(app.terminated_count(), 1);
+        assert!(app.history.get_history(2).is_some());
+
+        // Outside frozen mode it's gone as soon as the monitor stops reporting it
+        app.toggle_sort_freeze();
+        app.update_processes(ProcessMap::from([process(1)]));
+        assert_eq!(app.terminated_count(), 0);
+        assert!(app.history.get_history(2).is_none());
+    }
+
+    fn frozen_test_process(pid: i32, download_rate: u64) -> (i32, ProcessInfo) {
+        let mut process = ProcessInfo::new(pid, format!("proc{}", pid));
+        process.download_rate = download_rate;
+        process.upload_rate = download_rate / 2;
+        process.total_download = download_rate * 10;
+        (pid, process)
+    }
+
+    fn listed_pids(app: &AppState) -> Vec<i32> {
+        app.process_list.iter().map(|p| p.pid).collect()
+    }
+
+    #[test]
+    fn test_frozen_sort_keeps_order_while_stats_change() {
+        let mut app = AppState::new();
+        app.update_processes(ProcessMap::from([
+            frozen_test_process(1, 100),
+            frozen_test_process(2, 200),
+            frozen_test_process(3, 300),
+        ]));
+        assert_eq!(listed_pids(&app), vec![3, 2, 1]);
+        app.toggle_sort_freeze();
+
+        // PID 1 is now the busiest, but keeps its place
+        app.update_processes(ProcessMap::from([
+            frozen_test_process(1, 1000),
+            frozen_test_process(2, 200),
+            frozen_test_process(3, 5),
+        ]));
+        assert_eq!(listed_pids(&app), vec![3, 2, 1]);
+        assert_eq!(app.process_list[2].download_rate, 1000);
+        assert_eq!(app.process_list[2].total_download, 10000);
+        assert_eq!(app.process_list[0].download_rate, 5);
+
+        // Unfreezing sorts by the current rates again
+        app.toggle_sort_freeze();
+        app.update_processes(ProcessMap::from([
+            frozen_test_process(1, 1000),
+            frozen_test_process(2, 200),
+            frozen_test_process(3, 5),
+        ]));
+        assert_eq!(listed_pids(&app), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_frozen_sort_zeroes_rates_of_exited_processes() {
+        let mut app = AppState::new();
+        app.update_processes(ProcessMap::from([
+            frozen_test_process(1, 100),
+            frozen_test_process(2, 200),
+        ]));
+        app.toggle_sort_freeze();
+
+        app.update_processes(ProcessMap::from([frozen_test_process(1, 100)]));
+        assert_eq!(listed_pids(&app), vec![2, 1]);
+        let exited = &app.process_list[0];
+        assert!(exited.is_terminated);
+        assert_eq!((exited.download_rate, exited.upload_rate), (0, 0));
+        // Totals are kept for the record
+        assert_eq!(exited.total_download, 2000);
+        assert!(!app.process_list[1].is_terminated);
+
+        // A process the monitor reports as terminated is flagged the same way
+        let (_, mut closing) = frozen_test_process(1, 0);
+        closing.is_terminated = true;
+        app.update_processes(ProcessMap::from([(1, closing)]));
+        assert!(app.process_list.iter().all(|p| p.is_terminated));
+        assert_eq!(listed_pids(&app), vec![2, 1]);
+    }
+
+    #[test]
+    fn test_frozen_sort_appends_new_processes() {
+        let mut app = AppState::new();
+        app.update_processes(ProcessMap::from([
+            frozen_test_process(1, 100),
+            frozen_test_process(2, 200),
+        ]));
+        app.toggle_sort_freeze();
+
+        // New processes go to the end however busy they are, in arrival order
+        app.update_processes(ProcessMap::from([
+            frozen_test_process(1, 100),
+            frozen_test_process(2, 200),
+            frozen_test_process(3, 5000),
+        ]));
+        app.update_processes(ProcessMap::from([
+            frozen_test_process(1, 100),
+            frozen_test_process(2, 200),
+            frozen_test_process(3, 5000),
+            frozen_test_process(4, 9000),
+        ]));
+        assert_eq!(listed_pids(&app), vec![2, 1, 3, 4]);
+        assert_eq!(
+            app.frozen_order,
+            HashMap::from([(2, 0), (1, 1), (3, 2), (4, 3)])
+        );
+    }
+
+    #[test]
+    fn test_pinned_processes_sort_first() {
+        let process = |pid: i32, download_rate: u64| {
+            let mut process = ProcessInfo::new(pid, format!("proc{}", pid));
+            process.download_rate = download_rate;
+            (pid, process)
+        };
+        let processes = || ProcessMap::from([process(1, 100), process(2, 5000), process(3, 300)]);
+        let order = |app: &AppState| app.process_list.iter().map(|p| p.pid).collect::<Vec<_>>();
+
+        let mut app = AppState::new();
+        app.update_processes(processes());
+        assert_eq!(order(&app), vec![2, 3, 1]);
+
+        // Moves up right away, then stays there as rates change
+        app.toggle_pin(1, "proc1");
+        assert_eq!(order(&app), vec![1, 2, 3]);
+        app.update_processes(processes());
+        assert_eq!(order(&app), vec![1, 2, 3]);
+
+        // Above the frozen order as well
+        app.toggle_pin(1, "proc1");
+        app.toggle_sort_freeze();
+        app.toggle_pin(3, "proc3");
+        app.update_processes(processes());
+        assert_eq!(order(&app), vec![3, 2, 1]);
+    }
+
+    fn selected_pid(app: &AppState) -> Option<i32> {
+        app.get_selected_process().map(|p| p.pid)
+    }
+
+    #[test]
+    fn test_selection_follows_process_across_reorder_and_removal() {
+        let mut app = AppState::new();
+        app.update_processes(ProcessMap::from([
+            frozen_test_process(1, 100),
+            frozen_test_process(2, 200),
+            frozen_test_process(3, 300),
+        ]));
+        app.select_next();
+        app.select_next();
+        assert_eq!((app.selected_index, selected_pid(&app)), (Some(1), Some(2)));
+
+        // PID 3 drops to the bottom: the cursor stays on PID 2, now the top row
+        app.update_processes(ProcessMap::from([
+            frozen_test_process(1, 100),
+            frozen_test_process(2, 200),
+            frozen_test_process(3, 5),
+        ]));
+        assert_eq!(listed_pids(&app), vec![2, 1, 3]);
+        assert_eq!((app.selected_index, selected_pid(&app)), (Some(0), Some(2)));
+
+        // Navigation still moves by rows
+        app.select_next();
+        assert_eq!(selected_pid(&app), Some(1));
+
+        // PID 1 exits: the cursor stays on its row and takes the process now there
+        app.update_processes(ProcessMap::from([
+            frozen_test_process(2, 200),
+            frozen_test_process(3, 5),
+        ]));
+        assert_eq!((app.selected_index, selected_pid(&app)), (Some(1), Some(3)));
+        assert_eq!(app.selected_pid, Some(3));
+
+        // Past the end of a shorter list it clamps to the last row
+        app.update_processes(ProcessMap::from([frozen_test_process(2, 200)]));
+        assert_eq!((app.selected_index, selected_pid(&app)), (Some(0), Some(2)));
+    }
+
+    #[test]
+    fn test_selection_in_frozen_sort_follows_pinned_process() {
+        let mut app = AppState::new();
+        app.update_processes(ProcessMap::from([
+            frozen_test_process(1, 100),
+            frozen_test_process(2, 200),
+            frozen_test_process(3, 300),
+        ]));
+        app.toggle_sort_freeze();
+        app.select_previous();
+        app.select_previous();
+        assert_eq!(selected_pid(&app), Some(1));
+
+        // Pinning moves the process to the top of the frozen order; the cursor goes along
+        app.toggle_pin(1, "proc1");
+        app.update_processes(ProcessMap::from([
+            frozen_test_process(1, 100),
+            frozen_test_process(2, 200),
+            frozen_test_process(3, 300),
+        ]));
+        assert_eq!(listed_pids(&app), vec![1, 3, 2]);
+        assert_eq!((app.selected_index, selected_pid(&app)), (Some(0), Some(1)));
+    }
+
+    #[test]
+    fn test_selection_follows_process_across_filter_toggles() {
+        let process = |pid: i32, interface: &str, download_rate: u64| {
+            let (_, mut process) = frozen_test_process(pid, download_rate);
+            let stats = crate::process::InterfaceStats {
+                download_rate,
+                upload_rate: 0,
+                total_download: 0,
+                total_upload: 0,
+                internet_download_rate: download_rate,
+                internet_upload_rate: 0,
+                local_download_rate: 0,
+                local_upload_rate: 0,
+            };
+            process.interface_stats.insert(interface.to_string(), stats);
+            (pid, process)
+        };
+        let processes = || {
+            ProcessMap::from([
+                process(1, "eth0", 300),
+                process(2, "wlan0", 200),
+                process(3, "eth0", 100),
+            ])
+        };
+        let mut app = app_with_interfaces(&["eth0", "wlan0"]);
+        app.update_processes(processes());
+        app.select_index(2);
+        assert_eq!(selected_pid(&app), Some(3));
+
+        // Hiding wlan0 moves PID 3 up a row, and showing it again back down
+        app.toggle_interface_filter("wlan0".to_string());
+        app.update_processes(processes());
+        assert_eq!(listed_pids(&app), vec![1, 3]);
+        assert_eq!((app.selected_index, selected_pid(&app)), (Some(1), Some(3)));
+        app.toggle_interface_filter("wlan0".to_string());
+        app.update_processes(processes());
+        assert_eq!((app.selected_index, selected_pid(&app)), (Some(2), Some(3)));
+
+        // Showing nothing keeps the selection for when processes are listed again
+        app.toggle_all_interface_filters();
+        app.update_processes(processes());
+        assert!(app.process_list.is_empty());
+        assert_eq!(app.selected_pid, Some(3));
+        app.toggle_all_interface_filters();
+        app.update_processes(processes());
+        assert_eq!((app.selected_index, selected_pid(&app)), (Some(2), Some(3)));
+    }
+
+    #[test]
+    fn test_listen_only_processes_are_hidden_by_default() {
+        let process = |pid: i32, name: &str, listen_only: bool| {
+            let mut process = ProcessInfo::new(pid, name.to_string());
+            process.listen_only = listen_only;
+            process.has_active_transfer = !listen_only;
+            (pid, process)
+        };
+        let processes = || {
+            ProcessMap::from([
+                process(1, "sshd", true),
+                process(2, "nginx", true),
+                process(3, "curl", false),
+            ])
+        };
+        let order = |app: &AppState| app.process_list.iter().map(|p| p.pid).collect::<Vec<_>>();
+
+        let mut app = AppState::new();
+        app.update_processes(processes());
+        assert_eq!(order(&app), vec![3]);
+        assert_eq!(app.active_process_count(), 1);
+        assert_eq!(app.hidden_listen_only_count(), 2);
+
+        // Pinned listeners stay listed
+        app.toggle_pin(2, "nginx");
+        assert_eq!(order(&app), vec![2, 3]);
+        assert_eq!(app.hidden_listen_only_count(), 1);
+
+        app.toggle_listen_only();
+        assert_eq!(order(&app), vec![2, 3, 1]);
+        assert_eq!(app.hidden_listen_only_count(), 0);
+        assert_eq!(app.active_process_count(), 1);
+    }
+
+    #[test]
+    fn test_local_sort_rates_use_local_totals() {
+        let mut process = ProcessInfo::new(1, "rsync".to_string());
+        process.local_download_rate = 10;
+        process.local_total_download = 1_000;
+        process.local_upload_rate = 20;
+        process.local_total_upload = 2_000;
+
+        let mut app = AppState::new();
+        app.traffic_view_mode = TrafficViewMode::Local;
+        assert_eq!(app.get_sort_rates(&process), (10, 1_000, 20, 2_000));
+    }
+
+    #[test]
+    fn test_throttle_priority_influences_sort() {
+        let process = |pid: i32, download_rate: u64, priority: Option<Priority>| {
+            let mut process = ProcessInfo::new(pid, format!("proc{}", pid));
+            process.download_rate = download_rate;
+            process.throttle_limit = priority.map(|priority| ThrottleLimit {
+                download_limit: Some(10_000),
+                upload_limit: None,
+                upload_minimum: None,
+                traffic_type: crate::process::TrafficType::All,
+                interfaces: None,
+                policy: None,
+                family: AddressFamily::Any,
+                priority,
+            });
+            (pid, process)
+        };
+
+        let mut app = AppState::new();
+        app.update_processes(ProcessMap::from([
+            process(1, 100, Some(Priority::High)),
+            process(2, 5000, Some(Priority::Low)),
+            process(3, 300, None),
+            process(4, 200, Some(Priority::Normal)),
+        ]));
+        let order: Vec<i32> = app.process_list.iter().map(|p| p.pid).collect();
+        assert_eq!(order, vec![1, 3, 4, 2]);
+    }
+
+    #[test]
+    fn test_pending_removal_times_out() {
+        let mut app = AppState::new();
+        app.ask_removal(42, "curl".to_string());
+        assert_eq!(app.pending_removal().map(|p| p.pid), Some(42));
+        assert_eq!(app.take_pending_removal().map(|p| p.pid), Some(42));
+        assert!(app.pending_removal().is_none());
+
+        app.ask_removal(42, "curl".to_string());
+        if let Some(pending) = app.pending_removal.as_mut() {
+            pending.asked = Instant::now() - REMOVAL_CONFIRM_WINDOW;
+        }
+        assert!(app.pending_removal().is_none());
+        assert!(app.take_pending_removal().is_none());
+    }
+
+    #[test]
+    fn test_retransmit_spike_compares_with_unthrottled_baseline() {
+        let process = |retransmit_rate: f64, throttled: bool| {
+            let mut process = ProcessInfo::new(7, "scp".to_string());
+            process.retransmit_rate = retransmit_rate;
+            process.throttle_limit = throttled.then_some(crate::process::ThrottleLimit {
+                download_limit: None,
+                upload_limit: Some(100_000),
+                upload_minimum: None,
+                traffic_type: crate::process::TrafficType::All,
+                interfaces: None,
+                policy: None,
+                family: crate::process::AddressFamily::Any,
+                priority: crate::process::Priority::Normal,
+            });
+            process
+        };
+        let mut app = AppState::new();
+        app.update_processes(ProcessMap::from([(7, process(3.0, false))]));
+
+        // A busy but unthrottled flow is never flagged
+        assert!(!app.retransmit_spike(&process(9.0, false)));
+
+        // Once throttled, the baseline stays where it was
+        app.update_processes(ProcessMap::from([(7, process(9.0, true))]));
+        assert!(app.retransmit_spike(&process(9.0, true)));
+        assert!(!app.retransmit_spike(&process(5.0, true)));
+    }
+
+    #[test]
+    fn test_udp_warning_offers_applying_anyway() {
+        let mut process = ProcessInfo::new(42, "quic-client".to_string());
+        process.tcp_total_download = 1000;
+        process.udp_total_download = 3000;
+        let share = process.udp_share(false).unwrap();
+        assert!(share >= HIGH_UDP_SHARE);
+        assert_eq!(process.udp_share(true), None);
+
+        let mut dialog = BackendCompatibilityDialog::for_udp(
+            "dnctl".to_string(),
+            crate::process::TrafficType::All,
+            vec!["ifb_tc".to_string()],
+            false,
+            share,
+        );
+        assert_eq!(dialog.udp_share, Some(75));
+        assert_eq!(
+            dialog.get_action(),
+            BackendCompatibilityAction::SwitchTemporary("ifb_tc".to_string())
+        );
+        dialog.select_previous();
+        dialog.select_previous();
+        assert_eq!(dialog.get_action(), BackendCompatibilityAction::ApplyAnyway);
+    }
+
+    #[test]
+    fn test_throttle_dialog_typing_follows_selected_field() {
+        let mut dialog = ThrottleDialog::new();
+        for c in "512".chars() {
+            dialog.handle_char(c);
+        }
+        dialog.toggle_field();
+        assert_eq!(dialog.selected_field, ThrottleField::Upload);
+        for c in "64".chars() {
+            dialog.handle_char(c);
+        }
+        assert_eq!(dialog.download_input, "512");
+        assert_eq!(dialog.upload_input, "64");
+
+        // Switching back keeps what was typed, and edits continue where they left off
+        dialog.toggle_field();
+        assert_eq!(dialog.selected_field, ThrottleField::Minimum);
+        dialog.toggle_field();
+        assert_eq!(dialog.selected_field, ThrottleField::Download);
+        dialog.handle_backspace();
+        dialog.handle_char('0');
+        assert_eq!(dialog.download_input, "510");
+        assert_eq!(dialog.upload_input, "64");
+
+        // Without interfaces to offer, the field cycle skips the interface list
+        for _ in 0..3 {
+            dialog.toggle_field();
+        }
+        assert_eq!(dialog.selected_field, ThrottleField::Download);
+        dialog.set_available_interfaces(vec!["wlan0".to_string(), "eth0".to_string()]);
+        for _ in 0..3 {
+            dialog.toggle_field();
+        }
+        assert_eq!(dialog.selected_field, ThrottleField::Interfaces);
+        dialog.handle_char('9');
+        dialog.handle_backspace();
+        assert_eq!(
+            (dialog.download_input.as_str(), dialog.upload_input.as_str()),
+            ("510", "64")
+        );
+    }
+
+    #[test]
+    fn test_throttle_dialog_minimum_needs_upload_limit() {
+        let mut dialog = ThrottleDialog::new();
+        dialog.minimum_input = "64".to_string();
+        assert!(dialog.parse_limits().is_none());
+        assert!(dialog.limit_error().unwrap().starts_with("Minimum:"));
+
+        dialog.upload_input = "32".to_string();
+        assert!(dialog.parse_limits().is_none());
+        assert!(
+            dialog
+                .limit_error()
+                .unwrap()
+                .contains("above the upload limit")
+        );
+
+        dialog.upload_input = "256".to_string();
+        assert_eq!(dialog.parse_limits(), Some((None, Some(256 * 1024))));
+        assert_eq!(dialog.upload_minimum(), Some(64 * 1024));
+        assert_eq!(dialog.limit_error(), None);
+    }
+
+    #[test]
+    fn test_throttle_dialog_units_and_invalid_field() {
+        let mut dialog = ThrottleDialog::new();
+        for c in "2.5mbit".chars() {
+            assert!(dialog.accepts_char(c), "{c}");
+            dialog.handle_char(c);
+        }
+        assert_eq!(dialog.parse_limits(), Some((Some(312_500), None)));
+        // Letters that can't continue a unit are left to the shortcuts
+        dialog.download_input = "5".to_string();
+        assert!(!dialog.accepts_char('t'));
+        assert!(!dialog.accepts_char('i'));
+        assert!(dialog.accepts_char('M'));
+
+        // A bare number is still KB/s
+        dialog.upload_input = "5".to_string();
+        assert_eq!(dialog.preview(ThrottleField::Upload), Some(Ok(5 * 1024)));
+        assert_eq!(dialog.preview(ThrottleField::Minimum), None);
+
+        dialog.upload_input = "5kbit/".to_string();
+        assert!(matches!(
+            dialog.preview(ThrottleField::Upload),
+            Some(Err(_))
+        ));
+        assert_eq!(dialog.invalid_field(), Some(ThrottleField::Upload));
+    }
+
+    #[test]
+    fn test_throttle_dialog_backspace_on_empty_field() {
+        let mut dialog = ThrottleDialog::new();
+        dialog.handle_backspace();
+        assert_eq!(dialog.download_input, "");
+        dialog.handle_char('1');
+        dialog.handle_backspace();
+        dialog.handle_backspace();
+        assert_eq!(dialog.download_input, "");
+        assert_eq!(dialog.parse_limits(), Some((None, None)));
+    }
+
+    #[test]
+    fn test_throttle_dialog_traffic_type_cycle_wraps() {
+        use crate::process::TrafficType;
+        let mut dialog = ThrottleDialog::new();
+        let mut seen = vec![(dialog.traffic_type_index, dialog.get_traffic_type())];
+        for _ in 0..3 {
+            dialog.cycle_traffic_type();
+            seen.push((dialog.traffic_type_index, dialog.get_traffic_type()));
+        }
+        assert_eq!(
+            seen,
+            [
+                (0, TrafficType::All),
+                (1, TrafficType::Internet),
+                (2, TrafficType::Local),
+                (0, TrafficType::All),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_throttle_dialog_parse_limits_converts_kb() {
+        let mut dialog = ThrottleDialog::new();
+        dialog.download_input = "1024".to_string();
+        assert_eq!(dialog.parse_limits(), Some((Some(1024 * 1024), None)));
+
+        dialog.download_input.clear();
+        dialog.upload_input = "1".to_string();
+        assert_eq!(dialog.parse_limits(), Some((None, Some(1024))));
+
+        // Too slow to enforce
+        dialog.download_input = "0".to_string();
+        assert_eq!(dialog.parse_limits(), None);
+    }
+
+    #[test]
+    fn test_throttle_dialog_fractional_limits() {
+        let mut dialog = ThrottleDialog::new();
+        dialog.download_input = "1.5".to_string();
+        assert_eq!(dialog.parse_limits(), Some((Some(1536), None)));
+        assert_eq!(dialog.limit_error(), None);
+
+        // The minimum is 128 B/s
+        dialog.download_input = "0.125".to_string();
+        assert_eq!(dialog.parse_limits(), Some((Some(128), None)));
+        dialog.download_input = "0.12".to_string();
+        assert_eq!(dialog.parse_limits(), None);
+        assert_eq!(
+            dialog.limit_error().as_deref(),
+            Some("Download: 123 B/s is below the minimum limit of 128 B/s (0.125 KB/s)")
+        );
+
+        dialog.download_input.clear();
+        dialog.upload_input = "1.2.3".to_string();
+        assert_eq!(dialog.parse_limits(), None);
+        assert_eq!(
+            dialog.limit_error().as_deref(),
+            Some("Upload: \"1.2.3\" is not a valid limit")
+        );
+    }
+
+    #[test]
+    fn test_detail_backlog_growth() {
+        let mut app = AppState::new();
+        let queued = |backlog| {
+            Some(BackendStats {
+                backlog_bytes: Some(backlog),
+                queue_delay_estimate_ms: Some(0),
+                ..Default::default()
+            })
+        };
+
+        for backlog in [1000, 2000, 2000, 3000] {
+            app.update_detail_throttle_stats((queued(backlog), None));
+        }
+        assert_eq!(app.detail_backlog_growing(), (false, false));
+        app.update_detail_throttle_stats((queued(4000), None));
+        assert_eq!(app.detail_backlog_growing(), (true, false));
+
+        // Draining resets it
+        app.update_detail_throttle_stats((queued(0), None));
+        assert_eq!(app.detail_backlog_growing(), (false, false));
+    }
+
+    #[test]
+    fn test_yank_text_follows_view() {
+        let connection = |state: &str, remote_port: u16| ConnectionDetail {
+            protocol: "TCP".to_string(),
+            local_addr: "10.0.0.2".parse().unwrap(),
+            local_port: 51234,
+            remote_addr: "1.1.1.1".parse().unwrap(),
+            remote_port,
+            state: state.to_string(),
+            inode: 0,
+        };
+        let mut process = ProcessInfo::new(1234, "curl".to_string());
+        process.download_rate = 2048;
+        process.upload_rate = 512;
+        process.total_download = 1_000_000;
+        process.total_upload = 20_000;
+        process.connections = vec![connection("TimeWait", 80), connection("Established", 443)];
+
+        let mut app = AppState::new();
+        app.update_processes(ProcessMap::from([(1234, process)]));
+        app.select_index(0);
+        assert_eq!(
+            app.yank_text().as_deref(),
+            Some("1234\tcurl\t2048\t512\t1000000\t20000")
+        );
+
+        // Connections are copied in the order the tab lists them (established first)
+        app.enter_process_detail();
+        app.next_detail_tab();
+        assert_eq!(
+            app.yank_text().as_deref(),
+            Some("TCP 10.0.0.2:51234 1.1.1.1:443 Established")
+        );
+        app.detail_move_down();
+        assert_eq!(
+            app.yank_text().as_deref(),
+            Some("TCP 10.0.0.2:51234 1.1.1.1:80 TimeWait")
+        );
+        // Past the last connection the view scrolls instead
+        app.detail_move_down();
+        assert_eq!(app.detail_connection_index, 1);
+        assert_eq!(app.detail_scroll_offset, 1);
+    }
+
+    #[test]
+    fn test_standing_rule_input() {
+        let rule = StandingRulesModal::parse_input("apt* 2M").unwrap();
+        assert_eq!(rule.pattern, "apt*");
+        assert_eq!(rule.download.as_deref(), Some("2M"));
+        assert_eq!(rule.upload, None);
+
+        let rule = StandingRulesModal::parse_input(" steam  -  1M ").unwrap();
+        assert_eq!(rule.download, None);
+        assert_eq!(rule.upload.as_deref(), Some("1M"));
+
+        assert!(StandingRulesModal::parse_input("apt*").is_err());
+        assert!(StandingRulesModal::parse_input("apt* - -").is_err());
+        assert!(StandingRulesModal::parse_input("apt* fast").is_err());
+        assert!(StandingRulesModal::parse_input("apt* 2M 1M extra").is_err());
+    }
+}
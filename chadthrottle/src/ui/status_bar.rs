@@ -0,0 +1,264 @@
+// The status bar: key hints, filter state and status messages
+
+use crate::keybindings::Action;
+use crate::ui::{AppState, TrafficViewMode};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+};
+use unicode_width::UnicodeWidthStr;
+
+/// Wrap spans into multiple lines based on available width
+/// This ensures the status bar doesn't get truncated on narrow terminals
+/// Uses lookahead to keep related spans (like [key] description) together
+pub(super) fn wrap_spans_to_lines(spans: Vec<Span>, max_width: u16) -> Vec<Line> {
+    let mut lines = vec![];
+    let mut current_line = vec![];
+    let mut current_width = 0;
+    let mut i = 0;
+
+    while i < spans.len() {
+        let span = &spans[i];
+        let span_width = span.content.width() as u16;
+
+        // LOOKAHEAD: Check if this is a key span (like "[b]")
+        // If so, calculate combined width with the next span (description)
+        let lookahead_width = if span.content.starts_with('[') && i + 1 < spans.len() {
+            // This is a key span, check next span (likely the description)
+            let next_span = &spans[i + 1];
+            span_width + next_span.content.width() as u16
+        } else {
+            // Not a key, just use this span's width
+            span_width
+        };
+
+        // If adding this span (and its paired span if applicable) would overflow, wrap
+        if current_width + lookahead_width > max_width && !current_line.is_empty() {
+            lines.push(Line::from(std::mem::take(&mut current_line)));
+            current_width = 0;
+        }
+
+        // Add the current span
+        current_width += span_width;
+        current_line.push(span.clone());
+        i += 1;
+    }
+
+    // Add remaining spans as the last line
+    if !current_line.is_empty() {
+        lines.push(Line::from(current_line));
+    }
+
+    // Return at least one empty line if no content
+    if lines.is_empty() {
+        lines.push(Line::from(""));
+    }
+
+    lines
+}
+
+pub(super) fn draw_status_bar(f: &mut Frame, area: Rect, app: &AppState) {
+    let theme = app.theme;
+    // Auto-generate status bar from centralized keybindings
+    let mut spans = vec![];
+
+    for (i, (key, description)) in crate::keybindings::get_status_bar_keybindings(&app.keymap)
+        .iter()
+        .enumerate()
+    {
+        if i > 0 {
+            spans.push(Span::raw("  "));
+        }
+        spans.push(Span::styled(
+            format!("[{}]", key),
+            Style::default().fg(theme.highlight),
+        ));
+        spans.push(Span::raw(format!(" {}  ", description)));
+    }
+
+    spans.push(Span::raw("|  "));
+
+    // Show traffic view mode
+    let traffic_mode_text = match app.traffic_view_mode {
+        TrafficViewMode::All => "All",
+        TrafficViewMode::Internet => "Internet",
+        TrafficViewMode::Local => "Local",
+    };
+    let traffic_mode_icon = match app.traffic_view_mode {
+        TrafficViewMode::All => "🌐",
+        TrafficViewMode::Internet => "🌐",
+        TrafficViewMode::Local => "🏠",
+    };
+    spans.push(Span::styled(
+        format!("{} {} ", traffic_mode_icon, traffic_mode_text),
+        Style::default()
+            .fg(theme.title)
+            .add_modifier(Modifier::BOLD),
+    ));
+    spans.push(Span::raw("| "));
+
+    // Processes that moved bytes in the last minute, not every process with a socket
+    let hidden = app.hidden_listen_only_count();
+    let activity_text = if hidden > 0 {
+        format!(
+            "{} active ({} listening hidden) | ",
+            app.active_process_count(),
+            hidden
+        )
+    } else {
+        format!("{} active | ", app.active_process_count())
+    };
+    spans.push(Span::styled(
+        activity_text,
+        Style::default().fg(theme.secondary),
+    ));
+
+    if let Some(warning) = &app.privilege_warning {
+        spans.push(Span::styled(
+            format!("⚠ {} | ", warning),
+            Style::default()
+                .fg(theme.error)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+
+    if let Some(warning) = &app.quota_warning {
+        spans.push(Span::styled(
+            format!("⚠ {} | ", warning),
+            Style::default()
+                .fg(theme.warning)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+
+    if let Some(pending) = app.pending_removal() {
+        spans.push(Span::styled(
+            format!(
+                "Remove throttle from {} (PID {})? Press {} or y to confirm | ",
+                pending.name,
+                pending.pid,
+                app.keymap.label(Action::RemoveThrottle)
+            ),
+            Style::default()
+                .fg(theme.warning)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+
+    if let Some(alert) = app.active_alert() {
+        spans.push(Span::styled(
+            format!("⚠ {} | ", alert),
+            Style::default()
+                .fg(theme.error)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+
+    // Show filter status
+    match &app.active_interface_filters {
+        None => {
+            // No filter - show normal message
+            spans.push(Span::styled(
+                &app.status_message,
+                Style::default().fg(theme.secondary),
+            ));
+        }
+        Some(filters) if filters.is_empty() => {
+            // Empty filter
+            spans.push(Span::styled(
+                "FILTER: None (showing 0 processes) | ",
+                Style::default()
+                    .fg(theme.error)
+                    .add_modifier(Modifier::BOLD),
+            ));
+            spans.push(Span::styled(
+                &app.status_message,
+                Style::default().fg(theme.secondary),
+            ));
+        }
+        Some(filters) => {
+            // Active filter
+            let filter_text = if filters.len() <= 3 {
+                format!("FILTER: {} | ", filters.join(", "))
+            } else {
+                format!(
+                    "FILTER: {} +{} more | ",
+                    filters[..2].join(", "),
+                    filters.len() - 2
+                )
+            };
+            spans.push(Span::styled(
+                filter_text,
+                Style::default()
+                    .fg(theme.title)
+                    .add_modifier(Modifier::BOLD),
+            ));
+            spans.push(Span::styled(
+                &app.status_message,
+                Style::default().fg(theme.secondary),
+            ));
+        }
+    }
+
+    // Wrap spans to multiple lines if they exceed terminal width
+    let available_width = area.width.saturating_sub(2); // minus left/right borders
+    let wrapped_lines = wrap_spans_to_lines(spans, available_width);
+    let status = Paragraph::new(wrapped_lines).block(Block::default().borders(Borders::ALL));
+
+    f.render_widget(status, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line_text(line: &Line) -> String {
+        line.spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect()
+    }
+
+    #[test]
+    fn test_wrap_keeps_keys_with_their_descriptions() {
+        let spans = vec![
+            Span::raw("[q]"),
+            Span::raw(" Quit  "),
+            Span::raw("[t]"),
+            Span::raw(" Throttle  "),
+            Span::raw("| "),
+        ];
+        // "[q] Quit  [t]" fits in 14 columns, but "[t]" would be split from its description
+        let lines = wrap_spans_to_lines(spans.clone(), 14);
+        let texts: Vec<String> = lines.iter().map(line_text).collect();
+        assert_eq!(texts, vec!["[q] Quit  ", "[t] Throttle  ", "| "]);
+
+        // Everything on one line when it fits
+        assert_eq!(wrap_spans_to_lines(spans, 100).len(), 1);
+        // A span wider than the bar still gets a line of its own
+        let lines = wrap_spans_to_lines(vec![Span::raw("a long status message")], 5);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(wrap_spans_to_lines(vec![], 10).len(), 1);
+    }
+
+    #[test]
+    fn test_render_wraps_on_narrow_terminals() {
+        let app = AppState::new();
+        let buffer = crate::ui::render_to_buffer(60, 5, |f| draw_status_bar(f, f.area(), &app));
+        let lines = crate::ui::buffer_lines(&buffer);
+        let inner: Vec<&str> = lines[1..4]
+            .iter()
+            .map(|line| line.trim_matches('│').trim_end())
+            .collect();
+        // The hints wrap onto the next line instead of being cut off at the border
+        assert!(inner.iter().filter(|line| !line.is_empty()).count() > 1);
+        for line in inner {
+            assert!(line.width() <= 58);
+            // No key is left at the end of a line without its description
+            assert!(!line.ends_with(']'), "dangling key in {line:?}");
+        }
+    }
+}
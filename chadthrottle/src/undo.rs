@@ -0,0 +1,152 @@
+// Undo stack for throttle changes made in the TUI (kept in memory only)
+
+use crate::backends::ActiveThrottle;
+use crate::process::ThrottleLimit;
+use std::collections::VecDeque;
+
+/// How many throttle changes can be undone
+pub const UNDO_DEPTH: usize = 10;
+
+/// What a recorded change did to the process's throttle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MutationKind {
+    Apply,
+    Update,
+    Remove,
+}
+
+impl MutationKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            MutationKind::Apply => "throttle",
+            MutationKind::Update => "throttle change",
+            MutationKind::Remove => "throttle removal",
+        }
+    }
+}
+
+/// A throttle change, with the throttle the process had before it
+#[derive(Debug, Clone)]
+pub struct ThrottleMutation {
+    pub pid: i32,
+    pub name: String,
+    pub kind: MutationKind,
+    /// Limit to re-apply on undo (None = the process wasn't throttled, remove it)
+    pub previous: Option<ThrottleLimit>,
+}
+
+impl ThrottleMutation {
+    /// Describe a change from `previous` (the throttle before it); `removed` is
+    /// whether the change removed the throttle
+    pub fn new(pid: i32, name: String, previous: Option<&ActiveThrottle>, removed: bool) -> Self {
+        let kind = match (previous, removed) {
+            (_, true) => MutationKind::Remove,
+            (Some(_), false) => MutationKind::Update,
+            (None, false) => MutationKind::Apply,
+        };
+        Self {
+            pid,
+            name,
+            kind,
+            previous: previous.map(limit_of),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        format!("{} of {} (PID {})", self.kind.label(), self.name, self.pid)
+    }
+}
+
+/// The limit an active throttle was applied with
+fn limit_of(throttle: &ActiveThrottle) -> ThrottleLimit {
+    ThrottleLimit {
+        download_limit: throttle.download_limit,
+        upload_limit: throttle.upload_limit,
+        upload_minimum: throttle.upload_minimum,
+        // Re-applied as asked for, the manager downgrades it again if it has to
+        traffic_type: throttle.downgraded_from.unwrap_or(throttle.traffic_type),
+        interfaces: throttle.interfaces.clone(),
+        policy: throttle.policy,
+        family: throttle.family,
+        priority: throttle.priority,
+    }
+}
+
+/// The last `UNDO_DEPTH` throttle changes, most recent last
+#[derive(Debug, Default)]
+pub struct UndoStack {
+    entries: VecDeque<ThrottleMutation>,
+}
+
+impl UndoStack {
+    pub fn push(&mut self, mutation: ThrottleMutation) {
+        if self.entries.len() == UNDO_DEPTH {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(mutation);
+    }
+
+    pub fn pop(&mut self) -> Option<ThrottleMutation> {
+        self.entries.pop_back()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::{AddressFamily, Priority, TrafficType};
+
+    fn active(pid: i32, download: Option<u64>) -> ActiveThrottle {
+        ActiveThrottle {
+            pid,
+            process_name: "curl".to_string(),
+            upload_limit: None,
+            upload_minimum: None,
+            download_limit: download,
+            interfaces: None,
+            traffic_type: TrafficType::All,
+            family: AddressFamily::Any,
+            priority: Priority::Normal,
+            upload_backend: None,
+            download_backend: Some("ifb_tc".to_string()),
+            policy: None,
+            children: None,
+            downgraded_from: Some(TrafficType::Internet),
+            sandboxed: false,
+        }
+    }
+
+    #[test]
+    fn test_mutation_kind_and_previous_limit() {
+        let applied = ThrottleMutation::new(1, "curl".to_string(), None, false);
+        assert_eq!(applied.kind, MutationKind::Apply);
+        assert!(applied.previous.is_none());
+
+        let previous = active(1, Some(1024));
+        let updated = ThrottleMutation::new(1, "curl".to_string(), Some(&previous), false);
+        assert_eq!(updated.kind, MutationKind::Update);
+        let limit = updated.previous.unwrap();
+        assert_eq!(limit.download_limit, Some(1024));
+        // The traffic type asked for, not the downgraded one
+        assert_eq!(limit.traffic_type, TrafficType::Internet);
+
+        let removed = ThrottleMutation::new(1, "curl".to_string(), Some(&previous), true);
+        assert_eq!(removed.kind, MutationKind::Remove);
+        assert_eq!(removed.describe(), "throttle removal of curl (PID 1)");
+    }
+
+    #[test]
+    fn test_undo_stack_keeps_most_recent() {
+        let mut stack = UndoStack::default();
+        for pid in 0..(UNDO_DEPTH as i32 + 3) {
+            stack.push(ThrottleMutation::new(pid, "curl".to_string(), None, false));
+        }
+        let mut undone = Vec::new();
+        while let Some(mutation) = stack.pop() {
+            undone.push(mutation.pid);
+        }
+        assert_eq!(undone.len(), UNDO_DEPTH);
+        assert_eq!(undone.first(), Some(&(UNDO_DEPTH as i32 + 2)));
+        assert_eq!(undone.last(), Some(&3));
+    }
+}
@@ -0,0 +1,295 @@
+// How much of its limit each throttled process actually uses
+//
+// "Limited to 1 MB/s and moving 0.97 MB/s" means the throttle is what holds the
+// process back; "moving 0.2 MB/s" means something else is (the server, the disk).
+// Well above the limit means the throttle isn't working. Each throttled direction
+// keeps its last 30 samples of rate / limit, from one monitor update each, and the
+// rate is the one of the traffic the throttle covers: a limit on internet traffic is
+// compared with the internet rate only.
+
+use crate::process::{ProcessInfo, ProcessMap, ThrottleLimit, TrafficType};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Samples (monitor updates) the utilization is averaged over
+pub const WINDOW: usize = 30;
+
+/// Fewer samples than this are too noisy to show
+const MIN_SAMPLES: usize = 5;
+
+/// Above this share of the limit over a whole window, the throttle isn't holding
+pub const NOT_WORKING_PERCENT: f64 = 110.0;
+
+/// Below this share of the limit the throttle has nothing to do
+const IDLE_PERCENT: f64 = 2.0;
+
+/// A throttle idle for this long probably isn't needed anymore
+const STALE_AFTER: Duration = Duration::from_secs(10 * 60);
+
+/// Mean share of each limit used over the last `WINDOW` samples
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Utilization {
+    /// Percent of the download limit (None without a download limit)
+    pub download: Option<f64>,
+    pub upload: Option<f64>,
+    pub samples: usize,
+    /// Idle (under 2% of its limits) for 10 minutes or more
+    pub stale: bool,
+}
+
+impl Utilization {
+    /// The direction closest to (or furthest over) its limit
+    pub fn highest(&self) -> Option<f64> {
+        match (self.download, self.upload) {
+            (Some(download), Some(upload)) => Some(download.max(upload)),
+            (download, upload) => download.or(upload),
+        }
+    }
+
+    /// Well over the limit for a whole window
+    pub fn not_working(&self) -> bool {
+        self.samples >= WINDOW && self.highest().is_some_and(|p| p > NOT_WORKING_PERCENT)
+    }
+}
+
+/// A throttle whose process stayed above `NOT_WORKING_PERCENT` of its limit
+#[derive(Debug, Clone, PartialEq)]
+pub struct NotWorking {
+    pub pid: i32,
+    pub name: String,
+    /// "download" or "upload", whichever is further over
+    pub direction: &'static str,
+    pub percent: f64,
+    /// The limit exceeded (bytes/sec)
+    pub limit: u64,
+}
+
+#[derive(Debug)]
+struct Tracked {
+    /// Limits and traffic type the samples were taken against
+    limits: (Option<u64>, Option<u64>, TrafficType),
+    download: VecDeque<f64>,
+    upload: VecDeque<f64>,
+    /// When the process last used more than `IDLE_PERCENT` of a limit (or the
+    /// throttle was applied)
+    last_busy: Instant,
+    /// Reported as not working; reset once it's back under its limits
+    reported: bool,
+}
+
+impl Tracked {
+    fn new(limits: (Option<u64>, Option<u64>, TrafficType), now: Instant) -> Self {
+        Self {
+            limits,
+            download: VecDeque::with_capacity(WINDOW),
+            upload: VecDeque::with_capacity(WINDOW),
+            last_busy: now,
+            reported: false,
+        }
+    }
+
+    fn utilization(&self, now: Instant) -> Option<Utilization> {
+        let samples = self.download.len().max(self.upload.len());
+        if samples < MIN_SAMPLES {
+            return None;
+        }
+        let mean = |window: &VecDeque<f64>| {
+            (!window.is_empty()).then(|| window.iter().sum::<f64>() / window.len() as f64)
+        };
+        Some(Utilization {
+            download: mean(&self.download),
+            upload: mean(&self.upload),
+            samples,
+            stale: now.saturating_duration_since(self.last_busy) >= STALE_AFTER,
+        })
+    }
+}
+
+/// Rolling utilization of every active throttle
+#[derive(Debug, Default)]
+pub struct UtilizationTracker {
+    tracked: HashMap<i32, Tracked>,
+}
+
+impl UtilizationTracker {
+    /// Sample the latest rates and return the throttles that just turned out not to be
+    /// working (each is reported once until it's back under its limits)
+    pub fn update(&mut self, processes: &ProcessMap) -> Vec<NotWorking> {
+        self.update_at(processes, Instant::now())
+    }
+
+    fn update_at(&mut self, processes: &ProcessMap, now: Instant) -> Vec<NotWorking> {
+        // Throttles that were removed, and processes that are gone
+        self.tracked.retain(|pid, _| {
+            processes
+                .get(pid)
+                .is_some_and(|process| process.throttle_limit.is_some() && !process.is_terminated)
+        });
+
+        let mut not_working = Vec::new();
+        for (pid, process) in processes {
+            let Some(limit) = &process.throttle_limit else {
+                continue;
+            };
+            if process.is_terminated {
+                continue;
+            }
+            let limits = (limit.download_limit, limit.upload_limit, limit.traffic_type);
+            let tracked = self
+                .tracked
+                .entry(*pid)
+                .or_insert_with(|| Tracked::new(limits, now));
+            // New limits start a new window
+            if tracked.limits != limits {
+                *tracked = Tracked::new(limits, now);
+            }
+
+            let (download_rate, upload_rate) = covered_rates(process, limit);
+            let mut busy = false;
+            for (window, rate, limit) in [
+                (&mut tracked.download, download_rate, limit.download_limit),
+                (&mut tracked.upload, upload_rate, limit.upload_limit),
+            ] {
+                let Some(limit) = limit.filter(|&limit| limit > 0) else {
+                    continue;
+                };
+                let percent = rate as f64 / limit as f64 * 100.0;
+                busy |= percent >= IDLE_PERCENT;
+                if window.len() == WINDOW {
+                    window.pop_front();
+                }
+                window.push_back(percent);
+            }
+            if busy {
+                tracked.last_busy = now;
+            }
+
+            let Some(utilization) = tracked.utilization(now) else {
+                continue;
+            };
+            if !utilization.not_working() {
+                // Back under its limits: may be reported again
+                if utilization.highest().is_some_and(|p| p <= 100.0) {
+                    tracked.reported = false;
+                }
+                continue;
+            }
+            if tracked.reported {
+                continue;
+            }
+            tracked.reported = true;
+            let download = utilization.download.unwrap_or(0.0);
+            let upload = utilization.upload.unwrap_or(0.0);
+            let (direction, percent, limit) = if download >= upload {
+                ("download", download, limit.download_limit)
+            } else {
+                ("upload", upload, limit.upload_limit)
+            };
+            not_working.push(NotWorking {
+                pid: *pid,
+                name: process.name.clone(),
+                direction,
+                percent,
+                limit: limit.unwrap_or_default(),
+            });
+        }
+        not_working.sort_by_key(|event| event.pid);
+        not_working
+    }
+
+    /// The utilization of every throttle with enough samples, by PID
+    pub fn all(&self) -> HashMap<i32, Utilization> {
+        self.all_at(Instant::now())
+    }
+
+    fn all_at(&self, now: Instant) -> HashMap<i32, Utilization> {
+        self.tracked
+            .iter()
+            .filter_map(|(pid, tracked)| Some((*pid, tracked.utilization(now)?)))
+            .collect()
+    }
+}
+
+/// Download and upload rates of the traffic a throttle covers
+fn covered_rates(process: &ProcessInfo, limit: &ThrottleLimit) -> (u64, u64) {
+    match limit.traffic_type {
+        TrafficType::All => (process.download_rate, process.upload_rate),
+        TrafficType::Internet => (process.internet_download_rate, process.internet_upload_rate),
+        TrafficType::Local => (process.local_download_rate, process.local_upload_rate),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::{AddressFamily, Priority};
+
+    fn throttled(download_limit: u64, traffic_type: TrafficType, rates: (u64, u64)) -> ProcessMap {
+        let mut info = ProcessInfo::new(1, "steam".to_string());
+        info.download_rate = rates.0 + rates.1;
+        info.internet_download_rate = rates.0;
+        info.local_download_rate = rates.1;
+        info.throttle_limit = Some(ThrottleLimit {
+            download_limit: Some(download_limit),
+            upload_limit: None,
+            upload_minimum: None,
+            traffic_type,
+            interfaces: None,
+            policy: None,
+            family: AddressFamily::Any,
+            priority: Priority::Normal,
+        });
+        ProcessMap::from([(1, info)])
+    }
+
+    #[test]
+    fn test_utilization_window_and_not_working() {
+        let mut tracker = UtilizationTracker::default();
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        // Internet-only limit: the local traffic doesn't count
+        let processes = throttled(1000, TrafficType::Internet, (970, 5000));
+        for secs in 0..WINDOW as u64 {
+            assert!(tracker.update_at(&processes, at(secs)).is_empty());
+        }
+        let utilization = tracker.all_at(at(30))[&1];
+        assert_eq!(utilization.download.map(|p| p.round()), Some(97.0));
+        assert_eq!(utilization.upload, None);
+        assert!(!utilization.stale);
+
+        // Counting all traffic, the same rates are far over: reported once, after a
+        // fresh window for the new limits
+        let processes = throttled(1000, TrafficType::All, (970, 5000));
+        let mut reported = Vec::new();
+        for secs in 30..30 + 2 * WINDOW as u64 {
+            reported.extend(tracker.update_at(&processes, at(secs)));
+        }
+        assert_eq!(reported.len(), 1);
+        assert_eq!(
+            (reported[0].direction, reported[0].limit),
+            ("download", 1000)
+        );
+
+        // Removing the throttle forgets it
+        let mut processes = processes;
+        processes.get_mut(&1).unwrap().throttle_limit = None;
+        tracker.update_at(&processes, at(100));
+        assert!(tracker.all_at(at(100)).is_empty());
+    }
+
+    #[test]
+    fn test_idle_throttle_goes_stale() {
+        let mut tracker = UtilizationTracker::default();
+        let start = Instant::now();
+
+        let processes = throttled(1_000_000, TrafficType::All, (0, 0));
+        for secs in 0..=STALE_AFTER.as_secs() {
+            tracker.update_at(&processes, start + Duration::from_secs(secs));
+        }
+        let utilization = tracker.all_at(start + STALE_AFTER)[&1];
+        assert_eq!(utilization.highest(), Some(0.0));
+        assert!(utilization.stale);
+    }
+}
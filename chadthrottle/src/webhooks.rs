@@ -0,0 +1,245 @@
+// Webhook notifications (`webhooks` in the config)
+//
+// Each matching event is POSTed as JSON from its own tokio task, so a slow endpoint
+// never holds up the UI. A failed delivery is retried once. After a few deliveries
+// in a row fail, the circuit breaker stops sending for a while and says so once,
+// instead of logging a failure for every event while the endpoint is down.
+
+use crate::config::WebhookConfig;
+use crate::events::{ThrottleEvent, ThrottleEventKind};
+use crate::format;
+use anyhow::Result;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+/// Per-attempt request timeout
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Wait before the single retry
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Consecutive failed deliveries that open the circuit
+const FAILURES_TO_OPEN: u32 = 3;
+
+/// How long an open circuit drops events before trying the endpoint again
+const OPEN_DURATION: Duration = Duration::from_secs(60);
+
+/// Tracks delivery failures and decides whether to send at all
+#[derive(Debug, Default)]
+struct CircuitBreaker {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+    // Events dropped while open, reported when the circuit closes again
+    dropped: u64,
+}
+
+impl CircuitBreaker {
+    /// Whether to attempt a delivery now (false = drop the event)
+    fn allow(&mut self, now: Instant) -> bool {
+        match self.open_until {
+            Some(until) if now < until => {
+                self.dropped += 1;
+                false
+            }
+            _ => true,
+        }
+    }
+
+    fn record_success(&mut self) {
+        if self.open_until.take().is_some() {
+            log::info!(
+                "Webhook endpoint reachable again ({} event(s) dropped while it was down)",
+                self.dropped
+            );
+        }
+        self.consecutive_failures = 0;
+        self.dropped = 0;
+    }
+
+    /// Record a failed delivery; returns true when this failure opens the circuit
+    fn record_failure(&mut self, now: Instant) -> bool {
+        self.consecutive_failures += 1;
+        let was_open = self.open_until.is_some();
+        if self.consecutive_failures >= FAILURES_TO_OPEN {
+            // Re-arm after a failed probe, but only announce the first opening
+            self.open_until = Some(now + OPEN_DURATION);
+            return !was_open;
+        }
+        false
+    }
+}
+
+/// Sends events to the configured webhook
+pub struct WebhookNotifier {
+    url: String,
+    events: Vec<ThrottleEventKind>,
+    client: reqwest::Client,
+    breaker: Arc<Mutex<CircuitBreaker>>,
+    in_flight: Vec<JoinHandle<()>>,
+}
+
+impl WebhookNotifier {
+    pub fn new(config: &WebhookConfig) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(concat!("chadthrottle/", env!("CARGO_PKG_VERSION")))
+            .build()?;
+        Ok(Self {
+            url: config.url.clone(),
+            events: config.events.clone(),
+            client,
+            breaker: Arc::new(Mutex::new(CircuitBreaker::default())),
+            in_flight: Vec::new(),
+        })
+    }
+
+    /// Whether the config asks for this kind of event
+    pub fn wants(&self, kind: ThrottleEventKind) -> bool {
+        self.events.contains(&kind)
+    }
+
+    /// Send the event in the background (returns immediately)
+    pub fn notify(&mut self, event: &ThrottleEvent) {
+        if !self.wants(event.event) {
+            return;
+        }
+        if !self.breaker.lock().unwrap().allow(Instant::now()) {
+            return;
+        }
+        let body = payload(event).to_string();
+
+        let client = self.client.clone();
+        let url = self.url.clone();
+        let breaker = Arc::clone(&self.breaker);
+        self.in_flight.retain(|task| !task.is_finished());
+        self.in_flight.push(tokio::spawn(async move {
+            let mut result = post(&client, &url, &body).await;
+            if result.is_err() {
+                tokio::time::sleep(RETRY_DELAY).await;
+                result = post(&client, &url, &body).await;
+            }
+            let mut breaker = breaker.lock().unwrap();
+            match result {
+                Ok(()) => breaker.record_success(),
+                Err(e) => {
+                    if breaker.record_failure(Instant::now()) {
+                        log::warn!(
+                            "Webhook {} failing ({:#}); pausing notifications for {}s",
+                            url,
+                            e,
+                            OPEN_DURATION.as_secs()
+                        );
+                    } else {
+                        log::debug!("Webhook delivery to {} failed: {:#}", url, e);
+                    }
+                }
+            }
+        }));
+    }
+
+    /// Wait (up to `timeout`) for deliveries still in flight, e.g. before exiting
+    pub async fn flush(&mut self, timeout: Duration) {
+        let tasks = std::mem::take(&mut self.in_flight);
+        let _ = tokio::time::timeout(timeout, async {
+            for task in tasks {
+                let _ = task.await;
+            }
+        })
+        .await;
+    }
+}
+
+/// The event's fields plus a one-line `text` (what chat webhooks like Slack display)
+fn payload(event: &ThrottleEvent) -> serde_json::Value {
+    let limit = |limit: Option<u64>| limit.map_or("unlimited".to_string(), format::format_rate);
+    let what = match event.event {
+        ThrottleEventKind::Applied => "throttle applied to",
+        ThrottleEventKind::Removed => "throttle removed from",
+        ThrottleEventKind::LimitExceeded => "bandwidth alert for",
+        ThrottleEventKind::BackendFailed => "throttle failed for",
+        ThrottleEventKind::QuotaExceeded => "daily quota exceeded by",
+        ThrottleEventKind::ThrottleNotWorking => "throttle not working for",
+    };
+    let mut text = format!("chadthrottle: {} {} (PID {})", what, event.name, event.pid);
+    if event.download_limit.is_some() || event.upload_limit.is_some() {
+        text.push_str(&format!(
+            ", down {} / up {}",
+            limit(event.download_limit),
+            limit(event.upload_limit)
+        ));
+    }
+    if let Some(backend) = &event.backend {
+        text.push_str(&format!(" ({})", backend));
+    }
+    if let Some(detail) = &event.detail {
+        text.push_str(&format!(": {}", detail));
+    }
+
+    let mut payload = serde_json::to_value(event).unwrap_or_default();
+    if let Some(fields) = payload.as_object_mut() {
+        fields.insert("text".to_string(), text.into());
+    }
+    payload
+}
+
+async fn post(client: &reqwest::Client, url: &str, body: &str) -> Result<()> {
+    client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(body.to_string())
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_has_event_fields_and_text() {
+        let event = ThrottleEvent::new(ThrottleEventKind::Applied, 1234, "firefox")
+            .with_limits(Some(1024 * 1024), None)
+            .with_backends(Some("tc_htb"), Some("ifb_tc"));
+        let payload = payload(&event);
+        assert_eq!(payload["event"], "applied");
+        assert_eq!(payload["pid"], 1234);
+        assert_eq!(payload["download_limit"], 1024 * 1024);
+        assert!(payload["upload_limit"].is_null());
+        assert_eq!(payload["backend"], "tc_htb/ifb_tc");
+        assert!(payload.get("detail").is_none());
+        assert_eq!(
+            payload["text"],
+            format!(
+                "chadthrottle: throttle applied to firefox (PID 1234), down {} / up unlimited (tc_htb/ifb_tc)",
+                format::format_rate(1024 * 1024)
+            )
+        );
+    }
+
+    #[test]
+    fn test_circuit_opens_after_repeated_failures() {
+        let start = Instant::now();
+        let mut breaker = CircuitBreaker::default();
+        assert!(!breaker.record_failure(start));
+        assert!(!breaker.record_failure(start));
+        // The third failure in a row opens the circuit, announced once
+        assert!(breaker.record_failure(start));
+        assert!(!breaker.allow(start + Duration::from_secs(1)));
+        assert!(!breaker.allow(start + Duration::from_secs(2)));
+        assert_eq!(breaker.dropped, 2);
+
+        // After the pause one probe goes through; failing it keeps the circuit open quietly
+        let later = start + OPEN_DURATION;
+        assert!(breaker.allow(later));
+        assert!(!breaker.record_failure(later));
+        assert!(!breaker.allow(later + Duration::from_secs(1)));
+
+        // A success closes it again
+        breaker.record_success();
+        assert!(breaker.allow(later + Duration::from_secs(2)));
+        assert_eq!(breaker.consecutive_failures, 0);
+    }
+}